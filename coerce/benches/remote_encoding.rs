@@ -0,0 +1,98 @@
+//! Compares allocations made when encoding frames for remote nodes, with and without
+//! the [`BufferPool`]
+
+use bencher::{benchmark_group, benchmark_main, Bencher};
+use coerce::remote::net::buffer::BufferPool;
+use coerce::remote::net::message::SessionEvent;
+use coerce::remote::net::proto::network::MessageRequest;
+use coerce::remote::net::StreamData;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const MESSAGES: usize = 1000;
+
+// bencher runs each benchmark several times, only the first run is reported
+static REPORT_UNPOOLED: Once = Once::new();
+static REPORT_POOLED: Once = Once::new();
+
+fn message() -> SessionEvent {
+    SessionEvent::NotifyActor(MessageRequest {
+        message_id: "c6f7a3c5-1f0e-4f0b-9d8e-4b3a8b0b2f6e".to_string(),
+        handler_type: "BenchmarkActor.Msg".to_string(),
+        actor_id: "benchmark-actor".to_string(),
+        message: vec![0; 256],
+        ..Default::default()
+    })
+}
+
+fn report(name: &str, allocations: usize, iterations: usize) {
+    println!(
+        "{}: {:.2} allocations per message",
+        name,
+        allocations as f64 / (iterations * MESSAGES) as f64
+    );
+}
+
+fn encode_1000_messages(bench: &mut Bencher) {
+    let message = message();
+    let mut iterations = 0;
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    bench.iter(|| {
+        iterations += 1;
+        for _ in 0..MESSAGES {
+            let frame = message.write_to_bytes().unwrap();
+            bencher::black_box(frame);
+        }
+    });
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    REPORT_UNPOOLED.call_once(|| report("write_to_bytes", allocations, iterations));
+}
+
+fn encode_1000_messages_pooled(bench: &mut Bencher) {
+    let message = message();
+    let pool = BufferPool::new(16);
+    let mut iterations = 0;
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    bench.iter(|| {
+        iterations += 1;
+        for _ in 0..MESSAGES {
+            let frame = pool.encode(&message).unwrap();
+            bencher::black_box(frame);
+        }
+    });
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    REPORT_POOLED.call_once(|| {
+        report("pooled", allocations, iterations);
+        println!("pool hit rate: {:.4}", pool.stats().hit_rate());
+    });
+}
+
+benchmark_group!(
+    remote_encoding,
+    encode_1000_messages,
+    encode_1000_messages_pooled
+);
+benchmark_main!(remote_encoding);
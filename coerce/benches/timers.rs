@@ -0,0 +1,107 @@
+use bencher::{benchmark_group, benchmark_main, Bencher};
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message};
+use coerce::actor::scheduler::timer::{timer_stats, Timer, TimerTick};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::Actor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const TIMERS: usize = 10_000;
+const TICKS_PER_TIMER: usize = 5;
+const TICK_INTERVAL: Duration = Duration::from_millis(10);
+
+// bencher runs each benchmark several times, only the first run is reported
+static REPORT: Once = Once::new();
+
+fn rt() -> Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+struct TimerActor {
+    ticks: Arc<AtomicUsize>,
+}
+
+impl Actor for TimerActor {}
+
+#[derive(Clone)]
+struct Tick;
+
+impl Message for Tick {
+    type Result = ();
+}
+
+impl TimerTick for Tick {}
+
+#[async_trait::async_trait]
+impl Handler<Tick> for TimerActor {
+    async fn handle(&mut self, _message: Tick, _ctx: &mut ActorContext) {
+        self.ticks.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn tick_10000_timers(bench: &mut Bencher) {
+    let runtime = rt();
+    let system = runtime.block_on(async { ActorSystem::new() });
+    let ticks = Arc::new(AtomicUsize::new(0));
+
+    let actors = runtime.block_on(async {
+        let mut actors = Vec::with_capacity(TIMERS);
+        for _ in 0..TIMERS {
+            let actor = system
+                .new_anon_actor(TimerActor {
+                    ticks: ticks.clone(),
+                })
+                .await
+                .expect("unable to create actor");
+
+            actors.push(actor);
+        }
+
+        actors
+    });
+
+    let stats_before = timer_stats();
+
+    bench.iter(|| {
+        runtime.block_on(async {
+            ticks.store(0, Ordering::Relaxed);
+
+            let timers: Vec<Timer> = actors
+                .iter()
+                .map(|actor| Timer::start(actor.clone(), TICK_INTERVAL, Tick))
+                .collect();
+
+            while ticks.load(Ordering::Relaxed) < TIMERS * TICKS_PER_TIMER {
+                tokio::time::sleep(TICK_INTERVAL).await;
+            }
+
+            for timer in timers {
+                timer.stop();
+            }
+        })
+    });
+
+    let stats = timer_stats();
+    let wakeups = stats.wheel_wakeups - stats_before.wheel_wakeups;
+    let ticks_delivered = stats.ticks_delivered - stats_before.ticks_delivered;
+
+    // with a task per timer, every tick delivered would be a separate wakeup
+    REPORT.call_once(|| {
+        println!(
+            "timers={} ticks_delivered={} wheel_wakeups={} ticks_per_wakeup={:.1}",
+            TIMERS,
+            ticks_delivered,
+            wakeups,
+            ticks_delivered as f64 / wakeups.max(1) as f64
+        );
+    });
+}
+
+benchmark_group!(timers, tick_10000_timers);
+benchmark_main!(timers);
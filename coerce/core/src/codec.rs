@@ -0,0 +1,157 @@
+//! Framing of the Coerce wire protocol
+//!
+//! Every message exchanged between Coerce nodes is written as a frame, prefixed with the length
+//! of the frame as a 4 byte, big-endian unsigned integer. This is the same framing as Tokio's
+//! `LengthDelimitedCodec` with its default configuration, which is what the full runtime uses.
+
+use crate::stream::StreamData;
+use bytes::{Buf, BufMut, BytesMut};
+use core::fmt::{Display, Formatter};
+
+/// Length of the length prefix, written before every frame
+pub const FRAME_HEADER_LENGTH: usize = 4;
+
+/// Largest frame accepted by default, frames larger than this are rejected
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FrameErr {
+    /// The frame is larger than the maximum frame length
+    FrameTooLarge {
+        length: usize,
+        max_frame_length: usize,
+    },
+
+    /// The message could not be encoded
+    Encode,
+
+    /// The frame was received, but could not be decoded as the expected message type
+    Decode,
+}
+
+impl Display for FrameErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FrameErr::FrameTooLarge {
+                length,
+                max_frame_length,
+            } => write!(
+                f,
+                "frame length ({}) exceeds the maximum frame length ({})",
+                length, max_frame_length
+            ),
+            FrameErr::Encode => write!(f, "message failed to encode"),
+            FrameErr::Decode => write!(f, "frame failed to decode"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameErr {}
+
+/// Encodes and decodes length-prefixed frames
+#[derive(Debug, Copy, Clone)]
+pub struct FrameCodec {
+    max_frame_length: usize,
+}
+
+impl Default for FrameCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameCodec {
+    pub fn new() -> Self {
+        Self {
+            max_frame_length: DEFAULT_MAX_FRAME_LENGTH,
+        }
+    }
+
+    pub fn with_max_frame_length(max_frame_length: usize) -> Self {
+        Self { max_frame_length }
+    }
+
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Appends the payload to the buffer as a single frame
+    pub fn encode(&self, payload: &[u8], buffer: &mut BytesMut) -> Result<(), FrameErr> {
+        self.check_length(payload.len())?;
+
+        buffer.reserve(FRAME_HEADER_LENGTH + payload.len());
+        buffer.put_u32(payload.len() as u32);
+        buffer.extend_from_slice(payload);
+        Ok(())
+    }
+
+    /// Encodes the message directly into the buffer as a single frame, if the message can't be
+    /// encoded, the buffer is left as it was
+    pub fn encode_message<M: StreamData>(
+        &self,
+        message: &M,
+        buffer: &mut BytesMut,
+    ) -> Result<(), FrameErr> {
+        let start = buffer.len();
+        buffer.put_u32(0);
+
+        if !message.write_to_buffer(buffer) {
+            buffer.truncate(start);
+            return Err(FrameErr::Encode);
+        }
+
+        let length = buffer.len() - start - FRAME_HEADER_LENGTH;
+        if let Err(e) = self.check_length(length) {
+            buffer.truncate(start);
+            return Err(e);
+        }
+
+        buffer[start..start + FRAME_HEADER_LENGTH].copy_from_slice(&(length as u32).to_be_bytes());
+        Ok(())
+    }
+
+    /// Removes the next complete frame from the buffer, returning the payload of the frame.
+    /// Returns `None` if the buffer doesn't yet contain a complete frame.
+    pub fn decode(&self, buffer: &mut BytesMut) -> Result<Option<BytesMut>, FrameErr> {
+        if buffer.len() < FRAME_HEADER_LENGTH {
+            return Ok(None);
+        }
+
+        let mut header = [0u8; FRAME_HEADER_LENGTH];
+        header.copy_from_slice(&buffer[..FRAME_HEADER_LENGTH]);
+
+        let length = u32::from_be_bytes(header) as usize;
+        self.check_length(length)?;
+
+        if buffer.len() < FRAME_HEADER_LENGTH + length {
+            buffer.reserve(FRAME_HEADER_LENGTH + length - buffer.len());
+            return Ok(None);
+        }
+
+        buffer.advance(FRAME_HEADER_LENGTH);
+        Ok(Some(buffer.split_to(length)))
+    }
+
+    /// Removes the next complete frame from the buffer, decoding it as a message of type `M`
+    pub fn decode_message<M: StreamData>(
+        &self,
+        buffer: &mut BytesMut,
+    ) -> Result<Option<M>, FrameErr> {
+        match self.decode(buffer)? {
+            Some(frame) => M::read_from_slice(&frame).map(Some).ok_or(FrameErr::Decode),
+            None => Ok(None),
+        }
+    }
+
+    fn check_length(&self, length: usize) -> Result<(), FrameErr> {
+        if length > self.max_frame_length {
+            Err(FrameErr::FrameTooLarge {
+                length,
+                max_frame_length: self.max_frame_length,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
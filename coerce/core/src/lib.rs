@@ -0,0 +1,21 @@
+//! Coerce Core
+//!
+//! The message and wire codec primitives used by Coerce, split out from the full runtime so that
+//! constrained clients (such as embedded gateways) can encode and decode the Coerce wire protocol
+//! without depending on Tokio, or the actor runtime.
+//!
+//! `coerce-core` is `no_std` compatible (requiring only `alloc`) when the default `std` feature
+//! is disabled.
+//!
+//! Everything defined here is re-exported by `coerce`, so applications using the full runtime
+//! don't need to depend on `coerce-core` directly.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod codec;
+pub mod message;
+pub mod stream;
+
+pub use stream::StreamData;
@@ -0,0 +1,44 @@
+//! Errors raised while serialising messages and their results
+
+use core::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub enum MessageWrapErr {
+    Unknown,
+    NotTransmittable,
+    SerializationErr,
+}
+
+impl Display for MessageWrapErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match &self {
+            MessageWrapErr::NotTransmittable => write!(f, "Message serialisation not supported, messages must override Message::as_remote_envelop and Message::write_remote_result"),
+            MessageWrapErr::SerializationErr => write!(f, "Message failed to serialise"),
+            MessageWrapErr::Unknown => write!(f, "Message failed to serialise, unknown error"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MessageWrapErr {}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MessageUnwrapErr {
+    Unknown,
+    NotTransmittable,
+    DeserializationErr,
+}
+
+impl Display for MessageUnwrapErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match &self {
+            MessageUnwrapErr::NotTransmittable => write!(f, "Message deserialisation not supported, messages must override Message::as_remote_envelope, Message::from_remote_envelope, Message::read_remote_result, and Message::write_remote_result"),
+            MessageUnwrapErr::DeserializationErr => write!(f, "Message failed to deserialise"),
+            MessageUnwrapErr::Unknown => write!(f, "Message failed to deserialise, unknown error"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MessageUnwrapErr {}
@@ -0,0 +1,29 @@
+use alloc::vec::Vec;
+use bytes::BytesMut;
+
+/// Data that can be sent over the wire, such as the events exchanged by Coerce nodes
+/// or messages published to a distributed topic
+pub trait StreamData: 'static + Send + Sync + Sized {
+    fn read_from_bytes(data: Vec<u8>) -> Option<Self>;
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>>;
+
+    /// Reads the message from a borrowed frame, implementations that don't need to take ownership
+    /// of the data should override this, avoiding a copy of every frame received
+    fn read_from_slice(data: &[u8]) -> Option<Self> {
+        Self::read_from_bytes(data.to_vec())
+    }
+
+    /// Appends the encoded message to the buffer, returning false if the message could not be
+    /// encoded. Implementations should override this to encode directly into the buffer,
+    /// allowing buffers to be reused between messages
+    fn write_to_buffer(&self, buffer: &mut BytesMut) -> bool {
+        match self.write_to_bytes() {
+            Some(bytes) => {
+                buffer.extend_from_slice(&bytes);
+                true
+            }
+            None => false,
+        }
+    }
+}
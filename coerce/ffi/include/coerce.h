@@ -0,0 +1,78 @@
+#ifndef COERCE_H
+#define COERCE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum CoerceStatus {
+    COERCE_OK = 0,
+    COERCE_PENDING = 1,
+    COERCE_INVALID_ARGUMENT = -1,
+    COERCE_ACTOR_NOT_FOUND = -2,
+    COERCE_ACTOR_EXISTS = -3,
+    COERCE_NOT_SUPPORTED = -4,
+    COERCE_SERIALISATION = -5,
+    COERCE_UNKNOWN_REQUEST = -6,
+    COERCE_FAILED = -7,
+    COERCE_PANIC = -8,
+} CoerceStatus;
+
+typedef struct CoerceSystem CoerceSystem;
+
+typedef struct CoerceSystemOptions {
+    /* the id of this node, or 0 to generate one */
+    uint64_t node_id;
+
+    /* the tag of this node, or NULL for the default tag */
+    const char *node_tag;
+
+    /* how many threads the runtime uses, or 0 for one per CPU core */
+    size_t worker_threads;
+} CoerceSystemOptions;
+
+/* a buffer allocated by Coerce, which must be freed with coerce_buffer_free */
+typedef struct CoerceBuffer {
+    uint8_t *data;
+    size_t len;
+} CoerceBuffer;
+
+/* returns NULL if the system could not be created, options may be NULL */
+CoerceSystem *coerce_system_create(const CoerceSystemOptions *options);
+
+/* seed_addr may be NULL */
+CoerceStatus coerce_system_listen(CoerceSystem *system,
+                                  const char *listen_addr,
+                                  const char *seed_addr);
+
+/* stops every actor and the runtime, the system must not be used afterwards */
+void coerce_system_destroy(CoerceSystem *system);
+
+CoerceStatus coerce_actor_spawn(CoerceSystem *system,
+                                const char *actor_type,
+                                const char *actor_id,
+                                const uint8_t *recipe,
+                                size_t recipe_len);
+
+CoerceStatus coerce_actor_send(CoerceSystem *system,
+                               const char *actor_id,
+                               const char *message_type,
+                               const uint8_t *message,
+                               size_t message_len,
+                               uint64_t *request_id);
+
+/* returns COERCE_PENDING until the reply has been received */
+CoerceStatus coerce_reply_poll(CoerceSystem *system,
+                               uint64_t request_id,
+                               CoerceBuffer *reply);
+
+void coerce_buffer_free(CoerceBuffer buffer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* COERCE_H */
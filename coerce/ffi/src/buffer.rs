@@ -0,0 +1,50 @@
+use std::ptr;
+
+/// A byte buffer allocated by Coerce, which must be freed with [`coerce_buffer_free`]
+///
+/// [`coerce_buffer_free`]: crate::coerce_buffer_free
+#[repr(C)]
+#[derive(Debug)]
+pub struct CoerceBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl CoerceBuffer {
+    pub fn empty() -> Self {
+        Self {
+            data: ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        if self.data.is_null() {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.data, self.len) }
+        }
+    }
+
+    /// # Safety
+    /// The buffer must have been created from a `Vec<u8>`, and not already freed.
+    pub(crate) unsafe fn free(self) {
+        if !self.data.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                self.data, self.len,
+            )));
+        }
+    }
+}
+
+impl From<Vec<u8>> for CoerceBuffer {
+    fn from(bytes: Vec<u8>) -> Self {
+        if bytes.is_empty() {
+            return Self::empty();
+        }
+
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+    }
+}
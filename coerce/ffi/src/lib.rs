@@ -0,0 +1,273 @@
+//! A C ABI for embedding Coerce actor systems into existing C, C++ or Python hosts.
+//!
+//! Actor kinds and message handlers are still written in Rust. The embedding library registers
+//! them with [`register`], the host then drives the system through the `coerce_*` functions:
+//!
+//! 1. [`coerce_system_create`] starts a runtime and a [`RemoteActorSystem`], optionally followed
+//!    by [`coerce_system_listen`] to join a cluster.
+//! 2. [`coerce_actor_spawn`] creates an actor of a registered kind from a serialised recipe.
+//! 3. [`coerce_actor_send`] sends a serialised message to an actor, by the name its handler was
+//!    registered with, and returns a request id.
+//! 4. [`coerce_reply_poll`] takes the serialised reply, once the actor has handled the message.
+//! 5. [`coerce_system_destroy`] shuts down the actor system, and then the runtime.
+//!
+//! The system owns its own [tokio] runtime, the `coerce_*` functions block the calling thread
+//! until the runtime has accepted the work, and must be called from host threads, never from
+//! a thread owned by the runtime. Panics never cross the ABI boundary, they're reported as
+//! [`CoerceStatus::Panic`].
+//!
+//! The matching C declarations can be found in `include/coerce.h`.
+//!
+//! [`RemoteActorSystem`]: coerce::remote::system::RemoteActorSystem
+//! [tokio]: https://github.com/tokio/tokio-rs
+
+#[macro_use]
+extern crate tracing;
+
+use crate::system::CoerceSystem;
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+pub mod buffer;
+pub mod system;
+
+pub use buffer::CoerceBuffer;
+pub use system::{register, CoerceSystemOptions};
+
+/// Returned by every `coerce_*` function that can fail
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CoerceStatus {
+    Ok = 0,
+
+    /// The reply hasn't been received yet, poll again later
+    Pending = 1,
+    InvalidArgument = -1,
+    ActorNotFound = -2,
+    ActorExists = -3,
+
+    /// The actor kind or message type hasn't been registered
+    NotSupported = -4,
+    Serialisation = -5,
+
+    /// The request id is unknown, or its reply has already been taken
+    UnknownRequest = -6,
+    Failed = -7,
+    Panic = -8,
+}
+
+fn guard(f: impl FnOnce() -> CoerceStatus) -> CoerceStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        error!("panic caught at the ffi boundary");
+        CoerceStatus::Panic
+    })
+}
+
+unsafe fn read_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        None
+    } else {
+        CStr::from_ptr(s).to_str().ok()
+    }
+}
+
+unsafe fn read_bytes<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        Some(&[])
+    } else if data.is_null() {
+        None
+    } else {
+        Some(std::slice::from_raw_parts(data, len))
+    }
+}
+
+/// Creates an actor system with every actor kind and message handler that was [`register`]ed.
+///
+/// `options` may be null, in which case the defaults are used. Returns null if the system
+/// could not be created.
+///
+/// # Safety
+/// `options` must be null or point to a valid [`CoerceSystemOptions`], whose `node_tag`
+/// is null or a valid, nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn coerce_system_create(
+    options: *const CoerceSystemOptions,
+) -> *mut CoerceSystem {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let options = options.as_ref();
+        let node_tag = match options.map(|o| o.node_tag) {
+            Some(node_tag) if !node_tag.is_null() => Some(read_str(node_tag)?.to_string()),
+            _ => None,
+        };
+
+        let node_id = options.map_or(0, |o| o.node_id);
+        let worker_threads = options.map_or(0, |o| o.worker_threads);
+
+        match CoerceSystem::new(node_id, node_tag, worker_threads) {
+            Ok(system) => Some(Box::into_raw(Box::new(system))),
+            Err(e) => {
+                error!("failed to create runtime, error={}", e);
+                None
+            }
+        }
+    }));
+
+    result.ok().flatten().unwrap_or(ptr::null_mut())
+}
+
+/// Starts listening for cluster connections on `listen_addr`, joining the cluster via
+/// `seed_addr`, if it isn't null.
+///
+/// # Safety
+/// `system` must have been returned by [`coerce_system_create`] and not yet destroyed,
+/// `listen_addr` and `seed_addr` must be null or valid, nul-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn coerce_system_listen(
+    system: *mut CoerceSystem,
+    listen_addr: *const c_char,
+    seed_addr: *const c_char,
+) -> CoerceStatus {
+    guard(|| {
+        let system = match system.as_ref() {
+            Some(system) => system,
+            None => return CoerceStatus::InvalidArgument,
+        };
+
+        let listen_addr = match read_str(listen_addr) {
+            Some(listen_addr) => listen_addr,
+            None => return CoerceStatus::InvalidArgument,
+        };
+
+        let seed_addr = match seed_addr.is_null() {
+            true => None,
+            false => match read_str(seed_addr) {
+                Some(seed_addr) => Some(seed_addr),
+                None => return CoerceStatus::InvalidArgument,
+            },
+        };
+
+        system.listen(listen_addr, seed_addr);
+        CoerceStatus::Ok
+    })
+}
+
+/// Shuts down the actor system, waits for the runtime to stop, and frees the system.
+/// Replies that haven't been polled are discarded.
+///
+/// # Safety
+/// `system` must be null, or have been returned by [`coerce_system_create`] and not yet destroyed.
+/// No other `coerce_*` calls may use the system concurrently, or after it has been destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn coerce_system_destroy(system: *mut CoerceSystem) {
+    if system.is_null() {
+        return;
+    }
+
+    let system = Box::from_raw(system);
+    if catch_unwind(AssertUnwindSafe(|| system.shutdown())).is_err() {
+        error!("panic caught whilst shutting down actor system");
+    }
+}
+
+/// Creates an actor of the registered kind `actor_type` with the id `actor_id`, from the
+/// serialised recipe. Returns once the actor has started.
+///
+/// # Safety
+/// `system` must have been returned by [`coerce_system_create`] and not yet destroyed,
+/// `actor_type` and `actor_id` must be valid, nul-terminated strings, and `recipe` must be valid
+/// for reads of `recipe_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn coerce_actor_spawn(
+    system: *mut CoerceSystem,
+    actor_type: *const c_char,
+    actor_id: *const c_char,
+    recipe: *const u8,
+    recipe_len: usize,
+) -> CoerceStatus {
+    guard(|| {
+        match (
+            system.as_ref(),
+            read_str(actor_type),
+            read_str(actor_id),
+            read_bytes(recipe, recipe_len),
+        ) {
+            (Some(system), Some(actor_type), Some(actor_id), Some(recipe)) => {
+                system.spawn(actor_type, actor_id, recipe)
+            }
+            _ => CoerceStatus::InvalidArgument,
+        }
+    })
+}
+
+/// Sends the serialised message to the actor, `message_type` is the name the message handler
+/// was registered with. The id of the request is written to `request_id`, which is used to
+/// poll for the reply.
+///
+/// # Safety
+/// `system` must have been returned by [`coerce_system_create`] and not yet destroyed,
+/// `actor_id` and `message_type` must be valid, nul-terminated strings, `message` must be valid
+/// for reads of `message_len` bytes and `request_id` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn coerce_actor_send(
+    system: *mut CoerceSystem,
+    actor_id: *const c_char,
+    message_type: *const c_char,
+    message: *const u8,
+    message_len: usize,
+    request_id: *mut u64,
+) -> CoerceStatus {
+    guard(|| {
+        if request_id.is_null() {
+            return CoerceStatus::InvalidArgument;
+        }
+
+        match (
+            system.as_ref(),
+            read_str(actor_id),
+            read_str(message_type),
+            read_bytes(message, message_len),
+        ) {
+            (Some(system), Some(actor_id), Some(message_type), Some(message)) => {
+                *request_id = system.send(actor_id, message_type, message.to_vec());
+                CoerceStatus::Ok
+            }
+            _ => CoerceStatus::InvalidArgument,
+        }
+    })
+}
+
+/// Takes the reply to the request, if it has been received. On [`CoerceStatus::Ok`], the
+/// serialised reply is written to `reply`, and must be freed with [`coerce_buffer_free`].
+///
+/// Once a reply (or error) has been returned, the request id is no longer valid.
+///
+/// # Safety
+/// `system` must have been returned by [`coerce_system_create`] and not yet destroyed,
+/// and `reply` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn coerce_reply_poll(
+    system: *mut CoerceSystem,
+    request_id: u64,
+    reply: *mut CoerceBuffer,
+) -> CoerceStatus {
+    guard(|| match (system.as_ref(), reply.is_null()) {
+        (Some(system), false) => match system.poll(request_id) {
+            Ok(bytes) => {
+                *reply = CoerceBuffer::from(bytes);
+                CoerceStatus::Ok
+            }
+            Err(status) => status,
+        },
+        _ => CoerceStatus::InvalidArgument,
+    })
+}
+
+/// Frees a buffer that was returned by Coerce
+///
+/// # Safety
+/// `buffer` must have been returned by Coerce, and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn coerce_buffer_free(buffer: CoerceBuffer) {
+    buffer.free();
+}
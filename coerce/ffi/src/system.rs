@@ -0,0 +1,207 @@
+use crate::CoerceStatus;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{ActorRefErr, ToActorId};
+use coerce::remote::system::builder::RemoteSystemConfigBuilder;
+use coerce::remote::system::{RemoteActorErr, RemoteActorSystem};
+use std::collections::HashMap;
+use std::ffi::c_char;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const RUNTIME_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub type ConfigureFn = fn(&mut RemoteSystemConfigBuilder) -> &mut RemoteSystemConfigBuilder;
+
+static REGISTRY: Mutex<Vec<ConfigureFn>> = Mutex::new(vec![]);
+
+/// Registers actor kinds and message handlers, which are applied to every system created by
+/// [`coerce_system_create`] from then on. Called by the embedding library before the host
+/// creates a system.
+///
+/// [`coerce_system_create`]: crate::coerce_system_create
+pub fn register(configure: ConfigureFn) {
+    REGISTRY.lock().unwrap().push(configure);
+}
+
+#[repr(C)]
+pub struct CoerceSystemOptions {
+    /// The id of this node, or 0 to generate one
+    pub node_id: u64,
+
+    /// The tag of this node, or null for the default tag
+    pub node_tag: *const c_char,
+
+    /// How many threads the runtime uses, or 0 for one per CPU core
+    pub worker_threads: usize,
+}
+
+type Reply = Option<Result<Vec<u8>, ActorRefErr>>;
+
+/// An actor system, along with the runtime it runs on and the replies that haven't
+/// been polled yet
+pub struct CoerceSystem {
+    runtime: Runtime,
+    remote: RemoteActorSystem,
+    replies: Arc<Mutex<HashMap<u64, Reply>>>,
+    next_request_id: AtomicU64,
+}
+
+impl CoerceSystem {
+    pub(crate) fn new(
+        node_id: u64,
+        node_tag: Option<String>,
+        worker_threads: usize,
+    ) -> std::io::Result<CoerceSystem> {
+        let mut runtime = tokio::runtime::Builder::new_multi_thread();
+        runtime.enable_all().thread_name("coerce-ffi");
+        if worker_threads > 0 {
+            runtime.worker_threads(worker_threads);
+        }
+
+        let runtime = runtime.build()?;
+        let configure: Vec<ConfigureFn> = REGISTRY.lock().unwrap().clone();
+
+        let remote = runtime.block_on(async move {
+            let mut builder = RemoteActorSystem::builder().with_actor_system(ActorSystem::new());
+            if node_id > 0 {
+                builder = builder.with_id(node_id);
+            }
+
+            if let Some(node_tag) = node_tag {
+                builder = builder.with_tag(node_tag);
+            }
+
+            for configure in configure {
+                builder = builder.configure(configure);
+            }
+
+            builder.build().await
+        });
+
+        info!(node_id = remote.node_id(), "embedded actor system started");
+
+        Ok(CoerceSystem {
+            runtime,
+            remote,
+            replies: Arc::new(Mutex::new(HashMap::new())),
+            next_request_id: AtomicU64::new(1),
+        })
+    }
+
+    pub fn remote(&self) -> &RemoteActorSystem {
+        &self.remote
+    }
+
+    pub(crate) fn listen(&self, listen_addr: &str, seed_addr: Option<&str>) {
+        let mut worker = self
+            .remote
+            .clone()
+            .cluster_worker()
+            .listen_addr(listen_addr);
+        if let Some(seed_addr) = seed_addr {
+            worker = worker.with_seed_addr(seed_addr);
+        }
+
+        self.runtime.block_on(worker.start());
+    }
+
+    pub(crate) fn spawn(&self, actor_type: &str, actor_id: &str, recipe: &[u8]) -> CoerceStatus {
+        let result = self.runtime.block_on(self.remote.handle_create_actor(
+            Some(actor_id.to_actor_id()),
+            actor_type.to_string(),
+            recipe.to_vec(),
+        ));
+
+        match result {
+            Ok(_) => CoerceStatus::Ok,
+            Err(e) => {
+                warn!(actor_type, actor_id, "failed to spawn actor, error={:?}", e);
+                e.into()
+            }
+        }
+    }
+
+    /// Dispatches the message onto the runtime, returning the id of the request
+    pub(crate) fn send(&self, actor_id: &str, message_type: &str, message: Vec<u8>) -> u64 {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        self.replies.lock().unwrap().insert(request_id, None);
+
+        let remote = self.remote.clone();
+        let replies = self.replies.clone();
+        let actor_id = actor_id.to_actor_id();
+        let message_type = message_type.to_string();
+
+        self.runtime.spawn(async move {
+            let result = remote
+                .handle_message(&message_type, actor_id, &message)
+                .await;
+
+            // the entry is gone if the system was destroyed before the reply was received
+            if let Some(reply) = replies.lock().unwrap().get_mut(&request_id) {
+                *reply = Some(result);
+            }
+        });
+
+        request_id
+    }
+
+    pub(crate) fn poll(&self, request_id: u64) -> Result<Vec<u8>, CoerceStatus> {
+        let mut replies = self.replies.lock().unwrap();
+        match replies.get(&request_id) {
+            None => Err(CoerceStatus::UnknownRequest),
+            Some(None) => Err(CoerceStatus::Pending),
+            Some(Some(_)) => match replies.remove(&request_id).flatten().unwrap() {
+                Ok(bytes) => Ok(bytes),
+                Err(e) => Err(e.into()),
+            },
+        }
+    }
+
+    /// Stops every actor and the remote system, then stops the runtime, waiting at most
+    /// [`RUNTIME_SHUTDOWN_TIMEOUT`] for any tasks that are still running
+    pub(crate) fn shutdown(self) {
+        let CoerceSystem {
+            runtime,
+            remote,
+            replies,
+            ..
+        } = self;
+
+        runtime.block_on(remote.actor_system().shutdown());
+        replies.lock().unwrap().clear();
+
+        drop(remote);
+        runtime.shutdown_timeout(RUNTIME_SHUTDOWN_TIMEOUT);
+
+        info!("embedded actor system stopped");
+    }
+}
+
+impl From<ActorRefErr> for CoerceStatus {
+    fn from(e: ActorRefErr) -> Self {
+        match e {
+            ActorRefErr::NotFound(_) => CoerceStatus::ActorNotFound,
+            ActorRefErr::AlreadyExists(_) => CoerceStatus::ActorExists,
+            ActorRefErr::NotSupported { .. } => CoerceStatus::NotSupported,
+            ActorRefErr::Serialisation(_) | ActorRefErr::Deserialisation(_) => {
+                CoerceStatus::Serialisation
+            }
+            _ => CoerceStatus::Failed,
+        }
+    }
+}
+
+impl From<RemoteActorErr> for CoerceStatus {
+    fn from(e: RemoteActorErr) -> Self {
+        match e {
+            RemoteActorErr::ActorExists => CoerceStatus::ActorExists,
+            RemoteActorErr::ActorNotSupported => CoerceStatus::NotSupported,
+            RemoteActorErr::RecipeSerializationErr
+            | RemoteActorErr::MessageSerializationErr
+            | RemoteActorErr::ResultSerializationErr => CoerceStatus::Serialisation,
+            _ => CoerceStatus::Failed,
+        }
+    }
+}
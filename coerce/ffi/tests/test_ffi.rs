@@ -0,0 +1,193 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorRecipe};
+use coerce::remote::system::builder::RemoteSystemConfigBuilder;
+use coerce_ffi::{
+    coerce_actor_send, coerce_actor_spawn, coerce_buffer_free, coerce_reply_poll,
+    coerce_system_create, coerce_system_destroy, register, CoerceBuffer, CoerceStatus,
+    CoerceSystemOptions,
+};
+use coerce_macros::JsonMessage;
+use std::ffi::CString;
+use std::ptr;
+use std::time::{Duration, Instant};
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+struct Counter {
+    count: i64,
+}
+
+impl Actor for Counter {}
+
+#[derive(Serialize, Deserialize)]
+struct CounterRecipe {
+    initial: i64,
+}
+
+impl ActorRecipe for CounterRecipe {
+    fn read_from_bytes(bytes: &Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
+
+#[derive(Clone)]
+struct CounterFactory;
+
+#[async_trait]
+impl ActorFactory for CounterFactory {
+    type Actor = Counter;
+    type Recipe = CounterRecipe;
+
+    async fn create(&self, recipe: CounterRecipe) -> Result<Counter, ActorCreationErr> {
+        Ok(Counter {
+            count: recipe.initial,
+        })
+    }
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("i64")]
+struct Increment(i64);
+
+#[async_trait]
+impl Handler<Increment> for Counter {
+    async fn handle(&mut self, message: Increment, _ctx: &mut ActorContext) -> i64 {
+        self.count += message.0;
+        self.count
+    }
+}
+
+fn counter(builder: &mut RemoteSystemConfigBuilder) -> &mut RemoteSystemConfigBuilder {
+    builder
+        .with_actor(CounterFactory)
+        .with_handler::<Counter, Increment>("Increment")
+}
+
+unsafe fn wait_for_reply(
+    system: *mut coerce_ffi::system::CoerceSystem,
+    request_id: u64,
+) -> (CoerceStatus, CoerceBuffer) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut reply = CoerceBuffer::empty();
+    loop {
+        let status = coerce_reply_poll(system, request_id, &mut reply);
+        if status != CoerceStatus::Pending || Instant::now() > deadline {
+            return (status, reply);
+        }
+
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+pub fn test_ffi_spawn_send_and_poll() {
+    register(counter);
+
+    let node_tag = CString::new("ffi-node").unwrap();
+    let options = CoerceSystemOptions {
+        node_id: 1,
+        node_tag: node_tag.as_ptr(),
+        worker_threads: 2,
+    };
+
+    unsafe {
+        let system = coerce_system_create(&options);
+        assert!(!system.is_null());
+
+        let actor_type = CString::new(std::any::type_name::<Counter>()).unwrap();
+        let actor_id = CString::new("counter-1").unwrap();
+        let recipe = serde_json::to_vec(&CounterRecipe { initial: 10 }).unwrap();
+
+        let status = coerce_actor_spawn(
+            system,
+            actor_type.as_ptr(),
+            actor_id.as_ptr(),
+            recipe.as_ptr(),
+            recipe.len(),
+        );
+        assert_eq!(status, CoerceStatus::Ok);
+
+        let message_type = CString::new("Increment").unwrap();
+        let message = serde_json::to_vec(&Increment(5)).unwrap();
+        let mut request_id = 0;
+
+        let status = coerce_actor_send(
+            system,
+            actor_id.as_ptr(),
+            message_type.as_ptr(),
+            message.as_ptr(),
+            message.len(),
+            &mut request_id,
+        );
+        assert_eq!(status, CoerceStatus::Ok);
+
+        let (status, reply) = wait_for_reply(system, request_id);
+        assert_eq!(status, CoerceStatus::Ok);
+        assert_eq!(serde_json::from_slice::<i64>(reply.as_slice()).unwrap(), 15);
+        coerce_buffer_free(reply);
+
+        // the reply can only be taken once
+        let mut reply = CoerceBuffer::empty();
+        assert_eq!(
+            coerce_reply_poll(system, request_id, &mut reply),
+            CoerceStatus::UnknownRequest
+        );
+
+        let unknown_type = CString::new("Decrement").unwrap();
+        coerce_actor_send(
+            system,
+            actor_id.as_ptr(),
+            unknown_type.as_ptr(),
+            message.as_ptr(),
+            message.len(),
+            &mut request_id,
+        );
+        assert_eq!(
+            wait_for_reply(system, request_id).0,
+            CoerceStatus::NotSupported
+        );
+
+        let status = coerce_actor_spawn(
+            system,
+            actor_type.as_ptr(),
+            actor_id.as_ptr(),
+            recipe.as_ptr(),
+            recipe.len(),
+        );
+        assert_eq!(status, CoerceStatus::ActorExists);
+
+        let unknown_kind = CString::new("Unknown").unwrap();
+        let other_actor_id = CString::new("counter-2").unwrap();
+        let status = coerce_actor_spawn(
+            system,
+            unknown_kind.as_ptr(),
+            other_actor_id.as_ptr(),
+            recipe.as_ptr(),
+            recipe.len(),
+        );
+        assert_eq!(status, CoerceStatus::NotSupported);
+
+        assert_eq!(
+            coerce_actor_send(
+                system,
+                ptr::null(),
+                message_type.as_ptr(),
+                message.as_ptr(),
+                message.len(),
+                &mut request_id
+            ),
+            CoerceStatus::InvalidArgument
+        );
+
+        coerce_system_destroy(system);
+    }
+}
@@ -13,13 +13,13 @@ use syn::DeriveInput;
 
 const MESSAGE_ATTR: &str = "result";
 
-#[proc_macro_derive(JsonMessage, attributes(result))]
+#[proc_macro_derive(JsonMessage, attributes(result, delivery))]
 pub fn json_message_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     message::json::expand(&ast).into()
 }
 
-#[proc_macro_derive(JsonSnapshot)]
+#[proc_macro_derive(JsonSnapshot, attributes(schema_version))]
 pub fn json_snapshot_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     snapshot::json::expand(&ast).into()
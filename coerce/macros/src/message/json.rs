@@ -4,6 +4,7 @@ use quote::{quote, ToTokens};
 use crate::get_attribute_type_multiple;
 
 const RESULT_ATTR: &str = "result";
+const DELIVERY_ATTR: &str = "delivery";
 
 pub(crate) fn expand(ast: &syn::DeriveInput) -> TokenStream {
     let item_type = {
@@ -33,6 +34,17 @@ pub(crate) fn expand(ast: &syn::DeriveInput) -> TokenStream {
         .map(ToTokens::into_token_stream)
         .unwrap_or_else(|| quote! { () });
 
+    // #[delivery(fire_and_forget | ordered | reliable | priority)], defaults to ordered
+    let delivery_semantics = match delivery_semantics(ast) {
+        Ok(Some(semantics)) => quote! {
+            fn delivery_semantics() -> coerce::actor::message::DeliverySemantics {
+                coerce::actor::message::DeliverySemantics::#semantics
+            }
+        },
+        Ok(None) => quote! {},
+        Err(err) => return err.to_compile_error(),
+    };
+
     quote! {
         impl #impl_generics ::coerce::actor::message::Message for #name #ty_generics #where_clause {
             type Result = #item_type;
@@ -53,6 +65,40 @@ pub(crate) fn expand(ast: &syn::DeriveInput) -> TokenStream {
             fn write_remote_result(res: Self::Result) -> Result<Vec<u8>, coerce::actor::message::MessageWrapErr> {
                 serde_json::to_vec(&res).map_err(|_e| coerce::actor::message::MessageWrapErr::SerializationErr)
             }
+
+            #delivery_semantics
         }
     }
 }
+
+fn delivery_semantics(ast: &syn::DeriveInput) -> syn::Result<Option<syn::Ident>> {
+    let attr = match ast.attrs.iter().find(|a| a.path.is_ident(DELIVERY_ATTR)) {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+
+    let semantics = attr.parse_args::<syn::Ident>().map_err(|_| {
+        syn::Error::new_spanned(
+            attr,
+            format!(
+                "The correct syntax is #[{}(fire_and_forget | ordered | reliable | priority)]",
+                DELIVERY_ATTR
+            ),
+        )
+    })?;
+
+    let variant = match semantics.to_string().as_str() {
+        "fire_and_forget" => "FireAndForget",
+        "ordered" => "Ordered",
+        "reliable" => "Reliable",
+        "priority" => "Priority",
+        other => {
+            return Err(syn::Error::new_spanned(
+                &semantics,
+                format!("unknown delivery semantics `{}`", other),
+            ))
+        }
+    };
+
+    Ok(Some(syn::Ident::new(variant, semantics.span())))
+}
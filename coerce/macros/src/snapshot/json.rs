@@ -5,6 +5,17 @@ pub(crate) fn expand(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
+    // #[schema_version(n)], defaults to 1
+    let schema_version = ast
+        .attrs
+        .iter()
+        .find(|a| a.path.is_ident("schema_version"))
+        .map(|a| {
+            a.parse_args::<syn::LitInt>()
+                .expect("The correct syntax is #[schema_version(n)]")
+        })
+        .map_or_else(|| quote! { 1 }, |version| quote! { #version });
+
     quote! {
         impl #impl_generics ::coerce::persistent::journal::snapshot::Snapshot for #name #ty_generics #where_clause {
             fn into_remote_envelope(self) -> Result<coerce::actor::message::Envelope<Self>, coerce::actor::message::MessageWrapErr> {
@@ -16,6 +27,14 @@ pub(crate) fn expand(ast: &syn::DeriveInput) -> TokenStream {
             fn from_remote_envelope(bytes: Vec<u8>) -> Result<Self, coerce::actor::message::MessageUnwrapErr> {
                 serde_json::from_slice(bytes.as_slice()).map_err(|_e| coerce::actor::message::MessageUnwrapErr::DeserializationErr)
             }
+
+            fn schema_version() -> u32 {
+                #schema_version
+            }
+
+            fn serializer_id() -> &'static str {
+                "json"
+            }
         }
     }
 }
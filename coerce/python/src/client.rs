@@ -0,0 +1,226 @@
+use crate::events::{ClusterEvents, EventForwarder};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{ActorRefErr, IntoActor, ToActorId};
+use coerce::remote::admin::{AdminCommand, AdminRequest, AdminResponse};
+use coerce::remote::cluster::node::RemoteNodeState;
+use coerce::remote::system::{NodeId, RemoteActorSystem};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const RUNTIME_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum ClientErr {
+    Runtime(std::io::Error),
+    Actor(ActorRefErr),
+    Timeout,
+}
+
+impl Display for ClientErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientErr::Runtime(e) => write!(f, "failed to start runtime: {}", e),
+            ClientErr::Actor(e) => write!(f, "{}", e),
+            ClientErr::Timeout => write!(f, "timed out"),
+        }
+    }
+}
+
+impl std::error::Error for ClientErr {}
+
+impl From<ActorRefErr> for ClientErr {
+    fn from(e: ActorRefErr) -> Self {
+        ClientErr::Actor(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Where this client accepts connections from other nodes in the cluster
+    pub listen_addr: String,
+    pub seed_addr: String,
+
+    /// The id of this client's node, generated if not provided
+    pub node_id: Option<NodeId>,
+    pub node_tag: Option<String>,
+}
+
+/// Joins a Coerce cluster as a node without any actors, allowing messages to be sent to actors
+/// anywhere in the cluster by the name their message handler was registered with.
+///
+/// Every method blocks the calling thread, the client owns the runtime that the remote
+/// system runs on.
+pub struct ClusterClient {
+    runtime: Runtime,
+    remote: RemoteActorSystem,
+}
+
+impl ClusterClient {
+    /// Starts the client's node and waits until it has joined the cluster via the seed node
+    pub fn connect(config: ClientConfig) -> Result<ClusterClient, ClientErr> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("coerce-client")
+            .build()
+            .map_err(ClientErr::Runtime)?;
+
+        let remote = runtime.block_on(async {
+            let mut builder = RemoteActorSystem::builder().with_actor_system(ActorSystem::new());
+            if let Some(node_id) = config.node_id {
+                builder = builder.with_id(node_id);
+            }
+
+            if let Some(node_tag) = config.node_tag {
+                builder = builder.with_tag(node_tag);
+            }
+
+            let remote = builder.build().await;
+            remote
+                .clone()
+                .cluster_worker()
+                .listen_addr(config.listen_addr)
+                .with_seed_addr(config.seed_addr)
+                .start()
+                .await;
+
+            remote
+        });
+
+        let client = ClusterClient { runtime, remote };
+        let joined = client
+            .runtime
+            .block_on(client.remote.wait_for_members(2, CONNECT_TIMEOUT));
+
+        if joined.is_err() {
+            client.close();
+            return Err(ClientErr::Timeout);
+        }
+
+        info!(node_id = client.node_id(), "cluster client connected");
+        Ok(client)
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.remote.node_id()
+    }
+
+    /// Returns every node in the cluster, as known by the client's node
+    pub fn members(&self) -> Vec<RemoteNodeState> {
+        self.runtime.block_on(self.remote.get_nodes())
+    }
+
+    /// Returns the ID of the node hosting the actor, if the actor is registered with the cluster
+    pub fn locate_actor(&self, actor_id: &str) -> Option<NodeId> {
+        self.runtime
+            .block_on(self.remote.locate_actor_node(actor_id.to_actor_id()))
+    }
+
+    /// Locates the shard that the sharded entity belongs to, and the node hosting it,
+    /// see [`locate_shard_in_cluster`]
+    ///
+    /// [`locate_shard_in_cluster`]: coerce::sharding::host::locate::locate_shard_in_cluster
+    #[cfg(feature = "sharding")]
+    pub fn locate_entity(
+        &self,
+        entity_type: &str,
+        entity_id: &str,
+    ) -> Option<coerce::sharding::host::locate::ShardLocation> {
+        use coerce::sharding::host::locate::locate_shard_in_cluster;
+
+        self.runtime.block_on(locate_shard_in_cluster(
+            entity_type,
+            entity_id.to_actor_id(),
+            &self.remote,
+        ))
+    }
+
+    /// Subscribes to the system events published on the client's node, such as nodes joining
+    /// or leaving the cluster
+    pub fn cluster_events(&self) -> Result<ClusterEvents, ClientErr> {
+        let (forwarder, events) = EventForwarder::new();
+        let forwarder = self.runtime.block_on(
+            forwarder.into_anon_actor(Option::<String>::None, self.remote.actor_system()),
+        )?;
+
+        Ok(ClusterEvents::new(
+            self.runtime.handle().clone(),
+            events,
+            forwarder,
+        ))
+    }
+
+    /// Sends the serialised message to the actor and waits for the serialised reply
+    pub fn ask(
+        &self,
+        actor_id: &str,
+        message_type: &str,
+        message: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>, ClientErr> {
+        let send = self
+            .remote
+            .send_raw(message_type, actor_id.to_actor_id(), message);
+
+        self.runtime.block_on(async {
+            match timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, send).await {
+                    Ok(res) => Ok(res?),
+                    Err(_) => Err(ClientErr::Timeout),
+                },
+                None => Ok(send.await?),
+            }
+        })
+    }
+
+    /// Sends the serialised message to the actor, without waiting for it to be handled
+    pub fn tell(
+        &self,
+        actor_id: &str,
+        message_type: &str,
+        message: Vec<u8>,
+    ) -> Result<(), ClientErr> {
+        let notify = self
+            .remote
+            .notify_raw(message_type, actor_id.to_actor_id(), message);
+
+        Ok(self.runtime.block_on(notify)?)
+    }
+
+    /// Sends the admin command to the node with the provided ID, which must have been started
+    /// with [`RemoteActorSystemBuilder::with_admin`], see [`coerce::remote::admin`]
+    ///
+    /// [`RemoteActorSystemBuilder::with_admin`]: coerce::remote::system::builder::RemoteActorSystemBuilder::with_admin
+    pub fn admin(
+        &self,
+        node_id: NodeId,
+        token: &str,
+        command: AdminCommand,
+        timeout: Option<Duration>,
+    ) -> Result<AdminResponse, ClientErr> {
+        let send = self
+            .remote
+            .node_admin(node_id, AdminRequest::new(token, command));
+
+        self.runtime.block_on(async {
+            match timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, send).await {
+                    Ok(res) => Ok(res?),
+                    Err(_) => Err(ClientErr::Timeout),
+                },
+                None => Ok(send.await?),
+            }
+        })
+    }
+
+    /// Leaves the cluster and stops the runtime
+    pub fn close(self) {
+        let ClusterClient { runtime, remote } = self;
+
+        runtime.block_on(remote.actor_system().shutdown());
+
+        drop(remote);
+        runtime.shutdown_timeout(RUNTIME_SHUTDOWN_TIMEOUT);
+    }
+}
@@ -0,0 +1,88 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::{Actor, LocalActorRef};
+use coerce::remote::stream::pubsub::{PubSub, Receive, Subscription};
+use coerce::remote::stream::system::{SystemEvent, SystemTopic};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// System events published on the client's node, such as nodes joining or leaving the cluster,
+/// see [`ClusterClient::cluster_events`]
+///
+/// [`ClusterClient::cluster_events`]: crate::client::ClusterClient::cluster_events
+pub struct ClusterEvents {
+    runtime: Handle,
+    events: UnboundedReceiver<Arc<SystemEvent>>,
+    forwarder: LocalActorRef<EventForwarder>,
+}
+
+impl ClusterEvents {
+    pub(crate) fn new(
+        runtime: Handle,
+        events: UnboundedReceiver<Arc<SystemEvent>>,
+        forwarder: LocalActorRef<EventForwarder>,
+    ) -> Self {
+        Self {
+            runtime,
+            events,
+            forwarder,
+        }
+    }
+
+    /// Waits for the next event, returning `None` if the client has been closed
+    pub fn recv(&mut self) -> Option<Arc<SystemEvent>> {
+        self.runtime.block_on(self.events.recv())
+    }
+
+    /// Waits up to `timeout` for the next event
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<Arc<SystemEvent>> {
+        let events = &mut self.events;
+        self.runtime
+            .block_on(async { tokio::time::timeout(timeout, events.recv()).await })
+            .ok()
+            .flatten()
+    }
+}
+
+impl Drop for ClusterEvents {
+    fn drop(&mut self) {
+        let _ = self.forwarder.notify_stop();
+    }
+}
+
+pub(crate) struct EventForwarder {
+    events: UnboundedSender<Arc<SystemEvent>>,
+    subscription: Option<Subscription>,
+}
+
+impl EventForwarder {
+    pub fn new() -> (Self, UnboundedReceiver<Arc<SystemEvent>>) {
+        let (events, rx) = mpsc::unbounded_channel();
+        let forwarder = Self {
+            events,
+            subscription: None,
+        };
+
+        (forwarder, rx)
+    }
+}
+
+#[async_trait]
+impl Actor for EventForwarder {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.subscription = PubSub::subscribe::<Self, SystemTopic>(SystemTopic, ctx)
+            .await
+            .ok();
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<SystemTopic>> for EventForwarder {
+    async fn handle(&mut self, message: Receive<SystemTopic>, ctx: &mut ActorContext) {
+        if self.events.send(message.0).is_err() {
+            ctx.stop(None);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! A Python client for Coerce clusters.
+//!
+//! The client joins the cluster as a node without any actors, and sends serialised messages to
+//! actors by the name their message handler was registered with (see
+//! [`RemoteSystemConfigBuilder::with_handler`]). Messages and replies are passed as `bytes`, so
+//! they must be serialised in the format the actor's message type expects, for example JSON for
+//! messages derived with `JsonMessage`.
+//!
+//! The Python module is built with [maturin], from this directory:
+//! ```sh
+//! maturin develop --release
+//! ```
+//!
+//! ```python
+//! import json
+//! import coerce
+//!
+//! client = coerce.Client("127.0.0.1:30200", seed_addr="127.0.0.1:30100")
+//! reply = client.ask("user-1", "GetStatusRequest", json.dumps(None).encode(), timeout=5.0)
+//! client.tell("user-1", "SetStatusRequest", json.dumps({"status": "Active"}).encode())
+//! client.close()
+//! ```
+//!
+//! [`RemoteSystemConfigBuilder::with_handler`]: coerce::remote::system::builder::RemoteSystemConfigBuilder::with_handler
+//! [maturin]: https://github.com/PyO3/maturin
+
+#[macro_use]
+extern crate tracing;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod client;
+pub mod events;
+
+#[cfg(feature = "python")]
+mod python;
@@ -0,0 +1,130 @@
+use crate::client::{ClientConfig, ClientErr, ClusterClient};
+use coerce::actor::ActorRefErr;
+use coerce::remote::system::NodeId;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyRuntimeError, PyTimeoutError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::time::Duration;
+
+create_exception!(coerce, CoerceError, PyException);
+create_exception!(coerce, ActorNotFoundError, CoerceError);
+
+impl From<ClientErr> for PyErr {
+    fn from(e: ClientErr) -> Self {
+        match e {
+            ClientErr::Runtime(e) => PyRuntimeError::new_err(e.to_string()),
+            ClientErr::Timeout => PyTimeoutError::new_err(e.to_string()),
+            ClientErr::Actor(ActorRefErr::NotFound(id)) => {
+                ActorNotFoundError::new_err(format!("actor {} could not be found", id))
+            }
+            ClientErr::Actor(e) => CoerceError::new_err(e.to_string()),
+        }
+    }
+}
+
+/// A connection to a Coerce cluster
+#[pyclass(name = "Client", module = "coerce")]
+struct PyClient {
+    client: Option<ClusterClient>,
+}
+
+impl PyClient {
+    fn client(&self) -> PyResult<&ClusterClient> {
+        self.client
+            .as_ref()
+            .ok_or_else(|| CoerceError::new_err("client is closed"))
+    }
+}
+
+#[pymethods]
+impl PyClient {
+    #[new]
+    #[pyo3(signature = (listen_addr, seed_addr, node_id=None, node_tag=None))]
+    fn new(
+        py: Python<'_>,
+        listen_addr: String,
+        seed_addr: String,
+        node_id: Option<NodeId>,
+        node_tag: Option<String>,
+    ) -> PyResult<Self> {
+        let config = ClientConfig {
+            listen_addr,
+            seed_addr,
+            node_id,
+            node_tag,
+        };
+
+        let client = py.allow_threads(|| ClusterClient::connect(config))?;
+        Ok(PyClient {
+            client: Some(client),
+        })
+    }
+
+    #[getter]
+    fn node_id(&self) -> PyResult<NodeId> {
+        Ok(self.client()?.node_id())
+    }
+
+    /// Sends the message to the actor and waits for the reply, `timeout` is in seconds
+    #[pyo3(signature = (actor_id, message_type, message, timeout=None))]
+    fn ask<'py>(
+        &self,
+        py: Python<'py>,
+        actor_id: &str,
+        message_type: &str,
+        message: Vec<u8>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let client = self.client()?;
+        let timeout = timeout.map(Duration::from_secs_f64);
+        let reply = py.allow_threads(|| client.ask(actor_id, message_type, message, timeout))?;
+
+        Ok(PyBytes::new(py, &reply))
+    }
+
+    /// Sends the message to the actor, without waiting for it to be handled
+    fn tell(
+        &self,
+        py: Python<'_>,
+        actor_id: &str,
+        message_type: &str,
+        message: Vec<u8>,
+    ) -> PyResult<()> {
+        let client = self.client()?;
+        Ok(py.allow_threads(|| client.tell(actor_id, message_type, message))?)
+    }
+
+    /// Leaves the cluster, the client can't be used afterwards
+    fn close(&mut self, py: Python<'_>) {
+        if let Some(client) = self.client.take() {
+            py.allow_threads(|| client.close());
+        }
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) {
+        self.close(py);
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "coerce")]
+fn coerce_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyClient>()?;
+    m.add("CoerceError", m.py().get_type::<CoerceError>())?;
+    m.add(
+        "ActorNotFoundError",
+        m.py().get_type::<ActorNotFoundError>(),
+    )?;
+    Ok(())
+}
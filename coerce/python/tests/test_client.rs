@@ -0,0 +1,210 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorRefErr, IntoActor};
+use coerce::remote::stream::system::{ClusterEvent, SystemEvent};
+use coerce::remote::system::RemoteActorSystem;
+use coerce_macros::JsonMessage;
+use coerce_py::client::{ClientConfig, ClientErr, ClusterClient};
+use std::time::Duration;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+struct Counter {
+    count: i64,
+}
+
+impl Actor for Counter {}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("i64")]
+struct Increment(i64);
+
+#[async_trait]
+impl Handler<Increment> for Counter {
+    async fn handle(&mut self, message: Increment, _ctx: &mut ActorContext) -> i64 {
+        self.count += message.0;
+        self.count
+    }
+}
+
+#[test]
+pub fn test_cluster_client_ask_and_tell() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let remote = runtime.block_on(async {
+        let remote = RemoteActorSystem::builder()
+            .with_actor_system(ActorSystem::new())
+            .with_id(1)
+            .with_handlers(|builder| builder.with_handler::<Counter, Increment>("Increment"))
+            .build()
+            .await;
+
+        remote
+            .clone()
+            .cluster_worker()
+            .listen_addr("localhost:31101")
+            .start()
+            .await;
+
+        remote
+    });
+
+    let client = ClusterClient::connect(ClientConfig {
+        listen_addr: "localhost:31102".to_string(),
+        seed_addr: "localhost:31101".to_string(),
+        node_id: Some(2),
+        node_tag: None,
+    })
+    .unwrap();
+
+    runtime.block_on(async {
+        remote
+            .wait_for_members(2, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        Counter { count: 0 }
+            .into_actor(Some("counter"), remote.actor_system())
+            .await
+            .unwrap();
+    });
+
+    let increment = |n| serde_json::to_vec(&Increment(n)).unwrap();
+    let timeout = Some(Duration::from_secs(5));
+
+    // the actor may be registered with the client's node, which happens asynchronously
+    let mut attempts = 0;
+    let reply = loop {
+        match client.ask("counter", "Increment", increment(5), timeout) {
+            Err(ClientErr::Actor(ActorRefErr::NotFound(_))) if attempts < 50 => {
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            res => break res.unwrap(),
+        }
+    };
+    assert_eq!(serde_json::from_slice::<i64>(&reply).unwrap(), 5);
+
+    client.tell("counter", "Increment", increment(2)).unwrap();
+
+    let reply = client
+        .ask("counter", "Increment", increment(1), timeout)
+        .unwrap();
+    assert_eq!(serde_json::from_slice::<i64>(&reply).unwrap(), 8);
+
+    let err = client
+        .ask("counter", "Decrement", increment(1), timeout)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ClientErr::Actor(ActorRefErr::NotSupported { .. })
+    ));
+
+    let err = client
+        .ask("missing", "Increment", increment(1), timeout)
+        .unwrap_err();
+    assert!(matches!(err, ClientErr::Actor(ActorRefErr::NotFound(_))));
+
+    client.close();
+    runtime.block_on(remote.actor_system().shutdown());
+}
+
+#[test]
+pub fn test_cluster_client_members_and_events() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let remote = runtime.block_on(async {
+        let remote = RemoteActorSystem::builder()
+            .with_actor_system(ActorSystem::new())
+            .with_id(1)
+            .build()
+            .await;
+
+        remote
+            .clone()
+            .cluster_worker()
+            .listen_addr("localhost:31103")
+            .start()
+            .await;
+
+        remote
+    });
+
+    let client = ClusterClient::connect(ClientConfig {
+        listen_addr: "localhost:31104".to_string(),
+        seed_addr: "localhost:31103".to_string(),
+        node_id: Some(2),
+        node_tag: Some("client".to_string()),
+    })
+    .unwrap();
+
+    let mut members = client.members();
+    members.sort_by_key(|node| node.id);
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].addr, "localhost:31103");
+    assert_eq!(members[1].tag, "client");
+
+    runtime.block_on(async {
+        Counter { count: 0 }
+            .into_actor(Some("counter"), remote.actor_system())
+            .await
+            .unwrap();
+    });
+
+    // the actor may be registered with the client's node, which happens asynchronously
+    let mut attempts = 0;
+    let counter_node = loop {
+        match client.locate_actor("counter") {
+            None if attempts < 50 => {
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            node => break node,
+        }
+    };
+    assert_eq!(counter_node, Some(1));
+    assert_eq!(client.locate_actor("missing"), None);
+
+    let mut events = client.cluster_events().unwrap();
+
+    let node_3 = runtime.block_on(async {
+        let remote = RemoteActorSystem::builder()
+            .with_actor_system(ActorSystem::new())
+            .with_id(3)
+            .build()
+            .await;
+
+        remote
+            .clone()
+            .cluster_worker()
+            .listen_addr("localhost:31105")
+            .with_seed_addr("localhost:31103")
+            .start()
+            .await;
+
+        remote
+    });
+
+    let node_added = loop {
+        let event = events
+            .recv_timeout(Duration::from_secs(5))
+            .expect("cluster event");
+
+        let SystemEvent::Cluster(ClusterEvent::NodeAdded(node)) = event.as_ref() else {
+            continue;
+        };
+
+        break node.id;
+    };
+    assert_eq!(node_added, 3);
+
+    drop(events);
+    client.close();
+    runtime.block_on(async {
+        node_3.actor_system().shutdown().await;
+        remote.actor_system().shutdown().await;
+    });
+}
@@ -59,10 +59,7 @@ impl<A: Actor> LocalActorRef<A> {
         ActorMetrics::incr_messages_sent(A::type_name(), msg.name());
 
         let (tx, rx) = oneshot::channel();
-        match self
-            .sender()
-            .send(Box::new(ActorMessage::new(msg, Some(tx))))
-        {
+        match self.enqueue(Box::new(ActorMessage::new(msg, Some(tx)))) {
             Ok(_) => match rx.blocking_recv() {
                 Ok(res) => {
                     trace!(
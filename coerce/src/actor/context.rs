@@ -1,22 +1,27 @@
 //! Actor Context
 
-use crate::actor::message::{Handler, Message};
+use crate::actor::mailbox::SequenceToken;
+use crate::actor::message::{ActorMessage, Handler, Message, MessageHandler};
 use crate::actor::metrics::ActorMetrics;
+use crate::actor::rt;
 use crate::actor::system::ActorSystem;
 use crate::actor::{
-    Actor, ActorId, ActorPath, ActorRefErr, ActorTags, BoxedActorRef, CoreActorRef, IntoActorPath,
-    LocalActorRef,
+    Actor, ActorId, ActorPath, ActorRefErr, ActorTags, BoxedActorRef, CoreActorRef, IntoActorId,
+    IntoActorPath, LocalActorRef, Receiver,
 };
 use futures::{Stream, StreamExt};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::VecDeque;
 
 use tokio::sync::oneshot::Sender;
 use valuable::{Fields, NamedField, NamedValues, StructDef, Structable, Valuable, Value, Visit};
 
-use crate::actor::supervised::{ChildRef, Supervised};
+use crate::actor::supervised::{ChildRef, RestartPolicy, Supervised};
 use crate::actor::watch::watchers::Watchers;
+use crate::actor::watch::{ActorTerminated, Watchable};
 
+#[cfg(feature = "persistence")]
+use crate::persistent::checkpoint::CheckpointState;
 #[cfg(feature = "persistence")]
 use crate::persistent::context::ActorPersistence;
 
@@ -36,12 +41,48 @@ pub struct ActorContext {
     supervised: Option<Supervised>,
     system: Option<ActorSystem>,
     on_actor_stopped: Option<Vec<Sender<()>>>,
+    graceful_stop: bool,
     tags: ActorTags,
     full_path: ActorPath,
     watchers: Option<Watchers>,
+    deferred: VecDeque<DeferredMessage>,
+    reply_channel: Option<Box<dyn Any + Send + Sync>>,
 
     #[cfg(feature = "persistence")]
     persistence: Option<ActorPersistence>,
+
+    #[cfg(feature = "persistence")]
+    checkpoint: CheckpointState,
+}
+
+/// A message held by the actor until its virtual time reaches `token`, the handler is
+/// a [`MessageHandler`] of the actor, type-erased since the context isn't generic over the actor
+struct DeferredMessage {
+    token: SequenceToken,
+    handler: Box<dyn Any + Send + Sync>,
+}
+
+/// The reply channel of a message, taken from the [`ActorContext`] via
+/// [`ActorContext::defer_reply`], so the result can be sent after the handler has returned,
+/// for example, from a spawned task.
+pub struct DeferredReply<R> {
+    sender: Sender<R>,
+}
+
+impl<R> DeferredReply<R> {
+    /// Sends the result to the sender of the message, returning `false` if the sender
+    /// is no longer waiting for the result
+    pub fn reply(self, result: R) -> bool {
+        self.sender.send(result).is_ok()
+    }
+}
+
+impl<T, E> DeferredReply<Result<T, E>> {
+    /// Sends the error to the sender of the message, returning `false` if the sender
+    /// is no longer waiting for the result
+    pub fn reply_err(self, err: E) -> bool {
+        self.reply(Err(err))
+    }
 }
 
 #[derive(Debug)]
@@ -70,11 +111,16 @@ impl ActorContext {
             supervised: None,
             boxed_parent_ref: None,
             on_actor_stopped: None,
+            graceful_stop: false,
             watchers: None,
+            deferred: VecDeque::new(),
+            reply_channel: None,
             tags,
             // last_message_timestamp: None,
             #[cfg(feature = "persistence")]
             persistence: None,
+            #[cfg(feature = "persistence")]
+            checkpoint: CheckpointState::default(),
         }
     }
 
@@ -103,6 +149,8 @@ impl ActorContext {
         self.tags.clone()
     }
 
+    /// Stops the actor once the current message has been processed, any messages that remain
+    /// enqueued are discarded and published as dead letters.
     pub fn stop(&mut self, on_stopped_handler: Option<Sender<()>>) {
         if let Some(sender) = on_stopped_handler {
             self.add_on_stopped_handler(sender);
@@ -111,6 +159,17 @@ impl ActorContext {
         self.set_status(ActorStatus::Stopping);
     }
 
+    /// Stops the actor once all messages that have already been enqueued have been processed,
+    /// new messages are rejected as soon as the stop begins.
+    pub fn stop_gracefully(&mut self, on_stopped_handler: Option<Sender<()>>) {
+        self.graceful_stop = true;
+        self.stop(on_stopped_handler);
+    }
+
+    pub fn is_stopping_gracefully(&self) -> bool {
+        self.graceful_stop && self.status == ActorStatus::Stopping
+    }
+
     pub fn system(&self) -> &ActorSystem {
         if let Some(system) = &self.system {
             system
@@ -119,6 +178,10 @@ impl ActorContext {
         }
     }
 
+    pub(crate) fn try_system(&self) -> Option<&ActorSystem> {
+        self.system.as_ref()
+    }
+
     pub fn set_system(&mut self, system: ActorSystem) {
         self.system = Some(system);
     }
@@ -143,6 +206,106 @@ impl ActorContext {
             .clone()
     }
 
+    /// Delivers the message to this actor once every message currently queued in its mailbox has
+    /// been received, ahead of any messages that are enqueued after this call, returning the
+    /// [`SequenceToken`] the message will be delivered at.
+    ///
+    /// This allows multi-step operations to be split across messages, with the guarantee that
+    /// the next step runs once the current backlog has been processed, without waiting behind
+    /// messages that arrive afterwards.
+    pub fn notify_after_queued<A, M: Message>(&mut self, msg: M) -> SequenceToken
+    where
+        A: Actor + Handler<M>,
+    {
+        let token = self.actor_ref::<A>().sequence_token();
+        self.notify_at::<A, M>(token, msg);
+        token
+    }
+
+    /// Delivers the message to this actor once its virtual time has reached `token`, if the token
+    /// has already been reached, the message is delivered once the current message has been handled.
+    ///
+    /// Deferred messages are only delivered while the actor is running, any messages that remain
+    /// when the actor stops are discarded.
+    pub fn notify_at<A, M: Message>(&mut self, token: SequenceToken, msg: M)
+    where
+        A: Actor + Handler<M>,
+    {
+        let handler: MessageHandler<A> = Box::new(ActorMessage::new(msg, None));
+        let deferred = DeferredMessage {
+            token,
+            handler: Box::new(handler),
+        };
+
+        // deferred messages are kept ordered by token, messages with the same token are
+        // delivered in the order they were deferred
+        let position = self.deferred.partition_point(|d| d.token <= token);
+        self.deferred.insert(position, deferred);
+    }
+
+    pub(crate) fn take_due_message<A: Actor>(
+        &mut self,
+        virtual_time: u64,
+    ) -> Option<MessageHandler<A>> {
+        if !self.deferred.front()?.token.is_reached(virtual_time) {
+            return None;
+        }
+
+        let deferred = self.deferred.pop_front()?;
+        deferred
+            .handler
+            .downcast::<MessageHandler<A>>()
+            .ok()
+            .map(|h| *h)
+    }
+
+    /// Sends the result to the sender of the message currently being handled, before the handler
+    /// has returned. The value the handler returns is then discarded.
+    ///
+    /// Returns `false` if the message wasn't sent as a request, the reply was already sent,
+    /// `R` isn't the result type of the message, or the sender is no longer waiting for the result.
+    pub fn reply<R: 'static + Send + Sync>(&mut self, result: R) -> bool {
+        self.defer_reply()
+            .is_some_and(|reply: DeferredReply<R>| reply.reply(result))
+    }
+
+    /// Sends the error to the sender of the message currently being handled, for messages
+    /// whose result is a `Result<T, E>`, see [`ActorContext::reply`]
+    pub fn reply_err<T: 'static + Send + Sync, E: 'static + Send + Sync>(
+        &mut self,
+        err: E,
+    ) -> bool {
+        self.reply::<Result<T, E>>(Err(err))
+    }
+
+    /// Takes the reply channel of the message currently being handled, so the result can be sent
+    /// after the handler has returned, without blocking the actor from handling further messages.
+    /// The value the handler returns is then discarded.
+    ///
+    /// Returns `None` if the message wasn't sent as a request, the reply was already sent,
+    /// or `R` isn't the result type of the message.
+    pub fn defer_reply<R: 'static + Send + Sync>(&mut self) -> Option<DeferredReply<R>> {
+        let reply_channel = self.reply_channel.take()?;
+        match reply_channel.downcast::<Sender<R>>() {
+            Ok(sender) => Some(DeferredReply { sender: *sender }),
+            Err(reply_channel) => {
+                self.reply_channel = Some(reply_channel);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn set_reply_channel<R: 'static + Send + Sync>(
+        &mut self,
+        sender: Option<Sender<R>>,
+    ) {
+        self.reply_channel = sender.map(|s| Box::new(s) as Box<dyn Any + Send + Sync>);
+    }
+
+    pub(crate) fn take_reply_channel<R: 'static + Send + Sync>(&mut self) -> Option<Sender<R>> {
+        self.defer_reply().map(|reply| reply.sender)
+    }
+
     pub fn boxed_actor_ref(&self) -> BoxedActorRef {
         self.boxed_ref.clone()
     }
@@ -190,6 +353,11 @@ impl ActorContext {
         self.persistence = Some(persistence);
     }
 
+    #[cfg(feature = "persistence")]
+    pub(crate) fn checkpoint_state_mut(&mut self) -> &mut CheckpointState {
+        &mut self.checkpoint
+    }
+
     pub fn supervised_mut(&mut self) -> Option<&mut Supervised> {
         self.supervised.as_mut()
     }
@@ -236,6 +404,26 @@ impl ActorContext {
         supervised.spawn(id, actor, system, parent_ref).await
     }
 
+    /// Spawns a child actor whose lifecycle is tied to this actor, waiting for the child to be
+    /// started before returning the LocalActorRef.
+    ///
+    /// Once this actor begins stopping, every child is stopped, and awaited, before
+    /// [`Actor::stopped`] is called.
+    pub async fn spawn_child<A: Actor>(
+        &mut self,
+        actor: A,
+        id: impl IntoActorId,
+    ) -> Result<LocalActorRef<A>, ActorRefErr> {
+        self.spawn(id.into_actor_id(), actor).await
+    }
+
+    /// Stops every child actor, waiting for each of them to stop
+    pub async fn stop_children(&mut self) {
+        if let Some(supervised) = self.supervised.as_mut() {
+            supervised.stop_all().await;
+        }
+    }
+
     /// Spawns the supervised actor but doesn't wait for the actor to be completely started before
     /// completing, and returning the LocalActorRef.
     ///
@@ -261,6 +449,35 @@ impl ActorContext {
         supervised.spawn_deferred(id, actor, system, parent_ref)
     }
 
+    /// Spawns a supervised actor which is re-created using the provided factory whenever it fails,
+    /// as defined by the provided [`RestartPolicy`], waiting for the first instance to be started
+    /// before returning the LocalActorRef.
+    pub async fn spawn_restartable<A: Actor, F>(
+        &mut self,
+        id: ActorId,
+        factory: F,
+        policy: RestartPolicy,
+    ) -> Result<LocalActorRef<A>, ActorRefErr>
+    where
+        F: 'static + Fn() -> A + Send + Sync,
+    {
+        let supervised = {
+            if self.supervised.is_none() {
+                self.supervised =
+                    Some(Supervised::new(self.id().clone(), self.full_path().clone()));
+            }
+
+            self.supervised.as_mut().unwrap()
+        };
+
+        let system = self.system.as_ref().unwrap().clone();
+        let parent_ref = self.boxed_ref.clone();
+
+        supervised
+            .spawn_restartable(id, factory, policy, system, parent_ref)
+            .await
+    }
+
     pub fn supervised_count(&self) -> usize {
         self.supervised.as_ref().map_or(0, |s| s.count())
     }
@@ -308,6 +525,20 @@ impl ActorContext {
     pub fn take_watchers(&mut self) -> Option<Watchers> {
         self.watchers.take()
     }
+
+    /// Watches `actor_ref`, this actor is sent an [`ActorTerminated`] message once the watched
+    /// actor has stopped, or immediately, if it has already stopped.
+    pub fn watch<A>(&self, actor_ref: &impl Watchable)
+    where
+        A: Actor + Handler<ActorTerminated>,
+    {
+        actor_ref.add_watcher(Receiver::<ActorTerminated>::from(self.actor_ref::<A>()));
+    }
+
+    /// Stops watching `actor_ref`, no [`ActorTerminated`] message will be sent once it stops
+    pub fn unwatch(&self, actor_ref: &impl Watchable) {
+        actor_ref.remove_watcher(self.id().clone());
+    }
 }
 
 static LOG_CONTEXT_FIELDS: &[NamedField<'static>] =
@@ -354,7 +585,7 @@ impl Drop for ActorContext {
             let system = self.system.clone();
             let status = self.status.clone();
 
-            tokio::spawn(async move {
+            rt::spawn(async move {
                 supervised.stop_all().await;
 
                 on_context_dropped(&boxed_ref, &parent_ref, &status, &system);
@@ -377,9 +608,11 @@ fn on_context_dropped(
     let actor_type = actor.actor_type();
     let system_terminated = system.as_ref().map(|s| s.is_terminated());
 
+    let mut failed = false;
     match status {
         ActorStatus::Starting => {
             debug!("actor failed to start, context dropped");
+            failed = true;
         }
 
         ActorStatus::Started => {
@@ -395,6 +628,7 @@ fn on_context_dropped(
                     actor_type = actor_type,
                     "actor stopped unexpectedly"
                 );
+                failed = true;
             }
         }
 
@@ -424,7 +658,11 @@ fn on_context_dropped(
     }
 
     if let Some(boxed_parent_ref) = parent_ref {
-        let _ = boxed_parent_ref.notify_child_terminated(actor_id.clone());
+        if failed {
+            let _ = boxed_parent_ref.notify_child_failed(actor_id.clone());
+        } else {
+            let _ = boxed_parent_ref.notify_child_terminated(actor_id.clone());
+        }
     }
 }
 
@@ -440,7 +678,7 @@ pub fn attach_stream<S, T, R, E, A, M>(
     M: Message,
     S: Unpin,
 {
-    tokio::spawn(async move {
+    rt::spawn(async move {
         let mut reader = stream;
         while let Some(Ok(msg)) = reader.next().await {
             if let Some(message) = message_converter(msg) {
@@ -0,0 +1,75 @@
+//! Dead letters
+//!
+//! Messages that were accepted into an actor's mailbox but were never processed, for example
+//! when an actor is stopped immediately while messages are still queued, are published as
+//! [`DeadLetter`]s via the [`ActorSystem`]'s [`DeadLetters`] channel. Messages discarded because
+//! an actor's bounded mailbox was full are published in the same way, as are requests to remote
+//! actors which were abandoned because the node they were sent to was removed from the cluster.
+//!
+//! [`ActorSystem`]: crate::actor::system::ActorSystem
+
+use crate::actor::metrics::ActorMetrics;
+use crate::actor::ActorId;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+const DEAD_LETTER_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    pub message_type: &'static str,
+    pub reason: DeadLetterReason,
+
+    /// The serialised message, only available if the message supports serialisation
+    /// (see [`Message::as_bytes`][crate::actor::message::Message::as_bytes])
+    pub payload: Option<Arc<Vec<u8>>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DeadLetterReason {
+    /// The actor was stopped before the message could be processed
+    ActorStopped,
+
+    /// The actor's bounded mailbox was full, and the message was discarded according to the
+    /// mailbox's [`OverflowPolicy`][crate::actor::mailbox::OverflowPolicy]
+    MailboxFull,
+
+    /// The message was sent to an actor on a remote node, and the node was removed from the
+    /// cluster before the message's result was received
+    NodeRemoved,
+}
+
+#[derive(Clone)]
+pub struct DeadLetters {
+    sender: broadcast::Sender<DeadLetter>,
+}
+
+impl Default for DeadLetters {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(DEAD_LETTER_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl DeadLetters {
+    /// Subscribes to all dead letters published after the point of subscription
+    pub fn subscribe(&self) -> broadcast::Receiver<DeadLetter> {
+        self.sender.subscribe()
+    }
+
+    pub fn publish(&self, dead_letter: DeadLetter) {
+        debug!(
+            actor_id = dead_letter.actor_id.as_ref(),
+            actor_type = dead_letter.actor_type,
+            message_type = dead_letter.message_type,
+            reason = format!("{:?}", &dead_letter.reason),
+            "dead letter"
+        );
+
+        ActorMetrics::incr_dead_letters(dead_letter.actor_type, dead_letter.message_type);
+
+        let _ = self.sender.send(dead_letter);
+    }
+}
@@ -3,6 +3,7 @@
 //!
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
+use crate::actor::rt::{self, Instant};
 use crate::actor::scheduler::ActorScheduler;
 use crate::actor::supervised::ChildRef;
 use crate::actor::{
@@ -12,7 +13,6 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::Sender;
-use tokio::time::Instant;
 
 #[derive(Default, Debug)]
 pub struct Describe {
@@ -116,7 +116,7 @@ impl<A: Actor> Handler<Describe> for A {
             );
 
             if !describable_actors.is_empty() {
-                tokio::spawn(async move {
+                rt::spawn(async move {
                     let actors = describe_all(describable_actors, next_depth, &message).await;
 
                     let description = {
@@ -175,7 +175,7 @@ pub async fn describe_all(
             let start = Instant::now();
             match actor.describe(describe) {
                 Ok(_) => {
-                    let description = tokio::time::timeout(timeout, rx).await;
+                    let description = rt::timeout(timeout, rx).await;
                     if let Ok(description) = description {
                         match description {
                             Ok(description) => DescribeResult::Ok({
@@ -232,7 +232,7 @@ impl Handler<DescribeAll> for ActorScheduler {
         };
 
         trace!("describing actors (count={})", actors.len());
-        tokio::spawn(async move {
+        rt::spawn(async move {
             let describe = Describe {
                 options: message.options,
                 sender: None,
@@ -0,0 +1,112 @@
+//! System hooks and actor decorators
+//!
+//! A [`SystemHook`] is registered with the [`ActorSystem`] (via [`ActorSystemBuilder::add_hook`])
+//! and is invoked every time an actor is spawned into, or stopped within, the system.
+//!
+//! When an actor is spawned, each hook may return an [`ActorDecorator`], which wraps the actor's
+//! message processing, allowing cross-cutting concerns such as metrics, tracing and auditing to be
+//! implemented once, rather than within every actor implementation.
+//!
+//! [`ActorSystem`]: crate::actor::system::ActorSystem
+//! [`ActorSystemBuilder::add_hook`]: crate::actor::system::builder::ActorSystemBuilder::add_hook
+
+use crate::actor::context::ActorContext;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Hook invoked by the [`ActorSystem`][crate::actor::system::ActorSystem] throughout the lifecycle
+/// of every actor spawned into the system.
+pub trait SystemHook: 'static + Send + Sync {
+    /// Called when an actor has been spawned, before [`Actor::started`][crate::actor::Actor::started]
+    /// is invoked. Returning an [`ActorDecorator`] will decorate the message processing of the
+    /// spawned actor.
+    fn on_actor_spawn(&self, _ctx: &ActorContext) -> Option<Box<dyn ActorDecorator>> {
+        None
+    }
+
+    /// Called once an actor has stopped, after [`Actor::stopped`][crate::actor::Actor::stopped]
+    /// has been invoked.
+    fn on_actor_stop(&self, _ctx: &ActorContext) {}
+}
+
+/// Decorates the message processing of a single actor instance.
+pub trait ActorDecorator: 'static + Send + Sync {
+    /// Called before a message is handled by the decorated actor
+    fn before_handle(&mut self, _message_type: &'static str, _ctx: &ActorContext) {}
+
+    /// Called once a message has been handled by the decorated actor
+    fn after_handle(
+        &mut self,
+        _message_type: &'static str,
+        _processing_time: Duration,
+        _ctx: &ActorContext,
+    ) {
+    }
+
+    /// Called once the decorated actor has stopped
+    fn on_stop(&mut self, _ctx: &ActorContext) {}
+}
+
+/// Collection of [`SystemHook`]s registered with an [`ActorSystem`][crate::actor::system::ActorSystem].
+#[derive(Clone, Default)]
+pub struct SystemHooks {
+    hooks: Vec<Arc<dyn SystemHook>>,
+}
+
+impl SystemHooks {
+    pub fn add_hook(&mut self, hook: impl SystemHook) {
+        self.hooks.push(Arc::new(hook));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    pub(crate) fn on_actor_spawn(&self, ctx: &ActorContext) -> ActorDecorators {
+        ActorDecorators(
+            self.hooks
+                .iter()
+                .filter_map(|hook| hook.on_actor_spawn(ctx))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn on_actor_stop(&self, ctx: &ActorContext) {
+        for hook in &self.hooks {
+            hook.on_actor_stop(ctx);
+        }
+    }
+}
+
+/// The decorators applied to a single actor instance.
+#[derive(Default)]
+pub(crate) struct ActorDecorators(Vec<Box<dyn ActorDecorator>>);
+
+impl ActorDecorators {
+    pub fn before_handle(&mut self, message_type: &'static str, ctx: &ActorContext) {
+        for decorator in self.0.iter_mut() {
+            decorator.before_handle(message_type, ctx);
+        }
+    }
+
+    pub fn after_handle(
+        &mut self,
+        message_type: &'static str,
+        processing_time: Duration,
+        ctx: &ActorContext,
+    ) {
+        for decorator in self.0.iter_mut().rev() {
+            decorator.after_handle(message_type, processing_time, ctx);
+        }
+    }
+
+    pub fn on_stop(&mut self, ctx: &ActorContext) {
+        for decorator in self.0.iter_mut().rev() {
+            decorator.on_stop(ctx);
+        }
+    }
+}
@@ -2,13 +2,21 @@
 
 use crate::actor::context::ActorStatus::{Started, Starting, Stopped, Stopping};
 use crate::actor::context::{ActorContext, ActorStatus};
+use crate::actor::dead_letter::{DeadLetter, DeadLetterReason};
+use crate::actor::hooks::ActorDecorators;
+use crate::actor::mailbox::MailboxReceiver;
 use crate::actor::message::{Handler, Message, MessageHandler};
 use crate::actor::metrics::ActorMetrics;
 use crate::actor::scheduler::{ActorType, DeregisterActor};
+use crate::actor::supervised::{Incarnation, RestartReason};
 use crate::actor::system::ActorSystem;
 use crate::actor::{Actor, ActorId, BoxedActorRef, CoreActorRef, LocalActorRef};
 
-use tokio::sync::mpsc::UnboundedReceiver;
+use crate::actor::rt::Instant;
+use futures::FutureExt;
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
 use tracing::Instrument;
 use valuable::Valuable;
 
@@ -17,8 +25,16 @@ use tokio::sync::oneshot::Sender;
 
 pub struct Status;
 
+/// Stops the actor immediately after the message is processed, any messages that remain in the
+/// mailbox are discarded and published as [`DeadLetter`]s.
+///
+/// [`DeadLetter`]: crate::actor::dead_letter::DeadLetter
 pub struct Stop(pub Option<Sender<()>>);
 
+/// Stops the actor once every message that was enqueued before the stop began has been processed.
+/// Once the stop has begun, the actor's mailbox is closed and no new messages will be accepted.
+pub struct GracefulStop(pub Option<Sender<()>>);
+
 impl Message for Status {
     type Result = ActorStatus;
 }
@@ -27,6 +43,10 @@ impl Message for Stop {
     type Result = ();
 }
 
+impl Message for GracefulStop {
+    type Result = ();
+}
+
 #[async_trait]
 impl<A> Handler<Status> for A
 where
@@ -44,30 +64,74 @@ impl<A: Actor> Handler<Stop> for A {
     }
 }
 
+#[async_trait]
+impl<A: Actor> Handler<GracefulStop> for A {
+    async fn handle(&mut self, stop: GracefulStop, ctx: &mut ActorContext) {
+        ctx.stop_gracefully(stop.0);
+    }
+}
+
 pub struct ActorLoop {}
 
 impl ActorLoop {
+    #[allow(clippy::too_many_arguments)]
     pub async fn run<A: Actor>(
         mut actor: A,
         actor_type: ActorType,
-        mut receiver: UnboundedReceiver<MessageHandler<A>>,
+        mut receiver: MailboxReceiver<A>,
         mut on_start: Option<Sender<()>>,
         actor_ref: LocalActorRef<A>,
         parent_ref: Option<BoxedActorRef>,
         mut system: Option<ActorSystem>,
+        incarnation: Option<Incarnation>,
     ) {
         let actor_id = actor_ref.actor_id().clone();
+        let _stopped = actor_ref.stopped_token().clone().drop_guard();
+
         let mut ctx = actor
             .new_context(system.clone(), Starting, actor_ref.clone().into())
             .with_parent(parent_ref);
 
+        let mut decorators = system
+            .as_ref()
+            .map_or_else(ActorDecorators::default, |system| {
+                system.hooks().on_actor_spawn(&ctx)
+            });
+
         trace!(actor = ctx.full_path().as_ref(), "actor starting");
 
-        actor.started(&mut ctx).await;
+        #[cfg(feature = "persistence")]
+        crate::persistent::checkpoint::restore_checkpoint(&mut actor, &mut ctx).await;
+
+        if incarnation.is_some() {
+            let started = AssertUnwindSafe(actor.started(&mut ctx))
+                .catch_unwind()
+                .await;
+            if let Err(panic) = started {
+                let reason = RestartReason::StartFailed {
+                    panic_message: panic_message(&panic),
+                };
+
+                restart(&mut actor, &mut ctx, reason, panic).await;
+            }
+        } else {
+            actor.started(&mut ctx).await;
+        }
+
         ActorMetrics::incr_actor_created(A::type_name());
 
         if ctx.get_status() == &Stopping {
-            return actor_stopped(&mut actor, actor_type, &mut system, &actor_id, &mut ctx).await;
+            discard_mailbox(&mut receiver, &system, &actor_ref);
+
+            return actor_stopped(
+                &mut actor,
+                actor_type,
+                &mut system,
+                &actor_id,
+                &mut ctx,
+                &mut decorators,
+            )
+            .await;
         }
 
         ctx.set_status(Started);
@@ -78,49 +142,30 @@ impl ActorLoop {
             let _ = on_start.send(());
         }
 
-        let log = ctx.log();
-        while let Some(mut msg) = receiver.recv().await {
-            {
-                #[cfg(feature = "actor-tracing-info")]
-                let span = tracing::info_span!(
-                    "actor.recv",
-                    ctx = log.as_value(),
-                    message_type = msg.name(),
-                );
-
-                #[cfg(feature = "actor-tracing-debug")]
-                let span = tracing::debug_span!(
-                    "actor.recv",
-                    ctx = log.as_value(),
-                    message_type = msg.name(),
-                );
-
-                #[cfg(feature = "actor-tracing-trace")]
-                let span = tracing::trace_span!(
-                    "actor.recv",
-                    ctx = log.as_value(),
-                    message_type = msg.name(),
-                );
-
-                trace!(
-                    actor = ctx.full_path().as_ref(),
-                    msg_type = msg.name(),
-                    "actor message received"
-                );
-
-                let handle_fut = msg.handle(&mut actor, &mut ctx);
-
-                #[cfg(feature = "actor-tracing")]
-                let handle_fut = handle_fut.instrument(span);
-
-                handle_fut.await;
-
-                trace!(
-                    actor = ctx.full_path().as_ref(),
-                    msg_type = msg.name(),
-                    "actor message processed"
-                );
-            }
+        if incarnation == Some(Incarnation::Restarted) {
+            actor.post_restart(&mut ctx).await;
+        }
+
+        handle_deferred(
+            &actor_ref,
+            &mut actor,
+            &mut ctx,
+            &mut decorators,
+            incarnation,
+        )
+        .await;
+
+        while let Some(msg) = receiver.recv().await {
+            actor_ref.mailbox().received(msg.name());
+            receive_message(msg, &mut actor, &mut ctx, &mut decorators, incarnation).await;
+            handle_deferred(
+                &actor_ref,
+                &mut actor,
+                &mut ctx,
+                &mut decorators,
+                incarnation,
+            )
+            .await;
 
             if ctx.get_status() == &Stopping {
                 break;
@@ -131,7 +176,207 @@ impl ActorLoop {
 
         ctx.set_status(Stopping);
 
-        actor_stopped(&mut actor, actor_type, &mut system, &actor_id, &mut ctx).await
+        if ctx.is_stopping_gracefully() {
+            trace!(actor = ctx.full_path().as_ref(), "draining mailbox");
+
+            receiver.close();
+            while let Some(msg) = receiver.recv().await {
+                actor_ref.mailbox().received(msg.name());
+                handle_message(msg, &mut actor, &mut ctx, &mut decorators).await;
+                handle_deferred(&actor_ref, &mut actor, &mut ctx, &mut decorators, None).await;
+            }
+        } else {
+            discard_mailbox(&mut receiver, &system, &actor_ref);
+        }
+
+        actor_stopped(
+            &mut actor,
+            actor_type,
+            &mut system,
+            &actor_id,
+            &mut ctx,
+            &mut decorators,
+        )
+        .await
+    }
+}
+
+/// Handles a message received by the actor, if the actor is restartable, a panic is caught so
+/// [`Actor::pre_restart`] can be called before the failure is propagated
+async fn receive_message<A: Actor>(
+    msg: MessageHandler<A>,
+    actor: &mut A,
+    ctx: &mut ActorContext,
+    decorators: &mut ActorDecorators,
+    incarnation: Option<Incarnation>,
+) {
+    if incarnation.is_none() {
+        return handle_message(msg, actor, ctx, decorators).await;
+    }
+
+    let message_type = msg.name();
+    let handled = AssertUnwindSafe(handle_message(msg, actor, ctx, decorators))
+        .catch_unwind()
+        .await;
+
+    if let Err(panic) = handled {
+        let reason = RestartReason::Panicked {
+            message_type,
+            panic_message: panic_message(&panic),
+        };
+
+        restart(actor, ctx, reason, panic).await;
+    }
+}
+
+/// Handles every deferred message whose [`SequenceToken`] has been reached by the actor's
+/// virtual time, before the next message is received from the mailbox
+///
+/// [`SequenceToken`]: crate::actor::mailbox::SequenceToken
+async fn handle_deferred<A: Actor>(
+    actor_ref: &LocalActorRef<A>,
+    actor: &mut A,
+    ctx: &mut ActorContext,
+    decorators: &mut ActorDecorators,
+    incarnation: Option<Incarnation>,
+) {
+    while let Some(msg) = ctx.take_due_message::<A>(actor_ref.mailbox().virtual_time()) {
+        receive_message(msg, actor, ctx, decorators, incarnation).await;
+
+        if ctx.get_status() == &Stopping && !ctx.is_stopping_gracefully() {
+            break;
+        }
+    }
+}
+
+async fn handle_message<A: Actor>(
+    mut msg: MessageHandler<A>,
+    actor: &mut A,
+    ctx: &mut ActorContext,
+    decorators: &mut ActorDecorators,
+) {
+    #[cfg(feature = "actor-tracing")]
+    let sampled = ctx.try_system().is_none_or(|system| {
+        system
+            .trace_sampling()
+            .is_sampled(ctx.id().as_ref(), msg.name())
+    });
+
+    // only the most verbose enabled level's span is created
+    #[cfg(all(
+        feature = "actor-tracing-info",
+        not(feature = "actor-tracing-debug"),
+        not(feature = "actor-tracing-trace")
+    ))]
+    let span = if sampled {
+        tracing::info_span!(
+            "actor.recv",
+            ctx = ctx.log().as_value(),
+            message_type = msg.name(),
+        )
+    } else {
+        tracing::Span::none()
+    };
+
+    #[cfg(all(feature = "actor-tracing-debug", not(feature = "actor-tracing-trace")))]
+    let span = if sampled {
+        tracing::debug_span!(
+            "actor.recv",
+            ctx = ctx.log().as_value(),
+            message_type = msg.name(),
+        )
+    } else {
+        tracing::Span::none()
+    };
+
+    #[cfg(feature = "actor-tracing-trace")]
+    let span = if sampled {
+        tracing::trace_span!(
+            "actor.recv",
+            ctx = ctx.log().as_value(),
+            message_type = msg.name(),
+        )
+    } else {
+        tracing::Span::none()
+    };
+
+    trace!(
+        actor = ctx.full_path().as_ref(),
+        msg_type = msg.name(),
+        "actor message received"
+    );
+
+    let message_type = msg.name();
+    decorators.before_handle(message_type, ctx);
+
+    let start = Instant::now();
+    let handle_fut = msg.handle(actor, ctx);
+
+    #[cfg(feature = "actor-tracing")]
+    let handle_fut = handle_fut.instrument(span);
+
+    handle_fut.await;
+
+    decorators.after_handle(message_type, start.elapsed(), ctx);
+
+    #[cfg(feature = "persistence")]
+    crate::persistent::checkpoint::on_message_handled::<A>(ctx).await;
+
+    trace!(
+        actor = ctx.full_path().as_ref(),
+        msg_type = message_type,
+        "actor message processed"
+    );
+}
+
+/// Gives a failed restartable actor the chance to clean up via [`Actor::pre_restart`], before
+/// resuming the panic so the failure is reported to the supervisor
+async fn restart<A: Actor>(
+    actor: &mut A,
+    ctx: &mut ActorContext,
+    reason: RestartReason,
+    panic: Box<dyn Any + Send>,
+) -> ! {
+    warn!(
+        actor = ctx.full_path().as_ref(),
+        "actor failed, reason={:?}", &reason
+    );
+
+    actor.pre_restart(&reason, ctx).await;
+    std::panic::resume_unwind(panic)
+}
+
+pub(crate) fn panic_message(panic: &Box<dyn Any + Send>) -> Option<String> {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        Some(message.to_string())
+    } else {
+        panic.downcast_ref::<String>().cloned()
+    }
+}
+
+fn discard_mailbox<A: Actor>(
+    receiver: &mut MailboxReceiver<A>,
+    system: &Option<ActorSystem>,
+    actor_ref: &LocalActorRef<A>,
+) {
+    receiver.close();
+
+    while let Some(msg) = receiver.try_recv() {
+        actor_ref.mailbox().dequeued(msg.name());
+
+        let dead_letter = DeadLetter {
+            actor_id: actor_ref.actor_id().clone(),
+            actor_type: A::type_name(),
+            message_type: msg.name(),
+            reason: DeadLetterReason::ActorStopped,
+            payload: msg.as_bytes().map(Arc::new),
+        };
+
+        if let Some(system) = system {
+            system.dead_letters().publish(dead_letter);
+        } else {
+            ActorMetrics::incr_dead_letters(dead_letter.actor_type, dead_letter.message_type);
+        }
     }
 }
 
@@ -141,11 +386,26 @@ async fn actor_stopped<A: Actor>(
     system: &mut Option<ActorSystem>,
     actor_id: &ActorId,
     mut ctx: &mut ActorContext,
+    decorators: &mut ActorDecorators,
 ) {
+    // children are stopped before the parent, so the parent can rely on them being
+    // stopped once `Actor::stopped` is called
+    ctx.stop_children().await;
+
     actor.stopped(&mut ctx).await;
 
+    #[cfg(feature = "persistence")]
+    crate::persistent::checkpoint::on_stopped::<A>(ctx).await;
+
     ctx.set_status(Stopped);
 
+    decorators.on_stop(ctx);
+    if let Some(system) = system.as_ref() {
+        system.hooks().on_actor_stop(ctx);
+    }
+
+    ctx.actor_ref::<A>().stopped_token().cancel();
+
     if actor_type.is_tracked() {
         if let Some(system) = system.take() {
             if !system.is_terminated() {
@@ -0,0 +1,452 @@
+//! Actor mailboxes and mailbox diagnostics
+//!
+//! By default, an actor's mailbox is unbounded. A bounded mailbox can be configured when the actor
+//! is created (see [`ActorSystem::new_actor_with_opts`]), with an [`OverflowPolicy`] deciding what
+//! happens to messages sent while the mailbox is full.
+//!
+//! Every local actor keeps a count of the messages queued in its mailbox, by message type,
+//! allowing the mailbox to be inspected without consuming any of the messages, answering
+//! questions such as "what is this actor backlogged on?".
+//!
+//! The mailbox also tracks the actor's virtual time, the number of messages the actor has
+//! received so far, which is used to sequence deferred messages with
+//! [`ActorContext::notify_after_queued`] and [`ActorContext::notify_at`].
+//!
+//! [`ActorContext::notify_after_queued`]: crate::actor::context::ActorContext::notify_after_queued
+//! [`ActorContext::notify_at`]: crate::actor::context::ActorContext::notify_at
+//! [`ActorSystem::new_actor_with_opts`]: crate::actor::system::ActorSystem::new_actor_with_opts
+
+use crate::actor::dead_letter::DeadLetters;
+use crate::actor::message::MessageHandler;
+use crate::actor::Actor;
+use crate::actor::ActorRefErr;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Notify;
+
+/// What happens to a message sent to an actor whose bounded mailbox is full
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// The message being sent is discarded and published as a dead letter
+    DropNewest,
+
+    /// The oldest queued message is discarded and published as a dead letter, making room for
+    /// the message being sent
+    DropOldest,
+
+    /// The message is rejected, the sender receives [`ActorRefErr::MailboxFull`]
+    ///
+    /// [`ActorRefErr::MailboxFull`]: crate::actor::ActorRefErr::MailboxFull
+    #[default]
+    FailSender,
+
+    /// `send` waits until there is room in the mailbox. Since `notify` can't wait, messages sent
+    /// via `notify` are rejected with [`ActorRefErr::MailboxFull`]
+    ///
+    /// [`ActorRefErr::MailboxFull`]: crate::actor::ActorRefErr::MailboxFull
+    Backpressure,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MailboxConfig {
+    /// The maximum number of queued messages, or `None` if the mailbox is unbounded
+    pub capacity: Option<usize>,
+    pub overflow: OverflowPolicy,
+}
+
+impl MailboxConfig {
+    pub fn unbounded() -> Self {
+        Self::default()
+    }
+
+    pub fn bounded(capacity: usize, overflow: OverflowPolicy) -> Self {
+        Self {
+            capacity: Some(capacity.max(1)),
+            overflow,
+        }
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.capacity.is_some()
+    }
+}
+
+/// Creates the sending and receiving halves of an actor's mailbox
+pub(crate) fn mailbox<A: Actor>(
+    config: MailboxConfig,
+    dead_letters: Option<DeadLetters>,
+) -> (MailboxSender<A>, MailboxReceiver<A>) {
+    match config.capacity {
+        None => {
+            let (tx, rx) = mpsc::unbounded_channel();
+            (MailboxSender::Unbounded(tx), MailboxReceiver::Unbounded(rx))
+        }
+        Some(capacity) => {
+            let queue = Arc::new(BoundedQueue {
+                queue: Mutex::new(VecDeque::with_capacity(capacity)),
+                capacity,
+                reserved: AtomicUsize::new(0),
+                overflow: config.overflow,
+                closed: AtomicBool::new(false),
+                not_empty: Notify::new(),
+                not_full: Notify::new(),
+                dead_letters,
+            });
+
+            (
+                MailboxSender::Bounded(queue.clone()),
+                MailboxReceiver::Bounded(queue),
+            )
+        }
+    }
+}
+
+/// The sending half of an actor's mailbox
+pub enum MailboxSender<A: Actor> {
+    Unbounded(UnboundedSender<MessageHandler<A>>),
+    Bounded(Arc<BoundedQueue<A>>),
+}
+
+/// The receiving half of an actor's mailbox, owned by the actor's loop
+pub enum MailboxReceiver<A: Actor> {
+    Unbounded(UnboundedReceiver<MessageHandler<A>>),
+    Bounded(Arc<BoundedQueue<A>>),
+}
+
+pub struct BoundedQueue<A: Actor> {
+    queue: Mutex<VecDeque<MessageHandler<A>>>,
+    capacity: usize,
+
+    /// Slots reserved for messages that are yet to be enqueued, only modified while the queue
+    /// is locked
+    reserved: AtomicUsize,
+    overflow: OverflowPolicy,
+    closed: AtomicBool,
+    not_empty: Notify,
+    not_full: Notify,
+    dead_letters: Option<DeadLetters>,
+}
+
+/// The outcome of enqueueing a message
+pub(crate) enum Enqueued<A: Actor> {
+    /// The message was queued
+    Queued,
+
+    /// The message was queued, but a message was discarded to make room, either the message
+    /// itself or the oldest queued message, depending on the [`OverflowPolicy`]
+    Dropped(MessageHandler<A>),
+
+    /// The mailbox is full and the message was rejected
+    Full(MessageHandler<A>),
+
+    /// The mailbox is closed, the actor is stopped or stopping
+    Closed,
+}
+
+impl<A: Actor> MailboxSender<A> {
+    /// Enqueues the message without waiting, a mailbox using [`OverflowPolicy::Backpressure`]
+    /// rejects the message if the mailbox is full
+    pub(crate) fn try_send(&self, message: MessageHandler<A>) -> Enqueued<A> {
+        match self {
+            MailboxSender::Unbounded(sender) => match sender.send(message) {
+                Ok(_) => Enqueued::Queued,
+                Err(_) => Enqueued::Closed,
+            },
+            MailboxSender::Bounded(queue) => queue.try_push(message),
+        }
+    }
+
+    /// Enqueues the message, if the mailbox uses [`OverflowPolicy::Backpressure`], this waits
+    /// until there is room in the mailbox
+    pub(crate) async fn send(&self, mut message: MessageHandler<A>) -> Enqueued<A> {
+        let queue = match self {
+            MailboxSender::Bounded(queue) if queue.overflow == OverflowPolicy::Backpressure => {
+                queue
+            }
+            _ => return self.try_send(message),
+        };
+
+        loop {
+            // registered before trying to push, so a wakeup between the attempt and
+            // waiting isn't missed
+            let mut not_full = pin!(queue.not_full.notified());
+            not_full.as_mut().enable();
+
+            match queue.try_push(message) {
+                Enqueued::Full(m) => message = m,
+                res => return res,
+            }
+
+            not_full.await;
+        }
+    }
+
+    /// Reserves room for a message, which is enqueued via [`MailboxSender::send_reserved`], or
+    /// released via [`MailboxSender::cancel_reservation`]
+    pub(crate) fn reserve(&self) -> Result<(), ActorRefErr> {
+        match self {
+            MailboxSender::Unbounded(sender) if sender.is_closed() => Err(ActorRefErr::InvalidRef),
+            MailboxSender::Unbounded(_) => Ok(()),
+            MailboxSender::Bounded(queue) => queue.reserve(),
+        }
+    }
+
+    pub(crate) fn cancel_reservation(&self) {
+        if let MailboxSender::Bounded(queue) = self {
+            queue.cancel_reservation();
+        }
+    }
+
+    /// Enqueues a message into a previously reserved slot
+    pub(crate) fn send_reserved(&self, message: MessageHandler<A>) -> Enqueued<A> {
+        match self {
+            MailboxSender::Unbounded(_) => self.try_send(message),
+            MailboxSender::Bounded(queue) => queue.push_reserved(message),
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        match self {
+            MailboxSender::Unbounded(sender) => sender.is_closed(),
+            MailboxSender::Bounded(queue) => queue.closed.load(Ordering::SeqCst),
+        }
+    }
+
+    /// The maximum number of messages that can be queued, `None` if the mailbox is unbounded
+    pub fn capacity(&self) -> Option<usize> {
+        match self {
+            MailboxSender::Unbounded(_) => None,
+            MailboxSender::Bounded(queue) => Some(queue.capacity),
+        }
+    }
+
+    pub(crate) fn dead_letters(&self) -> Option<&DeadLetters> {
+        match self {
+            MailboxSender::Unbounded(_) => None,
+            MailboxSender::Bounded(queue) => queue.dead_letters.as_ref(),
+        }
+    }
+}
+
+impl<A: Actor> BoundedQueue<A> {
+    fn try_push(&self, message: MessageHandler<A>) -> Enqueued<A> {
+        let mut queue = self.queue.lock().unwrap();
+        if self.closed.load(Ordering::SeqCst) {
+            return Enqueued::Closed;
+        }
+
+        if queue.len() + self.reserved.load(Ordering::SeqCst) < self.capacity {
+            queue.push_back(message);
+            drop(queue);
+
+            self.not_empty.notify_one();
+            return Enqueued::Queued;
+        }
+
+        match self.overflow {
+            OverflowPolicy::DropNewest => Enqueued::Dropped(message),
+            OverflowPolicy::DropOldest => match queue.pop_front() {
+                Some(oldest) => {
+                    queue.push_back(message);
+                    Enqueued::Dropped(oldest)
+                }
+
+                // every slot is reserved, so there's no queued message to make room with
+                None => Enqueued::Dropped(message),
+            },
+            OverflowPolicy::FailSender | OverflowPolicy::Backpressure => Enqueued::Full(message),
+        }
+    }
+
+    fn reserve(&self) -> Result<(), ActorRefErr> {
+        let queue = self.queue.lock().unwrap();
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(ActorRefErr::InvalidRef);
+        }
+
+        if queue.len() + self.reserved.load(Ordering::SeqCst) >= self.capacity {
+            return Err(ActorRefErr::MailboxFull);
+        }
+
+        self.reserved.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn cancel_reservation(&self) {
+        let queue = self.queue.lock().unwrap();
+        self.reserved.fetch_sub(1, Ordering::SeqCst);
+        drop(queue);
+
+        self.not_full.notify_one();
+    }
+
+    fn push_reserved(&self, message: MessageHandler<A>) -> Enqueued<A> {
+        let mut queue = self.queue.lock().unwrap();
+        self.reserved.fetch_sub(1, Ordering::SeqCst);
+        if self.closed.load(Ordering::SeqCst) {
+            return Enqueued::Closed;
+        }
+
+        queue.push_back(message);
+        drop(queue);
+
+        self.not_empty.notify_one();
+        Enqueued::Queued
+    }
+
+    fn pop(&self) -> Option<MessageHandler<A>> {
+        let message = self.queue.lock().unwrap().pop_front();
+        if message.is_some() {
+            self.not_full.notify_one();
+        }
+
+        message
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.not_full.notify_waiters();
+    }
+}
+
+impl<A: Actor> MailboxReceiver<A> {
+    pub async fn recv(&mut self) -> Option<MessageHandler<A>> {
+        match self {
+            MailboxReceiver::Unbounded(receiver) => receiver.recv().await,
+            MailboxReceiver::Bounded(queue) => loop {
+                if let Some(message) = queue.pop() {
+                    return Some(message);
+                }
+
+                if queue.closed.load(Ordering::SeqCst) {
+                    return None;
+                }
+
+                // there's only a single receiver, so a notification sent while the receiver
+                // isn't waiting is stored and consumed by the next wait
+                queue.not_empty.notified().await;
+            },
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Option<MessageHandler<A>> {
+        match self {
+            MailboxReceiver::Unbounded(receiver) => receiver.try_recv().ok(),
+            MailboxReceiver::Bounded(queue) => queue.pop(),
+        }
+    }
+
+    /// Closes the mailbox, no more messages will be accepted, although messages that are already
+    /// queued can still be received
+    pub fn close(&mut self) {
+        match self {
+            MailboxReceiver::Unbounded(receiver) => receiver.close(),
+            MailboxReceiver::Bounded(queue) => queue.close(),
+        }
+    }
+}
+
+impl<A: Actor> Drop for MailboxReceiver<A> {
+    fn drop(&mut self) {
+        if let MailboxReceiver::Bounded(queue) = self {
+            queue.close();
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MailboxCounter {
+    queued: Mutex<HashMap<&'static str, usize>>,
+    received: AtomicU64,
+}
+
+impl MailboxCounter {
+    pub fn enqueued(&self, message_type: &'static str) {
+        *self.queued.lock().unwrap().entry(message_type).or_default() += 1;
+    }
+
+    pub fn dequeued(&self, message_type: &'static str) {
+        let mut queued = self.queued.lock().unwrap();
+        if let Some(count) = queued.get_mut(message_type) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                queued.remove(message_type);
+            }
+        }
+    }
+
+    /// Called once the message has been received by the actor, advancing the actor's virtual time
+    pub fn received(&self, message_type: &'static str) {
+        self.dequeued(message_type);
+        self.received.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn virtual_time(&self) -> u64 {
+        self.received.load(Ordering::SeqCst)
+    }
+
+    pub fn sequence_token(&self) -> SequenceToken {
+        // the queued count is read before the virtual time, if a message is received in between,
+        // the token is later than it needs to be, but never earlier
+        let queued: usize = self.queued.lock().unwrap().values().sum();
+        SequenceToken(self.virtual_time() + queued as u64)
+    }
+
+    pub fn snapshot(&self) -> MailboxSnapshot {
+        let queued = self
+            .queued
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(message_type, count)| (message_type.to_string(), *count))
+            .collect();
+
+        MailboxSnapshot { queued }
+    }
+}
+
+/// A point in an actor's virtual time, which is the number of messages the actor has received.
+///
+/// A token taken via [`LocalActorRef::sequence_token`] is reached once every message that was
+/// queued when the token was taken has been received.
+///
+/// [`LocalActorRef::sequence_token`]: crate::actor::LocalActorRef::sequence_token
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SequenceToken(pub(crate) u64);
+
+impl SequenceToken {
+    pub fn virtual_time(&self) -> u64 {
+        self.0
+    }
+
+    /// Whether the token has been reached at the provided virtual time
+    pub fn is_reached(&self, virtual_time: u64) -> bool {
+        self.0 <= virtual_time
+    }
+}
+
+/// Point-in-time view of the messages queued in an actor's mailbox
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct MailboxSnapshot {
+    /// Number of queued messages, by message type
+    pub queued: BTreeMap<String, usize>,
+}
+
+impl MailboxSnapshot {
+    /// Total number of queued messages
+    pub fn len(&self) -> usize {
+        self.queued.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+
+    /// Number of queued messages of the provided type
+    pub fn count(&self, message_type: &str) -> usize {
+        self.queued.get(message_type).copied().unwrap_or(0)
+    }
+}
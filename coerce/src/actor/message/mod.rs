@@ -36,6 +36,49 @@
 //! If the message has a non-default (i.e not `()`) - [`Message::read_remote_result`]
 //! and [`Message::write_remote_result`] must also be implemented.
 //!
+//! ## Delivery Semantics
+//! How a message is delivered to actors on other nodes is declared per message type, via
+//! [`Message::delivery_semantics`], see [`DeliverySemantics`]. Messages deriving `JsonMessage`
+//! declare their semantics with the `#[delivery(...)]` attribute, for example
+//! `#[delivery(fire_and_forget)]`, messages are delivered with [`DeliverySemantics::Ordered`]
+//! by default.
+//!
+//! ## Borrowed Messages
+//! Messages are owned, since they're moved into the actor's mailbox. Callers that build messages
+//! from borrowed data (for example, from the fields of an incoming request) can send a borrowed
+//! view instead, by implementing [`IntoMessage`] for the view, which converts it into the owned
+//! message when it's sent via [`LocalActorRef::send_into`] or [`LocalActorRef::notify_into`].
+//!
+//! ```rust
+//! use coerce::actor::message::{IntoMessage, Message};
+//!
+//! pub struct Rename {
+//!     name: String,
+//! }
+//!
+//! impl Message for Rename {
+//!     type Result = ();
+//! }
+//!
+//! pub struct RenameRef<'a> {
+//!     name: &'a str,
+//! }
+//!
+//! impl<'a> IntoMessage for RenameRef<'a> {
+//!     type Message = Rename;
+//!
+//!     fn into_message(self) -> Rename {
+//!         Rename {
+//!             name: self.name.to_string(),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ## Versioned Handlers
+//! An actor can handle the same message differently depending on the version the sender requests,
+//! allowing behaviour changes to be rolled out across a cluster gradually, see [`versioned`].
+//!
 //! [Coerce]: crate
 //! [`Message`]: Message
 //! [`Handler`]: Handler
@@ -45,19 +88,23 @@
 //! [`Message::from_bytes`]: Message::as_bytes
 //! [`Message::read_remote_result`]: Message::read_remote_result
 //! [`Message::write_remote_result`]: Message::write_remote_result
+//! [`LocalActorRef::send_into`]: crate::actor::LocalActorRef::send_into
+//! [`LocalActorRef::notify_into`]: crate::actor::LocalActorRef::notify_into
 //!
 use crate::actor::context::ActorContext;
 use crate::actor::Actor;
-use std::error::Error;
 
 use crate::actor::metrics::ActorMetrics;
-use std::fmt::{Debug, Display, Formatter};
 
+use crate::actor::rt::Instant;
 use std::marker::PhantomData;
-use std::time::Instant;
 use tokio::sync::oneshot;
 use tracing::{Instrument, Span};
 
+pub use coerce_core::message::{MessageUnwrapErr, MessageWrapErr};
+
+pub mod versioned;
+
 pub trait Message: 'static + Sync + Send + Sized {
     type Result: 'static + Sync + Send;
 
@@ -95,6 +142,14 @@ pub trait Message: 'static + Sync + Send + Sized {
         std::any::type_name::<Self>()
     }
 
+    /// How the message is delivered when it's sent to an actor on another node
+    fn delivery_semantics() -> DeliverySemantics
+    where
+        Self: Sized,
+    {
+        DeliverySemantics::Ordered
+    }
+
     fn type_name() -> &'static str
     where
         Self: Sized,
@@ -111,6 +166,26 @@ where
     async fn handle(&mut self, message: M, ctx: &mut ActorContext) -> M::Result;
 }
 
+/// Converts a value, typically a view that borrows its data, into an owned [`Message`]
+/// at the time it's sent, see [`LocalActorRef::send_into`].
+///
+/// Every [`Message`] converts into itself.
+///
+/// [`LocalActorRef::send_into`]: crate::actor::LocalActorRef::send_into
+pub trait IntoMessage {
+    type Message: Message;
+
+    fn into_message(self) -> Self::Message;
+}
+
+impl<M: Message> IntoMessage for M {
+    type Message = M;
+
+    fn into_message(self) -> M {
+        self
+    }
+}
+
 pub(crate) struct ActorMessage<A: Actor, M: Message>
 where
     A: Handler<M>,
@@ -127,6 +202,11 @@ pub trait ActorMessageHandler<A: Actor>: Sync + Send {
     async fn handle(&mut self, actor: &mut A, ctx: &mut ActorContext);
 
     fn name(&self) -> &'static str;
+
+    /// Serialises the message, if it has not yet been handled and supports serialisation
+    fn as_bytes(&self) -> Option<Vec<u8>> {
+        None
+    }
 }
 
 #[async_trait]
@@ -141,6 +221,10 @@ where
     fn name(&self) -> &'static str {
         std::any::type_name::<M>()
     }
+
+    fn as_bytes(&self) -> Option<Vec<u8>> {
+        self.msg.as_ref().and_then(|msg| msg.as_bytes().ok())
+    }
 }
 
 pub type MessageHandler<A> = Box<dyn ActorMessageHandler<A> + Sync + Send>;
@@ -163,6 +247,9 @@ where
         let message_waited_for = self.created_at.elapsed();
         let start = Instant::now();
 
+        // the handler can reply before returning, or defer the reply, see `ActorContext::reply`
+        ctx.set_reply_channel(self.sender.take());
+
         let msg = self.msg.take();
         let result = actor
             .handle(msg.unwrap(), ctx)
@@ -178,13 +265,13 @@ where
             message_processing_took,
         );
 
-        match self.sender.take() {
+        match ctx.take_reply_channel::<M::Result>() {
             Some(sender) => match sender.send(result) {
                 Ok(_) => trace!("sent result successfully"),
                 Err(_e) => warn!("failed to send result"),
             },
             None => {
-                trace!("no result consumer, message handling complete");
+                trace!("no result consumer or reply already sent, message handling complete");
                 return;
             }
         }
@@ -201,11 +288,31 @@ pub enum EnvelopeType {
     Remote,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub enum MessageWrapErr {
-    Unknown,
-    NotTransmittable,
-    SerializationErr,
+/// How a message is delivered when it's sent to an actor on another node, which decides how the
+/// message is buffered whilst the node is unreachable, whether it's acknowledged, and the lane
+/// of the connection it's written via.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum DeliverySemantics {
+    /// Written in the order it was sent, relative to other messages sent to the same actor, and
+    /// buffered whilst the node is unreachable, subject to the remote client's write buffer limits
+    #[default]
+    Ordered,
+
+    /// Written if the node is connected, otherwise the message is dropped rather than buffered,
+    /// if the message was sent as a request, the sender receives [`ActorRefErr::ActorUnavailable`]
+    ///
+    /// [`ActorRefErr::ActorUnavailable`]: crate::actor::ActorRefErr::ActorUnavailable
+    FireAndForget,
+
+    /// Buffered whilst the node is unreachable, and never discarded to make room for other
+    /// messages, if the write buffer is full, the sender is failed rather than the message being
+    /// dropped. Notifications wait for the remote node to acknowledge the message was handled,
+    /// so the message's result must be transmittable.
+    Reliable,
+
+    /// Written via the connection's priority lane, ahead of any queued messages, so the message
+    /// isn't ordered relative to other messages sent to the same actor
+    Priority,
 }
 
 pub trait ToBytes {
@@ -219,6 +326,7 @@ where
     fn from_bytes(buf: Vec<u8>) -> Result<Self, MessageUnwrapErr>;
 }
 
+#[cfg(any(feature = "remote", feature = "persistence"))]
 impl<T: protobuf::Message> ToBytes for T {
     fn to_bytes(self) -> Result<Vec<u8>, MessageWrapErr> {
         self.write_to_bytes()
@@ -226,6 +334,7 @@ impl<T: protobuf::Message> ToBytes for T {
     }
 }
 
+#[cfg(any(feature = "remote", feature = "persistence"))]
 impl<T: protobuf::Message> FromBytes for T
 where
     Self: Sized,
@@ -235,37 +344,6 @@ where
     }
 }
 
-impl Display for MessageWrapErr {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match &self {
-            MessageWrapErr::NotTransmittable => write!(f, "Message serialisation not supported, messages must override Message::as_remote_envelop and Message::write_remote_result"),
-            MessageWrapErr::SerializationErr => write!(f, "Message failed to serialise"),
-            MessageWrapErr::Unknown => write!(f, "Message failed to serialise, unknown error"),
-        }
-    }
-}
-
-impl Error for MessageWrapErr {}
-
-#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
-pub enum MessageUnwrapErr {
-    Unknown,
-    NotTransmittable,
-    DeserializationErr,
-}
-
-impl Display for MessageUnwrapErr {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match &self {
-            MessageUnwrapErr::NotTransmittable => write!(f, "Message deserialisation not supported, messages must override Message::as_remote_envelope, Message::from_remote_envelope, Message::read_remote_result, and Message::write_remote_result"),
-            MessageUnwrapErr::DeserializationErr => write!(f, "Message failed to deserialise"),
-            MessageUnwrapErr::Unknown => write!(f, "Message failed to deserialise, unknown error"),
-        }
-    }
-}
-
-impl Error for MessageUnwrapErr {}
-
 impl<M> Envelope<M> {
     pub fn into_bytes(self) -> Vec<u8> {
         match self {
@@ -0,0 +1,105 @@
+//! Versioned handlers, for changing how an actor handles a message during a rolling migration
+//!
+//! An actor can provide several implementations of how it handles the same message, one per
+//! version, by implementing [`VersionedHandler`] for each version, alongside the message's
+//! [`Handler`], which handles messages that don't request a version (for example, messages sent
+//! by nodes that haven't been migrated yet).
+//!
+//! When a message is sent to an actor on another node, the sender stamps the message with the
+//! version configured for the message's handler, and the receiving node routes the message to the
+//! [`VersionedHandler`] registered for that version, falling back to the message's [`Handler`]
+//! if there isn't one. This allows the new behaviour to be rolled out to a cluster node by node,
+//! and switched on (or back off) for the whole cluster once every node can handle it.
+//!
+//! Messages sent to sharded entities are versioned in the same way, whether the entity is
+//! hosted locally or by another node.
+//!
+//! Versioned handlers are registered with `RemoteSystemConfigBuilder::with_versioned_handler`,
+//! under the identifier of the message's handler, and the version a node sends is configured
+//! with `RemoteSystemConfigBuilder::message_version`, or changed at runtime with
+//! `RemoteActorSystem::set_message_version`.
+//!
+//! Locally, a specific version of the handler can be invoked by sending a [`Versioned`] message.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! #[async_trait]
+//! impl Handler<Withdraw> for Account {
+//!     async fn handle(&mut self, message: Withdraw, _ctx: &mut ActorContext) -> Balance {
+//!         self.withdraw(message.amount)
+//!     }
+//! }
+//!
+//! /// v2 applies the overdraft policy
+//! #[async_trait]
+//! impl VersionedHandler<Withdraw, 2> for Account {
+//!     async fn handle_version(&mut self, message: Withdraw, _ctx: &mut ActorContext) -> Balance {
+//!         self.withdraw_with_overdraft(message.amount)
+//!     }
+//! }
+//!
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .with_handlers(|handlers| {
+//!         handlers
+//!             .with_handler::<Account, Withdraw>("Account.Withdraw")
+//!             .with_versioned_handler::<Account, Withdraw, 2>("Account.Withdraw")
+//!     })
+//!     .build()
+//!     .await;
+//!
+//! // once every node has been migrated
+//! remote.set_message_version("Account.Withdraw", Some(2));
+//! ```
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{
+    DeliverySemantics, Handler, Message, MessageUnwrapErr, MessageWrapErr,
+};
+use crate::actor::Actor;
+
+/// Version `VERSION` of how the actor handles messages of type `M`
+#[async_trait]
+pub trait VersionedHandler<M: Message, const VERSION: u32>: Actor {
+    async fn handle_version(&mut self, message: M, ctx: &mut ActorContext) -> M::Result;
+}
+
+/// A message handled by version `VERSION` of the actor's handler
+pub struct Versioned<M: Message, const VERSION: u32>(pub M);
+
+impl<M: Message, const VERSION: u32> Message for Versioned<M, VERSION> {
+    type Result = M::Result;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        self.0.as_bytes()
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        M::from_bytes(b).map(Versioned)
+    }
+
+    fn read_remote_result(res: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        M::read_remote_result(res)
+    }
+
+    fn write_remote_result(res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        M::write_remote_result(res)
+    }
+
+    fn delivery_semantics() -> DeliverySemantics {
+        M::delivery_semantics()
+    }
+}
+
+#[async_trait]
+impl<A: VersionedHandler<M, VERSION>, M: Message, const VERSION: u32> Handler<Versioned<M, VERSION>>
+    for A
+{
+    async fn handle(
+        &mut self,
+        message: Versioned<M, VERSION>,
+        ctx: &mut ActorContext,
+    ) -> M::Result {
+        self.handle_version(message.0, ctx).await
+    }
+}
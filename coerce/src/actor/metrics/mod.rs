@@ -1,12 +1,17 @@
 //! Actor Metrics
+
+pub mod throughput;
+
 use std::time::Duration;
 
 pub const METRIC_ACTOR_CREATED: &str = "coerce_actor_created";
 pub const METRIC_ACTOR_STOPPED: &str = "coerce_actor_stopped";
+pub const METRIC_ACTOR_RESTARTED: &str = "coerce_actor_restarted";
 pub const METRIC_ACTOR_MESSAGES_SENT_TOTAL: &str = "coerce_actor_msg_sent_total";
 pub const METRIC_ACTOR_MESSAGE_WAIT_TIME: &str = "coerce_actor_msg_wait_time";
 pub const METRIC_ACTOR_MESSAGE_PROCESSING_TIME: &str = "coerce_actor_msg_processing_time";
 pub const METRIC_ACTOR_MESSAGES_PROCESSED_TOTAL: &str = "coerce_actor_msg_processed_total";
+pub const METRIC_ACTOR_DEAD_LETTERS_TOTAL: &str = "coerce_actor_dead_letters_total";
 
 pub const LABEL_ACTOR_TYPE: &str = "actor_type";
 pub const LABEL_MESSAGE_TYPE: &str = "msg_type";
@@ -30,6 +35,14 @@ impl ActorMetrics {
         );
     }
 
+    #[inline]
+    pub fn incr_actor_restarted(actor_type: &'static str) {
+        #[cfg(feature = "metrics")]
+        increment_counter!(METRIC_ACTOR_RESTARTED,
+            LABEL_ACTOR_TYPE => actor_type,
+        );
+    }
+
     #[inline]
     pub fn incr_messages_sent(actor_type: &'static str, msg_type: &'static str) {
         #[cfg(feature = "metrics")]
@@ -39,6 +52,15 @@ impl ActorMetrics {
         );
     }
 
+    #[inline]
+    pub fn incr_dead_letters(actor_type: &'static str, msg_type: &'static str) {
+        #[cfg(feature = "metrics")]
+        increment_counter!(METRIC_ACTOR_DEAD_LETTERS_TOTAL,
+            LABEL_ACTOR_TYPE => actor_type,
+            LABEL_MESSAGE_TYPE => msg_type
+        );
+    }
+
     #[inline]
     pub fn incr_messages_processed(
         actor_type: &'static str,
@@ -0,0 +1,184 @@
+//! Actor throughput summaries, emitted as `tracing` events
+//!
+//! The [`ThroughputReporter`] is a [`SystemHook`] which tracks how many messages each actor
+//! handles, and how long each message took to handle. At the configured cadence, a summary of each
+//! active actor's throughput is emitted as an `INFO` event with the target
+//! `coerce::actor::throughput`, giving environments without a metrics backend actionable data
+//! in their logs.
+//!
+//! Summaries are emitted by the actor itself, once it handles a message after the interval has
+//! elapsed, so actors that are idle for an entire interval don't emit a summary. Any remaining
+//! summary is emitted when the actor stops.
+//!
+//! Handle time percentiles are calculated from a uniform sample of up to 1000 messages per interval.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let system = ActorSystem::builder()
+//!     .add_hook(ThroughputReporter::new(Duration::from_secs(30)).min_messages(100))
+//!     .build();
+//! ```
+//!
+//! [`SystemHook`]: crate::actor::hooks::SystemHook
+
+use crate::actor::context::ActorContext;
+use crate::actor::hooks::{ActorDecorator, SystemHook};
+use crate::actor::rt::Instant;
+use rand::Rng;
+use std::sync::Arc;
+use std::time::Duration;
+
+const MAX_SAMPLES: usize = 1000;
+
+type ActorFilter = Arc<dyn Fn(&ActorContext) -> bool + Send + Sync>;
+
+pub struct ThroughputReporter {
+    interval: Duration,
+    min_messages: u64,
+    filter: Option<ActorFilter>,
+}
+
+impl ThroughputReporter {
+    /// Reports the throughput of each actor once per `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            min_messages: 1,
+            filter: None,
+        }
+    }
+
+    /// Only reports actors that handled at least `min_messages` messages during the interval,
+    /// defaults to 1
+    pub fn min_messages(mut self, min_messages: u64) -> Self {
+        self.min_messages = min_messages;
+        self
+    }
+
+    /// Only reports actors matching the filter, evaluated once when each actor is spawned
+    pub fn with_filter(
+        mut self,
+        filter: impl Fn(&ActorContext) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+}
+
+impl SystemHook for ThroughputReporter {
+    fn on_actor_spawn(&self, ctx: &ActorContext) -> Option<Box<dyn ActorDecorator>> {
+        if let Some(filter) = &self.filter {
+            if !filter(ctx) {
+                return None;
+            }
+        }
+
+        Some(Box::new(ThroughputDecorator {
+            interval: self.interval,
+            min_messages: self.min_messages,
+            window: ThroughputWindow::new(Instant::now()),
+        }))
+    }
+}
+
+struct ThroughputDecorator {
+    interval: Duration,
+    min_messages: u64,
+    window: ThroughputWindow,
+}
+
+struct ThroughputWindow {
+    started_at: Instant,
+    messages: u64,
+    max_handle_time: Duration,
+    samples: Vec<Duration>,
+}
+
+impl ThroughputWindow {
+    fn new(started_at: Instant) -> Self {
+        Self {
+            started_at,
+            messages: 0,
+            max_handle_time: Duration::ZERO,
+            samples: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, handle_time: Duration) {
+        self.messages += 1;
+        self.max_handle_time = self.max_handle_time.max(handle_time);
+
+        // reservoir sampling keeps a uniform sample of the window's handle times,
+        // without the memory usage growing with the actor's throughput
+        if self.samples.len() < MAX_SAMPLES {
+            self.samples.push(handle_time);
+        } else {
+            let i = rand::thread_rng().gen_range(0..self.messages) as usize;
+            if i < MAX_SAMPLES {
+                self.samples[i] = handle_time;
+            }
+        }
+    }
+
+    /// Returns the p50 and p99 handle times of the sampled messages
+    fn percentiles(&mut self) -> (Duration, Duration) {
+        self.samples.sort_unstable();
+
+        let percentile = |percentile: f64| {
+            let rank = (percentile * self.samples.len() as f64).ceil() as usize;
+            self.samples
+                .get(rank.clamp(1, self.samples.len().max(1)) - 1)
+                .copied()
+                .unwrap_or_default()
+        };
+
+        (percentile(0.5), percentile(0.99))
+    }
+}
+
+impl ThroughputDecorator {
+    fn report(&mut self, now: Instant, ctx: &ActorContext) {
+        let mut window = std::mem::replace(&mut self.window, ThroughputWindow::new(now));
+        if window.messages == 0 || window.messages < self.min_messages {
+            return;
+        }
+
+        let elapsed = now.duration_since(window.started_at);
+        let messages_per_sec = window.messages as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+        let (p50, p99) = window.percentiles();
+        let log = ctx.log();
+
+        info!(
+            target: "coerce::actor::throughput",
+            actor = log.actor_path.as_ref(),
+            actor_type = log.actor_type,
+            messages = window.messages,
+            messages_per_sec,
+            p50_handle_time_us = p50.as_micros() as u64,
+            p99_handle_time_us = p99.as_micros() as u64,
+            max_handle_time_us = window.max_handle_time.as_micros() as u64,
+            window_ms = elapsed.as_millis() as u64,
+            "actor throughput"
+        );
+    }
+}
+
+impl ActorDecorator for ThroughputDecorator {
+    fn after_handle(
+        &mut self,
+        _message_type: &'static str,
+        processing_time: Duration,
+        ctx: &ActorContext,
+    ) {
+        self.window.record(processing_time);
+
+        let now = Instant::now();
+        if now.duration_since(self.window.started_at) >= self.interval {
+            self.report(now, ctx);
+        }
+    }
+
+    fn on_stop(&mut self, ctx: &ActorContext) {
+        self.report(Instant::now(), ctx);
+    }
+}
@@ -18,7 +18,9 @@
 //!
 //! Whilst message handlers are `async`, the actor will always wait until for handler completion
 //! before moving onto subsequent messages in the mailbox. If the actor needs to defer work and
-//! return a result faster, an asynchronous task should be spawned.
+//! return a result faster, an asynchronous task should be spawned. Handlers can reply before they
+//! return via [`ActorContext::reply`], or take the reply channel via [`ActorContext::defer_reply`]
+//! and send the result from the spawned task.
 //!
 //! ## General lifecycle of an [`Actor`][Actor]:
 //! 1. [`ActorContext`] is created
@@ -37,6 +39,8 @@
 //! [`Actor`]: Actor
 //! [`Handler<M>`]: message::Handler
 //! [`ActorContext`]: context::ActorContext
+//! [`ActorContext::reply`]: context::ActorContext::reply
+//! [`ActorContext::defer_reply`]: context::ActorContext::defer_reply
 //! [`ActorScheduler`]: scheduler::ActorScheduler
 //! [`ActorType::Tracked`]: scheduler::ActorType::Tracked
 //! [`Actor::started`]: Actor::started
@@ -146,6 +150,7 @@ use crate::actor::message::{
 use crate::actor::metrics::ActorMetrics;
 use crate::actor::scheduler::ActorType::{Anonymous, Tracked};
 use crate::actor::supervised::Terminated;
+use crate::actor::supervised::{RestartReason, RestartStorm};
 use crate::actor::system::ActorSystem;
 use std::any::Any;
 use std::fmt::{Debug, Display, Formatter};
@@ -166,14 +171,24 @@ use crate::actor::message::Envelope;
 #[cfg(feature = "remote")]
 use crate::remote::{system::NodeId, RemoteActorRef};
 
+#[cfg(feature = "persistence")]
+use crate::persistent::checkpoint::Checkpoint;
+#[cfg(feature = "persistence")]
+use crate::persistent::migration::MigrationSnapshot;
+
 pub use refs::*;
 
+#[cfg(not(target_arch = "wasm32"))]
 pub mod blocking;
 
 pub mod context;
 
+pub mod dead_letter;
+
 pub mod describe;
 
+pub mod hooks;
+
 #[cfg(feature = "actor-events")]
 pub mod event;
 
@@ -181,12 +196,20 @@ pub mod watch;
 
 pub mod lifecycle;
 
+pub mod mailbox;
+
 pub mod message;
 
 pub mod metrics;
 
 pub mod refs;
 
+pub mod rt;
+
+pub mod sampling;
+
+pub mod scatter_gather;
+
 pub mod scheduler;
 
 pub mod supervised;
@@ -220,12 +243,45 @@ pub trait Actor: 'static + Send + Sync {
     /// Called once the Actor has been started
     async fn started(&mut self, _ctx: &mut ActorContext) {}
 
-    /// Called once the Actor has stopped
+    /// Called once the Actor has stopped, after every child actor has been stopped
     async fn stopped(&mut self, _ctx: &mut ActorContext) {}
 
     /// Called when a supervised actor has stopped
     async fn on_child_stopped(&mut self, _id: &ActorId, _ctx: &mut ActorContext) {}
 
+    /// Called when a restartable supervised actor has been restarted more times than its
+    /// [`RestartPolicy`][supervised::RestartPolicy] allows
+    async fn on_restart_storm(&mut self, _storm: &RestartStorm, _ctx: &mut ActorContext) {}
+
+    /// Called on the failed instance of a restartable supervised actor (see
+    /// [`ActorContext::spawn_restartable`][context::ActorContext::spawn_restartable]), before the
+    /// failure is reported to its supervisor, allowing resources to be released before the actor
+    /// is re-created.
+    ///
+    /// The supervisor may still decide not to restart the actor, if a
+    /// [`RestartStorm`][supervised::RestartStorm] is detected.
+    async fn pre_restart(&mut self, _reason: &RestartReason, _ctx: &mut ActorContext) {}
+
+    /// Called on the new instance of a restartable supervised actor, once it has been re-created
+    /// after a failure. Invoked after [`Actor::started`], before any messages are handled.
+    async fn post_restart(&mut self, _ctx: &mut ActorContext) {}
+
+    /// Called when the actor is being stopped so it can be migrated to another node, returning a
+    /// snapshot of its state that is shipped to the new node.
+    ///
+    /// Implemented for every [`PersistentActor`][crate::persistent::PersistentActor], see
+    /// [`PersistentActor::on_migrate`][crate::persistent::PersistentActor::on_migrate].
+    #[cfg(feature = "persistence")]
+    async fn migration_snapshot(&mut self, _ctx: &mut ActorContext) -> Option<MigrationSnapshot> {
+        None
+    }
+
+    /// Called when the actor is started with the latest checkpoint of its state, before
+    /// [`Actor::started`]. Only called if the actor has a [`CHECKPOINT_INTERVAL`](Actor::CHECKPOINT_INTERVAL)
+    /// and a checkpoint has been written, see [`checkpoint`][crate::persistent::checkpoint].
+    #[cfg(feature = "persistence")]
+    async fn restore(&mut self, _checkpoint: Checkpoint, _ctx: &mut ActorContext) {}
+
     /// Returns a [`LocalActorRef<Self>`] instance of the current actor,
     /// automatically casting from the [`ActorContext`][context::ActorContext]'s [`BoxedActorRef`][BoxedActorRef].
     ///
@@ -253,6 +309,11 @@ pub trait Actor: 'static + Send + Sync {
 
     /// Default tags used when creating the actor
     const DEFAULT_TAGS: ActorTags = { ActorTags::None };
+
+    /// How often state staged via [`ActorContext::checkpoint`][context::ActorContext::checkpoint]
+    /// is persisted, checkpointing is disabled by default
+    #[cfg(feature = "persistence")]
+    const CHECKPOINT_INTERVAL: Option<Duration> = None;
 }
 
 /// Trait allowing the creation of an [`Actor`][Actor] directly from itself
@@ -498,10 +559,10 @@ impl<A: Actor> LocalActorRef<A> {
         let actor_ref = self.clone();
         let cancellation_token = CancellationToken::new();
         let cancellation_token_clone = cancellation_token.clone();
-        let _join_handle = tokio::spawn(async move {
+        rt::spawn(async move {
             tokio::select! {
                 _ = cancellation_token_clone.cancelled() => { }
-                _ = tokio::time::sleep(delay) => {
+                _ = rt::sleep(delay) => {
                     let _ = actor_ref.notify(message);
                 }
             }
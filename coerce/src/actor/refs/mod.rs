@@ -1,13 +1,19 @@
 use crate::actor::context::ActorStatus;
+use crate::actor::dead_letter::{DeadLetter, DeadLetterReason};
 use crate::actor::describe::Describe;
-use crate::actor::lifecycle::{Status, Stop};
+use crate::actor::lifecycle::{GracefulStop, Status, Stop};
+use crate::actor::mailbox::{
+    Enqueued, MailboxCounter, MailboxSender, MailboxSnapshot, SequenceToken,
+};
 use crate::actor::message::{
-    ActorMessage, Envelope, Exec, Handler, Message, MessageHandler, MessageUnwrapErr,
+    ActorMessage, Envelope, Exec, Handler, IntoMessage, Message, MessageHandler, MessageUnwrapErr,
     MessageWrapErr,
 };
 use crate::actor::metrics::ActorMetrics;
-use crate::actor::supervised::Terminated;
-use crate::actor::{Actor, ActorId, ActorPath};
+use crate::actor::rt;
+use crate::actor::supervised::{ChildFailed, Terminated};
+use crate::actor::watch::{ActorTerminated, Unwatch, Watch, Watchable};
+use crate::actor::{Actor, ActorId, ActorPath, Receiver};
 use std::any::Any;
 use std::fmt::{Debug, Display, Formatter};
 use std::future::Future;
@@ -17,10 +23,14 @@ use std::sync::Arc;
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
 
 #[cfg(feature = "remote")]
 use crate::remote::{actor_ref::RemoteActorRef, system::NodeId};
 
+#[cfg(feature = "persistence")]
+use crate::persistent::migration::{MigrationSnapshot, StopForMigration};
+
 /// Location-transparent reference to an [`Actor`][Actor].
 ///
 /// Supported targets:
@@ -44,10 +54,8 @@ pub struct LocalActorRef<A: Actor> {
 }
 
 impl<A: Actor> LocalActorRef<A> {
-    /// Get the direct reference to the actor mailbox's [
-    ///
-    /// [`UnboundedSender`][tokio::sync::mpsc::unbounded::UnboundedSender]
-    pub fn sender(&self) -> &UnboundedSender<MessageHandler<A>> {
+    /// Get the direct reference to the sending half of the actor's mailbox
+    pub fn sender(&self) -> &MailboxSender<A> {
         &self.inner.sender
     }
 }
@@ -56,7 +64,9 @@ impl<A: Actor> LocalActorRef<A> {
 pub struct LocalActorRefInner<A: Actor> {
     pub id: ActorId,
     path: ActorPath,
-    sender: UnboundedSender<MessageHandler<A>>,
+    sender: MailboxSender<A>,
+    stopped: CancellationToken,
+    mailbox: MailboxCounter,
 }
 
 impl<A: Actor> Clone for Ref<A> {
@@ -132,9 +142,13 @@ impl<A: Actor> ActorRef<A> {
             Ref::Local(local_ref) => local_ref.send(msg).await,
 
             #[cfg(feature = "remote")]
-            Ref::Remote(remote_ref) => match msg.as_bytes() {
-                Ok(envelope) => remote_ref.send(Envelope::Remote(envelope)).await,
-                Err(e) => Err(ActorRefErr::Serialisation(e)),
+            Ref::Remote(remote_ref) => match remote_ref.resolve_local::<Msg>().await {
+                Some(Ok(local_ref)) => local_ref.send(msg).await,
+                Some(Err(e)) => Err(e),
+                None => match msg.as_bytes() {
+                    Ok(envelope) => remote_ref.send(Envelope::Remote(envelope)).await,
+                    Err(e) => Err(ActorRefErr::Serialisation(e)),
+                },
             },
         }
     }
@@ -147,13 +161,38 @@ impl<A: Actor> ActorRef<A> {
             Ref::Local(local_ref) => local_ref.notify(msg),
 
             #[cfg(feature = "remote")]
-            Ref::Remote(remote_ref) => match msg.as_bytes() {
-                Ok(envelope) => remote_ref.notify(Envelope::Remote(envelope)).await,
-                Err(e) => Err(ActorRefErr::Serialisation(e)),
+            Ref::Remote(remote_ref) => match remote_ref.resolve_local::<Msg>().await {
+                Some(Ok(local_ref)) => local_ref.notify(msg),
+                Some(Err(e)) => Err(e),
+                None => match msg.as_bytes() {
+                    Ok(envelope) => remote_ref.notify(Envelope::Remote(envelope)).await,
+                    Err(e) => Err(ActorRefErr::Serialisation(e)),
+                },
             },
         }
     }
 
+    /// Converts the value into its owned [`Message`] and sends it to the target actor,
+    /// waiting for the result, see [`IntoMessage`].
+    pub async fn send_into<T: IntoMessage>(
+        &self,
+        msg: T,
+    ) -> Result<<T::Message as Message>::Result, ActorRefErr>
+    where
+        A: Handler<T::Message>,
+    {
+        self.send(msg.into_message()).await
+    }
+
+    /// Converts the value into its owned [`Message`] and sends it to the target actor,
+    /// without waiting for it to be processed, see [`IntoMessage`].
+    pub async fn notify_into<T: IntoMessage>(&self, msg: T) -> Result<(), ActorRefErr>
+    where
+        A: Handler<T::Message>,
+    {
+        self.notify(msg.into_message()).await
+    }
+
     pub fn is_local(&self) -> bool {
         matches!(&self.inner_ref, &Ref::Local(_))
     }
@@ -179,6 +218,26 @@ impl<A: Actor> ActorRef<A> {
     }
 }
 
+impl<A: Actor> Watchable for ActorRef<A> {
+    fn add_watcher(&self, watcher: Receiver<ActorTerminated>) {
+        match &self.inner_ref {
+            Ref::Local(local_ref) => local_ref.add_watcher(watcher),
+
+            #[cfg(feature = "remote")]
+            Ref::Remote(remote_ref) => remote_ref.add_watcher(watcher),
+        }
+    }
+
+    fn remove_watcher(&self, watcher_id: ActorId) {
+        match &self.inner_ref {
+            Ref::Local(local_ref) => local_ref.remove_watcher(watcher_id),
+
+            #[cfg(feature = "remote")]
+            Ref::Remote(remote_ref) => remote_ref.remove_watcher(watcher_id),
+        }
+    }
+}
+
 /// Trait defining the core functionality of an [`ActorRef`][ActorRef].
 #[async_trait]
 pub trait CoreActorRef: Any {
@@ -190,7 +249,7 @@ pub trait CoreActorRef: Any {
 
     async fn status(&self) -> Result<ActorStatus, ActorRefErr>;
 
-    async fn stop(&self) -> Result<(), ActorRefErr>;
+    async fn stop(&self, graceful: bool) -> Result<(), ActorRefErr>;
 
     fn describe(&self, describe: Describe) -> Result<(), ActorRefErr>;
 
@@ -198,8 +257,19 @@ pub trait CoreActorRef: Any {
 
     fn notify_child_terminated(&self, id: ActorId) -> Result<(), ActorRefErr>;
 
+    fn notify_child_failed(&self, id: ActorId) -> Result<(), ActorRefErr>;
+
+    fn notify_watch(&self, watcher: Receiver<ActorTerminated>) -> Result<(), ActorRefErr>;
+
+    fn notify_unwatch(&self, watcher_id: ActorId) -> Result<(), ActorRefErr>;
+
     fn is_valid(&self) -> bool;
 
+    fn mailbox_snapshot(&self) -> MailboxSnapshot;
+
+    #[cfg(feature = "persistence")]
+    async fn stop_for_migration(&self) -> Result<Option<MigrationSnapshot>, ActorRefErr>;
+
     fn as_any(&self) -> &dyn Any;
 }
 
@@ -300,6 +370,8 @@ pub enum ActorRefErr {
         actor_type: String,
     },
     NotImplemented,
+    MailboxFull,
+    WriteBufferFull,
 }
 
 impl Display for ActorRefErr {
@@ -327,6 +399,10 @@ impl Display for ActorRefErr {
             ),
             ActorRefErr::ActorStartFailed => write!(f, "actor failed to start, channel closed"),
             ActorRefErr::NotImplemented => write!(f, "functionality is not yet implemented"),
+            ActorRefErr::MailboxFull => write!(f, "actor mailbox is full"),
+            ActorRefErr::WriteBufferFull => {
+                write!(f, "node unreachable and its client's write buffer is full")
+            }
         }
     }
 }
@@ -339,8 +415,18 @@ impl<A: Actor> LocalActorRef<A> {
     ///
     /// Generally this should not be used directly.
     pub fn new(id: ActorId, sender: UnboundedSender<MessageHandler<A>>, path: ActorPath) -> Self {
+        Self::from_mailbox(id, MailboxSender::Unbounded(sender), path)
+    }
+
+    pub(crate) fn from_mailbox(id: ActorId, sender: MailboxSender<A>, path: ActorPath) -> Self {
         Self {
-            inner: Arc::new(LocalActorRefInner { id, path, sender }),
+            inner: Arc::new(LocalActorRefInner {
+                id,
+                path,
+                sender,
+                stopped: CancellationToken::new(),
+                mailbox: MailboxCounter::default(),
+            }),
         }
     }
 
@@ -395,9 +481,8 @@ impl<A: Actor> LocalActorRef<A> {
 
         let (tx, rx) = oneshot::channel();
         match self
-            .inner
-            .sender
-            .send(Box::new(ActorMessage::new(msg, Some(tx))))
+            .enqueue_wait(Box::new(ActorMessage::new(msg, Some(tx))))
+            .await
         {
             Ok(_) => match rx.await {
                 Ok(res) => {
@@ -411,7 +496,7 @@ impl<A: Actor> LocalActorRef<A> {
                 }
                 Err(_e) => Err(ActorRefErr::ResultChannelClosed),
             },
-            Err(_e) => Err(ActorRefErr::InvalidRef),
+            Err(e) => Err(e),
         }
     }
 
@@ -427,14 +512,7 @@ impl<A: Actor> LocalActorRef<A> {
     {
         ActorMetrics::incr_messages_sent(A::type_name(), msg.name());
 
-        match self
-            .inner
-            .sender
-            .send(Box::new(ActorMessage::new(msg, Some(result_sender))))
-        {
-            Ok(_) => Ok(()),
-            Err(_) => Err(ActorRefErr::InvalidRef),
-        }
+        self.enqueue(Box::new(ActorMessage::new(msg, Some(result_sender))))
     }
 
     /// Sends a message to the target [`Actor`][Actor] but doesn't wait for the message to be processed.
@@ -464,16 +542,133 @@ impl<A: Actor> LocalActorRef<A> {
     {
         ActorMetrics::incr_messages_sent(A::type_name(), msg.name());
 
-        match self
-            .inner
-            .sender
-            .send(Box::new(ActorMessage::new(msg, None)))
-        {
-            Ok(_) => Ok(()),
-            Err(_e) => Err(ActorRefErr::InvalidRef),
+        self.enqueue(Box::new(ActorMessage::new(msg, None)))
+    }
+
+    /// Converts the value into its owned [`Message`] and sends it to the target [`Actor`][Actor],
+    /// waiting for the result, see [`IntoMessage`].
+    pub async fn send_into<T: IntoMessage>(
+        &self,
+        msg: T,
+    ) -> Result<<T::Message as Message>::Result, ActorRefErr>
+    where
+        A: Handler<T::Message>,
+    {
+        self.send(msg.into_message()).await
+    }
+
+    /// Converts the value into its owned [`Message`] and sends it to the target [`Actor`][Actor],
+    /// without waiting for it to be processed, see [`IntoMessage`].
+    pub fn notify_into<T: IntoMessage>(&self, msg: T) -> Result<(), ActorRefErr>
+    where
+        A: Handler<T::Message>,
+    {
+        self.notify(msg.into_message())
+    }
+
+    /// Enqueues the message into the actor's mailbox, counting it as queued until it is received
+    /// by the actor
+    pub(crate) fn enqueue(&self, message: MessageHandler<A>) -> Result<(), ActorRefErr> {
+        let message_type = message.name();
+
+        // counted before sending, so the actor can never receive the message before it is counted
+        self.inner.mailbox.enqueued(message_type);
+
+        let enqueued = self.inner.sender.try_send(message);
+        self.on_enqueued(message_type, enqueued)
+    }
+
+    /// Enqueues the message into the actor's mailbox, waiting for room in the mailbox if the
+    /// mailbox is bounded with [`OverflowPolicy::Backpressure`]
+    ///
+    /// [`OverflowPolicy::Backpressure`]: crate::actor::mailbox::OverflowPolicy::Backpressure
+    pub(crate) async fn enqueue_wait(&self, message: MessageHandler<A>) -> Result<(), ActorRefErr> {
+        let message_type = message.name();
+        self.inner.mailbox.enqueued(message_type);
+
+        let enqueued = self.inner.sender.send(message).await;
+        self.on_enqueued(message_type, enqueued)
+    }
+
+    /// Reserves room in the actor's mailbox for a message, which must then be enqueued via
+    /// [`LocalActorRef::enqueue_reserved`], or released via [`LocalActorRef::cancel_reservation`]
+    pub(crate) fn reserve(&self) -> Result<(), ActorRefErr> {
+        self.inner.sender.reserve()
+    }
+
+    pub(crate) fn cancel_reservation(&self) {
+        self.inner.sender.cancel_reservation()
+    }
+
+    pub(crate) fn enqueue_reserved(&self, message: MessageHandler<A>) -> Result<(), ActorRefErr> {
+        let message_type = message.name();
+        self.inner.mailbox.enqueued(message_type);
+
+        let enqueued = self.inner.sender.send_reserved(message);
+        self.on_enqueued(message_type, enqueued)
+    }
+
+    fn on_enqueued(
+        &self,
+        message_type: &'static str,
+        enqueued: Enqueued<A>,
+    ) -> Result<(), ActorRefErr> {
+        match enqueued {
+            Enqueued::Queued => Ok(()),
+            Enqueued::Dropped(dropped) => {
+                self.inner.mailbox.dequeued(dropped.name());
+
+                let dead_letter = DeadLetter {
+                    actor_id: self.inner.id.clone(),
+                    actor_type: A::type_name(),
+                    message_type: dropped.name(),
+                    reason: DeadLetterReason::MailboxFull,
+                    payload: dropped.as_bytes().map(Arc::new),
+                };
+
+                if let Some(dead_letters) = self.inner.sender.dead_letters() {
+                    dead_letters.publish(dead_letter);
+                } else {
+                    ActorMetrics::incr_dead_letters(
+                        dead_letter.actor_type,
+                        dead_letter.message_type,
+                    );
+                }
+
+                Ok(())
+            }
+            Enqueued::Full(_) => {
+                self.inner.mailbox.dequeued(message_type);
+                Err(ActorRefErr::MailboxFull)
+            }
+            Enqueued::Closed => {
+                self.inner.mailbox.dequeued(message_type);
+                Err(ActorRefErr::InvalidRef)
+            }
         }
     }
 
+    pub(crate) fn mailbox(&self) -> &MailboxCounter {
+        &self.inner.mailbox
+    }
+
+    /// Returns a snapshot of the messages currently queued in the actor's mailbox, by message type,
+    /// without consuming them
+    pub fn mailbox_snapshot(&self) -> MailboxSnapshot {
+        self.inner.mailbox.snapshot()
+    }
+
+    /// The actor's virtual time, the number of messages the actor has received
+    pub fn virtual_time(&self) -> u64 {
+        self.inner.mailbox.virtual_time()
+    }
+
+    /// Returns the point in the actor's virtual time at which every message currently queued in
+    /// the actor's mailbox will have been received
+    pub fn sequence_token(&self) -> SequenceToken {
+        self.inner.mailbox.sequence_token()
+    }
+
     pub async fn exec<F, R>(&self, f: F) -> Result<R, ActorRefErr>
     where
         F: (FnMut(&mut A) -> R) + 'static + Send + Sync,
@@ -494,10 +689,21 @@ impl<A: Actor> LocalActorRef<A> {
         self.send(Status).await
     }
 
-    /// Attempts to stop the target `Actor`, waiting for completion
-    pub async fn stop(&self) -> Result<(), ActorRefErr> {
+    /// Attempts to stop the target `Actor`, waiting for completion.
+    ///
+    /// If `graceful` is true, every message that was enqueued before the stop began will be processed
+    /// before the actor stops, otherwise the actor stops once the current message has been processed
+    /// and any remaining messages are discarded as [`DeadLetter`]s.
+    ///
+    /// [`DeadLetter`]: crate::actor::dead_letter::DeadLetter
+    pub async fn stop(&self, graceful: bool) -> Result<(), ActorRefErr> {
         let (tx, rx) = oneshot::channel();
-        let res = self.notify(Stop(Some(tx)));
+        let res = if graceful {
+            self.notify(GracefulStop(Some(tx)))
+        } else {
+            self.notify(Stop(Some(tx)))
+        };
+
         if res.is_ok() {
             rx.await.map_err(|_| ActorRefErr::InvalidRef)
         } else {
@@ -505,6 +711,23 @@ impl<A: Actor> LocalActorRef<A> {
         }
     }
 
+    /// Waits until the target [`Actor`][Actor] has stopped, resolving immediately if the actor
+    /// has already stopped.
+    ///
+    /// This does not request that the actor stops, see [`stop`][LocalActorRef::stop].
+    pub async fn wait_for_stop(&self) {
+        self.inner.stopped.cancelled().await
+    }
+
+    /// Returns true if the target [`Actor`][Actor] has stopped
+    pub fn is_stopped(&self) -> bool {
+        self.inner.stopped.is_cancelled()
+    }
+
+    pub(crate) fn stopped_token(&self) -> &CancellationToken {
+        &self.inner.stopped
+    }
+
     pub fn describe(&self, describe: Describe) -> Result<(), ActorRefErr> {
         self.notify(describe)
     }
@@ -537,8 +760,8 @@ impl<A: Actor> CoreActorRef for LocalActorRef<A> {
         self.status().await
     }
 
-    async fn stop(&self) -> Result<(), ActorRefErr> {
-        self.stop().await
+    async fn stop(&self, graceful: bool) -> Result<(), ActorRefErr> {
+        self.stop(graceful).await
     }
 
     fn describe(&self, describe: Describe) -> Result<(), ActorRefErr> {
@@ -553,10 +776,31 @@ impl<A: Actor> CoreActorRef for LocalActorRef<A> {
         self.notify(Terminated(id))
     }
 
+    fn notify_child_failed(&self, id: ActorId) -> Result<(), ActorRefErr> {
+        self.notify(ChildFailed(id))
+    }
+
+    fn notify_watch(&self, watcher: Receiver<ActorTerminated>) -> Result<(), ActorRefErr> {
+        self.notify(Watch::from(watcher))
+    }
+
+    fn notify_unwatch(&self, watcher_id: ActorId) -> Result<(), ActorRefErr> {
+        self.notify(Unwatch::from(watcher_id))
+    }
+
     fn is_valid(&self) -> bool {
         self.is_valid()
     }
 
+    fn mailbox_snapshot(&self) -> MailboxSnapshot {
+        self.mailbox_snapshot()
+    }
+
+    #[cfg(feature = "persistence")]
+    async fn stop_for_migration(&self) -> Result<Option<MigrationSnapshot>, ActorRefErr> {
+        self.send(StopForMigration).await
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -580,8 +824,8 @@ impl CoreActorRef for BoxedActorRef {
         self.0.status().await
     }
 
-    async fn stop(&self) -> Result<(), ActorRefErr> {
-        self.0.stop().await
+    async fn stop(&self, graceful: bool) -> Result<(), ActorRefErr> {
+        self.0.stop(graceful).await
     }
 
     fn describe(&self, describe: Describe) -> Result<(), ActorRefErr> {
@@ -596,10 +840,31 @@ impl CoreActorRef for BoxedActorRef {
         self.0.notify_child_terminated(id)
     }
 
+    fn notify_child_failed(&self, id: ActorId) -> Result<(), ActorRefErr> {
+        self.0.notify_child_failed(id)
+    }
+
+    fn notify_watch(&self, watcher: Receiver<ActorTerminated>) -> Result<(), ActorRefErr> {
+        self.0.notify_watch(watcher)
+    }
+
+    fn notify_unwatch(&self, watcher_id: ActorId) -> Result<(), ActorRefErr> {
+        self.0.notify_unwatch(watcher_id)
+    }
+
     fn is_valid(&self) -> bool {
         self.0.is_valid()
     }
 
+    fn mailbox_snapshot(&self) -> MailboxSnapshot {
+        self.0.mailbox_snapshot()
+    }
+
+    #[cfg(feature = "persistence")]
+    async fn stop_for_migration(&self) -> Result<Option<MigrationSnapshot>, ActorRefErr> {
+        self.0.stop_for_migration().await
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -641,7 +906,7 @@ where
 
     fn pipe_to(self, actor_ref: ActorRef<A>) {
         let fut = self;
-        tokio::spawn(async move {
+        rt::spawn(async move {
             let result = fut.await;
             let _ = actor_ref.notify(result).await;
         });
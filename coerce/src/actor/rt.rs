@@ -0,0 +1,116 @@
+//! Runtime primitives used by the core actor runtime
+//!
+//! On native targets, these are backed by [tokio]. When compiling for `wasm32`, tasks are instead
+//! spawned onto the browser's event loop via `wasm-bindgen-futures`, and timers are driven by
+//! `setTimeout`, which allows the core actor runtime (without `remote` or `persistence`)
+//! to be used in browser clients.
+//!
+//! [tokio]: https://github.com/tokio/tokio-rs
+
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub use web_time::Instant;
+
+/// Returned by [`timeout`] when the future did not complete in time
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Elapsed;
+
+impl Display for Elapsed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+/// Spawns the future as a new task, the task runs to completion independently of the caller
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn<F>(future: F)
+where
+    F: 'static + Future<Output = ()> + Send,
+{
+    tokio::spawn(future);
+}
+
+/// Spawns the future as a new task, the task runs to completion independently of the caller
+#[cfg(target_arch = "wasm32")]
+pub fn spawn<F>(future: F)
+where
+    F: 'static + Future<Output = ()> + Send,
+{
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    tokio::time::timeout(duration, future)
+        .await
+        .map_err(|_| Elapsed)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    use futures::future::{select, Either};
+
+    let future = std::pin::pin!(future);
+    let sleep = std::pin::pin!(sleep(duration));
+    match select(future, sleep).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Elapsed),
+    }
+}
+
+/// Yields once every `period`, the first tick completes after one `period` has elapsed.
+/// Ticks that are missed are yielded immediately, until the interval has caught up.
+pub struct Interval {
+    #[cfg(not(target_arch = "wasm32"))]
+    interval: tokio::time::Interval,
+
+    #[cfg(target_arch = "wasm32")]
+    period: Duration,
+}
+
+impl Interval {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(period: Duration) -> Self {
+        use tokio::time::MissedTickBehavior;
+
+        let start = tokio::time::Instant::now() + period;
+        let mut interval = tokio::time::interval_at(start, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+        Self { interval }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(period: Duration) -> Self {
+        Self { period }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn tick(&mut self) {
+        self.interval.tick().await;
+    }
+
+    /// Browser timers are coarse and throttled in background tabs, so rather than
+    /// tracking a deadline, each tick simply waits for the next period
+    #[cfg(target_arch = "wasm32")]
+    pub async fn tick(&mut self) {
+        sleep(self.period).await
+    }
+}
@@ -0,0 +1,132 @@
+//! Message trace sampling
+//!
+//! When actor tracing is enabled (via the `actor-tracing` features), a span is created for every
+//! message an actor handles. Tracing every message is rarely practical in production, so
+//! [`TraceSampling`] allows the spans to be limited to the messages matching a set of
+//! [`SamplingRule`]s, for example only the messages received by the actors of a single tenant, a
+//! single sharded entity, or only messages of a specific type.
+//!
+//! Sampling is configured on the [`ActorSystem`] and can be changed while the system is running,
+//! so tracing can be enabled for a problematic tenant or entity without tracing all traffic:
+//!
+//! ```rust,ignore
+//! system.set_trace_sampling(
+//!     TraceSampling::rules()
+//!         .with_rule(SamplingRule::actor_id_prefix("tenant-42/"))
+//!         .with_rule(SamplingRule::actor_id("order-1234").and_message_type("PlaceOrder")),
+//! );
+//! ```
+//!
+//! A message is sampled when it matches any of the configured rules. With no sampling configured,
+//! every message is sampled.
+//!
+//! [`ActorSystem`]: crate::actor::system::ActorSystem
+
+use crate::actor::{ActorId, IntoActorId};
+
+/// Decides which handled messages are traced, see the [module docs][self]
+#[derive(Debug, Clone, Default)]
+pub enum TraceSampling {
+    /// Every message is traced
+    #[default]
+    All,
+
+    /// No messages are traced
+    None,
+
+    /// Only messages matching at least one of the rules are traced
+    Rules(Vec<SamplingRule>),
+}
+
+/// Matches messages by the id of the actor handling them and by the message type,
+/// every criteria that is set must match for the rule to match.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SamplingRule {
+    actor_id_prefix: Option<String>,
+    actor_id: Option<ActorId>,
+    message_type: Option<String>,
+}
+
+impl TraceSampling {
+    /// Creates sampling without any rules, which samples nothing until a rule is added
+    pub fn rules() -> Self {
+        Self::Rules(vec![])
+    }
+
+    pub fn with_rule(self, rule: SamplingRule) -> Self {
+        match self {
+            Self::Rules(mut rules) => {
+                rules.push(rule);
+                Self::Rules(rules)
+            }
+            _ => Self::Rules(vec![rule]),
+        }
+    }
+
+    pub fn is_sampled(&self, actor_id: &str, message_type: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::None => false,
+            Self::Rules(rules) => rules.iter().any(|r| r.matches(actor_id, message_type)),
+        }
+    }
+}
+
+impl SamplingRule {
+    /// Matches actors whose id starts with `prefix`, such as all actors belonging to a tenant
+    pub fn actor_id_prefix(prefix: impl ToString) -> Self {
+        Self::default().and_actor_id_prefix(prefix)
+    }
+
+    /// Matches a single actor, such as a sharded entity by its key
+    pub fn actor_id(actor_id: impl IntoActorId) -> Self {
+        Self::default().and_actor_id(actor_id)
+    }
+
+    /// Matches messages by their type name, either the fully qualified type name
+    /// (`my_crate::orders::PlaceOrder`) or just the name of the type (`PlaceOrder`)
+    pub fn message_type(message_type: impl ToString) -> Self {
+        Self::default().and_message_type(message_type)
+    }
+
+    pub fn and_actor_id_prefix(mut self, prefix: impl ToString) -> Self {
+        self.actor_id_prefix = Some(prefix.to_string());
+        self
+    }
+
+    pub fn and_actor_id(mut self, actor_id: impl IntoActorId) -> Self {
+        self.actor_id = Some(actor_id.into_actor_id());
+        self
+    }
+
+    pub fn and_message_type(mut self, message_type: impl ToString) -> Self {
+        self.message_type = Some(message_type.to_string());
+        self
+    }
+
+    pub fn matches(&self, actor_id: &str, message_type: &str) -> bool {
+        let prefix_matches = self
+            .actor_id_prefix
+            .as_ref()
+            .is_none_or(|prefix| actor_id.starts_with(prefix.as_str()));
+
+        let actor_id_matches = self
+            .actor_id
+            .as_ref()
+            .is_none_or(|id| id.as_ref() == actor_id);
+
+        let message_type_matches = self
+            .message_type
+            .as_ref()
+            .is_none_or(|t| message_type_matches(message_type, t));
+
+        prefix_matches && actor_id_matches && message_type_matches
+    }
+}
+
+fn message_type_matches(message_type: &str, rule: &str) -> bool {
+    message_type == rule
+        || message_type
+            .strip_suffix(rule)
+            .is_some_and(|module| module.ends_with("::"))
+}
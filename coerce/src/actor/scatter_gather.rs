@@ -0,0 +1,206 @@
+//! Scatter-gather
+//!
+//! [`scatter_gather`] sends a copy of a message to every target actor concurrently, and resolves as
+//! soon as `quorum` of them have replied successfully. Once the quorum has been reached, replies
+//! from the remaining targets are no longer awaited, any of those messages which are still queued
+//! will still be handled by their actor, but the result is discarded.
+//!
+//! Messages are sent via [`ActorRef::send`], so a target with a bounded mailbox configured with
+//! [`OverflowPolicy::Backpressure`] delays its send rather than failing it, and the time spent
+//! waiting for room in the mailbox counts towards the `timeout`.
+//!
+//! If enough targets fail that the quorum can no longer be reached, or the timeout elapses first,
+//! a [`ScatterGatherErr`] is returned, describing which targets failed, and why.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let gathered = scatter_gather(&replicas, Read { key }, 2, Duration::from_secs(1)).await?;
+//! for (actor_id, value) in gathered.replies {
+//!     ...
+//! }
+//! ```
+//!
+//! [`OverflowPolicy::Backpressure`]: crate::actor::mailbox::OverflowPolicy::Backpressure
+
+use crate::actor::message::{Handler, Message};
+use crate::actor::rt::timeout;
+use crate::actor::{Actor, ActorId, ActorRef, ActorRefErr};
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// The outcome of a successful [`scatter_gather`]
+#[derive(Debug)]
+pub struct Gathered<R> {
+    /// Successful replies, in the order they were received
+    pub replies: Vec<(ActorId, R)>,
+
+    /// Targets that failed before the quorum was reached
+    pub failures: Vec<(ActorId, ActorRefErr)>,
+
+    /// Targets that hadn't replied by the time the quorum was reached
+    pub cancelled: Vec<ActorId>,
+}
+
+#[derive(Debug)]
+pub enum ScatterGatherErr {
+    /// The quorum was zero, or greater than the number of targets
+    InvalidQuorum { quorum: usize, targets: usize },
+
+    /// Too many targets failed for the quorum to be reached
+    QuorumUnreachable {
+        quorum: usize,
+        replies: usize,
+        failures: Vec<(ActorId, ActorRefErr)>,
+    },
+
+    /// The timeout elapsed before the quorum was reached
+    Timeout {
+        quorum: usize,
+        replies: usize,
+        failures: Vec<(ActorId, ActorRefErr)>,
+        pending: Vec<ActorId>,
+    },
+}
+
+impl Display for ScatterGatherErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScatterGatherErr::InvalidQuorum { quorum, targets } => {
+                write!(f, "invalid quorum (quorum={}, targets={})", quorum, targets)
+            }
+            ScatterGatherErr::QuorumUnreachable {
+                quorum,
+                replies,
+                failures,
+            } => {
+                write!(
+                    f,
+                    "quorum unreachable (quorum={}, replies={}, failures={})",
+                    quorum,
+                    replies,
+                    failures.len()
+                )?;
+
+                write_failures(f, failures)
+            }
+            ScatterGatherErr::Timeout {
+                quorum,
+                replies,
+                failures,
+                pending,
+            } => {
+                write!(
+                    f,
+                    "timed out waiting for quorum (quorum={}, replies={}, failures={}, pending={})",
+                    quorum,
+                    replies,
+                    failures.len(),
+                    pending.len()
+                )?;
+
+                write_failures(f, failures)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScatterGatherErr {}
+
+fn write_failures(f: &mut Formatter<'_>, failures: &[(ActorId, ActorRefErr)]) -> std::fmt::Result {
+    for (actor_id, error) in failures {
+        write!(f, ", {}: {}", actor_id, error)?;
+    }
+
+    Ok(())
+}
+
+/// Sends `msg` to every target, resolving once `quorum` targets have replied successfully,
+/// see [`scatter_gather`](crate::actor::scatter_gather)
+pub async fn scatter_gather<A, M>(
+    targets: &[ActorRef<A>],
+    msg: M,
+    quorum: usize,
+    timeout_after: Duration,
+) -> Result<Gathered<M::Result>, ScatterGatherErr>
+where
+    A: Actor + Handler<M>,
+    M: Message + Clone,
+{
+    if quorum == 0 || quorum > targets.len() {
+        return Err(ScatterGatherErr::InvalidQuorum {
+            quorum,
+            targets: targets.len(),
+        });
+    }
+
+    let max_failures = targets.len() - quorum;
+    let mut responded = vec![false; targets.len()];
+    let mut replies = vec![];
+    let mut failures = vec![];
+
+    let mut requests: FuturesUnordered<_> = targets
+        .iter()
+        .enumerate()
+        .map(|(index, target)| {
+            let msg = msg.clone();
+            async move { (index, target.send(msg).await) }
+        })
+        .collect();
+
+    let gather = async {
+        while let Some((index, res)) = requests.next().await {
+            responded[index] = true;
+
+            let actor_id = targets[index].actor_id().clone();
+            match res {
+                Ok(reply) => {
+                    replies.push((actor_id, reply));
+                    if replies.len() == quorum {
+                        return true;
+                    }
+                }
+                Err(e) => {
+                    failures.push((actor_id, e));
+                    if failures.len() > max_failures {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        false
+    };
+
+    let reached = timeout(timeout_after, gather).await;
+
+    // dropping the outstanding requests stops waiting on any remaining replies
+    drop(requests);
+
+    let outstanding = targets
+        .iter()
+        .zip(responded)
+        .filter(|(_, responded)| !responded)
+        .map(|(target, _)| target.actor_id().clone())
+        .collect();
+
+    match reached {
+        Ok(true) => Ok(Gathered {
+            replies,
+            failures,
+            cancelled: outstanding,
+        }),
+        Ok(false) => Err(ScatterGatherErr::QuorumUnreachable {
+            quorum,
+            replies: replies.len(),
+            failures,
+        }),
+        Err(_) => Err(ScatterGatherErr::Timeout {
+            quorum,
+            replies: replies.len(),
+            failures,
+            pending: outstanding,
+        }),
+    }
+}
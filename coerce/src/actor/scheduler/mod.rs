@@ -6,15 +6,17 @@ use crate::actor::{
 };
 
 use crate::actor::lifecycle::ActorLoop;
+use crate::actor::mailbox::{mailbox, MailboxConfig, MailboxSnapshot};
+use crate::actor::supervised::Incarnation;
 use crate::actor::system::ActorSystem;
 
 #[cfg(feature = "remote")]
 use crate::remote::{actor::message::SetRemote, system::RemoteActorSystem};
 
+use crate::actor::rt::{self, Instant};
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
-use std::time::Instant;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
@@ -64,7 +66,7 @@ impl Actor for ActorScheduler {
         let stop_results =
             futures::future::join_all(self.actors.iter().map(|(id, actor)| async move {
                 debug!(actor_id = actor.actor_id().as_ref(), "stopping actor");
-                (id.clone(), actor.stop().await)
+                (id.clone(), actor.stop(false).await)
             }))
             .await;
 
@@ -121,6 +123,58 @@ impl Handler<ActorCount> for ActorScheduler {
     }
 }
 
+/// Returns a [`MailboxSnapshot`] of the actor with the provided ID, if it is tracked by the scheduler
+pub struct GetMailboxSnapshot(pub ActorId);
+
+impl Message for GetMailboxSnapshot {
+    type Result = Option<MailboxSnapshot>;
+}
+
+#[async_trait]
+impl Handler<GetMailboxSnapshot> for ActorScheduler {
+    async fn handle(
+        &mut self,
+        message: GetMailboxSnapshot,
+        _ctx: &mut ActorContext,
+    ) -> Option<MailboxSnapshot> {
+        self.actors
+            .get(&message.0)
+            .map(|actor| actor.mailbox_snapshot())
+    }
+}
+
+/// Returns a reference to the tracked actor with the provided ID, regardless of its type
+pub(crate) struct GetBoxedActor(pub ActorId);
+
+impl Message for GetBoxedActor {
+    type Result = Option<BoxedActorRef>;
+}
+
+#[async_trait]
+impl Handler<GetBoxedActor> for ActorScheduler {
+    async fn handle(
+        &mut self,
+        message: GetBoxedActor,
+        _ctx: &mut ActorContext,
+    ) -> Option<BoxedActorRef> {
+        self.actors.get(&message.0).cloned()
+    }
+}
+
+/// Returns a reference to every actor tracked by the scheduler
+pub(crate) struct GetAllActors;
+
+impl Message for GetAllActors {
+    type Result = Vec<BoxedActorRef>;
+}
+
+#[async_trait]
+impl Handler<GetAllActors> for ActorScheduler {
+    async fn handle(&mut self, _: GetAllActors, _ctx: &mut ActorContext) -> Vec<BoxedActorRef> {
+        self.actors.values().cloned().collect()
+    }
+}
+
 pub struct SetSystem(pub ActorSystem);
 
 impl Message for SetSystem {
@@ -276,15 +330,79 @@ pub fn start_actor<A: Actor>(
 where
     A: 'static + Send + Sync,
 {
-    let (tx, rx) = mpsc::unbounded_channel();
-    let actor_ref = LocalActorRef::new(id, tx, path);
+    start_actor_with_mailbox(
+        actor,
+        id,
+        actor_type,
+        MailboxConfig::unbounded(),
+        on_start,
+        system,
+        parent_ref,
+        path,
+    )
+}
+
+/// Starts the actor with the provided [`MailboxConfig`], dropped messages of a bounded mailbox are
+/// published to the system's [`DeadLetters`][crate::actor::dead_letter::DeadLetters]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn start_actor_with_mailbox<A: Actor>(
+    actor: A,
+    id: ActorId,
+    actor_type: ActorType,
+    mailbox_config: MailboxConfig,
+    on_start: Option<tokio::sync::oneshot::Sender<()>>,
+    system: Option<ActorSystem>,
+    parent_ref: Option<BoxedActorRef>,
+    path: ActorPath,
+) -> LocalActorRef<A> {
+    let dead_letters = system.as_ref().map(|system| system.dead_letters().clone());
+    let (tx, rx) = mailbox(mailbox_config, dead_letters);
+    let actor_ref = LocalActorRef::from_mailbox(id, tx, path);
+    let cloned_ref = actor_ref.clone();
+    let running = system.as_ref().map(|system| system.actor_running());
+
+    rt::spawn(async move {
+        ActorLoop::run(
+            actor, actor_type, rx, on_start, cloned_ref, parent_ref, system, None,
+        )
+        .await;
+
+        drop(running);
+    });
+
+    actor_ref
+}
+
+/// Starts an instance of a restartable supervised actor, failures are caught so
+/// [`Actor::pre_restart`] can be invoked before the failure is reported to the supervisor
+pub(crate) fn start_restartable_actor<A: Actor>(
+    actor: A,
+    id: ActorId,
+    incarnation: Incarnation,
+    on_start: Option<tokio::sync::oneshot::Sender<()>>,
+    system: ActorSystem,
+    parent_ref: BoxedActorRef,
+    path: ActorPath,
+) -> LocalActorRef<A> {
+    let (tx, rx) = mailbox(MailboxConfig::unbounded(), None);
+    let actor_ref = LocalActorRef::from_mailbox(id, tx, path);
     let cloned_ref = actor_ref.clone();
+    let running = system.actor_running();
 
-    tokio::spawn(async move {
+    rt::spawn(async move {
         ActorLoop::run(
-            actor, actor_type, rx, on_start, cloned_ref, parent_ref, system,
+            actor,
+            ActorType::Anonymous,
+            rx,
+            on_start,
+            cloned_ref,
+            Some(parent_ref),
+            Some(system),
+            Some(incarnation),
         )
         .await;
+
+        drop(running);
     });
 
     actor_ref
@@ -0,0 +1,176 @@
+//! Timers, delivering a message to an actor on a fixed interval.
+//!
+//! Every timer is driven by a single [hashed timing wheel][wheel], rather than a task per timer,
+//! allowing tens of thousands of timers (such as entity receive-timeouts) to be active at once.
+//!
+//! The wheel is shared by every actor system in the process, and is driven from a dedicated
+//! thread with its own runtime, rather than the runtime the timer was started from. This means:
+//!
+//! - Pausing or advancing time on the caller's runtime (via [`tokio::time::pause`] or
+//!   `#[tokio::test(start_paused = true)]`) has no effect on timers, ticks are driven by
+//!   real time.
+//! - Ticks are rounded up to the wheel's [resolution][wheel::WHEEL_RESOLUTION] (10ms), a timer
+//!   fires no earlier than its interval, and at most one resolution later.
+use crate::actor::message::{Handler, Message};
+use crate::actor::scheduler::timer::wheel::{
+    run_wheel, TickResult, TimerCommand, TimerEntry, TimerId, TimerTarget, WheelCounters,
+};
+use crate::actor::{Actor, LocalActorRef};
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+pub mod wheel;
+
+pub use wheel::TimerStats;
+
+pub trait TimerTick: Message {}
+
+enum TimerMode {
+    #[allow(unused)]
+    Notify,
+    Send,
+}
+
+pub struct Timer {
+    id: TimerId,
+}
+
+impl Timer {
+    pub fn start_immediately<A, T>(actor: LocalActorRef<A>, tick: Duration, msg: T) -> Timer
+    where
+        A: Actor + 'static + Handler<T> + Sync + Send,
+        T: TimerTick + 'static + Clone + Sync + Send,
+        T::Result: 'static + Sync + Send,
+    {
+        start_timer(actor, tick, msg, true, TimerMode::Send)
+    }
+
+    pub fn start<A, T>(actor: LocalActorRef<A>, tick: Duration, msg: T) -> Timer
+    where
+        A: Actor + 'static + Handler<T> + Sync + Send,
+        T: TimerTick + 'static + Clone + Sync + Send,
+        T::Result: 'static + Sync + Send,
+    {
+        start_timer(actor, tick, msg, false, TimerMode::Send)
+    }
+
+    pub fn stop(self) -> bool {
+        wheel_driver()
+            .commands
+            .send(TimerCommand::Stop(self.id))
+            .is_ok()
+    }
+}
+
+/// Returns counters describing the activity of the timing wheel that drives every [`Timer`]
+pub fn timer_stats() -> TimerStats {
+    wheel_driver().counters.stats()
+}
+
+struct ActorTimer<A: Actor, T: TimerTick> {
+    actor: LocalActorRef<A>,
+    msg: T,
+    mode: TimerMode,
+}
+
+impl<A: Actor, T: TimerTick> TimerTarget for ActorTimer<A, T>
+where
+    A: Handler<T>,
+    T: Clone,
+{
+    fn tick(&self) -> TickResult {
+        match self.mode {
+            TimerMode::Notify => self.actor.notify(self.msg.clone()).map(|_| None),
+            TimerMode::Send => {
+                // the next tick isn't scheduled until this one has been processed
+                let (tx, rx) = oneshot::channel();
+                self.actor
+                    .deliver(self.msg.clone(), tx)
+                    .map(|_| Some(Box::pin(async move { rx.await.is_ok() }) as _))
+            }
+        }
+        .map_err(|_| ())
+    }
+}
+
+fn start_timer<A, T>(
+    actor: LocalActorRef<A>,
+    tick: Duration,
+    msg: T,
+    tick_immediately: bool,
+    mode: TimerMode,
+) -> Timer
+where
+    A: Actor + Handler<T>,
+    T: TimerTick + Clone,
+{
+    let driver = wheel_driver();
+    let id = driver.next_timer_id.fetch_add(1, Ordering::Relaxed);
+
+    trace!(
+        timer_id = id,
+        actor_id = actor.actor_id().as_ref(),
+        "timer starting"
+    );
+
+    let entry = TimerEntry {
+        target: Box::new(ActorTimer { actor, msg, mode }),
+        interval: tick,
+    };
+
+    let _ = driver.commands.send(TimerCommand::Start {
+        id,
+        entry,
+        tick_immediately,
+    });
+
+    Timer { id }
+}
+
+struct WheelDriver {
+    commands: mpsc::UnboundedSender<TimerCommand>,
+    next_timer_id: AtomicU64,
+    counters: &'static WheelCounters,
+}
+
+lazy_static! {
+    static ref WHEEL_COUNTERS: WheelCounters = WheelCounters::default();
+    static ref WHEEL_DRIVER: WheelDriver = WheelDriver::start();
+}
+
+fn wheel_driver() -> &'static WheelDriver {
+    &WHEEL_DRIVER
+}
+
+impl WheelDriver {
+    fn start() -> Self {
+        let (commands, rx) = mpsc::unbounded_channel();
+        let counters: &'static WheelCounters = &WHEEL_COUNTERS;
+
+        // the wheel outlives any individual runtime, so is driven from its own thread
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::Builder::new()
+            .name("coerce-timer-wheel".to_string())
+            .spawn(move || {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_time()
+                    .build()
+                    .expect("timer wheel runtime");
+
+                runtime.block_on(run_wheel(rx, counters));
+            })
+            .expect("start timer wheel thread");
+
+        // there's only ever the browser's event loop, and no threads to drive it from
+        #[cfg(target_arch = "wasm32")]
+        crate::actor::rt::spawn(run_wheel(rx, counters));
+
+        Self {
+            commands,
+            next_timer_id: AtomicU64::new(0),
+            counters,
+        }
+    }
+}
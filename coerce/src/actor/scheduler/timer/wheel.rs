@@ -0,0 +1,248 @@
+//! Hashed timing wheel, driving every [`Timer`][super::Timer] from a single task.
+//!
+//! Timers are placed into one of [`WHEEL_SLOTS`] slots, based on when they are next due. The
+//! driver advances one slot every [`WHEEL_RESOLUTION`], firing any timers in the slot that are
+//! due within the current rotation of the wheel. The driver only wakes while timers are active,
+//! and the number of wakeups doesn't grow with the number of timers.
+
+use crate::actor::rt::Interval;
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// How often the wheel advances, timers fire no earlier than their interval,
+/// and at most one resolution later
+pub const WHEEL_RESOLUTION: Duration = Duration::from_millis(10);
+
+/// Number of slots in the wheel, timers due further than one rotation away are kept
+/// in their slot for the number of remaining rotations
+pub const WHEEL_SLOTS: usize = 512;
+
+pub(crate) type TimerId = u64;
+
+/// The result of a tick, if the timer waits for the tick to be processed before scheduling
+/// the next one, the future resolves once processed, with `false` if the target has stopped.
+pub(crate) type TickResult = Result<Option<BoxFuture<'static, bool>>, ()>;
+
+pub(crate) trait TimerTarget: 'static + Send + Sync {
+    fn tick(&self) -> TickResult;
+}
+
+pub(crate) struct TimerEntry {
+    pub target: Box<dyn TimerTarget>,
+    pub interval: Duration,
+}
+
+pub(crate) enum TimerCommand {
+    Start {
+        id: TimerId,
+        entry: TimerEntry,
+        tick_immediately: bool,
+    },
+    Stop(TimerId),
+}
+
+/// Counters describing the activity of the timing wheel, see [`timer_stats`][super::timer_stats]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct TimerStats {
+    /// Number of timers currently scheduled
+    pub active_timers: u64,
+
+    /// Number of times the wheel driver has woken up to advance the wheel
+    pub wheel_wakeups: u64,
+
+    /// Number of ticks delivered to actors
+    pub ticks_delivered: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct WheelCounters {
+    pub active_timers: AtomicU64,
+    pub wheel_wakeups: AtomicU64,
+    pub ticks_delivered: AtomicU64,
+}
+
+impl WheelCounters {
+    pub fn stats(&self) -> TimerStats {
+        TimerStats {
+            active_timers: self.active_timers.load(Ordering::Relaxed),
+            wheel_wakeups: self.wheel_wakeups.load(Ordering::Relaxed),
+            ticks_delivered: self.ticks_delivered.load(Ordering::Relaxed),
+        }
+    }
+}
+
+struct Scheduled {
+    id: TimerId,
+    rotations: u64,
+}
+
+struct TimingWheel {
+    slots: Vec<Vec<Scheduled>>,
+    cursor: usize,
+    timers: HashMap<TimerId, TimerEntry>,
+    in_flight: FuturesUnordered<BoxFuture<'static, (TimerId, bool)>>,
+    counters: &'static WheelCounters,
+}
+
+impl TimingWheel {
+    fn new(counters: &'static WheelCounters) -> Self {
+        Self {
+            slots: (0..WHEEL_SLOTS).map(|_| vec![]).collect(),
+            cursor: 0,
+            timers: HashMap::new(),
+            in_flight: FuturesUnordered::new(),
+            counters,
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    fn schedule(&mut self, id: TimerId, delay: Duration) {
+        // one extra slot, as the current slot has already been partially elapsed
+        let ticks = (delay.as_nanos() / WHEEL_RESOLUTION.as_nanos()).max(1) as u64 + 1;
+        let slot = (self.cursor as u64 + ticks) % WHEEL_SLOTS as u64;
+        let rotations = (ticks - 1) / WHEEL_SLOTS as u64;
+
+        self.slots[slot as usize].push(Scheduled { id, rotations });
+    }
+
+    fn start(&mut self, id: TimerId, entry: TimerEntry, tick_immediately: bool) {
+        let interval = entry.interval;
+        self.timers.insert(id, entry);
+        self.counters.active_timers.fetch_add(1, Ordering::Relaxed);
+
+        if tick_immediately {
+            self.fire(id);
+        } else {
+            self.schedule(id, interval);
+        }
+    }
+
+    fn stop(&mut self, id: TimerId) {
+        // any scheduled or in-flight ticks are discarded once they find the timer has gone
+        if self.timers.remove(&id).is_some() {
+            self.counters.active_timers.fetch_sub(1, Ordering::Relaxed);
+        }
+
+        if self.timers.is_empty() {
+            self.slots.iter_mut().for_each(|slot| slot.clear());
+            self.in_flight = FuturesUnordered::new();
+        }
+    }
+
+    fn advance(&mut self) {
+        self.cursor = (self.cursor + 1) % WHEEL_SLOTS;
+
+        let slot = std::mem::take(&mut self.slots[self.cursor]);
+        let mut remaining = vec![];
+
+        for mut scheduled in slot {
+            if !self.timers.contains_key(&scheduled.id) {
+                continue;
+            }
+
+            if scheduled.rotations > 0 {
+                scheduled.rotations -= 1;
+                remaining.push(scheduled);
+            } else {
+                self.fire(scheduled.id);
+            }
+        }
+
+        self.slots[self.cursor].append(&mut remaining);
+    }
+
+    fn fire(&mut self, id: TimerId) {
+        let entry = match self.timers.get(&id) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        match entry.target.tick() {
+            Ok(None) => {
+                self.counters
+                    .ticks_delivered
+                    .fetch_add(1, Ordering::Relaxed);
+                let interval = entry.interval;
+                self.schedule(id, interval);
+            }
+            Ok(Some(processed)) => {
+                self.counters
+                    .ticks_delivered
+                    .fetch_add(1, Ordering::Relaxed);
+                self.in_flight
+                    .push(Box::pin(async move { (id, processed.await) }));
+            }
+            Err(_) => {
+                trace!(timer_id = id, "timer target stopped, removing timer");
+                self.stop(id);
+            }
+        }
+    }
+
+    fn tick_processed(&mut self, id: TimerId, target_running: bool) {
+        if !target_running {
+            self.stop(id);
+            return;
+        }
+
+        if let Some(entry) = self.timers.get(&id) {
+            let interval = entry.interval;
+            self.schedule(id, interval);
+        }
+    }
+}
+
+pub(crate) async fn run_wheel(
+    mut commands: mpsc::UnboundedReceiver<TimerCommand>,
+    counters: &'static WheelCounters,
+) {
+    let mut wheel = TimingWheel::new(counters);
+    let mut interval = Interval::new(WHEEL_RESOLUTION);
+
+    loop {
+        if wheel.is_idle() {
+            // nothing to drive, sleep until a timer is started
+            match commands.recv().await {
+                Some(TimerCommand::Start {
+                    id,
+                    entry,
+                    tick_immediately,
+                }) => {
+                    interval = Interval::new(WHEEL_RESOLUTION);
+                    wheel.start(id, entry, tick_immediately);
+                }
+                Some(TimerCommand::Stop(id)) => wheel.stop(id),
+                None => break,
+            }
+
+            continue;
+        }
+
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(TimerCommand::Start { id, entry, tick_immediately }) => {
+                    wheel.start(id, entry, tick_immediately)
+                }
+                Some(TimerCommand::Stop(id)) => wheel.stop(id),
+                None => break,
+            },
+
+            Some((id, target_running)) = wheel.in_flight.next(), if !wheel.in_flight.is_empty() => {
+                wheel.tick_processed(id, target_running);
+            }
+
+            _ = interval.tick() => {
+                counters.wheel_wakeups.fetch_add(1, Ordering::Relaxed);
+                wheel.advance();
+            }
+        }
+    }
+}
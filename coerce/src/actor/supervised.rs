@@ -1,10 +1,14 @@
 //! Actor supervision and child spawning
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
 
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
-use crate::actor::scheduler::{start_actor, ActorType};
+use crate::actor::metrics::ActorMetrics;
+use crate::actor::rt::Instant;
+use crate::actor::scheduler::{start_actor, start_restartable_actor, ActorType};
 use crate::actor::system::ActorSystem;
 use crate::actor::{
     Actor, ActorId, ActorPath, ActorRefErr, BoxedActorRef, CoreActorRef, LocalActorRef,
@@ -15,6 +19,7 @@ pub struct Supervised {
     pub actor_id: ActorId,
     pub path: ActorPath,
     pub children: HashMap<ActorId, ChildRef>,
+    restartable: HashMap<ActorId, RestartableChild>,
 }
 
 impl Supervised {
@@ -23,10 +28,131 @@ impl Supervised {
             actor_id,
             path,
             children: HashMap::new(),
+            restartable: HashMap::new(),
         }
     }
 }
 
+/// Defines how a supervised actor is restarted after it has failed, and what constitutes a
+/// restart storm (the actor is crash-looping).
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    /// Maximum number of restarts allowed within the `within` window before a
+    /// [`RestartStorm`] is raised
+    pub max_restarts: usize,
+
+    /// The window in which restarts are counted
+    pub within: Duration,
+
+    /// When true, the actor will be stopped permanently once a [`RestartStorm`] is detected,
+    /// otherwise the actor continues to be restarted
+    pub stop_on_storm: bool,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 10,
+            within: Duration::from_secs(60),
+            stop_on_storm: true,
+        }
+    }
+}
+
+/// Restart statistics of a single supervised actor
+#[derive(Debug, Clone, Default)]
+pub struct RestartStats {
+    total_restarts: u64,
+    last_restart: Option<Instant>,
+    recent_restarts: VecDeque<Instant>,
+}
+
+impl RestartStats {
+    pub fn total_restarts(&self) -> u64 {
+        self.total_restarts
+    }
+
+    pub fn last_restart(&self) -> Option<Instant> {
+        self.last_restart
+    }
+
+    /// Records a restart, returning the number of restarts within the provided window
+    fn record(&mut self, now: Instant, within: Duration) -> usize {
+        self.total_restarts += 1;
+        self.last_restart = Some(now);
+        self.recent_restarts.push_back(now);
+
+        while let Some(restart) = self.recent_restarts.front() {
+            if now.duration_since(*restart) > within {
+                self.recent_restarts.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        self.recent_restarts.len()
+    }
+}
+
+/// Raised when a supervised actor has been restarted more than [`RestartPolicy::max_restarts`]
+/// times within the [`RestartPolicy::within`] window.
+#[derive(Debug, Clone)]
+pub struct RestartStorm {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    pub restarts: usize,
+    pub within: Duration,
+
+    /// Whether the actor was stopped permanently, as defined by [`RestartPolicy::stop_on_storm`]
+    pub stopped: bool,
+}
+
+/// Describes why a restartable supervised actor failed, passed to [`Actor::pre_restart`]
+#[derive(Debug, Clone)]
+pub enum RestartReason {
+    /// The actor panicked in [`Actor::started`]
+    StartFailed { panic_message: Option<String> },
+
+    /// The actor panicked while handling a message
+    Panicked {
+        message_type: &'static str,
+        panic_message: Option<String>,
+    },
+}
+
+/// Whether an instance of a restartable actor is the first, or was created by a restart
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Incarnation {
+    Initial,
+    Restarted,
+}
+
+type ChildFactory =
+    Box<dyn Fn(ActorSystem, BoxedActorRef, ActorPath) -> BoxedActorRef + Send + Sync>;
+
+struct RestartableChild {
+    actor_type: &'static str,
+    factory: ChildFactory,
+    policy: RestartPolicy,
+    stats: RestartStats,
+}
+
+impl Debug for RestartableChild {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RestartableChild")
+            .field("actor_type", &self.actor_type)
+            .field("policy", &self.policy)
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+pub(crate) enum ChildFailure {
+    Stopped,
+    Restarted,
+    RestartStorm(RestartStorm),
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum ChildType {
     Spawned,
@@ -56,6 +182,40 @@ impl<A: Actor> Handler<Terminated> for A {
     }
 }
 
+/// Sent to a supervisor when a supervised actor has stopped unexpectedly, either by panicking
+/// or by failing to start.
+pub struct ChildFailed(pub ActorId);
+
+impl Message for ChildFailed {
+    type Result = ();
+}
+
+#[async_trait]
+impl<A: Actor> Handler<ChildFailed> for A {
+    async fn handle(&mut self, message: ChildFailed, ctx: &mut ActorContext) {
+        let system = ctx.system().clone();
+        let parent_ref = ctx.boxed_actor_ref();
+        let failure = ctx
+            .supervised_mut()
+            .map_or(ChildFailure::Stopped, |supervised| {
+                supervised.on_child_failed(&message.0, system, parent_ref)
+            });
+
+        match failure {
+            ChildFailure::Stopped => self.on_child_stopped(&message.0, ctx).await,
+            ChildFailure::Restarted => {}
+            ChildFailure::RestartStorm(storm) => {
+                let stopped = storm.stopped;
+                self.on_restart_storm(&storm, ctx).await;
+
+                if stopped {
+                    self.on_child_stopped(&message.0, ctx).await;
+                }
+            }
+        }
+    }
+}
+
 impl Supervised {
     pub async fn spawn<A: Actor>(
         &mut self,
@@ -118,6 +278,145 @@ impl Supervised {
         Ok(actor_ref)
     }
 
+    /// Spawns a supervised actor which is re-created, using the provided factory, each time the
+    /// actor fails, until a [`RestartStorm`] is detected and the [`RestartPolicy`] dictates
+    /// the actor should be stopped permanently.
+    ///
+    /// Note: each restart creates a new mailbox, references to the previous instance of the actor
+    ///       will no longer be valid.
+    pub async fn spawn_restartable<A: Actor, F>(
+        &mut self,
+        id: ActorId,
+        factory: F,
+        policy: RestartPolicy,
+        system: ActorSystem,
+        parent_ref: BoxedActorRef,
+    ) -> Result<LocalActorRef<A>, ActorRefErr>
+    where
+        F: 'static + Fn() -> A + Send + Sync,
+    {
+        if self.children.contains_key(&id) {
+            return Err(ActorRefErr::AlreadyExists(id));
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let actor_ref = start_restartable_actor(
+            factory(),
+            id.clone(),
+            Incarnation::Initial,
+            Some(tx),
+            system,
+            parent_ref,
+            self.path.clone(),
+        );
+
+        self.children
+            .insert(id.clone(), ChildRef::spawned(actor_ref.clone().into()));
+
+        if let Err(e) = rx.await {
+            error!("error spawning supervised actor (id={}) {}", &id, e);
+            return Err(ActorRefErr::ActorStartFailed);
+        }
+
+        let child_id = id.clone();
+
+        self.restartable.insert(
+            id,
+            RestartableChild {
+                actor_type: A::type_name(),
+                factory: Box::new(move |system, parent_ref, path| {
+                    start_restartable_actor(
+                        factory(),
+                        child_id.clone(),
+                        Incarnation::Restarted,
+                        None,
+                        system,
+                        parent_ref,
+                        path,
+                    )
+                    .into()
+                }),
+                policy,
+                stats: RestartStats::default(),
+            },
+        );
+
+        Ok(actor_ref)
+    }
+
+    pub fn restart_stats(&self, id: &ActorId) -> Option<&RestartStats> {
+        self.restartable.get(id).map(|r| &r.stats)
+    }
+
+    pub(crate) fn on_child_failed(
+        &mut self,
+        id: &ActorId,
+        system: ActorSystem,
+        parent_ref: BoxedActorRef,
+    ) -> ChildFailure {
+        let restartable = match self.restartable.get_mut(id) {
+            Some(restartable) if !system.is_terminated() => restartable,
+            _ => {
+                self.children.remove(id);
+                self.restartable.remove(id);
+                return ChildFailure::Stopped;
+            }
+        };
+
+        let policy = &restartable.policy;
+        let restarts = restartable.stats.record(Instant::now(), policy.within);
+        let storm = if restarts > policy.max_restarts {
+            Some(RestartStorm {
+                actor_id: id.clone(),
+                actor_type: restartable.actor_type,
+                restarts,
+                within: policy.within,
+                stopped: policy.stop_on_storm,
+            })
+        } else {
+            None
+        };
+
+        if let Some(storm) = storm {
+            warn!(
+                actor_id = id.as_ref(),
+                actor_type = storm.actor_type,
+                restarts = storm.restarts,
+                stopped = storm.stopped,
+                "restart storm detected"
+            );
+
+            if storm.stopped {
+                self.children.remove(id);
+                self.restartable.remove(id);
+                return ChildFailure::RestartStorm(storm);
+            }
+
+            self.restart_child(id, system, parent_ref);
+            ChildFailure::RestartStorm(storm)
+        } else {
+            self.restart_child(id, system, parent_ref);
+            ChildFailure::Restarted
+        }
+    }
+
+    fn restart_child(&mut self, id: &ActorId, system: ActorSystem, parent_ref: BoxedActorRef) {
+        if let Some(restartable) = self.restartable.get(id) {
+            debug!(
+                actor_id = id.as_ref(),
+                actor_type = restartable.actor_type,
+                total_restarts = restartable.stats.total_restarts(),
+                "restarting failed child actor"
+            );
+
+            ActorMetrics::incr_actor_restarted(restartable.actor_type);
+
+            let actor_ref = (restartable.factory)(system, parent_ref, self.path.clone());
+            self.children
+                .insert(id.clone(), ChildRef::spawned(actor_ref));
+        }
+    }
+
     pub fn count(&self) -> usize {
         self.children.len()
     }
@@ -144,7 +443,7 @@ impl Supervised {
         let stop_results = futures::future::join_all(
             self.children
                 .iter()
-                .map(|(id, actor)| async move { (id.clone(), actor.actor_ref.stop().await) }),
+                .map(|(id, actor)| async move { (id.clone(), actor.actor_ref.stop(false).await) }),
         )
         .await;
 
@@ -155,19 +454,29 @@ impl Supervised {
                     self.children.remove(&actor_id);
                 }
                 Err(e) => match e {
-                    ActorRefErr::InvalidRef => {},
+                    ActorRefErr::InvalidRef => {}
                     e => {
-                        warn!(actor_id = actor_id.as_ref(), error = format!("{}", e), "failed to stop child");
+                        warn!(
+                            actor_id = actor_id.as_ref(),
+                            error = format!("{}", e),
+                            "failed to stop child"
+                        );
                     }
                 },
             }
         }
-        
+
         let n = self.children.len();
-        trace!(actor_id = self.actor_id.as_ref(), total_children = n, "all child actors stopped");
+        trace!(
+            actor_id = self.actor_id.as_ref(),
+            total_children = n,
+            "all child actors stopped"
+        );
     }
 
     pub async fn on_child_stopped(&mut self, id: &ActorId) {
+        self.restartable.remove(id);
+
         if let Some(_) = self.children.remove(id) {
             trace!(actor_id = id.as_ref(), "child actor stopped");
         } else {
@@ -0,0 +1,171 @@
+//! All-or-nothing dispatch of messages to multiple actors
+//!
+//! [`ActorSystem::send_all_or_nothing`] enqueues a batch of messages, each to its own actor, only
+//! if every target mailbox can accept its message. Room is reserved in every mailbox before any
+//! message is enqueued, so a full bounded mailbox, or a stopped actor, results in none of the
+//! messages being delivered, rather than a half-applied fan-out.
+//!
+//! Messages are enqueued in the order they appear in the batch.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! system.send_all_or_nothing(vec![
+//!     BatchMessage::new(&account_a, Withdraw(100)),
+//!     BatchMessage::new(&account_b, Deposit(100)),
+//! ])?;
+//! ```
+
+use crate::actor::message::{ActorMessage, Handler, Message};
+use crate::actor::metrics::ActorMetrics;
+use crate::actor::system::ActorSystem;
+use crate::actor::{Actor, ActorId, ActorRefErr, LocalActorRef};
+use std::fmt::{Display, Formatter};
+
+/// A message to be dispatched to an actor as part of a batch
+pub struct BatchMessage {
+    actor_id: ActorId,
+    entry: Box<dyn BatchEntry>,
+}
+
+impl BatchMessage {
+    pub fn new<A, M: Message>(actor_ref: &LocalActorRef<A>, msg: M) -> Self
+    where
+        A: Actor + Handler<M>,
+    {
+        Self {
+            actor_id: actor_ref.actor_id().clone(),
+            entry: Box::new(Entry {
+                actor_ref: actor_ref.clone(),
+                msg,
+            }),
+        }
+    }
+
+    pub fn actor_id(&self) -> &ActorId {
+        &self.actor_id
+    }
+}
+
+impl<A: Actor, M: Message> From<(LocalActorRef<A>, M)> for BatchMessage
+where
+    A: Handler<M>,
+{
+    fn from((actor_ref, msg): (LocalActorRef<A>, M)) -> Self {
+        Self::new(&actor_ref, msg)
+    }
+}
+
+#[derive(Debug)]
+pub enum BatchDispatchErr {
+    /// The actor at `index` couldn't accept its message, none of the messages were enqueued
+    Rejected {
+        index: usize,
+        actor_id: ActorId,
+        error: ActorRefErr,
+    },
+
+    /// The actor at `index` was stopped after room had been reserved in its mailbox, the
+    /// messages before `index` were enqueued, the rest were not
+    Interrupted {
+        index: usize,
+        actor_id: ActorId,
+        error: ActorRefErr,
+    },
+}
+
+impl Display for BatchDispatchErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchDispatchErr::Rejected {
+                index,
+                actor_id,
+                error,
+            } => write!(
+                f,
+                "batch rejected, message {} (actor_id={}) could not be enqueued: {}",
+                index, actor_id, error
+            ),
+            BatchDispatchErr::Interrupted {
+                index,
+                actor_id,
+                error,
+            } => write!(
+                f,
+                "batch interrupted, message {} (actor_id={}) could not be enqueued: {}",
+                index, actor_id, error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BatchDispatchErr {}
+
+impl ActorSystem {
+    /// Enqueues every message in the batch, only if every target actor's mailbox accepts its
+    /// message, see [`batch`](crate::actor::system::batch)
+    pub fn send_all_or_nothing(&self, batch: Vec<BatchMessage>) -> Result<(), BatchDispatchErr> {
+        for (index, message) in batch.iter().enumerate() {
+            if let Err(error) = message.entry.reserve() {
+                for reserved in &batch[..index] {
+                    reserved.entry.cancel_reservation();
+                }
+
+                return Err(BatchDispatchErr::Rejected {
+                    index,
+                    actor_id: message.actor_id.clone(),
+                    error,
+                });
+            }
+        }
+
+        let mut batch = batch.into_iter().enumerate();
+        while let Some((index, message)) = batch.next() {
+            if let Err(error) = message.entry.commit() {
+                for (_, remaining) in batch {
+                    remaining.entry.cancel_reservation();
+                }
+
+                return Err(BatchDispatchErr::Interrupted {
+                    index,
+                    actor_id: message.actor_id,
+                    error,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+trait BatchEntry: 'static + Send + Sync {
+    fn reserve(&self) -> Result<(), ActorRefErr>;
+
+    fn cancel_reservation(&self);
+
+    fn commit(self: Box<Self>) -> Result<(), ActorRefErr>;
+}
+
+struct Entry<A: Actor, M: Message> {
+    actor_ref: LocalActorRef<A>,
+    msg: M,
+}
+
+impl<A: Actor, M: Message> BatchEntry for Entry<A, M>
+where
+    A: Handler<M>,
+{
+    fn reserve(&self) -> Result<(), ActorRefErr> {
+        self.actor_ref.reserve()
+    }
+
+    fn cancel_reservation(&self) {
+        self.actor_ref.cancel_reservation()
+    }
+
+    fn commit(self: Box<Self>) -> Result<(), ActorRefErr> {
+        ActorMetrics::incr_messages_sent(A::type_name(), self.msg.name());
+
+        self.actor_ref
+            .enqueue_reserved(Box::new(ActorMessage::new(self.msg, None)))
+    }
+}
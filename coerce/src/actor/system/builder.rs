@@ -1,7 +1,12 @@
+use crate::actor::dead_letter::DeadLetters;
+use crate::actor::hooks::{SystemHook, SystemHooks};
+use crate::actor::sampling::TraceSampling;
 use crate::actor::scheduler::ActorScheduler;
-use crate::actor::system::{ActorSystem, ActorSystemCore};
+use crate::actor::system::shutdown::{CoordinatedShutdown, DEFAULT_SHUTDOWN_PHASE_TIMEOUT};
+use crate::actor::system::{ActorSystem, ActorSystemCore, RunningActors};
 use std::sync::atomic::{AtomicBool, AtomicU64};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use uuid::Uuid;
 
 #[cfg(feature = "persistence")]
@@ -11,6 +16,9 @@ use crate::persistent::{provider::StorageProvider, Persistence};
 pub struct ActorSystemBuilder {
     system_id: Option<Uuid>,
     system_name: Option<String>,
+    hooks: SystemHooks,
+    shutdown_phase_timeout: Option<Duration>,
+    trace_sampling: TraceSampling,
 
     #[cfg(feature = "persistence")]
     persistence: Option<Arc<Persistence>>,
@@ -22,6 +30,29 @@ impl ActorSystemBuilder {
         self
     }
 
+    /// Registers a [`SystemHook`], invoked whenever an actor is spawned into or stopped within
+    /// the [`ActorSystem`]
+    pub fn add_hook(mut self, hook: impl SystemHook) -> Self {
+        self.hooks.add_hook(hook);
+        self
+    }
+
+    /// How long each phase of a [coordinated shutdown] is given to complete,
+    /// defaults to 10 seconds
+    ///
+    /// [coordinated shutdown]: crate::actor::system::shutdown
+    pub fn shutdown_phase_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_phase_timeout = Some(timeout);
+        self
+    }
+
+    /// Limits which handled messages are traced, defaults to tracing every message,
+    /// see [`TraceSampling`]
+    pub fn trace_sampling(mut self, sampling: TraceSampling) -> Self {
+        self.trace_sampling = sampling;
+        self
+    }
+
     #[cfg(feature = "persistence")]
     pub fn with_persistence<S: StorageProvider>(mut self, provider: S) -> Self {
         self.persistence = Some(Persistence::from(provider).into());
@@ -48,6 +79,14 @@ impl ActorSystemBuilder {
                 scheduler,
                 is_terminated: Arc::new(AtomicBool::new(false)),
                 context_counter: Arc::new(AtomicU64::new(1)),
+                hooks: Arc::new(self.hooks),
+                dead_letters: DeadLetters::default(),
+                running_actors: Arc::new(RunningActors::default()),
+                shutdown: Arc::new(CoordinatedShutdown::new(
+                    self.shutdown_phase_timeout
+                        .unwrap_or(DEFAULT_SHUTDOWN_PHASE_TIMEOUT),
+                )),
+                trace_sampling: Arc::new(RwLock::new(Arc::new(self.trace_sampling))),
 
                 #[cfg(feature = "persistence")]
                 persistence: self.persistence,
@@ -1,15 +1,24 @@
 //! Actor System
 //!
-use crate::actor::scheduler::{start_actor, ActorScheduler, ActorType, GetActor, RegisterActor};
+use crate::actor::mailbox::{MailboxConfig, MailboxSnapshot};
+use crate::actor::scheduler::{
+    start_actor, start_actor_with_mailbox, ActorScheduler, ActorType, GetActor, GetMailboxSnapshot,
+    RegisterActor,
+};
 use crate::actor::{
     new_actor_id, Actor, ActorId, ActorPath, ActorRefErr, BoxedActorRef, IntoActorId,
     LocalActorRef, ToActorId,
 };
 
+use crate::actor::dead_letter::DeadLetters;
+use crate::actor::hooks::SystemHooks;
+use crate::actor::sampling::TraceSampling;
 use crate::actor::system::builder::ActorSystemBuilder;
+use crate::actor::system::shutdown::CoordinatedShutdown;
 use std::sync::atomic::Ordering::Relaxed;
-use std::sync::atomic::{AtomicBool, AtomicU64};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
+use std::sync::{Arc, RwLock};
+use tokio::sync::Notify;
 use uuid::Uuid;
 
 #[cfg(feature = "remote")]
@@ -18,13 +27,47 @@ use crate::remote::system::RemoteActorSystem;
 #[cfg(feature = "persistence")]
 use crate::persistent::{journal::provider::StorageProvider, Persistence};
 
+pub mod batch;
 pub mod builder;
+pub mod shutdown;
 
 lazy_static! {
     pub static ref DEFAULT_ACTOR_PATH: ActorPath = String::default().into();
     static ref CURRENT_SYSTEM: ActorSystem = ActorSystem::new();
 }
 
+/// Options used when spawning an actor via [`ActorSystem::new_actor_with_opts`]
+#[derive(Debug, Clone, Copy)]
+pub struct ActorOptions {
+    pub actor_type: ActorType,
+    pub mailbox: MailboxConfig,
+}
+
+impl Default for ActorOptions {
+    fn default() -> Self {
+        Self {
+            actor_type: ActorType::Tracked,
+            mailbox: MailboxConfig::unbounded(),
+        }
+    }
+}
+
+impl ActorOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn actor_type(mut self, actor_type: ActorType) -> Self {
+        self.actor_type = actor_type;
+        self
+    }
+
+    pub fn mailbox(mut self, mailbox: MailboxConfig) -> Self {
+        self.mailbox = mailbox;
+        self
+    }
+}
+
 #[derive(Clone)]
 pub struct ActorSystem {
     core: Arc<ActorSystemCore>,
@@ -37,6 +80,11 @@ pub(crate) struct ActorSystemCore {
     scheduler: LocalActorRef<ActorScheduler>,
     is_terminated: Arc<AtomicBool>,
     context_counter: Arc<AtomicU64>,
+    hooks: Arc<SystemHooks>,
+    dead_letters: DeadLetters,
+    running_actors: Arc<RunningActors>,
+    shutdown: Arc<CoordinatedShutdown>,
+    trace_sampling: Arc<RwLock<Arc<TraceSampling>>>,
 
     #[cfg(feature = "persistence")]
     persistence: Option<Arc<Persistence>>,
@@ -77,6 +125,25 @@ impl ActorSystem {
         &self.core.scheduler
     }
 
+    pub fn hooks(&self) -> &SystemHooks {
+        &self.core.hooks
+    }
+
+    pub fn dead_letters(&self) -> &DeadLetters {
+        &self.core.dead_letters
+    }
+
+    /// The [`TraceSampling`] deciding which handled messages are traced
+    pub fn trace_sampling(&self) -> Arc<TraceSampling> {
+        self.core.trace_sampling.read().unwrap().clone()
+    }
+
+    /// Replaces the [`TraceSampling`], applying to every message handled from now on,
+    /// including by actors that are already running
+    pub fn set_trace_sampling(&self, sampling: TraceSampling) {
+        *self.core.trace_sampling.write().unwrap() = Arc::new(sampling);
+    }
+
     pub fn global_system() -> ActorSystem {
         CURRENT_SYSTEM.clone()
     }
@@ -141,13 +208,27 @@ impl ActorSystem {
         id: I,
         actor: A,
         actor_type: ActorType,
+    ) -> Result<LocalActorRef<A>, ActorRefErr> {
+        self.new_actor_with_opts(id, actor, ActorOptions::new().actor_type(actor_type))
+            .await
+    }
+
+    /// Spawns a new actor with the provided [`ActorOptions`], waiting for the actor to be started
+    #[instrument(skip(self, id, actor), level = "debug")]
+    pub async fn new_actor_with_opts<I: IntoActorId, A: Actor>(
+        &self,
+        id: I,
+        actor: A,
+        opts: ActorOptions,
     ) -> Result<LocalActorRef<A>, ActorRefErr> {
         let id = id.into_actor_id();
+        let actor_type = opts.actor_type;
         let (tx, rx) = tokio::sync::oneshot::channel();
-        let actor_ref = start_actor(
+        let actor_ref = start_actor_with_mailbox(
             actor,
             id.clone(),
             actor_type,
+            opts.mailbox,
             Some(tx),
             Some(self.clone()),
             None,
@@ -209,6 +290,33 @@ impl ActorSystem {
         }
     }
 
+    /// Returns the number of actors spawned into this system that have not yet stopped
+    pub fn running_actor_count(&self) -> usize {
+        self.core.running_actors.count.load(Relaxed)
+    }
+
+    /// Waits until every actor that was spawned into this system has stopped, resolving
+    /// immediately if there are no running actors.
+    ///
+    /// Useful in tests, after [`shutdown`][ActorSystem::shutdown] or after stopping a set of actors,
+    /// to wait for the stop procedure to fully complete.
+    pub async fn wait_for_idle(&self) {
+        let running_actors = &self.core.running_actors;
+        loop {
+            let idle = running_actors.idle.notified();
+            if running_actors.count.load(Relaxed) == 0 {
+                return;
+            }
+
+            idle.await;
+        }
+    }
+
+    pub(crate) fn actor_running(&self) -> RunningActorGuard {
+        self.core.running_actors.count.fetch_add(1, Relaxed);
+        RunningActorGuard(self.core.running_actors.clone())
+    }
+
     pub fn is_terminated(&self) -> bool {
         self.core.is_terminated.load(Relaxed)
     }
@@ -217,7 +325,7 @@ impl ActorSystem {
         info!("shutting down");
 
         self.core.is_terminated.store(true, Relaxed);
-        let _ = self.core.scheduler.stop().await;
+        let _ = self.core.scheduler.stop(false).await;
 
         #[cfg(feature = "remote")]
         if let Some(remote) = &self.core.remote {
@@ -241,6 +349,16 @@ impl ActorSystem {
             Err(_) => None,
         }
     }
+
+    /// Returns a snapshot of the messages queued in the mailbox of the tracked actor with the
+    /// provided ID, by message type, without consuming them
+    pub async fn mailbox_snapshot(&self, id: impl IntoActorId) -> Option<MailboxSnapshot> {
+        self.core
+            .scheduler
+            .send(GetMailboxSnapshot(id.into_actor_id()))
+            .await
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(feature = "remote")]
@@ -280,6 +398,23 @@ impl ActorSystem {
     }
 }
 
+#[derive(Default)]
+pub(crate) struct RunningActors {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+/// Tracks an actor as running until dropped
+pub(crate) struct RunningActorGuard(Arc<RunningActors>);
+
+impl Drop for RunningActorGuard {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Relaxed) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
 impl ActorSystemCore {
     #[cfg(feature = "remote")]
     pub fn new_remote(&self, remote: RemoteActorSystem) -> Self {
@@ -0,0 +1,258 @@
+//! Coordinated shutdown
+//!
+//! Shutdown tasks are registered with the [`ActorSystem`] against a [`ShutdownPhase`]. When a
+//! coordinated shutdown is triggered, either explicitly via [`ActorSystem::coordinated_shutdown`]
+//! or by a termination signal via [`ActorSystem::run_until_shutdown_signal`], the tasks of each
+//! phase are run concurrently, one phase after another, before the actor system itself is shut down.
+//!
+//! Each phase is given [`shutdown_phase_timeout`] to complete, so a stuck task can't prevent the
+//! process from exiting. Once complete, a [`ShutdownSummary`] describes what happened, which can
+//! be logged or used to decide the process exit code.
+//!
+//! When remoting is enabled, the system leaves the cluster during [`ShutdownPhase::LeaveCluster`],
+//! and when sharding is enabled, locally hosted shards are stopped during
+//! [`ShutdownPhase::ShardHandoff`], so they can be reallocated to the remaining nodes.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! system.add_shutdown_task(ShutdownPhase::Drain, "tcp-server", move || async move {
+//!     let _ = server.stop().await;
+//! });
+//!
+//! let summary = system.run_until_shutdown_signal().await;
+//! if !summary.is_clean() {
+//!     std::process::exit(1);
+//! }
+//! ```
+//!
+//! [`shutdown_phase_timeout`]: crate::actor::system::builder::ActorSystemBuilder::shutdown_phase_timeout
+
+use crate::actor::rt::{timeout, Instant};
+use crate::actor::system::ActorSystem;
+use futures::future::{join_all, BoxFuture};
+use futures::FutureExt;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const DEFAULT_SHUTDOWN_PHASE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Phases of a coordinated shutdown, run in the order they're declared
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ShutdownPhase {
+    /// Stop accepting new work, and wait for in-flight work to complete
+    Drain,
+
+    /// Stop locally hosted shards, so they can be reallocated to other nodes
+    ShardHandoff,
+
+    /// Leave the cluster, disconnecting from every other node
+    LeaveCluster,
+}
+
+impl ShutdownPhase {
+    pub const ALL: [ShutdownPhase; 3] = [
+        ShutdownPhase::Drain,
+        ShutdownPhase::ShardHandoff,
+        ShutdownPhase::LeaveCluster,
+    ];
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ShutdownSignal {
+    Terminate,
+    Interrupt,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ShutdownReason {
+    Signal(ShutdownSignal),
+    Requested,
+
+    /// The node was downed by the cluster's split brain resolver
+    Downed,
+}
+
+#[derive(Clone, Debug)]
+pub struct ShutdownTaskSummary {
+    pub phase: ShutdownPhase,
+    pub name: String,
+    pub duration: Duration,
+    pub timed_out: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct ShutdownSummary {
+    pub reason: ShutdownReason,
+    pub tasks: Vec<ShutdownTaskSummary>,
+    pub duration: Duration,
+
+    /// The number of actors that were still running once the shutdown completed
+    pub actors_remaining: usize,
+}
+
+impl ShutdownSummary {
+    /// Whether every task completed in time, and every actor was stopped
+    pub fn is_clean(&self) -> bool {
+        self.actors_remaining == 0 && self.tasks.iter().all(|t| !t.timed_out)
+    }
+}
+
+type ShutdownTaskFn = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+struct ShutdownTask {
+    phase: ShutdownPhase,
+    name: String,
+    task: ShutdownTaskFn,
+}
+
+pub(crate) struct CoordinatedShutdown {
+    tasks: Mutex<Vec<ShutdownTask>>,
+    phase_timeout: Duration,
+}
+
+impl CoordinatedShutdown {
+    pub fn new(phase_timeout: Duration) -> Self {
+        Self {
+            tasks: Mutex::new(vec![]),
+            phase_timeout,
+        }
+    }
+}
+
+impl ActorSystem {
+    /// Registers a task that will be run during the provided [`ShutdownPhase`] of a
+    /// coordinated shutdown
+    pub fn add_shutdown_task<F, Fut>(&self, phase: ShutdownPhase, name: impl ToString, task: F)
+    where
+        F: 'static + FnOnce() -> Fut + Send,
+        Fut: 'static + Future<Output = ()> + Send,
+    {
+        self.core.shutdown.tasks.lock().unwrap().push(ShutdownTask {
+            phase,
+            name: name.to_string(),
+            task: Box::new(move || task().boxed()),
+        });
+    }
+
+    /// Runs every registered shutdown task, phase by phase, and then shuts down the actor system.
+    ///
+    /// Tasks are only run once, if a coordinated shutdown has already been run, only the actor
+    /// system is shut down.
+    pub async fn coordinated_shutdown(&self, reason: ShutdownReason) -> ShutdownSummary {
+        let start = Instant::now();
+        let phase_timeout = self.core.shutdown.phase_timeout;
+        let mut tasks = std::mem::take(&mut *self.core.shutdown.tasks.lock().unwrap());
+
+        info!(
+            reason = format!("{:?}", reason),
+            "coordinated shutdown started"
+        );
+
+        let mut summaries = vec![];
+        for phase in ShutdownPhase::ALL {
+            let (phase_tasks, remaining) = tasks.into_iter().partition(|t| t.phase == phase);
+            tasks = remaining;
+
+            summaries.extend(run_phase(phase, phase_tasks, phase_timeout).await);
+        }
+
+        self.shutdown().await;
+
+        if timeout(phase_timeout, self.wait_for_idle()).await.is_err() {
+            warn!(
+                actors_remaining = self.running_actor_count(),
+                "timed out waiting for actors to stop"
+            );
+        }
+
+        let summary = ShutdownSummary {
+            reason,
+            tasks: summaries,
+            duration: start.elapsed(),
+            actors_remaining: self.running_actor_count(),
+        };
+
+        info!("coordinated shutdown complete, {}", &summary);
+        summary
+    }
+
+    /// Waits for a termination signal (`SIGTERM` or `SIGINT`), and then runs a
+    /// [coordinated shutdown](ActorSystem::coordinated_shutdown), resolving once it has completed
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn run_until_shutdown_signal(&self) -> ShutdownSummary {
+        let signal = wait_for_signal().await;
+        info!("received {:?} signal, shutting down", signal);
+
+        self.coordinated_shutdown(ShutdownReason::Signal(signal))
+            .await
+    }
+}
+
+async fn run_phase(
+    phase: ShutdownPhase,
+    tasks: Vec<ShutdownTask>,
+    phase_timeout: Duration,
+) -> Vec<ShutdownTaskSummary> {
+    if tasks.is_empty() {
+        return vec![];
+    }
+
+    debug!("running shutdown phase {:?} ({} tasks)", phase, tasks.len());
+
+    // every task in the phase shares the same deadline
+    let deadline = Instant::now() + phase_timeout;
+    join_all(tasks.into_iter().map(|t| async move {
+        let start = Instant::now();
+        let remaining = deadline.saturating_duration_since(start);
+        let timed_out = timeout(remaining, (t.task)()).await.is_err();
+        if timed_out {
+            warn!("shutdown task {} timed out in phase {:?}", &t.name, phase);
+        }
+
+        ShutdownTaskSummary {
+            phase,
+            name: t.name,
+            duration: start.elapsed(),
+            timed_out,
+        }
+    }))
+    .await
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> ShutdownSignal {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate()).expect("install SIGTERM handler");
+    let mut interrupt = signal(SignalKind::interrupt()).expect("install SIGINT handler");
+
+    tokio::select! {
+        _ = terminate.recv() => ShutdownSignal::Terminate,
+        _ = interrupt.recv() => ShutdownSignal::Interrupt,
+    }
+}
+
+#[cfg(all(not(unix), not(target_arch = "wasm32")))]
+async fn wait_for_signal() -> ShutdownSignal {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("install ctrl-c handler");
+    ShutdownSignal::Interrupt
+}
+
+impl Display for ShutdownSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let timed_out = self.tasks.iter().filter(|t| t.timed_out).count();
+        write!(
+            f,
+            "reason={:?}, duration={:?}, tasks={}, timed_out={}, actors_remaining={}",
+            self.reason,
+            self.duration,
+            self.tasks.len(),
+            timed_out,
+            self.actors_remaining
+        )
+    }
+}
@@ -1,10 +1,40 @@
 //! Actor watching allows one actor to watch another, ensuring that when the subject stops, the
 //! the watching actor will be notified immediately.
 //!
+//! An actor starts watching another via [`ActorContext::watch`], and is then sent an
+//! [`ActorTerminated`] message once the watched actor has stopped, which requires the watching
+//! actor to implement [`Handler<ActorTerminated>`].
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! #[async_trait]
+//! impl Handler<Subscribe> for Router {
+//!     async fn handle(&mut self, message: Subscribe, ctx: &mut ActorContext) {
+//!         ctx.watch::<Self>(&message.0);
+//!         self.routees.insert(message.0.actor_id().clone(), message.0);
+//!     }
+//! }
+//!
+//! #[async_trait]
+//! impl Handler<ActorTerminated> for Router {
+//!     async fn handle(&mut self, message: ActorTerminated, _ctx: &mut ActorContext) {
+//!         self.routees.remove(message.actor_id());
+//!     }
+//! }
+//! ```
+//!
+//! Any [`Watchable`] reference can be watched, including an [`ActorRef`] that points to an actor
+//! on another node, in which case the watch is registered with the remote node, and
+//! [`ActorTerminated`] is also sent if the remote node is terminated, or removed from the cluster.
+//!
+//! [`ActorRef`]: crate::actor::ActorRef
 
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
-use crate::actor::{Actor, ActorId, BoxedActorRef, LocalActorRef, Receiver};
+use crate::actor::{Actor, ActorId, BoxedActorRef, CoreActorRef, LocalActorRef, Receiver};
+
+#[cfg(feature = "remote")]
+use crate::remote::system::NodeId;
 
 pub mod watchers;
 
@@ -20,12 +50,42 @@ where
     A: Handler<ActorTerminated>,
 {
     fn watch<W: Actor>(&self, actor: &LocalActorRef<W>, ctx: &ActorContext) {
-        let terminated_receiver = Receiver::<ActorTerminated>::from(self.actor_ref(ctx));
-        let _ = actor.notify(Watch::from(terminated_receiver));
+        ctx.watch::<A>(actor);
     }
 
     fn unwatch<W: Actor>(&self, actor: &LocalActorRef<W>, ctx: &ActorContext) {
-        let _ = actor.notify(Unwatch::from(ctx.id().clone()));
+        ctx.unwatch(actor);
+    }
+}
+
+/// A reference to an actor that can be watched, see [`ActorContext::watch`]
+pub trait Watchable {
+    /// Registers `watcher` to be sent an [`ActorTerminated`] message once the actor has stopped,
+    /// or immediately, if it has already stopped
+    fn add_watcher(&self, watcher: Receiver<ActorTerminated>);
+
+    fn remove_watcher(&self, watcher_id: ActorId);
+}
+
+impl<A: Actor> Watchable for LocalActorRef<A> {
+    fn add_watcher(&self, watcher: Receiver<ActorTerminated>) {
+        BoxedActorRef::from(self.clone()).add_watcher(watcher);
+    }
+
+    fn remove_watcher(&self, watcher_id: ActorId) {
+        let _ = self.notify(Unwatch::from(watcher_id));
+    }
+}
+
+impl Watchable for BoxedActorRef {
+    fn add_watcher(&self, watcher: Receiver<ActorTerminated>) {
+        if self.notify_watch(watcher.clone()).is_err() {
+            let _ = watcher.notify(ActorTerminated::from(self.clone()));
+        }
+    }
+
+    fn remove_watcher(&self, watcher_id: ActorId) {
+        let _ = self.notify_unwatch(watcher_id);
     }
 }
 
@@ -73,18 +133,53 @@ impl Message for Unwatch {
 
 #[derive(Clone)]
 pub struct ActorTerminated {
-    actor_ref: BoxedActorRef,
+    actor_id: ActorId,
+    actor_ref: Option<BoxedActorRef>,
+    reason: TerminationReason,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TerminationReason {
+    /// The actor was stopped
+    Stopped,
+
+    /// The node hosting the actor was terminated, or removed from the cluster, so the actor
+    /// is presumed to have stopped
+    #[cfg(feature = "remote")]
+    NodeLost(NodeId),
 }
 
 impl ActorTerminated {
-    pub fn actor_ref(&self) -> &BoxedActorRef {
-        &self.actor_ref
+    #[cfg(feature = "remote")]
+    pub(crate) fn remote(actor_id: ActorId, reason: TerminationReason) -> Self {
+        Self {
+            actor_id,
+            actor_ref: None,
+            reason,
+        }
+    }
+
+    pub fn actor_id(&self) -> &ActorId {
+        &self.actor_id
+    }
+
+    /// The reference to the terminated actor, only available when the actor was local
+    pub fn actor_ref(&self) -> Option<&BoxedActorRef> {
+        self.actor_ref.as_ref()
+    }
+
+    pub fn reason(&self) -> TerminationReason {
+        self.reason
     }
 }
 
 impl From<BoxedActorRef> for ActorTerminated {
     fn from(value: BoxedActorRef) -> Self {
-        Self { actor_ref: value }
+        Self {
+            actor_id: value.actor_id().clone(),
+            actor_ref: Some(value),
+            reason: TerminationReason::Stopped,
+        }
     }
 }
 
@@ -19,4 +19,8 @@ impl Watchers {
     pub fn remove_watcher(&mut self, actor_id: ActorId) {
         self.watchers.remove(&actor_id);
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.watchers.is_empty()
+    }
 }
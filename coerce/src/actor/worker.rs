@@ -3,6 +3,7 @@
 
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
+use crate::actor::rt;
 use crate::actor::scheduler::ActorType::Anonymous;
 use crate::actor::system::ActorSystem;
 use crate::actor::{Actor, ActorRefErr, LocalActorRef};
@@ -126,7 +127,7 @@ where
             self.workers.push_back(worker);
 
             // main worker acts as a scheduler, don't block it by handling the task, dispatch it off
-            tokio::spawn(async move {
+            rt::spawn(async move {
                 match worker_ref.send(message.message).await {
                     Ok(res) => {
                         if message.res_tx.send(res).is_ok() {
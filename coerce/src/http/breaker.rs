@@ -0,0 +1,108 @@
+use std::time::{Duration, Instant};
+
+const DEFAULT_FAILURE_THRESHOLD: usize = 5;
+const DEFAULT_RESET_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Copy, Clone, Debug)]
+pub struct CircuitBreakerConfig {
+    /// The number of consecutive failed requests before the circuit is opened
+    pub failure_threshold: usize,
+
+    /// How long the circuit stays open before a trial request is allowed through
+    pub reset_timeout: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            reset_timeout: DEFAULT_RESET_TIMEOUT,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CircuitState {
+    /// Requests are allowed through
+    Closed,
+
+    /// Requests are rejected until the reset timeout has elapsed
+    Open,
+
+    /// A single trial request is allowed through, which decides whether the circuit is closed
+    /// or opened again
+    HalfOpen,
+}
+
+/// Tracks the outcome of requests to a single host
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: CircuitState,
+    consecutive_failures: usize,
+    opened_at: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+            trial_in_flight: false,
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// Returns whether a request is allowed through, moving an open circuit to half-open
+    /// once the reset timeout has elapsed
+    pub fn try_acquire(&mut self) -> bool {
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                let elapsed = self
+                    .opened_at
+                    .is_none_or(|opened_at| opened_at.elapsed() >= self.config.reset_timeout);
+
+                if elapsed {
+                    self.state = CircuitState::HalfOpen;
+                    self.trial_in_flight = true;
+                    true
+                } else {
+                    false
+                }
+            }
+            CircuitState::HalfOpen => {
+                if self.trial_in_flight {
+                    false
+                } else {
+                    self.trial_in_flight = true;
+                    true
+                }
+            }
+        }
+    }
+
+    pub fn on_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.trial_in_flight = false;
+        self.opened_at = None;
+        self.state = CircuitState::Closed;
+    }
+
+    pub fn on_failure(&mut self) {
+        self.consecutive_failures += 1;
+        self.trial_in_flight = false;
+
+        if self.state == CircuitState::HalfOpen
+            || self.consecutive_failures >= self.config.failure_threshold
+        {
+            self.state = CircuitState::Open;
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
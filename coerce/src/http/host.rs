@@ -0,0 +1,197 @@
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::Actor;
+use crate::http::breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use crate::http::request::{HttpRequest, HttpResponse};
+use crate::http::retry::{is_retryable_status, RetryPolicy};
+use crate::http::HttpClientErr;
+use reqwest::Url;
+use std::sync::Arc;
+use tokio::sync::oneshot::Sender;
+use tokio::sync::Semaphore;
+
+/// Sends requests to a single host, limiting the number of concurrent requests and tracking
+/// the host's circuit breaker.
+pub struct HostClient {
+    host: String,
+    client: reqwest::Client,
+    breaker: CircuitBreaker,
+    retry: RetryPolicy,
+    permits: Arc<Semaphore>,
+}
+
+impl HostClient {
+    pub(crate) fn new(
+        host: String,
+        client: reqwest::Client,
+        breaker: CircuitBreakerConfig,
+        retry: RetryPolicy,
+        max_concurrency: usize,
+    ) -> Self {
+        Self {
+            host,
+            client,
+            breaker: CircuitBreaker::new(breaker),
+            retry,
+            permits: Arc::new(Semaphore::new(max_concurrency)),
+        }
+    }
+}
+
+impl Actor for HostClient {}
+
+pub struct Execute {
+    pub request: HttpRequest,
+    pub url: Url,
+    pub result_channel: Sender<Result<HttpResponse, HttpClientErr>>,
+}
+
+impl Message for Execute {
+    type Result = ();
+}
+
+struct Completed {
+    success: bool,
+}
+
+impl Message for Completed {
+    type Result = ();
+}
+
+pub struct GetCircuitState;
+
+impl Message for GetCircuitState {
+    type Result = CircuitState;
+}
+
+#[async_trait]
+impl Handler<Execute> for HostClient {
+    async fn handle(&mut self, message: Execute, ctx: &mut ActorContext) {
+        if !self.breaker.try_acquire() {
+            let _ = message.result_channel.send(Err(HttpClientErr::CircuitOpen {
+                host: self.host.clone(),
+            }));
+
+            return;
+        }
+
+        let host_client = self.actor_ref(ctx);
+        let client = self.client.clone();
+        let permits = self.permits.clone();
+        let retry = self.retry;
+
+        tokio::spawn(async move {
+            // the permit is only released once every attempt has completed
+            let _permit = permits.acquire_owned().await.unwrap();
+
+            let res = execute(&client, message.request, message.url, retry).await;
+            let success = matches!(&res, Ok(res) if res.status < 500);
+
+            let _ = host_client.notify(Completed { success });
+            let _ = message.result_channel.send(res);
+        });
+    }
+}
+
+#[async_trait]
+impl Handler<Completed> for HostClient {
+    async fn handle(&mut self, message: Completed, _ctx: &mut ActorContext) {
+        let previous_state = self.breaker.state();
+        if message.success {
+            self.breaker.on_success();
+        } else {
+            self.breaker.on_failure();
+        }
+
+        let state = self.breaker.state();
+        if state != previous_state {
+            info!(
+                host = self.host.as_str(),
+                "circuit breaker state changed from {:?} to {:?}", previous_state, state
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<GetCircuitState> for HostClient {
+    async fn handle(&mut self, _message: GetCircuitState, _ctx: &mut ActorContext) -> CircuitState {
+        self.breaker.state()
+    }
+}
+
+async fn execute(
+    client: &reqwest::Client,
+    request: HttpRequest,
+    url: Url,
+    retry: RetryPolicy,
+) -> Result<HttpResponse, HttpClientErr> {
+    let max_attempts = if request.is_idempotent() {
+        retry.max_attempts.max(1)
+    } else {
+        1
+    };
+
+    let mut attempt = 1;
+    loop {
+        let res = execute_once(client, &request, url.clone()).await;
+        let retryable = match &res {
+            Ok(res) => is_retryable_status(res.status),
+            Err(e) => e.is_retryable(),
+        };
+
+        if !retryable || attempt >= max_attempts {
+            return res;
+        }
+
+        debug!(
+            url = url.as_str(),
+            attempt,
+            "request failed, retrying in {:?}",
+            retry.backoff(attempt)
+        );
+
+        tokio::time::sleep(retry.backoff(attempt)).await;
+        attempt += 1;
+    }
+}
+
+async fn execute_once(
+    client: &reqwest::Client,
+    request: &HttpRequest,
+    url: Url,
+) -> Result<HttpResponse, HttpClientErr> {
+    let mut builder = client.request(request.method.clone(), url);
+    for (name, value) in &request.headers {
+        builder = builder.header(name, value);
+    }
+
+    if let Some(body) = &request.body {
+        builder = builder.body(body.clone());
+    }
+
+    if let Some(timeout) = request.timeout {
+        builder = builder.timeout(timeout);
+    }
+
+    let response = builder.send().await?;
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.to_string(), value.to_string()))
+        })
+        .collect();
+
+    let body = response.bytes().await?.to_vec();
+
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
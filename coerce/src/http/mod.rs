@@ -0,0 +1,337 @@
+//! Outbound HTTP client, for actors that call external APIs.
+//!
+//! Requests are dispatched to a child actor per host (`scheme://host:port`), which limits
+//! the number of concurrent requests to the host, retries idempotent requests according to the
+//! client's [`RetryPolicy`], and tracks the host's [`CircuitBreaker`]. Once a host has failed
+//! too many times in a row, requests are rejected with [`HttpClientErr::CircuitOpen`] without
+//! being sent, until the breaker's reset timeout has elapsed.
+//!
+//! Connections are pooled and reused across requests to the same host.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let client = HttpClient::builder(system.clone())
+//!     .max_concurrency_per_host(16)
+//!     .retry(RetryPolicy::default())
+//!     .build()
+//!     .await?;
+//!
+//! let user: User = client
+//!     .send(HttpRequest::get("http://users.internal/users/1"))
+//!     .await?
+//!     .json()?;
+//! ```
+//!
+//! [`CircuitBreaker`]: breaker::CircuitBreaker
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::system::ActorSystem;
+use crate::actor::{Actor, ActorRefErr, IntoActor, IntoActorId, LocalActorRef};
+use crate::http::breaker::{CircuitBreakerConfig, CircuitState};
+use crate::http::host::{Execute, GetCircuitState, HostClient};
+use reqwest::Url;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::sync::oneshot::Sender;
+
+pub mod breaker;
+pub mod host;
+pub mod request;
+pub mod retry;
+
+pub use request::{HttpRequest, HttpResponse, Method};
+pub use retry::RetryPolicy;
+
+const DEFAULT_NAME: &str = "http-client";
+const DEFAULT_MAX_CONCURRENCY_PER_HOST: usize = 32;
+const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 32;
+const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum HttpClientErr {
+    InvalidUrl(String),
+    CircuitOpen { host: String },
+    Timeout,
+    Connect(String),
+    Request(String),
+    ActorRef(ActorRefErr),
+}
+
+impl HttpClientErr {
+    pub(crate) fn is_retryable(&self) -> bool {
+        matches!(self, HttpClientErr::Timeout | HttpClientErr::Connect(_))
+    }
+}
+
+impl Display for HttpClientErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpClientErr::InvalidUrl(url) => write!(f, "invalid url: {}", url),
+            HttpClientErr::CircuitOpen { host } => {
+                write!(f, "circuit breaker is open for host {}", host)
+            }
+            HttpClientErr::Timeout => write!(f, "request timed out"),
+            HttpClientErr::Connect(e) => write!(f, "failed to connect: {}", e),
+            HttpClientErr::Request(e) => write!(f, "request failed: {}", e),
+            HttpClientErr::ActorRef(e) => write!(f, "http client unavailable: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HttpClientErr {}
+
+impl From<reqwest::Error> for HttpClientErr {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            HttpClientErr::Timeout
+        } else if e.is_connect() {
+            HttpClientErr::Connect(e.to_string())
+        } else {
+            HttpClientErr::Request(e.to_string())
+        }
+    }
+}
+
+impl From<ActorRefErr> for HttpClientErr {
+    fn from(e: ActorRefErr) -> Self {
+        HttpClientErr::ActorRef(e)
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpClient {
+    pool: LocalActorRef<HostPool>,
+}
+
+pub struct HttpClientBuilder {
+    system: ActorSystem,
+    name: String,
+    max_concurrency_per_host: usize,
+    pool_max_idle_per_host: usize,
+    pool_idle_timeout: Duration,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+    retry: RetryPolicy,
+    circuit_breaker: CircuitBreakerConfig,
+}
+
+impl HttpClient {
+    pub fn builder(system: ActorSystem) -> HttpClientBuilder {
+        HttpClientBuilder {
+            system,
+            name: DEFAULT_NAME.to_string(),
+            max_concurrency_per_host: DEFAULT_MAX_CONCURRENCY_PER_HOST,
+            pool_max_idle_per_host: DEFAULT_POOL_MAX_IDLE_PER_HOST,
+            pool_idle_timeout: DEFAULT_POOL_IDLE_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            retry: RetryPolicy::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+        }
+    }
+
+    /// Sends the request, returning the response once it has been received in full.
+    ///
+    /// Responses with a non-success status are returned as `Ok`, although `5xx` responses
+    /// count towards opening the host's circuit breaker.
+    pub async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpClientErr> {
+        let (tx, rx) = oneshot::channel();
+        self.pool.notify(Dispatch {
+            request,
+            result_channel: tx,
+        })?;
+
+        rx.await
+            .map_err(|_| HttpClientErr::ActorRef(ActorRefErr::ResultChannelClosed))?
+    }
+
+    /// Returns the state of the circuit breaker for the host of `url`, or `None` if no requests
+    /// have been sent to the host
+    pub async fn circuit_state(&self, url: &str) -> Result<Option<CircuitState>, HttpClientErr> {
+        let url = parse_url(url)?;
+        Ok(self.pool.send(GetHostCircuitState(host_key(&url))).await?)
+    }
+
+    pub async fn stop(&self) -> Result<(), ActorRefErr> {
+        self.pool.stop(false).await
+    }
+}
+
+impl HttpClientBuilder {
+    /// The id of the client's actor, host actors are named `{name}-{host}`
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// The maximum number of in-flight requests to a single host, further requests wait
+    /// until one completes
+    pub fn max_concurrency_per_host(mut self, max_concurrency_per_host: usize) -> Self {
+        self.max_concurrency_per_host = max_concurrency_per_host.max(1);
+        self
+    }
+
+    pub fn pool_max_idle_per_host(mut self, pool_max_idle_per_host: usize) -> Self {
+        self.pool_max_idle_per_host = pool_max_idle_per_host;
+        self
+    }
+
+    pub fn pool_idle_timeout(mut self, pool_idle_timeout: Duration) -> Self {
+        self.pool_idle_timeout = pool_idle_timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// The timeout of each attempt, can be overridden per request with [`HttpRequest::timeout`]
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    pub async fn build(self) -> Result<HttpClient, HttpClientErr> {
+        let client = reqwest::Client::builder()
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.request_timeout)
+            .build()?;
+
+        let pool = HostPool {
+            client,
+            max_concurrency_per_host: self.max_concurrency_per_host,
+            retry: self.retry,
+            circuit_breaker: self.circuit_breaker,
+        }
+        .into_actor(Some(self.name.into_actor_id()), &self.system)
+        .await?;
+
+        Ok(HttpClient { pool })
+    }
+}
+
+/// Routes requests to the [`HostClient`] of the request's host, starting one if needed
+pub struct HostPool {
+    client: reqwest::Client,
+    max_concurrency_per_host: usize,
+    retry: RetryPolicy,
+    circuit_breaker: CircuitBreakerConfig,
+}
+
+impl Actor for HostPool {}
+
+impl HostPool {
+    async fn host_client(
+        &self,
+        host: String,
+        ctx: &mut ActorContext,
+    ) -> Result<LocalActorRef<HostClient>, ActorRefErr> {
+        let actor_id = format!("{}-{}", ctx.id(), &host).into_actor_id();
+        if let Some(host_client) = ctx.child_ref::<HostClient>(&actor_id) {
+            return Ok(host_client);
+        }
+
+        let host_client = HostClient::new(
+            host,
+            self.client.clone(),
+            self.circuit_breaker,
+            self.retry,
+            self.max_concurrency_per_host,
+        );
+
+        ctx.spawn(actor_id, host_client).await
+    }
+}
+
+pub struct Dispatch {
+    request: HttpRequest,
+    result_channel: Sender<Result<HttpResponse, HttpClientErr>>,
+}
+
+impl Message for Dispatch {
+    type Result = ();
+}
+
+pub struct GetHostCircuitState(String);
+
+impl Message for GetHostCircuitState {
+    type Result = Option<CircuitState>;
+}
+
+#[async_trait]
+impl Handler<Dispatch> for HostPool {
+    async fn handle(&mut self, message: Dispatch, ctx: &mut ActorContext) {
+        let url = match parse_url(&message.request.url) {
+            Ok(url) => url,
+            Err(e) => {
+                let _ = message.result_channel.send(Err(e));
+                return;
+            }
+        };
+
+        let host_client = match self.host_client(host_key(&url), ctx).await {
+            Ok(host_client) => host_client,
+            Err(e) => {
+                let _ = message.result_channel.send(Err(e.into()));
+                return;
+            }
+        };
+
+        let execute = Execute {
+            request: message.request,
+            url,
+            result_channel: message.result_channel,
+        };
+
+        if let Err(e) = host_client.notify(execute) {
+            warn!("failed to dispatch request to host client, error={}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<GetHostCircuitState> for HostPool {
+    async fn handle(
+        &mut self,
+        message: GetHostCircuitState,
+        ctx: &mut ActorContext,
+    ) -> Option<CircuitState> {
+        let actor_id = format!("{}-{}", ctx.id(), &message.0).into_actor_id();
+        let host_client = ctx.child_ref::<HostClient>(&actor_id)?;
+
+        host_client.send(GetCircuitState).await.ok()
+    }
+}
+
+fn parse_url(url: &str) -> Result<Url, HttpClientErr> {
+    match Url::parse(url) {
+        Ok(parsed) if parsed.host_str().is_some() => Ok(parsed),
+        _ => Err(HttpClientErr::InvalidUrl(url.to_string())),
+    }
+}
+
+fn host_key(url: &Url) -> String {
+    format!(
+        "{}://{}:{}",
+        url.scheme(),
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    )
+}
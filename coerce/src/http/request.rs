@@ -0,0 +1,104 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::string::FromUtf8Error;
+use std::time::Duration;
+
+pub use reqwest::Method;
+
+/// An outbound HTTP request, sent using [`HttpClient::send`](super::HttpClient::send)
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+
+    /// Overrides the client's request timeout
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: impl ToString) -> Self {
+        Self {
+            method,
+            url: url.to_string(),
+            headers: vec![],
+            body: None,
+            timeout: None,
+        }
+    }
+
+    pub fn get(url: impl ToString) -> Self {
+        Self::new(Method::GET, url)
+    }
+
+    pub fn post(url: impl ToString) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    pub fn put(url: impl ToString) -> Self {
+        Self::new(Method::PUT, url)
+    }
+
+    pub fn delete(url: impl ToString) -> Self {
+        Self::new(Method::DELETE, url)
+    }
+
+    pub fn header(mut self, name: impl ToString, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Serialises `body` as JSON, and sets the `content-type` header
+    pub fn json<T: Serialize>(self, body: &T) -> Result<Self, serde_json::Error> {
+        let body = serde_json::to_vec(body)?;
+        Ok(self.header("content-type", "application/json").body(body))
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Only idempotent requests are retried
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            self.method,
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Returns the first header with the provided name, ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn text(&self) -> Result<String, FromUtf8Error> {
+        String::from_utf8(self.body.clone())
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Decides how idempotent requests are retried when they fail with a connection error,
+/// a timeout, or a `5xx`/`429` response.
+#[derive(Copy, Clone, Debug)]
+pub struct RetryPolicy {
+    /// The total number of attempts, including the first
+    pub max_attempts: usize,
+
+    /// The delay before the first retry, doubled for each subsequent retry
+    pub initial_backoff: Duration,
+
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Requests are attempted once
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn backoff(&self, attempt: usize) -> Duration {
+        let multiplier = 2u32.saturating_pow(attempt.saturating_sub(1) as u32);
+        self.initial_backoff
+            .saturating_mul(multiplier)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+pub(crate) fn is_retryable_status(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
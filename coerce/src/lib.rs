@@ -63,6 +63,15 @@
 //! - `actor-tracing` - Enables actor tracing
 //! - `api` - Enables HTTP API server
 //! - `client-auth-jwt` - Enables JWT authentication between Coerce cluster nodes
+//! - `net` - Enables the actor-based TCP/UDP server toolkit
+//! - `http-client` - Enables the outbound HTTP client actor
+//!
+//! ## WebAssembly
+//! The core actor runtime can be compiled for `wasm32-unknown-unknown` with the default features,
+//! allowing actors to run in the browser. Tasks are spawned onto the browser's event loop rather
+//! than a Tokio runtime, see [`rt`] for details. The blocking APIs are not available on `wasm32`.
+//!
+//! [`rt`]: crate::actor::rt
 //!
 //! # Getting Started
 //! The entry point into the Coerce runtime is an [`ActorSystem`]. Every [`ActorSystem`] has an ID
@@ -171,12 +180,21 @@ extern crate tracing;
 
 pub mod actor;
 
+#[cfg(feature = "http-client")]
+pub mod http;
+
+#[cfg(feature = "net")]
+pub mod net;
+
 #[cfg(feature = "persistence")]
 pub mod persistent;
 
 #[cfg(feature = "remote")]
 pub mod remote;
 
+#[cfg(feature = "router")]
+pub mod router;
+
 #[cfg(feature = "sharding")]
 pub mod sharding;
 #[cfg(feature = "singleton")]
@@ -0,0 +1,81 @@
+//! Building blocks for custom protocol servers, where each connection is an actor.
+//!
+//! [`TcpServer`] accepts connections on behalf of an acceptor actor, which spawns a connection
+//! actor (created by a user-provided factory) for each socket. Frames are read from the socket
+//! using the provided [`Decoder`], and delivered to the connection actor as [`Received`] messages,
+//! one at a time, so a slow connection actor applies backpressure to its peer. Replies are written
+//! with the connection's [`FrameWriter`].
+//!
+//! The socket is tied to the lifecycle of the connection actor: when the peer disconnects,
+//! the actor is stopped, and when the actor stops, the socket is closed.
+//!
+//! [`UdpEndpoint`] does the same for datagrams, delivering each decoded datagram to a single
+//! actor as a [`Datagram`] message.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! struct LineConnection {
+//!     writer: FrameWriter<LinesCodec>,
+//! }
+//!
+//! impl Actor for LineConnection {}
+//!
+//! #[async_trait]
+//! impl Handler<Received<String>> for LineConnection {
+//!     async fn handle(&mut self, message: Received<String>, ctx: &mut ActorContext) {
+//!         if self.writer.send(message.0).await.is_err() {
+//!             ctx.stop(None);
+//!         }
+//!     }
+//! }
+//!
+//! let server = TcpServer::builder(system.clone())
+//!     .name("echo")
+//!     .listen_addr("0.0.0.0:7000")
+//!     .max_connections(1024)
+//!     .start(LinesCodec::new(), |conn| LineConnection { writer: conn.writer })
+//!     .await?;
+//! ```
+//!
+//! [`TcpServer`]: tcp::TcpServer
+//! [`FrameWriter`]: tcp::FrameWriter
+//! [`UdpEndpoint`]: udp::UdpEndpoint
+//! [`Datagram`]: udp::Datagram
+//! [`Decoder`]: tokio_util::codec::Decoder
+
+use crate::actor::message::Message;
+use crate::actor::ActorRefErr;
+use std::fmt::{Display, Formatter};
+
+pub mod tcp;
+pub mod udp;
+
+#[derive(Debug)]
+pub enum NetErr {
+    Bind(std::io::Error),
+    ActorStart(ActorRefErr),
+}
+
+impl Display for NetErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetErr::Bind(e) => write!(f, "failed to bind socket: {}", e),
+            NetErr::ActorStart(e) => write!(f, "failed to start actor: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NetErr {}
+
+impl From<ActorRefErr> for NetErr {
+    fn from(e: ActorRefErr) -> Self {
+        NetErr::ActorStart(e)
+    }
+}
+
+/// A frame that was decoded from a connection
+pub struct Received<T>(pub T);
+
+impl<T: 'static + Send + Sync> Message for Received<T> {
+    type Result = ();
+}
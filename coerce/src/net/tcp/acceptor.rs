@@ -0,0 +1,202 @@
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::{Actor, IntoActorId, LocalActorRef};
+use crate::net::tcp::{FrameWriter, NewConnection};
+use crate::net::Received;
+use futures::StreamExt;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::codec::{Decoder, FramedRead};
+
+/// Accepts connections, spawning a connection actor as a child for each one.
+///
+/// Stopping the acceptor stops accepting connections, and stops every connection actor.
+pub struct TcpAcceptor<A, C, F> {
+    listener: Option<TcpListener>,
+    codec: C,
+    factory: F,
+    connection_permits: Arc<Semaphore>,
+    next_connection_id: u64,
+    _a: PhantomData<fn() -> A>,
+}
+
+impl<A, C, F> TcpAcceptor<A, C, F> {
+    pub(crate) fn new(
+        listener: TcpListener,
+        codec: C,
+        factory: F,
+        connection_permits: Arc<Semaphore>,
+    ) -> Self {
+        Self {
+            listener: Some(listener),
+            codec,
+            factory,
+            connection_permits,
+            next_connection_id: 0,
+            _a: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<A, C, F> Actor for TcpAcceptor<A, C, F>
+where
+    A: Actor + Handler<Received<C::Item>>,
+    C: 'static + Decoder + Clone + Send + Sync,
+    C::Item: 'static + Send + Sync,
+    C::Error: Display + Send,
+    F: 'static + Fn(NewConnection<C>) -> A + Send + Sync,
+{
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        let listener = self.listener.take().unwrap();
+        let acceptor = self.actor_ref(ctx);
+        let connection_permits = self.connection_permits.clone();
+
+        tokio::spawn(accept_loop(listener, acceptor, connection_permits));
+    }
+}
+
+async fn accept_loop<A: Actor + Handler<Accepted>>(
+    listener: TcpListener,
+    acceptor: LocalActorRef<A>,
+    connection_permits: Arc<Semaphore>,
+) {
+    loop {
+        // wait for a connection to close before accepting another, once the limit is reached.
+        // the permit isn't held while accepting, so `TcpServer::connections` only counts
+        // connections that are open.
+        tokio::select! {
+            _ = acceptor.wait_for_stop() => break,
+            permit = connection_permits.acquire() => drop(permit),
+        };
+
+        let accepted = tokio::select! {
+            _ = acceptor.wait_for_stop() => break,
+            accepted = listener.accept() => accepted,
+        };
+
+        match accepted {
+            Ok((stream, addr)) => {
+                // only the accept loop acquires permits, so one is still available
+                let permit = connection_permits.clone().try_acquire_owned().unwrap();
+                if acceptor
+                    .notify(Accepted {
+                        stream,
+                        addr,
+                        permit,
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            Err(e) => warn!("error accepting connection, error={}", e),
+        }
+    }
+
+    debug!(
+        acceptor = acceptor.actor_id().as_ref(),
+        "tcp listener stopped"
+    );
+}
+
+pub struct Accepted {
+    stream: TcpStream,
+    addr: SocketAddr,
+    permit: OwnedSemaphorePermit,
+}
+
+impl Message for Accepted {
+    type Result = ();
+}
+
+#[async_trait]
+impl<A, C, F> Handler<Accepted> for TcpAcceptor<A, C, F>
+where
+    A: Actor + Handler<Received<C::Item>>,
+    C: 'static + Decoder + Clone + Send + Sync,
+    C::Item: 'static + Send + Sync,
+    C::Error: Display + Send,
+    F: 'static + Fn(NewConnection<C>) -> A + Send + Sync,
+{
+    async fn handle(&mut self, message: Accepted, ctx: &mut ActorContext) {
+        self.next_connection_id += 1;
+
+        let id = self.next_connection_id;
+        let (read, write) = message.stream.into_split();
+        let actor = (self.factory)(NewConnection {
+            id,
+            addr: message.addr,
+            writer: FrameWriter::new(write, self.codec.clone()),
+        });
+
+        let actor_id = format!("{}-{}", ctx.id(), id).into_actor_id();
+        match ctx.spawn(actor_id, actor).await {
+            Ok(connection) => {
+                trace!(
+                    connection = connection.actor_id().as_ref(),
+                    "connection accepted from {}",
+                    &message.addr
+                );
+
+                let read = FramedRead::new(read, self.codec.clone());
+                tokio::spawn(read_loop(connection, read, message.permit));
+            }
+            Err(e) => {
+                warn!(
+                    "failed to start connection actor, closing connection from {}, error={}",
+                    &message.addr, e
+                );
+            }
+        }
+    }
+}
+
+/// Delivers each frame to the connection actor, waiting for it to be handled before reading
+/// the next frame. Runs until the peer disconnects, or the actor stops.
+async fn read_loop<A, C>(
+    connection: LocalActorRef<A>,
+    mut read: FramedRead<OwnedReadHalf, C>,
+    _permit: OwnedSemaphorePermit,
+) where
+    A: Actor + Handler<Received<C::Item>>,
+    C: Decoder,
+    C::Item: 'static + Send + Sync,
+    C::Error: Display + Send,
+{
+    loop {
+        let frame = tokio::select! {
+            _ = connection.wait_for_stop() => return,
+            frame = read.next() => frame,
+        };
+
+        match frame {
+            Some(Ok(frame)) => {
+                if connection.send(Received(frame)).await.is_err() {
+                    return;
+                }
+            }
+            Some(Err(e)) => {
+                warn!(
+                    connection = connection.actor_id().as_ref(),
+                    "failed to decode frame, closing connection, error={}", e
+                );
+
+                break;
+            }
+            None => break,
+        }
+    }
+
+    trace!(
+        connection = connection.actor_id().as_ref(),
+        "connection closed by peer"
+    );
+
+    let _ = connection.notify_stop();
+}
@@ -0,0 +1,142 @@
+use crate::actor::message::Handler;
+use crate::actor::system::ActorSystem;
+use crate::actor::{Actor, ActorRefErr, BoxedActorRef, CoreActorRef, IntoActor};
+use crate::net::tcp::acceptor::TcpAcceptor;
+use crate::net::{NetErr, Received};
+use futures::SinkExt;
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
+use tokio_util::codec::{Decoder, Encoder, FramedWrite};
+
+pub mod acceptor;
+
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:0";
+const DEFAULT_NAME: &str = "tcp-server";
+
+/// Writes frames to a connection, using the connection's codec
+pub struct FrameWriter<C> {
+    write: FramedWrite<OwnedWriteHalf, C>,
+}
+
+impl<C> FrameWriter<C> {
+    pub(crate) fn new(write: OwnedWriteHalf, codec: C) -> Self {
+        Self {
+            write: FramedWrite::new(write, codec),
+        }
+    }
+
+    /// Encodes the frame and waits until it has been written to the socket
+    pub async fn send<I>(&mut self, frame: I) -> Result<(), C::Error>
+    where
+        C: Encoder<I>,
+    {
+        self.write.send(frame).await
+    }
+}
+
+/// A newly accepted connection, used to create the connection's actor
+pub struct NewConnection<C> {
+    /// Unique within the server, also used as the suffix of the connection actor's id
+    pub id: u64,
+    pub addr: SocketAddr,
+    pub writer: FrameWriter<C>,
+}
+
+/// A running TCP server, see the [module level docs](crate::net) for details
+pub struct TcpServer {
+    acceptor: BoxedActorRef,
+    local_addr: SocketAddr,
+    connection_permits: Arc<Semaphore>,
+    max_connections: usize,
+}
+
+pub struct TcpServerBuilder {
+    system: ActorSystem,
+    name: String,
+    listen_addr: String,
+    max_connections: usize,
+}
+
+impl TcpServer {
+    pub fn builder(system: ActorSystem) -> TcpServerBuilder {
+        TcpServerBuilder {
+            system,
+            name: DEFAULT_NAME.to_string(),
+            listen_addr: DEFAULT_LISTEN_ADDR.to_string(),
+            max_connections: Semaphore::MAX_PERMITS,
+        }
+    }
+
+    /// The address the server is listening on, useful when listening on port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The number of connections that are currently open
+    pub fn connections(&self) -> usize {
+        self.max_connections - self.connection_permits.available_permits()
+    }
+
+    /// Stops accepting connections and stops every connection actor, closing their sockets
+    pub async fn stop(&self) -> Result<(), ActorRefErr> {
+        self.acceptor.stop(false).await
+    }
+}
+
+impl TcpServerBuilder {
+    /// The id of the acceptor actor, connection actors are named `{name}-{connection_id}`
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn listen_addr(mut self, listen_addr: impl ToString) -> Self {
+        self.listen_addr = listen_addr.to_string();
+        self
+    }
+
+    /// Once there are `max_connections` open connections, the server stops accepting
+    /// connections until one is closed
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.min(Semaphore::MAX_PERMITS);
+        self
+    }
+
+    /// Binds the listener and starts the acceptor actor. Each connection is decoded with a clone
+    /// of `codec`, and handled by the actor returned by `factory`.
+    pub async fn start<A, C, F>(self, codec: C, factory: F) -> Result<TcpServer, NetErr>
+    where
+        A: Actor + Handler<Received<C::Item>>,
+        C: 'static + Decoder + Clone + Send + Sync,
+        C::Item: 'static + Send + Sync,
+        C::Error: Display + Send,
+        F: 'static + Fn(NewConnection<C>) -> A + Send + Sync,
+    {
+        let listener = TcpListener::bind(&self.listen_addr)
+            .await
+            .map_err(NetErr::Bind)?;
+
+        let local_addr = listener.local_addr().map_err(NetErr::Bind)?;
+        let connection_permits = Arc::new(Semaphore::new(self.max_connections));
+
+        let acceptor = TcpAcceptor::new(listener, codec, factory, connection_permits.clone())
+            .into_actor(Some(self.name), &self.system)
+            .await?;
+
+        info!(
+            acceptor = acceptor.actor_id().as_ref(),
+            "tcp server listening on {}", &local_addr
+        );
+
+        Ok(TcpServer {
+            acceptor: acceptor.into(),
+            local_addr,
+            connection_permits,
+            max_connections: self.max_connections,
+        })
+    }
+}
@@ -0,0 +1,126 @@
+use crate::actor::message::{Handler, Message};
+use crate::actor::system::ActorSystem;
+use crate::actor::{
+    Actor, ActorRefErr, BoxedActorRef, CoreActorRef, IntoActor, IntoActorId, LocalActorRef,
+};
+use crate::net::NetErr;
+use futures::{SinkExt, StreamExt};
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio_util::codec::{Decoder, Encoder};
+use tokio_util::udp::UdpFramed;
+
+/// A datagram that was decoded by a [`UdpEndpoint`]
+pub struct Datagram<T> {
+    pub frame: T,
+    pub addr: SocketAddr,
+}
+
+impl<T: 'static + Send + Sync> Message for Datagram<T> {
+    type Result = ();
+}
+
+/// Writes datagrams from the endpoint's socket, using the endpoint's codec
+pub struct DatagramWriter<C> {
+    write: UdpFramed<C, Arc<UdpSocket>>,
+}
+
+impl<C> DatagramWriter<C> {
+    pub async fn send_to<I>(&mut self, frame: I, addr: SocketAddr) -> Result<(), C::Error>
+    where
+        C: Encoder<I>,
+    {
+        self.write.send((frame, addr)).await
+    }
+}
+
+/// A bound UDP socket, where every datagram is handled by a single actor
+pub struct UdpEndpoint {
+    actor: BoxedActorRef,
+    local_addr: SocketAddr,
+}
+
+impl UdpEndpoint {
+    /// Binds the socket and starts the actor returned by `factory`, which receives each decoded
+    /// datagram as a [`Datagram`] message. Datagrams that can't be decoded are dropped.
+    ///
+    /// The socket is closed once the actor stops.
+    pub async fn bind<A, C, F>(
+        system: &ActorSystem,
+        actor_id: impl IntoActorId,
+        addr: &str,
+        codec: C,
+        factory: F,
+    ) -> Result<UdpEndpoint, NetErr>
+    where
+        A: Actor + Handler<Datagram<C::Item>>,
+        C: 'static + Decoder + Clone + Send + Sync,
+        C::Item: 'static + Send + Sync,
+        C::Error: Display + Send,
+        F: FnOnce(DatagramWriter<C>) -> A,
+    {
+        let socket = Arc::new(UdpSocket::bind(addr).await.map_err(NetErr::Bind)?);
+        let local_addr = socket.local_addr().map_err(NetErr::Bind)?;
+
+        let actor = factory(DatagramWriter {
+            write: UdpFramed::new(socket.clone(), codec.clone()),
+        });
+
+        let actor = actor
+            .into_actor(Some(actor_id.into_actor_id()), system)
+            .await?;
+
+        tokio::spawn(receive_loop(actor.clone(), UdpFramed::new(socket, codec)));
+
+        info!(
+            actor = actor.actor_id().as_ref(),
+            "udp endpoint bound to {}", &local_addr
+        );
+
+        Ok(UdpEndpoint {
+            actor: actor.into(),
+            local_addr,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops the actor, closing the socket
+    pub async fn stop(&self) -> Result<(), ActorRefErr> {
+        self.actor.stop(false).await
+    }
+}
+
+async fn receive_loop<A, C>(actor: LocalActorRef<A>, mut read: UdpFramed<C, Arc<UdpSocket>>)
+where
+    A: Actor + Handler<Datagram<C::Item>>,
+    C: Decoder,
+    C::Item: 'static + Send + Sync,
+    C::Error: Display + Send,
+{
+    loop {
+        let datagram = tokio::select! {
+            _ = actor.wait_for_stop() => break,
+            datagram = read.next() => datagram,
+        };
+
+        match datagram {
+            Some(Ok((frame, addr))) => {
+                if actor.send(Datagram { frame, addr }).await.is_err() {
+                    break;
+                }
+            }
+            Some(Err(e)) => {
+                debug!(
+                    actor = actor.actor_id().as_ref(),
+                    "dropped datagram that could not be decoded, error={}", e
+                );
+            }
+            None => break,
+        }
+    }
+}
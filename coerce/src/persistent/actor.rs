@@ -1,15 +1,17 @@
 use crate::actor::context::{ActorContext, ActorStatus};
-use crate::actor::message::Message;
+use crate::actor::message::{Handler, Message};
 use crate::actor::system::ActorSystem;
 use crate::actor::{Actor, ActorId, BoxedActorRef};
 
 use crate::persistent::failure::{should_retry, PersistFailurePolicy, RecoveryFailurePolicy};
-use crate::persistent::journal::snapshot::Snapshot;
+use crate::persistent::journal::snapshot::{Snapshot, SnapshotPolicy};
 use crate::persistent::journal::types::JournalTypes;
 use crate::persistent::journal::{PersistErr, RecoveryErr};
 use crate::persistent::recovery::{ActorRecovery, Recovery};
 
 use crate::persistent::batch::EventBatch;
+use crate::persistent::metadata::EventMetadata;
+use crate::persistent::migration::MigrationSnapshot;
 use crate::persistent::storage::JournalEntry;
 use crate::persistent::ReadMessages;
 use std::sync::Arc;
@@ -33,6 +35,21 @@ pub trait PersistentActor: 'static + Sized + Send + Sync {
         message: &M,
         ctx: &mut ActorContext,
     ) -> Result<(), PersistErr>
+    where
+        Self: Recover<M>,
+    {
+        self.persist_with_metadata(message, EventMetadata::default(), ctx)
+            .await
+    }
+
+    /// Persists the message along with the provided [`EventMetadata`],
+    /// the write timestamp is recorded automatically if not set
+    async fn persist_with_metadata<M: Message>(
+        &self,
+        message: &M,
+        metadata: EventMetadata,
+        ctx: &mut ActorContext,
+    ) -> Result<(), PersistErr>
     where
         Self: Recover<M>,
     {
@@ -45,10 +62,14 @@ pub trait PersistentActor: 'static + Sized + Send + Sync {
                     let result = ctx
                         .persistence_mut()
                         .journal_mut::<Self>()
-                        .persist_message::<M>(bytes.clone())
+                        .persist_message::<M>(bytes.clone(), metadata.clone())
                         .await;
 
                     if let Some(res) = check(result, &mut attempts, self, ctx).await {
+                        if res.is_ok() {
+                            snapshot_if_due(self, ctx);
+                        }
+
                         return res;
                     }
                 }
@@ -75,6 +96,10 @@ pub trait PersistentActor: 'static + Sized + Send + Sync {
                 .await;
 
             if let Some(res) = check(result, &mut attempts, self, ctx).await {
+                if res.is_ok() {
+                    snapshot_if_due(self, ctx);
+                }
+
                 return res;
             }
         }
@@ -102,6 +127,10 @@ pub trait PersistentActor: 'static + Sized + Send + Sync {
                         .await;
 
                     if let Some(res) = check(result, &mut attempts, self, ctx).await {
+                        if res.is_ok() {
+                            apply_snapshot_retention(self, ctx).await;
+                        }
+
                         return res;
                     }
                 }
@@ -147,6 +176,19 @@ pub trait PersistentActor: 'static + Sized + Send + Sync {
         PersistFailurePolicy::default()
     }
 
+    /// When the actor is asked to take a snapshot, via [`PersistentActor::on_snapshot_due`],
+    /// and which snapshots and messages are kept once a snapshot is persisted
+    fn snapshot_policy(&self) -> SnapshotPolicy {
+        SnapshotPolicy::default()
+    }
+
+    /// Called once a snapshot is due according to the actor's [`SnapshotPolicy`], after the
+    /// message that made it due has been handled. Actors take a snapshot of their current state
+    /// by calling [`PersistentActor::snapshot`], errors are logged.
+    async fn on_snapshot_due(&mut self, _ctx: &mut ActorContext) -> Result<(), PersistErr> {
+        Ok(())
+    }
+
     fn event_batch(&self, ctx: &ActorContext) -> EventBatch<Self> {
         EventBatch::create(ctx)
     }
@@ -156,6 +198,46 @@ pub trait PersistentActor: 'static + Sized + Send + Sync {
     async fn on_recovery_failed(&mut self, _ctx: &mut ActorContext) {}
 
     async fn on_child_stopped(&mut self, _id: &ActorId, _ctx: &mut ActorContext) {}
+
+    /// Called when the actor is being stopped so it can be migrated to another node, for example
+    /// when the shard hosting a sharded entity is rebalanced.
+    ///
+    /// Returning a snapshot of the actor's current state, created via
+    /// [`PersistentActor::migration_snapshot_of`], ships the snapshot to the new node, so the
+    /// actor can be recovered there without replaying its journal from the storage backend.
+    async fn on_migrate(&mut self, _ctx: &mut ActorContext) -> Option<MigrationSnapshot> {
+        None
+    }
+
+    /// Creates a [`MigrationSnapshot`] of the actor's current state, without persisting it
+    fn migration_snapshot_of<S: Snapshot>(
+        &self,
+        snapshot: S,
+        ctx: &ActorContext,
+    ) -> Option<MigrationSnapshot>
+    where
+        Self: RecoverSnapshot<S>,
+    {
+        let persistence_key = self.persistence_key(ctx);
+        match ctx.persistence().journal::<Self>().snapshot_entry(snapshot) {
+            Ok(entry) => Some(MigrationSnapshot {
+                persistence_key,
+                sequence: entry.sequence,
+                payload_type: entry.payload_type.to_string(),
+                bytes: entry.bytes.as_ref().clone(),
+            }),
+
+            Err(e) => {
+                error!(
+                    "failed to create migration snapshot, error={error}, actor_id={actor_id}",
+                    error = e,
+                    actor_id = ctx.id()
+                );
+
+                None
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -168,6 +250,80 @@ pub trait RecoverSnapshot<S: Snapshot> {
     async fn recover(&mut self, snapshot: S, ctx: &mut ActorContext);
 }
 
+/// Sent by a persistent actor to itself once a snapshot is due, so the snapshot is taken
+/// after the message that made it due has been handled
+pub struct SnapshotDue;
+
+impl Message for SnapshotDue {
+    type Result = ();
+}
+
+fn snapshot_if_due<A: PersistentActor>(actor: &A, ctx: &mut ActorContext) {
+    let journal = ctx.persistence_mut().journal_mut::<A>();
+    if journal.is_snapshot_requested()
+        || !actor
+            .snapshot_policy()
+            .is_snapshot_due(journal.events_since_snapshot())
+    {
+        return;
+    }
+
+    journal.set_snapshot_requested(true);
+    let _ = actor.actor_ref(ctx).notify(SnapshotDue);
+}
+
+#[async_trait]
+impl<A: PersistentActor> Handler<SnapshotDue> for A {
+    async fn handle(&mut self, _message: SnapshotDue, ctx: &mut ActorContext) {
+        let journal = ctx.persistence_mut().journal_mut::<A>();
+        journal.set_snapshot_requested(false);
+
+        // a snapshot may have been persisted since the request was sent
+        let events_since_snapshot = journal.events_since_snapshot();
+        if !self
+            .snapshot_policy()
+            .is_snapshot_due(events_since_snapshot)
+        {
+            return;
+        }
+
+        trace!(
+            "snapshot due, actor_id={}, events_since_snapshot={}",
+            ctx.id(),
+            events_since_snapshot
+        );
+
+        if let Err(e) = self.on_snapshot_due(ctx).await {
+            error!(
+                "failed to take snapshot, error={error}, actor_id={actor_id}",
+                error = e,
+                actor_id = ctx.id()
+            );
+        }
+    }
+}
+
+async fn apply_snapshot_retention<A: PersistentActor>(actor: &A, ctx: &mut ActorContext) {
+    let policy = actor.snapshot_policy();
+    if policy.keep_snapshots.is_none() && !policy.delete_messages {
+        return;
+    }
+
+    let result = ctx
+        .persistence_mut()
+        .journal_mut::<A>()
+        .apply_snapshot_retention(&policy)
+        .await;
+
+    if let Err(e) = result {
+        warn!(
+            "failed to apply snapshot retention, error={error}, actor_id={actor_id}",
+            error = e,
+            actor_id = ctx.id()
+        );
+    }
+}
+
 async fn check<A: PersistentActor>(
     result: Result<(), PersistErr>,
     attempts: &mut usize,
@@ -303,4 +459,8 @@ where
     async fn on_child_stopped(&mut self, id: &ActorId, ctx: &mut ActorContext) {
         self.on_child_stopped(id, ctx).await
     }
+
+    async fn migration_snapshot(&mut self, ctx: &mut ActorContext) -> Option<MigrationSnapshot> {
+        self.on_migrate(ctx).await
+    }
 }
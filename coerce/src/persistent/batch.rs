@@ -1,6 +1,7 @@
 use crate::actor::context::ActorContext;
 use crate::actor::message::Message;
 use crate::actor::Actor;
+use crate::persistent::metadata::EventMetadata;
 use crate::persistent::storage::JournalEntry;
 use crate::persistent::types::JournalTypes;
 use crate::persistent::{PersistentActor, Recover};
@@ -16,6 +17,7 @@ pub struct EventBatch<A: PersistentActor> {
 pub struct BatchedEntry {
     pub payload_type: Arc<str>,
     pub bytes: Arc<Vec<u8>>,
+    pub metadata: EventMetadata,
 }
 
 impl<A: PersistentActor> EventBatch<A> {
@@ -30,6 +32,17 @@ impl<A: PersistentActor> EventBatch<A> {
     }
 
     pub fn message<M: Message>(&mut self, message: M) -> &mut Self
+    where
+        A: Recover<M>,
+    {
+        self.message_with_metadata(message, EventMetadata::default())
+    }
+
+    pub fn message_with_metadata<M: Message>(
+        &mut self,
+        message: M,
+        metadata: EventMetadata,
+    ) -> &mut Self
     where
         A: Recover<M>,
     {
@@ -37,6 +50,7 @@ impl<A: PersistentActor> EventBatch<A> {
         let entry = BatchedEntry {
             payload_type,
             bytes: message.as_bytes().unwrap().into(),
+            metadata,
         };
 
         self.entries.push(entry);
@@ -0,0 +1,211 @@
+//! State checkpointing, for actors that aren't event sourced
+//!
+//! Actors that hold simple state, which doesn't warrant a [`PersistentActor`] with its own
+//! journal of events, can still be made resilient to crashes and restarts by checkpointing their
+//! state. The actor stages the latest copy of its state via [`ActorContext::checkpoint`], and the
+//! framework persists it, at most once per [`Actor::CHECKPOINT_INTERVAL`], and once more when the
+//! actor stops. When the actor is next started, the latest checkpoint is passed to
+//! [`Actor::restore`], before [`Actor::started`] is called.
+//!
+//! Checkpoints are opaque blobs, written as snapshots to the actor system's [`Persistence`]
+//! provider, keyed by the actor's ID. Any state staged since the last checkpoint was written is
+//! lost if the actor's process crashes.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! #[async_trait]
+//! impl Actor for Counter {
+//!     const CHECKPOINT_INTERVAL: Option<Duration> = Some(Duration::from_secs(5));
+//!
+//!     async fn restore(&mut self, checkpoint: Checkpoint, _ctx: &mut ActorContext) {
+//!         self.count = u64::from_le_bytes(checkpoint.bytes.try_into().unwrap());
+//!     }
+//! }
+//!
+//! #[async_trait]
+//! impl Handler<Increment> for Counter {
+//!     async fn handle(&mut self, _message: Increment, ctx: &mut ActorContext) {
+//!         self.count += 1;
+//!         ctx.checkpoint(self.count.to_le_bytes());
+//!     }
+//! }
+//! ```
+//!
+//! [`PersistentActor`]: crate::persistent::PersistentActor
+//! [`Persistence`]: crate::persistent::Persistence
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::Actor;
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::storage::{JournalEntry, JournalStorageRef};
+use std::any::TypeId;
+use std::sync::Arc;
+use std::time::Instant;
+
+const CHECKPOINT_PAYLOAD_TYPE: &str = "Checkpoint";
+
+/// The latest persisted state of an actor, passed to [`Actor::restore`]
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// Incremented each time a checkpoint of the actor is written
+    pub sequence: i64,
+    pub bytes: Vec<u8>,
+}
+
+/// The checkpoint state of an actor, held by its [`ActorContext`]
+#[derive(Default)]
+pub(crate) struct CheckpointState {
+    pending: Option<Arc<Vec<u8>>>,
+    sequence: i64,
+    last_written: Option<Instant>,
+    flush_scheduled: bool,
+}
+
+impl ActorContext {
+    /// Stages the actor's current state to be checkpointed, replacing any state staged since the
+    /// last checkpoint was written. Only used if the actor has a
+    /// [`CHECKPOINT_INTERVAL`](Actor::CHECKPOINT_INTERVAL), see [`checkpoint`](crate::persistent::checkpoint).
+    pub fn checkpoint(&mut self, state: impl Into<Vec<u8>>) {
+        self.checkpoint_state_mut().pending = Some(Arc::new(state.into()));
+    }
+}
+
+/// Writes the actor's staged checkpoint, scheduled once a checkpoint has been staged within
+/// [`Actor::CHECKPOINT_INTERVAL`] of the previous checkpoint being written
+pub struct FlushCheckpoint;
+
+impl Message for FlushCheckpoint {
+    type Result = ();
+}
+
+#[async_trait]
+impl<A: Actor> Handler<FlushCheckpoint> for A {
+    async fn handle(&mut self, _message: FlushCheckpoint, ctx: &mut ActorContext) {
+        ctx.checkpoint_state_mut().flush_scheduled = false;
+        write_checkpoint::<A>(ctx).await;
+    }
+}
+
+fn checkpoint_key(ctx: &ActorContext) -> String {
+    format!("checkpoint-{}", ctx.id())
+}
+
+fn storage<A: Actor>(ctx: &ActorContext) -> Option<JournalStorageRef> {
+    ctx.try_system()?
+        .persistence()?
+        .provider(TypeId::of::<A>())
+        .journal_storage()
+}
+
+/// Restores the actor from its latest checkpoint, if the actor has a checkpoint interval and a
+/// checkpoint has been written
+pub(crate) async fn restore_checkpoint<A: Actor>(actor: &mut A, ctx: &mut ActorContext) {
+    if A::CHECKPOINT_INTERVAL.is_none() {
+        return;
+    }
+
+    let Some(storage) = storage::<A>(ctx) else {
+        return;
+    };
+
+    let entry = match storage.read_latest_snapshot(&checkpoint_key(ctx)).await {
+        Ok(Some(entry)) => entry,
+        Ok(None) => return,
+        Err(e) => {
+            warn!(
+                actor = ctx.full_path().as_ref(),
+                error = format!("{}", e),
+                "failed to read checkpoint, starting without it"
+            );
+            return;
+        }
+    };
+
+    trace!(
+        actor = ctx.full_path().as_ref(),
+        sequence = entry.sequence,
+        "restoring actor from checkpoint"
+    );
+
+    ctx.checkpoint_state_mut().sequence = entry.sequence;
+
+    let checkpoint = Checkpoint {
+        sequence: entry.sequence,
+        bytes: Arc::try_unwrap(entry.bytes).unwrap_or_else(|bytes| bytes.as_ref().clone()),
+    };
+
+    actor.restore(checkpoint, ctx).await;
+}
+
+/// Called once each message has been handled, writes the staged checkpoint if the interval has
+/// elapsed since the last checkpoint was written, otherwise the write is scheduled for when it has
+pub(crate) async fn on_message_handled<A: Actor>(ctx: &mut ActorContext) {
+    let Some(interval) = A::CHECKPOINT_INTERVAL else {
+        return;
+    };
+
+    let state = ctx.checkpoint_state_mut();
+    if state.pending.is_none() || state.flush_scheduled {
+        return;
+    }
+
+    let since_last_write = state.last_written.map(|t| t.elapsed());
+    match since_last_write {
+        Some(elapsed) if elapsed < interval => {
+            state.flush_scheduled = true;
+            ctx.actor_ref::<A>()
+                .scheduled_notify(FlushCheckpoint, interval - elapsed);
+        }
+        _ => write_checkpoint::<A>(ctx).await,
+    }
+}
+
+/// Writes any staged checkpoint as the actor stops
+pub(crate) async fn on_stopped<A: Actor>(ctx: &mut ActorContext) {
+    if A::CHECKPOINT_INTERVAL.is_some() {
+        write_checkpoint::<A>(ctx).await;
+    }
+}
+
+async fn write_checkpoint<A: Actor>(ctx: &mut ActorContext) {
+    let Some(bytes) = ctx.checkpoint_state_mut().pending.take() else {
+        return;
+    };
+
+    let Some(storage) = storage::<A>(ctx) else {
+        warn!(
+            actor = ctx.full_path().as_ref(),
+            "actor system has no persistence configured, checkpoint discarded"
+        );
+        return;
+    };
+
+    let key = checkpoint_key(ctx);
+    let sequence = ctx.checkpoint_state_mut().sequence + 1;
+    let entry = JournalEntry {
+        sequence,
+        payload_type: CHECKPOINT_PAYLOAD_TYPE.into(),
+        bytes: bytes.clone(),
+        metadata: EventMetadata::default(),
+    };
+
+    let result = storage.write_snapshot(&key, entry).await;
+    let state = ctx.checkpoint_state_mut();
+    state.last_written = Some(Instant::now());
+
+    match result {
+        Ok(_) => state.sequence = sequence,
+        Err(e) => {
+            // the checkpoint is retried once the interval has elapsed, unless a newer
+            // checkpoint is staged in the meantime
+            state.pending.get_or_insert(bytes);
+
+            warn!(
+                actor = ctx.full_path().as_ref(),
+                error = format!("{}", e),
+                "failed to write checkpoint"
+            );
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Durable dead letter storage and replay
+//!
+//! The [`DeadLetterStore`] subscribes to the [`ActorSystem`]'s [`DeadLetters`] channel and writes
+//! every dead letter to a [`JournalStorage`] backend, allowing dead letters to be listed and
+//! re-delivered once the underlying issue has been resolved.
+//!
+//! Only dead letters whose message supports serialisation can be replayed, dead letters without
+//! a payload are still recorded for inspection purposes.
+//!
+//! [`ActorSystem`]: crate::actor::system::ActorSystem
+//! [`DeadLetters`]: crate::actor::dead_letter::DeadLetters
+//! [`JournalStorage`]: crate::persistent::journal::storage::JournalStorage
+
+use crate::actor::context::ActorContext;
+use crate::actor::dead_letter::{DeadLetter, DeadLetterReason};
+use crate::actor::message::{Handler, Message};
+use crate::actor::{Actor, ActorId, ActorRefErr, LocalActorRef};
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::storage::{JournalEntry, JournalStorageRef};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
+
+pub const DEAD_LETTER_PERSISTENCE_ID: &str = "coerce-dead-letters";
+
+const DEAD_LETTER_PAYLOAD_TYPE: &str = "coerce.DeadLetter";
+
+/// A dead letter that has been written to durable storage
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoredDeadLetter {
+    pub sequence: i64,
+    pub actor_id: ActorId,
+    pub actor_type: String,
+    pub message_type: String,
+    pub reason: DeadLetterReason,
+    /// Unix timestamp (in milliseconds) of when the dead letter was stored
+    pub timestamp: u64,
+    pub payload: Option<Vec<u8>>,
+}
+
+pub struct DeadLetterStore {
+    persistence_id: String,
+    storage: JournalStorageRef,
+    last_sequence: i64,
+}
+
+impl DeadLetterStore {
+    pub fn new(storage: JournalStorageRef) -> Self {
+        Self::with_persistence_id(DEAD_LETTER_PERSISTENCE_ID, storage)
+    }
+
+    pub fn with_persistence_id(persistence_id: impl ToString, storage: JournalStorageRef) -> Self {
+        Self {
+            persistence_id: persistence_id.to_string(),
+            storage,
+            last_sequence: 0,
+        }
+    }
+
+    async fn read_all(&self) -> anyhow::Result<Vec<StoredDeadLetter>> {
+        let entries = self
+            .storage
+            .read_latest_messages(&self.persistence_id, 0)
+            .await?
+            .unwrap_or_default();
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| serde_json::from_slice(entry.bytes.as_slice()).ok())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Actor for DeadLetterStore {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        match self.read_all().await {
+            Ok(dead_letters) => {
+                self.last_sequence = dead_letters.last().map_or(0, |d| d.sequence);
+            }
+            Err(e) => {
+                error!(
+                    error = format!("{}", e),
+                    "failed to load stored dead letters"
+                );
+            }
+        }
+
+        let mut dead_letters = ctx.system().dead_letters().subscribe();
+        let store = self.actor_ref(ctx);
+        tokio::spawn(async move {
+            loop {
+                match dead_letters.recv().await {
+                    Ok(dead_letter) => {
+                        if store.notify(StoreDeadLetter(dead_letter)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            skipped,
+                            "dead letter store lagged, dead letters were skipped"
+                        );
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        debug!(
+            last_sequence = self.last_sequence,
+            "dead letter store started"
+        );
+    }
+}
+
+struct StoreDeadLetter(DeadLetter);
+
+impl Message for StoreDeadLetter {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<StoreDeadLetter> for DeadLetterStore {
+    async fn handle(&mut self, message: StoreDeadLetter, _ctx: &mut ActorContext) {
+        let dead_letter = message.0;
+        let sequence = self.last_sequence + 1;
+        let stored = StoredDeadLetter {
+            sequence,
+            actor_id: dead_letter.actor_id,
+            actor_type: dead_letter.actor_type.to_string(),
+            message_type: dead_letter.message_type.to_string(),
+            reason: dead_letter.reason,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            payload: dead_letter.payload.map(|p| p.as_ref().clone()),
+        };
+
+        let bytes = match serde_json::to_vec(&stored) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!(error = format!("{}", e), "failed to serialise dead letter");
+                return;
+            }
+        };
+
+        let entry = JournalEntry {
+            sequence,
+            payload_type: DEAD_LETTER_PAYLOAD_TYPE.into(),
+            bytes: Arc::new(bytes),
+            metadata: EventMetadata::default(),
+        };
+
+        match self
+            .storage
+            .write_message(&self.persistence_id, entry)
+            .await
+        {
+            Ok(_) => self.last_sequence = sequence,
+            Err(e) => error!(error = format!("{}", e), "failed to store dead letter"),
+        }
+    }
+}
+
+/// Lists every dead letter currently held by the [`DeadLetterStore`]
+pub struct ListDeadLetters;
+
+impl Message for ListDeadLetters {
+    type Result = anyhow::Result<Vec<StoredDeadLetter>>;
+}
+
+#[async_trait]
+impl Handler<ListDeadLetters> for DeadLetterStore {
+    async fn handle(
+        &mut self,
+        _: ListDeadLetters,
+        _ctx: &mut ActorContext,
+    ) -> anyhow::Result<Vec<StoredDeadLetter>> {
+        self.read_all().await
+    }
+}
+
+/// Re-delivers the selected dead letters of message type `M` to the target actor.
+///
+/// Dead letters are selected by sequence, dead letters that were addressed to a different
+/// message type, or that do not have a payload, are skipped.
+pub struct ReplayDeadLetters<A: Actor, M: Message>
+where
+    A: Handler<M>,
+{
+    pub sequences: Vec<i64>,
+    pub target: LocalActorRef<A>,
+    _m: PhantomData<M>,
+}
+
+impl<A: Actor, M: Message> ReplayDeadLetters<A, M>
+where
+    A: Handler<M>,
+{
+    pub fn new(sequences: Vec<i64>, target: LocalActorRef<A>) -> Self {
+        Self {
+            sequences,
+            target,
+            _m: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ReplayResult {
+    pub replayed: Vec<i64>,
+    pub skipped: Vec<i64>,
+}
+
+impl<A: Actor, M: Message> Message for ReplayDeadLetters<A, M>
+where
+    A: Handler<M>,
+{
+    type Result = anyhow::Result<ReplayResult>;
+}
+
+#[async_trait]
+impl<A: Actor, M: Message> Handler<ReplayDeadLetters<A, M>> for DeadLetterStore
+where
+    A: Handler<M>,
+{
+    async fn handle(
+        &mut self,
+        message: ReplayDeadLetters<A, M>,
+        _ctx: &mut ActorContext,
+    ) -> anyhow::Result<ReplayResult> {
+        let dead_letters = self.read_all().await?;
+        let mut result = ReplayResult {
+            replayed: vec![],
+            skipped: vec![],
+        };
+
+        for sequence in message.sequences {
+            let dead_letter = dead_letters.iter().find(|d| d.sequence == sequence);
+            let msg = dead_letter
+                .filter(|d| d.message_type == M::type_name())
+                .and_then(|d| d.payload.clone())
+                .and_then(|payload| M::from_bytes(payload).ok());
+
+            match msg {
+                Some(msg) => match message.target.notify(msg) {
+                    Ok(_) => result.replayed.push(sequence),
+                    Err(ActorRefErr::InvalidRef) => {
+                        return Err(ActorRefErr::InvalidRef.into());
+                    }
+                    Err(_) => result.skipped.push(sequence),
+                },
+                None => result.skipped.push(sequence),
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Permanently deletes every stored dead letter up to and including the provided sequence
+pub struct PurgeDeadLetters {
+    pub to_sequence: i64,
+}
+
+impl Message for PurgeDeadLetters {
+    type Result = anyhow::Result<()>;
+}
+
+#[async_trait]
+impl Handler<PurgeDeadLetters> for DeadLetterStore {
+    async fn handle(
+        &mut self,
+        message: PurgeDeadLetters,
+        _ctx: &mut ActorContext,
+    ) -> anyhow::Result<()> {
+        self.storage
+            .delete_messages_to(&self.persistence_id, message.to_sequence + 1)
+            .await
+    }
+}
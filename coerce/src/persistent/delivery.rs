@@ -0,0 +1,470 @@
+//! At-least-once delivery, for persistent actors sending messages to other actors
+//!
+//! Messages sent with [`AtLeastOnceDelivery::deliver`] are assigned a [`DeliveryId`], and the
+//! intent to deliver the message is persisted to the actor's journal before the message is sent.
+//! The message is redelivered, every [`DeliveryState::with_redeliver_interval`], until the actor
+//! calls [`AtLeastOnceDelivery::confirm_delivery`], typically once the destination has replied
+//! with a confirmation carrying the delivery id. Confirmations are also persisted, so when the
+//! actor is recovered, any deliveries that were never confirmed are redelivered.
+//!
+//! Once a message has been delivered [`DeliveryState::with_warn_after_attempts`] times without
+//! being confirmed, [`AtLeastOnceDelivery::on_unconfirmed`] is called with an
+//! [`UnconfirmedWarning`], the message continues to be redelivered until it's confirmed.
+//!
+//! Destinations are addressed by [`ActorId`], and resolved on each attempt, so messages still
+//! reach the destination if it has been restarted, or moved to another node. Destinations can
+//! receive the same message more than once, so should de-duplicate using the delivery id.
+//!
+//! Each message type delivered must be registered via [`JournalTypes::delivery`]. Deliveries are
+//! recovered from the actor's journal, not its snapshots, so actors using at-least-once delivery
+//! shouldn't remove messages from their journal when taking a snapshot.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! struct OrderProcess {
+//!     deliveries: DeliveryState,
+//! }
+//!
+//! impl PersistentActor for OrderProcess {
+//!     fn configure(journal: &mut JournalTypes<Self>) {
+//!         journal.delivery::<PaymentService, TakePayment>("take-payment");
+//!     }
+//! }
+//!
+//! impl AtLeastOnceDelivery for OrderProcess {
+//!     fn delivery_state(&mut self) -> &mut DeliveryState {
+//!         &mut self.deliveries
+//!     }
+//! }
+//!
+//! impl Handler<PlaceOrder> for OrderProcess {
+//!     async fn handle(&mut self, message: PlaceOrder, ctx: &mut ActorContext) {
+//!         self.deliver::<PaymentService, _>("payments", |delivery_id| TakePayment {
+//!             delivery_id,
+//!             amount: message.amount,
+//!         }, ctx).await.unwrap();
+//!     }
+//! }
+//!
+//! impl Handler<PaymentTaken> for OrderProcess {
+//!     async fn handle(&mut self, message: PaymentTaken, ctx: &mut ActorContext) {
+//!         self.confirm_delivery(message.delivery_id, ctx).await.unwrap();
+//!     }
+//! }
+//! ```
+//!
+//! [`JournalTypes::delivery`]: crate::persistent::journal::types::JournalTypes::delivery
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use crate::actor::scheduler::timer::{Timer, TimerTick};
+use crate::actor::system::ActorSystem;
+use crate::actor::{Actor, ActorId, ActorRef, ActorRefErr, IntoActorId};
+use crate::persistent::journal::types::JournalTypes;
+use crate::persistent::journal::PersistErr;
+use crate::persistent::{PersistentActor, Recover};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+const DELIVERY_CONFIRMED_TYPE: &str = "coerce.DeliveryConfirmed";
+
+pub type DeliveryId = u64;
+
+/// A persistent actor which delivers messages to other actors at least once,
+/// see [`delivery`](crate::persistent::delivery)
+#[async_trait]
+pub trait AtLeastOnceDelivery: PersistentActor {
+    fn delivery_state(&mut self) -> &mut DeliveryState;
+
+    /// Called once deliveries have been attempted [`DeliveryState::with_warn_after_attempts`]
+    /// times without being confirmed, each delivery is only included in a warning once
+    async fn on_unconfirmed(&mut self, warning: UnconfirmedWarning, ctx: &mut ActorContext) {
+        for delivery in &warning.deliveries {
+            warn!(
+                actor_id = ctx.id().as_ref(),
+                delivery_id = delivery.delivery_id,
+                destination = delivery.destination.as_ref(),
+                message_type = delivery.message_type,
+                attempts = delivery.attempts,
+                "delivery unconfirmed"
+            );
+        }
+    }
+
+    /// Persists the intent to deliver the message created by `message` to the actor `destination`,
+    /// and sends the message, returning the id assigned to the delivery. The message is redelivered
+    /// until [`AtLeastOnceDelivery::confirm_delivery`] is called with the returned id.
+    async fn deliver<D: Actor, M: Message>(
+        &mut self,
+        destination: impl IntoActorId + Send,
+        message: impl FnOnce(DeliveryId) -> M + Send,
+        ctx: &mut ActorContext,
+    ) -> Result<DeliveryId, PersistErr>
+    where
+        D: Handler<M>,
+    {
+        let delivery_id = self.delivery_state().next_delivery_id;
+        let bytes = message(delivery_id)
+            .as_bytes()
+            .map_err(PersistErr::Serialisation)?;
+
+        let intent = DeliveryIntent::<D, M>::new(delivery_id, destination.into_actor_id(), bytes);
+        self.persist(&intent, ctx).await?;
+
+        let system = ctx.system().clone();
+        let state = self.delivery_state();
+        state.next_delivery_id = delivery_id + 1;
+
+        let mut delivery = Unconfirmed::new(intent);
+        delivery.attempt(&system).await;
+        state.unconfirmed.insert(delivery_id, delivery);
+
+        if state.timer.is_none() {
+            state.timer = Some(Timer::start(
+                ctx.actor_ref::<Self>(),
+                state.redeliver_interval,
+                RedeliveryTick,
+            ));
+        }
+
+        Ok(delivery_id)
+    }
+
+    /// Persists the confirmation of the delivery, so it's no longer redelivered,
+    /// returns `false` if the delivery wasn't pending confirmation
+    async fn confirm_delivery(
+        &mut self,
+        delivery_id: DeliveryId,
+        ctx: &mut ActorContext,
+    ) -> Result<bool, PersistErr> {
+        if !self.delivery_state().unconfirmed.contains_key(&delivery_id) {
+            return Ok(false);
+        }
+
+        self.persist(&DeliveryConfirmed { delivery_id }, ctx)
+            .await?;
+        self.delivery_state().confirm(delivery_id);
+        Ok(true)
+    }
+}
+
+/// The deliveries of an [`AtLeastOnceDelivery`] actor, which are pending confirmation
+pub struct DeliveryState {
+    next_delivery_id: DeliveryId,
+    unconfirmed: BTreeMap<DeliveryId, Unconfirmed>,
+    redeliver_interval: Duration,
+    warn_after_attempts: usize,
+    timer: Option<Timer>,
+}
+
+impl Default for DeliveryState {
+    fn default() -> Self {
+        Self {
+            next_delivery_id: 1,
+            unconfirmed: BTreeMap::new(),
+            redeliver_interval: Duration::from_secs(5),
+            warn_after_attempts: 5,
+            timer: None,
+        }
+    }
+}
+
+impl DeliveryState {
+    /// How long to wait for a delivery to be confirmed before it's redelivered,
+    /// defaults to 5 seconds
+    pub fn with_redeliver_interval(mut self, redeliver_interval: Duration) -> Self {
+        self.redeliver_interval = redeliver_interval;
+        self
+    }
+
+    /// The number of attempts made to deliver a message, without it being confirmed, before it's
+    /// included in an [`UnconfirmedWarning`], defaults to 5
+    pub fn with_warn_after_attempts(mut self, warn_after_attempts: usize) -> Self {
+        self.warn_after_attempts = warn_after_attempts.max(1);
+        self
+    }
+
+    /// The deliveries which haven't been confirmed yet, ordered by delivery id
+    pub fn unconfirmed(&self) -> Vec<UnconfirmedDelivery> {
+        self.unconfirmed
+            .iter()
+            .map(|(delivery_id, delivery)| delivery.describe(*delivery_id))
+            .collect()
+    }
+
+    pub fn unconfirmed_count(&self) -> usize {
+        self.unconfirmed.len()
+    }
+
+    fn confirm(&mut self, delivery_id: DeliveryId) {
+        self.unconfirmed.remove(&delivery_id);
+        if self.unconfirmed.is_empty() {
+            if let Some(timer) = self.timer.take() {
+                timer.stop();
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UnconfirmedDelivery {
+    pub delivery_id: DeliveryId,
+    pub destination: ActorId,
+    pub message_type: &'static str,
+
+    /// The number of times the message has been delivered since the actor was started
+    pub attempts: usize,
+}
+
+/// The deliveries which have reached [`DeliveryState::with_warn_after_attempts`],
+/// without being confirmed
+#[derive(Clone, Debug)]
+pub struct UnconfirmedWarning {
+    pub deliveries: Vec<UnconfirmedDelivery>,
+}
+
+struct Unconfirmed {
+    delivery: Box<dyn Redeliver>,
+    attempts: usize,
+    last_attempt: Option<Instant>,
+    warned: bool,
+}
+
+impl Unconfirmed {
+    fn new<D: Actor + Handler<M>, M: Message>(intent: DeliveryIntent<D, M>) -> Self {
+        Self {
+            delivery: Box::new(intent),
+            attempts: 0,
+            last_attempt: None,
+            warned: false,
+        }
+    }
+
+    async fn attempt(&mut self, system: &ActorSystem) {
+        self.attempts += 1;
+        self.last_attempt = Some(Instant::now());
+
+        if let Err(e) = self.delivery.redeliver(system).await {
+            debug!(
+                destination = self.delivery.destination().as_ref(),
+                attempts = self.attempts,
+                error = format!("{}", e),
+                "delivery attempt failed"
+            );
+        }
+    }
+
+    fn describe(&self, delivery_id: DeliveryId) -> UnconfirmedDelivery {
+        UnconfirmedDelivery {
+            delivery_id,
+            destination: self.delivery.destination().clone(),
+            message_type: self.delivery.message_type(),
+            attempts: self.attempts,
+        }
+    }
+}
+
+#[async_trait]
+trait Redeliver: 'static + Send + Sync {
+    async fn redeliver(&self, system: &ActorSystem) -> Result<(), ActorRefErr>;
+
+    fn destination(&self) -> &ActorId;
+
+    fn message_type(&self) -> &'static str;
+}
+
+/// The intent to deliver a message of type `M` to an actor of type `D`, which is persisted
+/// to the journal of the delivering actor
+pub struct DeliveryIntent<D, M> {
+    delivery_id: DeliveryId,
+    destination: ActorId,
+    message: Vec<u8>,
+    _types: PhantomData<fn() -> (D, M)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredDeliveryIntent {
+    delivery_id: DeliveryId,
+    destination: String,
+    message: Vec<u8>,
+}
+
+impl<D, M> DeliveryIntent<D, M> {
+    fn new(delivery_id: DeliveryId, destination: ActorId, message: Vec<u8>) -> Self {
+        Self {
+            delivery_id,
+            destination,
+            message,
+            _types: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<D: Actor, M: Message> Redeliver for DeliveryIntent<D, M>
+where
+    D: Handler<M>,
+{
+    async fn redeliver(&self, system: &ActorSystem) -> Result<(), ActorRefErr> {
+        let message = M::from_bytes(self.message.clone()).map_err(ActorRefErr::Deserialisation)?;
+        let destination = resolve::<D>(system, self.destination.clone())
+            .await
+            .ok_or_else(|| ActorRefErr::NotFound(self.destination.clone()))?;
+
+        destination.notify(message).await
+    }
+
+    fn destination(&self) -> &ActorId {
+        &self.destination
+    }
+
+    fn message_type(&self) -> &'static str {
+        M::type_name()
+    }
+}
+
+async fn resolve<D: Actor>(system: &ActorSystem, actor_id: ActorId) -> Option<ActorRef<D>> {
+    #[cfg(feature = "remote")]
+    if system.is_remote() {
+        return system.remote().actor_ref::<D>(actor_id).await;
+    }
+
+    system
+        .get_tracked_actor::<D>(actor_id)
+        .await
+        .map(ActorRef::from)
+}
+
+impl<D: Actor, M: Message> Message for DeliveryIntent<D, M>
+where
+    D: Handler<M>,
+{
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&StoredDeliveryIntent {
+            delivery_id: self.delivery_id,
+            destination: self.destination.to_string(),
+            message: self.message.clone(),
+        })
+        .map_err(|_| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        let stored: StoredDeliveryIntent =
+            serde_json::from_slice(&bytes).map_err(|_| MessageUnwrapErr::DeserializationErr)?;
+
+        Ok(Self::new(
+            stored.delivery_id,
+            stored.destination.into_actor_id(),
+            stored.message,
+        ))
+    }
+}
+
+/// A delivery was confirmed, persisted by [`AtLeastOnceDelivery::confirm_delivery`]
+#[derive(Serialize, Deserialize)]
+pub struct DeliveryConfirmed {
+    delivery_id: DeliveryId,
+}
+
+impl Message for DeliveryConfirmed {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(self).map_err(|_| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&bytes).map_err(|_| MessageUnwrapErr::DeserializationErr)
+    }
+}
+
+#[async_trait]
+impl<A: AtLeastOnceDelivery, D: Actor, M: Message> Recover<DeliveryIntent<D, M>> for A
+where
+    D: Handler<M>,
+{
+    async fn recover(&mut self, intent: DeliveryIntent<D, M>, ctx: &mut ActorContext) {
+        let state = self.delivery_state();
+        let delivery_id = intent.delivery_id;
+        state.next_delivery_id = state.next_delivery_id.max(delivery_id + 1);
+        state
+            .unconfirmed
+            .insert(delivery_id, Unconfirmed::new(intent));
+
+        // recovered deliveries are redelivered once the actor has started
+        if state.timer.is_none() {
+            state.timer = Some(Timer::start_immediately(
+                ctx.actor_ref::<Self>(),
+                state.redeliver_interval,
+                RedeliveryTick,
+            ));
+        }
+    }
+}
+
+#[async_trait]
+impl<A: AtLeastOnceDelivery> Recover<DeliveryConfirmed> for A {
+    async fn recover(&mut self, message: DeliveryConfirmed, _ctx: &mut ActorContext) {
+        self.delivery_state().confirm(message.delivery_id);
+    }
+}
+
+/// Redelivers any deliveries which haven't been confirmed within the redelivery interval
+#[derive(Clone)]
+pub struct RedeliveryTick;
+
+impl Message for RedeliveryTick {
+    type Result = ();
+}
+
+impl TimerTick for RedeliveryTick {}
+
+#[async_trait]
+impl<A: AtLeastOnceDelivery> Handler<RedeliveryTick> for A {
+    async fn handle(&mut self, _message: RedeliveryTick, ctx: &mut ActorContext) {
+        let system = ctx.system().clone();
+        let state = self.delivery_state();
+        let redeliver_interval = state.redeliver_interval;
+        let warn_after_attempts = state.warn_after_attempts;
+
+        let mut warnings = vec![];
+        for (delivery_id, delivery) in state.unconfirmed.iter_mut() {
+            let due = delivery
+                .last_attempt
+                .is_none_or(|last_attempt| last_attempt.elapsed() >= redeliver_interval);
+
+            if !due {
+                continue;
+            }
+
+            delivery.attempt(&system).await;
+
+            if delivery.attempts >= warn_after_attempts && !delivery.warned {
+                delivery.warned = true;
+                warnings.push(delivery.describe(*delivery_id));
+            }
+        }
+
+        if !warnings.is_empty() {
+            self.on_unconfirmed(
+                UnconfirmedWarning {
+                    deliveries: warnings,
+                },
+                ctx,
+            )
+            .await;
+        }
+    }
+}
+
+impl<A: AtLeastOnceDelivery> JournalTypes<A> {
+    /// Registers the delivery of messages of type `M` to actors of type `D`, via
+    /// [`AtLeastOnceDelivery::deliver`], see [`delivery`](crate::persistent::delivery)
+    pub fn delivery<D: Actor + Handler<M>, M: Message>(&mut self, identifier: &str) -> &mut Self {
+        self.message::<DeliveryIntent<D, M>>(identifier)
+            .message::<DeliveryConfirmed>(DELIVERY_CONFIRMED_TYPE)
+    }
+}
@@ -0,0 +1,104 @@
+//! Event adapters, for evolving the schema of persisted events
+//!
+//! Events are persisted with the identifier their type was registered with, via
+//! [`JournalTypes::message`], alongside their serialised bytes. Once an event's structure
+//! changes, the events already in the journal can no longer be deserialised as the new type.
+//!
+//! An [`EventAdapter`] registered for an event identifier, via [`JournalTypes::event_adapter`],
+//! sits between the persisted bytes and the recovered event. During recovery, each event with an
+//! adapter is passed through it, and the adapted event is recovered in its place. Adapted events
+//! are passed to the adapter registered for their new identifier, if there is one, so adapters can
+//! be chained, each upcasting the event by a single version.
+//!
+//! [`JournalTypes::upcast_event`] registers an adapter that deserialises the old event type,
+//! converts it and serialises it as the new event type:
+//!
+//! ```rust,compile_fail
+//! fn configure(journal: &mut JournalTypes<Self>) {
+//!     journal
+//!         .message::<OrderCreated>("order-created.v2")
+//!         .upcast_event::<OrderCreatedV1, OrderCreated>("order-created", |v1| OrderCreated {
+//!             order_id: v1.order_id,
+//!             currency: "GBP".to_string(),
+//!         });
+//! }
+//! ```
+//!
+//! Adapters are only applied during recovery, events are always persisted as their current type.
+//!
+//! [`JournalTypes::message`]: crate::persistent::journal::types::JournalTypes::message
+//! [`JournalTypes::event_adapter`]: crate::persistent::journal::types::JournalTypes::event_adapter
+//! [`JournalTypes::upcast_event`]: crate::persistent::journal::types::JournalTypes::upcast_event
+
+use crate::actor::message::Message;
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::storage::JournalEntry;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// An event as it was read from the journal, before it's recovered
+#[derive(Clone, Debug)]
+pub struct PersistedEvent {
+    /// The identifier of the event's type
+    pub payload_type: Arc<str>,
+    pub bytes: Vec<u8>,
+    pub metadata: EventMetadata,
+}
+
+/// Adapts a persisted event, typically into a newer version of the event,
+/// see [`adapter`](crate::persistent::journal::adapter)
+pub trait EventAdapter: 'static + Send + Sync {
+    fn adapt(&self, event: PersistedEvent) -> anyhow::Result<PersistedEvent>;
+}
+
+impl<F> EventAdapter for F
+where
+    F: Fn(PersistedEvent) -> anyhow::Result<PersistedEvent> + 'static + Send + Sync,
+{
+    fn adapt(&self, event: PersistedEvent) -> anyhow::Result<PersistedEvent> {
+        self(event)
+    }
+}
+
+/// Deserialises the event as `From`, and serialises the converted event as `To`
+pub(crate) struct Upcaster<From, To, F> {
+    payload_type: Arc<str>,
+    upcast: F,
+    _types: PhantomData<fn(From) -> To>,
+}
+
+impl<From, To, F> Upcaster<From, To, F> {
+    pub fn new(payload_type: Arc<str>, upcast: F) -> Self {
+        Self {
+            payload_type,
+            upcast,
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<From: Message, To: Message, F> EventAdapter for Upcaster<From, To, F>
+where
+    F: Fn(From) -> To + 'static + Send + Sync,
+{
+    fn adapt(&self, event: PersistedEvent) -> anyhow::Result<PersistedEvent> {
+        let from = From::from_bytes(event.bytes)?;
+        let to = (self.upcast)(from);
+
+        Ok(PersistedEvent {
+            payload_type: self.payload_type.clone(),
+            bytes: to.as_bytes()?,
+            metadata: event.metadata,
+        })
+    }
+}
+
+impl From<JournalEntry> for PersistedEvent {
+    fn from(entry: JournalEntry) -> Self {
+        Self {
+            payload_type: entry.payload_type,
+            bytes: Arc::try_unwrap(entry.bytes).unwrap_or_else(|bytes| bytes.as_ref().clone()),
+            metadata: entry.metadata,
+        }
+    }
+}
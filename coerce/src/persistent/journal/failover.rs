@@ -0,0 +1,542 @@
+//! Journal provider failover
+//!
+//! [`FailoverStorageProvider`] wraps a primary and a secondary [`StorageProvider`]. The primary is
+//! probed periodically via [`JournalStorage::health_check`], and once it has failed
+//! `failure_threshold` consecutive times (counting both probes and failed journal operations),
+//! it is considered unavailable and the configured [`UnavailablePolicy`] is applied:
+//!
+//! - [`UnavailablePolicy::FailFast`]: reads and writes fail immediately with
+//!   [`FailoverErr::PrimaryUnavailable`], until the primary recovers.
+//! - [`UnavailablePolicy::Queue`]: writes are queued in memory and flushed to the primary, in
+//!   order, once it recovers. Reads fail immediately.
+//! - [`UnavailablePolicy::Failover`]: all reads and writes are sent to the secondary provider.
+//!   This remains the case even after the primary recovers, until [`fail_back`] is called.
+//!   Entries written to the secondary are not copied back to the primary.
+//!
+//! State changes are logged and published as [`FailoverEvent`]s, see [`subscribe`].
+//!
+//! Persistent actors can be configured to use a failover provider individually, using
+//! [`Persistence::actor_provider`].
+//!
+//! [`fail_back`]: FailoverStorageProvider::fail_back
+//! [`subscribe`]: FailoverStorageProvider::subscribe
+//! [`Persistence::actor_provider`]: crate::persistent::Persistence::actor_provider
+
+use crate::persistent::journal::provider::StorageProvider;
+use crate::persistent::journal::storage::{
+    JournalEntry, JournalStorage, JournalStorageRef, TaggedEntry, UnsupportedQuery,
+};
+use anyhow::Result;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+const FAILOVER_EVENT_CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Clone, Debug)]
+pub struct FailoverConfig {
+    /// How often the primary provider is probed
+    pub probe_interval: Duration,
+
+    /// Number of consecutive failures before the primary is considered unavailable
+    pub failure_threshold: usize,
+
+    /// Number of consecutive successful probes before an unavailable primary is considered
+    /// recovered
+    pub recovery_threshold: usize,
+
+    /// What happens to reads and writes while the primary is unavailable
+    pub policy: UnavailablePolicy,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UnavailablePolicy {
+    /// Fail reads and writes immediately
+    FailFast,
+
+    /// Queue up to `max_queued` writes until the primary recovers, reads fail immediately
+    Queue { max_queued: usize },
+
+    /// Send all reads and writes to the secondary provider
+    Failover,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProviderStatus {
+    /// Reads and writes are sent to the primary provider
+    Healthy,
+
+    /// The primary provider is unavailable, reads and writes are handled according to the
+    /// configured [`UnavailablePolicy`]
+    Unavailable,
+
+    /// Reads and writes are sent to the secondary provider
+    FailedOver,
+}
+
+#[derive(Clone, Debug)]
+pub enum FailoverEvent {
+    PrimaryUnavailable {
+        consecutive_failures: usize,
+        error: String,
+    },
+    PrimaryRecovered,
+    FailedOver,
+    FailedBack,
+    QueueFlushed {
+        writes: usize,
+    },
+}
+
+#[derive(Debug)]
+pub enum FailoverErr {
+    PrimaryUnavailable,
+    QueueFull { max_queued: usize },
+}
+
+#[derive(Clone)]
+pub struct FailoverStorageProvider {
+    storage: Arc<FailoverJournalStorage>,
+}
+
+struct FailoverJournalStorage {
+    primary: JournalStorageRef,
+    secondary: JournalStorageRef,
+    config: FailoverConfig,
+    health: Mutex<HealthState>,
+    queue: tokio::sync::Mutex<VecDeque<QueuedWrite>>,
+    events: broadcast::Sender<FailoverEvent>,
+    monitor_started: AtomicBool,
+}
+
+struct HealthState {
+    primary_healthy: bool,
+    failed_over: bool,
+    consecutive_failures: usize,
+    consecutive_successes: usize,
+}
+
+enum QueuedWrite {
+    Snapshot(String, JournalEntry),
+    Message(String, JournalEntry),
+    Batch(String, Vec<JournalEntry>),
+    DeleteTo(String, i64),
+    DeleteSnapshotsTo(String, i64),
+    DeleteAll(String),
+}
+
+enum Route {
+    Primary,
+    Secondary,
+    Unavailable,
+}
+
+impl FailoverStorageProvider {
+    pub fn new(
+        primary: impl StorageProvider,
+        secondary: impl StorageProvider,
+        config: FailoverConfig,
+    ) -> Self {
+        let (events, _) = broadcast::channel(FAILOVER_EVENT_CHANNEL_CAPACITY);
+        let storage = Arc::new(FailoverJournalStorage {
+            primary: primary.journal_storage().expect("primary journal storage"),
+            secondary: secondary
+                .journal_storage()
+                .expect("secondary journal storage"),
+            config,
+            health: Mutex::new(HealthState {
+                primary_healthy: true,
+                failed_over: false,
+                consecutive_failures: 0,
+                consecutive_successes: 0,
+            }),
+            queue: tokio::sync::Mutex::new(VecDeque::new()),
+            events,
+            monitor_started: AtomicBool::new(false),
+        });
+
+        Self { storage }
+    }
+
+    /// Subscribes to all failover events published after the point of subscription
+    pub fn subscribe(&self) -> broadcast::Receiver<FailoverEvent> {
+        self.storage.events.subscribe()
+    }
+
+    pub fn status(&self) -> ProviderStatus {
+        let health = self.storage.health.lock();
+        if health.failed_over {
+            ProviderStatus::FailedOver
+        } else if health.primary_healthy {
+            ProviderStatus::Healthy
+        } else {
+            ProviderStatus::Unavailable
+        }
+    }
+
+    /// Moves reads and writes back to the primary provider, returns false if the provider
+    /// hasn't failed over, or if the primary is still unavailable
+    pub fn fail_back(&self) -> bool {
+        let mut health = self.storage.health.lock();
+        if !health.failed_over || !health.primary_healthy {
+            return false;
+        }
+
+        health.failed_over = false;
+        drop(health);
+
+        info!("journal storage failed back to primary provider");
+        self.storage.publish(FailoverEvent::FailedBack);
+        true
+    }
+
+    /// Returns the number of writes waiting for the primary to recover
+    pub async fn queued_writes(&self) -> usize {
+        self.storage.queue.lock().await.len()
+    }
+}
+
+impl StorageProvider for FailoverStorageProvider {
+    fn journal_storage(&self) -> Option<JournalStorageRef> {
+        if tokio::runtime::Handle::try_current().is_ok()
+            && !self.storage.monitor_started.swap(true, Ordering::Relaxed)
+        {
+            tokio::spawn(health_monitor(Arc::downgrade(&self.storage)));
+        }
+
+        Some(self.storage.clone())
+    }
+}
+
+async fn health_monitor(storage: Weak<FailoverJournalStorage>) {
+    loop {
+        let probe_interval = match storage.upgrade() {
+            Some(storage) => {
+                match storage.primary.health_check().await {
+                    Ok(_) => storage.probe_succeeded().await,
+                    Err(e) => storage.record_failure(&e),
+                }
+
+                storage.config.probe_interval
+            }
+            None => break,
+        };
+
+        tokio::time::sleep(probe_interval).await;
+    }
+}
+
+impl FailoverJournalStorage {
+    fn publish(&self, event: FailoverEvent) {
+        let _ = self.events.send(event);
+    }
+
+    fn route(&self) -> Route {
+        let health = self.health.lock();
+        if health.failed_over {
+            Route::Secondary
+        } else if health.primary_healthy {
+            Route::Primary
+        } else {
+            Route::Unavailable
+        }
+    }
+
+    fn record_success(&self) {
+        self.health.lock().consecutive_failures = 0;
+    }
+
+    fn record_failure(&self, error: &anyhow::Error) {
+        let mut health = self.health.lock();
+        health.consecutive_successes = 0;
+        health.consecutive_failures += 1;
+
+        if !health.primary_healthy || health.consecutive_failures < self.config.failure_threshold {
+            return;
+        }
+
+        health.primary_healthy = false;
+
+        let consecutive_failures = health.consecutive_failures;
+        let failed_over = !health.failed_over && self.config.policy == UnavailablePolicy::Failover;
+        if failed_over {
+            health.failed_over = true;
+        }
+
+        drop(health);
+
+        warn!(
+            consecutive_failures = consecutive_failures,
+            "primary journal storage unavailable, error: {}", error
+        );
+
+        self.publish(FailoverEvent::PrimaryUnavailable {
+            consecutive_failures,
+            error: error.to_string(),
+        });
+
+        if failed_over {
+            warn!("journal storage failed over to secondary provider");
+            self.publish(FailoverEvent::FailedOver);
+        }
+    }
+
+    async fn probe_succeeded(&self) {
+        {
+            let mut health = self.health.lock();
+            health.consecutive_failures = 0;
+
+            if health.primary_healthy {
+                return;
+            }
+
+            health.consecutive_successes += 1;
+            if health.consecutive_successes < self.config.recovery_threshold {
+                return;
+            }
+        }
+
+        // Queued writes are flushed while holding the queue lock, and the primary is only marked
+        // as healthy once they have all been written, so new writes can't overtake queued ones.
+        let mut queue = self.queue.lock().await;
+        let mut writes = 0;
+        while let Some(write) = queue.pop_front() {
+            if let Err(e) = write.apply(self.primary.as_ref()).await {
+                queue.push_front(write);
+                drop(queue);
+
+                warn!(
+                    "failed to flush queued journal writes to primary provider, error: {}",
+                    &e
+                );
+
+                self.record_failure(&e);
+                return;
+            }
+
+            writes += 1;
+        }
+
+        {
+            let mut health = self.health.lock();
+            health.primary_healthy = true;
+            health.consecutive_successes = 0;
+        }
+
+        drop(queue);
+
+        info!("primary journal storage recovered");
+        self.publish(FailoverEvent::PrimaryRecovered);
+
+        if writes > 0 {
+            info!(
+                writes = writes,
+                "flushed queued journal writes to primary provider"
+            );
+            self.publish(FailoverEvent::QueueFlushed { writes });
+        }
+    }
+
+    async fn write(&self, write: QueuedWrite) -> Result<()> {
+        if let UnavailablePolicy::Queue { max_queued } = self.config.policy {
+            let mut queue = self.queue.lock().await;
+            if let Route::Unavailable = self.route() {
+                if queue.len() >= max_queued {
+                    return Err(FailoverErr::QueueFull { max_queued }.into());
+                }
+
+                queue.push_back(write);
+                return Ok(());
+            }
+        }
+
+        match self.route() {
+            Route::Primary => {
+                let result = write.apply(self.primary.as_ref()).await;
+                self.record_primary_result(&result);
+                result
+            }
+            Route::Secondary => write.apply(self.secondary.as_ref()).await,
+            Route::Unavailable => Err(FailoverErr::PrimaryUnavailable.into()),
+        }
+    }
+
+    fn record_primary_result<T>(&self, result: &Result<T>) {
+        match result {
+            Ok(_) => self.record_success(),
+
+            // the primary doesn't support the query, which says nothing about its health
+            Err(e) if e.is::<UnsupportedQuery>() => {}
+
+            Err(e) => self.record_failure(e),
+        }
+    }
+}
+
+macro_rules! read {
+    ($self:ident, $storage:ident => $read:expr) => {
+        match $self.route() {
+            Route::Primary => {
+                let $storage = &$self.primary;
+                let result = $read.await;
+                $self.record_primary_result(&result);
+                result
+            }
+            Route::Secondary => {
+                let $storage = &$self.secondary;
+                $read.await
+            }
+            Route::Unavailable => Err(FailoverErr::PrimaryUnavailable.into()),
+        }
+    };
+}
+
+#[async_trait]
+impl JournalStorage for FailoverJournalStorage {
+    async fn write_snapshot(&self, persistence_id: &str, entry: JournalEntry) -> Result<()> {
+        self.write(QueuedWrite::Snapshot(persistence_id.to_string(), entry))
+            .await
+    }
+
+    async fn write_message(&self, persistence_id: &str, entry: JournalEntry) -> Result<()> {
+        self.write(QueuedWrite::Message(persistence_id.to_string(), entry))
+            .await
+    }
+
+    async fn write_message_batch(
+        &self,
+        persistence_id: &str,
+        entries: Vec<JournalEntry>,
+    ) -> Result<()> {
+        self.write(QueuedWrite::Batch(persistence_id.to_string(), entries))
+            .await
+    }
+
+    async fn read_latest_snapshot(&self, persistence_id: &str) -> Result<Option<JournalEntry>> {
+        read!(self, storage => storage.read_latest_snapshot(persistence_id))
+    }
+
+    async fn read_latest_messages(
+        &self,
+        persistence_id: &str,
+        from_sequence: i64,
+    ) -> Result<Option<Vec<JournalEntry>>> {
+        read!(self, storage => storage.read_latest_messages(persistence_id, from_sequence))
+    }
+
+    async fn read_message(
+        &self,
+        persistence_id: &str,
+        sequence_id: i64,
+    ) -> Result<Option<JournalEntry>> {
+        read!(self, storage => storage.read_message(persistence_id, sequence_id))
+    }
+
+    async fn read_messages(
+        &self,
+        persistence_id: &str,
+        from_sequence: i64,
+        to_sequence: i64,
+    ) -> Result<Option<Vec<JournalEntry>>> {
+        read!(self, storage => storage.read_messages(persistence_id, from_sequence, to_sequence))
+    }
+
+    async fn delete_messages_to(&self, persistence_id: &str, to_sequence: i64) -> Result<()> {
+        self.write(QueuedWrite::DeleteTo(
+            persistence_id.to_string(),
+            to_sequence,
+        ))
+        .await
+    }
+
+    async fn delete_snapshots_to(&self, persistence_id: &str, to_sequence: i64) -> Result<()> {
+        self.write(QueuedWrite::DeleteSnapshotsTo(
+            persistence_id.to_string(),
+            to_sequence,
+        ))
+        .await
+    }
+
+    async fn delete_all(&self, persistence_id: &str) -> Result<()> {
+        self.write(QueuedWrite::DeleteAll(persistence_id.to_string()))
+            .await
+    }
+
+    async fn read_messages_by_tag(
+        &self,
+        tag: &str,
+        from_offset: i64,
+        limit: usize,
+    ) -> Result<Vec<TaggedEntry>> {
+        read!(self, storage => storage.read_messages_by_tag(tag, from_offset, limit))
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        match self.route() {
+            Route::Primary => self.primary.health_check().await,
+            Route::Secondary => self.secondary.health_check().await,
+            Route::Unavailable => Err(FailoverErr::PrimaryUnavailable.into()),
+        }
+    }
+}
+
+impl QueuedWrite {
+    async fn apply(&self, storage: &dyn JournalStorage) -> Result<()> {
+        match self {
+            QueuedWrite::Snapshot(persistence_id, entry) => {
+                storage.write_snapshot(persistence_id, entry.clone()).await
+            }
+            QueuedWrite::Message(persistence_id, entry) => {
+                storage.write_message(persistence_id, entry.clone()).await
+            }
+            QueuedWrite::Batch(persistence_id, entries) => {
+                storage
+                    .write_message_batch(persistence_id, entries.clone())
+                    .await
+            }
+            QueuedWrite::DeleteTo(persistence_id, to_sequence) => {
+                storage
+                    .delete_messages_to(persistence_id, *to_sequence)
+                    .await
+            }
+            QueuedWrite::DeleteSnapshotsTo(persistence_id, to_sequence) => {
+                storage
+                    .delete_snapshots_to(persistence_id, *to_sequence)
+                    .await
+            }
+            QueuedWrite::DeleteAll(persistence_id) => storage.delete_all(persistence_id).await,
+        }
+    }
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            probe_interval: Duration::from_secs(1),
+            failure_threshold: 3,
+            recovery_threshold: 2,
+            policy: UnavailablePolicy::Failover,
+        }
+    }
+}
+
+impl Display for FailoverErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailoverErr::PrimaryUnavailable => {
+                write!(f, "primary journal storage is unavailable")
+            }
+            FailoverErr::QueueFull { max_queued } => write!(
+                f,
+                "primary journal storage is unavailable and the write queue is full (max_queued={})",
+                max_queued
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FailoverErr {}
@@ -0,0 +1,130 @@
+//! Metadata persisted alongside journal entries
+//!
+//! Every [`JournalEntry`] carries [`EventMetadata`], recording when the entry was written, along
+//! with optional correlation and causation ids, tags and any custom headers provided when the
+//! event was persisted, via [`PersistentActor::persist_with_metadata`] or
+//! [`EventBatch::message_with_metadata`].
+//!
+//! The metadata is stored by the journal storage provider alongside the entry, and can be read back
+//! via [`PersistentActor::read_messages`], so tooling such as audit logs or tracing can follow
+//! the chain of events that led to an entry being written.
+//!
+//! [`JournalEntry`]: crate::persistent::journal::storage::JournalEntry
+//! [`PersistentActor::persist_with_metadata`]: crate::persistent::PersistentActor::persist_with_metadata
+//! [`PersistentActor::read_messages`]: crate::persistent::PersistentActor::read_messages
+//! [`EventBatch::message_with_metadata`]: crate::persistent::batch::EventBatch::message_with_metadata
+
+use crate::persistent::journal::proto::journal::EventMetadata as ProtoEventMetadata;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EventMetadata {
+    /// Unix timestamp, in milliseconds, of when the entry was written.
+    /// `None` until the entry is written, or if it was written before metadata was recorded
+    pub timestamp: Option<u64>,
+
+    /// Identifies the request or workflow the event was persisted as part of
+    pub correlation_id: Option<String>,
+
+    /// Identifies the message or event that caused the event to be persisted
+    pub causation_id: Option<String>,
+
+    pub headers: HashMap<String, String>,
+
+    /// Tags the event can be queried by, across every persistent actor, via
+    /// [`ReadJournal::events_by_tag`]
+    ///
+    /// [`ReadJournal::events_by_tag`]: crate::persistent::query::ReadJournal::events_by_tag
+    pub tags: Vec<String>,
+}
+
+impl EventMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    pub fn with_causation_id(mut self, causation_id: impl Into<String>) -> Self {
+        self.causation_id = Some(causation_id.into());
+        self
+    }
+
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.has_tag(&tag) {
+            self.tags.push(tag);
+        }
+
+        self
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers.get(key).map(|v| v.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamp.is_none()
+            && self.correlation_id.is_none()
+            && self.causation_id.is_none()
+            && self.headers.is_empty()
+            && self.tags.is_empty()
+    }
+
+    /// Sets the write timestamp to now, unless one has already been set
+    pub(crate) fn stamp(mut self) -> Self {
+        if self.timestamp.is_none() {
+            self.timestamp = Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_millis() as u64),
+            );
+        }
+
+        self
+    }
+}
+
+impl From<ProtoEventMetadata> for EventMetadata {
+    fn from(metadata: ProtoEventMetadata) -> Self {
+        let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+
+        Self {
+            timestamp: if metadata.timestamp > 0 {
+                Some(metadata.timestamp)
+            } else {
+                None
+            },
+            correlation_id: non_empty(metadata.correlation_id),
+            causation_id: non_empty(metadata.causation_id),
+            headers: metadata.headers,
+            tags: metadata.tags,
+        }
+    }
+}
+
+impl From<&EventMetadata> for ProtoEventMetadata {
+    fn from(metadata: &EventMetadata) -> Self {
+        Self {
+            timestamp: metadata.timestamp.unwrap_or_default(),
+            correlation_id: metadata.correlation_id.clone().unwrap_or_default(),
+            causation_id: metadata.causation_id.clone().unwrap_or_default(),
+            headers: metadata.headers.clone(),
+            tags: metadata.tags.clone(),
+            ..Default::default()
+        }
+    }
+}
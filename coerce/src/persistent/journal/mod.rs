@@ -1,11 +1,16 @@
+pub mod adapter;
+pub mod failover;
+pub mod metadata;
 pub mod provider;
 pub mod snapshot;
 pub mod storage;
+pub mod tck;
 pub mod types;
 
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Message, MessageUnwrapErr, MessageWrapErr};
-use crate::persistent::journal::snapshot::Snapshot;
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::snapshot::{Snapshot, SnapshotEnvelope, SnapshotPolicy};
 use crate::persistent::journal::storage::{JournalEntry, JournalStorageRef};
 use crate::persistent::journal::types::{init_journal_types, JournalTypes};
 use crate::persistent::{PersistentActor, Recover, RecoverSnapshot};
@@ -26,6 +31,8 @@ pub struct Journal<A: PersistentActor> {
     persistence_id: String,
     last_sequence_id: i64,
     last_snapshot_sequence_id: Option<i64>,
+    snapshot_sequence_ids: Vec<i64>,
+    snapshot_requested: bool,
     storage: JournalStorageRef,
     types: Arc<JournalTypes<A>>,
 }
@@ -39,6 +46,8 @@ impl<A: PersistentActor> Journal<A> {
             persistence_id,
             last_sequence_id,
             last_snapshot_sequence_id,
+            snapshot_sequence_ids: vec![],
+            snapshot_requested: false,
             storage,
             types,
         }
@@ -51,6 +60,19 @@ impl<A: PersistentActor> Journal<A> {
     pub fn last_sequence_id(&self) -> i64 {
         self.last_sequence_id
     }
+
+    /// The number of messages persisted since the latest snapshot
+    pub fn events_since_snapshot(&self) -> i64 {
+        self.last_sequence_id - self.last_snapshot_sequence_id.unwrap_or(0)
+    }
+
+    pub(crate) fn is_snapshot_requested(&self) -> bool {
+        self.snapshot_requested
+    }
+
+    pub(crate) fn set_snapshot_requested(&mut self, snapshot_requested: bool) {
+        self.snapshot_requested = snapshot_requested;
+    }
 }
 
 type RecoveryHandlerRef<A> = Arc<dyn RecoveryHandler<A>>;
@@ -70,6 +92,22 @@ pub enum RecoveryErr {
         actor_type: &'static str,
     },
 
+    /// The snapshot's schema version couldn't be upcast to the current version,
+    /// see [`snapshot`](crate::persistent::journal::snapshot)
+    SnapshotSchema {
+        snapshot_type: String,
+        schema_version: u32,
+        current_schema_version: u32,
+        error: anyhow::Error,
+    },
+
+    /// A persisted event couldn't be adapted, see [`adapter`](crate::persistent::journal::adapter)
+    EventAdapter {
+        event_type: String,
+        message_sequence_id: i64,
+        error: anyhow::Error,
+    },
+
     Snapshot(anyhow::Error),
     Messages(anyhow::Error),
 }
@@ -103,6 +141,23 @@ impl Display for RecoveryErr {
                 )
             }
 
+            RecoveryErr::SnapshotSchema {
+                snapshot_type,
+                schema_version,
+                current_schema_version,
+                error,
+            } => {
+                write!(f, "Snapshot schema error, snapshot_type={snapshot_type}, schema_version={schema_version}, current_schema_version={current_schema_version}, error: {error}")
+            }
+
+            RecoveryErr::EventAdapter {
+                event_type,
+                message_sequence_id,
+                error,
+            } => {
+                write!(f, "Event adapter error, event_type={event_type}, sequence_id={message_sequence_id}, error: {error}")
+            }
+
             RecoveryErr::Snapshot(e) => {
                 write!(f, "Snapshot recovery error: {error}", error = e)
             }
@@ -215,6 +270,7 @@ impl<A: PersistentActor> Journal<A> {
                     sequence: sequence_id,
                     payload_type: e.payload_type.clone(),
                     bytes: e.bytes.clone(),
+                    metadata: e.metadata.clone().stamp(),
                 }
             })
             .collect();
@@ -251,7 +307,11 @@ impl<A: PersistentActor> Journal<A> {
         }
     }
 
-    pub async fn persist_message<M: Message>(&mut self, bytes: BytesRef) -> Result<(), PersistErr>
+    pub async fn persist_message<M: Message>(
+        &mut self,
+        bytes: BytesRef,
+        metadata: EventMetadata,
+    ) -> Result<(), PersistErr>
     where
         A: Recover<M>,
     {
@@ -266,13 +326,15 @@ impl<A: PersistentActor> Journal<A> {
             .message_type_mapping::<M>()
             .expect("message type not configured");
 
+        let sequence = self.last_sequence_id + 1;
         self.storage
             .write_message(
                 &self.persistence_id,
                 JournalEntry {
-                    sequence: self.last_sequence_id,
+                    sequence,
                     payload_type: payload_type.clone(),
                     bytes,
+                    metadata: metadata.stamp(),
                 },
             )
             .await?;
@@ -283,7 +345,7 @@ impl<A: PersistentActor> Journal<A> {
             M::type_name()
         );
 
-        self.last_sequence_id += 1;
+        self.last_sequence_id = sequence;
         Ok(())
     }
 
@@ -302,6 +364,7 @@ impl<A: PersistentActor> Journal<A> {
             .expect("snapshot type not configured");
 
         let sequence = self.last_sequence_id + 1;
+        let bytes = SnapshotEnvelope::new::<S>(bytes.as_ref().clone()).into_bytes();
 
         self.storage
             .write_snapshot(
@@ -309,13 +372,50 @@ impl<A: PersistentActor> Journal<A> {
                 JournalEntry {
                     sequence,
                     payload_type,
-                    bytes,
+                    bytes: Arc::new(bytes),
+                    metadata: EventMetadata::default().stamp(),
                 },
             )
             .await?;
 
         self.last_sequence_id = sequence;
         self.last_snapshot_sequence_id = Some(sequence);
+        self.snapshot_sequence_ids.push(sequence);
+        Ok(())
+    }
+
+    /// Deletes the snapshots and messages that are no longer retained by the provided policy,
+    /// called once a snapshot has been persisted
+    pub async fn apply_snapshot_retention(
+        &mut self,
+        policy: &SnapshotPolicy,
+    ) -> anyhow::Result<()> {
+        let snapshot_sequence_id = match self.last_snapshot_sequence_id {
+            Some(sequence) => sequence,
+            None => return Ok(()),
+        };
+
+        if policy.delete_messages {
+            self.storage
+                .delete_messages_to(&self.persistence_id, snapshot_sequence_id)
+                .await?;
+        }
+
+        if let Some(keep_snapshots) = policy.keep_snapshots {
+            let snapshot_count = self.snapshot_sequence_ids.len();
+            if snapshot_count > keep_snapshots {
+                // only the snapshots recovered or persisted by this journal are known, deleting
+                // everything before the oldest kept snapshot also deletes any older snapshots
+                let oldest_kept = self.snapshot_sequence_ids[snapshot_count - keep_snapshots];
+                self.storage
+                    .delete_snapshots_to(&self.persistence_id, oldest_kept)
+                    .await?;
+
+                self.snapshot_sequence_ids
+                    .drain(..snapshot_count - keep_snapshots);
+            }
+        }
+
         Ok(())
     }
 
@@ -325,31 +425,82 @@ impl<A: PersistentActor> Journal<A> {
             .read_latest_snapshot(&self.persistence_id)
             .await?
         {
-            let handler = self
-                .types
-                .recoverable_snapshots()
-                .get(raw_snapshot.payload_type.as_ref());
+            self.last_snapshot_sequence_id = Some(raw_snapshot.sequence);
+            self.snapshot_sequence_ids = vec![raw_snapshot.sequence];
+            Ok(self.recover_snapshot_entry(raw_snapshot)?)
+        } else {
+            Ok(None)
+        }
+    }
 
-            let sequence = raw_snapshot.sequence;
-            let bytes =
-                Arc::try_unwrap(raw_snapshot.bytes).map_or_else(|e| e.as_ref().clone(), |s| s);
+    /// Recovers from a snapshot shipped from another node rather than from the storage backend,
+    /// messages persisted after the snapshot was taken are still recovered from storage.
+    ///
+    /// The shipped snapshot is not persisted, so old messages can't be cleared until the actor
+    /// persists a snapshot of its own.
+    pub fn recover_shipped_snapshot(
+        &mut self,
+        snapshot: JournalEntry,
+    ) -> Result<Option<RecoveredPayload<A>>, RecoveryErr> {
+        debug!(
+            "recovering from shipped snapshot (persistence_id={}), sequence={}",
+            &self.persistence_id, snapshot.sequence
+        );
 
-            self.last_sequence_id = sequence;
-            self.last_snapshot_sequence_id = Some(sequence);
+        self.recover_snapshot_entry(snapshot)
+    }
 
-            debug!(
-                "snapshot recovered (persistence_id={}), last sequence={}, type={}",
-                &self.persistence_id, &self.last_sequence_id, &raw_snapshot.payload_type
-            );
+    fn recover_snapshot_entry(
+        &mut self,
+        raw_snapshot: JournalEntry,
+    ) -> Result<Option<RecoveredPayload<A>>, RecoveryErr> {
+        let handler = self
+            .types
+            .recoverable_snapshots()
+            .get(raw_snapshot.payload_type.as_ref());
 
-            Ok(handler.map(|handler| RecoveredPayload {
-                bytes,
-                sequence,
-                handler: handler.clone(),
-            }))
-        } else {
-            Ok(None)
-        }
+        let sequence = raw_snapshot.sequence;
+        let bytes = Arc::try_unwrap(raw_snapshot.bytes).unwrap_or_else(|e| e.as_ref().clone());
+        let bytes = self.types.upcast_snapshot(
+            raw_snapshot.payload_type.as_ref(),
+            SnapshotEnvelope::from_bytes(bytes),
+        )?;
+
+        self.last_sequence_id = sequence;
+
+        debug!(
+            "snapshot recovered (persistence_id={}), last sequence={}, type={}",
+            &self.persistence_id, &self.last_sequence_id, &raw_snapshot.payload_type
+        );
+
+        Ok(handler.map(|handler| RecoveredPayload {
+            bytes,
+            sequence,
+            handler: handler.clone(),
+        }))
+    }
+
+    /// Creates a snapshot of the actor's current state, tagged with the last persisted sequence,
+    /// without persisting it
+    pub fn snapshot_entry<S: Snapshot>(&self, snapshot: S) -> Result<JournalEntry, PersistErr> {
+        let payload_type = self
+            .types
+            .snapshot_type_mapping::<S>()
+            .ok_or(PersistErr::NotConfigured())?;
+
+        let bytes = snapshot
+            .into_remote_envelope()
+            .map_err(PersistErr::Serialisation)?
+            .into_bytes();
+
+        let bytes = SnapshotEnvelope::new::<S>(bytes).into_bytes();
+
+        Ok(JournalEntry {
+            sequence: self.last_sequence_id,
+            payload_type,
+            bytes: Arc::new(bytes),
+            metadata: EventMetadata::default().stamp(),
+        })
     }
 
     pub async fn recover_messages(
@@ -368,29 +519,28 @@ impl<A: PersistentActor> Journal<A> {
             for entry in messages {
                 self.last_sequence_id = entry.sequence;
 
+                let sequence = entry.sequence;
+                let event = self.types.adapt_event(sequence, entry.into())?;
                 if let Some(handler) = self
                     .types
                     .recoverable_messages()
-                    .get(entry.payload_type.as_ref())
+                    .get(event.payload_type.as_ref())
                 {
                     trace!(
                         "message recovered (persistence_id={}), sequence={}, starting_sequence={} type={}",
                         &self.persistence_id,
                         &self.last_sequence_id,
                         starting_sequence,
-                        &entry.payload_type
+                        &event.payload_type
                     );
 
-                    let bytes =
-                        Arc::try_unwrap(entry.bytes).map_or_else(|e| e.as_ref().clone(), |s| s);
-
                     recoverable_messages.push(RecoveredPayload {
-                        bytes,
-                        sequence: entry.sequence,
+                        bytes: event.bytes,
+                        sequence,
                         handler: handler.clone(),
                     })
                 } else {
-                    error!("persistence_id={} recovered message (type={}) but actor is not configured to process it",  &self.persistence_id, &entry.payload_type);
+                    error!("persistence_id={} recovered message (type={}) but actor is not configured to process it",  &self.persistence_id, &event.payload_type);
                     // TODO: this should fail recovery
                 }
             }
@@ -35,6 +35,8 @@ pub struct JournalEntry {
     pub payload_type: ::std::string::String,
     // @@protoc_insertion_point(field:coerce.persistent.journal.JournalEntry.bytes)
     pub bytes: ::std::vec::Vec<u8>,
+    // @@protoc_insertion_point(field:coerce.persistent.journal.JournalEntry.metadata)
+    pub metadata: ::protobuf::MessageField<EventMetadata>,
     // special fields
     // @@protoc_insertion_point(special_field:coerce.persistent.journal.JournalEntry.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -52,7 +54,7 @@ impl JournalEntry {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(3);
+        let mut fields = ::std::vec::Vec::with_capacity(4);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "sequence",
@@ -69,6 +71,11 @@ impl JournalEntry {
             |m: &JournalEntry| { &m.bytes },
             |m: &mut JournalEntry| { &mut m.bytes },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_message_field_accessor::<_, EventMetadata>(
+            "metadata",
+            |m: &JournalEntry| { &m.metadata },
+            |m: &mut JournalEntry| { &mut m.metadata },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<JournalEntry>(
             "JournalEntry",
             fields,
@@ -96,6 +103,9 @@ impl ::protobuf::Message for JournalEntry {
                 26 => {
                     self.bytes = is.read_bytes()?;
                 },
+                34 => {
+                    ::protobuf::rt::read_singular_message_into_field(is, &mut self.metadata)?;
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -117,6 +127,10 @@ impl ::protobuf::Message for JournalEntry {
         if !self.bytes.is_empty() {
             my_size += ::protobuf::rt::bytes_size(3, &self.bytes);
         }
+        if let Some(v) = self.metadata.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -132,6 +146,9 @@ impl ::protobuf::Message for JournalEntry {
         if !self.bytes.is_empty() {
             os.write_bytes(3, &self.bytes)?;
         }
+        if let Some(v) = self.metadata.as_ref() {
+            ::protobuf::rt::write_message_field_with_cached_size(4, v, os)?;
+        }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -152,6 +169,7 @@ impl ::protobuf::Message for JournalEntry {
         self.sequence = 0;
         self.payload_type.clear();
         self.bytes.clear();
+        self.metadata.clear();
         self.special_fields.clear();
     }
 
@@ -160,6 +178,7 @@ impl ::protobuf::Message for JournalEntry {
             sequence: 0,
             payload_type: ::std::string::String::new(),
             bytes: ::std::vec::Vec::new(),
+            metadata: ::protobuf::MessageField::none(),
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
@@ -183,11 +202,388 @@ impl ::protobuf::reflect::ProtobufValue for JournalEntry {
     type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
 }
 
+#[derive(PartialEq,Clone,Default,Debug)]
+// @@protoc_insertion_point(message:coerce.persistent.journal.EventMetadata)
+pub struct EventMetadata {
+    // message fields
+    // @@protoc_insertion_point(field:coerce.persistent.journal.EventMetadata.timestamp)
+    pub timestamp: u64,
+    // @@protoc_insertion_point(field:coerce.persistent.journal.EventMetadata.correlation_id)
+    pub correlation_id: ::std::string::String,
+    // @@protoc_insertion_point(field:coerce.persistent.journal.EventMetadata.causation_id)
+    pub causation_id: ::std::string::String,
+    // @@protoc_insertion_point(field:coerce.persistent.journal.EventMetadata.headers)
+    pub headers: ::std::collections::HashMap<::std::string::String, ::std::string::String>,
+    // @@protoc_insertion_point(field:coerce.persistent.journal.EventMetadata.tags)
+    pub tags: ::std::vec::Vec<::std::string::String>,
+    // special fields
+    // @@protoc_insertion_point(special_field:coerce.persistent.journal.EventMetadata.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a EventMetadata {
+    fn default() -> &'a EventMetadata {
+        <EventMetadata as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl EventMetadata {
+    pub fn new() -> EventMetadata {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(5);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "timestamp",
+            |m: &EventMetadata| { &m.timestamp },
+            |m: &mut EventMetadata| { &mut m.timestamp },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "correlation_id",
+            |m: &EventMetadata| { &m.correlation_id },
+            |m: &mut EventMetadata| { &mut m.correlation_id },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "causation_id",
+            |m: &EventMetadata| { &m.causation_id },
+            |m: &mut EventMetadata| { &mut m.causation_id },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_map_simpler_accessor::<_, _, _>(
+            "headers",
+            |m: &EventMetadata| { &m.headers },
+            |m: &mut EventMetadata| { &mut m.headers },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "tags",
+            |m: &EventMetadata| { &m.tags },
+            |m: &mut EventMetadata| { &mut m.tags },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<EventMetadata>(
+            "EventMetadata",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for EventMetadata {
+    const NAME: &'static str = "EventMetadata";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                8 => {
+                    self.timestamp = is.read_uint64()?;
+                },
+                18 => {
+                    self.correlation_id = is.read_string()?;
+                },
+                26 => {
+                    self.causation_id = is.read_string()?;
+                },
+                34 => {
+                    let len = is.read_raw_varint32()?;
+                    let old_limit = is.push_limit(len as u64)?;
+                    let mut key = ::std::default::Default::default();
+                    let mut value = ::std::default::Default::default();
+                    while let Some(tag) = is.read_raw_tag_or_eof()? {
+                        match tag {
+                            10 => key = is.read_string()?,
+                            18 => value = is.read_string()?,
+                            _ => ::protobuf::rt::skip_field_for_tag(tag, is)?,
+                        };
+                    }
+                    is.pop_limit(old_limit);
+                    self.headers.insert(key, value);
+                },
+                42 => {
+                    self.tags.push(is.read_string()?);
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if self.timestamp != 0 {
+            my_size += ::protobuf::rt::uint64_size(1, self.timestamp);
+        }
+        if !self.correlation_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.correlation_id);
+        }
+        if !self.causation_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.causation_id);
+        }
+        for (k, v) in &self.headers {
+            let mut entry_size = 0;
+            entry_size += ::protobuf::rt::string_size(1, &k);
+            entry_size += ::protobuf::rt::string_size(2, &v);
+            my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(entry_size) + entry_size
+        };
+        for value in &self.tags {
+            my_size += ::protobuf::rt::string_size(5, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if self.timestamp != 0 {
+            os.write_uint64(1, self.timestamp)?;
+        }
+        if !self.correlation_id.is_empty() {
+            os.write_string(2, &self.correlation_id)?;
+        }
+        if !self.causation_id.is_empty() {
+            os.write_string(3, &self.causation_id)?;
+        }
+        for (k, v) in &self.headers {
+            let mut entry_size = 0;
+            entry_size += ::protobuf::rt::string_size(1, &k);
+            entry_size += ::protobuf::rt::string_size(2, &v);
+            os.write_raw_varint32(34)?; // Tag.
+            os.write_raw_varint32(entry_size as u32)?;
+            os.write_string(1, &k)?;
+            os.write_string(2, &v)?;
+        };
+        for v in &self.tags {
+            os.write_string(5, &v)?;
+        };
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> EventMetadata {
+        EventMetadata::new()
+    }
+
+    fn clear(&mut self) {
+        self.timestamp = 0;
+        self.correlation_id.clear();
+        self.causation_id.clear();
+        self.headers.clear();
+        self.tags.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static EventMetadata {
+        static instance: ::protobuf::rt::Lazy<EventMetadata> = ::protobuf::rt::Lazy::new();
+        instance.get(EventMetadata::new)
+    }
+}
+
+impl ::protobuf::MessageFull for EventMetadata {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("EventMetadata").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for EventMetadata {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for EventMetadata {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
+#[derive(PartialEq,Clone,Default,Debug)]
+// @@protoc_insertion_point(message:coerce.persistent.journal.SnapshotEnvelope)
+pub struct SnapshotEnvelope {
+    // message fields
+    // @@protoc_insertion_point(field:coerce.persistent.journal.SnapshotEnvelope.serializer_id)
+    pub serializer_id: ::std::string::String,
+    // @@protoc_insertion_point(field:coerce.persistent.journal.SnapshotEnvelope.schema_version)
+    pub schema_version: u32,
+    // @@protoc_insertion_point(field:coerce.persistent.journal.SnapshotEnvelope.payload)
+    pub payload: ::std::vec::Vec<u8>,
+    // special fields
+    // @@protoc_insertion_point(special_field:coerce.persistent.journal.SnapshotEnvelope.special_fields)
+    pub special_fields: ::protobuf::SpecialFields,
+}
+
+impl<'a> ::std::default::Default for &'a SnapshotEnvelope {
+    fn default() -> &'a SnapshotEnvelope {
+        <SnapshotEnvelope as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl SnapshotEnvelope {
+    pub fn new() -> SnapshotEnvelope {
+        ::std::default::Default::default()
+    }
+
+    fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
+        let mut fields = ::std::vec::Vec::with_capacity(3);
+        let mut oneofs = ::std::vec::Vec::with_capacity(0);
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "serializer_id",
+            |m: &SnapshotEnvelope| { &m.serializer_id },
+            |m: &mut SnapshotEnvelope| { &mut m.serializer_id },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "schema_version",
+            |m: &SnapshotEnvelope| { &m.schema_version },
+            |m: &mut SnapshotEnvelope| { &mut m.schema_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "payload",
+            |m: &SnapshotEnvelope| { &m.payload },
+            |m: &mut SnapshotEnvelope| { &mut m.payload },
+        ));
+        ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<SnapshotEnvelope>(
+            "SnapshotEnvelope",
+            fields,
+            oneofs,
+        )
+    }
+}
+
+impl ::protobuf::Message for SnapshotEnvelope {
+    const NAME: &'static str = "SnapshotEnvelope";
+
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::Result<()> {
+        while let Some(tag) = is.read_raw_tag_or_eof()? {
+            match tag {
+                10 => {
+                    self.serializer_id = is.read_string()?;
+                },
+                16 => {
+                    self.schema_version = is.read_uint32()?;
+                },
+                26 => {
+                    self.payload = is.read_bytes()?;
+                },
+                tag => {
+                    ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u64 {
+        let mut my_size = 0;
+        if !self.serializer_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.serializer_id);
+        }
+        if self.schema_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(2, self.schema_version);
+        }
+        if !self.payload.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.payload);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
+        self.special_fields.cached_size().set(my_size as u32);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::Result<()> {
+        if !self.serializer_id.is_empty() {
+            os.write_string(1, &self.serializer_id)?;
+        }
+        if self.schema_version != 0 {
+            os.write_uint32(2, self.schema_version)?;
+        }
+        if !self.payload.is_empty() {
+            os.write_bytes(3, &self.payload)?;
+        }
+        os.write_unknown_fields(self.special_fields.unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn special_fields(&self) -> &::protobuf::SpecialFields {
+        &self.special_fields
+    }
+
+    fn mut_special_fields(&mut self) -> &mut ::protobuf::SpecialFields {
+        &mut self.special_fields
+    }
+
+    fn new() -> SnapshotEnvelope {
+        SnapshotEnvelope::new()
+    }
+
+    fn clear(&mut self) {
+        self.serializer_id.clear();
+        self.schema_version = 0;
+        self.payload.clear();
+        self.special_fields.clear();
+    }
+
+    fn default_instance() -> &'static SnapshotEnvelope {
+        static instance: SnapshotEnvelope = SnapshotEnvelope {
+            serializer_id: ::std::string::String::new(),
+            schema_version: 0,
+            payload: ::std::vec::Vec::new(),
+            special_fields: ::protobuf::SpecialFields::new(),
+        };
+        &instance
+    }
+}
+
+impl ::protobuf::MessageFull for SnapshotEnvelope {
+    fn descriptor() -> ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::Lazy::new();
+        descriptor.get(|| file_descriptor().message_by_package_relative_name("SnapshotEnvelope").unwrap()).clone()
+    }
+}
+
+impl ::std::fmt::Display for SnapshotEnvelope {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for SnapshotEnvelope {
+    type RuntimeType = ::protobuf::reflect::rt::RuntimeTypeMessage<Self>;
+}
+
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\x18persistent/journal.proto\x12\x19coerce.persistent.journal\"c\n\x0c\
-    JournalEntry\x12\x1a\n\x08sequence\x18\x01\x20\x01(\x03R\x08sequence\x12\
-    !\n\x0cpayload_type\x18\x02\x20\x01(\tR\x0bpayloadType\x12\x14\n\x05byte\
-    s\x18\x03\x20\x01(\x0cR\x05bytesb\x06proto3\
+    \n\x18persistent/journal.proto\x12\x19coerce.persistent.journal\"\xa9\
+    \x01\n\x0cJournalEntry\x12\x1a\n\x08sequence\x18\x01\x20\x01(\x03R\x08se\
+    quence\x12!\n\x0cpayload_type\x18\x02\x20\x01(\tR\x0bpayloadType\x12\x14\
+    \n\x05bytes\x18\x03\x20\x01(\x0cR\x05bytes\x12D\n\x08metadata\x18\x04\
+    \x20\x01(\x0b2(.coerce.persistent.journal.EventMetadataR\x08metadata\"\
+    \x98\x02\n\rEventMetadata\x12\x1c\n\ttimestamp\x18\x01\x20\x01(\x04R\tti\
+    mestamp\x12%\n\x0ecorrelation_id\x18\x02\x20\x01(\tR\rcorrelationId\x12!\
+    \n\x0ccausation_id\x18\x03\x20\x01(\tR\x0bcausationId\x12O\n\x07headers\
+    \x18\x04\x20\x03(\x0b25.coerce.persistent.journal.EventMetadata.HeadersE\
+    ntryR\x07headers\x12\x12\n\x04tags\x18\x05\x20\x03(\tR\x04tags\x1a:\n\
+    \x0cHeadersEntry\x12\x10\n\x03key\x18\x01\x20\x01(\tR\x03key\x12\x14\n\
+    \x05value\x18\x02\x20\x01(\tR\x05value:\x028\x01\"x\n\x10SnapshotEnvelop\
+    e\x12#\n\rserializer_id\x18\x01\x20\x01(\tR\x0cserializerId\x12%\n\x0esc\
+    hema_version\x18\x02\x20\x01(\rR\rschemaVersion\x12\x18\n\x07payload\x18\
+    \x03\x20\x01(\x0cR\x07payloadb\x06proto3\
 ";
 
 /// `FileDescriptorProto` object which was a source for this generated file
@@ -205,8 +601,10 @@ pub fn file_descriptor() -> &'static ::protobuf::reflect::FileDescriptor {
     file_descriptor.get(|| {
         let generated_file_descriptor = generated_file_descriptor_lazy.get(|| {
             let mut deps = ::std::vec::Vec::with_capacity(0);
-            let mut messages = ::std::vec::Vec::with_capacity(1);
+            let mut messages = ::std::vec::Vec::with_capacity(3);
             messages.push(JournalEntry::generated_message_descriptor_data());
+            messages.push(EventMetadata::generated_message_descriptor_data());
+            messages.push(SnapshotEnvelope::generated_message_descriptor_data());
             let mut enums = ::std::vec::Vec::with_capacity(0);
             ::protobuf::reflect::GeneratedFileDescriptor::new_generated(
                 file_descriptor_proto(),
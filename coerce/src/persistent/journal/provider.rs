@@ -9,7 +9,9 @@ pub type StorageProviderRef = Arc<dyn StorageProvider>;
 
 pub mod inmemory {
     use crate::persistent::journal::provider::StorageProvider;
-    use crate::persistent::journal::storage::{JournalEntry, JournalStorage, JournalStorageRef};
+    use crate::persistent::journal::storage::{
+        JournalEntry, JournalStorage, JournalStorageRef, TaggedEntry,
+    };
     use parking_lot::RwLock;
     use std::collections::hash_map::Entry;
     use std::collections::HashMap;
@@ -45,9 +47,34 @@ pub mod inmemory {
         }
     }
 
+    /// Every tagged message, in the order they were written, so they can be read by tag
+    #[derive(Debug, Default)]
+    struct TaggedMessages {
+        last_offset: i64,
+        entries: Vec<TaggedEntry>,
+    }
+
+    impl TaggedMessages {
+        fn append<'a>(
+            &mut self,
+            persistence_id: &str,
+            entries: impl Iterator<Item = &'a JournalEntry>,
+        ) {
+            for entry in entries.filter(|entry| !entry.metadata.tags.is_empty()) {
+                self.last_offset += 1;
+                self.entries.push(TaggedEntry {
+                    persistence_id: persistence_id.to_string(),
+                    offset: self.last_offset,
+                    entry: entry.clone(),
+                });
+            }
+        }
+    }
+
     #[derive(Default)]
     pub struct InMemoryJournalStorage {
         store: RwLock<HashMap<String, ActorJournal>>,
+        tagged: RwLock<TaggedMessages>,
     }
 
     #[derive(Default)]
@@ -93,6 +120,10 @@ pub mod inmemory {
             entry: JournalEntry,
         ) -> anyhow::Result<()> {
             let mut store = self.store.write();
+            self.tagged
+                .write()
+                .append(persistence_id, std::iter::once(&entry));
+
             if let Some(journal) = store.get_mut(persistence_id) {
                 journal.messages.push(entry);
             } else {
@@ -111,6 +142,8 @@ pub mod inmemory {
             entries: Vec<JournalEntry>,
         ) -> anyhow::Result<()> {
             let mut store = self.store.write();
+            self.tagged.write().append(persistence_id, entries.iter());
+
             if let Some(journal) = store.get_mut(persistence_id) {
                 let mut entries = entries;
                 journal.messages.append(&mut entries);
@@ -287,6 +320,25 @@ pub mod inmemory {
                     snapshots: mem::take(&mut journal.snapshots),
                     messages,
                 };
+
+                self.tagged.write().entries.retain(|tagged| {
+                    tagged.persistence_id != persistence_id || tagged.entry.sequence >= to_sequence
+                });
+            }
+
+            Ok(())
+        }
+
+        async fn delete_snapshots_to(
+            &self,
+            persistence_id: &str,
+            to_sequence: i64,
+        ) -> anyhow::Result<()> {
+            let mut store = self.store.write();
+            if let Some(journal) = store.get_mut(persistence_id) {
+                journal
+                    .snapshots
+                    .retain(|snapshot| snapshot.sequence >= to_sequence);
             }
 
             Ok(())
@@ -295,7 +347,32 @@ pub mod inmemory {
         async fn delete_all(&self, persistence_id: &str) -> anyhow::Result<()> {
             let mut store = self.store.write();
             store.remove(persistence_id);
+
+            self.tagged
+                .write()
+                .entries
+                .retain(|tagged| tagged.persistence_id != persistence_id);
+
             Ok(())
         }
+
+        async fn read_messages_by_tag(
+            &self,
+            tag: &str,
+            from_offset: i64,
+            limit: usize,
+        ) -> anyhow::Result<Vec<TaggedEntry>> {
+            let tagged = self.tagged.read();
+            let start = tagged
+                .entries
+                .partition_point(|tagged| tagged.offset <= from_offset);
+
+            Ok(tagged.entries[start..]
+                .iter()
+                .filter(|tagged| tagged.entry.metadata.has_tag(tag))
+                .take(limit)
+                .cloned()
+                .collect())
+        }
     }
 }
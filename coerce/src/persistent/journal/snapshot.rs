@@ -1,4 +1,23 @@
+//! Snapshots, and the versioned envelope they're persisted in
+//!
+//! Persisted snapshots are wrapped in a [`SnapshotEnvelope`], recording the id of the serializer
+//! that produced the snapshot's bytes, and the version of the snapshot's schema, see
+//! [`Snapshot::schema_version`]. When a snapshot written with an older schema version is
+//! recovered, it's passed through the chain of [`SnapshotUpcaster`]s registered via
+//! [`JournalTypes::snapshot_upcaster`], each upcasting the snapshot by a single version, until it
+//! matches the current version. This allows a snapshot's format to change without discarding
+//! existing snapshots and replaying the full journal, or migrating the stored data by hand.
+//!
+//! Snapshots persisted before envelopes were introduced are read as schema version 1.
+//!
+//! A [`SnapshotPolicy`] controls when a persistent actor is asked to take a snapshot, and how
+//! many snapshots (and messages) are kept once a new snapshot is persisted.
+//!
+//! [`JournalTypes::snapshot_upcaster`]: crate::persistent::journal::types::JournalTypes::snapshot_upcaster
+
 use crate::actor::message::{Envelope, EnvelopeType, MessageUnwrapErr, MessageWrapErr};
+use crate::persistent::journal::proto::journal::SnapshotEnvelope as ProtoSnapshotEnvelope;
+use protobuf::Message;
 
 pub struct JournalPayload {
     pub message_type: String,
@@ -35,4 +54,145 @@ pub trait Snapshot: 'static + Sync + Send + Sized {
     {
         std::any::type_name::<Self>()
     }
+
+    /// The version of the snapshot's schema, which should be incremented (alongside registering
+    /// a [`SnapshotUpcaster`] from the previous version) whenever the snapshot's format changes
+    fn schema_version() -> u32 {
+        1
+    }
+
+    /// Identifies the serializer used by [`Snapshot::into_remote_envelope`]
+    fn serializer_id() -> &'static str {
+        ""
+    }
+}
+
+/// Prefixes encoded [`SnapshotEnvelope`]s, so they can be told apart from snapshots
+/// that were persisted before envelopes were introduced
+const SNAPSHOT_ENVELOPE_MAGIC: &[u8] = &[0xc0, 0xe5, 0x53, 0x45];
+
+/// The schema version of snapshots persisted before envelopes were introduced
+const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotEnvelope {
+    pub serializer_id: String,
+    pub schema_version: u32,
+    pub payload: Vec<u8>,
+}
+
+impl SnapshotEnvelope {
+    pub fn new<S: Snapshot>(payload: Vec<u8>) -> Self {
+        Self {
+            serializer_id: S::serializer_id().to_string(),
+            schema_version: S::schema_version(),
+            payload,
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        let proto = ProtoSnapshotEnvelope {
+            serializer_id: self.serializer_id,
+            schema_version: self.schema_version,
+            payload: self.payload,
+            ..Default::default()
+        };
+
+        let mut bytes = SNAPSHOT_ENVELOPE_MAGIC.to_vec();
+        proto
+            .write_to_vec(&mut bytes)
+            .expect("snapshot envelope serialization");
+
+        bytes
+    }
+
+    /// Reads an envelope from persisted bytes, bytes that aren't an envelope are read as
+    /// a snapshot persisted before envelopes were introduced
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        if bytes.starts_with(SNAPSHOT_ENVELOPE_MAGIC) {
+            if let Ok(proto) =
+                ProtoSnapshotEnvelope::parse_from_bytes(&bytes[SNAPSHOT_ENVELOPE_MAGIC.len()..])
+            {
+                return Self {
+                    serializer_id: proto.serializer_id,
+                    schema_version: proto.schema_version,
+                    payload: proto.payload,
+                };
+            }
+        }
+
+        Self {
+            serializer_id: String::new(),
+            schema_version: LEGACY_SCHEMA_VERSION,
+            payload: bytes,
+        }
+    }
+}
+
+/// Upcasts a snapshot from one schema version to the next,
+/// see [`JournalTypes::snapshot_upcaster`]
+///
+/// [`JournalTypes::snapshot_upcaster`]: crate::persistent::journal::types::JournalTypes::snapshot_upcaster
+pub trait SnapshotUpcaster: 'static + Send + Sync {
+    fn upcast(&self, envelope: SnapshotEnvelope) -> anyhow::Result<Vec<u8>>;
+}
+
+impl<F> SnapshotUpcaster for F
+where
+    F: Fn(SnapshotEnvelope) -> anyhow::Result<Vec<u8>> + 'static + Send + Sync,
+{
+    fn upcast(&self, envelope: SnapshotEnvelope) -> anyhow::Result<Vec<u8>> {
+        self(envelope)
+    }
+}
+
+/// When a persistent actor is asked to take a snapshot, and which snapshots and messages are
+/// kept once a snapshot is persisted, see [`PersistentActor::snapshot_policy`]
+///
+/// The default policy never asks for a snapshot and keeps every snapshot and message.
+///
+/// [`PersistentActor::snapshot_policy`]: crate::persistent::PersistentActor::snapshot_policy
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct SnapshotPolicy {
+    /// Calls [`PersistentActor::on_snapshot_due`] once this many messages have been persisted
+    /// since the last snapshot
+    ///
+    /// [`PersistentActor::on_snapshot_due`]: crate::persistent::PersistentActor::on_snapshot_due
+    pub every_n_events: Option<u64>,
+
+    /// The number of snapshots to keep, older snapshots are deleted once a snapshot is persisted
+    pub keep_snapshots: Option<usize>,
+
+    /// Deletes the messages covered by a snapshot once it's persisted, messages are only
+    /// replayed from after the latest snapshot, so they're no longer needed for recovery
+    pub delete_messages: bool,
+}
+
+impl SnapshotPolicy {
+    /// Asks for a snapshot every `n` persisted messages
+    pub fn every(n: u64) -> Self {
+        Self {
+            every_n_events: Some(n),
+            ..Default::default()
+        }
+    }
+
+    /// Keeps the latest `n` snapshots, deleting older snapshots
+    pub fn keep_snapshots(mut self, n: usize) -> Self {
+        self.keep_snapshots = Some(n.max(1));
+        self
+    }
+
+    /// Deletes messages once they're covered by a persisted snapshot
+    pub fn delete_messages(mut self) -> Self {
+        self.delete_messages = true;
+        self
+    }
+
+    /// Whether a snapshot should be taken, after `events_since_snapshot` messages
+    /// were persisted since the last snapshot
+    pub fn is_snapshot_due(&self, events_since_snapshot: i64) -> bool {
+        self.every_n_events
+            .is_some_and(|n| n > 0 && events_since_snapshot >= n as i64)
+    }
 }
@@ -1,6 +1,9 @@
+use crate::persistent::journal::metadata::EventMetadata;
 use crate::persistent::journal::proto::journal::JournalEntry as ProtoJournalEntry;
 use anyhow::Result;
 use protobuf::Message;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
 #[derive(Clone, Debug)]
@@ -8,8 +11,26 @@ pub struct JournalEntry {
     pub sequence: i64,
     pub payload_type: Arc<str>,
     pub bytes: Arc<Vec<u8>>,
+    pub metadata: EventMetadata,
 }
 
+/// A message read by tag, via [`JournalStorage::read_messages_by_tag`]
+#[derive(Clone, Debug)]
+pub struct TaggedEntry {
+    pub persistence_id: String,
+
+    /// The position of the message among every tagged message in the journal, assigned when
+    /// the message is written. Offsets increase in the order tagged messages are written, but
+    /// aren't guaranteed to be contiguous
+    pub offset: i64,
+
+    pub entry: JournalEntry,
+}
+
+/// Returned by storage backends that don't support a query
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct UnsupportedQuery(pub &'static str);
+
 #[async_trait]
 pub trait JournalStorage: Send + Sync {
     // TODO: add the ability to limit the maximum size of snapshots,
@@ -29,6 +50,7 @@ pub trait JournalStorage: Send + Sync {
     // TODO: add the ability to stream the messages, rather than load all up front,
     //       if the actor has a very large journal, this could cause an unexpected OOM.
     //       payload size limits should also be applied.
+    /// Reads all messages with a sequence greater than `from_sequence`, in sequence order
     async fn read_latest_messages(
         &self,
         persistence_id: &str,
@@ -48,13 +70,59 @@ pub trait JournalStorage: Send + Sync {
         to_sequence: i64,
     ) -> Result<Option<Vec<JournalEntry>>>;
 
+    /// Deletes all messages with a sequence less than `to_sequence`, snapshots are not deleted.
+    ///
+    /// `to_sequence` is exclusive, the message at `to_sequence` itself is kept. Callers that want
+    /// to delete up to and including a message pass its sequence + 1.
     async fn delete_messages_to(&self, persistence_id: &str, to_sequence: i64) -> Result<()>;
 
+    /// Deletes all snapshots with a sequence less than `to_sequence`, messages are not deleted.
+    ///
+    /// Used to apply the snapshot retention of a [`SnapshotPolicy`], backends that don't
+    /// support deleting snapshots keep every snapshot.
+    ///
+    /// [`SnapshotPolicy`]: crate::persistent::journal::snapshot::SnapshotPolicy
+    async fn delete_snapshots_to(&self, _persistence_id: &str, _to_sequence: i64) -> Result<()> {
+        Ok(())
+    }
+
     async fn delete_all(&self, persistence_id: &str) -> Result<()>;
+
+    /// Reads up to `limit` messages tagged with `tag`, across every persistence id, with an
+    /// offset greater than `from_offset`, in offset order.
+    ///
+    /// Used by the [`ReadJournal`], backends that don't support querying messages by tag return
+    /// [`UnsupportedQuery`].
+    ///
+    /// [`ReadJournal`]: crate::persistent::query::ReadJournal
+    async fn read_messages_by_tag(
+        &self,
+        _tag: &str,
+        _from_offset: i64,
+        _limit: usize,
+    ) -> Result<Vec<TaggedEntry>> {
+        Err(UnsupportedQuery("read_messages_by_tag").into())
+    }
+
+    /// Checks whether the storage backend is reachable, used by the
+    /// [`FailoverStorageProvider`] to probe the health of its primary provider.
+    ///
+    /// [`FailoverStorageProvider`]: crate::persistent::journal::failover::FailoverStorageProvider
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub type JournalStorageRef = Arc<dyn JournalStorage>;
 
+impl Display for UnsupportedQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "query not supported by storage backend: {}", self.0)
+    }
+}
+
+impl Error for UnsupportedQuery {}
+
 impl JournalEntry {
     pub fn read_from_slice(data: &[u8]) -> Option<Self> {
         let journal_entry = ProtoJournalEntry::parse_from_bytes(data);
@@ -63,6 +131,10 @@ impl JournalEntry {
                 sequence: journal_entry.sequence,
                 payload_type: journal_entry.payload_type.into(),
                 bytes: Arc::new(journal_entry.bytes),
+                metadata: journal_entry
+                    .metadata
+                    .into_option()
+                    .map_or_else(EventMetadata::default, EventMetadata::from),
             })
         } else {
             None
@@ -79,6 +151,11 @@ impl JournalEntry {
             sequence: journal_entry.sequence,
             payload_type: journal_entry.payload_type.to_string(),
             bytes: journal_entry.bytes.as_ref().clone(),
+            metadata: if journal_entry.metadata.is_empty() {
+                None.into()
+            } else {
+                Some((&journal_entry.metadata).into()).into()
+            },
             ..Default::default()
         };
 
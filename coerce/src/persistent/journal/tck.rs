@@ -0,0 +1,777 @@
+//! Journal storage technology compatibility kit (TCK)
+//!
+//! A reusable suite of tests that validates a [`StorageProvider`] against the [`JournalStorage`]
+//! contract, covering message ordering, concurrent writers, gaps in sequence numbers,
+//! snapshot semantics, deletion and reading messages by tag.
+//!
+//! Third-party providers can run the full suite from an integration test using
+//! [`journal_provider_tck!`], either with a provider type that implements [`Default`]:
+//!
+//! ```rust,ignore
+//! use coerce::journal_provider_tck;
+//!
+//! journal_provider_tck!(MyStorageProvider);
+//! ```
+//!
+//! or with an expression that creates the provider, which is evaluated within an async block,
+//! so it can `.await`:
+//!
+//! ```rust,ignore
+//! journal_provider_tck!(
+//!     MyStorageProvider,
+//!     MyStorageProvider::connect(MyStorageConfig::default()).await
+//! );
+//! ```
+//!
+//! The tests are generated within a `journal_provider_tck` module, each test creates a new
+//! provider and uses its own persistence IDs.
+//!
+//! [`journal_provider_tck!`]: crate::journal_provider_tck
+
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::provider::StorageProvider;
+use crate::persistent::journal::storage::{
+    JournalEntry, JournalStorageRef, TaggedEntry, UnsupportedQuery,
+};
+use std::future::Future;
+use std::sync::Arc;
+
+const CONCURRENT_WRITERS: i64 = 8;
+const MESSAGES_PER_WRITER: i64 = 25;
+
+/// Generates the TCK test suite for a [`StorageProvider`], see the [`tck`] module docs.
+///
+/// [`StorageProvider`]: crate::persistent::journal::provider::StorageProvider
+/// [`tck`]: crate::persistent::journal::tck
+#[macro_export]
+macro_rules! journal_provider_tck {
+    (@tests $provider:ty, $create:expr, [$($test:ident),*]) => {
+        mod journal_provider_tck {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $(
+                #[test]
+                fn $test() {
+                    $crate::persistent::journal::tck::run(
+                        async {
+                            let provider: $provider = $create;
+                            provider
+                        },
+                        $crate::persistent::journal::tck::$test,
+                    );
+                }
+            )*
+        }
+    };
+
+    ($provider:ty) => {
+        $crate::journal_provider_tck!(
+            $provider,
+            <$provider as ::core::default::Default>::default()
+        );
+    };
+
+    ($provider:ty, $create:expr) => {
+        $crate::journal_provider_tck!(
+            @tests $provider,
+            $create,
+            [
+                message_ordering,
+                batch_ordering,
+                concurrent_writers,
+                sequence_gaps,
+                snapshots,
+                delete_messages_to,
+                delete_snapshots_to,
+                delete_all,
+                metadata,
+                events_by_tag
+            ]
+        );
+    };
+}
+
+/// Creates the provider and runs a single TCK test against its journal storage,
+/// within a new tokio runtime.
+pub fn run<P, C, T, F>(create_provider: C, test: T)
+where
+    P: StorageProvider,
+    C: Future<Output = P>,
+    T: FnOnce(JournalStorageRef) -> F,
+    F: Future<Output = ()>,
+{
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("tck runtime")
+        .block_on(async move {
+            let storage = create_provider
+                .await
+                .journal_storage()
+                .expect("provider should return journal storage");
+
+            test(storage).await
+        })
+}
+
+/// Messages are read back in the order they were written, starting after `from_sequence`
+pub async fn message_ordering(storage: JournalStorageRef) {
+    let persistence_id = "tck-message-ordering";
+
+    assert!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 0)
+                .await
+                .unwrap()
+        )
+        .is_empty(),
+        "an empty journal should have no messages"
+    );
+
+    for sequence in 1..=10 {
+        storage
+            .write_message(persistence_id, entry(sequence))
+            .await
+            .unwrap();
+    }
+
+    let messages = storage
+        .read_latest_messages(persistence_id, 0)
+        .await
+        .unwrap()
+        .expect("messages should be returned");
+
+    assert_eq!(
+        messages.iter().map(|m| m.sequence).collect::<Vec<_>>(),
+        (1..=10).collect::<Vec<_>>(),
+        "messages should be read in the order they were written"
+    );
+
+    for message in &messages {
+        assert_eq!(
+            message.payload_type.as_ref(),
+            payload_type(message.sequence)
+        );
+        assert_eq!(message.bytes.as_ref(), &payload(message.sequence));
+    }
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 5)
+                .await
+                .unwrap()
+        ),
+        (6..=10).collect::<Vec<_>>(),
+        "only messages after `from_sequence` should be read"
+    );
+
+    assert_eq!(
+        sequences(storage.read_messages(persistence_id, 1, 10).await.unwrap()),
+        (1..=10).collect::<Vec<_>>(),
+    );
+
+    let message = storage
+        .read_message(persistence_id, 7)
+        .await
+        .unwrap()
+        .expect("message should be found by its sequence");
+
+    assert_eq!(message.sequence, 7);
+    assert!(storage
+        .read_message(persistence_id, 11)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Batches are written in order, after any previously written messages
+pub async fn batch_ordering(storage: JournalStorageRef) {
+    let persistence_id = "tck-batch-ordering";
+
+    storage
+        .write_message(persistence_id, entry(1))
+        .await
+        .unwrap();
+
+    storage
+        .write_message_batch(persistence_id, (2..=5).map(entry).collect())
+        .await
+        .unwrap();
+
+    storage
+        .write_message(persistence_id, entry(6))
+        .await
+        .unwrap();
+
+    storage
+        .write_message_batch(persistence_id, (7..=8).map(entry).collect())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 0)
+                .await
+                .unwrap()
+        ),
+        (1..=8).collect::<Vec<_>>(),
+        "batched messages should be read in the order they were written"
+    );
+}
+
+/// Concurrent writers don't lose messages, and each writer's messages stay in order
+pub async fn concurrent_writers(storage: JournalStorageRef) {
+    let mut writers = vec![];
+    for writer in 0..CONCURRENT_WRITERS {
+        let storage = storage.clone();
+        writers.push(tokio::spawn(async move {
+            let own_journal = format!("tck-concurrent-writer-{}", writer);
+            for i in 1..=MESSAGES_PER_WRITER {
+                storage.write_message(&own_journal, entry(i)).await.unwrap();
+
+                let sequence = writer * 1000 + i;
+                storage
+                    .write_message("tck-concurrent-shared", entry(sequence))
+                    .await
+                    .unwrap();
+            }
+        }));
+    }
+
+    for writer in writers {
+        writer.await.expect("writer task");
+    }
+
+    for writer in 0..CONCURRENT_WRITERS {
+        let own_journal = format!("tck-concurrent-writer-{}", writer);
+        assert_eq!(
+            sequences(storage.read_latest_messages(&own_journal, 0).await.unwrap()),
+            (1..=MESSAGES_PER_WRITER).collect::<Vec<_>>(),
+            "concurrent writes to other journals should not affect each other"
+        );
+    }
+
+    let shared = sequences(
+        storage
+            .read_latest_messages("tck-concurrent-shared", 0)
+            .await
+            .unwrap(),
+    );
+
+    assert_eq!(
+        shared.len() as i64,
+        CONCURRENT_WRITERS * MESSAGES_PER_WRITER,
+        "no concurrent writes should be lost"
+    );
+
+    for writer in 0..CONCURRENT_WRITERS {
+        let written: Vec<i64> = shared
+            .iter()
+            .copied()
+            .filter(|sequence| sequence / 1000 == writer)
+            .collect();
+
+        assert_eq!(
+            written,
+            (1..=MESSAGES_PER_WRITER)
+                .map(|i| writer * 1000 + i)
+                .collect::<Vec<_>>(),
+            "each writer's messages should be read in the order they were written"
+        );
+    }
+}
+
+/// Sequence numbers don't need to be contiguous
+pub async fn sequence_gaps(storage: JournalStorageRef) {
+    let persistence_id = "tck-sequence-gaps";
+
+    for sequence in [1, 2, 5, 9, 20] {
+        storage
+            .write_message(persistence_id, entry(sequence))
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 0)
+                .await
+                .unwrap()
+        ),
+        vec![1, 2, 5, 9, 20]
+    );
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 3)
+                .await
+                .unwrap()
+        ),
+        vec![5, 9, 20],
+        "reading from a sequence within a gap should start at the next message"
+    );
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 5)
+                .await
+                .unwrap()
+        ),
+        vec![9, 20]
+    );
+
+    assert!(storage
+        .read_message(persistence_id, 3)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert_eq!(
+        storage
+            .read_message(persistence_id, 9)
+            .await
+            .unwrap()
+            .map(|m| m.sequence),
+        Some(9)
+    );
+}
+
+/// The most recently written snapshot is returned, snapshots are stored separately to messages
+pub async fn snapshots(storage: JournalStorageRef) {
+    let persistence_id = "tck-snapshots";
+
+    assert!(storage
+        .read_latest_snapshot(persistence_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    storage
+        .write_message_batch(persistence_id, (1..=5).map(entry).collect())
+        .await
+        .unwrap();
+
+    storage
+        .write_snapshot(persistence_id, entry(3))
+        .await
+        .unwrap();
+
+    storage
+        .write_snapshot(persistence_id, entry(5))
+        .await
+        .unwrap();
+
+    let snapshot = storage
+        .read_latest_snapshot(persistence_id)
+        .await
+        .unwrap()
+        .expect("snapshot should be returned");
+
+    assert_eq!(
+        snapshot.sequence, 5,
+        "the latest snapshot should be returned"
+    );
+    assert_eq!(snapshot.payload_type.as_ref(), payload_type(5));
+    assert_eq!(snapshot.bytes.as_ref(), &payload(5));
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 0)
+                .await
+                .unwrap()
+        ),
+        (1..=5).collect::<Vec<_>>(),
+        "writing snapshots should not affect messages"
+    );
+
+    storage.delete_messages_to(persistence_id, 6).await.unwrap();
+
+    assert_eq!(
+        storage
+            .read_latest_snapshot(persistence_id)
+            .await
+            .unwrap()
+            .map(|s| s.sequence),
+        Some(5),
+        "deleting messages should not delete snapshots"
+    );
+}
+
+/// Messages with a sequence before (but not including) `to_sequence` are deleted
+pub async fn delete_messages_to(storage: JournalStorageRef) {
+    let persistence_id = "tck-delete-messages-to";
+
+    storage
+        .write_message_batch(persistence_id, (1..=5).map(entry).collect())
+        .await
+        .unwrap();
+
+    storage.delete_messages_to(persistence_id, 3).await.unwrap();
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 0)
+                .await
+                .unwrap()
+        ),
+        vec![3, 4, 5],
+        "messages before `to_sequence` should be deleted"
+    );
+
+    assert!(storage
+        .read_message(persistence_id, 2)
+        .await
+        .unwrap()
+        .is_none());
+
+    storage
+        .write_message(persistence_id, entry(6))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 0)
+                .await
+                .unwrap()
+        ),
+        vec![3, 4, 5, 6],
+        "messages can be written after deleting"
+    );
+
+    storage
+        .delete_messages_to(persistence_id, 100)
+        .await
+        .unwrap();
+
+    assert!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 0)
+                .await
+                .unwrap()
+        )
+        .is_empty(),
+        "all messages before `to_sequence` should be deleted"
+    );
+
+    storage
+        .delete_messages_to("tck-delete-messages-to-empty", 10)
+        .await
+        .expect("deleting from an empty journal should succeed");
+}
+
+/// Snapshots with a sequence before (but not including) `to_sequence` are deleted,
+/// without affecting messages
+pub async fn delete_snapshots_to(storage: JournalStorageRef) {
+    let persistence_id = "tck-delete-snapshots-to";
+
+    storage
+        .write_message_batch(persistence_id, (1..=5).map(entry).collect())
+        .await
+        .unwrap();
+
+    storage
+        .write_snapshot(persistence_id, entry(3))
+        .await
+        .unwrap();
+
+    storage
+        .write_snapshot(persistence_id, entry(5))
+        .await
+        .unwrap();
+
+    storage
+        .delete_snapshots_to(persistence_id, 5)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        storage
+            .read_latest_snapshot(persistence_id)
+            .await
+            .unwrap()
+            .map(|s| s.sequence),
+        Some(5),
+        "snapshots from `to_sequence` should be kept"
+    );
+
+    storage
+        .delete_snapshots_to(persistence_id, 6)
+        .await
+        .unwrap();
+
+    assert!(
+        storage
+            .read_latest_snapshot(persistence_id)
+            .await
+            .unwrap()
+            .is_none(),
+        "all snapshots before `to_sequence` should be deleted"
+    );
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(persistence_id, 0)
+                .await
+                .unwrap()
+        ),
+        (1..=5).collect::<Vec<_>>(),
+        "deleting snapshots should not delete messages"
+    );
+
+    storage
+        .delete_snapshots_to("tck-delete-snapshots-to-empty", 10)
+        .await
+        .expect("deleting from an empty journal should succeed");
+}
+
+/// All messages and snapshots are deleted, without affecting other journals
+pub async fn delete_all(storage: JournalStorageRef) {
+    let persistence_id = "tck-delete-all";
+    let other_persistence_id = "tck-delete-all-other";
+
+    for persistence_id in [persistence_id, other_persistence_id] {
+        storage
+            .write_message_batch(persistence_id, (1..=3).map(entry).collect())
+            .await
+            .unwrap();
+
+        storage
+            .write_snapshot(persistence_id, entry(3))
+            .await
+            .unwrap();
+    }
+
+    storage.delete_all(persistence_id).await.unwrap();
+
+    assert!(sequences(
+        storage
+            .read_latest_messages(persistence_id, 0)
+            .await
+            .unwrap()
+    )
+    .is_empty());
+    assert!(storage
+        .read_latest_snapshot(persistence_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert_eq!(
+        sequences(
+            storage
+                .read_latest_messages(other_persistence_id, 0)
+                .await
+                .unwrap()
+        ),
+        vec![1, 2, 3],
+        "deleting a journal should not affect other journals"
+    );
+
+    assert!(storage
+        .read_latest_snapshot(other_persistence_id)
+        .await
+        .unwrap()
+        .is_some());
+
+    storage
+        .delete_all("tck-delete-all-empty")
+        .await
+        .expect("deleting an empty journal should succeed");
+}
+
+/// Event metadata is stored alongside messages and snapshots, and read back unchanged
+pub async fn metadata(storage: JournalStorageRef) {
+    let persistence_id = "tck-metadata";
+    let with_metadata = |sequence: i64| JournalEntry {
+        metadata: EventMetadata {
+            timestamp: Some(1_700_000_000_000 + sequence as u64),
+            ..EventMetadata::new()
+        }
+        .with_correlation_id(format!("correlation-{}", sequence))
+        .with_causation_id(format!("causation-{}", sequence))
+        .with_header("tenant", "tck")
+        .with_header("sequence", sequence.to_string())
+        .with_tag("tck"),
+        ..entry(sequence)
+    };
+
+    storage
+        .write_message(persistence_id, with_metadata(1))
+        .await
+        .unwrap();
+
+    storage
+        .write_message_batch(persistence_id, (2..=3).map(with_metadata).collect())
+        .await
+        .unwrap();
+
+    storage
+        .write_message(persistence_id, entry(4))
+        .await
+        .unwrap();
+
+    let messages = storage
+        .read_latest_messages(persistence_id, 0)
+        .await
+        .unwrap()
+        .expect("messages should be returned");
+
+    assert_eq!(sequences(Some(messages.clone())), vec![1, 2, 3, 4]);
+    for message in &messages[..3] {
+        assert_eq!(
+            message.metadata,
+            with_metadata(message.sequence).metadata,
+            "metadata should be read back as it was written"
+        );
+    }
+
+    assert!(
+        messages[3].metadata.is_empty(),
+        "messages written without metadata should have empty metadata"
+    );
+
+    let message = storage
+        .read_message(persistence_id, 2)
+        .await
+        .unwrap()
+        .expect("message should be found by its sequence");
+
+    assert_eq!(message.metadata, with_metadata(2).metadata);
+
+    storage
+        .write_snapshot(persistence_id, with_metadata(3))
+        .await
+        .unwrap();
+
+    let snapshot = storage
+        .read_latest_snapshot(persistence_id)
+        .await
+        .unwrap()
+        .expect("snapshot should be returned");
+
+    assert_eq!(snapshot.metadata, with_metadata(3).metadata);
+}
+
+/// Tagged messages are read across persistence ids in the order they were written, starting after
+/// `from_offset`. Skipped for backends that don't support reading messages by tag
+pub async fn events_by_tag(storage: JournalStorageRef) {
+    let tag = "tck-tag";
+    let tagged = |sequence: i64| JournalEntry {
+        metadata: EventMetadata::new().with_tag(tag),
+        ..entry(sequence)
+    };
+
+    match storage.read_messages_by_tag(tag, 0, 10).await {
+        Err(e) if e.is::<UnsupportedQuery>() => return,
+        result => assert!(
+            result.unwrap().is_empty(),
+            "no messages should have the tag yet"
+        ),
+    }
+
+    storage.write_message("tck-tag-a", tagged(1)).await.unwrap();
+
+    storage.write_message("tck-tag-a", entry(2)).await.unwrap();
+
+    storage
+        .write_message_batch("tck-tag-b", (1..=2).map(tagged).collect())
+        .await
+        .unwrap();
+
+    storage.write_message("tck-tag-a", tagged(3)).await.unwrap();
+
+    let read_ids = |messages: &[TaggedEntry]| {
+        messages
+            .iter()
+            .map(|m| (m.persistence_id.clone(), m.entry.sequence))
+            .collect::<Vec<_>>()
+    };
+
+    let messages = storage.read_messages_by_tag(tag, 0, 10).await.unwrap();
+    assert_eq!(
+        read_ids(&messages),
+        vec![
+            ("tck-tag-a".to_string(), 1),
+            ("tck-tag-b".to_string(), 1),
+            ("tck-tag-b".to_string(), 2),
+            ("tck-tag-a".to_string(), 3),
+        ],
+        "tagged messages should be read in the order they were written"
+    );
+
+    assert!(
+        messages.windows(2).all(|m| m[0].offset < m[1].offset),
+        "offsets should increase in the order messages were written"
+    );
+
+    assert_eq!(messages[0].entry.bytes.as_ref(), &payload(1));
+    assert!(messages[0].entry.metadata.has_tag(tag));
+
+    let page = storage
+        .read_messages_by_tag(tag, messages[0].offset, 2)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        read_ids(&page),
+        read_ids(&messages[1..3]),
+        "messages should be read after `from_offset`, up to `limit`"
+    );
+
+    storage.delete_all("tck-tag-b").await.unwrap();
+
+    let messages = storage.read_messages_by_tag(tag, 0, 10).await.unwrap();
+    assert_eq!(
+        read_ids(&messages),
+        vec![("tck-tag-a".to_string(), 1), ("tck-tag-a".to_string(), 3)],
+        "deleted messages should no longer be read by tag"
+    );
+
+    assert!(storage
+        .read_messages_by_tag("tck-tag-unknown", 0, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+fn entry(sequence: i64) -> JournalEntry {
+    JournalEntry {
+        sequence,
+        payload_type: payload_type(sequence).into(),
+        bytes: Arc::new(payload(sequence)),
+        metadata: EventMetadata::default(),
+    }
+}
+
+fn payload_type(sequence: i64) -> &'static str {
+    if sequence % 2 == 0 {
+        "tck.Even"
+    } else {
+        "tck.Odd"
+    }
+}
+
+fn payload(sequence: i64) -> Vec<u8> {
+    sequence.to_le_bytes().to_vec()
+}
+
+fn sequences(messages: Option<Vec<JournalEntry>>) -> Vec<i64> {
+    messages
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| m.sequence)
+        .collect()
+}
@@ -1,7 +1,8 @@
 use crate::actor::message::Message;
-use crate::persistent::journal::snapshot::Snapshot;
+use crate::persistent::journal::adapter::{EventAdapter, PersistedEvent, Upcaster};
+use crate::persistent::journal::snapshot::{Snapshot, SnapshotEnvelope, SnapshotUpcaster};
 use crate::persistent::journal::{
-    MessageRecoveryHandler, RecoveryHandlerRef, SnapshotRecoveryHandler,
+    MessageRecoveryHandler, RecoveryErr, RecoveryHandlerRef, SnapshotRecoveryHandler,
 };
 use crate::persistent::{PersistentActor, Recover, RecoverSnapshot};
 use std::any::Any;
@@ -19,6 +20,9 @@ pub struct JournalTypes<A: PersistentActor> {
     snapshot_type_map: HashMap<TypeId, Arc<str>>,
     recoverable_messages: HashMap<String, RecoveryHandlerRef<A>>,
     recoverable_snapshots: HashMap<String, RecoveryHandlerRef<A>>,
+    snapshot_schema_versions: HashMap<String, u32>,
+    snapshot_upcasters: HashMap<(String, u32), Arc<dyn SnapshotUpcaster>>,
+    event_adapters: HashMap<String, Arc<dyn EventAdapter>>,
 }
 
 impl<A: PersistentActor> Default for JournalTypes<A> {
@@ -27,11 +31,17 @@ impl<A: PersistentActor> Default for JournalTypes<A> {
         let snapshot_type_map = HashMap::new();
         let recoverable_messages = HashMap::new();
         let recoverable_snapshots = HashMap::new();
+        let snapshot_schema_versions = HashMap::new();
+        let snapshot_upcasters = HashMap::new();
+        let event_adapters = HashMap::new();
         JournalTypes {
             message_type_map,
             snapshot_type_map,
             recoverable_messages,
             recoverable_snapshots,
+            snapshot_schema_versions,
+            snapshot_upcasters,
+            event_adapters,
         }
     }
 }
@@ -64,9 +74,65 @@ impl<A: PersistentActor> JournalTypes<A> {
         self.snapshot_type_map
             .insert(TypeId::of::<S>(), identifier.into());
 
+        self.snapshot_schema_versions
+            .insert(identifier.to_string(), S::schema_version());
+
         self
     }
 
+    /// Registers an upcaster from `from_version` of the snapshot's schema to the next version,
+    /// see [`snapshot`](crate::persistent::journal::snapshot).
+    ///
+    /// The snapshot must already be registered via [`JournalTypes::snapshot`].
+    pub fn snapshot_upcaster<S: Snapshot>(
+        &mut self,
+        from_version: u32,
+        upcaster: impl SnapshotUpcaster,
+    ) -> &mut Self {
+        let identifier = self
+            .snapshot_type_mapping::<S>()
+            .expect("snapshot type not configured");
+
+        self.snapshot_upcasters
+            .insert((identifier.to_string(), from_version), Arc::new(upcaster));
+
+        self
+    }
+
+    /// Registers an adapter for events persisted with the identifier `payload_type`,
+    /// see [`adapter`](crate::persistent::journal::adapter).
+    pub fn event_adapter(&mut self, payload_type: &str, adapter: impl EventAdapter) -> &mut Self {
+        self.event_adapters
+            .insert(payload_type.to_string(), Arc::new(adapter));
+
+        self
+    }
+
+    /// Registers an adapter that upcasts events persisted as `From`, with the identifier
+    /// `payload_type`, to `To`.
+    ///
+    /// `To` must already be registered via [`JournalTypes::message`], with a different identifier.
+    pub fn upcast_event<From: Message, To: Message>(
+        &mut self,
+        payload_type: &str,
+        upcast: impl Fn(From) -> To + 'static + Send + Sync,
+    ) -> &mut Self {
+        let identifier = self
+            .message_type_mapping::<To>()
+            .expect("message type not configured");
+
+        assert_ne!(
+            identifier.as_ref(),
+            payload_type,
+            "event cannot be upcast to a type with the same identifier"
+        );
+
+        self.event_adapter(
+            payload_type,
+            Upcaster::<From, To, _>::new(identifier, upcast),
+        )
+    }
+
     pub fn snapshot_type_mapping<S: Snapshot>(&self) -> Option<Arc<str>> {
         self.snapshot_type_map.get(&TypeId::of::<S>()).cloned()
     }
@@ -82,6 +148,99 @@ impl<A: PersistentActor> JournalTypes<A> {
     pub fn recoverable_messages(&self) -> &HashMap<String, RecoveryHandlerRef<A>> {
         &self.recoverable_messages
     }
+
+    /// Upcasts a persisted snapshot to the current version of its schema, returning
+    /// the snapshot's payload
+    pub fn upcast_snapshot(
+        &self,
+        payload_type: &str,
+        mut envelope: SnapshotEnvelope,
+    ) -> Result<Vec<u8>, RecoveryErr> {
+        let current_version = match self.snapshot_schema_versions.get(payload_type) {
+            Some(version) => *version,
+            None => return Ok(envelope.payload),
+        };
+
+        let schema_err = |schema_version: u32, error: anyhow::Error| RecoveryErr::SnapshotSchema {
+            snapshot_type: payload_type.to_string(),
+            schema_version,
+            current_schema_version: current_version,
+            error,
+        };
+
+        if envelope.schema_version > current_version {
+            return Err(schema_err(
+                envelope.schema_version,
+                anyhow::anyhow!("snapshot was written by a newer version of the snapshot's schema"),
+            ));
+        }
+
+        while envelope.schema_version < current_version {
+            let schema_version = envelope.schema_version;
+            let upcaster = self
+                .snapshot_upcasters
+                .get(&(payload_type.to_string(), schema_version))
+                .ok_or_else(|| {
+                    schema_err(
+                        schema_version,
+                        anyhow::anyhow!("no upcaster registered from version {}", schema_version),
+                    )
+                })?;
+
+            trace!(
+                "upcasting snapshot (type={}) from version {}",
+                payload_type,
+                schema_version
+            );
+
+            let serializer_id = envelope.serializer_id.clone();
+            let payload = upcaster
+                .upcast(envelope)
+                .map_err(|e| schema_err(schema_version, e))?;
+
+            envelope = SnapshotEnvelope {
+                serializer_id,
+                schema_version: schema_version + 1,
+                payload,
+            };
+        }
+
+        Ok(envelope.payload)
+    }
+
+    /// Passes a persisted event through the adapters registered for its type, until there's no
+    /// adapter registered for the adapted event's type
+    pub fn adapt_event(
+        &self,
+        sequence: i64,
+        mut event: PersistedEvent,
+    ) -> Result<PersistedEvent, RecoveryErr> {
+        let mut adapted = 0;
+        while let Some(adapter) = self.event_adapters.get(event.payload_type.as_ref()) {
+            let event_type = event.payload_type.to_string();
+            let adapter_err = |error: anyhow::Error| RecoveryErr::EventAdapter {
+                event_type: event_type.clone(),
+                message_sequence_id: sequence,
+                error,
+            };
+
+            // each adapter can only be applied once, unless the adapters form a cycle
+            if adapted == self.event_adapters.len() {
+                return Err(adapter_err(anyhow::anyhow!("event adapters form a cycle")));
+            }
+
+            trace!(
+                "adapting event (type={}, sequence={})",
+                &event_type,
+                sequence
+            );
+
+            event = adapter.adapt(event).map_err(adapter_err)?;
+            adapted += 1;
+        }
+
+        Ok(event)
+    }
 }
 
 pub(crate) fn init_journal_types<A: PersistentActor>() -> Arc<JournalTypes<A>> {
@@ -0,0 +1,100 @@
+//! Snapshot shipping, for migrating persistent actors between nodes
+//!
+//! When a persistent actor is migrated to another node (for example, a sharded entity whose
+//! shard is being rebalanced), a snapshot of its current state can be shipped to the new node,
+//! rather than the new node replaying the actor's journal from the storage backend.
+//!
+//! Shipped snapshots are held by the receiving node's [`Persistence`] until the actor is started,
+//! the actor is then recovered from the shipped snapshot, followed by any messages persisted
+//! after the snapshot was taken.
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::Actor;
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::storage::JournalEntry;
+use crate::persistent::Persistence;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A snapshot of a persistent actor's current state, taken when the actor is being migrated
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MigrationSnapshot {
+    pub persistence_key: String,
+    pub sequence: i64,
+    pub payload_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Stops an actor so it can be migrated to another node, returning a snapshot of its state if the
+/// actor supports it, see [`Actor::migration_snapshot`].
+///
+/// The actor is stopped as soon as the snapshot has been taken, so no further messages
+/// can be persisted that the snapshot doesn't account for.
+pub struct StopForMigration;
+
+impl Message for StopForMigration {
+    type Result = Option<MigrationSnapshot>;
+}
+
+#[async_trait]
+impl<A: Actor> Handler<StopForMigration> for A {
+    async fn handle(
+        &mut self,
+        _message: StopForMigration,
+        ctx: &mut ActorContext,
+    ) -> Option<MigrationSnapshot> {
+        let snapshot = self.migration_snapshot(ctx).await;
+        ctx.stop(None);
+        snapshot
+    }
+}
+
+/// Snapshots shipped to this node, waiting for the actor they belong to to be started
+#[derive(Clone, Default)]
+pub struct ShippedSnapshots {
+    snapshots: Arc<parking_lot::Mutex<HashMap<String, MigrationSnapshot>>>,
+}
+
+impl ShippedSnapshots {
+    pub fn insert(&self, snapshot: MigrationSnapshot) {
+        let mut snapshots = self.snapshots.lock();
+        match snapshots.get(&snapshot.persistence_key) {
+            Some(existing) if existing.sequence > snapshot.sequence => {}
+            _ => {
+                snapshots.insert(snapshot.persistence_key.clone(), snapshot);
+            }
+        }
+    }
+
+    pub fn take(&self, persistence_key: &str) -> Option<MigrationSnapshot> {
+        self.snapshots.lock().remove(persistence_key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.lock().is_empty()
+    }
+}
+
+impl Persistence {
+    /// Snapshots shipped to this node, used to recover migrated actors without replaying
+    /// their journal
+    pub fn shipped_snapshots(&self) -> &ShippedSnapshots {
+        &self.shipped_snapshots
+    }
+}
+
+impl From<MigrationSnapshot> for JournalEntry {
+    fn from(snapshot: MigrationSnapshot) -> Self {
+        JournalEntry {
+            sequence: snapshot.sequence,
+            payload_type: snapshot.payload_type.into(),
+            bytes: Arc::new(snapshot.bytes),
+            metadata: EventMetadata::default(),
+        }
+    }
+}
@@ -2,10 +2,16 @@
 
 pub mod actor;
 pub mod batch;
+pub mod checkpoint;
 pub mod context;
+pub mod dead_letter;
+pub mod delivery;
 pub mod failure;
 pub mod inspect;
 pub mod journal;
+pub mod migration;
+pub mod outbox;
+pub mod query;
 pub mod recovery;
 
 pub use actor::*;
@@ -17,12 +23,14 @@ use std::any::TypeId;
 use std::collections::HashMap;
 
 use crate::persistent::journal::provider::{StorageProvider, StorageProviderRef};
+use crate::persistent::migration::ShippedSnapshots;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Persistence {
     default_provider: StorageProviderRef,
     actor_type_specific_providers: HashMap<TypeId, StorageProviderRef>,
+    shipped_snapshots: ShippedSnapshots,
 }
 
 impl<S: StorageProvider> From<S> for Persistence {
@@ -37,6 +45,7 @@ impl Persistence {
         Persistence {
             default_provider,
             actor_type_specific_providers: HashMap::new(),
+            shipped_snapshots: ShippedSnapshots::default(),
         }
     }
 
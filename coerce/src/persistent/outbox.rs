@@ -0,0 +1,345 @@
+//! Transactional outbox, relaying persisted events to external messaging systems
+//!
+//! Rather than actors publishing to an external broker (Kafka, NATS etc) directly, which could
+//! result in events being published that were never persisted (or persisted but never published),
+//! the [`OutboxRelay`] reads events back from the journals of the persistent actors it tracks, and
+//! publishes them via an [`OutboxSink`].
+//!
+//! The relay keeps track of the sequence of the last event published from each journal, which is
+//! only advanced once the sink has acknowledged the events. The offsets are stored in the same
+//! [`JournalStorage`] backend as the events, so relaying resumes from where it left off after
+//! a restart.
+//!
+//! This guarantees every stored event is published at least once, and that no event is published
+//! without first being stored. Sinks may receive the same event more than once (after a failed
+//! publish, or if the node stopped before the offsets were stored), so consumers should
+//! de-duplicate using the persistence id and sequence of each [`OutboxRecord`].
+//!
+//! An [`OutboxRelay`] is started on each node, tracking the persistent actors running on that node.
+//!
+//! [`JournalStorage`]: crate::persistent::journal::storage::JournalStorage
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::scheduler::timer::{Timer, TimerTick};
+use crate::actor::system::ActorSystem;
+use crate::actor::{Actor, ActorId, ActorRefErr, IntoActor, IntoActorId, LocalActorRef};
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::storage::{JournalEntry, JournalStorageRef};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+const OUTBOX_OFFSETS_PAYLOAD_TYPE: &str = "coerce.OutboxOffsets";
+
+/// An event read from a persistent actor's journal, to be published by an [`OutboxSink`]
+#[derive(Clone, Debug)]
+pub struct OutboxRecord {
+    pub persistence_id: Arc<str>,
+    pub sequence: i64,
+    pub payload_type: Arc<str>,
+    pub bytes: Arc<Vec<u8>>,
+    pub metadata: EventMetadata,
+}
+
+/// A bridge to an external messaging system
+#[async_trait]
+pub trait OutboxSink: 'static + Send + Sync {
+    /// Publishes the records, which are provided in journal order. The relay only advances past
+    /// the records once this returns `Ok`, if an error is returned, the records are published again
+    /// on the relay's next attempt.
+    async fn publish(&self, records: &[OutboxRecord]) -> anyhow::Result<()>;
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct OutboxOffsets {
+    offsets: HashMap<String, Option<i64>>,
+}
+
+pub struct OutboxRelay {
+    name: String,
+    storage: JournalStorageRef,
+    sink: Box<dyn OutboxSink>,
+    offsets: HashMap<String, Option<i64>>,
+    poll_interval: Duration,
+    batch_size: usize,
+    timer: Option<Timer>,
+}
+
+impl OutboxRelay {
+    pub fn new<S: OutboxSink>(storage: JournalStorageRef, sink: S) -> Self {
+        Self {
+            name: "default".to_string(),
+            storage,
+            sink: Box::new(sink),
+            offsets: HashMap::new(),
+            poll_interval: Duration::from_secs(1),
+            batch_size: 100,
+            timer: None,
+        }
+    }
+
+    /// Sets the name of the relay, allowing multiple relays (each with their own offsets),
+    /// to be started on the same node
+    pub fn with_name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// How often the relay checks the tracked journals for new events, defaults to 1 second
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// The maximum number of records provided to each [`OutboxSink::publish`] call,
+    /// defaults to 100
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Tracks the journal of the provided persistence id from the moment the relay starts,
+    /// see [`Track`]
+    pub fn with_source(mut self, persistence_id: impl ToString) -> Self {
+        self.offsets.entry(persistence_id.to_string()).or_default();
+        self
+    }
+
+    pub fn actor_id(name: &str) -> ActorId {
+        format!("outbox-relay-{}", name).into_actor_id()
+    }
+
+    pub async fn start(self, system: &ActorSystem) -> Result<LocalActorRef<Self>, ActorRefErr> {
+        let actor_id = Self::actor_id(&self.name);
+        self.into_actor(Some(actor_id), system).await
+    }
+
+    fn offsets_persistence_id(&self) -> String {
+        format!("coerce-outbox-{}", &self.name)
+    }
+
+    async fn load_offsets(&mut self) -> anyhow::Result<()> {
+        let snapshot = self
+            .storage
+            .read_latest_snapshot(&self.offsets_persistence_id())
+            .await?;
+
+        if let Some(snapshot) = snapshot {
+            let stored: OutboxOffsets = serde_json::from_slice(snapshot.bytes.as_slice())?;
+            for (persistence_id, offset) in stored.offsets {
+                self.offsets.insert(persistence_id, offset);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn store_offsets(&self) -> anyhow::Result<()> {
+        let bytes = serde_json::to_vec(&OutboxOffsets {
+            offsets: self.offsets.clone(),
+        })?;
+
+        let entry = JournalEntry {
+            sequence: 0,
+            payload_type: OUTBOX_OFFSETS_PAYLOAD_TYPE.into(),
+            bytes: Arc::new(bytes),
+            metadata: EventMetadata::default().stamp(),
+        };
+
+        self.storage
+            .write_snapshot(&self.offsets_persistence_id(), entry)
+            .await
+    }
+
+    /// Publishes any events persisted since the last relayed offset, returning the number of
+    /// events published
+    async fn relay(&mut self, persistence_id: &str) -> anyhow::Result<usize> {
+        let offset = self.offsets.get(persistence_id).copied().flatten();
+        let entries: Vec<JournalEntry> = self
+            .storage
+            .read_latest_messages(persistence_id, offset.unwrap_or_default())
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|entry| offset.is_none_or(|offset| entry.sequence > offset))
+            .collect();
+
+        let persistence_id: Arc<str> = persistence_id.into();
+        let mut published = 0;
+        for batch in entries.chunks(self.batch_size) {
+            let records: Vec<OutboxRecord> = batch
+                .iter()
+                .map(|entry| OutboxRecord {
+                    persistence_id: persistence_id.clone(),
+                    sequence: entry.sequence,
+                    payload_type: entry.payload_type.clone(),
+                    bytes: entry.bytes.clone(),
+                    metadata: entry.metadata.clone(),
+                })
+                .collect();
+
+            self.sink.publish(&records).await?;
+
+            if let Some(last) = records.last() {
+                self.offsets
+                    .insert(persistence_id.to_string(), Some(last.sequence));
+            }
+
+            published += records.len();
+        }
+
+        Ok(published)
+    }
+
+    async fn relay_all(&mut self) -> anyhow::Result<usize> {
+        let offsets = self.offsets.clone();
+        let mut published = 0;
+        let mut result = Ok(());
+
+        for persistence_id in offsets.keys() {
+            match self.relay(persistence_id).await {
+                Ok(n) => published += n,
+                Err(e) => {
+                    warn!(
+                        persistence_id = persistence_id,
+                        error = format!("{}", e),
+                        "failed to relay outbox events"
+                    );
+
+                    result = Err(e);
+                }
+            }
+        }
+
+        // a journal may have been partially relayed before failing
+        if self.offsets != offsets {
+            self.store_offsets().await?;
+        }
+
+        result.map(|_| published)
+    }
+}
+
+#[async_trait]
+impl Actor for OutboxRelay {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        if let Err(e) = self.load_offsets().await {
+            error!(
+                relay = &self.name,
+                error = format!("{}", e),
+                "failed to load outbox offsets"
+            );
+        }
+
+        self.timer = Some(Timer::start(
+            self.actor_ref(ctx),
+            self.poll_interval,
+            RelayTick,
+        ));
+
+        debug!(
+            relay = &self.name,
+            sources = self.offsets.len(),
+            "outbox relay started"
+        );
+    }
+
+    async fn stopped(&mut self, _ctx: &mut ActorContext) {
+        if let Some(timer) = self.timer.take() {
+            let _ = timer.stop();
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RelayTick;
+
+impl Message for RelayTick {
+    type Result = ();
+}
+
+impl TimerTick for RelayTick {}
+
+#[async_trait]
+impl Handler<RelayTick> for OutboxRelay {
+    async fn handle(&mut self, _message: RelayTick, _ctx: &mut ActorContext) {
+        // failures are logged by `relay_all`, and retried on the next tick
+        let _ = self.relay_all().await;
+    }
+}
+
+/// Starts relaying events from the journal of the provided persistence id,
+/// including any events persisted before the journal was tracked
+pub struct Track(pub String);
+
+impl Message for Track {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<Track> for OutboxRelay {
+    async fn handle(&mut self, message: Track, _ctx: &mut ActorContext) {
+        if self.offsets.contains_key(&message.0) {
+            return;
+        }
+
+        self.offsets.insert(message.0, None);
+        if let Err(e) = self.store_offsets().await {
+            error!(error = format!("{}", e), "failed to store outbox offsets");
+        }
+    }
+}
+
+/// Stops relaying events from the journal of the provided persistence id, the journal's offset is
+/// discarded, so if the journal is tracked again, relaying starts from the beginning of the journal
+pub struct Untrack(pub String);
+
+impl Message for Untrack {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<Untrack> for OutboxRelay {
+    async fn handle(&mut self, message: Untrack, _ctx: &mut ActorContext) {
+        if self.offsets.remove(&message.0).is_some() {
+            if let Err(e) = self.store_offsets().await {
+                error!(error = format!("{}", e), "failed to store outbox offsets");
+            }
+        }
+    }
+}
+
+/// Relays any pending events immediately, rather than waiting for the next poll,
+/// returning the number of events published
+pub struct Flush;
+
+impl Message for Flush {
+    type Result = anyhow::Result<usize>;
+}
+
+#[async_trait]
+impl Handler<Flush> for OutboxRelay {
+    async fn handle(&mut self, _message: Flush, _ctx: &mut ActorContext) -> anyhow::Result<usize> {
+        self.relay_all().await
+    }
+}
+
+/// Returns the sequence of the last event published from each tracked journal,
+/// `None` if no events have been published from the journal yet
+pub struct GetOffsets;
+
+impl Message for GetOffsets {
+    type Result = HashMap<String, Option<i64>>;
+}
+
+#[async_trait]
+impl Handler<GetOffsets> for OutboxRelay {
+    async fn handle(
+        &mut self,
+        _message: GetOffsets,
+        _ctx: &mut ActorContext,
+    ) -> HashMap<String, Option<i64>> {
+        self.offsets.clone()
+    }
+}
@@ -0,0 +1,199 @@
+//! Persistence query, reading events across every persistent actor
+//!
+//! Events can be tagged when they're persisted, via [`EventMetadata::with_tag`], and read back by
+//! tag from the [`ReadJournal`], regardless of which actor persisted them. This is the read side of
+//! event sourcing, used to build projections and read models from the events written by many
+//! actors.
+//!
+//! Each event read by tag has an offset, which increases in the order tagged events were written.
+//! Consumers record the offset of the last event they processed, and pass it back to resume the
+//! stream from where they left off, after a restart.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! // when persisting an event
+//! self.persist_with_metadata(&event, EventMetadata::new().with_tag("order"), ctx).await?;
+//!
+//! // when building the projection
+//! let read_journal = system.persistence().unwrap().read_journal().unwrap();
+//! let mut events = read_journal.events_by_tag("order", projection.last_offset);
+//! while let Some(event) = events.next().await {
+//!     let event = event?;
+//!     projection.apply(event.message::<OrderPlaced>()?);
+//!     projection.last_offset = event.offset;
+//! }
+//! ```
+//!
+//! [`EventMetadata::with_tag`]: crate::persistent::journal::metadata::EventMetadata::with_tag
+
+use crate::actor::message::{Message, MessageUnwrapErr};
+use crate::persistent::journal::storage::{JournalStorageRef, TaggedEntry};
+use crate::persistent::{Persistence, PersistentActor};
+
+use futures::{stream, Stream};
+use std::any::TypeId;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The number of events read from storage at a time
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// How long a live stream waits before checking for new events, once it has caught up
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone)]
+pub struct ReadJournal {
+    storage: JournalStorageRef,
+    batch_size: usize,
+    poll_interval: Duration,
+}
+
+impl ReadJournal {
+    pub fn new(storage: JournalStorageRef) -> Self {
+        Self {
+            storage,
+            batch_size: DEFAULT_BATCH_SIZE,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Streams events tagged with `tag`, with an offset greater than `offset`, in offset order.
+    ///
+    /// The stream doesn't complete once every current event has been read, it waits for events
+    /// to be written, checking for new events every `poll_interval`. If reading from storage
+    /// fails, the error is returned and the stream completes, it can be resumed from the offset
+    /// of the last event received.
+    pub fn events_by_tag(
+        &self,
+        tag: impl Into<String>,
+        offset: i64,
+    ) -> impl Stream<Item = anyhow::Result<TaggedEntry>> + Send + 'static {
+        self.query(tag.into(), offset, true)
+    }
+
+    /// Streams events tagged with `tag`, with an offset greater than `offset`, in offset order,
+    /// completing once every current event has been read.
+    pub fn current_events_by_tag(
+        &self,
+        tag: impl Into<String>,
+        offset: i64,
+    ) -> impl Stream<Item = anyhow::Result<TaggedEntry>> + Send + 'static {
+        self.query(tag.into(), offset, false)
+    }
+
+    fn query(
+        &self,
+        tag: String,
+        offset: i64,
+        live: bool,
+    ) -> impl Stream<Item = anyhow::Result<TaggedEntry>> + Send + 'static {
+        let query = TagQuery {
+            storage: self.storage.clone(),
+            tag,
+            offset,
+            batch_size: self.batch_size,
+            poll_interval: self.poll_interval,
+            live,
+            buffer: VecDeque::new(),
+            complete: false,
+        };
+
+        stream::unfold(query, |mut query| async move {
+            let next = query.next().await?;
+            Some((next, query))
+        })
+    }
+}
+
+struct TagQuery {
+    storage: JournalStorageRef,
+    tag: String,
+    offset: i64,
+    batch_size: usize,
+    poll_interval: Duration,
+    live: bool,
+    buffer: VecDeque<TaggedEntry>,
+    complete: bool,
+}
+
+impl TagQuery {
+    async fn next(&mut self) -> Option<anyhow::Result<TaggedEntry>> {
+        loop {
+            if let Some(entry) = self.buffer.pop_front() {
+                self.offset = entry.offset;
+                return Some(Ok(entry));
+            }
+
+            if self.complete {
+                return None;
+            }
+
+            let entries = match self
+                .storage
+                .read_messages_by_tag(&self.tag, self.offset, self.batch_size)
+                .await
+            {
+                Ok(entries) => entries,
+                Err(e) => {
+                    self.complete = true;
+                    return Some(Err(e));
+                }
+            };
+
+            trace!(
+                "read {} events (tag={}, from_offset={})",
+                entries.len(),
+                &self.tag,
+                self.offset
+            );
+
+            let caught_up = entries.len() < self.batch_size;
+            self.buffer.extend(entries);
+
+            if caught_up {
+                if !self.live {
+                    self.complete = true;
+                } else if self.buffer.is_empty() {
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        }
+    }
+}
+
+impl TaggedEntry {
+    /// Deserialises the event, check [`JournalEntry::payload_type`] to find which message
+    /// type the event was persisted as
+    ///
+    /// [`JournalEntry::payload_type`]: crate::persistent::journal::storage::JournalEntry::payload_type
+    pub fn message<M: Message>(&self) -> Result<M, MessageUnwrapErr> {
+        M::from_bytes(self.entry.bytes.to_vec())
+    }
+}
+
+impl Persistence {
+    /// Returns a [`ReadJournal`] over the default storage provider, or `None` if the provider
+    /// has no journal storage
+    pub fn read_journal(&self) -> Option<ReadJournal> {
+        self.default_provider
+            .journal_storage()
+            .map(ReadJournal::new)
+    }
+
+    /// Returns a [`ReadJournal`] over the storage provider used by actors of type `A`
+    pub fn read_journal_for<A: PersistentActor>(&self) -> Option<ReadJournal> {
+        self.provider(TypeId::of::<A>())
+            .journal_storage()
+            .map(ReadJournal::new)
+    }
+}
@@ -85,12 +85,20 @@ async fn load_journal<A: PersistentActor>(
     persistence_key: String,
     ctx: &mut ActorContext,
 ) -> Result<RecoveredJournal<A>, RecoveryErr> {
+    let shipped_snapshot = ctx
+        .system()
+        .persistence()
+        .and_then(|p| p.shipped_snapshots().take(&persistence_key));
+
     let journal = ctx.persistence_mut().init_journal::<A>(persistence_key);
 
-    let snapshot = journal
-        .recover_snapshot()
-        .await
-        .map_err(RecoveryErr::Snapshot)?;
+    let snapshot = match shipped_snapshot {
+        Some(snapshot) => journal.recover_shipped_snapshot(snapshot.into())?,
+        None => journal
+            .recover_snapshot()
+            .await
+            .map_err(RecoveryErr::Snapshot)?,
+    };
 
     let messages = journal
         .recover_messages()
@@ -1,25 +1,28 @@
 use crate::actor::context::ActorContext;
 use crate::actor::message::Handler;
 use crate::actor::scheduler::ActorType;
+use crate::actor::supervised::RestartPolicy;
 use crate::actor::system::ActorSystem;
 use crate::actor::{Actor, LocalActorRef};
-use crate::remote::actor::message::{ClientConnected, ClientWrite, NewClient, RemoveClient};
-use crate::remote::net::client::send::Write;
-use crate::remote::net::client::RemoteClient;
+use crate::remote::actor::message::{
+    ClientConnected, ClientWrite, GetClients, NewClient, RemoveClient, RemoveNodeClient, SetRemote,
+};
+use crate::remote::net::client::send::{FlushPriorityWrites, WriteFrame};
+use crate::remote::net::client::{PriorityLane, RemoteClient, WriteLane};
 use crate::remote::system::NodeId;
-use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 pub struct RemoteClientRegistry {
     node_addr_registry: HashMap<String, LocalActorRef<RemoteClient>>,
     node_id_registry: HashMap<NodeId, LocalActorRef<RemoteClient>>,
+    priority_lanes: HashMap<NodeId, PriorityLane>,
 }
 
 #[async_trait]
 impl Actor for RemoteClientRegistry {
     async fn stopped(&mut self, _ctx: &mut ActorContext) {
         for client in &self.node_id_registry {
-            let _ = client.1.stop().await;
+            let _ = client.1.stop(false).await;
         }
     }
 }
@@ -31,6 +34,7 @@ impl RemoteClientRegistry {
             RemoteClientRegistry {
                 node_addr_registry: HashMap::new(),
                 node_id_registry: HashMap::new(),
+                priority_lanes: HashMap::new(),
             },
             ActorType::Tracked,
         )
@@ -39,32 +43,62 @@ impl RemoteClientRegistry {
     }
 }
 
+#[async_trait]
+impl Handler<SetRemote> for RemoteClientRegistry {
+    async fn handle(&mut self, message: SetRemote, ctx: &mut ActorContext) {
+        // clients are spawned (and restarted) using the registry's system, which must be
+        // configured for remoting.
+        ctx.set_system(message.0.actor_system().clone());
+    }
+}
+
 #[async_trait]
 impl Handler<NewClient> for RemoteClientRegistry {
     async fn handle(
         &mut self,
         message: NewClient,
-        _ctx: &mut ActorContext,
+        ctx: &mut ActorContext,
     ) -> Option<LocalActorRef<RemoteClient>> {
-        let node_addr_entry = self.node_addr_registry.entry(message.addr.clone());
-        let entry = match node_addr_entry {
-            Entry::Vacant(vacant_entry) => vacant_entry,
-            Entry::Occupied(entry) => {
-                return {
-                    debug!("RemoteClient already exists for addr={}", &message.addr);
-                    let client = entry.get();
-                    Some(client.clone())
-                }
+        if let Some(client) = self.node_addr_registry.get(&message.addr).cloned() {
+            let client = restarted_client(&client, ctx).unwrap_or(client);
+            if client.is_valid() {
+                debug!("RemoteClient already exists for addr={}", &message.addr);
+                self.node_addr_registry
+                    .insert(message.addr.clone(), client.clone());
+
+                return Some(client);
             }
-        };
+
+            // the client has stopped and was not restarted, so a new one is created
+            self.node_addr_registry.remove(&message.addr);
+        }
 
         debug!("creating RemoteClient, addr={}", &message.addr);
 
-        let client_actor =
-            RemoteClient::new(message.addr.clone(), message.system, message.client_type).await;
+        // clients are supervised by the registry, if a client fails, it is
+        // recreated and will re-register itself once it has reconnected.
+        let client_actor = ctx
+            .spawn_restartable(
+                RemoteClient::actor_id(&message.addr),
+                RemoteClient::factory(message.addr.clone(), message.client_type),
+                RestartPolicy::default(),
+            )
+            .await;
+
+        let client_actor = match client_actor {
+            Ok(client_actor) => client_actor,
+            Err(e) => {
+                error!(
+                    "failed to create RemoteClient, addr={}, error={}",
+                    &message.addr, e
+                );
+                return None;
+            }
+        };
 
         debug!("created RemoteClient, addr={}", &message.addr);
-        entry.insert(client_actor.clone());
+        self.node_addr_registry
+            .insert(message.addr.clone(), client_actor.clone());
 
         Some(client_actor)
     }
@@ -73,11 +107,20 @@ impl Handler<NewClient> for RemoteClientRegistry {
 #[async_trait]
 impl Handler<RemoveClient> for RemoteClientRegistry {
     async fn handle(&mut self, message: RemoveClient, _: &mut ActorContext) {
+        let client = self.node_addr_registry.remove(&message.addr);
+
         if let Some(node_id) = message.node_id {
-            self.node_id_registry.remove(&node_id);
-        }
+            // the node may be registered against a different client, if so, it is kept
+            let is_node_client = match (client, self.node_id_registry.get(&node_id)) {
+                (Some(client), Some(node_client)) => client.actor_id() == node_client.actor_id(),
+                _ => true,
+            };
 
-        self.node_addr_registry.remove(&message.addr);
+            if is_node_client {
+                self.node_id_registry.remove(&node_id);
+                self.priority_lanes.remove(&node_id);
+            }
+        }
 
         debug!(
             addr = &message.addr,
@@ -87,30 +130,193 @@ impl Handler<RemoveClient> for RemoteClientRegistry {
     }
 }
 
+#[async_trait]
+impl Handler<RemoveNodeClient> for RemoteClientRegistry {
+    async fn handle(&mut self, message: RemoveNodeClient, ctx: &mut ActorContext) {
+        let node_id = message.node_id;
+        let mut removed = vec![];
+
+        if let Some(client) = self.node_id_registry.remove(&node_id) {
+            removed.push(restarted_client(&client, ctx).unwrap_or(client));
+        }
+
+        if let Some(client) = message
+            .addr
+            .and_then(|addr| self.node_addr_registry.remove(&addr))
+        {
+            let client = restarted_client(&client, ctx).unwrap_or(client);
+            if !removed.iter().any(|c| c.actor_id() == client.actor_id()) {
+                removed.push(client);
+            }
+        }
+
+        self.priority_lanes.remove(&node_id);
+
+        // the client may also be registered against other addresses, e.g. the seed address
+        // the node was discovered via
+        self.node_addr_registry.retain(|_, client| {
+            !removed
+                .iter()
+                .any(|removed| removed.actor_id() == client.actor_id())
+        });
+
+        for client in &removed {
+            let _ = client.notify_stop();
+        }
+
+        debug!(
+            node_id = node_id,
+            clients = removed.len(),
+            "node clients removed"
+        );
+    }
+}
+
+#[async_trait]
+impl Handler<GetClients> for RemoteClientRegistry {
+    async fn handle(
+        &mut self,
+        _: GetClients,
+        _ctx: &mut ActorContext,
+    ) -> Vec<(String, LocalActorRef<RemoteClient>)> {
+        // a client can be registered against more than one address, each client is only returned once
+        let mut clients: Vec<(String, LocalActorRef<RemoteClient>)> = vec![];
+        for (addr, client) in &self.node_addr_registry {
+            if !clients
+                .iter()
+                .any(|(_, c)| c.actor_id() == client.actor_id())
+            {
+                clients.push((addr.clone(), client.clone()));
+            }
+        }
+
+        clients
+    }
+}
+
 #[async_trait]
 impl Handler<ClientConnected> for RemoteClientRegistry {
     async fn handle(&mut self, message: ClientConnected, _ctx: &mut ActorContext) {
-        self.node_id_registry
-            .insert(message.remote_node_id, message.client_actor_ref);
+        let node_id = message.remote_node_id;
+        let client = message.client_actor_ref;
+
+        let existing_client = self
+            .node_id_registry
+            .get(&node_id)
+            .filter(|existing| existing.is_valid() && existing.actor_id() != client.actor_id())
+            .cloned();
+
+        if let Some(existing_client) = existing_client {
+            // the node has been dialled more than once (for example, once via a seed address and
+            // again via the address it advertises), only one connection is kept, so messages
+            // aren't split between multiple clients.
+            //
+            // two nodes dialling each other at the same time doesn't result in a duplicate, each
+            // node writes to the other via its own outbound client, and inbound connections are
+            // handled by a `RemoteSession`, which is never registered here.
+            let (kept, closed) = if client.actor_id() == &RemoteClient::actor_id(&message.addr) {
+                (client, existing_client)
+            } else {
+                (existing_client, client)
+            };
+
+            info!(
+                node_id = node_id,
+                kept = kept.actor_id().as_ref(),
+                closed = closed.actor_id().as_ref(),
+                "duplicate connection to node, closing connection"
+            );
+
+            for registered_client in self.node_addr_registry.values_mut() {
+                if registered_client.actor_id() == closed.actor_id() {
+                    *registered_client = kept.clone();
+                }
+            }
+
+            if kept.actor_id() != self.node_id_registry[&node_id].actor_id() {
+                self.node_id_registry.insert(node_id, kept);
+                self.priority_lanes.insert(node_id, message.priority_lane);
+            }
+
+            // the closing client may still be waiting for this message to be handled,
+            // so it is stopped without waiting for it to complete.
+            let _ = closed.notify_stop();
+            return;
+        }
+
+        self.node_id_registry.insert(node_id, client);
+        self.priority_lanes.insert(node_id, message.priority_lane);
     }
 }
 
 #[async_trait]
 impl Handler<ClientWrite> for RemoteClientRegistry {
-    async fn handle(&mut self, message: ClientWrite, _ctx: &mut ActorContext) {
-        let node_id = message.0;
-        let message = message.1;
+    async fn handle(&mut self, message: ClientWrite, ctx: &mut ActorContext) {
+        let node_id = message.node_id;
+
+        if let Some(client) = self
+            .node_id_registry
+            .get(&node_id)
+            .and_then(|client| restarted_client(client, ctx))
+        {
+            self.node_id_registry.insert(node_id, client);
+        }
 
         // TODO: we could open multiple clients per node and use some routing mechanism
         //       to potentially improve throughput, whilst still maintaining message ordering
 
         if let Some(client) = self.node_id_registry.get(&node_id) {
-            trace!("emitting message ({:?}) to node_id={}", &message, &node_id);
-            client.notify(Write(message)).expect("send client msg");
+            trace!(
+                "emitting frame (len={}) to node_id={}",
+                message.frame.len(),
+                &node_id
+            );
+
+            // replies and control messages skip the client's mailbox, they're picked up
+            // before the next queued write is processed.
+            match self.priority_lanes.get(&node_id) {
+                Some(priority_lane) if message.lane == WriteLane::System => {
+                    priority_lane.push(message.frame);
+                    if let Err(e) = client.notify(FlushPriorityWrites) {
+                        warn!(
+                            "failed to flush priority writes to client (node_id={}), error={}",
+                            &node_id, e
+                        );
+                    }
+                }
+                _ => {
+                    if let Err(e) = client.notify(WriteFrame(message.frame, message.semantics)) {
+                        warn!(
+                            "failed to write to client (node_id={}), error={}",
+                            &node_id, e
+                        );
+                    }
+                }
+            }
+
             trace!("written data to client");
         } else {
             // TODO: should we buffer the message incase the client will eventually exist
-            warn!("attempted to write message to node_id={} but no client was registered (message={:?})", &node_id, &message);
+            warn!(
+                "attempted to write message to node_id={} but no client was registered (len={})",
+                &node_id,
+                message.frame.len()
+            );
         }
     }
 }
+
+/// Resolves the current instance of the client, if the client has been restarted by
+/// the registry (as its supervisor), the reference to the new instance is returned.
+fn restarted_client(
+    client: &LocalActorRef<RemoteClient>,
+    ctx: &ActorContext,
+) -> Option<LocalActorRef<RemoteClient>> {
+    if client.is_valid() {
+        return None;
+    }
+
+    ctx.supervised()
+        .and_then(|supervised| supervised.child::<RemoteClient>(client.actor_id()))
+        .filter(|client| client.is_valid())
+}
@@ -2,12 +2,15 @@ use crate::remote::actor::RemoteRequest;
 use crate::remote::cluster::node::{RemoteNode, RemoteNodeState};
 use crate::remote::system::{NodeId, RemoteActorSystem};
 
-use crate::actor::message::Message;
-use crate::remote::net::client::{ClientType, RemoteClient};
+use crate::actor::message::{DeliverySemantics, Message};
+use crate::remote::net::buffer::BufferPool;
+use crate::remote::net::client::{ClientType, PriorityLane, RemoteClient, WriteLane};
+use crate::remote::net::encoding::{MessageCodec, ProtobufCodec};
 use crate::remote::net::message::SessionEvent;
 
 use crate::actor::{ActorId, LocalActorRef};
 
+use bytes::Bytes;
 use uuid::Uuid;
 
 pub struct SetRemote(pub RemoteActorSystem);
@@ -53,10 +56,29 @@ impl Message for RemoveClient {
     type Result = ();
 }
 
+/// Removes and stops the client connected to the node, along with any client created for
+/// the node's address, used once the node has been removed from the cluster
+pub struct RemoveNodeClient {
+    pub node_id: NodeId,
+    pub addr: Option<String>,
+}
+
+impl Message for RemoveNodeClient {
+    type Result = ();
+}
+
+/// Returns each client within the registry, along with the address it was created for
+pub struct GetClients;
+
+impl Message for GetClients {
+    type Result = Vec<(String, LocalActorRef<RemoteClient>)>;
+}
+
 pub struct ClientConnected {
     pub addr: String,
     pub remote_node_id: NodeId,
     pub client_actor_ref: LocalActorRef<RemoteClient>,
+    pub priority_lane: PriorityLane,
 }
 
 impl Message for ClientConnected {
@@ -87,7 +109,53 @@ impl Message for NodeTerminated {
     type Result = ();
 }
 
-pub struct ClientWrite(pub NodeId, pub SessionEvent);
+pub struct QuarantineNode(pub NodeId);
+
+impl Message for QuarantineNode {
+    type Result = bool;
+}
+
+/// A frame to be written to a node, encoded by the sender rather than by the node's client,
+/// so writes to the same node aren't all encoded one at a time on the client actor.
+pub struct ClientWrite {
+    pub node_id: NodeId,
+    pub frame: Bytes,
+    pub lane: WriteLane,
+    pub semantics: DeliverySemantics,
+}
+
+impl ClientWrite {
+    /// Encodes the message as protobuf, returning `None` if the message could not be encoded
+    pub fn new(node_id: NodeId, message: &SessionEvent) -> Option<ClientWrite> {
+        Self::encode(node_id, message, &ProtobufCodec)
+    }
+
+    /// Encodes the message with the provided codec, returning `None` if the message could not
+    /// be encoded
+    pub fn encode(
+        node_id: NodeId,
+        message: &SessionEvent,
+        codec: &dyn MessageCodec,
+    ) -> Option<ClientWrite> {
+        let lane = message.lane();
+        BufferPool::global()
+            .encode_with(|buffer| message.write_to_buffer_with(codec, buffer))
+            .map(|frame| ClientWrite {
+                node_id,
+                frame,
+                lane,
+                semantics: lane.semantics(),
+            })
+    }
+
+    /// Writes the frame with the [`DeliverySemantics`] declared by the message it contains,
+    /// see [`Message::delivery_semantics`](crate::actor::message::Message::delivery_semantics)
+    pub fn with_semantics(mut self, semantics: DeliverySemantics) -> ClientWrite {
+        self.lane = WriteLane::of(semantics);
+        self.semantics = semantics;
+        self
+    }
+}
 
 impl Message for ClientWrite {
     type Result = ();
@@ -109,6 +177,18 @@ impl Message for RegisterActor {
     type Result = ();
 }
 
+/// Removes the actor's registration, if it's still registered to `node_id`,
+/// so the actor's location is looked up again the next time it's needed
+#[derive(Debug)]
+pub struct InvalidateActor {
+    pub actor_id: ActorId,
+    pub node_id: NodeId,
+}
+
+impl Message for InvalidateActor {
+    type Result = ();
+}
+
 pub struct GetActorNode {
     pub actor_id: ActorId,
     pub sender: tokio::sync::oneshot::Sender<Option<NodeId>>,
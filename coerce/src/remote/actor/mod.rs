@@ -1,6 +1,7 @@
-use crate::actor::ActorRefErr;
+use crate::actor::{ActorId, ActorRefErr};
 
 use crate::remote::handler::{ActorHandler, ActorMessageHandler};
+use crate::remote::system::NodeId;
 
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -8,6 +9,7 @@ use uuid::Uuid;
 pub mod clients;
 pub mod message;
 pub mod registry;
+pub mod watcher;
 
 pub(crate) type BoxedActorHandler = Box<dyn ActorHandler + Send + Sync>;
 
@@ -29,10 +31,47 @@ impl RemoteHandler {
     pub fn inflight_request_count(&self) -> usize {
         self.requests.len()
     }
+
+    /// The number of requests sent to `node_id` which are still waiting for a result
+    pub fn inflight_node_request_count(&self, node_id: NodeId) -> usize {
+        self.requests
+            .values()
+            .filter(|request| request.node_id == Some(node_id))
+            .count()
+    }
+
+    /// Removes every request that was sent to `node_id`
+    pub fn pop_node_requests(&mut self, node_id: NodeId) -> Vec<RemoteRequest> {
+        let message_ids: Vec<Uuid> = self
+            .requests
+            .iter()
+            .filter(|(_, request)| request.node_id == Some(node_id))
+            .map(|(message_id, _)| *message_id)
+            .collect();
+
+        message_ids
+            .into_iter()
+            .filter_map(|message_id| self.requests.remove(&message_id))
+            .collect()
+    }
 }
 
 pub struct RemoteRequest {
     pub res_tx: tokio::sync::oneshot::Sender<RemoteResponse>,
+
+    /// The node the request was sent to, if the node is removed from the cluster before
+    /// the result is received, the request fails
+    pub node_id: Option<NodeId>,
+
+    /// The actor and message the request was sent to, published as a dead letter
+    /// if the request fails because the node was removed
+    pub target: Option<RequestTarget>,
+}
+
+pub struct RequestTarget {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    pub message_type: &'static str,
 }
 
 #[derive(Debug)]
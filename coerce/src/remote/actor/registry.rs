@@ -4,18 +4,20 @@ use crate::actor::scheduler::ActorType;
 use crate::actor::system::ActorSystem;
 use crate::actor::{Actor, ActorId, LocalActorRef};
 use crate::remote::actor::message::{
-    GetActorNode, GetNodes, NodeTerminated, RegisterActor, RegisterNode, SetRemote, UpdateNodes,
+    GetActorNode, GetNodes, InvalidateActor, NodeTerminated, QuarantineNode, RegisterActor,
+    RegisterNode, RemoveNodeClient, SetRemote, UpdateNodes,
 };
 use crate::remote::actor::RemoteResponse;
 use crate::remote::cluster::node::{RemoteNode, RemoteNodeState, RemoteNodeStore};
 use crate::remote::net::message::SessionEvent;
 use crate::remote::net::proto::network::{ActorAddress, FindActorEvent};
 use crate::remote::stream::pubsub::{PubSub, Receive, Subscription};
-use crate::remote::stream::system::{SystemEvent, SystemTopic};
+use crate::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
 use crate::remote::system::{NodeId, RemoteActorSystem};
 use protobuf::well_known_types::wrappers::UInt64Value;
 use protobuf::Message;
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 pub struct RemoteRegistry {
@@ -81,6 +83,34 @@ impl RemoteRegistry {
     pub fn register_node(&mut self, node: RemoteNode) {
         self.nodes.add(node);
     }
+
+    /// Clears everything held for a node that's no longer part of the cluster, the node's clients
+    /// are stopped, the locations of actors on the node are forgotten, and requests still waiting
+    /// for a result from the node are failed
+    fn node_removed(&mut self, node_id: NodeId) {
+        let system = match &self.system {
+            Some(system) if system.node_id() != node_id => system,
+            _ => return,
+        };
+
+        let actor_count = self.actors.len();
+        self.actors
+            .retain(|_, actor_node_id| *actor_node_id != node_id);
+
+        let addr = self.nodes.get(&node_id).map(|node| node.addr.clone());
+        let _ = system
+            .client_registry()
+            .notify(RemoveNodeClient { node_id, addr });
+
+        let failed_requests = system.fail_node_requests(node_id);
+
+        debug!(
+            node_id = node_id,
+            actors = actor_count - self.actors.len(),
+            failed_requests = failed_requests,
+            "node removed from registry"
+        );
+    }
 }
 
 #[async_trait]
@@ -100,6 +130,35 @@ impl Handler<NodeTerminated> for RemoteRegistry {
     }
 }
 
+#[async_trait]
+impl Handler<QuarantineNode> for RemoteRegistry {
+    async fn handle(&mut self, message: QuarantineNode, _ctx: &mut ActorContext) -> bool {
+        let node = match self.nodes.node_quarantined(message.0) {
+            Some(node) => node,
+            None => return false,
+        };
+
+        warn!("node_id={} quarantined", message.0);
+
+        self.node_removed(message.0);
+
+        if let Some(system) = &self.system {
+            let system = system.clone();
+            let node = Arc::new(node.into());
+            tokio::spawn(async move {
+                let _ = PubSub::publish_locally(
+                    SystemTopic,
+                    SystemEvent::Cluster(ClusterEvent::NodeQuarantined(node)),
+                    &system,
+                )
+                .await;
+            });
+        }
+
+        true
+    }
+}
+
 #[async_trait]
 impl Handler<GetActorNode> for RemoteRegistry {
     async fn handle(&mut self, message: GetActorNode, _: &mut ActorContext) {
@@ -149,7 +208,7 @@ impl Handler<GetActorNode> for RemoteRegistry {
                 let (res_tx, res_rx) = tokio::sync::oneshot::channel();
 
                 trace!("remote request={}", message_id);
-                system.push_request(message_id, res_tx);
+                system.push_node_request(message_id, assigned_registry_node, res_tx);
 
                 trace!("sending actor lookup request to={}", assigned_registry_node);
                 let trace_id = String::new(); //extract_trace_identifier(&span);
@@ -182,7 +241,14 @@ impl Handler<GetActorNode> for RemoteRegistry {
                             }
                         }
                     }
-                    _ => panic!("get actornode failed"),
+                    _ => {
+                        warn!(
+                            "actor lookup failed (actor_id={}, node_id={})",
+                            &id, assigned_registry_node
+                        );
+
+                        let _ = sender.send(None);
+                    }
                 }
             });
         }
@@ -232,29 +298,27 @@ impl Handler<RegisterActor> for RemoteRegistry {
     }
 }
 
+#[async_trait]
+impl Handler<InvalidateActor> for RemoteRegistry {
+    async fn handle(&mut self, message: InvalidateActor, _ctx: &mut ActorContext) {
+        // the actor may have already been re-registered elsewhere
+        if self.actors.get(&message.actor_id) == Some(&message.node_id) {
+            trace!("invalidating actor: {:?}", &message);
+            self.actors.remove(&message.actor_id);
+        }
+    }
+}
+
 #[async_trait]
 impl Handler<Receive<SystemTopic>> for RemoteRegistry {
-    async fn handle(&mut self, event: Receive<SystemTopic>, ctx: &mut ActorContext) {
+    async fn handle(&mut self, event: Receive<SystemTopic>, _ctx: &mut ActorContext) {
         match event.0.as_ref() {
             SystemEvent::Cluster(e) => {
                 debug!("cluster event - {:?}", e);
-                let system = self.system.as_ref().unwrap().clone();
-                let registry_ref = self.actor_ref(ctx);
-                //
-                // // TODO: remove all of this stuff
-                // tokio::spawn(async move {
-                //     let sys = system;
-                //     let actor_ids = sys
-                //         .actor_system()
-                //         .scheduler()
-                //         .exec::<_, Vec<ActorId>>(|s| s.actors.keys().map(|k| k.clone()).collect())
-                //         .await
-                //         .expect("unable to get active actor ids from scheduler");
-                //
-                //     for actor_id in actor_ids {
-                //         let _ = registry_ref.notify(RegisterActor::new(actor_id, None));
-                //     }
-                // });
+
+                if let ClusterEvent::NodeRemoved(node) = e {
+                    self.node_removed(node.id);
+                }
             }
         }
     }
@@ -0,0 +1,459 @@
+//! Remote death watch
+//!
+//! Every node runs a [`RemoteWatcher`], which registers watches on behalf of local actors with
+//! the [`RemoteWatcher`] of the node hosting the watched actor. When the watched actor stops,
+//! the hosting node notifies every node watching it, which in turn notifies the local watchers.
+//!
+//! If the hosting node is terminated, or removed from the cluster, every watcher of an actor on
+//! that node is sent an [`ActorTerminated`] message with [`TerminationReason::NodeLost`].
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{
+    FromBytes, Handler, Message, MessageUnwrapErr, MessageWrapErr, ToBytes,
+};
+use crate::actor::scheduler::{ActorType, GetBoxedActor};
+use crate::actor::system::ActorSystem;
+use crate::actor::watch::watchers::Watchers;
+use crate::actor::watch::{ActorTerminated, TerminationReason, Watchable};
+use crate::actor::{Actor, ActorId, ActorRef, IntoActorId, LocalActorRef, Receiver};
+use crate::remote::actor::message::{NodeTerminated, SetRemote};
+use crate::remote::actor_ref::RemoteActorRef;
+use crate::remote::net::proto::network::ActorAddress;
+use crate::remote::stream::pubsub::{PubSub, Receive, Subscription};
+use crate::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
+use crate::remote::system::builder::RemoteSystemConfigBuilder;
+use crate::remote::system::{NodeId, RemoteActorSystem};
+use protobuf::well_known_types::wrappers::UInt64Value;
+use std::collections::{HashMap, HashSet};
+
+const REMOTE_WATCHER_ID: &str = "remote-watcher";
+
+pub struct RemoteWatcher {
+    system: Option<RemoteActorSystem>,
+
+    /// Actors on other nodes, watched by local actors
+    watching: HashMap<NodeId, HashMap<ActorId, Watchers>>,
+
+    /// Local actors, watched by actors on other nodes
+    watched_by: HashMap<ActorId, HashSet<NodeId>>,
+
+    system_event_subscription: Option<Subscription>,
+}
+
+impl RemoteWatcher {
+    pub async fn new(sys: &ActorSystem) -> LocalActorRef<RemoteWatcher> {
+        sys.new_actor(
+            REMOTE_WATCHER_ID,
+            RemoteWatcher {
+                system: None,
+                watching: HashMap::new(),
+                watched_by: HashMap::new(),
+                system_event_subscription: None,
+            },
+            ActorType::Tracked,
+        )
+        .await
+        .expect("RemoteWatcher")
+    }
+
+    fn remote_watcher(&self, node_id: NodeId) -> ActorRef<RemoteWatcher> {
+        RemoteActorRef::new(
+            REMOTE_WATCHER_ID.into_actor_id(),
+            node_id,
+            self.system.clone().unwrap(),
+        )
+        .into()
+    }
+
+    fn node_lost(&mut self, node_id: NodeId) {
+        self.watched_by.retain(|_, nodes| {
+            nodes.remove(&node_id);
+            !nodes.is_empty()
+        });
+
+        if let Some(actors) = self.watching.remove(&node_id) {
+            debug!(
+                node_id,
+                watched_actors = actors.len(),
+                "node lost, notifying watchers"
+            );
+
+            let reason = TerminationReason::NodeLost(node_id);
+            for (actor_id, watchers) in actors {
+                notify_watchers(actor_id, watchers, reason);
+            }
+        }
+    }
+}
+
+fn notify_watchers(actor_id: ActorId, watchers: Watchers, reason: TerminationReason) {
+    let actor_terminated = ActorTerminated::remote(actor_id, reason);
+    for watcher in watchers.iter() {
+        let _ = watcher.notify(actor_terminated.clone());
+    }
+}
+
+impl Actor for RemoteWatcher {}
+
+impl<A: Actor> Watchable for RemoteActorRef<A> {
+    fn add_watcher(&self, watcher: Receiver<ActorTerminated>) {
+        let _ = self.system().remote_watcher().notify(WatchRemote {
+            actor_id: self.actor_id().clone(),
+            node_id: self.node_id(),
+            watcher,
+        });
+    }
+
+    fn remove_watcher(&self, watcher_id: ActorId) {
+        let _ = self.system().remote_watcher().notify(UnwatchRemote {
+            actor_id: self.actor_id().clone(),
+            node_id: self.node_id(),
+            watcher_id,
+        });
+    }
+}
+
+/// Registers the handlers used by [`RemoteWatcher`]s to communicate with each other
+pub fn remote_watch(builder: &mut RemoteSystemConfigBuilder) -> &mut RemoteSystemConfigBuilder {
+    builder
+        .with_handler::<RemoteWatcher, WatchActor>("RemoteWatcher.WatchActor")
+        .with_handler::<RemoteWatcher, UnwatchActor>("RemoteWatcher.UnwatchActor")
+        .with_handler::<RemoteWatcher, WatchedActorStopped>("RemoteWatcher.WatchedActorStopped")
+}
+
+pub struct WatchRemote {
+    pub actor_id: ActorId,
+    pub node_id: NodeId,
+    pub watcher: Receiver<ActorTerminated>,
+}
+
+impl Message for WatchRemote {
+    type Result = ();
+}
+
+pub struct UnwatchRemote {
+    pub actor_id: ActorId,
+    pub node_id: NodeId,
+    pub watcher_id: ActorId,
+}
+
+impl Message for UnwatchRemote {
+    type Result = ();
+}
+
+/// Sent to the node hosting `actor_id`, by the watching node `node_id`
+pub struct WatchActor {
+    pub actor_id: ActorId,
+    pub node_id: NodeId,
+}
+
+/// Sent to the node hosting `actor_id`, by the node `node_id`, once it has no more watchers
+pub struct UnwatchActor {
+    pub actor_id: ActorId,
+    pub node_id: NodeId,
+}
+
+/// Sent to every node watching `actor_id`, by the hosting node `node_id`
+pub struct WatchedActorStopped {
+    pub actor_id: ActorId,
+    pub node_id: NodeId,
+}
+
+#[async_trait]
+impl Handler<SetRemote> for RemoteWatcher {
+    async fn handle(&mut self, message: SetRemote, ctx: &mut ActorContext) {
+        let sys = message.0;
+        ctx.set_system(sys.actor_system().clone());
+        self.system = Some(sys);
+
+        let subscription = PubSub::subscribe::<Self, SystemTopic>(SystemTopic, ctx).await;
+        if let Ok(subscription) = subscription {
+            self.system_event_subscription = Some(subscription);
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<WatchRemote> for RemoteWatcher {
+    async fn handle(&mut self, message: WatchRemote, _ctx: &mut ActorContext) {
+        let system = self.system.as_ref().unwrap();
+        let node_lost = !system
+            .get_nodes()
+            .await
+            .into_iter()
+            .find(|n| n.id == message.node_id)
+            .is_some_and(|n| n.status.is_member());
+
+        if node_lost {
+            let _ = message.watcher.notify(ActorTerminated::remote(
+                message.actor_id,
+                TerminationReason::NodeLost(message.node_id),
+            ));
+
+            return;
+        }
+
+        let watchers = self
+            .watching
+            .entry(message.node_id)
+            .or_default()
+            .entry(message.actor_id.clone())
+            .or_default();
+
+        let first_watcher = watchers.is_empty();
+        watchers.add_watcher(message.watcher);
+
+        if first_watcher {
+            let node_id = system.node_id();
+            let remote_watcher = self.remote_watcher(message.node_id);
+            tokio::spawn(async move {
+                let _ = remote_watcher
+                    .notify(WatchActor {
+                        actor_id: message.actor_id,
+                        node_id,
+                    })
+                    .await;
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<UnwatchRemote> for RemoteWatcher {
+    async fn handle(&mut self, message: UnwatchRemote, _ctx: &mut ActorContext) {
+        let Some(actors) = self.watching.get_mut(&message.node_id) else {
+            return;
+        };
+
+        let Some(watchers) = actors.get_mut(&message.actor_id) else {
+            return;
+        };
+
+        watchers.remove_watcher(message.watcher_id);
+        if !watchers.is_empty() {
+            return;
+        }
+
+        actors.remove(&message.actor_id);
+        if actors.is_empty() {
+            self.watching.remove(&message.node_id);
+        }
+
+        let node_id = self.system.as_ref().unwrap().node_id();
+        let remote_watcher = self.remote_watcher(message.node_id);
+        tokio::spawn(async move {
+            let _ = remote_watcher
+                .notify(UnwatchActor {
+                    actor_id: message.actor_id,
+                    node_id,
+                })
+                .await;
+        });
+    }
+}
+
+#[async_trait]
+impl Handler<WatchActor> for RemoteWatcher {
+    async fn handle(&mut self, message: WatchActor, ctx: &mut ActorContext) {
+        debug!(
+            actor_id = message.actor_id.as_ref(),
+            watcher_node_id = message.node_id,
+            "watching actor on behalf of remote node"
+        );
+
+        let nodes = self.watched_by.entry(message.actor_id.clone()).or_default();
+        if !nodes.insert(message.node_id) || nodes.len() > 1 {
+            return;
+        }
+
+        let watcher = Receiver::<ActorTerminated>::from(self.actor_ref(ctx));
+        let actor_ref = ctx
+            .system()
+            .scheduler()
+            .send(GetBoxedActor(message.actor_id.clone()))
+            .await
+            .unwrap_or_default();
+
+        match actor_ref {
+            Some(actor_ref) => actor_ref.add_watcher(watcher),
+            None => {
+                let _ = watcher.notify(ActorTerminated::remote(
+                    message.actor_id,
+                    TerminationReason::Stopped,
+                ));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<UnwatchActor> for RemoteWatcher {
+    async fn handle(&mut self, message: UnwatchActor, ctx: &mut ActorContext) {
+        let Some(nodes) = self.watched_by.get_mut(&message.actor_id) else {
+            return;
+        };
+
+        nodes.remove(&message.node_id);
+        if !nodes.is_empty() {
+            return;
+        }
+
+        self.watched_by.remove(&message.actor_id);
+
+        let actor_ref = ctx
+            .system()
+            .scheduler()
+            .send(GetBoxedActor(message.actor_id))
+            .await
+            .unwrap_or_default();
+
+        if let Some(actor_ref) = actor_ref {
+            actor_ref.remove_watcher(ctx.id().clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<ActorTerminated> for RemoteWatcher {
+    async fn handle(&mut self, message: ActorTerminated, _ctx: &mut ActorContext) {
+        let Some(nodes) = self.watched_by.remove(message.actor_id()) else {
+            return;
+        };
+
+        debug!(
+            actor_id = message.actor_id().as_ref(),
+            watcher_nodes = nodes.len(),
+            "watched actor stopped, notifying remote watchers"
+        );
+
+        let node_id = self.system.as_ref().unwrap().node_id();
+        for watching_node_id in nodes {
+            let remote_watcher = self.remote_watcher(watching_node_id);
+            let actor_id = message.actor_id().clone();
+            tokio::spawn(async move {
+                let _ = remote_watcher
+                    .notify(WatchedActorStopped { actor_id, node_id })
+                    .await;
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<WatchedActorStopped> for RemoteWatcher {
+    async fn handle(&mut self, message: WatchedActorStopped, _ctx: &mut ActorContext) {
+        debug!(
+            actor_id = message.actor_id.as_ref(),
+            node_id = message.node_id,
+            "remote actor stopped, notifying watchers"
+        );
+
+        let Some(actors) = self.watching.get_mut(&message.node_id) else {
+            return;
+        };
+
+        if let Some(watchers) = actors.remove(&message.actor_id) {
+            notify_watchers(message.actor_id, watchers, TerminationReason::Stopped);
+        }
+
+        if actors.is_empty() {
+            self.watching.remove(&message.node_id);
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<NodeTerminated> for RemoteWatcher {
+    async fn handle(&mut self, message: NodeTerminated, _ctx: &mut ActorContext) {
+        self.node_lost(message.0);
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<SystemTopic>> for RemoteWatcher {
+    async fn handle(&mut self, event: Receive<SystemTopic>, _ctx: &mut ActorContext) {
+        if let SystemEvent::Cluster(
+            ClusterEvent::NodeRemoved(node) | ClusterEvent::NodeQuarantined(node),
+        ) = event.0.as_ref()
+        {
+            self.node_lost(node.id);
+        }
+    }
+}
+
+fn address_to_bytes(actor_id: &ActorId, node_id: NodeId) -> Result<Vec<u8>, MessageWrapErr> {
+    ActorAddress {
+        actor_id: actor_id.to_string(),
+        node_id: Some(UInt64Value::from(node_id)).into(),
+        ..Default::default()
+    }
+    .to_bytes()
+}
+
+fn address_from_bytes(buf: Vec<u8>) -> Result<(ActorId, NodeId), MessageUnwrapErr> {
+    let address = ActorAddress::from_bytes(buf)?;
+    match address.node_id.into_option() {
+        Some(node_id) => Ok((address.actor_id.into_actor_id(), node_id.value)),
+        None => Err(MessageUnwrapErr::DeserializationErr),
+    }
+}
+
+impl Message for WatchActor {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        address_to_bytes(&self.actor_id, self.node_id)
+    }
+
+    fn from_bytes(buf: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        address_from_bytes(buf).map(|(actor_id, node_id)| Self { actor_id, node_id })
+    }
+
+    fn read_remote_result(_: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        Ok(())
+    }
+
+    fn write_remote_result(_res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+}
+
+impl Message for UnwatchActor {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        address_to_bytes(&self.actor_id, self.node_id)
+    }
+
+    fn from_bytes(buf: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        address_from_bytes(buf).map(|(actor_id, node_id)| Self { actor_id, node_id })
+    }
+
+    fn read_remote_result(_: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        Ok(())
+    }
+
+    fn write_remote_result(_res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+}
+
+impl Message for WatchedActorStopped {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        address_to_bytes(&self.actor_id, self.node_id)
+    }
+
+    fn from_bytes(buf: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        address_from_bytes(buf).map(|(actor_id, node_id)| Self { actor_id, node_id })
+    }
+
+    fn read_remote_result(_: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        Ok(())
+    }
+
+    fn write_remote_result(_res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+}
@@ -1,7 +1,8 @@
-use crate::actor::message::{Envelope, Handler, Message, MessageWrapErr};
-use crate::actor::{Actor, ActorId, ActorRefErr};
-use crate::remote::actor::RemoteResponse;
+use crate::actor::message::{DeliverySemantics, Envelope, Handler, Message, MessageWrapErr};
+use crate::actor::{Actor, ActorId, ActorRef, ActorRefErr, LocalActorRef};
+use crate::remote::actor::{RemoteResponse, RequestTarget};
 use crate::remote::net::message::SessionEvent;
+use crate::remote::net::metrics::NetworkMetrics;
 use crate::remote::net::proto::network::MessageRequest;
 use crate::remote::system::{NodeId, RemoteActorSystem};
 
@@ -23,9 +24,25 @@ where
     _a: PhantomData<A>,
 }
 
+/// A serializable reference to an actor, made up of the actor's ID and the ID of the node the
+/// actor is running on, allowing references to be embedded in messages sent to other nodes,
+/// for example so the receiver knows which actor to reply to.
+///
+/// The receiving node turns it back into an [`ActorRef`] with [`SerializableActorRef::resolve`].
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SerializableActorRef<A: Actor> {
+    actor_id: ActorId,
+    node_id: NodeId,
+
+    #[serde(skip)]
+    _a: PhantomData<fn() -> A>,
+}
+
 pub struct RemoteMessageHeader {
     pub actor_id: ActorId,
     pub handler_type: String,
+    pub message_version: u32,
 }
 
 impl<A: Actor> RemoteActorRef<A>
@@ -49,6 +66,58 @@ where
         self.node_id
     }
 
+    pub(crate) fn system(&self) -> &RemoteActorSystem {
+        &self.system
+    }
+
+    /// Resolves the actor if the reference points at this node, so the message can be dispatched
+    /// to it directly rather than being serialised. Returns `None` if the actor is on another node,
+    /// or if messages of this type request a specific version of the actor's handler, which is
+    /// only resolved once the message has been serialised.
+    pub(crate) async fn resolve_local<Msg: Message>(
+        &self,
+    ) -> Option<Result<LocalActorRef<A>, ActorRefErr>> {
+        if self.node_id != self.system.node_id() || self.is_versioned::<Msg>() {
+            return None;
+        }
+
+        match self
+            .system
+            .actor_system()
+            .get_tracked_actor(self.id.clone())
+            .await
+        {
+            Some(actor_ref) => {
+                NetworkMetrics::incr_local_bypass(A::type_name(), Msg::type_name());
+                Some(Ok(actor_ref))
+            }
+            None => {
+                // the actor is no longer on this node, so it's located again next time
+                self.system.invalidate_actor(self.id.clone(), self.node_id);
+                Some(Err(ActorRefErr::ActorUnavailable))
+            }
+        }
+    }
+
+    fn is_versioned<Msg: Message>(&self) -> bool {
+        self.system
+            .handler_name::<A, Msg>()
+            .is_some_and(|handler_type| self.system.message_version(&handler_type) != 0)
+    }
+
+    /// Handles the message with the handler version it requests, for messages sent to actors
+    /// on this node that couldn't be dispatched to the actor directly
+    async fn handle_local(&self, request: MessageRequest) -> Result<Vec<u8>, ActorRefErr> {
+        self.system
+            .handle_versioned_message(
+                &request.handler_type,
+                request.message_version,
+                self.id.clone(),
+                &request.message,
+            )
+            .await
+    }
+
     pub async fn notify<Msg: Message>(&self, msg: Envelope<Msg>) -> Result<(), ActorRefErr>
     where
         A: Handler<Msg>,
@@ -59,10 +128,22 @@ where
         // let span = tracing::trace_span!("RemoteActorRef::notify", actor_type, message_type);
         // let _enter = span.enter();
 
+        let semantics = Msg::delivery_semantics();
+        if semantics == DeliverySemantics::Reliable {
+            // sent as a request, the result acknowledges that the message was handled
+            return self.request(msg).await.map(|_| ());
+        }
+
         let id = Uuid::new_v4();
 
         let request = self.create_request(msg, String::new(), id, false)?;
-        self.system.notify_node(self.node_id, request).await;
+        if self.node_id == self.system.node_id() {
+            return self.handle_local(request).await.map(|_| ());
+        }
+
+        self.system
+            .notify_node_with_semantics(self.node_id, SessionEvent::NotifyActor(request), semantics)
+            .await;
 
         Ok(())
     }
@@ -72,28 +153,59 @@ where
         A: Handler<Msg>,
         Msg: 'static + Send + Sync,
         <Msg as Message>::Result: 'static + Send + Sync,
+    {
+        let res = self.request(msg).await?;
+        Msg::read_remote_result(res).map_err(|e| {
+            error!("failed to decode result");
+            ActorRefErr::Deserialisation(e)
+        })
+    }
+
+    /// Sends the message, returning the encoded result once the message has been handled
+    async fn request<Msg: Message>(&self, msg: Envelope<Msg>) -> Result<Vec<u8>, ActorRefErr>
+    where
+        A: Handler<Msg>,
     {
         let id = Uuid::new_v4();
 
-        let (res_tx, res_rx) = oneshot::channel();
-        self.system.push_request(id, res_tx);
+        let request = self.create_request(msg, String::new(), id, true)?;
+        if self.node_id == self.system.node_id() {
+            return self.handle_local(request).await;
+        }
 
-        let event = self.create_request(msg, String::new(), id, true)?;
+        let (res_tx, res_rx) = oneshot::channel();
+        self.system.push_actor_request(
+            id,
+            self.node_id,
+            RequestTarget {
+                actor_id: self.id.clone(),
+                actor_type: A::type_name(),
+                message_type: Msg::type_name(),
+            },
+            res_tx,
+        );
 
         // TODO: we could make this fail fast if the node is known to be terminated?
-        self.system.notify_node(self.node_id, event).await;
+        self.system
+            .notify_node_with_semantics(
+                self.node_id,
+                SessionEvent::NotifyActor(request),
+                Msg::delivery_semantics(),
+            )
+            .await;
+
         match res_rx.await {
-            Ok(RemoteResponse::Ok(res)) => match Msg::read_remote_result(res) {
-                Ok(res) => Ok(res),
-                Err(e) => {
-                    error!("failed to decode result");
-                    Err(ActorRefErr::Deserialisation(e))
-                }
-            },
+            Ok(RemoteResponse::Ok(res)) => Ok(res),
             Err(e) => {
                 error!("failed to receive result, e={}", e);
                 Err(ActorRefErr::ResultChannelClosed)
             }
+            Ok(RemoteResponse::Err(ActorRefErr::ActorUnavailable)) => {
+                // the actor is no longer on the node, so it's located again next time
+                self.system.invalidate_actor(self.id.clone(), self.node_id);
+
+                Err(ActorRefErr::ActorUnavailable)
+            }
             Ok(RemoteResponse::Err(e)) => Err(e),
         }
     }
@@ -104,7 +216,7 @@ where
         trace_id: String,
         id: Uuid,
         requires_response: bool,
-    ) -> Result<SessionEvent, ActorRefErr>
+    ) -> Result<MessageRequest, ActorRefErr>
     where
         Msg: 'static + Send + Sync,
     {
@@ -117,7 +229,7 @@ where
             let handler_type = header.handler_type;
             let actor_id = header.actor_id.to_string();
             let origin_node_id = self.system.node_id();
-            SessionEvent::NotifyActor(MessageRequest {
+            MessageRequest {
                 message_id: id.to_string(),
                 handler_type,
                 actor_id,
@@ -125,8 +237,9 @@ where
                 message,
                 requires_response,
                 origin_node_id,
+                message_version: header.message_version,
                 ..Default::default()
-            })
+            }
         })
     }
 }
@@ -148,3 +261,76 @@ where
         RemoteActorRef::new(self.id.clone(), self.node_id, self.system.clone())
     }
 }
+
+impl<A: Actor> SerializableActorRef<A> {
+    /// Creates a serializable reference to the provided actor, local actors are referenced
+    /// as running on the provided system's node
+    pub fn new(actor_ref: &ActorRef<A>, system: &RemoteActorSystem) -> Self {
+        let node_id = actor_ref.node_id().unwrap_or_else(|| system.node_id());
+        Self::from_parts(actor_ref.actor_id().clone(), node_id)
+    }
+
+    pub fn from_parts(actor_id: ActorId, node_id: NodeId) -> Self {
+        Self {
+            actor_id,
+            node_id,
+            _a: PhantomData,
+        }
+    }
+
+    pub fn actor_id(&self) -> &ActorId {
+        &self.actor_id
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Resolves the reference into an [`ActorRef`] that can be used to message the actor.
+    ///
+    /// References to actors running on a remote node always resolve, references to actors on the
+    /// local node resolve to the local actor, returning `None` if it isn't running, or was created
+    /// as an anonymous actor.
+    pub async fn resolve(&self, system: &RemoteActorSystem) -> Option<ActorRef<A>> {
+        if self.node_id == system.node_id() {
+            system
+                .actor_system()
+                .get_tracked_actor(self.actor_id.clone())
+                .await
+                .map(ActorRef::from)
+        } else {
+            Some(ActorRef::from(RemoteActorRef::new(
+                self.actor_id.clone(),
+                self.node_id,
+                system.clone(),
+            )))
+        }
+    }
+}
+
+impl<A: Actor> From<&RemoteActorRef<A>> for SerializableActorRef<A> {
+    fn from(actor_ref: &RemoteActorRef<A>) -> Self {
+        Self::from_parts(actor_ref.id.clone(), actor_ref.node_id)
+    }
+}
+
+impl<A: Actor> Debug for SerializableActorRef<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(&format!("SerializableActorRef<{}>", A::type_name()))
+            .field("actor_id", &self.actor_id)
+            .field("node_id", &self.node_id)
+            .finish()
+    }
+}
+
+impl<A: Actor> Clone for SerializableActorRef<A> {
+    fn clone(&self) -> Self {
+        Self::from_parts(self.actor_id.clone(), self.node_id)
+    }
+}
+
+impl<A: Actor> PartialEq for SerializableActorRef<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.actor_id == other.actor_id && self.node_id == other.node_id
+    }
+}
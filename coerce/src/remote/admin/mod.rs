@@ -0,0 +1,371 @@
+//! Node administration
+//!
+//! A node started with [`RemoteActorSystemBuilder::with_admin`] runs a [`NodeAdmin`] actor, which
+//! accepts [`AdminCommand`]s from any other node in the cluster, allowing operators to inspect and
+//! debug the node without the HTTP API being deployed.
+//!
+//! Every [`AdminRequest`] carries a token, which must match the token the node was configured with,
+//! requests with a missing or incorrect token are rejected with [`AdminErr::Unauthorized`].
+//!
+//! Commands are addressed to a specific node, either via [`RemoteActorSystem::node_admin`], or by
+//! sending the JSON encoded [`AdminRequest`] to the [`NODE_ADMIN_ID`] actor of the node, using the
+//! [`NODE_ADMIN_HANDLER`] message handler.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .with_admin(AdminConfig::new("admin-token").on_set_log_level(move |level| {
+//!         let filter = EnvFilter::try_new(level).map_err(|e| e.to_string())?;
+//!         reload_handle.reload(filter).map_err(|e| e.to_string())
+//!     }))
+//!     .build()
+//!     .await;
+//!
+//! let response = remote
+//!     .node_admin(node_id, AdminRequest::new("admin-token", AdminCommand::ListActors))
+//!     .await?;
+//! ```
+//!
+//! [`RemoteActorSystemBuilder::with_admin`]: crate::remote::system::builder::RemoteActorSystemBuilder::with_admin
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use crate::actor::scheduler::{ActorType, GetAllActors, GetBoxedActor};
+use crate::actor::system::ActorSystem;
+use crate::actor::{
+    Actor, ActorRef, ActorRefErr, CoreActorRef, IntoActorId, LocalActorRef, ToActorId,
+};
+use crate::remote::actor::message::SetRemote;
+use crate::remote::actor_ref::RemoteActorRef;
+use crate::remote::handler::actor_ref_cache_size;
+use crate::remote::system::builder::RemoteSystemConfigBuilder;
+use crate::remote::system::{NodeId, RemoteActorSystem};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+pub const NODE_ADMIN_ID: &str = "node-admin";
+
+pub const NODE_ADMIN_HANDLER: &str = "NodeAdmin.AdminRequest";
+
+pub type LogLevelHandler = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+pub struct AdminConfig {
+    token: String,
+    log_level_handler: Option<LogLevelHandler>,
+
+    #[cfg(feature = "metrics")]
+    prometheus: Option<metrics_exporter_prometheus::PrometheusHandle>,
+}
+
+impl AdminConfig {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            log_level_handler: None,
+
+            #[cfg(feature = "metrics")]
+            prometheus: None,
+        }
+    }
+
+    /// Handles [`AdminCommand::SetLogLevel`], the log level can only be changed if the application
+    /// installed its tracing subscriber in a way that allows it to be reloaded, without a handler,
+    /// the command is rejected with [`AdminErr::Unsupported`]
+    pub fn on_set_log_level<F>(mut self, f: F) -> Self
+    where
+        F: 'static + Fn(&str) -> Result<(), String> + Send + Sync,
+    {
+        self.log_level_handler = Some(Arc::new(f));
+        self
+    }
+
+    /// Includes the rendered prometheus metrics in the response to [`AdminCommand::DumpMetrics`]
+    #[cfg(feature = "metrics")]
+    pub fn with_prometheus(
+        mut self,
+        handle: metrics_exporter_prometheus::PrometheusHandle,
+    ) -> Self {
+        self.prometheus = Some(handle);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum AdminCommand {
+    /// Lists every actor tracked by the node
+    ListActors,
+
+    /// Requests that the tracked actor is stopped
+    StopActor { actor_id: String },
+
+    /// Returns the node's statistics, and prometheus metrics, if configured
+    DumpMetrics,
+
+    /// Changes the node's log level, `level` is passed as-is to the configured handler
+    SetLogLevel { level: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AdminRequest {
+    pub token: String,
+    pub command: AdminCommand,
+}
+
+impl AdminRequest {
+    pub fn new(token: impl Into<String>, command: AdminCommand) -> Self {
+        Self {
+            token: token.into(),
+            command,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct TrackedActor {
+    pub actor_id: String,
+    pub actor_type: String,
+    pub path: String,
+    pub queued_messages: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct NodeMetrics {
+    pub node_id: NodeId,
+    pub node_tag: String,
+    pub tracked_actors: usize,
+    pub running_actors: usize,
+    pub inflight_remote_requests: usize,
+    pub remote_actor_ref_cache_len: usize,
+    pub prometheus: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum AdminResponse {
+    Actors(Vec<TrackedActor>),
+    StopRequested { actor_id: String },
+    Metrics(NodeMetrics),
+    LogLevelSet { level: String },
+    Err(AdminErr),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum AdminErr {
+    Unauthorized,
+    ActorNotFound(String),
+    Unsupported(String),
+    Failed(String),
+}
+
+impl Display for AdminErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdminErr::Unauthorized => write!(f, "unauthorized"),
+            AdminErr::ActorNotFound(actor_id) => write!(f, "actor {} not found", actor_id),
+            AdminErr::Unsupported(command) => {
+                write!(f, "{} is not supported by this node", command)
+            }
+            AdminErr::Failed(e) => write!(f, "command failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AdminErr {}
+
+pub struct NodeAdmin {
+    config: AdminConfig,
+    system: Option<RemoteActorSystem>,
+}
+
+impl Actor for NodeAdmin {}
+
+impl NodeAdmin {
+    pub async fn new(config: AdminConfig, sys: &ActorSystem) -> LocalActorRef<NodeAdmin> {
+        sys.new_actor(
+            NODE_ADMIN_ID,
+            NodeAdmin {
+                config,
+                system: None,
+            },
+            ActorType::Tracked,
+        )
+        .await
+        .expect("NodeAdmin")
+    }
+
+    fn is_authorised(&self, token: &str) -> bool {
+        // compared in constant time, so the token can't be guessed byte by byte
+        let expected = self.config.token.as_bytes();
+        let token = token.as_bytes();
+
+        !expected.is_empty()
+            && expected.len() == token.len()
+            && expected
+                .iter()
+                .zip(token)
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    async fn list_actors(&self, ctx: &ActorContext) -> AdminResponse {
+        let actors = ctx
+            .system()
+            .scheduler()
+            .send(GetAllActors)
+            .await
+            .unwrap_or_default();
+
+        let mut actors: Vec<TrackedActor> = actors
+            .into_iter()
+            .map(|actor| TrackedActor {
+                actor_id: actor.actor_id().to_string(),
+                actor_type: actor.actor_type().to_string(),
+                path: actor.actor_path().to_string(),
+                queued_messages: actor.mailbox_snapshot().len(),
+            })
+            .collect();
+
+        actors.sort_by(|a, b| a.actor_id.cmp(&b.actor_id));
+        AdminResponse::Actors(actors)
+    }
+
+    async fn stop_actor(&self, actor_id: String, ctx: &ActorContext) -> AdminResponse {
+        let actor_ref = ctx
+            .system()
+            .scheduler()
+            .send(GetBoxedActor(actor_id.to_actor_id()))
+            .await
+            .unwrap_or_default();
+
+        match actor_ref {
+            // not waiting for the actor to stop, so an actor can't block the admin actor,
+            // which also allows the admin actor to stop itself
+            Some(actor_ref) => match actor_ref.notify_stop() {
+                Ok(_) => AdminResponse::StopRequested { actor_id },
+                Err(ActorRefErr::ActorUnavailable) => {
+                    AdminResponse::Err(AdminErr::ActorNotFound(actor_id))
+                }
+                Err(e) => AdminResponse::Err(AdminErr::Failed(e.to_string())),
+            },
+            None => AdminResponse::Err(AdminErr::ActorNotFound(actor_id)),
+        }
+    }
+
+    async fn dump_metrics(&self, ctx: &ActorContext) -> AdminResponse {
+        let system = self.system.as_ref().unwrap();
+        let tracked_actors = ctx
+            .system()
+            .scheduler()
+            .send(GetAllActors)
+            .await
+            .map_or(0, |actors| actors.len());
+
+        #[cfg(feature = "metrics")]
+        let prometheus = self.config.prometheus.as_ref().map(|p| p.render());
+
+        #[cfg(not(feature = "metrics"))]
+        let prometheus = None;
+
+        AdminResponse::Metrics(NodeMetrics {
+            node_id: system.node_id(),
+            node_tag: system.node_tag().to_string(),
+            tracked_actors,
+            running_actors: ctx.system().running_actor_count(),
+            inflight_remote_requests: system.inflight_remote_request_count(),
+            remote_actor_ref_cache_len: actor_ref_cache_size(),
+            prometheus,
+        })
+    }
+
+    fn set_log_level(&self, level: String) -> AdminResponse {
+        let Some(handler) = &self.config.log_level_handler else {
+            return AdminResponse::Err(AdminErr::Unsupported("SetLogLevel".to_string()));
+        };
+
+        match handler(&level) {
+            Ok(_) => AdminResponse::LogLevelSet { level },
+            Err(e) => AdminResponse::Err(AdminErr::Failed(e)),
+        }
+    }
+}
+
+/// Registers the handler used to send [`AdminRequest`]s to a node's [`NodeAdmin`]
+pub fn node_admin(builder: &mut RemoteSystemConfigBuilder) -> &mut RemoteSystemConfigBuilder {
+    builder.with_handler::<NodeAdmin, AdminRequest>(NODE_ADMIN_HANDLER)
+}
+
+impl RemoteActorSystem {
+    /// Sends the [`AdminRequest`] to the [`NodeAdmin`] of the node with the provided ID
+    pub async fn node_admin(
+        &self,
+        node_id: NodeId,
+        request: AdminRequest,
+    ) -> Result<AdminResponse, ActorRefErr> {
+        let admin: ActorRef<NodeAdmin> = if node_id == self.node_id() {
+            self.actor_system()
+                .get_tracked_actor::<NodeAdmin>(NODE_ADMIN_ID.into_actor_id())
+                .await
+                .ok_or_else(|| ActorRefErr::NotFound(NODE_ADMIN_ID.into_actor_id()))?
+                .into()
+        } else {
+            RemoteActorRef::new(NODE_ADMIN_ID.into_actor_id(), node_id, self.clone()).into()
+        };
+
+        admin.send(request).await
+    }
+}
+
+impl Message for AdminRequest {
+    type Result = AdminResponse;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&self).map_err(|_| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_bytes(buf: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&buf).map_err(|_| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn read_remote_result(buf: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        serde_json::from_slice(&buf).map_err(|_| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn write_remote_result(res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&res).map_err(|_| MessageWrapErr::SerializationErr)
+    }
+}
+
+#[async_trait]
+impl Handler<SetRemote> for NodeAdmin {
+    async fn handle(&mut self, message: SetRemote, ctx: &mut ActorContext) {
+        let sys = message.0;
+        ctx.set_system(sys.actor_system().clone());
+        self.system = Some(sys);
+    }
+}
+
+#[async_trait]
+impl Handler<AdminRequest> for NodeAdmin {
+    async fn handle(&mut self, message: AdminRequest, ctx: &mut ActorContext) -> AdminResponse {
+        if !self.is_authorised(&message.token) {
+            warn!(
+                command = format!("{:?}", &message.command),
+                "rejected unauthorised admin command"
+            );
+
+            return AdminResponse::Err(AdminErr::Unauthorized);
+        }
+
+        info!(
+            command = format!("{:?}", &message.command),
+            "handling admin command"
+        );
+
+        match message.command {
+            AdminCommand::ListActors => self.list_actors(ctx).await,
+            AdminCommand::StopActor { actor_id } => self.stop_actor(actor_id, ctx).await,
+            AdminCommand::DumpMetrics => self.dump_metrics(ctx).await,
+            AdminCommand::SetLogLevel { level } => self.set_log_level(level),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::remote::api::Routes;
 use crate::remote::cluster::node::RemoteNodeState;
+use crate::remote::cluster::version::NodeVersion;
+use crate::remote::net::client::status::{ClientConnectionStatus, ClientStatus};
 use crate::remote::system::RemoteActorSystem;
 use axum::response::IntoResponse;
 use axum::routing::get;
@@ -19,10 +21,19 @@ impl ClusterApi {
 
 impl Routes for ClusterApi {
     fn routes(&self, router: Router) -> Router {
-        router.route("/cluster/nodes", {
-            let system = self.system.clone();
-            get(move || get_nodes(system))
-        })
+        router
+            .route("/cluster/nodes", {
+                let system = self.system.clone();
+                get(move || get_nodes(system))
+            })
+            .route("/cluster/clients", {
+                let system = self.system.clone();
+                get(move || get_clients(system))
+            })
+            .route("/cluster/versions", {
+                let system = self.system.clone();
+                get(move || get_versions(system))
+            })
     }
 }
 
@@ -35,7 +46,10 @@ pub struct ClusterNode {
     pub last_heartbeat: Option<String>,
     pub node_started_at: Option<chrono::DateTime<chrono::Utc>>,
     pub status: NodeStatus,
+    pub reachable: bool,
+    pub maintenance: bool,
     pub attributes: HashMap<String, String>,
+    pub roles: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, ToSchema)]
@@ -52,6 +66,7 @@ pub enum NodeStatus {
     Healthy,
     Unhealthy,
     Terminated,
+    Quarantined,
 }
 
 impl From<crate::remote::cluster::node::NodeStatus> for NodeStatus {
@@ -61,6 +76,7 @@ impl From<crate::remote::cluster::node::NodeStatus> for NodeStatus {
             crate::remote::cluster::node::NodeStatus::Healthy => Self::Healthy,
             crate::remote::cluster::node::NodeStatus::Unhealthy => Self::Unhealthy,
             crate::remote::cluster::node::NodeStatus::Terminated => Self::Terminated,
+            crate::remote::cluster::node::NodeStatus::Quarantined => Self::Quarantined,
         }
     }
 }
@@ -113,11 +129,163 @@ impl From<RemoteNodeState> for ClusterNode {
             last_heartbeat: node.last_heartbeat.map(|h| format!("{:?}", h)),
             node_started_at: node.node_started_at.map(|p| p),
             status: node.status.into(),
+            reachable: node.health.reachable,
+            maintenance: node.maintenance,
             attributes: node
                 .attributes
                 .iter()
                 .map(|(k, v)| (k.to_string(), v.to_string()))
                 .collect(),
+            roles: node.roles,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ClusterClient {
+    pub addr: String,
+    pub node_id: Option<u64>,
+    pub status: ClientStatusKind,
+    pub buffered_messages: usize,
+    pub buffered_system_messages: usize,
+    pub buffered_bytes: usize,
+    pub priority_messages: usize,
+    pub dropped_messages: u64,
+    pub last_write: Option<chrono::DateTime<chrono::Utc>>,
+    pub connection_attempts: usize,
+    pub last_disconnect: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ClusterClients {
+    pub node_id: u64,
+    pub clients: Vec<ClusterClient>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub enum ClientStatusKind {
+    Connected,
+    Idle,
+    Quarantined,
+    Unresponsive,
+}
+
+impl From<ClientConnectionStatus> for ClientStatusKind {
+    fn from(value: ClientConnectionStatus) -> Self {
+        match value {
+            ClientConnectionStatus::Connected => Self::Connected,
+            ClientConnectionStatus::Idle => Self::Idle,
+            ClientConnectionStatus::Quarantined => Self::Quarantined,
+            ClientConnectionStatus::Unresponsive => Self::Unresponsive,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/cluster/clients",
+    responses(
+    (
+        status = 200, description = "The state of the connections from this node to each other node", body = ClusterClients),
+    )
+)]
+async fn get_clients(system: RemoteActorSystem) -> impl IntoResponse {
+    let clients = system
+        .client_statuses()
+        .await
+        .into_iter()
+        .map(|client| client.into())
+        .collect();
+
+    Json(ClusterClients {
+        node_id: system.node_id(),
+        clients,
+    })
+}
+
+impl From<ClientStatus> for ClusterClient {
+    fn from(client: ClientStatus) -> Self {
+        ClusterClient {
+            addr: client.addr,
+            node_id: client.node_id,
+            status: client.status.into(),
+            buffered_messages: client.buffered_messages,
+            buffered_system_messages: client.buffered_system_messages,
+            buffered_bytes: client.buffered_bytes,
+            priority_messages: client.priority_messages,
+            dropped_messages: client.dropped_messages,
+            last_write: client.last_write,
+            connection_attempts: client.connection_attempts,
+            last_disconnect: client.last_disconnect.map(|cause| cause.to_string()),
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ClusterNodeVersion {
+    pub id: u64,
+    pub addr: String,
+    pub tag: String,
+    pub crate_version: Option<String>,
+    pub min_protocol_version: Option<u32>,
+    pub max_protocol_version: Option<u32>,
+    pub features: Vec<String>,
+
+    /// Differences from this node's version that exceed the configured version skew policy
+    pub skew: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ClusterVersions {
+    pub node_id: u64,
+    pub nodes: Vec<ClusterNodeVersion>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/cluster/versions",
+    responses(
+    (
+        status = 200, description = "The version of Coerce each known cluster node is running, and how it differs from this node", body = ClusterVersions),
+    )
+)]
+async fn get_versions(system: RemoteActorSystem) -> impl IntoResponse {
+    let local_version = NodeVersion::current();
+    let policy = system.config().version_skew_policy();
+
+    let mut nodes: Vec<ClusterNodeVersion> = system
+        .get_nodes()
+        .await
+        .into_iter()
+        .map(|node| {
+            let skew = if node.id == system.node_id() {
+                vec![]
+            } else {
+                policy
+                    .check(&local_version, node.version.as_ref())
+                    .iter()
+                    .map(|skew| skew.to_string())
+                    .collect()
+            };
+
+            let version = node.version;
+            ClusterNodeVersion {
+                id: node.id,
+                addr: node.addr,
+                tag: node.tag,
+                crate_version: version.as_ref().map(|v| v.crate_version.clone()),
+                min_protocol_version: version.as_ref().map(|v| v.protocol_versions.min),
+                max_protocol_version: version.as_ref().map(|v| v.protocol_versions.max),
+                features: version.map_or_else(Vec::new, |v| v.features),
+                skew,
+            }
+        })
+        .collect();
+
+    nodes.sort_by_key(|n| n.id);
+
+    Json(ClusterVersions {
+        node_id: system.node_id(),
+        nodes,
+    })
+}
@@ -26,6 +26,8 @@ pub struct SystemApiDoc;
 #[openapi(
     paths(
         actors::get_all,
+        actors::get_location,
+        actors::get_mailbox,
     ),
     components(
         schemas(
@@ -36,6 +38,8 @@ pub struct SystemApiDoc;
             system::actors::Status,
             system::actors::ActorTags,
             system::actors::SupervisedDescription,
+            system::actors::ActorLocation,
+            system::actors::ActorMailbox,
         )
     ),
     tags(
@@ -48,12 +52,19 @@ pub struct ActorsApiDoc;
 #[openapi(
     paths(
         cluster::get_nodes,
+        cluster::get_clients,
+        cluster::get_versions,
     ),
     components(
         schemas(
             cluster::ClusterNodes,
             cluster::ClusterNode,
             cluster::NodeStatus,
+            cluster::ClusterClients,
+            cluster::ClusterClient,
+            cluster::ClientStatusKind,
+            cluster::ClusterVersions,
+            cluster::ClusterNodeVersion,
         )
     ),
     tags(
@@ -70,6 +81,7 @@ pub mod sharding {
             get_sharding_types,
             get_sharding_stats,
             get_node_stats,
+            get_entity_location,
         ),
         components(
             schemas(
@@ -81,6 +93,7 @@ pub mod sharding {
                 node::Stats,
                 node::ShardStats,
                 ShardTypes,
+                EntityLocation,
             )
         ),
         tags(
@@ -90,6 +103,7 @@ pub mod sharding {
     pub struct ShardingApiDoc;
 
     use crate::remote::api::sharding as sharding_api;
+    pub use sharding_api::EntityLocation;
     pub use sharding_api::ShardTypes;
 
     pub use sharding_api::cluster;
@@ -101,6 +115,9 @@ pub mod sharding {
     pub use sharding_api::node::__path_get_node_stats;
     pub use sharding_api::node::get_node_stats;
 
+    pub use sharding_api::__path_get_entity_location;
+    pub use sharding_api::get_entity_location;
+
     pub use sharding_api::__path_get_sharding_types;
     pub use sharding_api::get_sharding_types;
 }
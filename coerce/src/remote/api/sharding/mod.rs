@@ -6,11 +6,14 @@ use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
 use crate::actor::system::ActorSystem;
 use crate::actor::{Actor, ActorFactory, IntoActor, IntoActorId, LocalActorRef};
+use crate::sharding::host::locate::locate_entity;
 use crate::sharding::host::ShardHost;
 use crate::sharding::Sharding;
+use axum::extract::Path;
 use axum::response::IntoResponse;
 use axum::Json;
 use std::collections::HashMap;
+use tokio::sync::oneshot;
 
 #[derive(Default)]
 pub struct ShardingApi {
@@ -70,3 +73,81 @@ pub async fn get_sharding_types(sharding_api: LocalActorRef<ShardingApi>) -> imp
             .expect("unable to get shard types"),
     )
 }
+
+pub struct LocateEntity {
+    entity_type: String,
+    entity_id: String,
+}
+
+type EntityLocationReceiver = oneshot::Receiver<Option<EntityLocation>>;
+
+impl Message for LocateEntity {
+    type Result = Option<EntityLocationReceiver>;
+}
+
+#[async_trait]
+impl Handler<LocateEntity> for ShardingApi {
+    async fn handle(
+        &mut self,
+        message: LocateEntity,
+        ctx: &mut ActorContext,
+    ) -> Option<EntityLocationReceiver> {
+        let shard_host = self.shard_hosts.get(&message.entity_type)?.clone();
+        let remote = ctx.system().remote_owned();
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let location =
+                locate_entity(&shard_host, message.entity_id.into_actor_id(), &remote).await;
+
+            let _ = tx.send(location.map(|location| EntityLocation {
+                entity_id: location.entity_id.to_string(),
+                shard_id: location.shard_id,
+                node_id: location.node.node_id,
+                addr: location.node.addr,
+                node_tag: location.node.node_tag,
+            }));
+        });
+
+        Some(rx)
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EntityLocation {
+    pub entity_id: String,
+    pub shard_id: u32,
+    pub node_id: u64,
+    pub addr: String,
+    pub node_tag: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/sharding/locate/{entity}/{entity_id}",
+    responses(
+        (status = 200, description = "The node hosting the chosen entity", body = EntityLocation),
+    ),
+    params(
+        ("entity" = String, Path, description = "Sharded entity type name"),
+        ("entity_id" = String, Path, description = "Entity ID"),
+    )
+)]
+pub async fn get_entity_location(
+    sharding_api: LocalActorRef<ShardingApi>,
+    Path((entity_type, entity_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let location: Option<EntityLocationReceiver> = sharding_api
+        .send(LocateEntity {
+            entity_type,
+            entity_id,
+        })
+        .await
+        .unwrap();
+
+    if let Some(location) = location {
+        Json(location.await.unwrap_or(None))
+    } else {
+        Json(None)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::actor::LocalActorRef;
 use crate::remote::api::sharding::cluster::{get_shard_host_stats, get_sharding_stats};
 use crate::remote::api::sharding::node::{get_node_stats, GetAllStats};
-use crate::remote::api::sharding::{get_sharding_types, ShardingApi};
+use crate::remote::api::sharding::{get_entity_location, get_sharding_types, ShardingApi};
 use crate::remote::api::Routes;
 
 use axum::routing::get;
@@ -26,6 +26,10 @@ impl Routes for LocalActorRef<ShardingApi> {
                 let actor_ref = self.clone();
                 get(move |path| get_node_stats(actor_ref, path))
             })
+            .route("/sharding/locate/:entity/:entity_id", {
+                let actor_ref = self.clone();
+                get(move |path| get_entity_location(actor_ref, path))
+            })
             .route("/sharding/node/stats/all", {
                 let actor_ref = self.clone();
                 get(|| async move {
@@ -1,8 +1,9 @@
 use crate::actor::describe;
 use crate::remote::system::RemoteActorSystem;
-use axum::extract::Query;
+use axum::extract::{Path, Query};
 use axum::response::IntoResponse;
 use axum::Json;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::oneshot;
@@ -177,3 +178,68 @@ impl From<crate::actor::ActorTags> for ActorTags {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct ActorLocation {
+    pub actor_id: String,
+    pub node_id: u64,
+    pub addr: String,
+    pub node_tag: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/actors/{actor_id}/location",
+    responses(
+        (status = 200, description = "The node the actor is running on", body = ActorLocation),
+    ),
+    params(
+        ("actor_id" = String, Path, description = "Actor ID"),
+    )
+)]
+pub(super) async fn get_location(
+    system: RemoteActorSystem,
+    Path(actor_id): Path<String>,
+) -> impl IntoResponse {
+    let location = system.locate(actor_id.clone()).await;
+
+    Json(location.map(|location| ActorLocation {
+        actor_id,
+        node_id: location.node_id,
+        addr: location.addr,
+        node_tag: location.node_tag,
+    }))
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct ActorMailbox {
+    pub actor_id: String,
+    pub total_queued: usize,
+    pub queued_by_message_type: HashMap<String, usize>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/actors/{actor_id}/mailbox",
+    responses(
+        (status = 200, description = "Messages queued in the actor's mailbox, by message type", body = ActorMailbox),
+    ),
+    params(
+        ("actor_id" = String, Path, description = "Actor ID"),
+    )
+)]
+pub(super) async fn get_mailbox(
+    system: RemoteActorSystem,
+    Path(actor_id): Path<String>,
+) -> impl IntoResponse {
+    let mailbox = system
+        .actor_system()
+        .mailbox_snapshot(actor_id.clone())
+        .await;
+
+    Json(mailbox.map(|mailbox| ActorMailbox {
+        actor_id,
+        total_queued: mailbox.len(),
+        queued_by_message_type: mailbox.queued.into_iter().collect(),
+    }))
+}
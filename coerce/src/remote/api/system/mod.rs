@@ -63,6 +63,14 @@ impl Routes for SystemApi {
                 let system = self.system.clone();
                 get(move |options| actors::get_all(system, options))
             })
+            .route("/actors/:actor_id/location", {
+                let system = self.system.clone();
+                get(move |path| actors::get_location(system, path))
+            })
+            .route("/actors/:actor_id/mailbox", {
+                let system = self.system.clone();
+                get(move |path| actors::get_mailbox(system, path))
+            })
     }
 }
 
@@ -0,0 +1,171 @@
+//! Nodes that are refused membership of the cluster
+//!
+//! A node can be evicted from the cluster with [`RemoteActorSystem::down`], which quarantines the
+//! node, disconnects from it, and adds both its id and address to this node's blocklist. Blocked
+//! nodes are refused at handshake, and are never discovered, so they're unable to rejoin the
+//! cluster (via this node) until the entry is removed with [`RemoteActorSystem::unblock_node`].
+//!
+//! The blocklist is held in memory by default. When configured with a path, the blocklist is
+//! loaded from the file when the system is started, and written to it whenever an entry is
+//! added or removed, so entries survive restarts of this node.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .configure(|c| c.blocklist_path("/var/lib/my-app/blocklist"))
+//!     .build()
+//!     .await;
+//!
+//! remote.down(misbehaving_node_id).await;
+//!
+//! // later, once the node has been fixed
+//! remote.unblock_node(BlockedNode::Id(misbehaving_node_id));
+//! ```
+//!
+//! [`RemoteActorSystem::down`]: crate::remote::system::RemoteActorSystem::down
+//! [`RemoteActorSystem::unblock_node`]: crate::remote::system::RemoteActorSystem::unblock_node
+
+use crate::remote::system::NodeId;
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// An entry in the [`NodeBlocklist`]
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum BlockedNode {
+    /// Refuses the node with this id, regardless of its address
+    Id(NodeId),
+
+    /// Refuses any node advertising this address
+    Addr(String),
+}
+
+impl Display for BlockedNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockedNode::Id(node_id) => write!(f, "id={}", node_id),
+            BlockedNode::Addr(addr) => write!(f, "addr={}", addr),
+        }
+    }
+}
+
+impl FromStr for BlockedNode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some(("id", node_id)) => node_id.parse().map(BlockedNode::Id).map_err(|_| ()),
+            Some(("addr", addr)) if !addr.is_empty() => Ok(BlockedNode::Addr(addr.to_string())),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct NodeBlocklist {
+    entries: Arc<RwLock<HashSet<BlockedNode>>>,
+    path: Option<Arc<PathBuf>>,
+}
+
+impl NodeBlocklist {
+    /// Creates a blocklist that's held in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a blocklist persisted to the provided file, loading any entries already in it.
+    ///
+    /// The file contains one entry per line, `id=<node_id>` or `addr=<host:port>`, lines that
+    /// can't be parsed are ignored.
+    pub fn persisted(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .filter_map(|line| match line.parse() {
+                    Ok(entry) => Some(entry),
+                    Err(_) => {
+                        warn!(path = ?path, line, "ignoring invalid blocklist entry");
+                        None
+                    }
+                })
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => {
+                error!(path = ?path, error = %e, "failed to read blocklist");
+                HashSet::new()
+            }
+        };
+
+        Self {
+            entries: Arc::new(RwLock::new(entries)),
+            path: Some(Arc::new(path)),
+        }
+    }
+
+    /// Adds the entry, returning `false` if it was already blocked
+    pub fn block(&self, entry: BlockedNode) -> bool {
+        let mut entries = self.entries.write();
+        let added = entries.insert(entry);
+        if added {
+            self.persist(&entries);
+        }
+
+        added
+    }
+
+    /// Removes the entry, returning `false` if it wasn't blocked
+    pub fn unblock(&self, entry: &BlockedNode) -> bool {
+        let mut entries = self.entries.write();
+        let removed = entries.remove(entry);
+        if removed {
+            self.persist(&entries);
+        }
+
+        removed
+    }
+
+    /// Whether a node with the provided id, or advertising the provided address, is blocked
+    pub fn is_blocked(&self, node_id: NodeId, addr: &str) -> bool {
+        let entries = self.entries.read();
+        !entries.is_empty()
+            && (entries.contains(&BlockedNode::Id(node_id))
+                || entries.contains(&BlockedNode::Addr(addr.to_string())))
+    }
+
+    pub fn entries(&self) -> Vec<BlockedNode> {
+        let mut entries: Vec<BlockedNode> = self.entries.read().iter().cloned().collect();
+        entries.sort();
+        entries
+    }
+
+    fn persist(&self, entries: &HashSet<BlockedNode>) {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return,
+        };
+
+        let mut entries: Vec<&BlockedNode> = entries.iter().collect();
+        entries.sort();
+
+        let contents: String = entries
+            .into_iter()
+            .map(|entry| format!("{}\n", entry))
+            .collect();
+
+        // written to a temporary file first, so a failed write doesn't lose existing entries
+        let tmp_path = path.with_extension("tmp");
+        let result =
+            std::fs::write(&tmp_path, contents).and_then(|_| std::fs::rename(&tmp_path, &**path));
+
+        if let Err(e) = result {
+            error!(path = ?path, error = %e, "failed to persist blocklist");
+        }
+    }
+}
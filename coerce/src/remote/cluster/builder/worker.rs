@@ -1,10 +1,22 @@
-use crate::remote::cluster::discovery::{Discover, Seed};
+use crate::actor::system::shutdown::ShutdownPhase;
+use crate::remote::cluster::discovery::seed::{start_seed_refresh, ClusterSeed};
+use crate::remote::cluster::discovery::{Discover, Seed, StartRediscovery};
 use crate::remote::cluster::node::RemoteNode;
+use crate::remote::cluster::version::NodeVersion;
 use crate::remote::net::server::{RemoteServer, RemoteServerConfig};
+#[cfg(feature = "tls")]
+use crate::remote::net::tls::{rustls, ClientTls};
+use crate::remote::stream::pubsub::PubSub;
+use crate::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
 use crate::remote::system::RemoteActorSystem;
+use std::collections::HashSet;
 use std::env;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "tls")]
+use std::sync::Arc;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::net::lookup_host;
 use tokio::sync::oneshot;
 use tokio::time::sleep;
@@ -12,29 +24,122 @@ use tokio::time::sleep;
 pub struct ClusterWorkerBuilder {
     server_listen_addr: String,
     server_external_addr: Option<String>,
-    seed_addr: Option<String>,
+    seed_addrs: Vec<String>,
+    seed: Option<Box<dyn ClusterSeed>>,
+    bootstrap: Option<ClusterBootstrap>,
+    rediscovery_interval: Option<Duration>,
+    roles: Vec<String>,
     system: RemoteActorSystem,
+
+    #[cfg(feature = "tls")]
+    tls: Option<WorkerTls>,
+}
+
+#[cfg(feature = "tls")]
+struct WorkerTls {
+    server_config: Arc<rustls::ServerConfig>,
+    client_config: Arc<rustls::ClientConfig>,
+    server_name: Option<String>,
+}
+
+/// Defines what happens when a node fails to join the cluster via its seed within the
+/// configured bootstrap timeout
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BootstrapFallback {
+    /// Startup is aborted, [`ClusterWorkerBuilder::try_start`] returns an error
+    Abort,
+
+    /// The node continues as a standalone, single-node cluster, and
+    /// [`ClusterEvent::Standalone`] is published to the [`SystemTopic`]
+    Standalone,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ClusterBootstrap {
+    timeout: Duration,
+    fallback: BootstrapFallback,
+}
+
+#[derive(Debug)]
+pub enum ClusterBootstrapErr {
+    /// None of the seeds responded within the bootstrap timeout
+    Timeout {
+        seed_addrs: Vec<String>,
+        time_taken_millis: u64,
+    },
+
+    /// Peers could not be discovered from any of the seeds
+    DiscoveryFailed { seed_addrs: Vec<String> },
+}
+
+impl Display for ClusterBootstrapErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClusterBootstrapErr::Timeout {
+                seed_addrs,
+                time_taken_millis,
+            } => write!(
+                f,
+                "cluster bootstrap via seeds (addrs={:?}) timed out after {}ms",
+                seed_addrs, time_taken_millis
+            ),
+            ClusterBootstrapErr::DiscoveryFailed { seed_addrs } => write!(
+                f,
+                "unable to discover cluster peers via seeds (addrs={:?})",
+                seed_addrs
+            ),
+        }
+    }
 }
 
+impl Error for ClusterBootstrapErr {}
+
 impl ClusterWorkerBuilder {
     pub fn new(system: RemoteActorSystem) -> ClusterWorkerBuilder {
         let server_listen_addr = "0.0.0.0:30101".to_owned();
-        let seed_addr = None;
         let server_external_addr = None;
         ClusterWorkerBuilder {
             server_listen_addr,
             server_external_addr,
             system,
-            seed_addr,
+            seed_addrs: vec![],
+            seed: None,
+            bootstrap: None,
+            rediscovery_interval: None,
+            roles: vec![],
+
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
     pub fn with_seed_addr<T: ToString>(mut self, seed_addr: T) -> Self {
-        self.seed_addr = Some(seed_addr.to_string());
+        self.seed_addrs = vec![seed_addr.to_string()];
+
+        self
+    }
+
+    /// Sets multiple seed addresses, the seeds are attempted in order when the node first
+    /// joins the cluster, and are rotated through when re-running discovery
+    /// (see [`ClusterWorkerBuilder::rediscovery_interval`]).
+    pub fn with_seed_addrs<T: ToString>(mut self, seed_addrs: impl IntoIterator<Item = T>) -> Self {
+        self.seed_addrs = seed_addrs.into_iter().map(|s| s.to_string()).collect();
 
         self
     }
 
+    /// Resolves the seed addresses from the provided [`ClusterSeed`] (for example, a [`DnsSeed`])
+    /// when the node starts, in addition to any seed addresses that were provided directly.
+    ///
+    /// If the seed has a [`refresh_interval`](ClusterSeed::refresh_interval), it's periodically
+    /// re-resolved once the node has started, and any new addresses are discovered.
+    ///
+    /// [`DnsSeed`]: crate::remote::cluster::discovery::dns::DnsSeed
+    pub fn with_seed(mut self, seed: impl ClusterSeed) -> Self {
+        self.seed = Some(Box::new(seed));
+        self
+    }
+
     pub fn external_addr<T: ToString>(mut self, server_external_addr: T) -> Self {
         self.server_external_addr = Some(server_external_addr.to_string());
         self
@@ -46,18 +151,95 @@ impl ClusterWorkerBuilder {
         self
     }
 
-    pub async fn start(mut self) -> RemoteServer {
+    /// Limits how long the node waits to join the cluster via its seed, once the timeout
+    /// has elapsed (or if peers cannot be discovered), the provided [`BootstrapFallback`] is applied.
+    ///
+    /// Without a bootstrap timeout, the node continues to wait for the seed to become available.
+    pub fn bootstrap_timeout(mut self, timeout: Duration, fallback: BootstrapFallback) -> Self {
+        self.bootstrap = Some(ClusterBootstrap { timeout, fallback });
+        self
+    }
+
+    /// Periodically re-runs discovery via the seeds, even once the node has joined the cluster.
+    ///
+    /// Allows long-lived nodes to find and join a new incarnation of the cluster, if every
+    /// member it originally joined with has since been replaced (for example, after a full redeploy).
+    pub fn rediscovery_interval(mut self, interval: Duration) -> Self {
+        self.rediscovery_interval = Some(interval);
+        self
+    }
+
+    /// Sets the roles this node performs within the cluster, which are shared with the other nodes
+    /// when they connect. Roles can be used to restrict where shards are allocated
+    /// (see [`ShardingBuilder::with_role`]) and where cluster singletons are started
+    /// (see [`SingletonBuilder::with_role`]).
+    ///
+    /// [`ShardingBuilder::with_role`]: crate::sharding::builder::ShardingBuilder::with_role
+    /// [`SingletonBuilder::with_role`]: crate::singleton::SingletonBuilder::with_role
+    pub fn with_roles<T: ToString>(mut self, roles: impl IntoIterator<Item = T>) -> Self {
+        self.roles = roles.into_iter().map(|r| r.to_string()).collect();
+        self
+    }
+
+    /// Secures the connections between nodes with TLS, `server_config` is used to accept connections
+    /// from other nodes and `client_config` to connect to them, verifying their certificates.
+    ///
+    /// Every node in the cluster must be configured with TLS. For mutual TLS, where nodes must
+    /// present a trusted certificate before the handshake is accepted, `server_config` should be
+    /// configured with a client certificate verifier (see [`mutual_tls`]).
+    ///
+    /// [`mutual_tls`]: crate::remote::net::tls::mutual_tls
+    #[cfg(feature = "tls")]
+    pub fn with_tls(
+        mut self,
+        server_config: impl Into<Arc<rustls::ServerConfig>>,
+        client_config: impl Into<Arc<rustls::ClientConfig>>,
+    ) -> Self {
+        self.tls = Some(WorkerTls {
+            server_config: server_config.into(),
+            client_config: client_config.into(),
+            server_name: None,
+        });
+
+        self
+    }
+
+    /// Verifies peer certificates against the provided name, rather than the host of each peer's
+    /// address, useful when every node shares the same certificate.
+    /// Has no effect unless TLS is configured via [`ClusterWorkerBuilder::with_tls`].
+    #[cfg(feature = "tls")]
+    pub fn with_tls_server_name<T: ToString>(mut self, server_name: T) -> Self {
+        if let Some(tls) = &mut self.tls {
+            tls.server_name = Some(server_name.to_string());
+        }
+
+        self
+    }
+
+    pub async fn start(self) -> RemoteServer {
+        self.try_start().await.expect("cluster bootstrap")
+    }
+
+    pub async fn try_start(mut self) -> Result<RemoteServer, ClusterBootstrapErr> {
         let started_at = *self.system.started_at();
         let cluster_node_addr = self.cluster_node_addr();
 
+        if !self.roles.is_empty() {
+            self.system.set_node_roles(std::mem::take(&mut self.roles));
+        }
+
         self.system
-            .register_node(RemoteNode::new(
-                self.system.node_id(),
-                cluster_node_addr.clone(),
-                self.system.node_tag().to_string(),
-                Some(started_at),
-                self.system.config().get_attributes().clone(),
-            ))
+            .register_node(
+                RemoteNode::new(
+                    self.system.node_id(),
+                    cluster_node_addr.clone(),
+                    self.system.node_tag().to_string(),
+                    Some(started_at),
+                    self.system.config().get_attributes().clone(),
+                    self.system.node_roles().to_vec(),
+                )
+                .with_version(NodeVersion::current()),
+            )
             .await;
 
         let system = self.system.clone();
@@ -79,21 +261,131 @@ impl ClusterWorkerBuilder {
             override_incoming_node_addr,
         );
 
+        #[cfg(feature = "tls")]
+        let config = match self.tls.take() {
+            Some(tls) => {
+                let client_tls = ClientTls::new(tls.client_config, tls.server_name)
+                    .expect("invalid TLS configuration");
+
+                self.system.set_client_tls(client_tls);
+                config.with_tls(tls.server_config)
+            }
+            None => config,
+        };
+
         server
             .start(config, system)
             .await
             .expect("failed to start server");
 
-        if let Some(seed_addr) = self.seed_addr.take() {
-            // TODO: this check only works if the listen addr & cluster node addr are equal,
-            //        should we perform a resolution via `lookup_host` instead?
+        let mut seed = self.seed.take();
+        let mut resolved_seed_addrs = HashSet::new();
+        if let Some(seed) = &mut seed {
+            match seed.seed_addrs().await {
+                Ok(addrs) => {
+                    resolved_seed_addrs.extend(addrs.iter().cloned());
+                    self.seed_addrs.extend(addrs);
+                }
+                Err(e) => warn!("{}", e),
+            }
+        }
+
+        // TODO: this check only works if the listen addr & cluster node addr are equal,
+        //        should we perform a resolution via `lookup_host` instead?
+        let seed_addrs: Vec<String> = self
+            .seed_addrs
+            .drain(..)
+            .filter(|seed_addr| seed_addr != &cluster_node_addr)
+            .collect();
+
+        if !seed_addrs.is_empty() {
+            if let Err(e) = self.bootstrap(seed_addrs.clone()).await {
+                server.stop();
+                return Err(e);
+            }
 
-            if self.seed_addr.as_ref() != Some(&cluster_node_addr) {
-                discover_peers(seed_addr, &self.system).await;
+            if let Some(interval) = self.rediscovery_interval {
+                let _ = self.system.node_discovery().notify(StartRediscovery {
+                    seed_addrs,
+                    interval,
+                });
             }
         }
 
-        server
+        let seed_refresh = seed.and_then(|seed| {
+            let interval = seed.refresh_interval()?;
+            Some(start_seed_refresh(
+                seed,
+                interval,
+                resolved_seed_addrs,
+                cluster_node_addr,
+                self.system.clone(),
+            ))
+        });
+
+        let leaving_server = server.clone();
+        let leaving_system = self.system.clone();
+        self.system.actor_system().add_shutdown_task(
+            ShutdownPhase::LeaveCluster,
+            "leave-cluster",
+            move || async move {
+                if let Some(seed_refresh) = seed_refresh {
+                    seed_refresh.abort();
+                }
+
+                leaving_server.stop();
+                leaving_system.shutdown().await;
+            },
+        );
+
+        Ok(server)
+    }
+
+    async fn bootstrap(&self, seed_addrs: Vec<String>) -> Result<(), ClusterBootstrapErr> {
+        let bootstrap = match self.bootstrap {
+            Some(bootstrap) => bootstrap,
+            None => {
+                discover_peers_via_seeds(&seed_addrs, &self.system).await;
+                return Ok(());
+            }
+        };
+
+        let start = Instant::now();
+        let err = match tokio::time::timeout(
+            bootstrap.timeout,
+            discover_peers_via_seeds(&seed_addrs, &self.system),
+        )
+        .await
+        {
+            Ok(true) => return Ok(()),
+            Ok(false) => ClusterBootstrapErr::DiscoveryFailed { seed_addrs },
+            Err(_) => ClusterBootstrapErr::Timeout {
+                seed_addrs,
+                time_taken_millis: start.elapsed().as_millis() as u64,
+            },
+        };
+
+        match bootstrap.fallback {
+            BootstrapFallback::Abort => {
+                error!("{}, aborting startup", &err);
+                Err(err)
+            }
+
+            BootstrapFallback::Standalone => {
+                warn!("{}, continuing in standalone mode", &err);
+
+                if self.system.stream_mediator().is_some() {
+                    PubSub::publish_locally(
+                        SystemTopic,
+                        SystemEvent::Cluster(ClusterEvent::Standalone),
+                        &self.system,
+                    )
+                    .await;
+                }
+
+                Ok(())
+            }
+        }
     }
 
     fn cluster_node_addr(&self) -> String {
@@ -103,7 +395,17 @@ impl ClusterWorkerBuilder {
     }
 }
 
-async fn discover_peers(seed_addr: String, system: &RemoteActorSystem) {
+async fn discover_peers_via_seeds(seed_addrs: &[String], system: &RemoteActorSystem) -> bool {
+    for seed_addr in seed_addrs {
+        if discover_peers(seed_addr.clone(), system).await {
+            return true;
+        }
+    }
+
+    false
+}
+
+async fn discover_peers(seed_addr: String, system: &RemoteActorSystem) -> bool {
     const SEED_RESOLVE_MAX_ATTEMPTS: usize = 12;
     const SEED_RESOLVE_RETRY_DELAY: Duration = Duration::from_secs(5);
 
@@ -117,7 +419,7 @@ async fn discover_peers(seed_addr: String, system: &RemoteActorSystem) {
                 "Cannot resolve DNS for address: {} after 10 attempts, peer discovery cancelled",
                 &seed_addr
             );
-            return;
+            return false;
         }
 
         if seed_addr_resolves(&seed_addr).await {
@@ -143,11 +445,14 @@ async fn discover_peers(seed_addr: String, system: &RemoteActorSystem) {
 
     info!("discovering cluster peers (seed={})", &seed_addr);
 
-    let _ = rx
-        .await
-        .expect(&format!("unable to discover nodes from addr={}", seed_addr));
+    let discovered = rx.await.unwrap_or(false);
+    if discovered {
+        info!("cluster peers discovered successfully");
+    } else {
+        warn!("unable to discover cluster peers (seed={})", &seed_addr);
+    }
 
-    info!("cluster peers discovered successfully");
+    discovered
 }
 
 async fn seed_addr_resolves(seed_addr: &str) -> bool {
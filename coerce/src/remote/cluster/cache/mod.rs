@@ -0,0 +1,193 @@
+//! Per-node caches, kept consistent across the cluster by broadcasting invalidations.
+//!
+//! Each node runs its own [`CacheStore`] replica for a named cache. Values are only ever cached
+//! locally, but whenever a key is inserted or invalidated on one node, the key is invalidated on
+//! every other node, so stale values are never read once the invalidation has been received.
+//!
+//! An optional [`CacheLoader`] allows the cache to be used as a read-through cache, loading
+//! values on a cache miss.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let users: ReplicatedCache<UserId, User> = ReplicatedCache::builder("users", remote.clone())
+//!     .loader(UserLoader { db })
+//!     .ttl(Duration::from_secs(60))
+//!     .build()
+//!     .await;
+//!
+//! let user = users.get(&user_id).await?;
+//!
+//! // after updating the user, ensure no node continues to serve the old value
+//! users.invalidate(&user_id).await?;
+//! ```
+
+use crate::actor::{ActorRefErr, IntoActor, LocalActorRef};
+use crate::remote::cluster::cache::store::{
+    CacheLookup, CacheStore, GetCached, InsertCached, InvalidateCached,
+};
+use crate::remote::system::RemoteActorSystem;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod store;
+
+/// Loads values on a cache miss, allowing a [`ReplicatedCache`] to be used as a read-through cache
+#[async_trait]
+pub trait CacheLoader<K, V>: 'static + Send + Sync {
+    /// Loads the value of the key, or `None` if there is no value for the key
+    async fn load(&self, key: &K) -> Option<V>;
+}
+
+/// Keys must be serialisable so they can be included in invalidations sent to other nodes
+pub trait CacheKey:
+    'static + Serialize + DeserializeOwned + Hash + Eq + Clone + Send + Sync
+{
+}
+
+impl<K> CacheKey for K where
+    K: 'static + Serialize + DeserializeOwned + Hash + Eq + Clone + Send + Sync
+{
+}
+
+pub trait CacheValue: 'static + Clone + Send + Sync {}
+
+impl<V> CacheValue for V where V: 'static + Clone + Send + Sync {}
+
+#[derive(Debug)]
+pub enum CacheErr {
+    ActorRef(ActorRefErr),
+}
+
+impl Display for CacheErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheErr::ActorRef(e) => write!(f, "cache store unavailable: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CacheErr {}
+
+impl From<ActorRefErr> for CacheErr {
+    fn from(e: ActorRefErr) -> Self {
+        CacheErr::ActorRef(e)
+    }
+}
+
+pub struct ReplicatedCache<K: CacheKey, V: CacheValue> {
+    store: LocalActorRef<CacheStore<K, V>>,
+    loader: Option<Arc<dyn CacheLoader<K, V>>>,
+}
+
+impl<K: CacheKey, V: CacheValue> Clone for ReplicatedCache<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            loader: self.loader.clone(),
+        }
+    }
+}
+
+pub struct ReplicatedCacheBuilder<K: CacheKey, V: CacheValue> {
+    name: String,
+    system: RemoteActorSystem,
+    loader: Option<Arc<dyn CacheLoader<K, V>>>,
+    ttl: Option<Duration>,
+}
+
+impl<K: CacheKey, V: CacheValue> ReplicatedCache<K, V> {
+    /// Creates a builder for the cache `name`, caches with the same name on
+    /// different nodes invalidate each other's keys
+    pub fn builder(name: impl ToString, system: RemoteActorSystem) -> ReplicatedCacheBuilder<K, V> {
+        ReplicatedCacheBuilder {
+            name: name.to_string(),
+            system,
+            loader: None,
+            ttl: None,
+        }
+    }
+
+    /// Reads the value of the key from the local cache. If the key isn't cached and the cache
+    /// has a [`CacheLoader`], the value is loaded and cached
+    pub async fn get(&self, key: &K) -> Result<Option<V>, CacheErr> {
+        let generation = match self.store.send(GetCached::new(key.clone())).await? {
+            CacheLookup::Hit(value) => return Ok(Some(value)),
+            CacheLookup::Miss { generation } => generation,
+        };
+
+        let loader = match &self.loader {
+            Some(loader) => loader,
+            None => return Ok(None),
+        };
+
+        let value = loader.load(key).await;
+        if let Some(value) = &value {
+            // if the key was invalidated while loading, the loaded value isn't cached
+            self.store
+                .send(InsertCached {
+                    key: key.clone(),
+                    value: value.clone(),
+                    generation: Some(generation),
+                })
+                .await?;
+        }
+
+        Ok(value)
+    }
+
+    /// Caches the value locally, invalidating the key on every other node
+    pub async fn insert(&self, key: K, value: V) -> Result<(), CacheErr> {
+        Ok(self
+            .store
+            .send(InsertCached {
+                key,
+                value,
+                generation: None,
+            })
+            .await?)
+    }
+
+    /// Removes the key from the cache on every node
+    pub async fn invalidate(&self, key: &K) -> Result<(), CacheErr> {
+        Ok(self
+            .store
+            .send(InvalidateCached(Some(vec![key.clone()])))
+            .await?)
+    }
+
+    /// Removes every key from the cache on every node
+    pub async fn invalidate_all(&self) -> Result<(), CacheErr> {
+        Ok(self.store.send(InvalidateCached(None)).await?)
+    }
+}
+
+impl<K: CacheKey, V: CacheValue> ReplicatedCacheBuilder<K, V> {
+    /// Loads values on a cache miss
+    pub fn loader(mut self, loader: impl CacheLoader<K, V>) -> Self {
+        self.loader = Some(Arc::new(loader));
+        self
+    }
+
+    /// How long values are cached for, by default values are cached until invalidated
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub async fn build(self) -> ReplicatedCache<K, V> {
+        let actor_id = format!("coerce-cache-{}", &self.name);
+        let store = CacheStore::new(self.name, self.system.node_id(), self.ttl)
+            .into_actor(Some(actor_id), self.system.actor_system())
+            .await
+            .expect("start cache store");
+
+        ReplicatedCache {
+            store,
+            loader: self.loader,
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::Actor;
+use crate::remote::cluster::cache::{CacheKey, CacheValue};
+use crate::remote::net::StreamData;
+use crate::remote::stream::pubsub::{PubSub, Receive, Subscription, Topic};
+use crate::remote::system::NodeId;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+pub struct CacheInvalidationTopic(pub String);
+
+/// Published whenever keys are inserted or invalidated, `keys` is `None` if every key
+/// has been invalidated
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CacheInvalidation {
+    pub node_id: NodeId,
+    pub keys: Option<Vec<Value>>,
+}
+
+impl Topic for CacheInvalidationTopic {
+    type Message = CacheInvalidation;
+
+    fn topic_name() -> &'static str {
+        "coerce-cache-invalidation"
+    }
+
+    fn key(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl StreamData for CacheInvalidation {
+    fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
+
+struct CacheEntry<V> {
+    value: V,
+    cached_at: Instant,
+}
+
+/// Local replica of a [`ReplicatedCache`][super::ReplicatedCache], one per node.
+///
+/// Every invalidation, whether local or received from another node, increments the cache's
+/// generation. Values loaded on a cache miss are only cached if the generation hasn't changed
+/// since the miss, so a value that was invalidated while it was being loaded is never cached.
+pub struct CacheStore<K: CacheKey, V: CacheValue> {
+    name: String,
+    node_id: NodeId,
+    ttl: Option<Duration>,
+    entries: HashMap<K, CacheEntry<V>>,
+    generation: u64,
+    subscription: Option<Subscription>,
+}
+
+impl<K: CacheKey, V: CacheValue> CacheStore<K, V> {
+    pub fn new(name: String, node_id: NodeId, ttl: Option<Duration>) -> Self {
+        Self {
+            name,
+            node_id,
+            ttl,
+            entries: HashMap::new(),
+            generation: 0,
+            subscription: None,
+        }
+    }
+
+    fn invalidate(&mut self, keys: Option<&[K]>) {
+        self.generation += 1;
+
+        match keys {
+            Some(keys) => {
+                for key in keys {
+                    self.entries.remove(key);
+                }
+            }
+            None => self.entries.clear(),
+        }
+    }
+
+    async fn publish_invalidation(&self, keys: Option<&[K]>, ctx: &ActorContext) {
+        let keys = keys.map(|keys| {
+            keys.iter()
+                .filter_map(|key| serde_json::to_value(key).ok())
+                .collect()
+        });
+
+        let invalidation = CacheInvalidation {
+            node_id: self.node_id,
+            keys,
+        };
+
+        PubSub::publish(
+            CacheInvalidationTopic(self.name.clone()),
+            invalidation,
+            ctx.system().remote(),
+        )
+        .await;
+    }
+}
+
+#[async_trait]
+impl<K: CacheKey, V: CacheValue> Actor for CacheStore<K, V> {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.subscription = Some(
+            PubSub::subscribe::<Self, CacheInvalidationTopic>(
+                CacheInvalidationTopic(self.name.clone()),
+                ctx,
+            )
+            .await
+            .unwrap(),
+        );
+    }
+}
+
+pub struct GetCached<K, V> {
+    pub key: K,
+    _v: PhantomData<fn() -> V>,
+}
+
+impl<K, V> GetCached<K, V> {
+    pub fn new(key: K) -> Self {
+        Self {
+            key,
+            _v: PhantomData,
+        }
+    }
+}
+
+pub enum CacheLookup<V> {
+    Hit(V),
+    Miss { generation: u64 },
+}
+
+pub struct InsertCached<K, V> {
+    pub key: K,
+    pub value: V,
+
+    /// The generation of the cache when the value was found to be missing,
+    /// or `None` if the value is being inserted directly
+    pub generation: Option<u64>,
+}
+
+pub struct InvalidateCached<K>(pub Option<Vec<K>>);
+
+impl<K: CacheKey, V: CacheValue> Message for GetCached<K, V> {
+    type Result = CacheLookup<V>;
+}
+
+impl<K: CacheKey, V: CacheValue> Message for InsertCached<K, V> {
+    type Result = ();
+}
+
+impl<K: CacheKey> Message for InvalidateCached<K> {
+    type Result = ();
+}
+
+#[async_trait]
+impl<K: CacheKey, V: CacheValue> Handler<GetCached<K, V>> for CacheStore<K, V> {
+    async fn handle(
+        &mut self,
+        message: GetCached<K, V>,
+        _ctx: &mut ActorContext,
+    ) -> CacheLookup<V> {
+        let expired = match self.entries.get(&message.key) {
+            Some(entry) => match self.ttl {
+                Some(ttl) if entry.cached_at.elapsed() >= ttl => true,
+                _ => return CacheLookup::Hit(entry.value.clone()),
+            },
+            None => false,
+        };
+
+        if expired {
+            self.entries.remove(&message.key);
+        }
+
+        CacheLookup::Miss {
+            generation: self.generation,
+        }
+    }
+}
+
+#[async_trait]
+impl<K: CacheKey, V: CacheValue> Handler<InsertCached<K, V>> for CacheStore<K, V> {
+    async fn handle(&mut self, message: InsertCached<K, V>, ctx: &mut ActorContext) {
+        match message.generation {
+            Some(generation) => {
+                if generation != self.generation {
+                    return;
+                }
+            }
+            None => {
+                // other nodes may still be caching the previous value
+                let keys = [message.key.clone()];
+                self.invalidate(Some(&keys));
+                self.publish_invalidation(Some(&keys), ctx).await;
+            }
+        }
+
+        self.entries.insert(
+            message.key,
+            CacheEntry {
+                value: message.value,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl<K: CacheKey, V: CacheValue> Handler<InvalidateCached<K>> for CacheStore<K, V> {
+    async fn handle(&mut self, message: InvalidateCached<K>, ctx: &mut ActorContext) {
+        let keys = message.0.as_deref();
+
+        self.invalidate(keys);
+        self.publish_invalidation(keys, ctx).await;
+    }
+}
+
+#[async_trait]
+impl<K: CacheKey, V: CacheValue> Handler<Receive<CacheInvalidationTopic>> for CacheStore<K, V> {
+    async fn handle(&mut self, message: Receive<CacheInvalidationTopic>, _ctx: &mut ActorContext) {
+        let invalidation = message.0.as_ref();
+        if invalidation.node_id == self.node_id {
+            return;
+        }
+
+        match &invalidation.keys {
+            Some(keys) => {
+                let keys: Vec<K> = keys
+                    .iter()
+                    .filter_map(|key| match serde_json::from_value(key.clone()) {
+                        Ok(key) => Some(key),
+                        Err(e) => {
+                            warn!(
+                                cache = self.name.as_str(),
+                                "unable to deserialize invalidated key, error={}", e
+                            );
+                            None
+                        }
+                    })
+                    .collect();
+
+                self.invalidate(Some(&keys));
+            }
+            None => self.invalidate(None),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Replicated, typed key-value config, shared by every node in the cluster.
+//!
+//! Each node runs a [`ConfigStore`] replica. Values can be read locally, and actors can watch
+//! keys to be notified with a [`ConfigChanged`] message whenever the value changes, enabling
+//! dynamic feature flags without an external system.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let config = ClusterConfig::builder(remote.clone()).build().await;
+//!
+//! config.set("feature.new-checkout", &true).await?;
+//! config.watch::<_, bool>("feature.new-checkout", checkout_actor).await?;
+//!
+//! let enabled: Option<bool> = config.get("feature.new-checkout").await?;
+//! ```
+
+use crate::actor::message::{Handler, Message};
+use crate::actor::{Actor, ActorRefErr, IntoActor, LocalActorRef};
+use crate::remote::cluster::config::store::{
+    ActorWatcher, ConfigStore, GetConfig, SetConfig, WatchConfig,
+};
+use crate::remote::system::RemoteActorSystem;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+pub mod store;
+
+const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Sent to actors watching a config key when the value of the key changes,
+/// `value` is `None` if the key was removed
+#[derive(Debug)]
+pub struct ConfigChanged<T> {
+    pub key: String,
+    pub value: Option<T>,
+}
+
+impl<T: 'static + Send + Sync> Message for ConfigChanged<T> {
+    type Result = ();
+}
+
+#[derive(Debug)]
+pub enum ConfigErr {
+    Serialization(serde_json::Error),
+    Deserialization(serde_json::Error),
+    ActorRef(ActorRefErr),
+}
+
+impl Display for ConfigErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigErr::Serialization(e) => write!(f, "unable to serialize config value: {}", e),
+            ConfigErr::Deserialization(e) => {
+                write!(f, "unable to deserialize config value: {}", e)
+            }
+            ConfigErr::ActorRef(e) => write!(f, "config store unavailable: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigErr {}
+
+impl From<ActorRefErr> for ConfigErr {
+    fn from(e: ActorRefErr) -> Self {
+        ConfigErr::ActorRef(e)
+    }
+}
+
+#[derive(Clone)]
+pub struct ClusterConfig {
+    store: LocalActorRef<ConfigStore>,
+}
+
+pub struct ClusterConfigBuilder {
+    system: RemoteActorSystem,
+    sync_interval: Duration,
+}
+
+impl ClusterConfig {
+    pub fn builder(system: RemoteActorSystem) -> ClusterConfigBuilder {
+        ClusterConfigBuilder {
+            system,
+            sync_interval: DEFAULT_SYNC_INTERVAL,
+        }
+    }
+
+    /// Sets the value of the key, replicating it to every node in the cluster
+    pub async fn set<T: Serialize>(&self, key: impl ToString, value: &T) -> Result<(), ConfigErr> {
+        let value = serde_json::to_value(value).map_err(ConfigErr::Serialization)?;
+
+        Ok(self
+            .store
+            .send(SetConfig {
+                key: key.to_string(),
+                value: Some(value),
+            })
+            .await?)
+    }
+
+    /// Removes the key, replicating the removal to every node in the cluster
+    pub async fn remove(&self, key: impl ToString) -> Result<(), ConfigErr> {
+        Ok(self
+            .store
+            .send(SetConfig {
+                key: key.to_string(),
+                value: None,
+            })
+            .await?)
+    }
+
+    /// Reads the value of the key from the local replica
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        key: impl ToString,
+    ) -> Result<Option<T>, ConfigErr> {
+        match self.store.send(GetConfig(key.to_string())).await? {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(ConfigErr::Deserialization),
+            None => Ok(None),
+        }
+    }
+
+    /// Notifies the actor with a [`ConfigChanged`] message each time the value of the key changes,
+    /// until the actor stops
+    pub async fn watch<A, T>(
+        &self,
+        key: impl ToString,
+        actor_ref: LocalActorRef<A>,
+    ) -> Result<(), ConfigErr>
+    where
+        A: Actor + Handler<ConfigChanged<T>>,
+        T: 'static + DeserializeOwned + Send + Sync,
+    {
+        Ok(self
+            .store
+            .send(WatchConfig {
+                key: key.to_string(),
+                watcher: Box::new(ActorWatcher::<A, T>::new(actor_ref)),
+            })
+            .await?)
+    }
+}
+
+impl ClusterConfigBuilder {
+    /// How often every entry is re-published to the rest of the cluster, allowing replicas that
+    /// missed an update to converge
+    pub fn sync_interval(mut self, sync_interval: Duration) -> Self {
+        self.sync_interval = sync_interval;
+        self
+    }
+
+    pub async fn build(self) -> ClusterConfig {
+        let store = ConfigStore::new(self.system.node_id(), self.sync_interval)
+            .into_actor(Some("cluster-config-store"), self.system.actor_system())
+            .await
+            .expect("start cluster config store");
+
+        ClusterConfig { store }
+    }
+}
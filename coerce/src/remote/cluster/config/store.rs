@@ -0,0 +1,310 @@
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::scheduler::timer::{Timer, TimerTick};
+use crate::actor::{Actor, LocalActorRef};
+use crate::remote::cluster::config::ConfigChanged;
+use crate::remote::net::StreamData;
+use crate::remote::stream::pubsub::{PubSub, Receive, Subscription, Topic};
+use crate::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
+use crate::remote::system::NodeId;
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+/// Orders writes to the same key, the write with the highest version wins
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ConfigVersion {
+    pub timestamp: i64,
+    pub node_id: NodeId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigEntry {
+    pub key: String,
+
+    /// The value of the key, or `None` if the key has been removed
+    pub value: Option<Value>,
+    pub version: ConfigVersion,
+}
+
+pub struct ConfigTopic;
+
+/// Config entries published to every node in the cluster, either as the result of a write,
+/// or as part of a periodic sync of every entry a node knows of
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConfigUpdate {
+    pub entries: Vec<ConfigEntry>,
+}
+
+impl Topic for ConfigTopic {
+    type Message = ConfigUpdate;
+
+    fn topic_name() -> &'static str {
+        "coerce-config"
+    }
+}
+
+impl StreamData for ConfigUpdate {
+    fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
+
+pub(crate) trait ConfigWatcher: 'static + Send + Sync {
+    /// Notifies the watcher of a change, returns false if the watcher is no longer valid
+    fn notify(&self, key: &str, value: Option<&Value>) -> bool;
+}
+
+pub(crate) struct ActorWatcher<A: Actor, T> {
+    actor_ref: LocalActorRef<A>,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<A: Actor, T> ActorWatcher<A, T> {
+    pub fn new(actor_ref: LocalActorRef<A>) -> Self {
+        Self {
+            actor_ref,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<A: Actor, T: 'static + DeserializeOwned + Send + Sync> ConfigWatcher for ActorWatcher<A, T>
+where
+    A: Handler<ConfigChanged<T>>,
+{
+    fn notify(&self, key: &str, value: Option<&Value>) -> bool {
+        let value = match value {
+            Some(value) => match serde_json::from_value(value.clone()) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    warn!(
+                        key = key,
+                        actor_id = self.actor_ref.actor_id().as_ref(),
+                        "unable to deserialize config value, error={}",
+                        e
+                    );
+                    return true;
+                }
+            },
+            None => None,
+        };
+
+        self.actor_ref
+            .notify(ConfigChanged {
+                key: key.to_string(),
+                value,
+            })
+            .is_ok()
+    }
+}
+
+/// Local replica of the cluster config, one per node.
+///
+/// Writes are published to every other replica, conflicting writes are resolved by keeping the
+/// write with the highest [`ConfigVersion`] (last-writer-wins), so every replica converges
+/// on the same entries.
+pub struct ConfigStore {
+    node_id: NodeId,
+    entries: HashMap<String, ConfigEntry>,
+    watchers: HashMap<String, Vec<Box<dyn ConfigWatcher>>>,
+    last_timestamp: i64,
+    sync_interval: Duration,
+    sync_timer: Option<Timer>,
+    config_subscription: Option<Subscription>,
+    system_event_subscription: Option<Subscription>,
+}
+
+impl ConfigStore {
+    pub fn new(node_id: NodeId, sync_interval: Duration) -> Self {
+        Self {
+            node_id,
+            entries: Default::default(),
+            watchers: Default::default(),
+            last_timestamp: 0,
+            sync_interval,
+            sync_timer: None,
+            config_subscription: None,
+            system_event_subscription: None,
+        }
+    }
+
+    fn next_version(&mut self) -> ConfigVersion {
+        // never go backwards, even if the clock does
+        let timestamp = Utc::now().timestamp_millis().max(self.last_timestamp + 1);
+        self.last_timestamp = timestamp;
+
+        ConfigVersion {
+            timestamp,
+            node_id: self.node_id,
+        }
+    }
+
+    /// Applies the entry if it is newer than the current entry for the same key,
+    /// notifying any watchers if the value has changed
+    fn merge(&mut self, entry: ConfigEntry) {
+        self.last_timestamp = self.last_timestamp.max(entry.version.timestamp);
+
+        let changed = match self.entries.get(&entry.key) {
+            Some(current) if current.version >= entry.version => return,
+            Some(current) => current.value != entry.value,
+            None => entry.value.is_some(),
+        };
+
+        if changed {
+            if let Some(watchers) = self.watchers.get_mut(&entry.key) {
+                watchers.retain(|watcher| watcher.notify(&entry.key, entry.value.as_ref()));
+            }
+        }
+
+        self.entries.insert(entry.key.clone(), entry);
+    }
+
+    async fn sync(&self, ctx: &ActorContext) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let update = ConfigUpdate {
+            entries: self.entries.values().cloned().collect(),
+        };
+
+        PubSub::publish(ConfigTopic, update, ctx.system().remote()).await;
+    }
+}
+
+#[async_trait]
+impl Actor for ConfigStore {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.config_subscription = Some(
+            PubSub::subscribe::<Self, ConfigTopic>(ConfigTopic, ctx)
+                .await
+                .unwrap(),
+        );
+
+        self.system_event_subscription = Some(
+            PubSub::subscribe::<Self, SystemTopic>(SystemTopic, ctx)
+                .await
+                .unwrap(),
+        );
+
+        self.sync_timer = Some(Timer::start(
+            self.actor_ref(ctx),
+            self.sync_interval,
+            SyncConfig,
+        ));
+    }
+
+    async fn stopped(&mut self, _ctx: &mut ActorContext) {
+        if let Some(sync_timer) = self.sync_timer.take() {
+            sync_timer.stop();
+        }
+    }
+}
+
+pub struct SetConfig {
+    pub key: String,
+    pub value: Option<Value>,
+}
+
+pub struct GetConfig(pub String);
+
+pub(crate) struct WatchConfig {
+    pub key: String,
+    pub watcher: Box<dyn ConfigWatcher>,
+}
+
+#[derive(Clone)]
+pub struct SyncConfig;
+
+impl Message for SetConfig {
+    type Result = ();
+}
+
+impl Message for GetConfig {
+    type Result = Option<Value>;
+}
+
+impl Message for WatchConfig {
+    type Result = ();
+}
+
+impl Message for SyncConfig {
+    type Result = ();
+}
+
+impl TimerTick for SyncConfig {}
+
+#[async_trait]
+impl Handler<SetConfig> for ConfigStore {
+    async fn handle(&mut self, message: SetConfig, ctx: &mut ActorContext) {
+        let entry = ConfigEntry {
+            key: message.key,
+            value: message.value,
+            version: self.next_version(),
+        };
+
+        self.merge(entry.clone());
+
+        let update = ConfigUpdate {
+            entries: vec![entry],
+        };
+
+        PubSub::publish(ConfigTopic, update, ctx.system().remote()).await;
+    }
+}
+
+#[async_trait]
+impl Handler<GetConfig> for ConfigStore {
+    async fn handle(&mut self, message: GetConfig, _ctx: &mut ActorContext) -> Option<Value> {
+        self.entries
+            .get(&message.0)
+            .and_then(|entry| entry.value.clone())
+    }
+}
+
+#[async_trait]
+impl Handler<WatchConfig> for ConfigStore {
+    async fn handle(&mut self, message: WatchConfig, _ctx: &mut ActorContext) {
+        self.watchers
+            .entry(message.key)
+            .or_default()
+            .push(message.watcher);
+    }
+}
+
+#[async_trait]
+impl Handler<SyncConfig> for ConfigStore {
+    async fn handle(&mut self, _message: SyncConfig, ctx: &mut ActorContext) {
+        self.sync(ctx).await;
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<ConfigTopic>> for ConfigStore {
+    async fn handle(&mut self, message: Receive<ConfigTopic>, _ctx: &mut ActorContext) {
+        for entry in &message.0.entries {
+            self.merge(entry.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<SystemTopic>> for ConfigStore {
+    async fn handle(&mut self, message: Receive<SystemTopic>, ctx: &mut ActorContext) {
+        match message.0.as_ref() {
+            SystemEvent::Cluster(ClusterEvent::NodeAdded(node)) if node.id != self.node_id => {
+                // bring the new node up to date, rather than waiting for the next sync
+                self.sync(ctx).await;
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,57 +1,135 @@
-// use crate::remote::cluster::discovery::{ClusterSeed, ClusterSeedErr, DiscoveredWorker};
-//
-// use std::net::{IpAddr, SocketAddr};
-//
-// use std::str::FromStr;
-// use trust_dns_client::client::{AsyncClient, ClientHandle};
-//
-// use trust_dns_client::rr::{DNSClass, Name, RecordType};
-// use trust_dns_client::udp::UdpClientStream;
-// use trust_dns_proto::udp::UdpResponse;
-//
-// use tokio::net::UdpSocket;
-//
-// pub struct DnsClusterSeed {
-//     client: AsyncClient<UdpResponse>,
-//     seed_host: String,
-// }
-//
-// impl DnsClusterSeed {
-//     pub async fn new(upstream: SocketAddr, seed_host: String) -> DnsClusterSeed {
-//         let stream = UdpClientStream::<UdpSocket>::new(upstream);
-//         let (client, bg) = AsyncClient::connect(stream).await.unwrap();
-//
-//         tokio::spawn(bg);
-//
-//         DnsClusterSeed { client, seed_host }
-//     }
-//
-//     pub async fn all_a_records(&mut self, host: &str) -> Result<Vec<IpAddr>, ClusterSeedErr> {
-//         match self
-//             .client
-//             .query(Name::from_str(host).unwrap(), DNSClass::IN, RecordType::A)
-//             .await
-//         {
-//             Ok(res) => Ok(res
-//                 .answers()
-//                 .into_iter()
-//                 .filter_map(|a| a.rdata().to_ip_addr())
-//                 .collect()),
-//
-//             Err(e) => Err(ClusterSeedErr::Err(format!("{:?}", e))),
-//         }
-//     }
-// }
-//
-// #[async_trait]
-// impl ClusterSeed for DnsClusterSeed {
-//     async fn initial_workers(&mut self) -> Result<Vec<DiscoveredWorker>, ClusterSeedErr> {
-//         let seed_host = self.seed_host.clone();
-//         let records = self.all_a_records(&seed_host).await?;
-//
-//         Ok(records
-//             .into_iter()
-//             .map(DiscoveredWorker::from_host)
-//             .collect())
-//     }
-// }
+//! DNS-based seed discovery
+//!
+//! [`DnsSeed`] resolves the cluster's seed nodes from DNS, for example a Kubernetes headless
+//! service, which has a record for each ready pod backing the service. The record is re-resolved
+//! every 30 seconds by default, so nodes which become ready after this node started are
+//! discovered automatically.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let server = remote
+//!     .cluster_worker()
+//!     .listen_addr("0.0.0.0:30101")
+//!     .external_addr(format!("{}:30101", pod_ip))
+//!     .with_seed(DnsSeed::srv("_coerce._tcp.coerce-headless.default.svc.cluster.local"))
+//!     .start()
+//!     .await;
+//! ```
+
+use crate::remote::cluster::discovery::seed::{ClusterSeed, ClusterSeedErr};
+use hickory_resolver::TokioAsyncResolver;
+use std::collections::BTreeSet;
+use std::time::Duration;
+use tokio::net::lookup_host;
+
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct DnsSeed {
+    name: String,
+    record: DnsRecord,
+    refresh_interval: Duration,
+    resolver: Option<TokioAsyncResolver>,
+}
+
+enum DnsRecord {
+    Srv,
+    A { port: u16 },
+}
+
+impl DnsSeed {
+    /// Resolves the seed addresses from the target and port of each of the name's SRV records
+    pub fn srv(name: impl ToString) -> Self {
+        Self::new(name.to_string(), DnsRecord::Srv)
+    }
+
+    /// Resolves the seed addresses from the name's A records, each node is expected to be
+    /// listening on the provided port
+    pub fn a(name: impl ToString, port: u16) -> Self {
+        Self::new(name.to_string(), DnsRecord::A { port })
+    }
+
+    fn new(name: String, record: DnsRecord) -> Self {
+        Self {
+            name,
+            record,
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            resolver: None,
+        }
+    }
+
+    /// How often the record is re-resolved, defaults to 30 seconds
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    /// Uses the provided resolver for SRV lookups, rather than one created from the
+    /// system's resolver configuration
+    pub fn with_resolver(mut self, resolver: TokioAsyncResolver) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    fn unresolved(&self, reason: impl ToString) -> ClusterSeedErr {
+        ClusterSeedErr::Unresolved {
+            seed: self.name.clone(),
+            reason: reason.to_string(),
+        }
+    }
+
+    async fn srv_addrs(&mut self) -> Result<BTreeSet<String>, ClusterSeedErr> {
+        if self.resolver.is_none() {
+            let resolver =
+                TokioAsyncResolver::tokio_from_system_conf().map_err(|e| self.unresolved(e))?;
+
+            self.resolver = Some(resolver);
+        }
+
+        let resolver = self.resolver.as_ref().unwrap();
+        let records = resolver
+            .srv_lookup(self.name.as_str())
+            .await
+            .map_err(|e| self.unresolved(e))?;
+
+        Ok(records
+            .iter()
+            .map(|srv| {
+                let target = srv.target().to_utf8();
+                format!("{}:{}", target.trim_end_matches('.'), srv.port())
+            })
+            .collect())
+    }
+
+    async fn a_addrs(&self, port: u16) -> Result<BTreeSet<String>, ClusterSeedErr> {
+        let addrs = lookup_host((self.name.as_str(), port))
+            .await
+            .map_err(|e| self.unresolved(e))?;
+
+        Ok(addrs
+            .filter(|addr| addr.is_ipv4())
+            .map(|addr| addr.to_string())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ClusterSeed for DnsSeed {
+    async fn seed_addrs(&mut self) -> Result<Vec<String>, ClusterSeedErr> {
+        let addrs = match self.record {
+            DnsRecord::Srv => self.srv_addrs().await?,
+            DnsRecord::A { port } => self.a_addrs(port).await?,
+        };
+
+        trace!(
+            seed = self.name.as_str(),
+            "resolved seed addresses: {:?}",
+            &addrs
+        );
+
+        Ok(addrs.into_iter().collect())
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        Some(self.refresh_interval)
+    }
+}
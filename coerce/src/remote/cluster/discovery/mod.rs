@@ -1,8 +1,14 @@
+#[cfg(feature = "dns-seed")]
+pub mod dns;
+pub mod seed;
+
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
+use crate::actor::scheduler::timer::{Timer, TimerTick};
 use crate::actor::Actor;
 use crate::remote::actor::message::SetRemote;
-use crate::remote::cluster::node::{NodeIdentity, NodeStatus, RemoteNode};
+use crate::remote::cluster::node::{NodeIdentity, RemoteNode};
+use crate::remote::cluster::version::NodeVersion;
 
 use crate::remote::net::client::RemoteClientRef;
 use crate::remote::stream::pubsub::PubSub;
@@ -12,6 +18,7 @@ use futures::future::join_all;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::oneshot::Sender;
 use uuid::Uuid;
 
@@ -21,9 +28,19 @@ pub struct NodeDiscovery {
     discovered_nodes_by_addr: HashMap<String, Arc<NodeIdentity>>,
     discovered_nodes_by_id: HashMap<NodeId, Arc<NodeIdentity>>,
     remote_system: Option<RemoteActorSystem>,
+    seed_addrs: Vec<String>,
+    next_seed: usize,
+    rediscovery_timer: Option<Timer>,
 }
 
-impl Actor for NodeDiscovery {}
+#[async_trait]
+impl Actor for NodeDiscovery {
+    async fn stopped(&mut self, _ctx: &mut ActorContext) {
+        if let Some(rediscovery_timer) = self.rediscovery_timer.take() {
+            rediscovery_timer.stop();
+        }
+    }
+}
 
 pub struct Discover {
     pub seed: Seed,
@@ -37,6 +54,15 @@ pub enum Seed {
 
 pub struct Forget(pub String);
 
+/// Periodically re-runs discovery, rotating through the provided seeds
+pub struct StartRediscovery {
+    pub seed_addrs: Vec<String>,
+    pub interval: Duration,
+}
+
+#[derive(Clone)]
+pub struct Rediscover;
+
 impl Message for Discover {
     type Result = ();
 }
@@ -45,6 +71,16 @@ impl Message for Forget {
     type Result = ();
 }
 
+impl Message for StartRediscovery {
+    type Result = ();
+}
+
+impl Message for Rediscover {
+    type Result = ();
+}
+
+impl TimerTick for Rediscover {}
+
 #[async_trait]
 impl Handler<SetRemote> for NodeDiscovery {
     async fn handle(&mut self, message: SetRemote, _ctx: &mut ActorContext) {
@@ -54,7 +90,7 @@ impl Handler<SetRemote> for NodeDiscovery {
 
 #[async_trait]
 impl Handler<Discover> for NodeDiscovery {
-    async fn handle(&mut self, message: Discover, ctx: &mut ActorContext) {
+    async fn handle(&mut self, message: Discover, _ctx: &mut ActorContext) {
         let remote = self.remote_system.clone().unwrap();
 
         match message.seed {
@@ -70,6 +106,7 @@ impl Handler<Discover> for NodeDiscovery {
                 }
 
                 if let Some(seed_node) = self.get_node_identity(addr.clone(), &remote).await {
+                    let seed_node_id = seed_node.node.id;
                     let mut discovered_nodes: HashMap<NodeId, Arc<NodeIdentity>> = HashMap::new();
                     discovered_nodes.insert(seed_node.node.id, seed_node.clone());
 
@@ -91,12 +128,14 @@ impl Handler<Discover> for NodeDiscovery {
                         return;
                     }
 
-                    let _ = self.actor_ref(ctx).notify(Discover {
-                        seed: Seed::Nodes(
-                            discovered_nodes.values().map(|n| n.node.clone()).collect(),
-                        ),
-                        on_discovery_complete: message.on_discovery_complete,
-                    });
+                    let nodes = discovered_nodes.values().map(|n| n.node.clone()).collect();
+                    self.handshake_nodes(
+                        &remote,
+                        nodes,
+                        Some(seed_node_id),
+                        message.on_discovery_complete,
+                    )
+                    .await;
                 } else {
                     warn!(
                         node_id = remote.node_id(),
@@ -110,64 +149,56 @@ impl Handler<Discover> for NodeDiscovery {
             }
 
             Seed::Nodes(nodes) => {
-                let current_nodes: HashSet<NodeId> = remote
-                    .get_nodes()
-                    .await
-                    .into_iter()
-                    .filter(|n| n.status != NodeStatus::Terminated)
-                    .map(|n| n.id)
-                    .collect();
-                let node_count = nodes.len();
-
-                info!("discovering {} nodes", node_count);
-
-                let mut tasks = vec![];
-
-                for node in nodes {
-                    if !current_nodes.contains(&node.id)
-                        && !self.discovering_nodes.contains(&node.id)
-                    {
-                        let node_addr = node.addr.clone();
-                        if let Some(client) = remote.get_remote_client(node_addr).await {
-                            remote.register_node(node.clone()).await;
-
-                            let seed_nodes = remote
-                                .get_nodes()
-                                .await
-                                .into_iter()
-                                .filter(|n| n.status != NodeStatus::Terminated)
-                                .map(|n| n.into())
-                                .collect();
-
-                            self.discovering_nodes.insert(node.id);
-                            tasks.push(discover_node_handshake(
-                                node,
-                                remote.clone(),
-                                client,
-                                seed_nodes,
-                            ));
-                        }
-                    }
-                }
+                self.handshake_nodes(&remote, nodes, None, message.on_discovery_complete)
+                    .await;
+            }
+        }
+    }
+}
 
-                let on_discovery_complete = message.on_discovery_complete;
-                tokio::spawn(async move {
-                    let nodes_discovering_count = tasks.len();
-                    let _ = join_all(tasks).await;
-                    info!(
-                        "discovered {} new nodes (out of {})",
-                        nodes_discovering_count, node_count
-                    );
+#[async_trait]
+impl Handler<StartRediscovery> for NodeDiscovery {
+    async fn handle(&mut self, message: StartRediscovery, ctx: &mut ActorContext) {
+        if let Some(rediscovery_timer) = self.rediscovery_timer.take() {
+            rediscovery_timer.stop();
+        }
 
-                    if let Some(discovery_complete) = on_discovery_complete {
-                        let _ = discovery_complete.send(true);
-                    }
-                });
-            }
+        self.seed_addrs = message.seed_addrs;
+        self.next_seed = 0;
+
+        if !self.seed_addrs.is_empty() {
+            self.rediscovery_timer = Some(Timer::start(
+                self.actor_ref(ctx),
+                message.interval,
+                Rediscover,
+            ));
         }
     }
 }
 
+#[async_trait]
+impl Handler<Rediscover> for NodeDiscovery {
+    async fn handle(&mut self, _: Rediscover, ctx: &mut ActorContext) {
+        if self.seed_addrs.is_empty() {
+            return;
+        }
+
+        let seed_addr = self.seed_addrs[self.next_seed % self.seed_addrs.len()].clone();
+        self.next_seed = self.next_seed.wrapping_add(1);
+
+        // the seed's identity is re-fetched, so that if the seed has been replaced by a new node
+        // (at the same address), the new incarnation of the cluster is discovered.
+        self.discovered_nodes_by_addr.remove(&seed_addr);
+
+        debug!(seed_addr = &seed_addr, "re-running discovery");
+
+        let _ = self.actor_ref(ctx).notify(Discover {
+            seed: Seed::Addr(seed_addr),
+            on_discovery_complete: None,
+        });
+    }
+}
+
 #[async_trait]
 impl Handler<Forget> for NodeDiscovery {
     async fn handle(&mut self, message: Forget, _ctx: &mut ActorContext) {
@@ -214,6 +245,20 @@ impl Handler<NodeDiscovered> for NodeDiscovery {
         let remote = self.remote_system.as_ref().unwrap();
         if message.successful {
             if self.discovering_nodes.remove(&message.node.id) {
+                let skew = remote
+                    .config()
+                    .version_skew_policy()
+                    .check(&NodeVersion::current(), message.node.version.as_ref());
+
+                for skew in skew {
+                    warn!(
+                        node_id = message.node.id,
+                        addr = &message.node.addr,
+                        "version skew detected: {}",
+                        skew
+                    );
+                }
+
                 PubSub::publish_locally(
                     SystemTopic,
                     SystemEvent::Cluster(ClusterEvent::NodeAdded(Arc::new(message.node))),
@@ -238,9 +283,16 @@ async fn discover_node_handshake(
     system: RemoteActorSystem,
     client: RemoteClientRef,
     seed_nodes: Vec<RemoteNode>,
-) {
+) -> (NodeId, bool) {
+    let node_id = node.id;
     let request_id = Uuid::new_v4();
-    let handshake_result = client.handshake(request_id, seed_nodes).await;
+    let handshake_result = client
+        .handshake(
+            request_id,
+            seed_nodes,
+            system.config().exchange_retry_policy(),
+        )
+        .await;
     let successful = handshake_result.is_ok();
     match handshake_result {
         Ok(_) => {
@@ -259,9 +311,86 @@ async fn discover_node_handshake(
     let _ = system
         .node_discovery()
         .notify(NodeDiscovered { node, successful });
+
+    (node_id, successful)
 }
 
 impl NodeDiscovery {
+    /// Handshakes with each node that isn't already part of the cluster. When the nodes were
+    /// discovered via a seed, discovery only succeeds if the seed accepted the handshake, a seed
+    /// that rejects the node (or can't be reached) means it can't join the cluster via that seed.
+    async fn handshake_nodes(
+        &mut self,
+        remote: &RemoteActorSystem,
+        nodes: Vec<RemoteNode>,
+        seed_node_id: Option<NodeId>,
+        on_discovery_complete: Option<Sender<bool>>,
+    ) {
+        let current_nodes: HashSet<NodeId> = remote
+            .get_nodes()
+            .await
+            .into_iter()
+            .filter(|n| n.status.is_member())
+            .map(|n| n.id)
+            .collect();
+        let node_count = nodes.len();
+
+        info!("discovering {} nodes", node_count);
+
+        let mut tasks = vec![];
+
+        for node in nodes {
+            if remote.is_node_blocked(node.id, &node.addr) {
+                debug!(
+                    node_id = node.id,
+                    addr = &node.addr,
+                    "skipping blocked node"
+                );
+                continue;
+            }
+
+            if !current_nodes.contains(&node.id) && !self.discovering_nodes.contains(&node.id) {
+                let node_addr = node.addr.clone();
+                if let Some(client) = remote.get_remote_client(node_addr).await {
+                    remote.register_node(node.clone()).await;
+
+                    let seed_nodes = remote
+                        .get_nodes()
+                        .await
+                        .into_iter()
+                        .filter(|n| n.status.is_member())
+                        .map(|n| n.into())
+                        .collect();
+
+                    self.discovering_nodes.insert(node.id);
+                    tasks.push(discover_node_handshake(
+                        node,
+                        remote.clone(),
+                        client,
+                        seed_nodes,
+                    ));
+                }
+            }
+        }
+
+        tokio::spawn(async move {
+            let nodes_discovering_count = tasks.len();
+            let handshakes = join_all(tasks).await;
+            info!(
+                "discovered {} new nodes (out of {})",
+                nodes_discovering_count, node_count
+            );
+
+            let seed_rejected = handshakes
+                .iter()
+                .any(|(node_id, successful)| !successful && Some(*node_id) == seed_node_id);
+
+            if let Some(discovery_complete) = on_discovery_complete {
+                let _ = discovery_complete.send(!seed_rejected);
+            }
+        });
+    }
+
     async fn discover_nodes(
         &mut self,
         remote: &RemoteActorSystem,
@@ -298,31 +427,43 @@ impl NodeDiscovery {
 
         let client = remote.get_remote_client(addr.clone()).await;
         if let Some(client) = client {
-            let identity = client.identify().await;
-            if let Ok(Some(identity)) = identity {
-                let identity = Arc::new(identity);
-                let node_remote_addr = identity.node.addr.clone();
-                let node_id = identity.node.id;
-
-                info!(
-                    "cached node identity (addr={}, remote_addr={})",
-                    &addr, &node_remote_addr
-                );
+            match client.identify().await {
+                Ok(identity) => {
+                    if remote.is_node_blocked(identity.node.id, &identity.node.addr) {
+                        warn!(
+                            node_id = identity.node.id,
+                            addr = &addr,
+                            "identified node is blocked, ignoring"
+                        );
+
+                        return None;
+                    }
 
-                if addr != node_remote_addr {
-                    self.discovered_nodes_by_addr.insert(addr, identity.clone());
-                }
+                    let identity = Arc::new(identity);
+                    let node_remote_addr = identity.node.addr.clone();
+                    let node_id = identity.node.id;
 
-                self.discovered_nodes_by_addr
-                    .insert(node_remote_addr, identity.clone());
+                    info!(
+                        "cached node identity (addr={}, remote_addr={})",
+                        &addr, &node_remote_addr
+                    );
 
-                self.discovered_nodes_by_id
-                    .insert(node_id, identity.clone());
+                    if addr != node_remote_addr {
+                        self.discovered_nodes_by_addr.insert(addr, identity.clone());
+                    }
 
-                Some(identity)
-            } else {
-                info!("unable to identify node");
-                None
+                    self.discovered_nodes_by_addr
+                        .insert(node_remote_addr, identity.clone());
+
+                    self.discovered_nodes_by_id
+                        .insert(node_id, identity.clone());
+
+                    Some(identity)
+                }
+                Err(e) => {
+                    info!("unable to identify node, error: {}", e);
+                    None
+                }
             }
         } else {
             warn!(
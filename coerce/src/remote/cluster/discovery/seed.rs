@@ -0,0 +1,88 @@
+//! Pluggable sources of seed nodes
+//!
+//! A [`ClusterSeed`] provides the addresses a node joins the cluster through, as an alternative
+//! to a fixed list of seed addresses (see [`ClusterWorkerBuilder::with_seed_addrs`]). The seed is
+//! resolved when the node starts, and if the seed has a [`refresh_interval`], it's periodically
+//! re-resolved, so any new peers are discovered and registered with the cluster.
+//!
+//! [`ClusterWorkerBuilder::with_seed_addrs`]: crate::remote::cluster::builder::worker::ClusterWorkerBuilder::with_seed_addrs
+//! [`refresh_interval`]: ClusterSeed::refresh_interval
+
+use crate::remote::cluster::discovery::{Discover, Seed};
+use crate::remote::system::RemoteActorSystem;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+#[async_trait]
+pub trait ClusterSeed: 'static + Send + Sync {
+    /// Resolves the addresses of the seed nodes
+    async fn seed_addrs(&mut self) -> Result<Vec<String>, ClusterSeedErr>;
+
+    /// How often the seed is re-resolved once the node has started, by default the seed is
+    /// only resolved once
+    fn refresh_interval(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub enum ClusterSeedErr {
+    /// The seed could not be resolved
+    Unresolved { seed: String, reason: String },
+}
+
+impl Display for ClusterSeedErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClusterSeedErr::Unresolved { seed, reason } => {
+                write!(f, "unable to resolve seed (seed={}): {}", seed, reason)
+            }
+        }
+    }
+}
+
+impl Error for ClusterSeedErr {}
+
+/// Periodically re-resolves the seed, discovering any addresses that weren't previously resolved
+pub(crate) fn start_seed_refresh(
+    mut seed: Box<dyn ClusterSeed>,
+    interval: Duration,
+    known_addrs: HashSet<String>,
+    node_addr: String,
+    system: RemoteActorSystem,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut known_addrs = known_addrs;
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let seed_addrs = match seed.seed_addrs().await {
+                Ok(seed_addrs) => seed_addrs,
+                Err(e) => {
+                    warn!("{}, retrying in {}ms", e, interval.as_millis());
+                    continue;
+                }
+            };
+
+            for seed_addr in &seed_addrs {
+                if seed_addr == &node_addr || known_addrs.contains(seed_addr) {
+                    continue;
+                }
+
+                info!(seed_addr = seed_addr.as_str(), "new seed address resolved");
+
+                let _ = system.node_discovery().notify(Discover {
+                    seed: Seed::Addr(seed_addr.clone()),
+                    on_discovery_complete: None,
+                });
+            }
+
+            // addresses that disappear and later reappear (for example, a pod being rescheduled
+            // with the same IP) are discovered again
+            known_addrs = seed_addrs.into_iter().collect();
+        }
+    })
+}
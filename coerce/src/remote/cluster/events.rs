@@ -0,0 +1,149 @@
+//! Cluster membership events
+//!
+//! [`RemoteActorSystem::subscribe_cluster_events`] returns a [`ClusterEventStream`] of
+//! [`MemberEvent`]s, allowing applications to react to nodes joining, leaving or becoming
+//! unreachable, without polling the node list.
+//!
+//! The stream starts with the current state of the cluster, a [`MemberEvent::MemberUp`] for each
+//! current member (followed by [`MemberEvent::MemberUnreachable`] if the member is currently
+//! unreachable), and a [`MemberEvent::LeaderChanged`] for the current leader, if there is one.
+//! Changes after that point are emitted as they happen.
+//!
+//! [`RemoteActorSystem::subscribe_cluster_events`]: crate::remote::system::RemoteActorSystem::subscribe_cluster_events
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::Handler;
+use crate::actor::{Actor, ActorId, IntoActor, LocalActorRef};
+use crate::remote::cluster::node::RemoteNode;
+use crate::remote::stream::pubsub::{PubSub, Receive, Subscription};
+use crate::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
+use crate::remote::system::{NodeId, RemoteActorSystem};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio_stream::Stream;
+
+#[derive(Debug, Clone)]
+pub enum MemberEvent {
+    /// The node has joined the cluster
+    MemberUp(RemoteNode),
+
+    /// The node has left the cluster, was terminated, or was quarantined
+    MemberRemoved(RemoteNode),
+
+    /// The node is the new leader of the cluster
+    LeaderChanged(NodeId),
+
+    /// The local node's failure detector suspects the node has failed,
+    /// see [`NodeHealth`][crate::remote::cluster::node::NodeHealth]
+    MemberUnreachable(RemoteNode),
+
+    /// The node, which was previously unreachable, is reachable again
+    MemberReachable(RemoteNode),
+}
+
+/// A stream of [`MemberEvent`]s, the subscription ends once the stream is dropped
+pub struct ClusterEventStream {
+    receiver: UnboundedReceiver<MemberEvent>,
+    forwarder: LocalActorRef<ClusterEventForwarder>,
+}
+
+struct ClusterEventForwarder {
+    subscription: Option<Subscription>,
+    sender: UnboundedSender<MemberEvent>,
+}
+
+impl ClusterEventStream {
+    pub(crate) async fn subscribe(system: &RemoteActorSystem) -> ClusterEventStream {
+        let (sender, receiver) = unbounded_channel();
+        let forwarder = ClusterEventForwarder {
+            subscription: None,
+            sender,
+        }
+        .into_anon_actor(None::<ActorId>, system.actor_system())
+        .await
+        .expect("cluster event forwarder");
+
+        ClusterEventStream {
+            receiver,
+            forwarder,
+        }
+    }
+}
+
+impl Stream for ClusterEventStream {
+    type Item = MemberEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for ClusterEventStream {
+    fn drop(&mut self) {
+        let _ = self.forwarder.notify_stop();
+    }
+}
+
+impl ClusterEventForwarder {
+    fn forward(&self, event: MemberEvent, ctx: &mut ActorContext) {
+        if self.sender.send(event).is_err() {
+            // the stream was dropped
+            ctx.stop(None);
+        }
+    }
+}
+
+#[async_trait]
+impl Actor for ClusterEventForwarder {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.subscription = Some(
+            PubSub::subscribe::<Self, SystemTopic>(SystemTopic, ctx)
+                .await
+                .expect("system topic subscription"),
+        );
+
+        let remote = ctx.system().remote_owned();
+        for node in remote.get_nodes().await {
+            if !node.status.is_member() {
+                continue;
+            }
+
+            let reachable = node.health.reachable;
+            let node: RemoteNode = node.into();
+
+            self.forward(MemberEvent::MemberUp(node.clone()), ctx);
+            if !reachable {
+                self.forward(MemberEvent::MemberUnreachable(node), ctx);
+            }
+        }
+
+        if let Some(leader_id) = remote.current_leader() {
+            self.forward(MemberEvent::LeaderChanged(leader_id), ctx);
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<SystemTopic>> for ClusterEventForwarder {
+    async fn handle(&mut self, message: Receive<SystemTopic>, ctx: &mut ActorContext) {
+        let event = match message.0.as_ref() {
+            SystemEvent::Cluster(event) => match event {
+                ClusterEvent::NodeAdded(node) => MemberEvent::MemberUp(node.as_ref().clone()),
+                ClusterEvent::NodeRemoved(node) | ClusterEvent::NodeQuarantined(node) => {
+                    MemberEvent::MemberRemoved(node.as_ref().clone())
+                }
+                ClusterEvent::LeaderChanged(node_id) => MemberEvent::LeaderChanged(*node_id),
+                ClusterEvent::NodeUnreachable(node) => {
+                    MemberEvent::MemberUnreachable(node.as_ref().clone())
+                }
+                ClusterEvent::NodeReachable(node) => {
+                    MemberEvent::MemberReachable(node.as_ref().clone())
+                }
+                ClusterEvent::MemberUp(_) | ClusterEvent::Standalone => return,
+            },
+        };
+
+        self.forward(event, ctx);
+    }
+}
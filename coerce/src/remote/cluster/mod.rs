@@ -1,4 +1,9 @@
+pub mod blocklist;
 pub mod builder;
+pub mod cache;
 pub mod client;
+pub mod config;
 pub mod discovery;
+pub mod events;
 pub mod node;
+pub mod version;
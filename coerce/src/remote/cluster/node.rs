@@ -2,11 +2,15 @@ use crate::remote::system::NodeId;
 
 use hashring::HashRing;
 
+use crate::remote::cluster::version::NodeVersion;
 use crate::remote::config::SystemCapabilities;
+use crate::remote::net::compression::Compression;
 use crate::remote::net::message::{datetime_to_timestamp, timestamp_to_datetime};
 use crate::remote::net::proto::network;
+use crate::remote::net::version::ProtocolVersions;
 use crate::remote::stream::system::ClusterEvent::NodeAdded;
 use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::{Hash, Hasher};
@@ -24,12 +28,26 @@ pub enum NodeStatus {
     Healthy,
     Unhealthy,
     Terminated,
+
+    /// The node could not be re-connected to, within the limits of the [`ReconnectPolicy`].
+    /// Quarantined nodes are excluded from leadership, sharding and singleton placement, until
+    /// they rejoin the cluster, see [`RemoteActorSystem::rejoin_node`].
+    ///
+    /// [`ReconnectPolicy`]: crate::remote::net::client::reconnect::ReconnectPolicy
+    /// [`RemoteActorSystem::rejoin_node`]: crate::remote::system::RemoteActorSystem::rejoin_node
+    Quarantined,
 }
 
 impl NodeStatus {
     pub fn is_healthy(&self) -> bool {
         return matches!(&self, Self::Healthy);
     }
+
+    /// Returns whether the node is still considered part of the cluster,
+    /// meaning it has not been terminated or quarantined
+    pub fn is_member(&self) -> bool {
+        !matches!(&self, Self::Terminated | Self::Quarantined)
+    }
 }
 
 pub type NodeAttribute = (Arc<str>, Arc<str>);
@@ -47,7 +65,30 @@ pub struct RemoteNodeState {
     pub last_heartbeat: Option<DateTime<Utc>>,
     pub node_started_at: Option<DateTime<Utc>>,
     pub status: NodeStatus,
+    pub health: NodeHealth,
     pub attributes: NodeAttributesRef,
+    pub roles: Vec<String>,
+
+    /// The version of Coerce the node is running, if advertised, see [`version`]
+    ///
+    /// [`version`]: crate::remote::cluster::version
+    pub version: Option<NodeVersion>,
+
+    /// Whether the node is in maintenance mode, the node remains a member of the cluster but
+    /// isn't chosen for new placements, see [`RemoteActorSystem::enter_maintenance`]
+    ///
+    /// [`RemoteActorSystem::enter_maintenance`]: crate::remote::system::RemoteActorSystem::enter_maintenance
+    pub maintenance: bool,
+}
+
+/// Reachability of a node, as seen by the local node's failure detector,
+/// see [`PhiAccrualFailureDetector`].
+///
+/// [`PhiAccrualFailureDetector`]: crate::remote::heartbeat::failure_detector::PhiAccrualFailureDetector
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NodeHealth {
+    pub reachable: bool,
+    pub phi: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -57,11 +98,23 @@ pub struct RemoteNode {
     pub tag: String,
     pub node_started_at: Option<DateTime<Utc>>,
     pub attributes: NodeAttributesRef,
+
+    /// The roles this node performs within the cluster,
+    /// see [`ClusterWorkerBuilder::with_roles`]
+    ///
+    /// [`ClusterWorkerBuilder::with_roles`]: crate::remote::cluster::builder::worker::ClusterWorkerBuilder::with_roles
+    pub roles: Vec<String>,
+
+    /// The version of Coerce the node is running, if advertised, see [`version`]
+    ///
+    /// [`version`]: crate::remote::cluster::version
+    pub version: Option<NodeVersion>,
 }
 
 pub enum NodeSelector {
     All,
     Attribute(NodeAttribute),
+    Role(Arc<str>),
 }
 
 impl NodeSelector {
@@ -69,16 +122,24 @@ impl NodeSelector {
         Self::Attribute((key, value))
     }
 
+    pub fn role(role: &str) -> Self {
+        Self::Role(role.into())
+    }
+
     pub fn includes(&self, node: &RemoteNode) -> bool {
         match &self {
             NodeSelector::All => true,
             NodeSelector::Attribute((key, value)) => node.attributes.get(key) == Some(value),
+            NodeSelector::Role(role) => node.has_role(role),
         }
     }
 }
 
 pub type RemoteNodeRef = Arc<RemoteNode>;
 
+/// The node attribute used to describe the role a node performs within the cluster
+pub const NODE_ROLE_ATTRIBUTE: &str = "role";
+
 impl Hash for RemoteNode {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.id.hash(state);
@@ -88,11 +149,31 @@ impl Hash for RemoteNode {
     }
 }
 
+/// The cluster node that an actor (or sharded entity) is running on
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NodeLocation {
+    pub node_id: NodeId,
+    pub addr: String,
+    pub node_tag: String,
+}
+
+impl From<&RemoteNodeState> for NodeLocation {
+    fn from(node: &RemoteNodeState) -> Self {
+        Self {
+            node_id: node.id,
+            addr: node.addr.clone(),
+            node_tag: node.tag.clone(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct NodeIdentity {
     pub node: RemoteNode,
     pub peers: Vec<RemoteNode>,
     pub capabilities: SystemCapabilities,
+    pub protocol_versions: ProtocolVersions,
+    pub compression: Option<Compression>,
 }
 
 impl RemoteNodeStore {
@@ -111,7 +192,12 @@ impl RemoteNodeStore {
     }
 
     pub fn update_nodes(&mut self, nodes: Vec<RemoteNodeState>) {
-        for node in nodes {
+        for mut node in nodes {
+            // quarantined nodes stay quarantined until they're re-registered
+            if self.get(&node.id).map(|n| n.status) == Some(NodeStatus::Quarantined) {
+                node.status = NodeStatus::Quarantined;
+            }
+
             self.nodes.insert(node.id, node);
         }
     }
@@ -123,6 +209,17 @@ impl RemoteNodeStore {
         }
     }
 
+    /// Marks the node as quarantined, returning the node if it wasn't already quarantined
+    pub fn node_quarantined(&mut self, node_id: NodeId) -> Option<RemoteNodeState> {
+        let node = self.get_mut(&node_id)?;
+        if node.status == NodeStatus::Quarantined {
+            return None;
+        }
+
+        node.status = NodeStatus::Quarantined;
+        Some(node.clone())
+    }
+
     pub fn get(&self, node_id: &NodeId) -> Option<&RemoteNodeState> {
         self.nodes.get(node_id)
     }
@@ -182,7 +279,23 @@ impl RemoteNodeState {
             ping_latency: None,
             last_heartbeat: None,
             status: NodeStatus::Joining,
+            health: NodeHealth::default(),
             attributes: node.attributes.clone(),
+            roles: node.roles,
+            version: node.version,
+            maintenance: false,
+        }
+    }
+
+    /// Orders nodes by age, oldest first, nodes that started at the same time
+    /// are ordered by their ID
+    pub fn cmp_age(&self, other: &Self) -> Ordering {
+        match Ord::cmp(
+            &self.node_started_at.unwrap_or(DateTime::<Utc>::MIN_UTC),
+            &other.node_started_at.unwrap_or(DateTime::<Utc>::MIN_UTC),
+        ) {
+            Ordering::Equal => Ord::cmp(&self.id, &other.id),
+            ordering => ordering,
         }
     }
 }
@@ -195,6 +308,8 @@ impl From<RemoteNodeState> for RemoteNode {
             tag: s.tag,
             node_started_at: s.node_started_at,
             attributes: s.attributes,
+            roles: s.roles,
+            version: s.version,
         }
     }
 }
@@ -212,6 +327,13 @@ impl From<network::RemoteNode> for RemoteNode {
                 .map(|(k, v)| (k.into(), v.into()))
                 .collect::<NodeAttributes>()
                 .into(),
+            roles: n.roles,
+            version: NodeVersion::from_wire(
+                n.crate_version,
+                n.min_protocol_version,
+                n.max_protocol_version,
+                n.features,
+            ),
         }
     }
 }
@@ -228,6 +350,14 @@ impl From<RemoteNode> for network::RemoteNode {
                 .iter()
                 .map(|(k, v)| (k.to_string(), v.to_string()))
                 .collect(),
+            roles: n.roles,
+            crate_version: n
+                .version
+                .as_ref()
+                .map_or_else(String::new, |v| v.crate_version.clone()),
+            min_protocol_version: n.version.as_ref().map_or(0, |v| v.protocol_versions.min),
+            max_protocol_version: n.version.as_ref().map_or(0, |v| v.protocol_versions.max),
+            features: n.version.map_or_else(Vec::new, |v| v.features),
             ..Self::default()
         }
     }
@@ -245,6 +375,17 @@ impl From<&RemoteNode> for network::RemoteNode {
                 .iter()
                 .map(|(k, v)| (k.to_string(), v.to_string()))
                 .collect(),
+            roles: n.roles.clone(),
+            crate_version: n
+                .version
+                .as_ref()
+                .map_or_else(String::new, |v| v.crate_version.clone()),
+            min_protocol_version: n.version.as_ref().map_or(0, |v| v.protocol_versions.min),
+            max_protocol_version: n.version.as_ref().map_or(0, |v| v.protocol_versions.max),
+            features: n
+                .version
+                .as_ref()
+                .map_or_else(Vec::new, |v| v.features.clone()),
             ..Self::default()
         }
     }
@@ -262,6 +403,14 @@ impl From<RemoteNodeState> for network::RemoteNode {
                 .iter()
                 .map(|(k, v)| (k.to_string(), v.to_string()))
                 .collect(),
+            roles: s.roles,
+            crate_version: s
+                .version
+                .as_ref()
+                .map_or_else(String::new, |v| v.crate_version.clone()),
+            min_protocol_version: s.version.as_ref().map_or(0, |v| v.protocol_versions.min),
+            max_protocol_version: s.version.as_ref().map_or(0, |v| v.protocol_versions.max),
+            features: s.version.map_or_else(Vec::new, |v| v.features),
             ..Self::default()
         }
     }
@@ -284,6 +433,13 @@ impl From<&network::NodeIdentity> for RemoteNode {
                 .map(|(k, v)| (k.clone().into(), v.clone().into()))
                 .collect::<NodeAttributes>()
                 .into(),
+            roles: n.roles.clone(),
+            version: NodeVersion::from_wire(
+                n.crate_version.clone(),
+                n.min_protocol_version,
+                n.max_protocol_version,
+                n.features.clone(),
+            ),
         }
     }
 }
@@ -295,10 +451,23 @@ impl Default for RemoteNodeState {
             addr: String::default(),
             tag: String::default(),
             status: NodeStatus::Joining,
+            health: NodeHealth::default(),
             ping_latency: None,
             last_heartbeat: None,
             node_started_at: None,
             attributes: Arc::new(NodeAttributes::new()),
+            roles: vec![],
+            version: None,
+            maintenance: false,
+        }
+    }
+}
+
+impl Default for NodeHealth {
+    fn default() -> Self {
+        Self {
+            reachable: true,
+            phi: 0.0,
         }
     }
 }
@@ -310,6 +479,7 @@ impl RemoteNode {
         tag: String,
         node_started_at: Option<DateTime<Utc>>,
         attributes: NodeAttributesRef,
+        roles: Vec<String>,
     ) -> RemoteNode {
         RemoteNode {
             id,
@@ -317,8 +487,25 @@ impl RemoteNode {
             tag,
             node_started_at,
             attributes,
+            roles,
+            version: None,
         }
     }
+
+    /// Sets the version of Coerce the node is running
+    pub fn with_version(mut self, version: NodeVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Returns whether the node has the provided role, either via [`ClusterWorkerBuilder::with_roles`]
+    /// or the [`NODE_ROLE_ATTRIBUTE`] attribute
+    ///
+    /// [`ClusterWorkerBuilder::with_roles`]: crate::remote::cluster::builder::worker::ClusterWorkerBuilder::with_roles
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+            || self.attributes.get(NODE_ROLE_ATTRIBUTE).map(|r| r.as_ref()) == Some(role)
+    }
 }
 
 impl Display for RemoteNode {
@@ -0,0 +1,264 @@
+//! Version and feature skew detection
+//!
+//! Every node advertises the version of Coerce it was built with, the range of protocol versions
+//! it supports, and the Coerce features it was built with, both when identifying itself and in the
+//! node lists exchanged during the handshake. When a node joins the cluster, its version is
+//! compared with the local node's, and a warning is logged for each difference that exceeds the
+//! configured [`VersionSkewPolicy`], which makes mismatched nodes visible during a rolling upgrade.
+//!
+//! Nodes that predate version advertisement are reported as having an unknown version.
+
+use crate::remote::net::version::ProtocolVersions;
+use crate::CARGO_PKG_VERSION;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+/// The version of Coerce a node is running, and the Coerce features it was built with
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NodeVersion {
+    pub crate_version: String,
+    pub protocol_versions: ProtocolVersions,
+    pub features: Vec<String>,
+}
+
+impl NodeVersion {
+    /// The version of this node
+    pub fn current() -> Self {
+        Self {
+            crate_version: CARGO_PKG_VERSION.to_string(),
+            protocol_versions: ProtocolVersions::current(),
+            features: enabled_features().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    /// Reads a version advertised by another node, where an empty crate version means
+    /// the version wasn't advertised
+    pub(crate) fn from_wire(
+        crate_version: String,
+        min_protocol_version: u32,
+        max_protocol_version: u32,
+        features: Vec<String>,
+    ) -> Option<Self> {
+        if crate_version.is_empty() {
+            None
+        } else {
+            Some(Self {
+                crate_version,
+                protocol_versions: ProtocolVersions::from_wire(
+                    min_protocol_version,
+                    max_protocol_version,
+                ),
+                features,
+            })
+        }
+    }
+}
+
+impl Display for NodeVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "coerce={}, protocol={}, features=[{}]",
+            self.crate_version,
+            self.protocol_versions,
+            self.features.join(",")
+        )
+    }
+}
+
+const FEATURES: &[(&str, bool)] = &[
+    ("remote", cfg!(feature = "remote")),
+    ("persistence", cfg!(feature = "persistence")),
+    ("metrics", cfg!(feature = "metrics")),
+    ("sharding", cfg!(feature = "sharding")),
+    ("api", cfg!(feature = "api")),
+    ("actor-tracing", cfg!(feature = "actor-tracing")),
+    ("actor-events", cfg!(feature = "actor-events")),
+    ("client-auth-jwt", cfg!(feature = "client-auth-jwt")),
+    ("singleton", cfg!(feature = "singleton")),
+    ("scheduler", cfg!(feature = "scheduler")),
+    ("net", cfg!(feature = "net")),
+    ("http-client", cfg!(feature = "http-client")),
+    ("tls", cfg!(feature = "tls")),
+    ("dns-seed", cfg!(feature = "dns-seed")),
+    ("lz4", cfg!(feature = "lz4")),
+    ("zstd", cfg!(feature = "zstd")),
+    ("msgpack", cfg!(feature = "msgpack")),
+];
+
+/// The Coerce features this node was built with
+pub fn enabled_features() -> impl Iterator<Item = &'static str> {
+    FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| *feature)
+}
+
+/// The most significant part of the Coerce version that may differ between nodes
+/// before a warning is logged
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum CrateVersionSkew {
+    /// Every node must run the same version
+    None,
+
+    /// Nodes may run different patch versions
+    Patch,
+
+    /// Nodes may run different minor (and patch) versions
+    Minor,
+
+    /// Nodes may run any version
+    Major,
+}
+
+/// Which differences between the local node's version and another node's version
+/// are logged as warnings, see [`RemoteSystemConfigBuilder::version_skew_policy`]
+///
+/// [`RemoteSystemConfigBuilder::version_skew_policy`]: crate::remote::system::builder::RemoteSystemConfigBuilder::version_skew_policy
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct VersionSkewPolicy {
+    pub max_crate_version_skew: CrateVersionSkew,
+    pub warn_on_protocol_skew: bool,
+    pub warn_on_feature_skew: bool,
+    pub warn_on_unknown_version: bool,
+}
+
+impl Default for VersionSkewPolicy {
+    fn default() -> Self {
+        Self {
+            max_crate_version_skew: CrateVersionSkew::Patch,
+            warn_on_protocol_skew: true,
+            warn_on_feature_skew: true,
+            warn_on_unknown_version: true,
+        }
+    }
+}
+
+/// A difference between the local node's version and another node's version
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum VersionSkew {
+    /// The node didn't advertise its version
+    Unknown,
+
+    CrateVersion {
+        local: String,
+        remote: String,
+    },
+
+    Protocol {
+        local: ProtocolVersions,
+        remote: ProtocolVersions,
+    },
+
+    /// `missing` are the features enabled locally but not on the node,
+    /// `additional` are the features enabled on the node but not locally
+    Features {
+        missing: Vec<String>,
+        additional: Vec<String>,
+    },
+}
+
+impl Display for VersionSkew {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionSkew::Unknown => write!(f, "unknown version"),
+            VersionSkew::CrateVersion { local, remote } => {
+                write!(f, "coerce version (local={}, remote={})", local, remote)
+            }
+            VersionSkew::Protocol { local, remote } => {
+                write!(f, "protocol versions (local={}, remote={})", local, remote)
+            }
+            VersionSkew::Features {
+                missing,
+                additional,
+            } => write!(
+                f,
+                "features (missing=[{}], additional=[{}])",
+                missing.join(","),
+                additional.join(",")
+            ),
+        }
+    }
+}
+
+impl VersionSkewPolicy {
+    /// Returns the differences between the local and remote versions that exceed this policy
+    pub fn check(&self, local: &NodeVersion, remote: Option<&NodeVersion>) -> Vec<VersionSkew> {
+        let remote = match remote {
+            Some(remote) => remote,
+            None if self.warn_on_unknown_version => return vec![VersionSkew::Unknown],
+            None => return vec![],
+        };
+
+        let mut skew = vec![];
+        if crate_version_skew(&local.crate_version, &remote.crate_version)
+            > self.max_crate_version_skew
+        {
+            skew.push(VersionSkew::CrateVersion {
+                local: local.crate_version.clone(),
+                remote: remote.crate_version.clone(),
+            });
+        }
+
+        if self.warn_on_protocol_skew && local.protocol_versions != remote.protocol_versions {
+            skew.push(VersionSkew::Protocol {
+                local: local.protocol_versions,
+                remote: remote.protocol_versions,
+            });
+        }
+
+        if self.warn_on_feature_skew {
+            let missing: Vec<String> = local
+                .features
+                .iter()
+                .filter(|f| !remote.features.contains(f))
+                .cloned()
+                .collect();
+
+            let additional: Vec<String> = remote
+                .features
+                .iter()
+                .filter(|f| !local.features.contains(f))
+                .cloned()
+                .collect();
+
+            if !missing.is_empty() || !additional.is_empty() {
+                skew.push(VersionSkew::Features {
+                    missing,
+                    additional,
+                });
+            }
+        }
+
+        skew
+    }
+}
+
+/// Returns the most significant part that differs between two versions,
+/// versions that can't be parsed are compared as a whole
+fn crate_version_skew(a: &str, b: &str) -> CrateVersionSkew {
+    if a == b {
+        return CrateVersionSkew::None;
+    }
+
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => match (a[0].cmp(&b[0]), a[1].cmp(&b[1]), a[2].cmp(&b[2])) {
+            (Ordering::Equal, Ordering::Equal, Ordering::Equal) => CrateVersionSkew::None,
+            (Ordering::Equal, Ordering::Equal, _) => CrateVersionSkew::Patch,
+            (Ordering::Equal, _, _) => CrateVersionSkew::Minor,
+            _ => CrateVersionSkew::Major,
+        },
+        _ => CrateVersionSkew::Major,
+    }
+}
+
+fn parse_version(version: &str) -> Option<[u64; 3]> {
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|p| p.parse::<u64>().ok());
+
+    Some([
+        parts.next()??,
+        parts.next().unwrap_or(Some(0))?,
+        parts.next().unwrap_or(Some(0))?,
+    ])
+}
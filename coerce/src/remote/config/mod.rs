@@ -1,12 +1,27 @@
 use crate::actor::message::Message;
 use crate::actor::Actor;
 use crate::remote::actor::{BoxedActorHandler, BoxedMessageHandler};
+use crate::remote::cluster::blocklist::NodeBlocklist;
 use crate::remote::cluster::node::NodeAttributesRef;
+use crate::remote::cluster::version::VersionSkewPolicy;
 use crate::remote::handler::{RemoteActorMarker, RemoteActorMessageMarker};
 use crate::remote::heartbeat::HeartbeatConfig;
+use crate::remote::net::chunk::ChunkingConfig;
+use crate::remote::net::client::buffer::WriteBufferConfig;
+use crate::remote::net::client::reconnect::ReconnectPolicy;
+use crate::remote::net::client::retry::ExchangeRetryPolicy;
+use crate::remote::net::compression::CompressionConfig;
+use crate::remote::net::decode::DecodeConfig;
+use crate::remote::net::encoding::{MessageCodec, WireFormat};
+use crate::remote::net::security::handshake::{Authenticator, CredentialsProvider};
 use crate::remote::net::security::ClientAuth;
+use crate::remote::net::server::pool::HandlerExecutionConfig;
+use crate::remote::net::unhandled::UnhandledFrameHook;
+use crate::remote::stream::interest::PubSubRouting;
+use parking_lot::RwLock;
 use std::any::TypeId;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct SystemCapabilities {
@@ -20,15 +35,31 @@ pub struct RemoteSystemConfig {
     actor_types: HashMap<TypeId, String>,
     handler_types: HashMap<TypeId, String>,
     message_handlers: HashMap<String, BoxedMessageHandler>,
+    versioned_handlers: HashMap<String, HashMap<u32, BoxedMessageHandler>>,
+    message_versions: RwLock<HashMap<String, u32>>,
     actor_handlers: HashMap<String, BoxedActorHandler>,
     heartbeat_config: HeartbeatConfig,
+    reconnect_policy: ReconnectPolicy,
+    exchange_retry_policy: ExchangeRetryPolicy,
+    handler_execution: HandlerExecutionConfig,
     node_attributes: NodeAttributesRef,
     security: RemoteSystemSecurity,
+    unhandled_frame_hook: Option<Arc<dyn UnhandledFrameHook>>,
+    compression: Option<CompressionConfig>,
+    chunking: ChunkingConfig,
+    decoding: DecodeConfig,
+    wire_codec: &'static dyn MessageCodec,
+    write_buffer: WriteBufferConfig,
+    blocklist: NodeBlocklist,
+    pubsub_routing: PubSubRouting,
+    version_skew_policy: VersionSkewPolicy,
 }
 
 #[derive(Default)]
 pub struct RemoteSystemSecurity {
     client_auth: ClientAuth,
+    authenticator: Option<Arc<dyn Authenticator>>,
+    credentials: Option<Arc<dyn CredentialsProvider>>,
 }
 
 impl RemoteSystemConfig {
@@ -38,10 +69,24 @@ impl RemoteSystemConfig {
         actor_types: HashMap<TypeId, String>,
         handler_types: HashMap<TypeId, String>,
         message_handlers: HashMap<String, BoxedMessageHandler>,
+        versioned_handlers: HashMap<String, HashMap<u32, BoxedMessageHandler>>,
+        message_versions: HashMap<String, u32>,
         actor_handlers: HashMap<String, BoxedActorHandler>,
         heartbeat_config: HeartbeatConfig,
+        reconnect_policy: ReconnectPolicy,
+        exchange_retry_policy: ExchangeRetryPolicy,
+        handler_execution: HandlerExecutionConfig,
         node_attributes: NodeAttributesRef,
         security: RemoteSystemSecurity,
+        unhandled_frame_hook: Option<Arc<dyn UnhandledFrameHook>>,
+        compression: Option<CompressionConfig>,
+        chunking: ChunkingConfig,
+        decoding: DecodeConfig,
+        wire_codec: &'static dyn MessageCodec,
+        write_buffer: WriteBufferConfig,
+        blocklist: NodeBlocklist,
+        pubsub_routing: PubSubRouting,
+        version_skew_policy: VersionSkewPolicy,
     ) -> RemoteSystemConfig {
         RemoteSystemConfig {
             node_tag,
@@ -49,10 +94,24 @@ impl RemoteSystemConfig {
             actor_types,
             handler_types,
             message_handlers,
+            versioned_handlers,
+            message_versions: RwLock::new(message_versions),
             actor_handlers,
             heartbeat_config,
+            reconnect_policy,
+            exchange_retry_policy,
+            handler_execution,
             node_attributes,
             security,
+            unhandled_frame_hook,
+            compression,
+            chunking,
+            decoding,
+            wire_codec,
+            write_buffer,
+            blocklist,
+            pubsub_routing,
+            version_skew_policy,
         }
     }
 
@@ -82,6 +141,45 @@ impl RemoteSystemConfig {
             .map(|handler| handler.new_boxed())
     }
 
+    /// The handler registered for `version` of the message handler, falling back to the
+    /// message's unversioned handler if there isn't one (or `version` is 0)
+    pub fn versioned_message_handler(
+        &self,
+        key: &str,
+        version: u32,
+    ) -> Option<BoxedMessageHandler> {
+        if version != 0 {
+            let handler = self
+                .versioned_handlers
+                .get(key)
+                .and_then(|handlers| handlers.get(&version));
+
+            if let Some(handler) = handler {
+                return Some(handler.new_boxed());
+            }
+        }
+
+        self.message_handler(key)
+    }
+
+    /// The version of the handler messages sent with the handler identifier `key` request,
+    /// 0 if unversioned
+    pub fn message_version(&self, key: &str) -> u32 {
+        self.message_versions.read().get(key).copied().unwrap_or(0)
+    }
+
+    pub fn set_message_version(&self, key: &str, version: Option<u32>) {
+        let mut message_versions = self.message_versions.write();
+        match version {
+            Some(version) if version != 0 => {
+                message_versions.insert(key.to_string(), version);
+            }
+            _ => {
+                message_versions.remove(key);
+            }
+        }
+    }
+
     pub fn actor_handler(&self, key: &str) -> Option<BoxedActorHandler> {
         self.actor_handlers
             .get(key)
@@ -92,6 +190,18 @@ impl RemoteSystemConfig {
         &self.heartbeat_config
     }
 
+    pub fn reconnect_policy(&self) -> &ReconnectPolicy {
+        &self.reconnect_policy
+    }
+
+    pub fn exchange_retry_policy(&self) -> &ExchangeRetryPolicy {
+        &self.exchange_retry_policy
+    }
+
+    pub fn handler_execution(&self) -> &HandlerExecutionConfig {
+        &self.handler_execution
+    }
+
     pub fn get_capabilities(&self) -> SystemCapabilities {
         let mut actors: Vec<String> = self.actor_types.values().map(|a| a.clone()).collect();
         actors.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
@@ -109,14 +219,83 @@ impl RemoteSystemConfig {
     pub fn security(&self) -> &RemoteSystemSecurity {
         &self.security
     }
+
+    /// Observes frames and messages this node doesn't understand, if configured
+    pub fn unhandled_frame_hook(&self) -> Option<&dyn UnhandledFrameHook> {
+        self.unhandled_frame_hook.as_deref()
+    }
+
+    /// The compression offered to, and accepted from, other nodes, if configured
+    pub fn compression(&self) -> Option<&CompressionConfig> {
+        self.compression.as_ref()
+    }
+
+    pub fn chunking(&self) -> &ChunkingConfig {
+        &self.chunking
+    }
+
+    pub fn decoding(&self) -> &DecodeConfig {
+        &self.decoding
+    }
+
+    /// The format events written to other nodes are encoded in
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire_codec.format()
+    }
+
+    /// The codec events written to other nodes are encoded with,
+    /// see [`encoding`](crate::remote::net::encoding)
+    pub fn wire_codec(&self) -> &'static dyn MessageCodec {
+        self.wire_codec
+    }
+
+    pub fn write_buffer(&self) -> &WriteBufferConfig {
+        &self.write_buffer
+    }
+
+    pub fn blocklist(&self) -> &NodeBlocklist {
+        &self.blocklist
+    }
+
+    pub fn pubsub_routing(&self) -> PubSubRouting {
+        self.pubsub_routing
+    }
+
+    pub fn version_skew_policy(&self) -> &VersionSkewPolicy {
+        &self.version_skew_policy
+    }
 }
 
 impl RemoteSystemSecurity {
     pub fn new(client_auth: ClientAuth) -> Self {
-        Self { client_auth }
+        Self {
+            client_auth,
+            authenticator: None,
+            credentials: None,
+        }
+    }
+
+    pub fn with_authenticator(mut self, authenticator: Option<Arc<dyn Authenticator>>) -> Self {
+        self.authenticator = authenticator;
+        self
+    }
+
+    pub fn with_credentials(mut self, credentials: Option<Arc<dyn CredentialsProvider>>) -> Self {
+        self.credentials = credentials;
+        self
     }
 
     pub fn client_authentication(&self) -> &ClientAuth {
         &self.client_auth
     }
+
+    /// Authenticates the handshake of nodes connecting to this node, if configured
+    pub fn authenticator(&self) -> Option<&dyn Authenticator> {
+        self.authenticator.as_deref()
+    }
+
+    /// Provides the credentials this node sends when connecting to other nodes, if configured
+    pub fn credentials(&self) -> Option<&dyn CredentialsProvider> {
+        self.credentials.as_deref()
+    }
 }
@@ -14,6 +14,7 @@ use std::marker::PhantomData;
 use std::time::Duration;
 
 use tokio::sync::oneshot::Sender;
+use uuid::Uuid;
 
 #[async_trait]
 pub trait ActorHandler: 'static + Any + Sync + Send {
@@ -183,8 +184,10 @@ where
     }
 }
 
+/// Cached actor refs, keyed by the ID of the owning actor system as well as the actor's ID, so
+/// actor systems running in the same process don't resolve each other's actors
 struct ActorRefCache {
-    inner: parking_lot::Mutex<HashMap<ActorId, BoxedActorRef>>,
+    inner: parking_lot::Mutex<HashMap<(Uuid, ActorId), BoxedActorRef>>,
 }
 
 impl ActorRefCache {
@@ -194,12 +197,17 @@ impl ActorRefCache {
         }
     }
 
-    pub fn add(&self, actor_id: &ActorId, actor_ref: BoxedActorRef) {
-        self.inner.lock().insert(actor_id.clone(), actor_ref);
+    pub fn add(&self, system_id: Uuid, actor_id: &ActorId, actor_ref: BoxedActorRef) {
+        self.inner
+            .lock()
+            .insert((system_id, actor_id.clone()), actor_ref);
     }
 
-    pub fn get(&self, actor_id: &ActorId) -> Option<BoxedActorRef> {
-        self.inner.lock().get(actor_id).cloned()
+    pub fn get(&self, system_id: Uuid, actor_id: &ActorId) -> Option<BoxedActorRef> {
+        self.inner
+            .lock()
+            .get(&(system_id, actor_id.clone()))
+            .cloned()
     }
 
     pub fn len(&self) -> usize {
@@ -221,7 +229,7 @@ async fn get_actor_ref<A: Actor>(
     system: &ActorSystem,
     actor_id: &ActorId,
 ) -> Option<LocalActorRef<A>> {
-    if let Some(boxed_ref) = ACTOR_REF_CACHE.get(actor_id) {
+    if let Some(boxed_ref) = ACTOR_REF_CACHE.get(*system.system_id(), actor_id) {
         let actor_ref = boxed_ref.as_actor();
         if let Some(actor_ref) = actor_ref {
             if actor_ref.is_valid() {
@@ -231,7 +239,11 @@ async fn get_actor_ref<A: Actor>(
     }
 
     if let Some(actor_ref) = system.get_tracked_actor::<A>(actor_id.clone()).await {
-        ACTOR_REF_CACHE.add(actor_id, BoxedActorRef::from(actor_ref.clone()));
+        ACTOR_REF_CACHE.add(
+            *system.system_id(),
+            actor_id,
+            BoxedActorRef::from(actor_ref.clone()),
+        );
 
         Some(actor_ref)
     } else {
@@ -256,9 +268,8 @@ where
             let envelope = M::from_envelope(Envelope::Remote(buffer.to_vec()));
             match envelope {
                 Ok(m) => {
-                    let result = actor.send(m).await;
-                    if let Ok(result) = result {
-                        match M::write_remote_result(result) {
+                    match actor.send(m).await {
+                        Ok(result) => match M::write_remote_result(result) {
                             Ok(buffer) => {
                                 let send_res = res.send(Ok(buffer));
                                 if let Err(_) = send_res {
@@ -270,6 +281,15 @@ where
                                 error!("failed to encode message result");
                                 let _ = res.send(Err(ActorRefErr::Serialisation(e)));
                             }
+                        },
+
+                        // the actor stopped after it was located, but before it handled the message
+                        Err(ActorRefErr::InvalidRef) => {
+                            let _ = res.send(Err(ActorRefErr::ActorUnavailable));
+                        }
+
+                        Err(e) => {
+                            let _ = res.send(Err(e));
                         }
                     }
                 }
@@ -290,7 +310,9 @@ where
                     &actor_id, attempt
                 );
 
-                let _ = res.send(Err(ActorRefErr::NotFound(actor_id)));
+                // the actor may have existed when the sender located it, the sender
+                // invalidates its cached location of the actor upon receiving this
+                let _ = res.send(Err(ActorRefErr::ActorUnavailable));
                 return;
             }
 
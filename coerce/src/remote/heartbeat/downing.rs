@@ -0,0 +1,363 @@
+//! Split brain resolution
+//!
+//! When the network between nodes fails, the cluster can be split into partitions which can't
+//! reach each other. Without intervention, each partition carries on as if the other nodes had
+//! failed, meaning singletons and shards could be running on both sides of the partition at once.
+//!
+//! The [`SplitBrainResolver`] runs on the leader of each partition (the oldest reachable node),
+//! and once the set of reachable and unreachable members has been stable for the configured
+//! `stable_after` period, it asks a [`DowningProvider`] which side of the partition survives:
+//!
+//! - [`KeepMajority`] keeps the side with the most nodes.
+//! - [`KeepOldest`] keeps the side with the oldest node.
+//! - [`StaticQuorum`] keeps the side with at least a fixed number of nodes.
+//!
+//! Surviving nodes quarantine the unreachable nodes, so they're excluded from leadership, sharding
+//! and singleton placement. Nodes on the losing side down themselves by running a
+//! [coordinated shutdown](crate::actor::system::ActorSystem::coordinated_shutdown), which stops
+//! their sharded entities and remote clients. Since every partition runs the same provider against
+//! the same view of the cluster, the partitions agree on which of them survives.
+//!
+//! The `stable_after` period should be long enough for the failure detector to detect every
+//! unreachable node, otherwise a partition may be resolved based on a partial view.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .configure(|c| {
+//!         c.heartbeat(HeartbeatConfig {
+//!             split_brain_resolver: Some(
+//!                 SplitBrainResolverConfig::new(KeepMajority)
+//!                     .stable_after(Duration::from_secs(20)),
+//!             ),
+//!             ..Default::default()
+//!         })
+//!     })
+//!     .build()
+//!     .await;
+//! ```
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use crate::actor::system::shutdown::ShutdownReason;
+use crate::actor::{ActorRef, IntoActorId};
+use crate::remote::actor_ref::RemoteActorRef;
+use crate::remote::cluster::node::RemoteNodeState;
+use crate::remote::heartbeat::{Heartbeat, HEARTBEAT_ACTOR_ID};
+use crate::remote::system::builder::RemoteSystemConfigBuilder;
+use crate::remote::system::{NodeId, RemoteActorSystem};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const DEFAULT_STABLE_AFTER: Duration = Duration::from_secs(20);
+
+/// A partition of the cluster, as seen by the leader of the reachable side
+#[derive(Debug, Clone)]
+pub struct Partition {
+    /// The members reachable from the leader, including the leader itself, oldest first
+    pub reachable: Vec<RemoteNodeState>,
+
+    /// The members the leader's failure detector considers unreachable, oldest first
+    pub unreachable: Vec<RemoteNodeState>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DowningDecision {
+    /// The reachable side survives, the unreachable nodes are downed
+    DownUnreachable,
+
+    /// The unreachable side survives, the reachable nodes (including the leader) are downed
+    DownReachable,
+
+    /// Neither side can safely continue, every node is downed
+    DownAll,
+}
+
+/// Decides which side of a partition survives
+pub trait DowningProvider: 'static + Send + Sync {
+    fn decide(&self, partition: &Partition) -> DowningDecision;
+}
+
+/// Keeps the side of the partition with the most nodes. If both sides have the same number of
+/// nodes, the side containing the node with the lowest ID is kept.
+pub struct KeepMajority;
+
+/// Keeps the side of the partition containing the oldest node
+pub struct KeepOldest;
+
+/// Keeps the side of the partition with at least `quorum_size` nodes, which should be more than
+/// half of the expected cluster size. If neither side has enough nodes, every node is downed.
+pub struct StaticQuorum {
+    pub quorum_size: usize,
+}
+
+impl StaticQuorum {
+    pub fn new(quorum_size: usize) -> Self {
+        Self { quorum_size }
+    }
+}
+
+impl DowningProvider for KeepMajority {
+    fn decide(&self, partition: &Partition) -> DowningDecision {
+        let lowest_id = |nodes: &[RemoteNodeState]| nodes.iter().map(|n| n.id).min();
+
+        match partition.reachable.len().cmp(&partition.unreachable.len()) {
+            std::cmp::Ordering::Greater => DowningDecision::DownUnreachable,
+            std::cmp::Ordering::Less => DowningDecision::DownReachable,
+            std::cmp::Ordering::Equal => {
+                if lowest_id(&partition.reachable) < lowest_id(&partition.unreachable) {
+                    DowningDecision::DownUnreachable
+                } else {
+                    DowningDecision::DownReachable
+                }
+            }
+        }
+    }
+}
+
+impl DowningProvider for KeepOldest {
+    fn decide(&self, partition: &Partition) -> DowningDecision {
+        let oldest = partition
+            .reachable
+            .iter()
+            .chain(partition.unreachable.iter())
+            .min_by(|a, b| a.cmp_age(b));
+
+        match oldest {
+            Some(oldest) if partition.unreachable.iter().any(|n| n.id == oldest.id) => {
+                DowningDecision::DownReachable
+            }
+            _ => DowningDecision::DownUnreachable,
+        }
+    }
+}
+
+impl DowningProvider for StaticQuorum {
+    fn decide(&self, partition: &Partition) -> DowningDecision {
+        let reachable_quorum = partition.reachable.len() >= self.quorum_size;
+        let unreachable_quorum = partition.unreachable.len() >= self.quorum_size;
+
+        match (reachable_quorum, unreachable_quorum) {
+            (true, false) => DowningDecision::DownUnreachable,
+            (false, true) => DowningDecision::DownReachable,
+
+            // both sides having a quorum means the cluster is larger than the quorum size
+            // was configured for, neither side can safely assume the other will stop
+            (true, true) | (false, false) => DowningDecision::DownAll,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SplitBrainResolverConfig {
+    pub provider: Arc<dyn DowningProvider>,
+    pub stable_after: Duration,
+}
+
+impl SplitBrainResolverConfig {
+    pub fn new<P: DowningProvider>(provider: P) -> Self {
+        Self {
+            provider: Arc::new(provider),
+            stable_after: DEFAULT_STABLE_AFTER,
+        }
+    }
+
+    /// How long the reachability of every member must remain unchanged before the partition
+    /// is resolved, defaults to 20 seconds
+    pub fn stable_after(mut self, stable_after: Duration) -> Self {
+        self.stable_after = stable_after;
+        self
+    }
+}
+
+/// Tracks the reachability of the cluster's members, resolving the partition once the leader's
+/// view of the cluster has been stable for the configured period
+pub struct SplitBrainResolver {
+    config: SplitBrainResolverConfig,
+    view: Option<(BTreeSet<NodeId>, BTreeSet<NodeId>)>,
+    stable_since: Instant,
+}
+
+impl SplitBrainResolver {
+    pub fn new(config: SplitBrainResolverConfig) -> Self {
+        Self {
+            config,
+            view: None,
+            stable_since: Instant::now(),
+        }
+    }
+
+    /// Updates the view of the cluster (members sorted oldest first) and, if the local node is
+    /// the leader, returns the partition and the provider's decision once the view has been stable
+    /// for long enough and contains unreachable members
+    pub(crate) fn resolve(
+        &mut self,
+        node_id: NodeId,
+        is_leader: bool,
+        nodes: &[RemoteNodeState],
+        now: Instant,
+    ) -> Option<(Partition, DowningDecision)> {
+        let (reachable, unreachable): (Vec<RemoteNodeState>, Vec<RemoteNodeState>) = nodes
+            .iter()
+            .filter(|n| n.status.is_member())
+            .cloned()
+            .partition(|n| n.id == node_id || n.health.reachable);
+
+        let view = (
+            reachable.iter().map(|n| n.id).collect(),
+            unreachable.iter().map(|n| n.id).collect(),
+        );
+
+        if self.view.as_ref() != Some(&view) {
+            self.view = Some(view);
+            self.stable_since = now;
+            return None;
+        }
+
+        if !is_leader
+            || unreachable.is_empty()
+            || now.duration_since(self.stable_since) < self.config.stable_after
+        {
+            return None;
+        }
+
+        // the view is reset, so the partition isn't resolved again on the next tick
+        self.view = None;
+
+        let partition = Partition {
+            reachable,
+            unreachable,
+        };
+
+        let decision = self.config.provider.decide(&partition);
+        warn!(
+            leader_id = node_id,
+            reachable = format!(
+                "{:?}",
+                partition.reachable.iter().map(|n| n.id).collect::<Vec<_>>()
+            ),
+            unreachable = format!(
+                "{:?}",
+                partition
+                    .unreachable
+                    .iter()
+                    .map(|n| n.id)
+                    .collect::<Vec<_>>()
+            ),
+            decision = format!("{:?}", decision),
+            "resolving cluster partition"
+        );
+
+        Some((partition, decision))
+    }
+}
+
+/// Downs the provided nodes, sent by the leader to every reachable member of its partition.
+/// If the receiving node is one of the downed nodes, it shuts itself down, the remaining
+/// nodes are quarantined.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DownNodes {
+    pub node_ids: Vec<NodeId>,
+}
+
+impl Message for DownNodes {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&self).map_err(|_e| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&b).map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn read_remote_result(_: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        Ok(())
+    }
+
+    fn write_remote_result(_res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait]
+impl Handler<DownNodes> for Heartbeat {
+    async fn handle(&mut self, message: DownNodes, _ctx: &mut ActorContext) {
+        if let Some(system) = &self.system {
+            let system = system.clone();
+            tokio::spawn(async move { down_nodes(message.node_ids, &system).await });
+        }
+    }
+}
+
+/// Downs the losing side of the partition, notifying the reachable members of the partition
+/// first, since the leader itself may be one of the downed nodes
+pub(crate) async fn down_partition(
+    partition: Partition,
+    decision: DowningDecision,
+    system: RemoteActorSystem,
+) {
+    let reachable: Vec<NodeId> = partition.reachable.iter().map(|n| n.id).collect();
+    let downed = match decision {
+        DowningDecision::DownUnreachable => partition.unreachable,
+        DowningDecision::DownReachable => partition.reachable,
+        DowningDecision::DownAll => {
+            let mut nodes = partition.reachable;
+            nodes.extend(partition.unreachable);
+            nodes
+        }
+    };
+
+    let message = DownNodes {
+        node_ids: downed.into_iter().map(|n| n.id).collect(),
+    };
+
+    for node_id in reachable {
+        if node_id == system.node_id() {
+            continue;
+        }
+
+        let heartbeat: ActorRef<Heartbeat> =
+            RemoteActorRef::new(HEARTBEAT_ACTOR_ID.into_actor_id(), node_id, system.clone()).into();
+
+        if let Err(e) = heartbeat.send(message.clone()).await {
+            warn!(
+                node_id,
+                error = format!("{}", e),
+                "failed to notify node of partition resolution"
+            );
+        }
+    }
+
+    down_nodes(message.node_ids, &system).await;
+}
+
+async fn down_nodes(node_ids: Vec<NodeId>, system: &RemoteActorSystem) {
+    let mut down_self = false;
+    for node_id in node_ids {
+        if node_id == system.node_id() {
+            down_self = true;
+        } else if system.quarantine_node(node_id).await {
+            warn!(node_id, "node downed");
+        }
+    }
+
+    if down_self {
+        warn!(
+            node_id = system.node_id(),
+            "node downed by split brain resolver, shutting down"
+        );
+
+        system
+            .actor_system()
+            .coordinated_shutdown(ShutdownReason::Downed)
+            .await;
+    }
+}
+
+/// Registers the handler used by the [`SplitBrainResolver`] to down the nodes of its partition
+pub fn downing(builder: &mut RemoteSystemConfigBuilder) -> &mut RemoteSystemConfigBuilder {
+    builder.with_handler::<Heartbeat, DownNodes>("Heartbeat.DownNodes")
+}
@@ -0,0 +1,172 @@
+//! Phi accrual failure detector
+//!
+//! Rather than deciding a node is up or down after a fixed timeout, the [`PhiAccrualFailureDetector`]
+//! keeps a history of the intervals between heartbeats (successful pings) received from a node,
+//! and computes `phi`, a suspicion level that the node has failed. The longer it's been since
+//! the last heartbeat, relative to the intervals seen previously, the higher `phi` gets.
+//!
+//! A `phi` of 1 means there's roughly a 10% chance the detector is wrong in suspecting the node
+//! has failed, 2 means roughly 1%, 3 roughly 0.1% and so on. Once `phi` reaches the configured
+//! threshold, the node is considered unreachable.
+//!
+//! Based on [The φ Accrual Failure Detector](https://doi.org/10.1109/RELDIS.2004.1353004),
+//! Hayashibara et al.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct FailureDetectorConfig {
+    /// The `phi` at which a node is considered unreachable
+    pub threshold: f64,
+
+    /// Maximum number of heartbeat intervals kept in the history
+    pub max_sample_size: usize,
+
+    /// Minimum standard deviation used when calculating `phi`, too low of a standard deviation
+    /// can result in nodes being considered unreachable after only small deviations
+    pub min_std_deviation: Duration,
+
+    /// Length of time a heartbeat can be late before `phi` starts to grow, in addition to the
+    /// mean heartbeat interval
+    pub acceptable_heartbeat_pause: Duration,
+
+    /// The estimated heartbeat interval used to seed the history, before any heartbeats
+    /// have been received
+    pub first_heartbeat_estimate: Duration,
+}
+
+pub struct PhiAccrualFailureDetector {
+    config: FailureDetectorConfig,
+    intervals: HeartbeatHistory,
+    last_heartbeat: Option<Instant>,
+}
+
+struct HeartbeatHistory {
+    max_sample_size: usize,
+    intervals: VecDeque<f64>,
+    interval_sum: f64,
+    squared_interval_sum: f64,
+}
+
+impl PhiAccrualFailureDetector {
+    pub fn new(config: FailureDetectorConfig) -> Self {
+        let mut intervals = HeartbeatHistory::new(config.max_sample_size);
+
+        // seed the history with two intervals, around the first heartbeat estimate,
+        // so there's a (fairly wide) distribution to compare the first heartbeats against
+        let estimate = millis(config.first_heartbeat_estimate);
+        let std_deviation = estimate / 4.0;
+        intervals.push(estimate - std_deviation);
+        intervals.push(estimate + std_deviation);
+
+        Self {
+            config,
+            intervals,
+            last_heartbeat: None,
+        }
+    }
+
+    /// Records a heartbeat received at `now`
+    pub fn heartbeat(&mut self, now: Instant) {
+        if let Some(last_heartbeat) = self.last_heartbeat {
+            // intervals where the node was unreachable aren't recorded, otherwise one long
+            // pause would make the detector much slower to detect the next one
+            if self.is_available(now) {
+                self.intervals
+                    .push(millis(now.saturating_duration_since(last_heartbeat)));
+            }
+        }
+
+        self.last_heartbeat = Some(now);
+    }
+
+    /// Returns the current suspicion level that the node has failed,
+    /// 0.0 if no heartbeats have been received yet
+    pub fn phi(&self, now: Instant) -> f64 {
+        let last_heartbeat = match self.last_heartbeat {
+            Some(last_heartbeat) => last_heartbeat,
+            None => return 0.0,
+        };
+
+        let time_since_heartbeat = millis(now.saturating_duration_since(last_heartbeat));
+        let mean = self.intervals.mean() + millis(self.config.acceptable_heartbeat_pause);
+        let std_deviation = self
+            .intervals
+            .std_deviation()
+            .max(millis(self.config.min_std_deviation));
+
+        phi(time_since_heartbeat, mean, std_deviation)
+    }
+
+    /// Returns whether `phi` is below the configured threshold
+    pub fn is_available(&self, now: Instant) -> bool {
+        self.phi(now) < self.config.threshold
+    }
+
+    pub fn last_heartbeat(&self) -> Option<Instant> {
+        self.last_heartbeat
+    }
+}
+
+/// Approximates `-log10(1 - cdf(time_since_heartbeat))` of a normal distribution,
+/// using the logistic approximation of the cumulative distribution function
+fn phi(time_since_heartbeat: f64, mean: f64, std_deviation: f64) -> f64 {
+    let y = (time_since_heartbeat - mean) / std_deviation;
+    let e = (-y * (1.5976 + 0.070566 * y * y)).exp();
+    if time_since_heartbeat > mean {
+        -(e / (1.0 + e)).log10()
+    } else {
+        -(1.0 - 1.0 / (1.0 + e)).log10()
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl HeartbeatHistory {
+    fn new(max_sample_size: usize) -> Self {
+        Self {
+            max_sample_size: max_sample_size.max(1),
+            intervals: VecDeque::new(),
+            interval_sum: 0.0,
+            squared_interval_sum: 0.0,
+        }
+    }
+
+    fn push(&mut self, interval: f64) {
+        if self.intervals.len() >= self.max_sample_size {
+            if let Some(oldest) = self.intervals.pop_front() {
+                self.interval_sum -= oldest;
+                self.squared_interval_sum -= oldest * oldest;
+            }
+        }
+
+        self.intervals.push_back(interval);
+        self.interval_sum += interval;
+        self.squared_interval_sum += interval * interval;
+    }
+
+    fn mean(&self) -> f64 {
+        self.interval_sum / self.intervals.len() as f64
+    }
+
+    fn std_deviation(&self) -> f64 {
+        let mean = self.mean();
+        let variance = self.squared_interval_sum / self.intervals.len() as f64 - mean * mean;
+        variance.max(0.0).sqrt()
+    }
+}
+
+impl Default for FailureDetectorConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 8.0,
+            max_sample_size: 200,
+            min_std_deviation: Duration::from_millis(100),
+            acceptable_heartbeat_pause: Duration::from_secs(3),
+            first_heartbeat_estimate: Duration::from_secs(1),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod downing;
+pub mod failure_detector;
 pub mod health;
 
 use crate::actor::context::ActorContext;
@@ -7,20 +9,27 @@ use crate::actor::system::ActorSystem;
 use crate::actor::{Actor, BoxedActorRef, IntoActor, LocalActorRef};
 use crate::actor::{ActorId, CoreActorRef};
 use crate::remote::actor::message::{NodeTerminated, SetRemote};
-use crate::remote::cluster::node::{NodeStatus, RemoteNodeRef, RemoteNodeState};
+use crate::remote::cluster::node::{NodeHealth, NodeStatus, RemoteNodeRef, RemoteNodeState};
 use crate::remote::net::proto::network::PongEvent;
 use crate::remote::stream::pubsub::PubSub;
-use crate::remote::stream::system::ClusterEvent::{LeaderChanged, MemberUp};
+use crate::remote::stream::system::ClusterEvent::{
+    LeaderChanged, MemberUp, NodeReachable, NodeUnreachable,
+};
 use crate::remote::stream::system::{ClusterMemberUp, SystemEvent, SystemTopic};
 use crate::remote::system::{NodeId, RemoteActorSystem};
 use chrono::{DateTime, Utc};
 
-use std::cmp::Ordering;
 use std::collections::{HashMap, VecDeque};
 
 use std::ops::Add;
 use std::sync::Arc;
 
+use crate::remote::heartbeat::downing::{
+    down_partition, SplitBrainResolver, SplitBrainResolverConfig,
+};
+use crate::remote::heartbeat::failure_detector::{
+    FailureDetectorConfig, PhiAccrualFailureDetector,
+};
 use crate::remote::heartbeat::health::{
     GetHealth, RegisterHealthCheck, RemoveHealthCheck, SystemHealth,
 };
@@ -28,14 +37,18 @@ use std::time::{Duration, Instant};
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::Sender;
 
+pub(crate) const HEARTBEAT_ACTOR_ID: &str = "heartbeat";
+
 pub struct Heartbeat {
     system: Option<RemoteActorSystem>,
     cluster_member_up: bool,
     heartbeat_timer: Option<Timer>,
     last_heartbeat: Option<DateTime<Utc>>,
     node_pings: HashMap<NodeId, NodePing>,
+    failure_detectors: HashMap<NodeId, PhiAccrualFailureDetector>,
     on_next_leader_changed: VecDeque<Sender<NodeId>>,
     health_check_actors: Vec<BoxedActorRef>,
+    split_brain_resolver: Option<SplitBrainResolver>,
     config: HeartbeatConfig,
 }
 
@@ -46,6 +59,11 @@ pub struct HeartbeatConfig {
     pub unhealthy_node_heartbeat_timeout: Duration,
     pub terminated_node_heartbeat_timeout: Duration,
     pub minimum_cluster_size: Option<usize>,
+    pub failure_detector: FailureDetectorConfig,
+
+    /// Resolves network partitions by downing one side of the partition,
+    /// see [`downing`]. Disabled by default.
+    pub split_brain_resolver: Option<SplitBrainResolverConfig>,
 }
 
 impl Heartbeat {
@@ -56,11 +74,16 @@ impl Heartbeat {
             heartbeat_timer: None,
             last_heartbeat: None,
             node_pings: HashMap::new(),
+            failure_detectors: HashMap::new(),
             on_next_leader_changed: VecDeque::new(),
             health_check_actors: Vec::new(),
+            split_brain_resolver: config
+                .split_brain_resolver
+                .clone()
+                .map(SplitBrainResolver::new),
             config,
         }
-        .into_actor(Some("heartbeat"), sys)
+        .into_actor(Some(HEARTBEAT_ACTOR_ID), sys)
         .await
         .expect("heartbeat actor")
     }
@@ -128,12 +151,14 @@ impl Default for HeartbeatConfig {
             unhealthy_node_heartbeat_timeout: Duration::from_millis(1500),
             terminated_node_heartbeat_timeout: Duration::from_secs(30),
             minimum_cluster_size: None,
+            failure_detector: FailureDetectorConfig::default(),
+            split_brain_resolver: None,
         }
     }
 }
 
 #[derive(Clone)]
-struct HeartbeatTick;
+pub(crate) struct HeartbeatTick;
 
 impl Message for HeartbeatTick {
     type Result = ();
@@ -176,6 +201,14 @@ impl Message for OnLeaderChanged {
 #[async_trait]
 impl Handler<NodePing> for Heartbeat {
     async fn handle(&mut self, message: NodePing, _ctx: &mut ActorContext) {
+        if message.1.is_ok() {
+            let failure_detector_config = &self.config.failure_detector;
+            self.failure_detectors
+                .entry(message.0)
+                .or_insert_with(|| PhiAccrualFailureDetector::new(failure_detector_config.clone()))
+                .heartbeat(Instant::now());
+        }
+
         let _ = self.node_pings.insert(message.0, message);
     }
 }
@@ -185,10 +218,12 @@ impl Handler<NodeTerminated> for Heartbeat {
     async fn handle(&mut self, message: NodeTerminated, ctx: &mut ActorContext) {
         let node_id = message.0;
         if let Some(system) = &self.system {
+            let _ = system.remote_watcher().notify(NodeTerminated(node_id));
             let _ = system.registry().send(message).await;
         }
 
         self.node_pings.remove(&node_id);
+        self.failure_detectors.remove(&node_id);
         self.handle(HeartbeatTick, ctx).await;
     }
 }
@@ -224,24 +259,45 @@ impl Handler<HeartbeatTick> for Heartbeat {
 
         let mut new_leader_id = None;
         let mut updates = vec![];
+        let mut reachability_changes = vec![];
 
         for node in nodes {
             if node.id == current_node {
                 let mut node = node;
                 node.status = NodeStatus::Healthy;
                 node.last_heartbeat = Some(Utc::now());
+                node.maintenance = system.is_in_maintenance();
                 updates.push(node);
 
                 continue;
             }
 
             let node_id = node.id;
-            updates.push(update_node(
+            let previous_status = node.status;
+            let previous_health = node.health;
+            let mut node = update_node(
                 current_node,
                 node,
                 self.node_pings.get(&node_id).map(|r| r.1.clone()),
                 &self.config,
-            ));
+            );
+
+            if node.status == NodeStatus::Terminated && previous_status != NodeStatus::Terminated {
+                let _ = system.remote_watcher().notify(NodeTerminated(node_id));
+            }
+
+            if node.status.is_member() {
+                node.health = self.node_health(node_id, now);
+                if node.health.reachable != previous_health.reachable {
+                    reachability_changes.push(node.clone());
+                }
+            } else {
+                // the node will have to rejoin the cluster, which starts with a new history
+                self.failure_detectors.remove(&node_id);
+                node.health = NodeHealth::default();
+            }
+
+            updates.push(node);
         }
 
         trace!(
@@ -251,18 +307,18 @@ impl Handler<HeartbeatTick> for Heartbeat {
             now.elapsed().as_millis()
         );
 
-        updates.sort_by(|a, b| {
-            match Ord::cmp(
-                &a.node_started_at.unwrap_or(DateTime::<Utc>::MIN_UTC),
-                &b.node_started_at.unwrap_or(DateTime::<Utc>::MIN_UTC),
-            ) {
-                Ordering::Equal => Ord::cmp(&a.id, &b.id),
-                ordering => ordering,
-            }
-        });
+        updates.sort_by(RemoteNodeState::cmp_age);
 
         if self.last_heartbeat.is_some() {
-            let oldest_healthy_node = updates.iter().filter(|n| n.status.is_healthy()).next();
+            // nodes in maintenance only lead the cluster when there's no other healthy node,
+            // so entering maintenance hands leadership (and leader-hosted singletons) over
+            let is_leader_candidate =
+                |n: &&RemoteNodeState| n.status.is_healthy() && n.health.reachable;
+            let oldest_healthy_node = updates
+                .iter()
+                .filter(is_leader_candidate)
+                .find(|n| !n.maintenance)
+                .or_else(|| updates.iter().find(is_leader_candidate));
 
             match oldest_healthy_node {
                 None => {}
@@ -283,6 +339,19 @@ impl Handler<HeartbeatTick> for Heartbeat {
 
         system.update_nodes(updates.clone()).await;
 
+        if !reachability_changes.is_empty() {
+            self.publish_reachability_changes(reachability_changes);
+        }
+
+        if let Some(resolver) = &mut self.split_brain_resolver {
+            let is_leader = new_leader_id.or_else(|| system.current_leader()) == Some(current_node);
+            if let Some((partition, decision)) =
+                resolver.resolve(current_node, is_leader, &updates, now)
+            {
+                tokio::spawn(down_partition(partition, decision, system.clone()));
+            }
+        }
+
         if let Some(new_leader_id) = new_leader_id {
             if !self.cluster_member_up {
                 let min_cluster_size_reached = match self.config.minimum_cluster_size {
@@ -306,6 +375,52 @@ impl Handler<HeartbeatTick> for Heartbeat {
 }
 
 impl Heartbeat {
+    fn node_health(&self, node_id: NodeId, now: Instant) -> NodeHealth {
+        match self.failure_detectors.get(&node_id) {
+            Some(failure_detector) => {
+                let phi = failure_detector.phi(now);
+                NodeHealth {
+                    reachable: phi < self.config.failure_detector.threshold,
+                    phi,
+                }
+            }
+            None => NodeHealth::default(),
+        }
+    }
+
+    fn publish_reachability_changes(&self, nodes: Vec<RemoteNodeState>) {
+        let events: Vec<SystemEvent> = nodes
+            .into_iter()
+            .map(|node| {
+                let reachable = node.health.reachable;
+                if reachable {
+                    info!(node_id = node.id, addr = &node.addr, "node reachable");
+                } else {
+                    warn!(
+                        node_id = node.id,
+                        addr = &node.addr,
+                        phi = node.health.phi,
+                        "node unreachable"
+                    );
+                }
+
+                let node = Arc::new(node.into());
+                SystemEvent::Cluster(if reachable {
+                    NodeReachable(node)
+                } else {
+                    NodeUnreachable(node)
+                })
+            })
+            .collect();
+
+        let sys = self.system.as_ref().unwrap().clone();
+        tokio::spawn(async move {
+            for event in events {
+                let _ = PubSub::publish_locally(SystemTopic, event, &sys).await;
+            }
+        });
+    }
+
     fn update_leader(&mut self, node_id: NodeId) {
         let system = self.system.as_ref().unwrap();
         system.update_leader(node_id);
@@ -350,9 +465,10 @@ fn update_node(
     match &ping {
         None => {}
         Some(ping) => match ping {
-            PingResult::Ok(_pong, ping_latency, pong_received_at) => {
+            PingResult::Ok(pong, ping_latency, pong_received_at) => {
                 node.last_heartbeat = Some(*pong_received_at);
                 node.ping_latency = Some(*ping_latency);
+                node.maintenance = pong.maintenance;
             }
             PingResult::Timeout | PingResult::Disconnected | PingResult::Err => {
                 node.ping_latency = None;
@@ -380,6 +496,11 @@ fn node_status(
     ping: Option<PingResult>,
     config: &HeartbeatConfig,
 ) -> NodeStatus {
+    // quarantined nodes are no longer pinged, they stay quarantined until they rejoin the cluster
+    if previous_status == NodeStatus::Quarantined {
+        return NodeStatus::Quarantined;
+    }
+
     match ping {
         Some(PingResult::Ok(_, ping_latency, pong_received_at)) => {
             let time_since_ping = (Utc::now() - pong_received_at).to_std().unwrap();
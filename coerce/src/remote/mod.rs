@@ -85,12 +85,18 @@
 //! }
 //! ```
 //!
+//! ## Local Affinity
+//! Messages sent via a [`RemoteActorRef`] that points at an actor on the local node are dispatched
+//! to the actor directly, without being serialised, and are counted by the
+//! `coerce_network_local_bypass_total` metric.
+//!
 //! [`NodeId`]: system::NodeId
 //! [`RemoteActorSystemBuilder`]: system::builder::RemoteActorSystemBuilder
 //! [`RemoteActorSystem`]: system::RemoteActorSystem
 
 pub mod actor;
 pub mod actor_ref;
+pub mod admin;
 pub mod cluster;
 pub mod config;
 pub mod handler;
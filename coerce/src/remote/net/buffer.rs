@@ -0,0 +1,132 @@
+//! Pooled encode buffers for frames written to remote nodes
+//!
+//! Rather than allocating a new buffer for every message sent to a remote node, messages are
+//! encoded into a [`BytesMut`] taken from a [`BufferPool`]. The encoded frame is split off and
+//! frozen, and once the frame has been written and dropped, the next message encoded with the
+//! same buffer reclaims the original allocation.
+
+use crate::remote::net::metrics::NetworkMetrics;
+use crate::remote::net::StreamData;
+use bytes::{Bytes, BytesMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+const DEFAULT_MAX_POOLED_BUFFERS: usize = 256;
+const DEFAULT_BUFFER_CAPACITY: usize = 4 * 1024;
+const MAX_POOLED_BUFFER_CAPACITY: usize = 1024 * 1024;
+
+lazy_static! {
+    static ref GLOBAL_BUFFER_POOL: BufferPool = BufferPool::new(DEFAULT_MAX_POOLED_BUFFERS);
+}
+
+pub struct BufferPool {
+    buffers: Mutex<Vec<BytesMut>>,
+    max_pooled_buffers: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct BufferPoolStats {
+    /// Number of times a buffer was taken from the pool
+    pub hits: u64,
+
+    /// Number of times the pool was empty, and a new buffer was allocated
+    pub misses: u64,
+
+    /// Number of buffers currently held by the pool
+    pub pooled_buffers: usize,
+}
+
+impl BufferPoolStats {
+    /// The proportion of encodes that reused a pooled buffer, between 0.0 and 1.0
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+impl BufferPool {
+    /// Creates a pool that holds on to at most `max_pooled_buffers` buffers
+    pub fn new(max_pooled_buffers: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::with_capacity(max_pooled_buffers)),
+            max_pooled_buffers,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The pool used when writing frames to remote nodes
+    pub fn global() -> &'static BufferPool {
+        &GLOBAL_BUFFER_POOL
+    }
+
+    /// Encodes the message into a pooled buffer, returning the encoded frame,
+    /// or `None` if the message could not be encoded
+    pub fn encode<M: StreamData>(&self, message: &M) -> Option<Bytes> {
+        self.encode_with(|buffer| message.write_to_buffer(buffer))
+    }
+
+    /// Encodes into a pooled buffer using the provided function, which returns whether
+    /// the message could be encoded
+    pub fn encode_with(&self, encode: impl FnOnce(&mut BytesMut) -> bool) -> Option<Bytes> {
+        let mut buffer = self.acquire();
+        let encoded = encode(&mut buffer);
+
+        // buffers that have grown beyond the limit are dropped, rather than holding on to
+        // the memory for the lifetime of the pool
+        let oversized = buffer.capacity() > MAX_POOLED_BUFFER_CAPACITY;
+        let frame = buffer.split().freeze();
+
+        if !oversized {
+            self.release(buffer);
+        }
+
+        if encoded {
+            Some(frame)
+        } else {
+            None
+        }
+    }
+
+    pub fn stats(&self) -> BufferPoolStats {
+        BufferPoolStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            pooled_buffers: self.buffers.lock().unwrap().len(),
+        }
+    }
+
+    fn acquire(&self) -> BytesMut {
+        let buffer = self.buffers.lock().unwrap().pop();
+        match buffer {
+            Some(mut buffer) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                NetworkMetrics::incr_buffer_pool_hits();
+
+                // reclaims the original allocation if every frame previously encoded
+                // with this buffer has since been dropped
+                buffer.reserve(DEFAULT_BUFFER_CAPACITY);
+                buffer
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                NetworkMetrics::incr_buffer_pool_misses();
+
+                BytesMut::with_capacity(DEFAULT_BUFFER_CAPACITY)
+            }
+        }
+    }
+
+    fn release(&self, buffer: BytesMut) {
+        let mut buffers = self.buffers.lock().unwrap();
+        if buffers.len() < self.max_pooled_buffers {
+            buffers.push(buffer);
+        }
+    }
+}
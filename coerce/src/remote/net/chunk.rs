@@ -0,0 +1,187 @@
+//! Chunking of frames too large to be written as a single frame
+//!
+//! Frames larger than the configured [`ChunkingConfig::threshold`] are split into chunks before
+//! they're written, and reassembled by the receiving node before they're decoded, so large
+//! messages are delivered as a single message. Chunks are written one at a time, waiting for each
+//! to be flushed, and clients write any queued replies and control messages between each chunk,
+//! so one large message doesn't hold up the rest of the connection's traffic.
+//!
+//! Each chunk starts with a byte flagging it as a chunk, in place of the usual event type,
+//! followed by the id of the message it belongs to, and whether it's the message's last chunk.
+//! Chunks of different messages can be interleaved, each message is reassembled separately.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .configure(|c| c.chunking(ChunkingConfig::default().threshold(256 * 1024)))
+//!     .build()
+//!     .await;
+//! ```
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+
+/// Set in the first byte of a chunk, which can't clash with an event type
+const CHUNK_FRAME: u8 = 0x40;
+
+/// The flag, the id of the message the chunk belongs to, and whether it's the last chunk
+const CHUNK_HEADER_LENGTH: usize = 6;
+
+/// Chunks must fit within the maximum length of a single frame
+const MAX_CHUNK_LENGTH: usize = 8 * 1024 * 1024 - CHUNK_HEADER_LENGTH;
+
+const DEFAULT_THRESHOLD: usize = 1024 * 1024;
+
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+const DEFAULT_MAX_PARTIAL_MESSAGES: usize = 64;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkingConfig {
+    threshold: usize,
+    max_message_size: usize,
+    max_partial_messages: usize,
+}
+
+impl ChunkingConfig {
+    /// Frames larger than `threshold` bytes are split into chunks of at most `threshold` bytes,
+    /// defaults to 1MB, and can't exceed the maximum frame length of 8MB
+    pub fn threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold.clamp(1, MAX_CHUNK_LENGTH);
+        self
+    }
+
+    /// The largest message that will be reassembled from chunks, defaults to 256MB. Connections
+    /// that send a larger message are closed.
+    pub fn max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// The most messages that can be partially received at once, defaults to 64. Connections
+    /// that start more messages without finishing them are closed.
+    pub fn max_partial_messages(mut self, max_partial_messages: usize) -> Self {
+        self.max_partial_messages = max_partial_messages.max(1);
+        self
+    }
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_THRESHOLD,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_partial_messages: DEFAULT_MAX_PARTIAL_MESSAGES,
+        }
+    }
+}
+
+/// Splits the frames written to a connection into chunks
+#[derive(Debug, Clone)]
+pub(crate) struct Chunker {
+    threshold: usize,
+    next_id: u32,
+}
+
+impl Chunker {
+    pub fn new(config: &ChunkingConfig) -> Self {
+        Self {
+            threshold: config.threshold,
+            next_id: 0,
+        }
+    }
+
+    /// Splits the frame into chunks, or returns `None` if the frame is small enough
+    /// to be written as it is
+    pub fn split(&mut self, frame: &Bytes) -> Option<Vec<Bytes>> {
+        if frame.len() <= self.threshold {
+            return None;
+        }
+
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+
+        let count = frame.len().div_ceil(self.threshold);
+        let chunks = frame
+            .chunks(self.threshold)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut buf = BytesMut::with_capacity(CHUNK_HEADER_LENGTH + chunk.len());
+                buf.put_u8(CHUNK_FRAME);
+                buf.put_u32(id);
+                buf.put_u8((i + 1 == count) as u8);
+                buf.extend_from_slice(chunk);
+                buf.freeze()
+            })
+            .collect();
+
+        Some(chunks)
+    }
+}
+
+/// Reassembles the chunks read from a connection
+#[derive(Debug, Clone)]
+pub(crate) struct ChunkAssembler {
+    max_message_size: usize,
+    max_partial_messages: usize,
+    messages: HashMap<u32, BytesMut>,
+}
+
+impl ChunkAssembler {
+    pub fn new(config: &ChunkingConfig) -> Self {
+        Self {
+            max_message_size: config.max_message_size,
+            max_partial_messages: config.max_partial_messages,
+            messages: HashMap::new(),
+        }
+    }
+
+    /// Returns the frame if it isn't a chunk, or the reassembled message if the frame
+    /// is the message's last chunk
+    pub fn receive(&mut self, mut frame: BytesMut) -> Result<Option<BytesMut>, Error> {
+        if frame.first() != Some(&CHUNK_FRAME) {
+            return Ok(Some(frame));
+        }
+
+        if frame.len() < CHUNK_HEADER_LENGTH {
+            return Err(Error::new(ErrorKind::InvalidData, "invalid chunk header"));
+        }
+
+        frame.advance(1);
+        let id = frame.get_u32();
+        let last = frame.get_u8() != 0;
+
+        if !self.messages.contains_key(&id) && self.messages.len() >= self.max_partial_messages {
+            self.messages.clear();
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "too many partially received chunked messages (max {})",
+                    self.max_partial_messages
+                ),
+            ));
+        }
+
+        let message = self.messages.entry(id).or_default();
+        if message.len() + frame.len() > self.max_message_size {
+            self.messages.remove(&id);
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "chunked message exceeds the maximum message size ({} bytes)",
+                    self.max_message_size
+                ),
+            ));
+        }
+
+        message.unsplit(frame);
+
+        Ok(if last {
+            self.messages.remove(&id)
+        } else {
+            None
+        })
+    }
+}
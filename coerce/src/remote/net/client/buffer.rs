@@ -0,0 +1,89 @@
+//! Limits on the writes a [`RemoteClient`] buffers whilst it isn't connected
+//!
+//! By default, a client buffers every write made whilst its node is unreachable, until the node is
+//! re-connected to or quarantined. A bounded buffer can be configured, with a
+//! [`WriteBufferOverflow`] policy deciding what happens to writes made whilst the buffer is full.
+//! Dropped writes are logged, and counted by the `coerce_network_write_buffer_dropped` metric.
+//!
+//! Only messages sent to remote actors are subject to the limits, system writes (see
+//! [`WriteLane::System`]) are always buffered, since they're needed to re-establish the cluster.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .configure(|c| {
+//!         c.write_buffer(
+//!             WriteBufferConfig::default()
+//!                 .max_messages(10_000)
+//!                 .max_bytes(64 * 1024 * 1024)
+//!                 .overflow(WriteBufferOverflow::DropOldest),
+//!         )
+//!     })
+//!     .build()
+//!     .await;
+//! ```
+//!
+//! [`RemoteClient`]: crate::remote::net::client::RemoteClient
+//! [`WriteLane::System`]: crate::remote::net::client::WriteLane::System
+
+/// What happens to a write made whilst a client's write buffer is full
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum WriteBufferOverflow {
+    /// The write being made is discarded
+    DropNewest,
+
+    /// The oldest buffered writes are discarded, making room for the write being made
+    DropOldest,
+
+    /// The write is rejected, if the message was sent as a request, the sender receives
+    /// [`ActorRefErr::WriteBufferFull`]
+    ///
+    /// [`ActorRefErr::WriteBufferFull`]: crate::actor::ActorRefErr::WriteBufferFull
+    #[default]
+    FailSender,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct WriteBufferConfig {
+    /// The maximum number of buffered messages, or `None` if the number of messages is unbounded
+    pub max_messages: Option<usize>,
+    /// The maximum total size of all buffered writes, or `None` if the size is unbounded
+    pub max_bytes: Option<usize>,
+    pub overflow: WriteBufferOverflow,
+}
+
+impl WriteBufferConfig {
+    pub fn unbounded() -> Self {
+        Self::default()
+    }
+
+    pub fn max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = Some(max_messages);
+        self
+    }
+
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn overflow(mut self, overflow: WriteBufferOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.max_messages.is_some() || self.max_bytes.is_some()
+    }
+
+    /// Whether a write of `len` bytes would exceed the limits of a buffer that already holds
+    /// `messages` messages, totalling `bytes` bytes
+    pub fn is_full(&self, messages: usize, bytes: usize, len: usize) -> bool {
+        self.max_messages
+            .is_some_and(|max_messages| messages >= max_messages)
+            || self
+                .max_bytes
+                .is_some_and(|max_bytes| bytes + len > max_bytes)
+    }
+}
@@ -0,0 +1,83 @@
+//! Callbacks waiting on the client's exchanges with the node
+//!
+//! Callers of [`Identify`] and [`BeginHandshake`] register a callback that is resolved once the
+//! node has identified itself, or has acknowledged the handshake. Callbacks are resolved in the
+//! order they were registered, and every callback is resolved, with a typed error if the exchange
+//! can't complete: when connecting fails, when the connection is lost and when the client is
+//! stopped, so callers never wait on a callback that was forgotten.
+//!
+//! [`Identify`]: crate::remote::net::client::Identify
+//! [`BeginHandshake`]: crate::remote::net::client::BeginHandshake
+
+use crate::remote::cluster::node::NodeIdentity;
+use crate::remote::net::client::{HandshakeErr, IdentifyErr};
+
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::oneshot::Sender;
+use uuid::Uuid;
+
+pub(crate) type IdentifyCallback = Sender<Result<NodeIdentity, IdentifyErr>>;
+
+pub(crate) type HandshakeCallback = Sender<Result<(), HandshakeErr>>;
+
+/// Callbacks waiting for the client to identify the remote node
+pub(crate) type IdentifiedCallbacks = PendingCallbacks<NodeIdentity, IdentifyErr>;
+
+/// Callbacks waiting for the remote node to acknowledge the client's handshake
+pub(crate) type HandshakeCallbacks = PendingCallbacks<(), HandshakeErr>;
+
+/// A FIFO table of callbacks waiting on the same exchange
+pub(crate) struct PendingCallbacks<T, E>(Arc<Mutex<VecDeque<PendingCallback<T, E>>>>);
+
+struct PendingCallback<T, E> {
+    request_id: Option<Uuid>,
+    callback: Sender<Result<T, E>>,
+}
+
+impl<T: Clone, E: Clone> PendingCallbacks<T, E> {
+    pub fn push(&self, callback: Sender<Result<T, E>>) {
+        self.0.lock().push_back(PendingCallback {
+            request_id: None,
+            callback,
+        });
+    }
+
+    pub fn push_request(&self, request_id: Uuid, callback: Sender<Result<T, E>>) {
+        self.0.lock().push_back(PendingCallback {
+            request_id: Some(request_id),
+            callback,
+        });
+    }
+
+    /// Resolves every pending callback with `result`, in the order they were registered,
+    /// returning the number of callbacks resolved
+    pub fn resolve(&self, result: Result<T, E>) -> usize {
+        let callbacks: Vec<_> = self.0.lock().drain(..).collect();
+        let count = callbacks.len();
+
+        for pending in callbacks {
+            if let Some(request_id) = &pending.request_id {
+                trace!("resolving callback (request_id={})", request_id);
+            }
+
+            // the caller may have given up waiting
+            let _ = pending.callback.send(result.clone());
+        }
+
+        count
+    }
+}
+
+impl<T, E> Clone for PendingCallbacks<T, E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T, E> Default for PendingCallbacks<T, E> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(VecDeque::new())))
+    }
+}
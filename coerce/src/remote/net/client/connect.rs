@@ -8,22 +8,25 @@ use crate::remote::cluster::node::RemoteNode;
 use crate::remote::net::client::ping::PingTick;
 use crate::remote::net::client::receive::{ClientMessageReceiver, HandshakeAcknowledge};
 use crate::remote::net::client::send::write_bytes;
+use crate::remote::net::client::watchdog::{watch_receive_task, DisconnectCause};
 use crate::remote::net::client::{
-    BeginHandshake, ClientState, ConnectionState, HandshakeAckCallback, HandshakeStatus,
+    BeginHandshake, ClientState, ConnectionState, HandshakeErr, HandshakeStatus, IdentifyErr,
     RemoteClient,
 };
 use crate::remote::net::message::SessionEvent;
 use crate::remote::net::proto::network::{self as proto, IdentifyEvent};
-use crate::remote::net::{receive_loop, StreamData};
+use crate::remote::net::stream::NodeStream;
+use crate::remote::net::version::ProtocolVersions;
+use crate::remote::net::{receive_loop, NetworkCodec, StreamData};
 
 use bytes::Bytes;
 use chrono::Utc;
+use futures::SinkExt;
 use protobuf::EnumOrUnknown;
-use std::time::Duration;
 use tokio::net::TcpStream;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::Sender;
-use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
+use tokio_util::codec::{FramedRead, FramedWrite};
 use valuable::Valuable;
 
 pub struct Connect;
@@ -35,7 +38,7 @@ impl RemoteClient {
         &mut self,
         _connect: Connect,
         ctx: &mut ActorContext,
-    ) -> Option<ConnectionState> {
+    ) -> Result<ConnectionState, IdentifyErr> {
         let log_ctx = ctx.log();
         let stream = TcpStream::connect(&self.addr).await;
         if stream.is_err() {
@@ -44,19 +47,40 @@ impl RemoteClient {
                 ctx = log_ctx.as_value(),
                 "connection to {} failed, error: {}", &self.addr, error
             );
-            return None;
+            return Err(IdentifyErr::Unreachable);
         }
 
         let stream = stream.unwrap();
+        let remote = ctx.system().remote_owned();
+
+        #[cfg(feature = "tls")]
+        let stream: NodeStream = match remote.client_tls() {
+            Some(tls) => match tls.connect(&self.addr, stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    error!(
+                        ctx = log_ctx.as_value(),
+                        "connection to {} failed, error: {}", &self.addr, e
+                    );
+                    return Err(IdentifyErr::Unreachable);
+                }
+            },
+            None => stream.into(),
+        };
+
+        #[cfg(not(feature = "tls"))]
+        let stream: NodeStream = stream.into();
+
         let (read, writer) = tokio::io::split(stream);
 
-        let codec = LengthDelimitedCodec::new();
+        let mut codec = NetworkCodec::new();
+        codec.set_chunking(remote.config().chunking());
+
         let reader = FramedRead::new(read, codec.clone());
         let mut write = FramedWrite::new(writer, codec.clone());
 
         let (identity_tx, identity_rx) = oneshot::channel();
-
-        let remote = ctx.system().remote_owned();
+        let protocol = ProtocolVersions::current();
 
         let identify = SessionEvent::Identify(IdentifyEvent {
             source_node_id: remote.node_id(),
@@ -66,17 +90,25 @@ impl RemoteClient {
                 .security()
                 .client_authentication()
                 .generate_token(),
+            min_protocol_version: protocol.min,
+            max_protocol_version: protocol.max,
+            compression: remote
+                .config()
+                .compression()
+                .map(|config| config.algorithms().map(|a| a.id()).collect())
+                .unwrap_or_default(),
             ..Default::default()
         });
 
-        match write_bytes(Bytes::from(identify.write_to_bytes().unwrap()), &mut write).await {
+        let identify = Bytes::from(identify.write_to_bytes().unwrap());
+        match write_bytes(identify.clone(), &mut write).await {
             Ok(_) => {}
             Err(e) => {
                 error!(
                     ctx = log_ctx.as_value(),
                     "failed to write identify message to begin authentication, error={}", e
                 );
-                return None;
+                return Err(IdentifyErr::Unreachable);
             }
         };
 
@@ -88,24 +120,104 @@ impl RemoteClient {
             ClientMessageReceiver::new(self.actor_ref(ctx), identity_tx, self.addr.clone()),
         ));
 
+        self.connection_count += 1;
+        let connection_id = self.connection_count;
+        let receive_task = watch_receive_task(receive_task, connection_id, self.actor_ref(ctx));
+
         self.ping_timer = Some(Timer::start_immediately(
             self.actor_ref(ctx),
             ctx.system().remote().config().heartbeat_config().interval,
             PingTick,
         ));
 
-        let identity = match identity_rx.await {
-            Ok(identity) => identity,
-            Err(_) => {
-                warn!(
+        // the identify is written again if the node doesn't reply in time,
+        // in case it was lost whilst the connection stayed up
+        let retry_policy = remote.config().exchange_retry_policy();
+        let mut identity_rx = identity_rx;
+        let mut attempt = 1;
+        let identity = loop {
+            match tokio::time::timeout(retry_policy.identify_timeout, &mut identity_rx).await {
+                Ok(Ok(identity)) => break identity,
+                Ok(Err(_)) => {
+                    warn!(
+                        ctx = log_ctx.as_value(),
+                        "no identity received (addr={})", &self.addr
+                    );
+
+                    receive_task.abort();
+                    return Err(IdentifyErr::Unreachable);
+                }
+                Err(_) if attempt < retry_policy.identify_attempts => {
+                    warn!(
+                        ctx = log_ctx.as_value(),
+                        "identity not received in time (addr={}, attempt={}), re-sending identify",
+                        &self.addr,
+                        attempt
+                    );
+
+                    attempt += 1;
+                    if write_bytes(identify.clone(), &mut write).await.is_err() {
+                        receive_task.abort();
+                        return Err(IdentifyErr::Unreachable);
+                    }
+                }
+                Err(_) => {
+                    warn!(
+                        ctx = log_ctx.as_value(),
+                        "identity not received (addr={}, attempts={}), resetting connection",
+                        &self.addr,
+                        attempt
+                    );
+
+                    let _ = write.close().await;
+                    receive_task.abort();
+
+                    self.last_disconnect = Some(DisconnectCause::IdentifyTimedOut);
+                    return Err(IdentifyErr::Unreachable);
+                }
+            }
+        };
+
+        match protocol.negotiate(&identity.protocol_versions) {
+            Ok(protocol_version) => {
+                debug!(
                     ctx = log_ctx.as_value(),
-                    "no identity received (addr={})", &self.addr
+                    "negotiated protocol_version={} (addr={})", protocol_version, &self.addr
                 );
-                return None;
             }
-        };
+            Err(e) => {
+                error!(
+                    ctx = log_ctx.as_value(),
+                    "connection to {} refused, error: {}", &self.addr, e
+                );
 
-        Some(ConnectionState {
+                receive_task.abort();
+                return Err(IdentifyErr::IncompatibleProtocol(e));
+            }
+        }
+
+        // the node chooses from the algorithms offered, but only if it supports them too
+        let compressor = identity.compression.and_then(|compression| {
+            let config = remote.config().compression()?;
+            config
+                .algorithms()
+                .any(|a| a == compression)
+                .then(|| config.compressor(compression))
+        });
+
+        if let Some(compressor) = compressor {
+            debug!(
+                ctx = log_ctx.as_value(),
+                "negotiated compression={:?} (addr={})",
+                compressor.algorithm(),
+                &self.addr
+            );
+
+            write.encoder_mut().set_compressor(compressor);
+        }
+
+        Ok(ConnectionState {
+            id: connection_id,
             identity,
             handshake: HandshakeStatus::None,
             write,
@@ -116,8 +228,6 @@ impl RemoteClient {
 
 pub struct Disconnected;
 
-const RECONNECT_DELAY: Duration = Duration::from_millis(5000);
-
 #[async_trait]
 impl Handler<Connect> for RemoteClient {
     async fn handle(&mut self, message: Connect, ctx: &mut ActorContext) {
@@ -131,40 +241,41 @@ impl Handler<Connect> for RemoteClient {
             }
         }
 
-        if let Some(connection_state) = self.connect(message, ctx).await {
-            let client_actor_ref = self.actor_ref(ctx);
-            let _ = ctx
-                .system()
-                .remote()
-                .client_registry()
-                .send(ClientConnected {
-                    addr: connection_state.identity.node.addr.clone(),
-                    remote_node_id: connection_state.identity.node.id,
-                    client_actor_ref,
-                })
-                .await;
+        match self.connect(message, ctx).await {
+            Ok(connection_state) => {
+                let client_actor_ref = self.actor_ref(ctx);
+                let _ = ctx
+                    .system()
+                    .remote()
+                    .client_registry()
+                    .send(ClientConnected {
+                        addr: connection_state.identity.node.addr.clone(),
+                        remote_node_id: connection_state.identity.node.id,
+                        client_actor_ref,
+                        priority_lane: self.priority_lane.clone(),
+                    })
+                    .await;
+
+                self.on_identified_callbacks
+                    .resolve(Ok(connection_state.identity.clone()));
+
+                self.node_id = Some(connection_state.identity.node.id);
+                self.state = Some(ClientState::Connected(connection_state));
+
+                debug!("RemoteClient connected to node (addr={})", &self.addr);
+
+                let _ = ctx.system().remote().node_discovery().notify(Discover {
+                    seed: Seed::Addr(self.addr.clone()),
+                    on_discovery_complete: None,
+                });
 
-            while let Some(callback) = self.on_identified_callbacks.pop() {
-                let _ = callback.send(Some(connection_state.identity.clone()));
+                self.flush_buffered_writes().await;
             }
+            Err(e) => {
+                self.on_identified_callbacks.resolve(Err(e));
 
-            self.node_id = Some(connection_state.identity.node.id);
-            self.state = Some(ClientState::Connected(connection_state));
-
-            debug!("RemoteClient connected to node (addr={})", &self.addr);
-
-            let _ = ctx.system().remote().node_discovery().notify(Discover {
-                seed: Seed::Addr(self.addr.clone()),
-                on_discovery_complete: None,
-            });
-
-            self.flush_buffered_writes().await;
-        } else {
-            while let Some(callback) = self.on_identified_callbacks.pop() {
-                let _ = callback.send(None);
+                self.handle(Disconnected, ctx).await;
             }
-
-            self.handle(Disconnected, ctx).await;
         }
     }
 }
@@ -175,29 +286,33 @@ impl Handler<BeginHandshake> for RemoteClient {
         let mut connection = match &mut self.state {
             Some(ClientState::Connected(connection)) => connection,
             _ => {
-                // let actor_ref = self.actor_ref(ctx);
-                // let _ = actor_ref.notify(Connect);
-                // let _ = actor_ref.notify(message);
+                let _ = message
+                    .on_handshake_complete
+                    .send(Err(HandshakeErr::NotConnected));
                 return;
             }
         };
 
         match &connection.handshake {
             &HandshakeStatus::Acknowledged(_) => {
-                let _ = message.on_handshake_complete.send(());
+                let _ = message.on_handshake_complete.send(Ok(()));
             }
 
-            &HandshakeStatus::Pending => {
-                self.on_handshake_ack_callbacks.push(HandshakeAckCallback {
-                    request_id: message.request_id,
-                    callback: message.on_handshake_complete,
-                });
+            // retries re-send the handshake, in case it or its acknowledgement was lost
+            &HandshakeStatus::Pending if message.attempt <= 1 => {
+                self.on_handshake_ack_callbacks
+                    .push_request(message.request_id, message.on_handshake_complete);
             }
 
             _ => {
                 let remote = ctx.system().remote_owned();
                 let node_id = remote.node_id();
                 let node_tag = remote.node_tag().to_string();
+                let token = remote
+                    .config()
+                    .security()
+                    .credentials()
+                    .map_or_else(Vec::new, |credentials| credentials.credentials(&self.addr));
 
                 connection.handshake = HandshakeStatus::Pending;
 
@@ -206,12 +321,12 @@ impl Handler<BeginHandshake> for RemoteClient {
                     &self.addr, &message.request_id
                 );
 
-                write_bytes(
+                let write_result = write_bytes(
                     Bytes::from(
                         SessionEvent::Handshake(proto::SessionHandshake {
                             node_id,
                             node_tag,
-                            token: vec![],
+                            token,
                             client_type: EnumOrUnknown::new(self.client_type.into()),
                             trace_id: message.request_id.to_string(),
                             nodes: message
@@ -226,18 +341,28 @@ impl Handler<BeginHandshake> for RemoteClient {
                     ),
                     &mut connection.write,
                 )
-                .await
-                .expect("write handshake");
+                .await;
+
+                self.on_handshake_ack_callbacks
+                    .push_request(message.request_id, message.on_handshake_complete);
+
+                if write_result.is_err() {
+                    warn!(
+                        "failed to write client handshake (client_addr={}, request_id={})",
+                        &self.addr, &message.request_id
+                    );
+
+                    // the handshake is written again by the next attempt
+                    connection.handshake = HandshakeStatus::None;
+                    self.on_handshake_ack_callbacks
+                        .resolve(Err(HandshakeErr::WriteFailed));
+                    return;
+                }
 
                 debug!(
                     "written client handshake (client_addr={}, request_id={})",
                     &self.addr, &message.request_id
                 );
-
-                self.on_handshake_ack_callbacks.push(HandshakeAckCallback {
-                    request_id: message.request_id,
-                    callback: message.on_handshake_complete,
-                });
             }
         }
     }
@@ -255,14 +380,11 @@ impl Handler<HandshakeAcknowledge> for RemoteClient {
             Some(ClientState::Connected(state)) => {
                 state.handshake = HandshakeStatus::Acknowledged(message);
 
-                while let Some(callback) = self.on_handshake_ack_callbacks.pop() {
-                    debug!(
-                        "ack callback executed (request_id={}, client_addr={})",
-                        callback.request_id, &self.addr
-                    );
-
-                    let _ = callback.callback.send(());
-                }
+                let resolved = self.on_handshake_ack_callbacks.resolve(Ok(()));
+                debug!(
+                    "ack callbacks executed (count={}, client_addr={})",
+                    resolved, &self.addr
+                );
             }
             _ => {
                 warn!("received HandshakeAck but the client connection state is invalid, addr={}, node_id={}", &self.addr, message.node_id);
@@ -274,62 +396,74 @@ impl Handler<HandshakeAcknowledge> for RemoteClient {
 #[async_trait]
 impl Handler<Disconnected> for RemoteClient {
     async fn handle(&mut self, _msg: Disconnected, ctx: &mut ActorContext) {
-        if let Some(true) = self.state.as_ref().map(|n| n.is_connected()) {
-            warn!(
-                addr = &self.addr,
-                reconnect_delay_millis = RECONNECT_DELAY.as_millis(),
-                "RemoteClient disconnected from node",
-            );
-        } else {
-            warn!(
-                addr = &self.addr,
-                reconnect_delay_millis = RECONNECT_DELAY.as_millis(),
-                "RemoteClient failed to re-connect to node",
-            );
+        let remote = ctx.system().remote_owned();
+        let reconnect_policy = remote.config().reconnect_policy();
+        let was_connected = self.state.as_ref().map_or(false, |n| n.is_connected());
+
+        if was_connected {
+            self.last_disconnect = Some(DisconnectCause::StreamClosed);
         }
 
-        let mut reconnect = true;
+        // handshakes are only acknowledged over the connection they were written to
+        self.on_handshake_ack_callbacks
+            .resolve(Err(HandshakeErr::Disconnected));
+
         let state = match self.state.take().unwrap() {
             ClientState::Idle {
                 connection_attempts,
-            } => {
-                let connection_attempts = connection_attempts + 1;
-                if connection_attempts > 10 {
-                    reconnect = false;
-
-                    warn!(
-                        addr = &self.addr,
-                        connection_attempts = connection_attempts,
-                        "client terminating, no longer attempting to re-connect"
-                    );
-
-                    ClientState::Terminated
-                } else {
-                    ClientState::Idle {
-                        connection_attempts,
-                    }
-                }
-            }
+            } => ClientState::Idle {
+                connection_attempts: connection_attempts + 1,
+            },
 
-            ClientState::Connected(mut state) => ClientState::Idle {
+            ClientState::Connected(_) => ClientState::Idle {
                 connection_attempts: 1,
             },
 
             state => state,
         };
 
-        self.state = Some(state);
+        let connection_attempts = state.connection_attempts();
+        let reconnect_delay = match connection_attempts {
+            Some(attempt) if reconnect_policy.should_reconnect(attempt) => {
+                Some(reconnect_policy.delay(attempt))
+            }
+            _ => None,
+        };
+
+        self.state = Some(match reconnect_delay {
+            Some(_) => state,
+            None => ClientState::Terminated,
+        });
+
+        if let Some(reconnect_delay) = reconnect_delay {
+            if was_connected {
+                warn!(
+                    addr = &self.addr,
+                    reconnect_delay_millis = reconnect_delay.as_millis(),
+                    "RemoteClient disconnected from node",
+                );
+            } else {
+                warn!(
+                    addr = &self.addr,
+                    connection_attempts = connection_attempts,
+                    reconnect_delay_millis = reconnect_delay.as_millis(),
+                    "RemoteClient failed to re-connect to node",
+                );
+            }
 
-        if reconnect {
             let self_ref = self.actor_ref(ctx);
             tokio::spawn(async move {
-                tokio::time::sleep(RECONNECT_DELAY).await;
+                tokio::time::sleep(reconnect_delay).await;
                 let _res = self_ref.send(Connect).await;
             });
         } else {
-            let _ = ctx
-                .system()
-                .remote()
+            warn!(
+                addr = &self.addr,
+                connection_attempts = connection_attempts,
+                "client terminating, no longer attempting to re-connect"
+            );
+
+            let _ = remote
                 .client_registry()
                 .send(RemoveClient {
                     addr: self.addr.clone(),
@@ -337,6 +471,10 @@ impl Handler<Disconnected> for RemoteClient {
                 })
                 .await;
 
+            if let Some(node_id) = self.node_id {
+                remote.quarantine_node(node_id).await;
+            }
+
             let _ = ctx.boxed_actor_ref().notify_stop();
         }
     }
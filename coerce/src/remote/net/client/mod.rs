@@ -1,36 +1,50 @@
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use futures::SinkExt;
+use parking_lot::Mutex;
 use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::io::WriteHalf;
-use tokio::net::TcpStream;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::{Receiver, Sender};
-use tokio::task::JoinHandle;
-use tokio_util::codec::{FramedWrite, LengthDelimitedCodec};
+use tokio::task::AbortHandle;
+use tokio_util::codec::FramedWrite;
 use uuid::Uuid;
 
 use crate::actor::context::ActorContext;
-use crate::actor::message::{Handler, Message};
+use crate::actor::message::{DeliverySemantics, Handler, Message};
 use crate::actor::scheduler::timer::Timer;
-use crate::actor::{Actor, ActorRefErr, IntoActor, LocalActorRef};
+use crate::actor::{Actor, ActorId, ActorRefErr, ActorTags, IntoActor, IntoActorId, LocalActorRef};
 
 use crate::remote::cluster::node::{NodeIdentity, RemoteNode};
+use crate::remote::net::client::callbacks::{
+    HandshakeCallback, HandshakeCallbacks, IdentifiedCallbacks, IdentifyCallback,
+};
 use crate::remote::net::client::connect::Connect;
 use crate::remote::net::client::receive::HandshakeAcknowledge;
+use crate::remote::net::client::retry::{ExchangeRetryPolicy, ResetConnection};
 use crate::remote::net::client::send::write_bytes;
+use crate::remote::net::client::watchdog::DisconnectCause;
 use crate::remote::net::message::SessionEvent;
 use crate::remote::net::proto::network as proto;
 use crate::remote::net::proto::network::PingEvent;
-use crate::remote::net::StreamData;
+use crate::remote::net::stream::NodeStream;
+use crate::remote::net::version::IncompatibleProtocol;
+use crate::remote::net::{NetworkCodec, StreamData};
 use crate::remote::system::{NodeId, RemoteActorSystem};
 
+pub mod buffer;
+pub mod callbacks;
 pub mod connect;
 pub mod ping;
 pub mod receive;
+pub mod reconnect;
+pub mod retry;
 pub mod send;
+pub mod status;
+pub mod watchdog;
 
 pub struct RemoteClient {
     addr: String,
@@ -39,15 +53,16 @@ pub struct RemoteClient {
     state: Option<ClientState>,
     stop: Option<Sender<bool>>,
     write_buffer_bytes_total: usize,
-    write_buffer: VecDeque<Vec<u8>>,
-    on_identified_callbacks: Vec<Sender<Option<NodeIdentity>>>,
-    on_handshake_ack_callbacks: Vec<HandshakeAckCallback>,
+    write_buffer: VecDeque<(Bytes, DeliverySemantics)>,
+    system_write_buffer: VecDeque<Bytes>,
+    dropped_writes: u64,
+    priority_lane: PriorityLane,
+    on_identified_callbacks: IdentifiedCallbacks,
+    on_handshake_ack_callbacks: HandshakeCallbacks,
     ping_timer: Option<Timer>,
-}
-
-struct HandshakeAckCallback {
-    request_id: Uuid,
-    callback: Sender<()>,
+    last_write: Option<DateTime<Utc>>,
+    connection_count: u64,
+    last_disconnect: Option<DisconnectCause>,
 }
 
 pub struct RemoteClientRef {
@@ -60,12 +75,53 @@ impl RemoteClient {
         system: RemoteActorSystem,
         client_type: ClientType,
     ) -> LocalActorRef<Self> {
-        let actor_id = Some(format!("remote-client-{}", &addr));
+        let actor_id = Some(Self::actor_id(&addr));
         debug!(
             "Creating RemoteClient (actor_id={})",
             actor_id.as_ref().unwrap()
         );
 
+        RemoteClient::create(
+            addr,
+            client_type,
+            PriorityLane::default(),
+            IdentifiedCallbacks::default(),
+        )
+        .into_actor(actor_id, system.actor_system())
+        .await
+        .unwrap()
+    }
+
+    /// Creates a factory that is used to (re)create the client each time it is started by its
+    /// supervisor, the [`PriorityLane`] and any pending identify callbacks are shared between
+    /// each instance of the client, so they survive the client being restarted.
+    pub(crate) fn factory(
+        addr: String,
+        client_type: ClientType,
+    ) -> impl Fn() -> RemoteClient + Send + Sync + 'static {
+        let priority_lane = PriorityLane::default();
+        let on_identified_callbacks = IdentifiedCallbacks::default();
+
+        move || {
+            RemoteClient::create(
+                addr.clone(),
+                client_type,
+                priority_lane.clone(),
+                on_identified_callbacks.clone(),
+            )
+        }
+    }
+
+    pub fn actor_id(addr: &str) -> ActorId {
+        format!("remote-client-{}", addr).into_actor_id()
+    }
+
+    fn create(
+        addr: String,
+        client_type: ClientType,
+        priority_lane: PriorityLane,
+        on_identified_callbacks: IdentifiedCallbacks,
+    ) -> RemoteClient {
         RemoteClient {
             addr,
             client_type,
@@ -75,14 +131,17 @@ impl RemoteClient {
                 connection_attempts: 0,
             }),
             write_buffer: VecDeque::new(),
+            system_write_buffer: VecDeque::new(),
+            dropped_writes: 0,
             write_buffer_bytes_total: 0,
-            on_identified_callbacks: vec![],
-            on_handshake_ack_callbacks: vec![],
+            priority_lane,
+            on_identified_callbacks,
+            on_handshake_ack_callbacks: HandshakeCallbacks::default(),
             ping_timer: None,
+            last_write: None,
+            connection_count: 0,
+            last_disconnect: None,
         }
-        .into_actor(actor_id, system.actor_system())
-        .await
-        .unwrap()
     }
 
     pub fn close(&mut self) -> bool {
@@ -95,7 +154,70 @@ impl RemoteClient {
 }
 
 pub struct Identify {
-    callback: Sender<Option<NodeIdentity>>,
+    callback: IdentifyCallback,
+}
+
+#[derive(Debug, Clone)]
+pub enum IdentifyErr {
+    /// The node could not be connected to
+    Unreachable,
+    /// The node doesn't support any of the protocol versions supported by this node
+    IncompatibleProtocol(IncompatibleProtocol),
+    /// The client was stopped before the node was identified
+    ClientStopped,
+    Actor(ActorRefErr),
+}
+
+#[derive(Debug, Clone)]
+pub enum HandshakeErr {
+    /// The client isn't connected to the node
+    NotConnected,
+    /// The handshake could not be written to the connection
+    WriteFailed,
+    /// The connection was lost before the handshake was acknowledged
+    Disconnected,
+    /// The client was stopped before the handshake was acknowledged
+    ClientStopped,
+    Actor(ActorRefErr),
+}
+
+impl Display for IdentifyErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdentifyErr::Unreachable => write!(f, "node unreachable"),
+            IdentifyErr::IncompatibleProtocol(e) => write!(f, "{}", e),
+            IdentifyErr::ClientStopped => write!(f, "client stopped"),
+            IdentifyErr::Actor(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Display for HandshakeErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandshakeErr::NotConnected => write!(f, "client not connected"),
+            HandshakeErr::WriteFailed => write!(f, "failed to write handshake"),
+            HandshakeErr::Disconnected => write!(f, "disconnected before handshake acknowledged"),
+            HandshakeErr::ClientStopped => write!(f, "client stopped"),
+            HandshakeErr::Actor(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for IdentifyErr {}
+
+impl std::error::Error for HandshakeErr {}
+
+impl From<ActorRefErr> for IdentifyErr {
+    fn from(value: ActorRefErr) -> Self {
+        IdentifyErr::Actor(value)
+    }
+}
+
+impl From<ActorRefErr> for HandshakeErr {
+    fn from(value: ActorRefErr) -> Self {
+        HandshakeErr::Actor(value)
+    }
 }
 
 impl Message for Identify {
@@ -107,7 +229,7 @@ impl Handler<Identify> for RemoteClient {
     async fn handle(&mut self, message: Identify, _ctx: &mut ActorContext) {
         match &self.state {
             Some(ClientState::Connected(state)) => {
-                let _ = message.callback.send(Some(state.identity.clone()));
+                let _ = message.callback.send(Ok(state.identity.clone()));
             }
             _ => {
                 self.on_identified_callbacks.push(message.callback);
@@ -116,35 +238,38 @@ impl Handler<Identify> for RemoteClient {
     }
 }
 
-const REMOTE_CLIENT_HANDSHAKE_TIMEOUT: Duration =
-    Duration::from_secs(3 /*TODO: pull this from config*/);
-const REMOTE_CLIENT_HANDSHAKE_MAX_ATTEMPTS: usize = 5;
-
 impl RemoteClientRef {
-    pub async fn identify(&self) -> Result<Option<NodeIdentity>, ActorRefErr> {
+    pub async fn identify(&self) -> Result<NodeIdentity, IdentifyErr> {
         const REMOTE_CLIENT_IDENTIFY_TIMEOUT: Duration =
             Duration::from_secs(1 /*TODO: pull this from config*/);
 
         let (tx, rx) = oneshot::channel();
         if let Err(e) = self.client.notify(Identify { callback: tx }) {
-            Err(e)
+            Err(e.into())
         } else {
-            await_timeout(REMOTE_CLIENT_IDENTIFY_TIMEOUT, rx).await
+            await_timeout(REMOTE_CLIENT_IDENTIFY_TIMEOUT, rx).await?
         }
     }
 
+    /// Handshakes with the node, re-sending the handshake whenever it isn't acknowledged in time,
+    /// the connection is reset once every attempt has timed out, see [`retry`](retry)
     pub async fn handshake(
         &self,
         request_id: Uuid,
         seed_nodes: Vec<RemoteNode>,
-    ) -> Result<(), ActorRefErr> {
+        policy: &ExchangeRetryPolicy,
+    ) -> Result<(), HandshakeErr> {
         let start = Instant::now();
-        for _attempt in 0..REMOTE_CLIENT_HANDSHAKE_MAX_ATTEMPTS {
-            match self.handshake_attempt(request_id, seed_nodes.clone()).await {
-                Err(ActorRefErr::Timeout { .. }) => {
+        for attempt in 1..=policy.handshake_attempts {
+            match self
+                .handshake_attempt(request_id, seed_nodes.clone(), attempt, policy)
+                .await
+            {
+                Err(HandshakeErr::Actor(ActorRefErr::Timeout { .. })) => {
                     warn!(
-                        "handshake request to node (addr={}) timed out",
+                        "handshake request to node (addr={}) timed out, attempt={}",
                         &self.client.actor_id(),
+                        attempt
                     );
                 }
 
@@ -152,26 +277,33 @@ impl RemoteClientRef {
             }
         }
 
-        Err(ActorRefErr::Timeout {
+        let _ = self.client.notify(ResetConnection {
+            cause: DisconnectCause::HandshakeTimedOut,
+        });
+
+        Err(HandshakeErr::Actor(ActorRefErr::Timeout {
             time_taken_millis: start.elapsed().as_millis() as u64,
-        })
+        }))
     }
 
     async fn handshake_attempt(
         &self,
         request_id: Uuid,
         seed_nodes: Vec<RemoteNode>,
-    ) -> Result<(), ActorRefErr> {
+        attempt: usize,
+        policy: &ExchangeRetryPolicy,
+    ) -> Result<(), HandshakeErr> {
         let (tx, rx) = oneshot::channel();
 
         if let Err(e) = self.client.notify(BeginHandshake {
             request_id,
-            seed_nodes: seed_nodes.clone(),
+            seed_nodes,
+            attempt,
             on_handshake_complete: tx,
         }) {
-            Err(e)
+            Err(e.into())
         } else {
-            await_timeout(REMOTE_CLIENT_HANDSHAKE_TIMEOUT, rx).await
+            await_timeout(policy.handshake_timeout, rx).await?
         }
     }
 }
@@ -201,6 +333,10 @@ impl Actor for RemoteClient {
         let _ = self.actor_ref(ctx).notify(Connect {});
     }
 
+    fn tags(&self, _ctx: &ActorContext) -> ActorTags {
+        (&self.status()).into()
+    }
+
     async fn stopped(&mut self, ctx: &mut ActorContext) {
         match &mut self.state {
             None => {}
@@ -238,6 +374,12 @@ impl Actor for RemoteClient {
             ping_timer.stop();
         }
 
+        self.on_identified_callbacks
+            .resolve(Err(IdentifyErr::ClientStopped));
+
+        self.on_handshake_ack_callbacks
+            .resolve(Err(HandshakeErr::ClientStopped));
+
         debug!("client actor: {} stopped", &self.addr);
     }
 }
@@ -260,11 +402,70 @@ impl ClientState {
     }
 }
 
+/// The logical lanes of a [`RemoteClient`]'s connection.
+///
+/// System traffic skips the client's mailbox via the [`PriorityLane`], and is buffered separately
+/// whilst the client isn't connected, so it's written ahead of any buffered user traffic once the
+/// client re-connects. A flood of messages sent to remote actors therefore can't delay heartbeats
+/// and other control messages.
+///
+/// Messages declaring [`DeliverySemantics::Priority`] are also written via the system lane.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WriteLane {
+    /// Replies and system control messages, see [`SessionEvent::is_priority`]
+    System,
+    /// Messages sent to remote actors
+    User,
+}
+
+impl WriteLane {
+    /// The lane that messages with the provided [`DeliverySemantics`] are written via
+    pub fn of(semantics: DeliverySemantics) -> WriteLane {
+        match semantics {
+            DeliverySemantics::Priority => WriteLane::System,
+            _ => WriteLane::User,
+        }
+    }
+
+    /// The semantics of frames written via the lane, that weren't sent with semantics of their own
+    pub fn semantics(&self) -> DeliverySemantics {
+        match self {
+            WriteLane::System => DeliverySemantics::Priority,
+            WriteLane::User => DeliverySemantics::Ordered,
+        }
+    }
+}
+
+/// Frames that are written ahead of any normal traffic queued within a [`RemoteClient`]'s mailbox.
+///
+/// Used for the [`WriteLane::System`] lane, see [`SessionEvent::is_priority`].
+#[derive(Clone, Default)]
+pub struct PriorityLane(Arc<Mutex<VecDeque<Bytes>>>);
+
+impl PriorityLane {
+    pub fn push(&self, bytes: Bytes) {
+        self.0.lock().push_back(bytes);
+    }
+
+    pub fn drain(&self) -> Vec<Bytes> {
+        self.0.lock().drain(..).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().is_empty()
+    }
+}
+
 pub struct ConnectionState {
+    id: u64,
     identity: NodeIdentity,
     handshake: HandshakeStatus,
-    write: FramedWrite<WriteHalf<TcpStream>, LengthDelimitedCodec>,
-    receive_task: JoinHandle<()>,
+    write: FramedWrite<WriteHalf<NodeStream>, NetworkCodec>,
+    receive_task: AbortHandle,
 }
 
 pub enum HandshakeStatus {
@@ -277,6 +478,7 @@ pub enum HandshakeStatus {
 pub enum RemoteClientErr {
     Encoding,
     StreamErr(tokio::io::Error),
+    WriteBufferFull,
 }
 
 impl Display for RemoteClientErr {
@@ -288,6 +490,9 @@ impl Display for RemoteClientErr {
             RemoteClientErr::StreamErr(e) => {
                 write!(f, "stream error (error={})", e)
             }
+            RemoteClientErr::WriteBufferFull => {
+                write!(f, "not connected, write buffer is full")
+            }
         }
     }
 }
@@ -301,7 +506,8 @@ pub enum ClientType {
 pub struct BeginHandshake {
     request_id: Uuid,
     seed_nodes: Vec<RemoteNode>,
-    on_handshake_complete: Sender<()>,
+    attempt: usize,
+    on_handshake_complete: HandshakeCallback,
 }
 
 impl Message for BeginHandshake {
@@ -12,7 +12,7 @@ use crate::remote::actor::RemoteResponse;
 use crate::remote::cluster::discovery::Forget;
 
 use crate::remote::heartbeat::{NodePing, PingResult};
-use crate::remote::net::client::{ClientState, RemoteClient};
+use crate::remote::net::client::{ClientState, RemoteClient, WriteLane};
 use crate::remote::net::message::SessionEvent;
 use crate::remote::net::proto::network::{PingEvent, PongEvent};
 
@@ -74,7 +74,7 @@ impl Handler<PingTick> for RemoteClient {
 
         let client_addr = self.addr.clone();
         let ping_start = Instant::now();
-        let write_res = self.write(ping_event, ctx).await;
+        let write_res = self.write(ping_event, WriteLane::System, ctx).await;
         if write_res.is_ok() {
             tokio::spawn(async move {
                 let timeout = remote.config().heartbeat_config().ping_timeout;
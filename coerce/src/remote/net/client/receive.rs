@@ -4,10 +4,16 @@ use std::io::Error;
 use crate::actor::LocalActorRef;
 use crate::remote::actor::RemoteResponse;
 use crate::remote::cluster::node::{NodeIdentity, RemoteNode};
+use crate::remote::net::buffer::BufferPool;
 use crate::remote::net::client::connect::Disconnected;
+use crate::remote::net::client::send::WriteFrame;
 use crate::remote::net::client::RemoteClient;
-use crate::remote::net::message::{timestamp_to_datetime, ClientEvent};
-use crate::remote::net::proto::network::PongEvent;
+use crate::remote::net::compression::Compression;
+use crate::remote::net::message::{
+    decode_failure_frame, is_decode_failure_frame, timestamp_to_datetime, ClientEvent, SessionEvent,
+};
+use crate::remote::net::metrics::NetworkMetrics;
+use crate::remote::net::version::ProtocolVersions;
 use crate::remote::net::StreamReceiver;
 use crate::remote::system::{NodeId, RemoteActorSystem};
 use chrono::{DateTime, Utc};
@@ -71,6 +77,11 @@ impl StreamReceiver for ClientMessageReceiver {
                                 messages: capabilities.messages.to_vec(),
                             })
                             .unwrap_or_else(|| SystemCapabilities::default()),
+                        protocol_versions: ProtocolVersions::from_wire(
+                            identity.min_protocol_version,
+                            identity.max_protocol_version,
+                        ),
+                        compression: Compression::from_id(identity.compression),
                     });
                 } else {
                     debug!("received `Identity` but the client was already identified");
@@ -106,26 +117,32 @@ impl StreamReceiver for ClientMessageReceiver {
                     warn!("error sending handshake_tx");
                 }
             }
-            ClientEvent::Result(res) => {
-                match sys.pop_request(Uuid::from_str(&res.message_id).unwrap()) {
-                    Some(res_tx) => {
-                        let _ = res_tx.send(RemoteResponse::Ok(res.result));
-                    }
-                    None => {
-                        trace!(
-                            "node_tag={}, node_id={}, received unknown request result (id={})",
-                            sys.node_tag(),
-                            sys.node_id(),
-                            res.message_id
-                        );
-                    }
+            ClientEvent::Result(res) => match pop_request(sys, &res.message_id) {
+                Some(res_tx) => {
+                    let _ = res_tx.send(RemoteResponse::Ok(res.result));
                 }
-            }
+                None => {
+                    trace!(
+                        "node_tag={}, node_id={}, received unknown request result (id={})",
+                        sys.node_tag(),
+                        sys.node_id(),
+                        res.message_id
+                    );
+                }
+            },
             ClientEvent::Err(e) => {
+                if is_decode_failure_frame(&e) {
+                    warn!(
+                        "node (addr={}) failed to decode a frame sent by this node",
+                        &self.addr
+                    );
+                    return;
+                }
+
                 info!("received client error!");
-                match sys.pop_request(Uuid::from_str(&e.message_id).unwrap()) {
+                match pop_request(sys, &e.message_id) {
                     Some(res_tx) => {
-                        let _ = res_tx.send(RemoteResponse::Err(e.error.unwrap().into()));
+                        let _ = res_tx.send(RemoteResponse::Err(e.into()));
                     }
                     None => {
                         //                                          :P
@@ -135,15 +152,10 @@ impl StreamReceiver for ClientMessageReceiver {
             }
             ClientEvent::Ping(_ping) => {}
             ClientEvent::Pong(pong) => {
-                match sys.pop_request(Uuid::from_str(&pong.message_id).unwrap()) {
+                match pop_request(sys, &pong.message_id) {
                     Some(res_tx) => {
                         let _ = res_tx.send(RemoteResponse::Ok(
-                            PongEvent {
-                                message_id: pong.message_id,
-                                ..Default::default()
-                            }
-                            .write_to_bytes()
-                            .expect("serialised pong"),
+                            pong.write_to_bytes().expect("serialised pong"),
                         ));
                     }
                     None => {
@@ -160,8 +172,23 @@ impl StreamReceiver for ClientMessageReceiver {
         let _ = self.actor_ref.send(Disconnected).await;
     }
 
-    fn on_deserialisation_failed(&mut self) {
+    fn on_deserialisation_failed(&mut self, frame: &[u8], sys: &RemoteActorSystem) {
+        if let Some(hook) = sys.config().unhandled_frame_hook() {
+            if hook.on_undecodable_frame(&self.addr, frame, sys) {
+                return;
+            }
+        }
+
         warn!("message serialisation failed (addr={})", &self.addr);
+
+        NetworkMetrics::incr_decode_failures(&self.addr);
+
+        let event = SessionEvent::Err(decode_failure_frame());
+        if let Some(frame) = BufferPool::global().encode(&event) {
+            let _ = self
+                .actor_ref
+                .notify(WriteFrame(frame, event.lane().semantics()));
+        }
     }
 
     fn on_stream_lost(&mut self, error: Error) {
@@ -179,3 +206,12 @@ impl StreamReceiver for ClientMessageReceiver {
         self.should_close
     }
 }
+
+pub(crate) fn pop_request(
+    sys: &RemoteActorSystem,
+    message_id: &str,
+) -> Option<Sender<RemoteResponse>> {
+    Uuid::from_str(message_id)
+        .ok()
+        .and_then(|message_id| sys.pop_request(message_id))
+}
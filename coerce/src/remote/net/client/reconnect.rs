@@ -0,0 +1,71 @@
+use rand::Rng;
+use std::time::Duration;
+
+/// Controls how a [`RemoteClient`] re-connects to a node after the connection is lost.
+///
+/// The delay before each attempt grows exponentially from `initial_delay`, up to `max_delay`,
+/// with up to `jitter` (as a fraction of the delay) randomly added or removed, so clients that
+/// lost their connections at the same time don't all re-connect at once.
+///
+/// Once `max_attempts` is reached, the client stops and the node is quarantined,
+/// see [`NodeStatus::Quarantined`].
+///
+/// [`RemoteClient`]: crate::remote::net::client::RemoteClient
+/// [`NodeStatus::Quarantined`]: crate::remote::cluster::node::NodeStatus::Quarantined
+#[derive(Clone, Debug)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+    pub max_attempts: Option<usize>,
+}
+
+impl ReconnectPolicy {
+    /// Re-connects after a fixed `delay`, with no jitter
+    pub fn fixed(delay: Duration, max_attempts: Option<usize>) -> Self {
+        Self {
+            initial_delay: delay,
+            max_delay: delay,
+            multiplier: 1.0,
+            jitter: 0.0,
+            max_attempts,
+        }
+    }
+
+    /// Returns whether another attempt should be made, `attempt` starts at 1
+    pub fn should_reconnect(&self, attempt: usize) -> bool {
+        match self.max_attempts {
+            Some(max_attempts) => attempt <= max_attempts,
+            None => true,
+        }
+    }
+
+    /// Returns the delay before the provided attempt, `attempt` starts at 1
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as usize) as i32;
+        let delay = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        let delay = delay.min(self.max_delay.as_secs_f64());
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let delay = if jitter > 0.0 {
+            delay * rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter)
+        } else {
+            delay
+        };
+
+        Duration::from_secs_f64(delay.max(0.0))
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: Some(10),
+        }
+    }
+}
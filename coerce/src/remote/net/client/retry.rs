@@ -0,0 +1,73 @@
+//! Retries of the identify and handshake exchanges with a node
+//!
+//! Once connected, a [`RemoteClient`] identifies itself and waits for the node's identity, then
+//! (when joining the cluster) sends a handshake and waits for it to be acknowledged. If a reply
+//! never arrives but the TCP connection stays up, the request is written again over the same
+//! connection, up to the configured number of attempts.
+//!
+//! Once the attempts are exhausted, the connection is reset: the socket is closed and the client
+//! re-connects according to its [`ReconnectPolicy`], and the cause is recorded, see
+//! [`ClientStatus::last_disconnect`].
+//!
+//! [`ReconnectPolicy`]: crate::remote::net::client::reconnect::ReconnectPolicy
+//! [`ClientStatus::last_disconnect`]: crate::remote::net::client::status::ClientStatus::last_disconnect
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::remote::net::client::connect::Disconnected;
+use crate::remote::net::client::watchdog::DisconnectCause;
+use crate::remote::net::client::{ClientState, RemoteClient};
+use futures::SinkExt;
+use std::time::Duration;
+
+/// Controls how long a [`RemoteClient`] waits for replies to the identify and handshake
+/// exchanges, and how many times each is attempted before the connection is reset,
+/// see the [module docs][self]
+#[derive(Clone, Debug)]
+pub struct ExchangeRetryPolicy {
+    pub identify_timeout: Duration,
+    pub identify_attempts: usize,
+    pub handshake_timeout: Duration,
+    pub handshake_attempts: usize,
+}
+
+impl Default for ExchangeRetryPolicy {
+    fn default() -> Self {
+        Self {
+            identify_timeout: Duration::from_secs(3),
+            identify_attempts: 3,
+            handshake_timeout: Duration::from_secs(3),
+            handshake_attempts: 5,
+        }
+    }
+}
+
+/// Closes the client's connection, so it re-connects to the node
+pub(crate) struct ResetConnection {
+    pub cause: DisconnectCause,
+}
+
+impl Message for ResetConnection {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<ResetConnection> for RemoteClient {
+    async fn handle(&mut self, message: ResetConnection, ctx: &mut ActorContext) {
+        let connection = match &mut self.state {
+            Some(ClientState::Connected(connection)) => connection,
+            _ => return,
+        };
+
+        warn!(
+            addr = &self.addr,
+            "resetting connection, cause={:?}", &message.cause
+        );
+
+        let _ = connection.write.close().await;
+        connection.receive_task.abort();
+
+        self.handle(Disconnected, ctx).await;
+        self.last_disconnect = Some(message.cause);
+    }
+}
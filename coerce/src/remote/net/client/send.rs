@@ -1,13 +1,24 @@
 use crate::actor::context::ActorContext;
-use crate::actor::message::{Handler, Message};
+use crate::actor::message::{DeliverySemantics, Handler, Message};
+use crate::actor::ActorRefErr;
+use crate::remote::actor::RemoteResponse;
+use crate::remote::net::buffer::BufferPool;
+use crate::remote::net::client::buffer::{WriteBufferConfig, WriteBufferOverflow};
 use crate::remote::net::client::connect::Disconnected;
-use crate::remote::net::client::{ClientState, ConnectionState, RemoteClient, RemoteClientErr};
-use crate::remote::net::StreamData;
+use crate::remote::net::client::receive::pop_request;
+use crate::remote::net::client::{
+    ClientState, ConnectionState, PriorityLane, RemoteClient, RemoteClientErr, WriteLane,
+};
+use crate::remote::net::message::SessionEvent;
+use crate::remote::net::metrics::NetworkMetrics;
+use crate::remote::net::stream::NodeStream;
+use crate::remote::net::{NetworkCodec, StreamData};
+use crate::remote::system::RemoteActorSystem;
 use bytes::{Bytes, BytesMut};
+use chrono::Utc;
 use futures::SinkExt;
 use tokio::io::WriteHalf;
-use tokio::net::TcpStream;
-use tokio_util::codec::{FramedWrite, LengthDelimitedCodec};
+use tokio_util::codec::FramedWrite;
 
 pub struct Write<M: StreamData>(pub M);
 
@@ -22,7 +33,38 @@ impl<M: StreamData> Handler<Write<M>> for RemoteClient {
         message: Write<M>,
         ctx: &mut ActorContext,
     ) -> Result<(), RemoteClientErr> {
-        self.write(message.0, ctx).await
+        self.flush_priority_writes(ctx).await;
+        self.write(message.0, WriteLane::User, ctx).await
+    }
+}
+
+/// Writes a frame that was encoded by the sender, with the [`DeliverySemantics`] of the message
+/// the frame contains, see [`ClientWrite`](crate::remote::actor::message::ClientWrite)
+pub struct WriteFrame(pub Bytes, pub DeliverySemantics);
+
+impl Message for WriteFrame {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<WriteFrame> for RemoteClient {
+    async fn handle(&mut self, message: WriteFrame, ctx: &mut ActorContext) {
+        self.flush_priority_writes(ctx).await;
+        let _ = self.write_raw(message.0, message.1, ctx).await;
+    }
+}
+
+/// Writes any frames queued within the client's [`PriorityLane`][crate::remote::net::client::PriorityLane]
+pub struct FlushPriorityWrites;
+
+impl Message for FlushPriorityWrites {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<FlushPriorityWrites> for RemoteClient {
+    async fn handle(&mut self, _: FlushPriorityWrites, ctx: &mut ActorContext) {
+        self.flush_priority_writes(ctx).await
     }
 }
 
@@ -47,18 +89,39 @@ impl RemoteClient {
         };
 
         debug!(
-            "flushing {} pending messages (addr={})",
+            "flushing {} pending messages, {} system messages (addr={})",
             self.write_buffer.len(),
+            self.system_write_buffer.len(),
             &self.addr
         );
 
-        while let Some(buffered_message) = self.write_buffer.pop_front() {
-            let len = buffered_message.len();
-            let bytes = Bytes::from(buffered_message);
+        // system traffic is written ahead of any buffered user traffic
+        while let Some(bytes) = self.system_write_buffer.pop_front() {
+            let len = bytes.len();
             if let Ok(()) = write_bytes(bytes.clone(), &mut connection_state.write).await {
                 self.write_buffer_bytes_total -= len;
+                self.last_write = Some(Utc::now());
             } else {
-                self.write_buffer.push_front(bytes.to_vec());
+                self.system_write_buffer.push_front(bytes);
+
+                // write failed, no point trying again - reconnect/retry later
+                return;
+            }
+        }
+
+        while let Some((bytes, semantics)) = self.write_buffer.pop_front() {
+            let len = bytes.len();
+            if let Ok(()) = write_prioritised(
+                bytes.clone(),
+                &mut connection_state.write,
+                &self.priority_lane,
+            )
+            .await
+            {
+                self.write_buffer_bytes_total -= len;
+                self.last_write = Some(Utc::now());
+            } else {
+                self.write_buffer.push_front((bytes, semantics));
 
                 // write failed, no point trying again - break and reconnect/retry later
                 break;
@@ -66,76 +129,259 @@ impl RemoteClient {
         }
     }
 
-    pub fn buffer_message(&mut self, message_bytes: Vec<u8>) {
+    /// Buffers the frame until the client re-connects, returning any frames that were dropped
+    /// because the buffer is full, see [`WriteBufferConfig`].
+    ///
+    /// Frames containing [`DeliverySemantics::Reliable`] messages are never dropped to make room
+    /// for other frames.
+    pub fn buffer_message(
+        &mut self,
+        message_bytes: Bytes,
+        semantics: DeliverySemantics,
+        config: &WriteBufferConfig,
+    ) -> Vec<(Bytes, DeliverySemantics)> {
+        let mut dropped = vec![];
+        let lane = WriteLane::of(semantics);
+        if lane == WriteLane::User {
+            let len = message_bytes.len();
+            while config.is_full(self.write_buffer.len(), self.write_buffer_bytes_total, len) {
+                let oldest = match config.overflow {
+                    WriteBufferOverflow::DropOldest => self
+                        .write_buffer
+                        .iter()
+                        .position(|(_, semantics)| *semantics != DeliverySemantics::Reliable)
+                        .and_then(|index| self.write_buffer.remove(index)),
+                    WriteBufferOverflow::DropNewest | WriteBufferOverflow::FailSender => None,
+                };
+
+                match oldest {
+                    Some(oldest) => {
+                        self.write_buffer_bytes_total -= oldest.0.len();
+                        dropped.push(oldest);
+                    }
+                    None => {
+                        dropped.push((message_bytes, semantics));
+                        return dropped;
+                    }
+                }
+            }
+        }
+
         self.write_buffer_bytes_total += message_bytes.len();
-        self.write_buffer.push_back(message_bytes);
+        match lane {
+            WriteLane::System => self.system_write_buffer.push_back(message_bytes),
+            WriteLane::User => self.write_buffer.push_back((message_bytes, semantics)),
+        }
+
+        dropped
     }
 
     pub async fn write<M: StreamData>(
         &mut self,
         message: M,
+        lane: WriteLane,
         ctx: &mut ActorContext,
     ) -> Result<(), RemoteClientErr>
     where
         M: Sync + Send,
     {
-        if let Some(bytes) = message.write_to_bytes() {
-            let mut buffer_message = None;
+        if let Some(bytes) = BufferPool::global().encode(&message) {
+            self.write_raw(bytes, lane.semantics(), ctx).await
+        } else {
+            Err(RemoteClientErr::Encoding)
+        }
+    }
+
+    pub async fn flush_priority_writes(&mut self, ctx: &mut ActorContext) {
+        if self.priority_lane.is_empty() {
+            return;
+        }
 
-            let stream_write_error = match &mut self.state.as_mut().unwrap() {
-                ClientState::Idle { .. } => {
-                    buffer_message = Some(bytes);
+        for bytes in self.priority_lane.drain() {
+            let _ = self
+                .write_raw(bytes, DeliverySemantics::Priority, ctx)
+                .await;
+        }
+    }
 
-                    debug!("attempt to write to addr={} but no connection is established, buffering message (total_buffered={})",
-                        &self.addr,
-                        self.write_buffer.len()
-                    );
+    async fn write_raw(
+        &mut self,
+        bytes: Bytes,
+        semantics: DeliverySemantics,
+        ctx: &mut ActorContext,
+    ) -> Result<(), RemoteClientErr> {
+        let mut buffer_message = None;
 
-                    false
-                }
+        let stream_write_error = match &mut self.state.as_mut().unwrap() {
+            ClientState::Idle { .. } => {
+                buffer_message = Some(bytes);
+
+                debug!("attempt to write to addr={} but no connection is established, buffering message (total_buffered={})",
+                    &self.addr,
+                    self.write_buffer.len()
+                );
+
+                false
+            }
 
-                ClientState::Connected(state) => {
-                    let bytes = Bytes::from(bytes);
-                    if let Err(e) = write_bytes(bytes.clone(), &mut state.write).await {
-                        match e {
-                            RemoteClientErr::StreamErr(_e) => {
-                                warn!("node {} (addr={}) is unreachable but marked as connected, buffering message (total_buffered={})",
-                                    &state.identity.node.id,
-                                    &self.addr,
-                                    self.write_buffer.len());
-
-                                buffer_message = Some(bytes.to_vec());
-
-                                true
-                            }
-                            _ => false,
+            ClientState::Connected(state) => {
+                if let Err(e) =
+                    write_prioritised(bytes.clone(), &mut state.write, &self.priority_lane).await
+                {
+                    match e {
+                        RemoteClientErr::StreamErr(_e) => {
+                            warn!("node {} (addr={}) is unreachable but marked as connected, buffering message (total_buffered={})",
+                                &state.identity.node.id,
+                                &self.addr,
+                                self.write_buffer.len());
+
+                            buffer_message = Some(bytes);
+
+                            true
                         }
-                    } else {
-                        false
+                        _ => false,
                     }
+                } else {
+                    self.last_write = Some(Utc::now());
+                    false
                 }
+            }
+
+            ClientState::Terminated => true,
+        };
 
-                ClientState::Terminated => true,
-            };
+        let mut result = Ok(());
+        if let Some(message_bytes) = buffer_message {
+            let remote = ctx.system().remote();
+            if semantics == DeliverySemantics::FireAndForget {
+                // fire and forget messages are only written whilst the node is connected
+                trace!(
+                    "node (addr={}) isn't connected, dropping fire and forget message",
+                    &self.addr
+                );
 
-            if let Some(message_bytes) = buffer_message {
-                self.buffer_message(message_bytes);
+                fail_request(&message_bytes, remote, ActorRefErr::ActorUnavailable);
+            } else {
+                let config = remote.config().write_buffer();
+                let dropped = self.buffer_message(message_bytes, semantics, config);
+                if !dropped.is_empty() {
+                    result = self.on_writes_dropped(dropped, config, remote);
+                }
             }
+        }
+
+        if stream_write_error {
+            self.handle(Disconnected, ctx).await;
+        }
 
-            if stream_write_error {
-                self.handle(Disconnected, ctx).await;
+        result
+    }
+
+    fn on_writes_dropped(
+        &mut self,
+        dropped: Vec<(Bytes, DeliverySemantics)>,
+        config: &WriteBufferConfig,
+        remote: &RemoteActorSystem,
+    ) -> Result<(), RemoteClientErr> {
+        warn!(
+            "write buffer full (addr={}, max_messages={:?}, max_bytes={:?}, overflow={:?}), dropped {} message(s)",
+            &self.addr,
+            config.max_messages,
+            config.max_bytes,
+            config.overflow,
+            dropped.len()
+        );
+
+        self.dropped_writes += dropped.len() as u64;
+        NetworkMetrics::incr_write_buffer_dropped(dropped.len() as u64, &self.addr);
+
+        // reliable messages always fail their sender, rather than being dropped silently
+        let fail_sender = config.overflow == WriteBufferOverflow::FailSender;
+        let mut failed = false;
+        for (frame, semantics) in dropped {
+            if fail_sender || semantics == DeliverySemantics::Reliable {
+                fail_request(&frame, remote, ActorRefErr::WriteBufferFull);
+                failed = true;
             }
+        }
+
+        if failed {
+            Err(RemoteClientErr::WriteBufferFull)
         } else {
-            return Err(RemoteClientErr::Encoding);
+            Ok(())
         }
+    }
+}
 
-        Ok(())
+/// Fails the pending request of a message that won't be written, rather than the sender waiting
+/// for a reply that will never arrive
+fn fail_request(frame: &[u8], remote: &RemoteActorSystem, error: ActorRefErr) {
+    if let Some(res_tx) = SessionEvent::message_request_id(frame)
+        .and_then(|message_id| pop_request(remote, &message_id))
+    {
+        let _ = res_tx.send(RemoteResponse::Err(error));
     }
 }
 
+/// Writes the frame, split into chunks if it's too large to be written as a single frame,
+/// see [`chunk`](crate::remote::net::chunk)
 pub(crate) async fn write_bytes(
     bytes: Bytes,
-    writer: &mut FramedWrite<WriteHalf<TcpStream>, LengthDelimitedCodec>,
+    writer: &mut FramedWrite<WriteHalf<NodeStream>, NetworkCodec>,
+) -> Result<(), RemoteClientErr> {
+    match writer.encoder_mut().split(&bytes) {
+        Some(chunks) => {
+            for chunk in chunks {
+                write_frame(chunk, writer).await?;
+            }
+
+            Ok(())
+        }
+        None => write_frame(bytes, writer).await,
+    }
+}
+
+/// Writes the frame, like [`write_bytes`], but any frames queued in the [`PriorityLane`] are
+/// written first, and when the frame is split into chunks, between each chunk, so replies and
+/// control messages aren't held up behind buffered or large messages.
+async fn write_prioritised(
+    bytes: Bytes,
+    writer: &mut FramedWrite<WriteHalf<NodeStream>, NetworkCodec>,
+    priority_lane: &PriorityLane,
+) -> Result<(), RemoteClientErr> {
+    let chunks = writer
+        .encoder_mut()
+        .split(&bytes)
+        .unwrap_or_else(|| vec![bytes]);
+
+    for chunk in chunks {
+        write_priority_lane(writer, priority_lane).await?;
+        write_frame(chunk, writer).await?;
+    }
+
+    Ok(())
+}
+
+async fn write_priority_lane(
+    writer: &mut FramedWrite<WriteHalf<NodeStream>, NetworkCodec>,
+    priority_lane: &PriorityLane,
+) -> Result<(), RemoteClientErr> {
+    let mut priority_frames = priority_lane.drain().into_iter();
+    while let Some(frame) = priority_frames.next() {
+        if let Err(e) = write_bytes(frame.clone(), writer).await {
+            // written once the client has re-connected
+            priority_lane.push(frame);
+            priority_frames.for_each(|frame| priority_lane.push(frame));
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn write_frame(
+    bytes: Bytes,
+    writer: &mut FramedWrite<WriteHalf<NodeStream>, NetworkCodec>,
 ) -> Result<(), RemoteClientErr> {
     match writer.send(bytes).await {
         Ok(()) => Ok(()),
@@ -0,0 +1,135 @@
+//! Diagnostics describing the state of the connections from this node to each other node,
+//! see [`RemoteActorSystem::client_statuses`]
+//!
+//! [`RemoteActorSystem::client_statuses`]: crate::remote::system::RemoteActorSystem::client_statuses
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::ActorTags;
+use crate::remote::net::client::watchdog::DisconnectCause;
+use crate::remote::net::client::{ClientState, RemoteClient};
+use crate::remote::system::NodeId;
+use chrono::{DateTime, Utc};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ClientConnectionStatus {
+    /// Connected and identified by the remote node
+    Connected,
+    /// Not connected, the client is waiting to re-connect, writes are buffered until it does
+    Idle,
+    /// The client has given up re-connecting, and the node has been quarantined
+    Quarantined,
+    /// The client didn't respond in time, for example, whilst blocked on a write or connection attempt
+    Unresponsive,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClientStatus {
+    pub addr: String,
+    pub node_id: Option<NodeId>,
+    pub status: ClientConnectionStatus,
+    /// Writes buffered whilst the client isn't connected
+    pub buffered_messages: usize,
+    /// System writes buffered whilst the client isn't connected, written ahead of any other
+    /// buffered writes once the client re-connects
+    pub buffered_system_messages: usize,
+    /// Total size of all buffered writes
+    pub buffered_bytes: usize,
+    /// Replies and control messages waiting to be written ahead of any other traffic
+    pub priority_messages: usize,
+    /// Writes dropped because the write buffer was full, see [`WriteBufferConfig`]
+    ///
+    /// [`WriteBufferConfig`]: crate::remote::net::client::buffer::WriteBufferConfig
+    pub dropped_messages: u64,
+    pub last_write: Option<DateTime<Utc>>,
+    /// Failed connection attempts since the client was last connected
+    pub connection_attempts: usize,
+    /// Why the connection to the node was last lost, if it has ever been lost
+    pub last_disconnect: Option<DisconnectCause>,
+}
+
+impl ClientStatus {
+    pub(crate) fn unresponsive(addr: String) -> Self {
+        Self {
+            addr,
+            node_id: None,
+            status: ClientConnectionStatus::Unresponsive,
+            buffered_messages: 0,
+            buffered_system_messages: 0,
+            buffered_bytes: 0,
+            priority_messages: 0,
+            dropped_messages: 0,
+            last_write: None,
+            connection_attempts: 0,
+            last_disconnect: None,
+        }
+    }
+}
+
+impl From<&ClientStatus> for ActorTags {
+    fn from(status: &ClientStatus) -> Self {
+        let mut tags = vec![
+            format!("status={:?}", status.status),
+            format!("buffered_messages={}", status.buffered_messages),
+            format!(
+                "buffered_system_messages={}",
+                status.buffered_system_messages
+            ),
+            format!("buffered_bytes={}", status.buffered_bytes),
+            format!("priority_messages={}", status.priority_messages),
+            format!("dropped_messages={}", status.dropped_messages),
+            format!("connection_attempts={}", status.connection_attempts),
+        ];
+
+        if let Some(node_id) = status.node_id {
+            tags.push(format!("node_id={}", node_id));
+        }
+
+        if let Some(last_write) = status.last_write {
+            tags.push(format!("last_write={}", last_write.to_rfc3339()));
+        }
+
+        tags.into()
+    }
+}
+
+pub struct GetClientStatus;
+
+impl Message for GetClientStatus {
+    type Result = ClientStatus;
+}
+
+#[async_trait]
+impl Handler<GetClientStatus> for RemoteClient {
+    async fn handle(&mut self, _: GetClientStatus, _ctx: &mut ActorContext) -> ClientStatus {
+        self.status()
+    }
+}
+
+impl RemoteClient {
+    pub fn status(&self) -> ClientStatus {
+        let status = match &self.state {
+            Some(ClientState::Connected(_)) => ClientConnectionStatus::Connected,
+            Some(ClientState::Idle { .. }) | None => ClientConnectionStatus::Idle,
+            Some(ClientState::Terminated) => ClientConnectionStatus::Quarantined,
+        };
+
+        ClientStatus {
+            addr: self.addr.clone(),
+            node_id: self.node_id,
+            status,
+            buffered_messages: self.write_buffer.len(),
+            buffered_system_messages: self.system_write_buffer.len(),
+            buffered_bytes: self.write_buffer_bytes_total,
+            priority_messages: self.priority_lane.len(),
+            dropped_messages: self.dropped_writes,
+            last_write: self.last_write,
+            connection_attempts: self
+                .state
+                .as_ref()
+                .and_then(|state| state.connection_attempts())
+                .unwrap_or(0),
+            last_disconnect: self.last_disconnect.clone(),
+        }
+    }
+}
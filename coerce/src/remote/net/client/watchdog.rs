@@ -0,0 +1,120 @@
+//! Watchdog for the task that receives from a client's connection
+//!
+//! The receive task notifies the client with [`Disconnected`] once the stream is closed, but if
+//! the task dies without reaching that point, for example if decoding a frame panics or the task
+//! is aborted, the client would be left with a connection that no longer receives anything.
+//!
+//! The watchdog awaits the task and, if it terminated abnormally, the connection is treated as
+//! disconnected and the cause recorded, see [`ClientStatus::last_disconnect`].
+//!
+//! [`ClientStatus::last_disconnect`]: crate::remote::net::client::status::ClientStatus::last_disconnect
+
+use crate::actor::context::ActorContext;
+use crate::actor::lifecycle::panic_message;
+use crate::actor::message::{Handler, Message};
+use crate::actor::LocalActorRef;
+use crate::remote::net::client::connect::Disconnected;
+use crate::remote::net::client::{ClientState, RemoteClient};
+use std::fmt::{Display, Formatter};
+use tokio::task::{AbortHandle, JoinError, JoinHandle};
+
+/// Why the client's connection to the node was last lost
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum DisconnectCause {
+    /// The stream was closed, either by the node or because of a network error
+    StreamClosed,
+    /// The task receiving from the stream panicked
+    ReceiveTaskPanicked { panic_message: Option<String> },
+    /// The task receiving from the stream was aborted
+    ReceiveTaskAborted,
+    /// The node didn't reply with its identity, see [`ExchangeRetryPolicy`]
+    ///
+    /// [`ExchangeRetryPolicy`]: crate::remote::net::client::retry::ExchangeRetryPolicy
+    IdentifyTimedOut,
+    /// The node didn't acknowledge the handshake, see [`ExchangeRetryPolicy`]
+    ///
+    /// [`ExchangeRetryPolicy`]: crate::remote::net::client::retry::ExchangeRetryPolicy
+    HandshakeTimedOut,
+}
+
+impl Display for DisconnectCause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisconnectCause::StreamClosed => write!(f, "stream closed"),
+            DisconnectCause::ReceiveTaskPanicked {
+                panic_message: Some(panic_message),
+            } => write!(f, "receive task panicked (message={})", panic_message),
+            DisconnectCause::ReceiveTaskPanicked {
+                panic_message: None,
+            } => write!(f, "receive task panicked"),
+            DisconnectCause::ReceiveTaskAborted => write!(f, "receive task aborted"),
+            DisconnectCause::IdentifyTimedOut => write!(f, "identify timed out"),
+            DisconnectCause::HandshakeTimedOut => write!(f, "handshake timed out"),
+        }
+    }
+}
+
+pub(crate) struct ReceiveTaskTerminated {
+    connection_id: u64,
+    cause: DisconnectCause,
+}
+
+impl Message for ReceiveTaskTerminated {
+    type Result = ();
+}
+
+impl From<JoinError> for DisconnectCause {
+    fn from(e: JoinError) -> Self {
+        if e.is_panic() {
+            DisconnectCause::ReceiveTaskPanicked {
+                panic_message: panic_message(&e.into_panic()),
+            }
+        } else {
+            DisconnectCause::ReceiveTaskAborted
+        }
+    }
+}
+
+/// Watches the receive task of the connection identified by `connection_id`,
+/// returning a handle that can be used to abort the task
+pub(crate) fn watch_receive_task(
+    receive_task: JoinHandle<()>,
+    connection_id: u64,
+    client: LocalActorRef<RemoteClient>,
+) -> AbortHandle {
+    let abort_handle = receive_task.abort_handle();
+
+    tokio::spawn(async move {
+        if let Err(e) = receive_task.await {
+            let _ = client.notify(ReceiveTaskTerminated {
+                connection_id,
+                cause: e.into(),
+            });
+        }
+    });
+
+    abort_handle
+}
+
+#[async_trait]
+impl Handler<ReceiveTaskTerminated> for RemoteClient {
+    async fn handle(&mut self, message: ReceiveTaskTerminated, ctx: &mut ActorContext) {
+        // tasks aborted by the client itself belong to connections it has already given up on
+        let is_current_connection = matches!(
+            &self.state,
+            Some(ClientState::Connected(connection)) if connection.id == message.connection_id
+        );
+
+        if !is_current_connection {
+            return;
+        }
+
+        error!(
+            addr = &self.addr,
+            "receive task terminated, cause={:?}", &message.cause
+        );
+
+        self.handle(Disconnected, ctx).await;
+        self.last_disconnect = Some(message.cause);
+    }
+}
@@ -0,0 +1,222 @@
+//! Frame compression for connections between nodes
+//!
+//! Compression is negotiated per connection: the connecting node offers the algorithms configured
+//! in its [`CompressionConfig`], in order of preference, and the accepting node chooses the first
+//! one it also supports. Once negotiated, each node compresses the frames it writes that are at
+//! least its configured threshold, so small frames (pings, acknowledgements) skip compression.
+//!
+//! A compressed frame starts with a byte flagging it as compressed, along with the algorithm used,
+//! in place of the usual event type, followed by the compressed frame. Uncompressed frames are
+//! unchanged, so nodes without compression configured can still talk to nodes that have it.
+//!
+//! Algorithms are enabled via the `lz4` and `zstd` features.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .with_compression(CompressionConfig::new(Compression::Zstd).threshold(4096))
+//!     .build()
+//!     .await;
+//! ```
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+/// Set in the first byte of a compressed frame, which can't clash with an event type
+const COMPRESSED_FRAME: u8 = 0x80;
+
+const DEFAULT_THRESHOLD: usize = 1024;
+
+const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// Frames are never decompressed beyond the maximum length of an uncompressed frame
+#[cfg(any(feature = "lz4", feature = "zstd"))]
+const MAX_DECOMPRESSED_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Compression {
+    Lz4,
+    Zstd,
+}
+
+impl Compression {
+    pub(crate) fn id(&self) -> u32 {
+        match self {
+            Compression::Lz4 => 1,
+            Compression::Zstd => 2,
+        }
+    }
+
+    pub(crate) fn from_id(id: u32) -> Option<Self> {
+        match id {
+            1 => Some(Compression::Lz4),
+            2 => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Whether support for the algorithm was enabled, via the `lz4` or `zstd` feature
+    pub fn is_available(&self) -> bool {
+        match self {
+            Compression::Lz4 => cfg!(feature = "lz4"),
+            Compression::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompressionConfig {
+    algorithms: Vec<Compression>,
+    threshold: usize,
+    zstd_level: i32,
+}
+
+impl CompressionConfig {
+    /// Compresses frames using `algorithm`, with connections to nodes that support it
+    pub fn new(algorithm: Compression) -> Self {
+        Self {
+            algorithms: vec![algorithm],
+            threshold: DEFAULT_THRESHOLD,
+            zstd_level: DEFAULT_ZSTD_LEVEL,
+        }
+    }
+
+    /// Offers `algorithm` to nodes that don't support any of the previously configured algorithms
+    pub fn with_fallback(mut self, algorithm: Compression) -> Self {
+        if !self.algorithms.contains(&algorithm) {
+            self.algorithms.push(algorithm);
+        }
+
+        self
+    }
+
+    /// Frames smaller than `threshold` bytes are written uncompressed, defaults to 1024
+    pub fn threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// The level frames are compressed at when using [`Compression::Zstd`], defaults to 3
+    pub fn zstd_level(mut self, level: i32) -> Self {
+        self.zstd_level = level;
+        self
+    }
+
+    /// The configured algorithms that are available, in order of preference
+    pub fn algorithms(&self) -> impl Iterator<Item = Compression> + '_ {
+        self.algorithms.iter().copied().filter(|a| a.is_available())
+    }
+
+    /// Chooses the first algorithm offered by the connecting node that this node also supports
+    pub(crate) fn negotiate(&self, offered: &[u32]) -> Option<Compression> {
+        offered
+            .iter()
+            .filter_map(|id| Compression::from_id(*id))
+            .find(|algorithm| self.algorithms().any(|a| a == *algorithm))
+    }
+
+    pub(crate) fn compressor(&self, algorithm: Compression) -> FrameCompressor {
+        FrameCompressor {
+            algorithm,
+            threshold: self.threshold,
+            zstd_level: self.zstd_level,
+        }
+    }
+}
+
+/// Compresses the frames written to a connection, using the algorithm negotiated for it
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FrameCompressor {
+    algorithm: Compression,
+    threshold: usize,
+    zstd_level: i32,
+}
+
+impl FrameCompressor {
+    pub fn algorithm(&self) -> Compression {
+        self.algorithm
+    }
+
+    /// Compresses the frame if it's at least the threshold, and compressing it reduces its size
+    pub fn compress(&self, frame: Bytes) -> Bytes {
+        if frame.len() < self.threshold {
+            return frame;
+        }
+
+        match compress(self.algorithm, &frame, self.zstd_level) {
+            Some(compressed) if compressed.len() + 1 < frame.len() => {
+                let mut buf = BytesMut::with_capacity(compressed.len() + 1);
+                buf.put_u8(COMPRESSED_FRAME | self.algorithm.id() as u8);
+                buf.extend_from_slice(&compressed);
+                buf.freeze()
+            }
+            _ => frame,
+        }
+    }
+}
+
+/// Decompresses the frame if it was compressed. Frames that can't be decompressed are returned
+/// as they are, and fail to decode like any other invalid frame.
+pub(crate) fn decompress_frame(frame: BytesMut) -> BytesMut {
+    let algorithm = match frame.first() {
+        Some(flag) if flag & COMPRESSED_FRAME != 0 => {
+            Compression::from_id((flag & !COMPRESSED_FRAME) as u32)
+        }
+        _ => return frame,
+    };
+
+    match algorithm.and_then(|algorithm| decompress(algorithm, &frame[1..])) {
+        Some(decompressed) => BytesMut::from(decompressed.as_slice()),
+        None => {
+            warn!("unable to decompress frame (len={})", frame.len());
+            frame
+        }
+    }
+}
+
+#[allow(unused_variables)]
+fn compress(algorithm: Compression, bytes: &[u8], zstd_level: i32) -> Option<Vec<u8>> {
+    match algorithm {
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => Some(lz4_flex::compress_prepend_size(bytes)),
+
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => zstd::bulk::compress(bytes, zstd_level).ok(),
+
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+#[allow(unused_variables)]
+fn decompress(algorithm: Compression, bytes: &[u8]) -> Option<Vec<u8>> {
+    match algorithm {
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => {
+            let len = bytes.get(..4)?;
+            let len = u32::from_le_bytes(len.try_into().ok()?) as usize;
+            if len > MAX_DECOMPRESSED_FRAME_LENGTH {
+                return None;
+            }
+
+            lz4_flex::decompress_size_prepended(bytes).ok()
+        }
+
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => {
+            use std::io::Read;
+
+            let mut decompressed = vec![];
+            zstd::stream::read::Decoder::new(bytes)
+                .ok()?
+                .take(MAX_DECOMPRESSED_FRAME_LENGTH as u64 + 1)
+                .read_to_end(&mut decompressed)
+                .ok()?;
+
+            (decompressed.len() <= MAX_DECOMPRESSED_FRAME_LENGTH).then_some(decompressed)
+        }
+
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
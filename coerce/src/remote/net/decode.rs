@@ -0,0 +1,174 @@
+//! Decoding of inbound frames off the receive loop
+//!
+//! Frames read by a [`receive_loop`] are decoded by a small pool of workers, so decoding one large
+//! message doesn't add latency to every other message received via the same connection.
+//!
+//! Each frame is given an ordering key by its [`StreamReceiver`], and frames that share a key are
+//! always decoded by the same worker, so they're delivered in the order they were received.
+//! Messages sent to the same actor share a key, as do all system events. Frames smaller than
+//! [`DecodeConfig::inline_threshold`] are decoded by the receive loop itself, unless frames that
+//! share its key are still being decoded by a worker.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .configure(|c| c.decoding(DecodeConfig::default().workers(8)))
+//!     .build()
+//!     .await;
+//! ```
+//!
+//! [`receive_loop`]: crate::remote::net::receive_loop
+//! [`StreamReceiver`]: crate::remote::net::StreamReceiver
+
+use crate::remote::net::StreamData;
+use bytes::BytesMut;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+const DEFAULT_WORKERS: usize = 4;
+
+const DEFAULT_INLINE_THRESHOLD: usize = 64 * 1024;
+
+const DEFAULT_MAX_IN_FLIGHT: usize = 256;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeConfig {
+    workers: usize,
+    inline_threshold: usize,
+    max_in_flight: usize,
+}
+
+impl DecodeConfig {
+    /// The number of workers each connection decodes frames with, defaults to 4. With no
+    /// workers, every frame is decoded by the receive loop.
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Frames smaller than `inline_threshold` bytes are decoded by the receive loop, avoiding
+    /// the hand-off to a worker, defaults to 64KB
+    pub fn inline_threshold(mut self, inline_threshold: usize) -> Self {
+        self.inline_threshold = inline_threshold;
+        self
+    }
+
+    /// The maximum number of frames a connection can have waiting to be decoded, once reached,
+    /// reads are paused until a frame has been decoded. Defaults to 256.
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+}
+
+impl Default for DecodeConfig {
+    fn default() -> Self {
+        Self {
+            workers: DEFAULT_WORKERS,
+            inline_threshold: DEFAULT_INLINE_THRESHOLD,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+        }
+    }
+}
+
+pub(crate) enum Decoded<M> {
+    Message(M),
+    Failed(BytesMut),
+}
+
+impl<M: StreamData> Decoded<M> {
+    fn decode(frame: BytesMut) -> Self {
+        match M::read_from_slice(&frame) {
+            Some(message) => Decoded::Message(message),
+            None => Decoded::Failed(frame),
+        }
+    }
+}
+
+struct DecodeWorker {
+    frames: UnboundedSender<BytesMut>,
+    in_flight: usize,
+}
+
+/// The workers decoding a single connection's frames, stopped once the pool is dropped
+pub(crate) struct DecodePool<M> {
+    workers: Vec<DecodeWorker>,
+    decoded: UnboundedReceiver<(usize, Decoded<M>)>,
+    inline_threshold: usize,
+    max_in_flight: usize,
+    in_flight: usize,
+}
+
+impl<M: StreamData> DecodePool<M> {
+    pub fn new(config: &DecodeConfig) -> Self {
+        let (decoded_tx, decoded) = unbounded_channel();
+        let workers = (0..config.workers)
+            .map(|worker| {
+                let (frames, mut frames_rx) = unbounded_channel::<BytesMut>();
+                let decoded_tx = decoded_tx.clone();
+                tokio::spawn(async move {
+                    while let Some(frame) = frames_rx.recv().await {
+                        if decoded_tx.send((worker, Decoded::decode(frame))).is_err() {
+                            break;
+                        }
+                    }
+                });
+
+                DecodeWorker {
+                    frames,
+                    in_flight: 0,
+                }
+            })
+            .collect();
+
+        Self {
+            workers,
+            decoded,
+            inline_threshold: config.inline_threshold,
+            max_in_flight: config.max_in_flight,
+            in_flight: 0,
+        }
+    }
+
+    /// Decodes the frame in-line, if possible without overtaking frames that share its key,
+    /// otherwise the frame is handed to a worker and is later returned by [`DecodePool::next`]
+    pub fn decode(&mut self, frame: BytesMut, key: u64) -> Option<Decoded<M>> {
+        if self.workers.is_empty() {
+            return Some(Decoded::decode(frame));
+        }
+
+        let worker_count = self.workers.len();
+        let worker = &mut self.workers[(key % worker_count as u64) as usize];
+        if worker.in_flight == 0 && frame.len() < self.inline_threshold {
+            return Some(Decoded::decode(frame));
+        }
+
+        match worker.frames.send(frame) {
+            Ok(_) => {
+                worker.in_flight += 1;
+                self.in_flight += 1;
+                None
+            }
+            Err(e) => Some(Decoded::decode(e.0)),
+        }
+    }
+
+    /// Waits for the next frame to be decoded by a worker
+    pub async fn next(&mut self) -> Option<Decoded<M>> {
+        let (worker, decoded) = self.decoded.recv().await?;
+
+        self.workers[worker].in_flight -= 1;
+        self.in_flight -= 1;
+        Some(decoded)
+    }
+
+    /// Whether there are no frames waiting to be decoded
+    pub fn is_idle(&self) -> bool {
+        self.in_flight == 0
+    }
+
+    /// Whether no more frames should be read until one has been decoded
+    pub fn is_saturated(&self) -> bool {
+        self.in_flight >= self.max_in_flight
+    }
+}
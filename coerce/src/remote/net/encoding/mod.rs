@@ -0,0 +1,137 @@
+//! Wire encodings of the events exchanged between nodes
+//!
+//! The body of each [`SessionEvent`] and [`ClientEvent`] is encoded by a [`MessageCodec`]. The
+//! format a node writes events with is chosen when the remote actor system is built, and
+//! defaults to [`WireFormat::Protobuf`].
+//!
+//! The byte identifying the type of each event also records the format the event was written
+//! in, so a node reads events written in any format it was built with, regardless of the format
+//! it writes with. A cluster can be moved to a different format one node at a time, as long as
+//! every node supports the new format before any node starts writing with it. Events exchanged
+//! whilst a connection is being established are always written as protobuf.
+//!
+//! [`WireFormat::MessagePack`] is enabled via the `msgpack` feature, see [`msgpack`].
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .with_wire_format(WireFormat::MessagePack)
+//!     .build()
+//!     .await;
+//! ```
+//!
+//! [`SessionEvent`]: crate::remote::net::message::SessionEvent
+//! [`ClientEvent`]: crate::remote::net::message::ClientEvent
+
+use bytes::BytesMut;
+use protobuf::rt::WireType;
+use protobuf::{CodedInputStream, CodedOutputStream, MessageDyn};
+
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+
+#[cfg(feature = "msgpack")]
+pub use msgpack::MessagePackCodec;
+
+/// Set in the first byte of an event written as MessagePack, alongside the event type
+const MESSAGE_PACK_EVENT: u8 = 0x20;
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum WireFormat {
+    #[default]
+    Protobuf,
+    MessagePack,
+}
+
+impl WireFormat {
+    /// Whether support for the format was enabled, MessagePack requires the `msgpack` feature
+    pub fn is_available(&self) -> bool {
+        match self {
+            WireFormat::Protobuf => true,
+            WireFormat::MessagePack => cfg!(feature = "msgpack"),
+        }
+    }
+
+    /// The codec events are written in this format with, or `None` if the format isn't available
+    pub fn codec(&self) -> Option<&'static dyn MessageCodec> {
+        match self {
+            WireFormat::Protobuf => Some(&ProtobufCodec),
+
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => Some(&MessagePackCodec),
+
+            #[cfg(not(feature = "msgpack"))]
+            WireFormat::MessagePack => None,
+        }
+    }
+
+    /// Sets the format in the first byte of an event of the provided type
+    pub(crate) fn event_byte(&self, event_id: u8) -> u8 {
+        match self {
+            WireFormat::Protobuf => event_id,
+            WireFormat::MessagePack => event_id | MESSAGE_PACK_EVENT,
+        }
+    }
+
+    /// Splits the first byte of an event into the event type, and the format it was written in
+    pub(crate) fn from_event_byte(byte: u8) -> (u8, WireFormat) {
+        if byte & MESSAGE_PACK_EVENT != 0 {
+            (byte & !MESSAGE_PACK_EVENT, WireFormat::MessagePack)
+        } else {
+            (byte, WireFormat::Protobuf)
+        }
+    }
+}
+
+/// Encodes and decodes the body of the events exchanged between nodes
+pub trait MessageCodec: 'static + Send + Sync {
+    /// The format of the events written by the codec
+    fn format(&self) -> WireFormat;
+
+    /// Appends the encoded message to the buffer, returning `false` if it couldn't be encoded
+    fn encode(&self, message: &dyn MessageDyn, buffer: &mut BytesMut) -> bool;
+
+    /// Merges the encoded message into `message`, returning `false` if it couldn't be decoded
+    fn decode(&self, bytes: &[u8], message: &mut dyn MessageDyn) -> bool;
+
+    /// Reads a single string field of an encoded message, without decoding the rest of it
+    fn read_string_field(&self, bytes: &[u8], field_number: u32) -> Option<String>;
+}
+
+/// The default codec, events are encoded as protobuf (see `network.proto`)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProtobufCodec;
+
+impl MessageCodec for ProtobufCodec {
+    fn format(&self) -> WireFormat {
+        WireFormat::Protobuf
+    }
+
+    fn encode(&self, message: &dyn MessageDyn, buffer: &mut BytesMut) -> bool {
+        let len = message.compute_size_dyn() as usize;
+
+        let start = buffer.len();
+        buffer.resize(start + len, 0);
+
+        let mut output = CodedOutputStream::bytes(&mut buffer[start..]);
+        message.write_to_with_cached_sizes_dyn(&mut output).is_ok() && output.flush().is_ok()
+    }
+
+    fn decode(&self, bytes: &[u8], message: &mut dyn MessageDyn) -> bool {
+        message.merge_from_bytes_dyn(bytes).is_ok()
+    }
+
+    fn read_string_field(&self, bytes: &[u8], field_number: u32) -> Option<String> {
+        let mut input = CodedInputStream::from_bytes(bytes);
+        while let Some(tag) = input.read_raw_tag_or_eof().ok()? {
+            if tag >> 3 == field_number {
+                return input.read_string().ok();
+            }
+
+            input.skip_field(WireType::new(tag & 7)?).ok()?;
+        }
+
+        None
+    }
+}
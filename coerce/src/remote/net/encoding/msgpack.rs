@@ -0,0 +1,199 @@
+//! MessagePack encoding of events, enabled via the `msgpack` feature
+//!
+//! Messages are written as MessagePack maps, keyed by the protobuf field number of each field
+//! (see `network.proto`), with fields that aren't set left out. Nested messages are written as
+//! maps, repeated fields as arrays, map fields as maps and enums as their numeric value.
+//!
+//! Fields with numbers the reader doesn't know about are skipped, so fields can be added to
+//! events in the same way they can be when events are written as protobuf.
+
+use crate::remote::net::encoding::{MessageCodec, WireFormat};
+use bytes::{BufMut, BytesMut};
+use protobuf::reflect::{
+    ReflectFieldRef, ReflectValueBox, ReflectValueRef, RuntimeFieldType, RuntimeType,
+};
+use protobuf::MessageDyn;
+use rmp::encode;
+use rmpv::ValueRef;
+use std::io::Write;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MessagePackCodec;
+
+impl MessageCodec for MessagePackCodec {
+    fn format(&self) -> WireFormat {
+        WireFormat::MessagePack
+    }
+
+    fn encode(&self, message: &dyn MessageDyn, buffer: &mut BytesMut) -> bool {
+        write_message(&mut buffer.writer(), message).is_some()
+    }
+
+    fn decode(&self, bytes: &[u8], message: &mut dyn MessageDyn) -> bool {
+        read_value(bytes).is_some_and(|value| merge_message(&value, message).is_some())
+    }
+
+    fn read_string_field(&self, bytes: &[u8], field_number: u32) -> Option<String> {
+        match read_value(bytes)? {
+            ValueRef::Map(fields) => fields
+                .into_iter()
+                .find(|(number, _)| field_number_of(number) == Some(field_number))
+                .and_then(|(_, value)| match value {
+                    ValueRef::String(value) => value.into_string(),
+                    _ => None,
+                }),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a single value, which must make up the entirety of the encoded message
+fn read_value(bytes: &[u8]) -> Option<ValueRef<'_>> {
+    let mut input = bytes;
+    let value = rmpv::decode::read_value_ref(&mut input).ok()?;
+    input.is_empty().then_some(value)
+}
+
+fn field_number_of(value: &ValueRef) -> Option<u32> {
+    match value {
+        ValueRef::Integer(number) => number.as_u64()?.try_into().ok(),
+        _ => None,
+    }
+}
+
+fn write_message<W: Write>(output: &mut W, message: &dyn MessageDyn) -> Option<()> {
+    let descriptor = message.descriptor_dyn();
+    let fields: Vec<_> = descriptor
+        .fields()
+        .filter_map(|field| {
+            let is_set = match field.get_reflect(message) {
+                ReflectFieldRef::Optional(value) => value.value().is_some(),
+                ReflectFieldRef::Repeated(values) => !values.is_empty(),
+                ReflectFieldRef::Map(entries) => !entries.is_empty(),
+            };
+
+            is_set.then_some(field)
+        })
+        .collect();
+
+    encode::write_map_len(output, fields.len() as u32).ok()?;
+
+    for field in fields {
+        encode::write_uint(output, field.number() as u64).ok()?;
+
+        match field.get_reflect(message) {
+            ReflectFieldRef::Optional(value) => write_value(output, value.value()?)?,
+            ReflectFieldRef::Repeated(values) => {
+                encode::write_array_len(output, values.len() as u32).ok()?;
+                for value in values {
+                    write_value(output, value)?;
+                }
+            }
+            ReflectFieldRef::Map(entries) => {
+                encode::write_map_len(output, entries.len() as u32).ok()?;
+                for (key, value) in &entries {
+                    write_value(output, key)?;
+                    write_value(output, value)?;
+                }
+            }
+        }
+    }
+
+    Some(())
+}
+
+fn write_value<W: Write>(output: &mut W, value: ReflectValueRef) -> Option<()> {
+    match value {
+        ReflectValueRef::U32(value) => encode::write_uint(output, value as u64).map(drop).ok(),
+        ReflectValueRef::U64(value) => encode::write_uint(output, value).map(drop).ok(),
+        ReflectValueRef::I32(value) => encode::write_sint(output, value as i64).map(drop).ok(),
+        ReflectValueRef::I64(value) => encode::write_sint(output, value).map(drop).ok(),
+        ReflectValueRef::F32(value) => encode::write_f32(output, value).ok(),
+        ReflectValueRef::F64(value) => encode::write_f64(output, value).ok(),
+        ReflectValueRef::Bool(value) => encode::write_bool(output, value).ok(),
+        ReflectValueRef::String(value) => encode::write_str(output, value).ok(),
+        ReflectValueRef::Bytes(value) => encode::write_bin(output, value).ok(),
+        ReflectValueRef::Enum(_, value) => encode::write_sint(output, value as i64).map(drop).ok(),
+        ReflectValueRef::Message(message) => write_message(output, &*message),
+    }
+}
+
+fn merge_message(value: &ValueRef, message: &mut dyn MessageDyn) -> Option<()> {
+    let fields = match value {
+        ValueRef::Map(fields) => fields,
+        _ => return None,
+    };
+
+    let descriptor = message.descriptor_dyn();
+    for (number, value) in fields {
+        let field = match descriptor.field_by_number(field_number_of(number)?) {
+            Some(field) => field,
+            None => continue,
+        };
+
+        match field.runtime_field_type() {
+            RuntimeFieldType::Singular(RuntimeType::Message(_)) => {
+                merge_message(value, field.mut_message(message))?
+            }
+            RuntimeFieldType::Singular(value_type) => {
+                field.set_singular_field(message, read_reflect_value(&value_type, value)?)
+            }
+            RuntimeFieldType::Repeated(value_type) => {
+                let values = match value {
+                    ValueRef::Array(values) => values,
+                    _ => return None,
+                };
+
+                let mut repeated = field.mut_repeated(message);
+                for value in values {
+                    repeated.push(read_reflect_value(&value_type, value)?);
+                }
+            }
+            RuntimeFieldType::Map(key_type, value_type) => {
+                let entries = match value {
+                    ValueRef::Map(entries) => entries,
+                    _ => return None,
+                };
+
+                let mut map = field.mut_map(message);
+                for (key, value) in entries {
+                    map.insert(
+                        read_reflect_value(&key_type, key)?,
+                        read_reflect_value(&value_type, value)?,
+                    );
+                }
+            }
+        }
+    }
+
+    Some(())
+}
+
+fn read_reflect_value(value_type: &RuntimeType, value: &ValueRef) -> Option<ReflectValueBox> {
+    Some(match (value_type, value) {
+        (RuntimeType::I32, ValueRef::Integer(value)) => {
+            ReflectValueBox::I32(value.as_i64()?.try_into().ok()?)
+        }
+        (RuntimeType::I64, ValueRef::Integer(value)) => ReflectValueBox::I64(value.as_i64()?),
+        (RuntimeType::U32, ValueRef::Integer(value)) => {
+            ReflectValueBox::U32(value.as_u64()?.try_into().ok()?)
+        }
+        (RuntimeType::U64, ValueRef::Integer(value)) => ReflectValueBox::U64(value.as_u64()?),
+        (RuntimeType::F32, ValueRef::F32(value)) => ReflectValueBox::F32(*value),
+        (RuntimeType::F64, ValueRef::F64(value)) => ReflectValueBox::F64(*value),
+        (RuntimeType::Bool, ValueRef::Boolean(value)) => ReflectValueBox::Bool(*value),
+        (RuntimeType::String, ValueRef::String(value)) => {
+            ReflectValueBox::String(value.as_str()?.to_string())
+        }
+        (RuntimeType::VecU8, ValueRef::Binary(value)) => ReflectValueBox::Bytes(value.to_vec()),
+        (RuntimeType::Enum(descriptor), ValueRef::Integer(value)) => {
+            ReflectValueBox::Enum(descriptor.clone(), value.as_i64()?.try_into().ok()?)
+        }
+        (RuntimeType::Message(descriptor), value) => {
+            let mut message = descriptor.new_instance();
+            merge_message(value, message.as_mut())?;
+            ReflectValueBox::Message(message)
+        }
+        _ => return None,
+    })
+}
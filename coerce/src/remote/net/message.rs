@@ -1,14 +1,35 @@
+//! Events exchanged between Coerce nodes
+//!
+//! Each [`SessionEvent`] (sent by a node's client to a peer's server) and [`ClientEvent`]
+//! (sent back by the server) is written as a single byte identifying the [`Event`] type, followed
+//! by the event, encoded by one of the codecs in [`encoding`] (protobuf by default, see
+//! `network.proto`). The encoded event is then framed by the [`FrameCodec`], prefixed with its
+//! length.
+//!
+//! The payloads carried by events (for example, the message within a [`MessageRequest`]) are
+//! opaque bytes, encoded by each message type's [`Message`](crate::actor::message::Message)
+//! implementation, so applications choose the encoding of their own messages independently of
+//! the wire encoding.
+//!
+//! [`FrameCodec`]: crate::remote::net::codec::FrameCodec
+//! [`encoding`]: crate::remote::net::encoding
+
 use crate::actor::message::{MessageUnwrapErr, MessageWrapErr};
 use crate::actor::{ActorRefErr, ToActorId};
+use crate::remote::net::client::WriteLane;
+use crate::remote::net::encoding::{MessageCodec, ProtobufCodec, WireFormat};
 use crate::remote::net::proto::network::{
     ActorAddress, ClientErr, ClientHandshake, ClientResult, CreateActorEvent, Event,
     FindActorEvent, IdentifyEvent, MessageRequest, NodeIdentity, PingEvent, PongEvent, RaftRequest,
     SessionHandshake, StreamPublishEvent,
 };
 use crate::remote::net::{proto, StreamData};
+use bytes::{BufMut, BytesMut};
 use chrono::{DateTime, NaiveDateTime, Utc};
-use protobuf::{Enum, Error, Message};
+use protobuf::{Enum, Error, Message, MessageDyn, MessageFull};
+use std::collections::hash_map::DefaultHasher;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -37,7 +58,98 @@ pub enum SessionEvent {
     Raft(RaftRequest),
 }
 
-impl SessionEvent {}
+impl SessionEvent {
+    /// Whether the event should be written via the priority lane of the remote link,
+    /// bypassing any normal traffic queued before it.
+    ///
+    /// Replies (results and errors) and system control events are prioritised, so outstanding
+    /// requests can still be resolved promptly, and failures detected, when the link is under
+    /// heavy load.
+    pub fn is_priority(&self) -> bool {
+        matches!(
+            self,
+            SessionEvent::Result(_)
+                | SessionEvent::Err(_)
+                | SessionEvent::Ping(_)
+                | SessionEvent::Pong(_)
+                | SessionEvent::Identify(_)
+                | SessionEvent::Handshake(_)
+                | SessionEvent::RegisterActor(_)
+                | SessionEvent::FindActor(_)
+                | SessionEvent::Raft(_)
+        )
+    }
+
+    /// The lane of the remote link the event is written via, see [`WriteLane`]
+    pub fn lane(&self) -> WriteLane {
+        if self.is_priority() {
+            WriteLane::System
+        } else {
+            WriteLane::User
+        }
+    }
+
+    /// The ordering key of an encoded event, see [`decode`](crate::remote::net::decode).
+    ///
+    /// Messages are keyed by the id of the actor they're sent to, which is read without decoding
+    /// the rest of the message, every other event shares the same key.
+    pub fn ordering_key(frame: &[u8]) -> u64 {
+        match read_message_request_field(frame, MESSAGE_REQUEST_ACTOR_ID) {
+            Some(actor_id) => {
+                let mut hasher = DefaultHasher::new();
+                actor_id.hash(&mut hasher);
+                hasher.finish()
+            }
+            None => 0,
+        }
+    }
+
+    /// The id of the message within an encoded [`SessionEvent::NotifyActor`], used to resolve the
+    /// pending request of a message that could not be written
+    pub fn message_request_id(frame: &[u8]) -> Option<String> {
+        read_message_request_field(frame, MESSAGE_REQUEST_MESSAGE_ID)
+    }
+
+    /// Writes the event into the buffer, encoded with the provided codec
+    pub fn write_to_buffer_with(&self, codec: &dyn MessageCodec, buffer: &mut BytesMut) -> bool {
+        match self {
+            SessionEvent::Handshake(e) => write_event_to_buffer(Event::Handshake, e, codec, buffer),
+            SessionEvent::Ping(e) => write_event_to_buffer(Event::Ping, e, codec, buffer),
+            SessionEvent::Pong(e) => write_event_to_buffer(Event::Pong, e, codec, buffer),
+            SessionEvent::RegisterActor(e) => {
+                write_event_to_buffer(Event::RegisterActor, e, codec, buffer)
+            }
+            SessionEvent::NotifyActor(e) => {
+                write_event_to_buffer(Event::NotifyActor, e, codec, buffer)
+            }
+            SessionEvent::FindActor(e) => write_event_to_buffer(Event::FindActor, e, codec, buffer),
+            SessionEvent::CreateActor(e) => {
+                write_event_to_buffer(Event::CreateActor, e, codec, buffer)
+            }
+            SessionEvent::StreamPublish(e) => {
+                write_event_to_buffer(Event::StreamPublish, e.as_ref(), codec, buffer)
+            }
+            SessionEvent::Result(e) => write_event_to_buffer(Event::Result, e, codec, buffer),
+            SessionEvent::Identify(e) => write_event_to_buffer(Event::Identify, e, codec, buffer),
+            SessionEvent::Err(e) => write_event_to_buffer(Event::Err, e, codec, buffer),
+            _ => false,
+        }
+    }
+}
+
+impl ClientEvent {
+    /// Writes the event into the buffer, encoded with the provided codec
+    pub fn write_to_buffer_with(&self, codec: &dyn MessageCodec, buffer: &mut BytesMut) -> bool {
+        match self {
+            ClientEvent::Identity(e) => write_event_to_buffer(Event::Identity, e, codec, buffer),
+            ClientEvent::Handshake(e) => write_event_to_buffer(Event::Handshake, e, codec, buffer),
+            ClientEvent::Result(e) => write_event_to_buffer(Event::Result, e, codec, buffer),
+            ClientEvent::Err(e) => write_event_to_buffer(Event::Err, e, codec, buffer),
+            ClientEvent::Ping(e) => write_event_to_buffer(Event::Ping, e, codec, buffer),
+            ClientEvent::Pong(e) => write_event_to_buffer(Event::Pong, e, codec, buffer),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct ClientError {
@@ -53,29 +165,22 @@ impl Display for ClientError {
 
 impl StreamData for ClientEvent {
     fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
-        match data.split_first() {
-            Some((event, message)) => match Event::from_i32(*event as i32) {
-                Some(Event::Identity) => Some(ClientEvent::Identity(
-                    NodeIdentity::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Handshake) => Some(ClientEvent::Handshake(
-                    ClientHandshake::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Result) => Some(ClientEvent::Result(
-                    ClientResult::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Err) => Some(ClientEvent::Err(
-                    ClientErr::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Ping) => Some(ClientEvent::Ping(
-                    PingEvent::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Pong) => Some(ClientEvent::Pong(
-                    PongEvent::parse_from_bytes(message).unwrap(),
-                )),
-                _ => None,
-            },
-            None => None,
+        Self::read_from_slice(&data)
+    }
+
+    fn read_from_slice(data: &[u8]) -> Option<Self> {
+        let (event, message) = data.split_first()?;
+        let (event, format) = WireFormat::from_event_byte(*event);
+        let codec = format.codec()?;
+
+        match Event::from_i32(event as i32) {
+            Some(Event::Identity) => decode_event(codec, message).map(ClientEvent::Identity),
+            Some(Event::Handshake) => decode_event(codec, message).map(ClientEvent::Handshake),
+            Some(Event::Result) => decode_event(codec, message).map(ClientEvent::Result),
+            Some(Event::Err) => decode_event(codec, message).map(ClientEvent::Err),
+            Some(Event::Ping) => decode_event(codec, message).map(ClientEvent::Ping),
+            Some(Event::Pong) => decode_event(codec, message).map(ClientEvent::Pong),
+            _ => None,
         }
     }
 
@@ -91,48 +196,39 @@ impl StreamData for ClientEvent {
 
         write_event(event_id, message)
     }
+
+    fn write_to_buffer(&self, buffer: &mut BytesMut) -> bool {
+        self.write_to_buffer_with(&ProtobufCodec, buffer)
+    }
 }
 
 impl StreamData for SessionEvent {
     fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
-        match data.split_first() {
-            Some((event, message)) => match Event::from_i32(*event as i32) {
-                Some(Event::Identify) => Some(SessionEvent::Identify(
-                    IdentifyEvent::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Handshake) => Some(SessionEvent::Handshake(
-                    SessionHandshake::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Ping) => Some(SessionEvent::Ping(
-                    PingEvent::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Pong) => Some(SessionEvent::Pong(
-                    PongEvent::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::CreateActor) => Some(SessionEvent::CreateActor(
-                    CreateActorEvent::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::FindActor) => Some(SessionEvent::FindActor(
-                    FindActorEvent::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::NotifyActor) => Some(SessionEvent::NotifyActor(
-                    MessageRequest::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::RegisterActor) => Some(SessionEvent::RegisterActor(
-                    ActorAddress::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::StreamPublish) => Some(SessionEvent::StreamPublish(Arc::new(
-                    StreamPublishEvent::parse_from_bytes(message).unwrap(),
-                ))),
-                Some(Event::Result) => Some(SessionEvent::Result(
-                    ClientResult::parse_from_bytes(message).unwrap(),
-                )),
-                Some(Event::Err) => Some(SessionEvent::Err(
-                    ClientErr::parse_from_bytes(message).unwrap(),
-                )),
-                _ => None,
-            },
-            None => None,
+        Self::read_from_slice(&data)
+    }
+
+    fn read_from_slice(data: &[u8]) -> Option<Self> {
+        let (event, message) = data.split_first()?;
+        let (event, format) = WireFormat::from_event_byte(*event);
+        let codec = format.codec()?;
+
+        match Event::from_i32(event as i32) {
+            Some(Event::Identify) => decode_event(codec, message).map(SessionEvent::Identify),
+            Some(Event::Handshake) => decode_event(codec, message).map(SessionEvent::Handshake),
+            Some(Event::Ping) => decode_event(codec, message).map(SessionEvent::Ping),
+            Some(Event::Pong) => decode_event(codec, message).map(SessionEvent::Pong),
+            Some(Event::CreateActor) => decode_event(codec, message).map(SessionEvent::CreateActor),
+            Some(Event::FindActor) => decode_event(codec, message).map(SessionEvent::FindActor),
+            Some(Event::NotifyActor) => decode_event(codec, message).map(SessionEvent::NotifyActor),
+            Some(Event::RegisterActor) => {
+                decode_event(codec, message).map(SessionEvent::RegisterActor)
+            }
+            Some(Event::StreamPublish) => {
+                decode_event(codec, message).map(|e| SessionEvent::StreamPublish(Arc::new(e)))
+            }
+            Some(Event::Result) => decode_event(codec, message).map(SessionEvent::Result),
+            Some(Event::Err) => decode_event(codec, message).map(SessionEvent::Err),
+            _ => None,
         }
     }
 
@@ -154,6 +250,33 @@ impl StreamData for SessionEvent {
 
         write_event(event_id, message)
     }
+
+    fn write_to_buffer(&self, buffer: &mut BytesMut) -> bool {
+        self.write_to_buffer_with(&ProtobufCodec, buffer)
+    }
+}
+
+/// The `message_id` field of an encoded [`MessageRequest`]
+const MESSAGE_REQUEST_MESSAGE_ID: u32 = 1;
+
+/// The `actor_id` field of an encoded [`MessageRequest`]
+const MESSAGE_REQUEST_ACTOR_ID: u32 = 3;
+
+/// Reads a single string field of an encoded [`SessionEvent::NotifyActor`], without decoding
+/// the rest of the message
+fn read_message_request_field(frame: &[u8], field: u32) -> Option<String> {
+    let (event, message) = frame.split_first()?;
+    let (event, format) = WireFormat::from_event_byte(*event);
+    if event as i32 != Event::NotifyActor as i32 {
+        return None;
+    }
+
+    format.codec()?.read_string_field(message, field)
+}
+
+fn decode_event<M: MessageFull>(codec: &dyn MessageCodec, message: &[u8]) -> Option<M> {
+    let mut event = M::new();
+    codec.decode(message, &mut event).then_some(event)
 }
 
 fn write_event(event_id: Event, message: Result<Vec<u8>, Error>) -> Option<Vec<u8>> {
@@ -166,6 +289,18 @@ fn write_event(event_id: Event, message: Result<Vec<u8>, Error>) -> Option<Vec<u
     }
 }
 
+/// Writes the event id followed by the message directly into the buffer,
+/// avoiding the intermediate allocation made by [`write_event`]
+fn write_event_to_buffer(
+    event_id: Event,
+    message: &dyn MessageDyn,
+    codec: &dyn MessageCodec,
+    buffer: &mut BytesMut,
+) -> bool {
+    buffer.put_u8(codec.format().event_byte(event_id as u8));
+    codec.encode(message, buffer)
+}
+
 pub fn datetime_to_timestamp(
     date_time: &DateTime<Utc>,
 ) -> protobuf::well_known_types::timestamp::Timestamp {
@@ -180,7 +315,8 @@ pub fn timestamp_to_datetime(
     timestamp: protobuf::well_known_types::timestamp::Timestamp,
 ) -> DateTime<Utc> {
     DateTime::<Utc>::from_utc(
-        NaiveDateTime::from_timestamp_opt(timestamp.seconds, timestamp.nanos as u32).unwrap(),
+        NaiveDateTime::from_timestamp_opt(timestamp.seconds, timestamp.nanos as u32)
+            .unwrap_or_default(),
         Utc,
     )
 }
@@ -241,6 +377,10 @@ impl From<ActorRefErr> for proto::network::ActorRefErr {
                 ErrorType::NotSupported
             }
             ActorRefErr::NotImplemented => ErrorType::NotImplemented,
+
+            // not part of the wire protocol, from the remote caller's point of view the actor
+            // is unable to accept the message
+            ActorRefErr::MailboxFull | ActorRefErr::WriteBufferFull => ErrorType::ActorUnavailable,
         }
         .into();
 
@@ -254,22 +394,27 @@ impl From<proto::network::ActorRefErr> for ActorRefErr {
         use proto::network::MessageUnwrapErr as ProtoUnwrapErr;
         use proto::network::MessageWrapErr as ProtoWrapErr;
 
-        match err.type_.unwrap() {
+        let error_type = match err.type_.enum_value() {
+            Ok(error_type) => error_type,
+            Err(_) => return ActorRefErr::Deserialisation(MessageUnwrapErr::Unknown),
+        };
+
+        match error_type {
             ErrorType::ActorUnavailable => ActorRefErr::ActorUnavailable,
             ErrorType::NotFound => ActorRefErr::NotFound(err.actor_id.to_actor_id()),
             ErrorType::AlreadyExists => ActorRefErr::AlreadyExists(err.actor_id.to_actor_id()),
             ErrorType::Serialisation => {
-                ActorRefErr::Serialisation(match err.serialization_error.unwrap() {
-                    ProtoWrapErr::WrapUnsupported => MessageWrapErr::NotTransmittable,
-                    ProtoWrapErr::SerializationErr => MessageWrapErr::SerializationErr,
-                    ProtoWrapErr::UnknownWrapErr => MessageWrapErr::Unknown,
+                ActorRefErr::Serialisation(match err.serialization_error.enum_value() {
+                    Ok(ProtoWrapErr::WrapUnsupported) => MessageWrapErr::NotTransmittable,
+                    Ok(ProtoWrapErr::SerializationErr) => MessageWrapErr::SerializationErr,
+                    Ok(ProtoWrapErr::UnknownWrapErr) | Err(_) => MessageWrapErr::Unknown,
                 })
             }
             ErrorType::Deserialisation => {
-                ActorRefErr::Deserialisation(match err.deserialization_error.unwrap() {
-                    ProtoUnwrapErr::UnwrapUnsupported => MessageUnwrapErr::NotTransmittable,
-                    ProtoUnwrapErr::DeserializationErr => MessageUnwrapErr::DeserializationErr,
-                    ProtoUnwrapErr::UnknownUnwrapErr => MessageUnwrapErr::Unknown,
+                ActorRefErr::Deserialisation(match err.deserialization_error.enum_value() {
+                    Ok(ProtoUnwrapErr::UnwrapUnsupported) => MessageUnwrapErr::NotTransmittable,
+                    Ok(ProtoUnwrapErr::DeserializationErr) => MessageUnwrapErr::DeserializationErr,
+                    Ok(ProtoUnwrapErr::UnknownUnwrapErr) | Err(_) => MessageUnwrapErr::Unknown,
                 })
             }
             ErrorType::Timeout => ActorRefErr::Timeout {
@@ -288,3 +433,28 @@ impl From<proto::network::ActorRefErr> for ActorRefErr {
         }
     }
 }
+
+impl From<ClientErr> for ActorRefErr {
+    fn from(err: ClientErr) -> Self {
+        err.error.into_option().map_or(
+            ActorRefErr::Deserialisation(MessageUnwrapErr::Unknown),
+            ActorRefErr::from,
+        )
+    }
+}
+
+/// Error frame sent to the peer when a frame received from it could not be decoded.
+///
+/// Decode failures are not attributable to a specific request, so the frame has no `message_id`.
+pub fn decode_failure_frame() -> ClientErr {
+    ClientErr {
+        error: Some(ActorRefErr::Deserialisation(MessageUnwrapErr::DeserializationErr).into())
+            .into(),
+        ..Default::default()
+    }
+}
+
+/// Whether the error frame was sent by a peer that failed to decode a frame sent by this node
+pub fn is_decode_failure_frame(err: &ClientErr) -> bool {
+    err.message_id.is_empty()
+}
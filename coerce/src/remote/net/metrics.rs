@@ -1,8 +1,15 @@
 pub const METRIC_NETWORK_BYTES_RECV: &str = "coerce_network_bytes_recv";
 pub const METRIC_NETWORK_BYTES_SENT: &str = "coerce_network_bytes_sent";
+pub const METRIC_NETWORK_DECODE_FAILURES: &str = "coerce_network_decode_failures";
+pub const METRIC_NETWORK_BUFFER_POOL_HITS: &str = "coerce_network_buffer_pool_hits";
+pub const METRIC_NETWORK_BUFFER_POOL_MISSES: &str = "coerce_network_buffer_pool_misses";
+pub const METRIC_NETWORK_WRITE_BUFFER_DROPPED: &str = "coerce_network_write_buffer_dropped";
+pub const METRIC_NETWORK_LOCAL_BYPASS_TOTAL: &str = "coerce_network_local_bypass_total";
 
 pub const LABEL_SRC_ADDR: &str = "src_addr";
 pub const LABEL_DEST_ADDR: &str = "dest_addr";
+pub const LABEL_ACTOR_TYPE: &str = "actor_type";
+pub const LABEL_MESSAGE_TYPE: &str = "msg_type";
 
 pub struct NetworkMetrics;
 
@@ -26,4 +33,47 @@ impl NetworkMetrics {
             LABEL_DEST_ADDR => dest_addr.to_owned()
         );
     }
+
+    #[inline]
+    pub fn incr_decode_failures(src_addr: &str) {
+        #[cfg(feature = "metrics")]
+        increment_counter!(
+            METRIC_NETWORK_DECODE_FAILURES,
+            LABEL_SRC_ADDR => src_addr.to_owned()
+        );
+    }
+
+    #[inline]
+    pub fn incr_buffer_pool_hits() {
+        #[cfg(feature = "metrics")]
+        increment_counter!(METRIC_NETWORK_BUFFER_POOL_HITS);
+    }
+
+    #[inline]
+    pub fn incr_buffer_pool_misses() {
+        #[cfg(feature = "metrics")]
+        increment_counter!(METRIC_NETWORK_BUFFER_POOL_MISSES);
+    }
+
+    #[inline]
+    pub fn incr_write_buffer_dropped(count: u64, dest_addr: &str) {
+        #[cfg(feature = "metrics")]
+        counter!(
+            METRIC_NETWORK_WRITE_BUFFER_DROPPED,
+            count,
+            LABEL_DEST_ADDR => dest_addr.to_owned()
+        );
+    }
+
+    /// Counts messages sent via a remote reference to an actor on this node,
+    /// which were dispatched locally rather than serialised
+    #[inline]
+    pub fn incr_local_bypass(actor_type: &'static str, msg_type: &'static str) {
+        #[cfg(feature = "metrics")]
+        increment_counter!(
+            METRIC_NETWORK_LOCAL_BYPASS_TOTAL,
+            LABEL_ACTOR_TYPE => actor_type,
+            LABEL_MESSAGE_TYPE => msg_type
+        );
+    }
 }
@@ -3,24 +3,106 @@ use crate::remote::system::RemoteActorSystem;
 use std::future::Future;
 use std::io::Error;
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+use crate::remote::net::chunk::{ChunkAssembler, Chunker, ChunkingConfig};
+use crate::remote::net::compression::{decompress_frame, FrameCompressor};
+use crate::remote::net::decode::{DecodePool, Decoded};
 use futures::StreamExt;
-use tokio_util::codec::{FramedRead, LengthDelimitedCodec};
+use tokio_util::codec::{Decoder, Encoder, FramedRead, LengthDelimitedCodec};
 
+pub mod buffer;
+pub mod chunk;
 pub mod client;
+pub mod compression;
+pub mod decode;
+pub mod encoding;
 pub mod message;
 pub mod metrics;
 pub mod proto;
 pub mod security;
 pub mod server;
+pub mod stream;
+pub mod unhandled;
+pub mod version;
+
+#[cfg(feature = "tls")]
+pub mod tls;
+
+pub use coerce_core::codec;
+pub use coerce_core::StreamData;
+
+/// Length-delimited frames, compressed once compression has been negotiated for the connection.
+/// Chunked messages are reassembled before they're decoded, see [`chunk`].
+#[derive(Debug, Clone)]
+pub struct NetworkCodec {
+    frames: LengthDelimitedCodec,
+    compressor: Option<FrameCompressor>,
+    chunker: Chunker,
+    chunks: ChunkAssembler,
+}
+
+impl NetworkCodec {
+    pub fn new() -> Self {
+        let chunking = ChunkingConfig::default();
+        Self {
+            frames: LengthDelimitedCodec::new(),
+            compressor: None,
+            chunker: Chunker::new(&chunking),
+            chunks: ChunkAssembler::new(&chunking),
+        }
+    }
+
+    /// Must be set before any frames are read or written
+    pub(crate) fn set_chunking(&mut self, config: &ChunkingConfig) {
+        self.chunker = Chunker::new(config);
+        self.chunks = ChunkAssembler::new(config);
+    }
+
+    pub(crate) fn set_compressor(&mut self, compressor: FrameCompressor) {
+        self.compressor = Some(compressor);
+    }
+
+    /// Splits the frame into chunks if it's too large to be written as a single frame
+    pub(crate) fn split(&mut self, frame: &Bytes) -> Option<Vec<Bytes>> {
+        self.chunker.split(frame)
+    }
+}
+
+impl Default for NetworkCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Encoder<Bytes> for NetworkCodec {
+    type Error = Error;
 
-pub trait StreamData: 'static + Send + Sync + Sized {
-    fn read_from_bytes(data: Vec<u8>) -> Option<Self>;
+    fn encode(&mut self, frame: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let frame = match &self.compressor {
+            Some(compressor) => compressor.compress(frame),
+            None => frame,
+        };
 
-    fn write_to_bytes(&self) -> Option<Vec<u8>>;
+        self.frames.encode(frame, dst)
+    }
+}
+
+impl Decoder for NetworkCodec {
+    type Item = BytesMut;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        while let Some(frame) = self.frames.decode(src)? {
+            if let Some(frame) = self.chunks.receive(decompress_frame(frame))? {
+                return Ok(Some(frame));
+            }
+        }
+
+        Ok(None)
+    }
 }
 
 #[async_trait]
@@ -31,7 +113,13 @@ pub trait StreamReceiver {
 
     async fn on_close(&mut self, sys: &RemoteActorSystem);
 
-    fn on_deserialisation_failed(&mut self);
+    fn on_deserialisation_failed(&mut self, frame: &[u8], sys: &RemoteActorSystem);
+
+    /// Frames that share an ordering key are delivered in the order they were received, frames
+    /// with different keys may be delivered out of order, see [`decode`]
+    fn ordering_key(&self, _frame: &[u8]) -> u64 {
+        0
+    }
 
     fn on_stream_lost(&mut self, error: Error);
 
@@ -41,13 +129,13 @@ pub trait StreamReceiver {
 }
 
 pub struct StreamReceiverFuture<S: tokio::io::AsyncRead> {
-    stream: FramedRead<S, LengthDelimitedCodec>,
+    stream: FramedRead<S, NetworkCodec>,
     stop_rx: tokio::sync::oneshot::Receiver<bool>,
 }
 
 impl<S: tokio::io::AsyncRead> StreamReceiverFuture<S> {
     pub fn new(
-        stream: FramedRead<S, LengthDelimitedCodec>,
+        stream: FramedRead<S, NetworkCodec>,
         stop_rx: tokio::sync::oneshot::Receiver<bool>,
     ) -> StreamReceiverFuture<S> {
         StreamReceiverFuture { stream, stop_rx }
@@ -81,29 +169,53 @@ where
 
 pub async fn receive_loop<R: StreamReceiver, S: tokio::io::AsyncRead + Unpin>(
     mut system: RemoteActorSystem,
-    read: FramedRead<S, LengthDelimitedCodec>,
+    read: FramedRead<S, NetworkCodec>,
     mut receiver: R,
 ) where
     R: Send,
 {
     let mut reader = read;
-    while let Some(res) = reader.next().await {
-        match res {
-            Ok(res) => match R::Message::read_from_bytes(res.to_vec()) {
-                Some(msg) => {
-                    receiver.on_receive(msg, &system).await;
-                    if receiver.should_close() {
-                        break;
+    let mut decoder = DecodePool::<R::Message>::new(system.config().decoding());
+    let mut reading = true;
+
+    // frames already handed to the decoder are still delivered once the stream has ended
+    while reading || !decoder.is_idle() {
+        let decoded = tokio::select! {
+            biased;
+
+            Some(decoded) = decoder.next(), if !decoder.is_idle() => decoded,
+
+            res = reader.next(), if reading && !decoder.is_saturated() => match res {
+                Some(Ok(frame)) => {
+                    let key = receiver.ordering_key(&frame);
+                    match decoder.decode(frame, key) {
+                        Some(decoded) => decoded,
+                        None => continue,
                     }
                 }
+                Some(Err(e)) => {
+                    receiver.on_stream_lost(e);
+                    reading = false;
+                    continue;
+                }
                 None => {
-                    // TODO: either pass the buffer into here or more context, this is pretty useless at the moment..
-                    receiver.on_deserialisation_failed();
+                    reading = false;
+                    continue;
                 }
             },
-            Err(e) => {
-                receiver.on_stream_lost(e);
-                break;
+
+            else => break,
+        };
+
+        match decoded {
+            Decoded::Message(msg) => {
+                receiver.on_receive(msg, &system).await;
+                if receiver.should_close() {
+                    break;
+                }
+            }
+            Decoded::Failed(frame) => {
+                receiver.on_deserialisation_failed(&frame, &system);
             }
         }
     }
@@ -39,6 +39,16 @@ pub struct RemoteNode {
     pub tag: ::std::string::String,
     // @@protoc_insertion_point(field:coerce.network.RemoteNode.attributes)
     pub attributes: ::std::collections::HashMap<::std::string::String, ::std::string::String>,
+    // @@protoc_insertion_point(field:coerce.network.RemoteNode.roles)
+    pub roles: ::std::vec::Vec<::std::string::String>,
+    // @@protoc_insertion_point(field:coerce.network.RemoteNode.crate_version)
+    pub crate_version: ::std::string::String,
+    // @@protoc_insertion_point(field:coerce.network.RemoteNode.min_protocol_version)
+    pub min_protocol_version: u32,
+    // @@protoc_insertion_point(field:coerce.network.RemoteNode.max_protocol_version)
+    pub max_protocol_version: u32,
+    // @@protoc_insertion_point(field:coerce.network.RemoteNode.features)
+    pub features: ::std::vec::Vec<::std::string::String>,
     // special fields
     // @@protoc_insertion_point(special_field:coerce.network.RemoteNode.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -56,7 +66,7 @@ impl RemoteNode {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(5);
+        let mut fields = ::std::vec::Vec::with_capacity(10);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "node_id",
@@ -83,6 +93,31 @@ impl RemoteNode {
             |m: &RemoteNode| { &m.attributes },
             |m: &mut RemoteNode| { &mut m.attributes },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "roles",
+            |m: &RemoteNode| { &m.roles },
+            |m: &mut RemoteNode| { &mut m.roles },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "crate_version",
+            |m: &RemoteNode| { &m.crate_version },
+            |m: &mut RemoteNode| { &mut m.crate_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "min_protocol_version",
+            |m: &RemoteNode| { &m.min_protocol_version },
+            |m: &mut RemoteNode| { &mut m.min_protocol_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "max_protocol_version",
+            |m: &RemoteNode| { &m.max_protocol_version },
+            |m: &mut RemoteNode| { &mut m.max_protocol_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "features",
+            |m: &RemoteNode| { &m.features },
+            |m: &mut RemoteNode| { &mut m.features },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<RemoteNode>(
             "RemoteNode",
             fields,
@@ -128,6 +163,21 @@ impl ::protobuf::Message for RemoteNode {
                     is.pop_limit(old_limit);
                     self.attributes.insert(key, value);
                 },
+                50 => {
+                    self.roles.push(is.read_string()?);
+                },
+                58 => {
+                    self.crate_version = is.read_string()?;
+                },
+                64 => {
+                    self.min_protocol_version = is.read_uint32()?;
+                },
+                72 => {
+                    self.max_protocol_version = is.read_uint32()?;
+                },
+                82 => {
+                    self.features.push(is.read_string()?);
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -159,6 +209,21 @@ impl ::protobuf::Message for RemoteNode {
             entry_size += ::protobuf::rt::string_size(2, &v);
             my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(entry_size) + entry_size
         };
+        for value in &self.roles {
+            my_size += ::protobuf::rt::string_size(6, &value);
+        };
+        if !self.crate_version.is_empty() {
+            my_size += ::protobuf::rt::string_size(7, &self.crate_version);
+        }
+        if self.min_protocol_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(8, self.min_protocol_version);
+        }
+        if self.max_protocol_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(9, self.max_protocol_version);
+        }
+        for value in &self.features {
+            my_size += ::protobuf::rt::string_size(10, &value);
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -186,6 +251,21 @@ impl ::protobuf::Message for RemoteNode {
             os.write_string(1, &k)?;
             os.write_string(2, &v)?;
         };
+        for v in &self.roles {
+            os.write_string(6, &v)?;
+        };
+        if !self.crate_version.is_empty() {
+            os.write_string(7, &self.crate_version)?;
+        }
+        if self.min_protocol_version != 0 {
+            os.write_uint32(8, self.min_protocol_version)?;
+        }
+        if self.max_protocol_version != 0 {
+            os.write_uint32(9, self.max_protocol_version)?;
+        }
+        for v in &self.features {
+            os.write_string(10, &v)?;
+        };
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -208,6 +288,11 @@ impl ::protobuf::Message for RemoteNode {
         self.node_started_at.clear();
         self.tag.clear();
         self.attributes.clear();
+        self.roles.clear();
+        self.crate_version.clear();
+        self.min_protocol_version = 0;
+        self.max_protocol_version = 0;
+        self.features.clear();
         self.special_fields.clear();
     }
 
@@ -244,6 +329,12 @@ pub struct IdentifyEvent {
     pub source_node_tag: ::std::string::String,
     // @@protoc_insertion_point(field:coerce.network.IdentifyEvent.token)
     pub token: ::std::string::String,
+    // @@protoc_insertion_point(field:coerce.network.IdentifyEvent.min_protocol_version)
+    pub min_protocol_version: u32,
+    // @@protoc_insertion_point(field:coerce.network.IdentifyEvent.max_protocol_version)
+    pub max_protocol_version: u32,
+    // @@protoc_insertion_point(field:coerce.network.IdentifyEvent.compression)
+    pub compression: ::std::vec::Vec<u32>,
     // special fields
     // @@protoc_insertion_point(special_field:coerce.network.IdentifyEvent.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -261,7 +352,7 @@ impl IdentifyEvent {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(3);
+        let mut fields = ::std::vec::Vec::with_capacity(6);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "source_node_id",
@@ -278,6 +369,21 @@ impl IdentifyEvent {
             |m: &IdentifyEvent| { &m.token },
             |m: &mut IdentifyEvent| { &mut m.token },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "min_protocol_version",
+            |m: &IdentifyEvent| { &m.min_protocol_version },
+            |m: &mut IdentifyEvent| { &mut m.min_protocol_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "max_protocol_version",
+            |m: &IdentifyEvent| { &m.max_protocol_version },
+            |m: &mut IdentifyEvent| { &mut m.max_protocol_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "compression",
+            |m: &IdentifyEvent| { &m.compression },
+            |m: &mut IdentifyEvent| { &mut m.compression },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<IdentifyEvent>(
             "IdentifyEvent",
             fields,
@@ -305,6 +411,18 @@ impl ::protobuf::Message for IdentifyEvent {
                 26 => {
                     self.token = is.read_string()?;
                 },
+                32 => {
+                    self.min_protocol_version = is.read_uint32()?;
+                },
+                40 => {
+                    self.max_protocol_version = is.read_uint32()?;
+                },
+                50 => {
+                    is.read_repeated_packed_uint32_into(&mut self.compression)?;
+                },
+                48 => {
+                    self.compression.push(is.read_uint32()?);
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -326,6 +444,15 @@ impl ::protobuf::Message for IdentifyEvent {
         if !self.token.is_empty() {
             my_size += ::protobuf::rt::string_size(3, &self.token);
         }
+        if self.min_protocol_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(4, self.min_protocol_version);
+        }
+        if self.max_protocol_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(5, self.max_protocol_version);
+        }
+        for value in &self.compression {
+            my_size += ::protobuf::rt::uint32_size(6, *value);
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -341,6 +468,15 @@ impl ::protobuf::Message for IdentifyEvent {
         if !self.token.is_empty() {
             os.write_string(3, &self.token)?;
         }
+        if self.min_protocol_version != 0 {
+            os.write_uint32(4, self.min_protocol_version)?;
+        }
+        if self.max_protocol_version != 0 {
+            os.write_uint32(5, self.max_protocol_version)?;
+        }
+        for v in &self.compression {
+            os.write_uint32(6, *v)?;
+        };
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -361,6 +497,9 @@ impl ::protobuf::Message for IdentifyEvent {
         self.source_node_id = 0;
         self.source_node_tag.clear();
         self.token.clear();
+        self.min_protocol_version = 0;
+        self.max_protocol_version = 0;
+        self.compression.clear();
         self.special_fields.clear();
     }
 
@@ -369,6 +508,9 @@ impl ::protobuf::Message for IdentifyEvent {
             source_node_id: 0,
             source_node_tag: ::std::string::String::new(),
             token: ::std::string::String::new(),
+            min_protocol_version: 0,
+            max_protocol_version: 0,
+            compression: ::std::vec::Vec::new(),
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
@@ -414,6 +556,18 @@ pub struct NodeIdentity {
     pub capabilities: ::protobuf::MessageField<SystemCapabilities>,
     // @@protoc_insertion_point(field:coerce.network.NodeIdentity.attributes)
     pub attributes: ::std::collections::HashMap<::std::string::String, ::std::string::String>,
+    // @@protoc_insertion_point(field:coerce.network.NodeIdentity.roles)
+    pub roles: ::std::vec::Vec<::std::string::String>,
+    // @@protoc_insertion_point(field:coerce.network.NodeIdentity.min_protocol_version)
+    pub min_protocol_version: u32,
+    // @@protoc_insertion_point(field:coerce.network.NodeIdentity.max_protocol_version)
+    pub max_protocol_version: u32,
+    // @@protoc_insertion_point(field:coerce.network.NodeIdentity.compression)
+    pub compression: u32,
+    // @@protoc_insertion_point(field:coerce.network.NodeIdentity.crate_version)
+    pub crate_version: ::std::string::String,
+    // @@protoc_insertion_point(field:coerce.network.NodeIdentity.features)
+    pub features: ::std::vec::Vec<::std::string::String>,
     // special fields
     // @@protoc_insertion_point(special_field:coerce.network.NodeIdentity.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -431,7 +585,7 @@ impl NodeIdentity {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(9);
+        let mut fields = ::std::vec::Vec::with_capacity(15);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "node_id",
@@ -478,6 +632,36 @@ impl NodeIdentity {
             |m: &NodeIdentity| { &m.attributes },
             |m: &mut NodeIdentity| { &mut m.attributes },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "roles",
+            |m: &NodeIdentity| { &m.roles },
+            |m: &mut NodeIdentity| { &mut m.roles },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "min_protocol_version",
+            |m: &NodeIdentity| { &m.min_protocol_version },
+            |m: &mut NodeIdentity| { &mut m.min_protocol_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "max_protocol_version",
+            |m: &NodeIdentity| { &m.max_protocol_version },
+            |m: &mut NodeIdentity| { &mut m.max_protocol_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "compression",
+            |m: &NodeIdentity| { &m.compression },
+            |m: &mut NodeIdentity| { &mut m.compression },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "crate_version",
+            |m: &NodeIdentity| { &m.crate_version },
+            |m: &mut NodeIdentity| { &mut m.crate_version },
+        ));
+        fields.push(::protobuf::reflect::rt::v2::make_vec_simpler_accessor::<_, _>(
+            "features",
+            |m: &NodeIdentity| { &m.features },
+            |m: &mut NodeIdentity| { &mut m.features },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<NodeIdentity>(
             "NodeIdentity",
             fields,
@@ -535,6 +719,24 @@ impl ::protobuf::Message for NodeIdentity {
                     is.pop_limit(old_limit);
                     self.attributes.insert(key, value);
                 },
+                82 => {
+                    self.roles.push(is.read_string()?);
+                },
+                88 => {
+                    self.min_protocol_version = is.read_uint32()?;
+                },
+                96 => {
+                    self.max_protocol_version = is.read_uint32()?;
+                },
+                104 => {
+                    self.compression = is.read_uint32()?;
+                },
+                114 => {
+                    self.crate_version = is.read_string()?;
+                },
+                122 => {
+                    self.features.push(is.read_string()?);
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -580,6 +782,24 @@ impl ::protobuf::Message for NodeIdentity {
             entry_size += ::protobuf::rt::string_size(2, &v);
             my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(entry_size) + entry_size
         };
+        for value in &self.roles {
+            my_size += ::protobuf::rt::string_size(10, &value);
+        };
+        if self.min_protocol_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(11, self.min_protocol_version);
+        }
+        if self.max_protocol_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(12, self.max_protocol_version);
+        }
+        if self.compression != 0 {
+            my_size += ::protobuf::rt::uint32_size(13, self.compression);
+        }
+        if !self.crate_version.is_empty() {
+            my_size += ::protobuf::rt::string_size(14, &self.crate_version);
+        }
+        for value in &self.features {
+            my_size += ::protobuf::rt::string_size(15, &value);
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -619,6 +839,24 @@ impl ::protobuf::Message for NodeIdentity {
             os.write_string(1, &k)?;
             os.write_string(2, &v)?;
         };
+        for v in &self.roles {
+            os.write_string(10, &v)?;
+        };
+        if self.min_protocol_version != 0 {
+            os.write_uint32(11, self.min_protocol_version)?;
+        }
+        if self.max_protocol_version != 0 {
+            os.write_uint32(12, self.max_protocol_version)?;
+        }
+        if self.compression != 0 {
+            os.write_uint32(13, self.compression)?;
+        }
+        if !self.crate_version.is_empty() {
+            os.write_string(14, &self.crate_version)?;
+        }
+        for v in &self.features {
+            os.write_string(15, &v)?;
+        };
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -645,6 +883,12 @@ impl ::protobuf::Message for NodeIdentity {
         self.peers.clear();
         self.capabilities.clear();
         self.attributes.clear();
+        self.roles.clear();
+        self.min_protocol_version = 0;
+        self.max_protocol_version = 0;
+        self.compression = 0;
+        self.crate_version.clear();
+        self.features.clear();
         self.special_fields.clear();
     }
 
@@ -1508,6 +1752,8 @@ pub struct PongEvent {
     pub message_id: ::std::string::String,
     // @@protoc_insertion_point(field:coerce.network.PongEvent.trace_id)
     pub trace_id: ::std::string::String,
+    // @@protoc_insertion_point(field:coerce.network.PongEvent.maintenance)
+    pub maintenance: bool,
     // special fields
     // @@protoc_insertion_point(special_field:coerce.network.PongEvent.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -1525,7 +1771,7 @@ impl PongEvent {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(2);
+        let mut fields = ::std::vec::Vec::with_capacity(3);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "message_id",
@@ -1537,6 +1783,11 @@ impl PongEvent {
             |m: &PongEvent| { &m.trace_id },
             |m: &mut PongEvent| { &mut m.trace_id },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "maintenance",
+            |m: &PongEvent| { &m.maintenance },
+            |m: &mut PongEvent| { &mut m.maintenance },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<PongEvent>(
             "PongEvent",
             fields,
@@ -1561,6 +1812,9 @@ impl ::protobuf::Message for PongEvent {
                 18 => {
                     self.trace_id = is.read_string()?;
                 },
+                24 => {
+                    self.maintenance = is.read_bool()?;
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -1579,6 +1833,9 @@ impl ::protobuf::Message for PongEvent {
         if !self.trace_id.is_empty() {
             my_size += ::protobuf::rt::string_size(2, &self.trace_id);
         }
+        if self.maintenance != false {
+            my_size += 1 + 1;
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -1591,6 +1848,9 @@ impl ::protobuf::Message for PongEvent {
         if !self.trace_id.is_empty() {
             os.write_string(2, &self.trace_id)?;
         }
+        if self.maintenance != false {
+            os.write_bool(3, self.maintenance)?;
+        }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -1610,6 +1870,7 @@ impl ::protobuf::Message for PongEvent {
     fn clear(&mut self) {
         self.message_id.clear();
         self.trace_id.clear();
+        self.maintenance = false;
         self.special_fields.clear();
     }
 
@@ -1617,6 +1878,7 @@ impl ::protobuf::Message for PongEvent {
         static instance: PongEvent = PongEvent {
             message_id: ::std::string::String::new(),
             trace_id: ::std::string::String::new(),
+            maintenance: false,
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
@@ -2169,6 +2431,8 @@ pub struct MessageRequest {
     pub requires_response: bool,
     // @@protoc_insertion_point(field:coerce.network.MessageRequest.origin_node_id)
     pub origin_node_id: u64,
+    // @@protoc_insertion_point(field:coerce.network.MessageRequest.message_version)
+    pub message_version: u32,
     // special fields
     // @@protoc_insertion_point(special_field:coerce.network.MessageRequest.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -2186,7 +2450,7 @@ impl MessageRequest {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(7);
+        let mut fields = ::std::vec::Vec::with_capacity(8);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "message_id",
@@ -2223,6 +2487,11 @@ impl MessageRequest {
             |m: &MessageRequest| { &m.origin_node_id },
             |m: &mut MessageRequest| { &mut m.origin_node_id },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "message_version",
+            |m: &MessageRequest| { &m.message_version },
+            |m: &mut MessageRequest| { &mut m.message_version },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<MessageRequest>(
             "MessageRequest",
             fields,
@@ -2262,6 +2531,9 @@ impl ::protobuf::Message for MessageRequest {
                 56 => {
                     self.origin_node_id = is.read_uint64()?;
                 },
+                64 => {
+                    self.message_version = is.read_uint32()?;
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -2295,6 +2567,9 @@ impl ::protobuf::Message for MessageRequest {
         if self.origin_node_id != 0 {
             my_size += ::protobuf::rt::uint64_size(7, self.origin_node_id);
         }
+        if self.message_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(8, self.message_version);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -2322,6 +2597,9 @@ impl ::protobuf::Message for MessageRequest {
         if self.origin_node_id != 0 {
             os.write_uint64(7, self.origin_node_id)?;
         }
+        if self.message_version != 0 {
+            os.write_uint32(8, self.message_version)?;
+        }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -2346,6 +2624,7 @@ impl ::protobuf::Message for MessageRequest {
         self.trace_id.clear();
         self.requires_response = false;
         self.origin_node_id = 0;
+        self.message_version = 0;
         self.special_fields.clear();
     }
 
@@ -2358,6 +2637,7 @@ impl ::protobuf::Message for MessageRequest {
             trace_id: ::std::string::String::new(),
             requires_response: false,
             origin_node_id: 0,
+            message_version: 0,
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
@@ -4168,63 +4448,77 @@ impl MessageWrapErr {
 
 static file_descriptor_proto_data: &'static [u8] = b"\
     \n\rnetwork.proto\x12\x0ecoerce.network\x1a\x1egoogle/protobuf/wrappers.\
-    proto\x1a\x1fgoogle/protobuf/timestamp.proto\"\x9a\x02\n\nRemoteNode\x12\
+    proto\x1a\x1fgoogle/protobuf/timestamp.proto\"\xd5\x03\n\nRemoteNode\x12\
     \x17\n\x07node_id\x18\x01\x20\x01(\x04R\x06nodeId\x12\x12\n\x04addr\x18\
     \x02\x20\x01(\tR\x04addr\x12B\n\x0fnode_started_at\x18\x03\x20\x01(\x0b2\
     \x1a.google.protobuf.TimestampR\rnodeStartedAt\x12\x10\n\x03tag\x18\x04\
     \x20\x01(\tR\x03tag\x12J\n\nattributes\x18\x05\x20\x03(\x0b2*.coerce.net\
-    work.RemoteNode.AttributesEntryR\nattributes\x1a=\n\x0fAttributesEntry\
-    \x12\x10\n\x03key\x18\x01\x20\x01(\tR\x03key\x12\x14\n\x05value\x18\x02\
-    \x20\x01(\tR\x05value:\x028\x01\"s\n\rIdentifyEvent\x12$\n\x0esource_nod\
-    e_id\x18\x01\x20\x01(\x04R\x0csourceNodeId\x12&\n\x0fsource_node_tag\x18\
-    \x02\x20\x01(\tR\rsourceNodeTag\x12\x14\n\x05token\x18\x03\x20\x01(\tR\
-    \x05token\"\xfd\x03\n\x0cNodeIdentity\x12\x17\n\x07node_id\x18\x01\x20\
-    \x01(\x04R\x06nodeId\x12\x19\n\x08node_tag\x18\x02\x20\x01(\tR\x07nodeTa\
-    g\x12\x12\n\x04addr\x18\x03\x20\x01(\tR\x04addr\x12/\n\x13application_ve\
-    rsion\x18\x04\x20\x01(\tR\x12applicationVersion\x12)\n\x10protocol_versi\
-    on\x18\x05\x20\x01(\tR\x0fprotocolVersion\x12B\n\x0fnode_started_at\x18\
-    \x06\x20\x01(\x0b2\x1a.google.protobuf.TimestampR\rnodeStartedAt\x120\n\
-    \x05peers\x18\x07\x20\x03(\x0b2\x1a.coerce.network.RemoteNodeR\x05peers\
-    \x12F\n\x0ccapabilities\x18\x08\x20\x01(\x0b2\".coerce.network.SystemCap\
-    abilitiesR\x0ccapabilities\x12L\n\nattributes\x18\t\x20\x03(\x0b2,.coerc\
-    e.network.NodeIdentity.AttributesEntryR\nattributes\x1a=\n\x0fAttributes\
-    Entry\x12\x10\n\x03key\x18\x01\x20\x01(\tR\x03key\x12\x14\n\x05value\x18\
-    \x02\x20\x01(\tR\x05value:\x028\x01\"H\n\x12SystemCapabilities\x12\x16\n\
-    \x06actors\x18\x01\x20\x03(\tR\x06actors\x12\x1a\n\x08messages\x18\x02\
-    \x20\x03(\tR\x08messages\"\xd6\x01\n\x0fClientHandshake\x12\x17\n\x07nod\
-    e_id\x18\x01\x20\x01(\x04R\x06nodeId\x120\n\x05nodes\x18\x02\x20\x03(\
-    \x0b2\x1a.coerce.network.RemoteNodeR\x05nodes\x12\x19\n\x08node_tag\x18\
-    \x03\x20\x01(\tR\x07nodeTag\x12\x19\n\x08trace_id\x18\x04\x20\x01(\tR\
-    \x07traceId\x12B\n\x0fnode_started_at\x18\x05\x20\x01(\x0b2\x1a.google.p\
-    rotobuf.TimestampR\rnodeStartedAt\"`\n\x0cClientResult\x12\x1d\n\nmessag\
-    e_id\x18\x01\x20\x01(\tR\tmessageId\x12\x16\n\x06result\x18\x02\x20\x01(\
-    \x0cR\x06result\x12\x19\n\x08trace_id\x18\x03\x20\x01(\tR\x07traceId\"x\
-    \n\tClientErr\x12\x1d\n\nmessage_id\x18\x01\x20\x01(\tR\tmessageId\x121\
-    \n\x05error\x18\x02\x20\x01(\x0b2\x1b.coerce.network.ActorRefErrR\x05err\
-    or\x12\x19\n\x08trace_id\x18\x03\x20\x01(\tR\x07traceId\"\x8b\x01\n\tPin\
-    gEvent\x12\x1d\n\nmessage_id\x18\x01\x20\x01(\tR\tmessageId\x12\x19\n\
-    \x08trace_id\x18\x02\x20\x01(\tR\x07traceId\x12\x17\n\x07node_id\x18\x03\
-    \x20\x01(\x04R\x06nodeId\x12+\n\x11system_terminated\x18\x04\x20\x01(\
-    \x08R\x10systemTerminated\"E\n\tPongEvent\x12\x1d\n\nmessage_id\x18\x01\
-    \x20\x01(\tR\tmessageId\x12\x19\n\x08trace_id\x18\x02\x20\x01(\tR\x07tra\
-    ceId\"\x9e\x01\n\x10CreateActorEvent\x12\x1d\n\nmessage_id\x18\x01\x20\
-    \x01(\tR\tmessageId\x12\x19\n\x08actor_id\x18\x02\x20\x01(\tR\x07actorId\
-    \x12\x1d\n\nactor_type\x18\x03\x20\x01(\tR\tactorType\x12\x16\n\x06recip\
-    e\x18\x04\x20\x01(\x0cR\x06recipe\x12\x19\n\x08trace_id\x18\x05\x20\x01(\
-    \tR\x07traceId\"e\n\x0eFindActorEvent\x12\x1d\n\nmessage_id\x18\x01\x20\
-    \x01(\tR\tmessageId\x12\x19\n\x08actor_id\x18\x02\x20\x01(\tR\x07actorId\
-    \x12\x19\n\x08trace_id\x18\x03\x20\x01(\tR\x07traceId\"{\n\x0cActorAddre\
-    ss\x12\x19\n\x08actor_id\x18\x01\x20\x01(\tR\x07actorId\x125\n\x07node_i\
-    d\x18\x02\x20\x01(\x0b2\x1c.google.protobuf.UInt64ValueR\x06nodeId\x12\
-    \x19\n\x08trace_id\x18\x03\x20\x01(\tR\x07traceId\"\xf5\x01\n\x0eMessage\
-    Request\x12\x1d\n\nmessage_id\x18\x01\x20\x01(\tR\tmessageId\x12!\n\x0ch\
-    andler_type\x18\x02\x20\x01(\tR\x0bhandlerType\x12\x19\n\x08actor_id\x18\
-    \x03\x20\x01(\tR\x07actorId\x12\x18\n\x07message\x18\x04\x20\x01(\x0cR\
-    \x07message\x12\x19\n\x08trace_id\x18\x05\x20\x01(\tR\x07traceId\x12+\n\
-    \x11requires_response\x18\x06\x20\x01(\x08R\x10requiresResponse\x12$\n\
-    \x0eorigin_node_id\x18\x07\x20\x01(\x04R\x0coriginNodeId\"\xe6\x01\n\x10\
-    SessionHandshake\x12\x17\n\x07node_id\x18\x01\x20\x01(\x04R\x06nodeId\
-    \x120\n\x05nodes\x18\x02\x20\x03(\x0b2\x1a.coerce.network.RemoteNodeR\
+    work.RemoteNode.AttributesEntryR\nattributes\x12\x14\n\x05roles\x18\x06\
+    \x20\x03(\tR\x05roles\x12#\n\rcrate_version\x18\x07\x20\x01(\tR\x0ccrate\
+    Version\x120\n\x14min_protocol_version\x18\x08\x20\x01(\rR\x12minProtoco\
+    lVersion\x120\n\x14max_protocol_version\x18\t\x20\x01(\rR\x12maxProtocol\
+    Version\x12\x1a\n\x08features\x18\n\x20\x03(\tR\x08features\x1a=\n\x0fAt\
+    tributesEntry\x12\x10\n\x03key\x18\x01\x20\x01(\tR\x03key\x12\x14\n\x05v\
+    alue\x18\x02\x20\x01(\tR\x05value:\x028\x01\"\xf9\x01\n\rIdentifyEvent\
+    \x12$\n\x0esource_node_id\x18\x01\x20\x01(\x04R\x0csourceNodeId\x12&\n\
+    \x0fsource_node_tag\x18\x02\x20\x01(\tR\rsourceNodeTag\x12\x14\n\x05toke\
+    n\x18\x03\x20\x01(\tR\x05token\x120\n\x14min_protocol_version\x18\x04\
+    \x20\x01(\rR\x12minProtocolVersion\x120\n\x14max_protocol_version\x18\
+    \x05\x20\x01(\rR\x12maxProtocolVersion\x12\x20\n\x0bcompression\x18\x06\
+    \x20\x03(\rR\x0bcompression\"\xda\x05\n\x0cNodeIdentity\x12\x17\n\x07nod\
+    e_id\x18\x01\x20\x01(\x04R\x06nodeId\x12\x19\n\x08node_tag\x18\x02\x20\
+    \x01(\tR\x07nodeTag\x12\x12\n\x04addr\x18\x03\x20\x01(\tR\x04addr\x12/\n\
+    \x13application_version\x18\x04\x20\x01(\tR\x12applicationVersion\x12)\n\
+    \x10protocol_version\x18\x05\x20\x01(\tR\x0fprotocolVersion\x12B\n\x0fno\
+    de_started_at\x18\x06\x20\x01(\x0b2\x1a.google.protobuf.TimestampR\rnode\
+    StartedAt\x120\n\x05peers\x18\x07\x20\x03(\x0b2\x1a.coerce.network.Remot\
+    eNodeR\x05peers\x12F\n\x0ccapabilities\x18\x08\x20\x01(\x0b2\".coerce.ne\
+    twork.SystemCapabilitiesR\x0ccapabilities\x12L\n\nattributes\x18\t\x20\
+    \x03(\x0b2,.coerce.network.NodeIdentity.AttributesEntryR\nattributes\x12\
+    \x14\n\x05roles\x18\n\x20\x03(\tR\x05roles\x120\n\x14min_protocol_versio\
+    n\x18\x0b\x20\x01(\rR\x12minProtocolVersion\x120\n\x14max_protocol_versi\
+    on\x18\x0c\x20\x01(\rR\x12maxProtocolVersion\x12\x20\n\x0bcompression\
+    \x18\r\x20\x01(\rR\x0bcompression\x12#\n\rcrate_version\x18\x0e\x20\x01(\
+    \tR\x0ccrateVersion\x12\x1a\n\x08features\x18\x0f\x20\x03(\tR\x08feature\
+    s\x1a=\n\x0fAttributesEntry\x12\x10\n\x03key\x18\x01\x20\x01(\tR\x03key\
+    \x12\x14\n\x05value\x18\x02\x20\x01(\tR\x05value:\x028\x01\"H\n\x12Syste\
+    mCapabilities\x12\x16\n\x06actors\x18\x01\x20\x03(\tR\x06actors\x12\x1a\
+    \n\x08messages\x18\x02\x20\x03(\tR\x08messages\"\xd6\x01\n\x0fClientHand\
+    shake\x12\x17\n\x07node_id\x18\x01\x20\x01(\x04R\x06nodeId\x120\n\x05nod\
+    es\x18\x02\x20\x03(\x0b2\x1a.coerce.network.RemoteNodeR\x05nodes\x12\x19\
+    \n\x08node_tag\x18\x03\x20\x01(\tR\x07nodeTag\x12\x19\n\x08trace_id\x18\
+    \x04\x20\x01(\tR\x07traceId\x12B\n\x0fnode_started_at\x18\x05\x20\x01(\
+    \x0b2\x1a.google.protobuf.TimestampR\rnodeStartedAt\"`\n\x0cClientResult\
+    \x12\x1d\n\nmessage_id\x18\x01\x20\x01(\tR\tmessageId\x12\x16\n\x06resul\
+    t\x18\x02\x20\x01(\x0cR\x06result\x12\x19\n\x08trace_id\x18\x03\x20\x01(\
+    \tR\x07traceId\"x\n\tClientErr\x12\x1d\n\nmessage_id\x18\x01\x20\x01(\tR\
+    \tmessageId\x121\n\x05error\x18\x02\x20\x01(\x0b2\x1b.coerce.network.Act\
+    orRefErrR\x05error\x12\x19\n\x08trace_id\x18\x03\x20\x01(\tR\x07traceId\
+    \"\x8b\x01\n\tPingEvent\x12\x1d\n\nmessage_id\x18\x01\x20\x01(\tR\tmessa\
+    geId\x12\x19\n\x08trace_id\x18\x02\x20\x01(\tR\x07traceId\x12\x17\n\x07n\
+    ode_id\x18\x03\x20\x01(\x04R\x06nodeId\x12+\n\x11system_terminated\x18\
+    \x04\x20\x01(\x08R\x10systemTerminated\"g\n\tPongEvent\x12\x1d\n\nmessag\
+    e_id\x18\x01\x20\x01(\tR\tmessageId\x12\x19\n\x08trace_id\x18\x02\x20\
+    \x01(\tR\x07traceId\x12\x20\n\x0bmaintenance\x18\x03\x20\x01(\x08R\x0bma\
+    intenance\"\x9e\x01\n\x10CreateActorEvent\x12\x1d\n\nmessage_id\x18\x01\
+    \x20\x01(\tR\tmessageId\x12\x19\n\x08actor_id\x18\x02\x20\x01(\tR\x07act\
+    orId\x12\x1d\n\nactor_type\x18\x03\x20\x01(\tR\tactorType\x12\x16\n\x06r\
+    ecipe\x18\x04\x20\x01(\x0cR\x06recipe\x12\x19\n\x08trace_id\x18\x05\x20\
+    \x01(\tR\x07traceId\"e\n\x0eFindActorEvent\x12\x1d\n\nmessage_id\x18\x01\
+    \x20\x01(\tR\tmessageId\x12\x19\n\x08actor_id\x18\x02\x20\x01(\tR\x07act\
+    orId\x12\x19\n\x08trace_id\x18\x03\x20\x01(\tR\x07traceId\"{\n\x0cActorA\
+    ddress\x12\x19\n\x08actor_id\x18\x01\x20\x01(\tR\x07actorId\x125\n\x07no\
+    de_id\x18\x02\x20\x01(\x0b2\x1c.google.protobuf.UInt64ValueR\x06nodeId\
+    \x12\x19\n\x08trace_id\x18\x03\x20\x01(\tR\x07traceId\"\x9e\x02\n\x0eMes\
+    sageRequest\x12\x1d\n\nmessage_id\x18\x01\x20\x01(\tR\tmessageId\x12!\n\
+    \x0chandler_type\x18\x02\x20\x01(\tR\x0bhandlerType\x12\x19\n\x08actor_i\
+    d\x18\x03\x20\x01(\tR\x07actorId\x12\x18\n\x07message\x18\x04\x20\x01(\
+    \x0cR\x07message\x12\x19\n\x08trace_id\x18\x05\x20\x01(\tR\x07traceId\
+    \x12+\n\x11requires_response\x18\x06\x20\x01(\x08R\x10requiresResponse\
+    \x12$\n\x0eorigin_node_id\x18\x07\x20\x01(\x04R\x0coriginNodeId\x12'\n\
+    \x0fmessage_version\x18\x08\x20\x01(\rR\x0emessageVersion\"\xe6\x01\n\
+    \x10SessionHandshake\x12\x17\n\x07node_id\x18\x01\x20\x01(\x04R\x06nodeI\
+    d\x120\n\x05nodes\x18\x02\x20\x03(\x0b2\x1a.coerce.network.RemoteNodeR\
     \x05nodes\x12\x14\n\x05token\x18\x03\x20\x01(\x0cR\x05token\x12\x19\n\
     \x08node_tag\x18\x04\x20\x01(\tR\x07nodeTag\x12;\n\x0bclient_type\x18\
     \x05\x20\x01(\x0e2\x1a.coerce.network.ClientTypeR\nclientType\x12\x19\n\
@@ -0,0 +1,72 @@
+//! Authentication of the nodes joining the cluster
+//!
+//! Each [`SessionHandshake`] carries credentials provided by the connecting node's
+//! [`CredentialsProvider`], which are checked by the receiving node's [`Authenticator`] before
+//! the node is accepted into the cluster. Once a node is configured with an [`Authenticator`],
+//! inbound sessions must complete an authenticated handshake before any actor messages
+//! they send are handled.
+
+use crate::remote::net::proto::network::SessionHandshake;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AuthResult {
+    Accepted,
+    Rejected(String),
+}
+
+/// Authenticates the handshake of each node that connects to this node
+pub trait Authenticator: 'static + Send + Sync {
+    fn authenticate(&self, handshake: &SessionHandshake) -> AuthResult;
+}
+
+/// Provides the credentials sent via the handshake when connecting to the node at `addr`
+pub trait CredentialsProvider: 'static + Send + Sync {
+    fn credentials(&self, addr: &str) -> Vec<u8>;
+}
+
+impl<F> Authenticator for F
+where
+    F: 'static + Fn(&SessionHandshake) -> AuthResult + Send + Sync,
+{
+    fn authenticate(&self, handshake: &SessionHandshake) -> AuthResult {
+        self(handshake)
+    }
+}
+
+/// A token shared by every node in the cluster, used as both the credentials sent by this node,
+/// and to authenticate the nodes connecting to it
+#[derive(Clone)]
+pub struct SharedToken(Vec<u8>);
+
+impl SharedToken {
+    pub fn new(token: impl Into<Vec<u8>>) -> Self {
+        Self(token.into())
+    }
+}
+
+impl Authenticator for SharedToken {
+    fn authenticate(&self, handshake: &SessionHandshake) -> AuthResult {
+        // compared in constant time, so the token can't be guessed byte by byte
+        let token = &handshake.token;
+        let matches = !self.0.is_empty()
+            && self.0.len() == token.len()
+            && self
+                .0
+                .iter()
+                .zip(token)
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0;
+
+        if matches {
+            AuthResult::Accepted
+        } else {
+            AuthResult::Rejected(format!("invalid token from node_id={}", handshake.node_id))
+        }
+    }
+}
+
+impl CredentialsProvider for SharedToken {
+    fn credentials(&self, _addr: &str) -> Vec<u8> {
+        self.0.clone()
+    }
+}
@@ -1,3 +1,4 @@
 pub mod auth;
+pub mod handshake;
 
 pub use auth::*;
@@ -1,13 +1,16 @@
 use crate::actor::{IntoActor, LocalActorRef};
 use crate::remote::net::server::session::store::{NewSession, RemoteSessionStore};
 use crate::remote::net::server::session::RemoteSession;
+use crate::remote::net::stream::NodeStream;
 use crate::remote::system::RemoteActorSystem;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 
+pub mod pool;
 pub mod session;
 
+#[derive(Clone)]
 pub struct RemoteServer {
     cancellation_token: CancellationToken,
 }
@@ -32,6 +35,10 @@ pub struct RemoteServerConfig {
     /// used by the inbound client, rather than the address provided by
     /// the node via the handshake.
     pub override_incoming_node_addr: bool,
+
+    /// When set, inbound connections must complete a TLS handshake before the session is created
+    #[cfg(feature = "tls")]
+    pub tls: Option<Arc<crate::remote::net::tls::rustls::ServerConfig>>,
 }
 
 impl RemoteServerConfig {
@@ -44,8 +51,17 @@ impl RemoteServerConfig {
             listen_addr,
             external_node_addr,
             override_incoming_node_addr,
+
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
+
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: Arc<crate::remote::net::tls::rustls::ServerConfig>) -> Self {
+        self.tls = Some(tls);
+        self
+    }
 }
 
 impl RemoteServer {
@@ -124,21 +140,41 @@ pub async fn server_loop(
                 let session_id = session_count;
                 trace!("client accepted {}, session_id={}", addr, session_id);
 
-                let session = session_store
-                    .send(NewSession(RemoteSession::new(
-                        session_id,
-                        addr,
-                        stream,
-                        remote_server_config,
-                    )))
-                    .await;
-
-                if let Err(e) = session {
-                    error!(
-                        "error creating session actor (session_id={}, addr={}), error: {:?}",
-                        session_id, addr, e
-                    );
+                #[cfg(feature = "tls")]
+                if let Some(tls) = remote_server_config.tls.clone() {
+                    // the TLS handshake happens off the accept loop, so slow or malicious
+                    // clients can't prevent other nodes from connecting
+                    let session_store = session_store.clone();
+                    tokio::spawn(async move {
+                        match crate::remote::net::tls::accept(tls, stream).await {
+                            Ok(stream) => {
+                                new_session(
+                                    session_id,
+                                    addr,
+                                    stream,
+                                    &session_store,
+                                    remote_server_config,
+                                )
+                                .await
+                            }
+                            Err(e) => warn!(
+                                "rejected client (session_id={}, addr={}), error: {}",
+                                session_id, addr, e
+                            ),
+                        }
+                    });
+
+                    continue;
                 }
+
+                new_session(
+                    session_id,
+                    addr,
+                    stream.into(),
+                    &session_store,
+                    remote_server_config,
+                )
+                .await;
             }
             Some(Err(e)) => error!("error accepting client: {:?}", e),
             None => break,
@@ -147,3 +183,27 @@ pub async fn server_loop(
 
     info!("tcp listener {:?} stopped", &listener)
 }
+
+async fn new_session(
+    session_id: i64,
+    addr: SocketAddr,
+    stream: NodeStream,
+    session_store: &LocalActorRef<RemoteSessionStore>,
+    remote_server_config: RemoteServerConfigRef,
+) {
+    let session = session_store
+        .send(NewSession(RemoteSession::new(
+            session_id,
+            addr,
+            stream,
+            remote_server_config,
+        )))
+        .await;
+
+    if let Err(e) = session {
+        error!(
+            "error creating session actor (session_id={}, addr={}), error: {:?}",
+            session_id, addr, e
+        );
+    }
+}
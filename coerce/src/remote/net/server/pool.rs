@@ -0,0 +1,88 @@
+//! Bounded execution of remotely dispatched message handlers
+//!
+//! Messages received via a [`RemoteSession`] are dispatched via the [`HandlerExecutionPool`],
+//! which limits the total number of remote handlers executing concurrently, as well as
+//! (optionally) the number of concurrent executions per handler type, meaning a single expensive
+//! handler cannot starve every other handler received via the same session.
+//!
+//! [`RemoteSession`]: crate::remote::net::server::session::RemoteSession
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+const DEFAULT_MAX_CONCURRENT_HANDLERS: usize = 1024;
+
+#[derive(Clone, Debug)]
+pub struct HandlerExecutionConfig {
+    /// The maximum number of remote message handlers that can be executing at any one time,
+    /// once reached, reads from remote sessions are paused until a handler completes.
+    pub max_concurrent_handlers: usize,
+
+    /// Per-handler concurrency limits, keyed by the handler identifier
+    pub handler_limits: HashMap<String, usize>,
+}
+
+impl Default for HandlerExecutionConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_handlers: DEFAULT_MAX_CONCURRENT_HANDLERS,
+            handler_limits: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HandlerExecutionPool {
+    permits: Arc<Semaphore>,
+    handler_permits: Arc<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HandlerExecutionPool {
+    pub fn new(config: &HandlerExecutionConfig) -> Self {
+        let handler_permits = config
+            .handler_limits
+            .iter()
+            .map(|(handler, limit)| (handler.clone(), Arc::new(Semaphore::new(*limit))))
+            .collect();
+
+        Self {
+            permits: Arc::new(Semaphore::new(config.max_concurrent_handlers)),
+            handler_permits: Arc::new(handler_permits),
+        }
+    }
+
+    /// Waits for a slot within the pool to become available
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        self.permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("handler execution pool closed")
+    }
+
+    /// Waits for a slot to become available for the provided handler type, returns `None`
+    /// if the handler has no concurrency limit configured.
+    pub async fn acquire_handler(&self, handler_type: &str) -> Option<OwnedSemaphorePermit> {
+        let permits = self.handler_permits.get(handler_type)?.clone();
+        Some(
+            permits
+                .acquire_owned()
+                .await
+                .expect("handler execution pool closed"),
+        )
+    }
+
+    /// The number of handler executions that can be started before the pool is saturated
+    pub fn available(&self) -> usize {
+        self.permits.available_permits()
+    }
+
+    /// The number of executions that can be started for the provided handler type before its
+    /// limit is reached, returns `None` if the handler has no concurrency limit configured.
+    pub fn available_for_handler(&self, handler_type: &str) -> Option<usize> {
+        self.handler_permits
+            .get(handler_type)
+            .map(|permits| permits.available_permits())
+    }
+}
@@ -1,21 +1,32 @@
 use crate::actor::context::{ActorContext, LogContext};
-use crate::actor::message::Handler;
-use crate::actor::{Actor, ActorId, IntoActorId, LocalActorRef};
+use crate::actor::message::{Handler, Message};
+use crate::actor::{Actor, ActorId, ActorRefErr, IntoActorId, LocalActorRef};
 use crate::remote::actor::message::NodeTerminated;
 use crate::remote::actor::RemoteResponse;
 use crate::remote::cluster::discovery::{Discover, Seed};
 use crate::remote::cluster::node::{NodeAttributes, RemoteNode};
+use crate::remote::cluster::version::{enabled_features, NodeVersion};
+use crate::remote::net::buffer::BufferPool;
+use crate::remote::net::client::receive::pop_request;
+use crate::remote::net::client::send::write_bytes;
+use crate::remote::net::encoding::{MessageCodec, ProtobufCodec};
 use crate::remote::net::message::{
-    datetime_to_timestamp, timestamp_to_datetime, ClientEvent, SessionEvent,
+    datetime_to_timestamp, decode_failure_frame, is_decode_failure_frame, timestamp_to_datetime,
+    ClientEvent, SessionEvent,
 };
+use crate::remote::net::metrics::NetworkMetrics;
 use crate::remote::net::proto::network::{
-    ActorAddress, ClientHandshake, ClientResult, CreateActorEvent, MessageRequest, NodeIdentity,
-    PongEvent, RemoteNode as RemoteNodeProto, SessionHandshake, StreamPublishEvent,
-    SystemCapabilities,
+    ActorAddress, ClientErr, ClientHandshake, ClientResult, CreateActorEvent, IdentifyEvent,
+    MessageRequest, NodeIdentity, PongEvent, RemoteNode as RemoteNodeProto, SessionHandshake,
+    StreamPublishEvent, SystemCapabilities,
 };
+use crate::remote::net::security::handshake::AuthResult;
+use crate::remote::net::server::session::state::{SessionAction, SessionStateMachine};
 use crate::remote::net::server::session::store::{RemoteSessionStore, SessionClosed, SessionWrite};
 use crate::remote::net::server::RemoteServerConfigRef;
-use crate::remote::net::{receive_loop, StreamData, StreamReceiver};
+use crate::remote::net::stream::NodeStream;
+use crate::remote::net::version::{ProtocolVersions, PROTOCOL_VERSION};
+use crate::remote::net::{receive_loop, NetworkCodec, StreamData, StreamReceiver};
 use crate::remote::stream::mediator::PublishRaw;
 use crate::remote::system::{NodeId, RemoteActorSystem};
 use crate::CARGO_PKG_VERSION;
@@ -23,40 +34,41 @@ use futures::{SinkExt, StreamExt};
 use protobuf::well_known_types::wrappers::UInt64Value;
 use protobuf::{Message as ProtoMessage, MessageField};
 
-use bytes::Bytes;
 use std::io::Error;
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio::io::{ReadHalf, WriteHalf};
-use tokio::net::TcpStream;
 use tokio::sync::oneshot;
-use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
+use tokio_util::codec::{FramedRead, FramedWrite};
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 use valuable::Valuable;
 
+pub mod state;
 pub mod store;
 
 pub struct RemoteSession {
     id: i64,
     addr: SocketAddr,
-    write: FramedWrite<WriteHalf<TcpStream>, LengthDelimitedCodec>,
-    read: Option<FramedRead<ReadHalf<TcpStream>, LengthDelimitedCodec>>,
+    write: FramedWrite<WriteHalf<NodeStream>, NetworkCodec>,
+    read: Option<FramedRead<ReadHalf<NodeStream>, NetworkCodec>>,
     read_cancellation_token: Option<CancellationToken>,
     remote_server_config: RemoteServerConfigRef,
+    compression: u32,
+    codec: &'static dyn MessageCodec,
 }
 
 impl RemoteSession {
     pub fn new(
         id: i64,
         addr: SocketAddr,
-        stream: TcpStream,
+        stream: NodeStream,
         remote_server_config: RemoteServerConfigRef,
     ) -> RemoteSession {
         let (read, write) = tokio::io::split(stream);
-        let read = Some(FramedRead::new(read, LengthDelimitedCodec::new()));
-        let write = FramedWrite::new(write, LengthDelimitedCodec::new());
+        let read = Some(FramedRead::new(read, NetworkCodec::new()));
+        let write = FramedWrite::new(write, NetworkCodec::new());
         RemoteSession {
             id,
             addr,
@@ -64,6 +76,8 @@ impl RemoteSession {
             read,
             read_cancellation_token: Some(CancellationToken::new()),
             remote_server_config,
+            compression: 0,
+            codec: &ProtobufCodec,
         }
     }
 }
@@ -79,44 +93,88 @@ impl Actor for RemoteSession {
             "session started (addr={}, session_id={}), validating token", &self.addr, &self.id
         );
 
+        self.codec = system.config().wire_codec();
+
+        let chunking = system.config().chunking();
+        self.write.encoder_mut().set_chunking(chunking);
         if let Some(read) = &mut self.read {
-            if !validate_session_token(ctx, log, &system, read).await {
+            read.decoder_mut().set_chunking(chunking);
+        }
+
+        let mut state = if system.config().security().authenticator().is_some() {
+            SessionStateMachine::with_authentication()
+        } else {
+            SessionStateMachine::new()
+        };
+
+        let identify = match &mut self.read {
+            Some(read) => validate_session_token(ctx, &log, &system, read, &mut state).await,
+            None => None,
+        };
+
+        let identify = match identify {
+            Some(identify) => identify,
+            None => {
                 ctx.stop(None);
                 return;
             }
-        }
+        };
 
-        let peers = system
-            .get_nodes()
-            .await
-            .into_iter()
-            .map(|node| node.into())
-            .collect::<Vec<RemoteNodeProto>>();
+        let client_protocol = ProtocolVersions::from_wire(
+            identify.min_protocol_version,
+            identify.max_protocol_version,
+        );
 
-        let capabilities = system.config().get_capabilities();
-        let capabilities = Some(SystemCapabilities {
-            actors: capabilities.actors.into(),
-            messages: capabilities.messages.into(),
-            ..Default::default()
+        let compressor = system.config().compression().and_then(|config| {
+            config
+                .negotiate(&identify.compression)
+                .map(|compression| config.compressor(compression))
         });
 
-        self.write(ClientEvent::Identity(NodeIdentity {
-            node_id: system.node_id(),
-            node_tag: system.node_tag().to_string(),
-            application_version: format!("pkg_version={},protocol_version=1", CARGO_PKG_VERSION),
-            addr: self.remote_server_config.external_node_addr.to_string(),
-            node_started_at: Some(datetime_to_timestamp(system.started_at())).into(),
-            peers: peers.into(),
-            capabilities: capabilities.into(),
-            attributes: system
-                .config()
-                .get_attributes()
-                .iter()
-                .map(|(k, v)| (k.to_string(), v.to_string()))
-                .collect(),
-            ..Default::default()
-        }))
-        .await;
+        self.compression = compressor
+            .as_ref()
+            .map_or(0, |compressor| compressor.algorithm().id());
+
+        let identity = self.identity(&system).await;
+        self.write(ClientEvent::Identity(identity)).await;
+
+        // the identity is sent regardless, so the client can report why it was refused
+        match ProtocolVersions::current().negotiate(&client_protocol) {
+            Ok(protocol_version) => {
+                debug!(
+                    ctx = log.as_value(),
+                    "negotiated protocol_version={} (addr={}, session_id={})",
+                    protocol_version,
+                    &self.addr,
+                    &self.id
+                );
+            }
+            Err(e) => {
+                warn!(
+                    ctx = log.as_value(),
+                    "{}, disconnecting session({})",
+                    e,
+                    ctx.id()
+                );
+
+                // stopped once started, since the session store is awaiting the session's start
+                let _ = self.actor_ref(ctx).notify_stop();
+                return;
+            }
+        }
+
+        // the identity is written uncompressed, since it tells the client which compression to use
+        if let Some(compressor) = compressor {
+            debug!(
+                ctx = log.as_value(),
+                "negotiated compression={:?} (addr={}, session_id={})",
+                compressor.algorithm(),
+                &self.addr,
+                &self.id
+            );
+
+            self.write.encoder_mut().set_compressor(compressor);
+        }
 
         let _session = tokio::spawn(receive_loop(
             system.clone(),
@@ -126,6 +184,7 @@ impl Actor for RemoteSession {
                 self.actor_ref(ctx),
                 self.addr,
                 self.remote_server_config.clone(),
+                state,
             ),
         ));
     }
@@ -146,53 +205,74 @@ impl Actor for RemoteSession {
 
 async fn validate_session_token(
     ctx: &mut ActorContext,
-    log: LogContext,
+    log: &LogContext,
     system: &RemoteActorSystem,
-    read: &mut FramedRead<ReadHalf<TcpStream>, LengthDelimitedCodec>,
-) -> bool {
+    read: &mut FramedRead<ReadHalf<NodeStream>, NetworkCodec>,
+    state: &mut SessionStateMachine,
+) -> Option<IdentifyEvent> {
     let bytes = read.next().await;
-    if let Some(Ok(bytes)) = bytes {
-        match SessionEvent::read_from_bytes(bytes.to_vec()) {
-            Some(SessionEvent::Identify(identify)) => {
-                let token = identify.token;
-                let token_valid = system
-                    .config()
-                    .security()
-                    .client_authentication()
-                    .validate_token(token.as_str());
-
-                if !token_valid {
-                    error!(
-                        ctx = log.as_value(),
-                        "invalid token received, disconnecting session({})",
-                        ctx.id(),
-                    );
-                } else {
-                    info!(
-                        ctx = log.as_value(),
-                        "token validated - connection accepted",
-                    );
+    let event = match bytes {
+        Some(Ok(bytes)) => SessionEvent::read_from_bytes(bytes.to_vec()),
+        _ => {
+            error!(
+                ctx = log.as_value(),
+                "unable to read initial authentication payload"
+            );
+
+            return None;
+        }
+    };
 
-                    return true;
-                }
-            }
+    let event = match event {
+        Some(event) => event,
+        None => {
+            error!(
+                ctx = log.as_value(),
+                "initial payload could not be decoded, disconnecting session({})",
+                ctx.id()
+            );
 
-            value => {
+            return None;
+        }
+    };
+
+    match state.on_event(event) {
+        SessionAction::Handle(SessionEvent::Identify(identify)) => {
+            let token_valid = system
+                .config()
+                .security()
+                .client_authentication()
+                .validate_token(identify.token.as_str());
+
+            if !token_valid {
                 error!(
                     ctx = log.as_value(),
-                    "initial payload invalid, expected SessionEvent::Identify, disconnecting session({}), value={:?}",
-                    ctx.id(), value
+                    "invalid token received, disconnecting session({})",
+                    ctx.id(),
+                );
+
+                state.close();
+                None
+            } else {
+                info!(
+                    ctx = log.as_value(),
+                    "token validated - connection accepted",
                 );
+
+                Some(identify)
             }
         }
-    } else {
-        error!(
-            ctx = log.as_value(),
-            "unable to read initial authentication payload"
-        );
-    }
 
-    false
+        action => {
+            error!(
+                ctx = log.as_value(),
+                "initial payload invalid, expected SessionEvent::Identify, disconnecting session({}), action={:?}",
+                ctx.id(), action
+            );
+
+            None
+        }
+    }
 }
 
 #[async_trait]
@@ -202,12 +282,82 @@ impl Handler<SessionWrite> for RemoteSession {
     }
 }
 
+/// Writes the session's identity again, sent when the client repeats its `Identify`
+/// because the identity it was originally sent never arrived
+pub struct WriteIdentity;
+
+impl Message for WriteIdentity {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<WriteIdentity> for RemoteSession {
+    async fn handle(&mut self, _message: WriteIdentity, ctx: &mut ActorContext) {
+        let system = ctx.system().remote_owned();
+        let identity = self.identity(&system).await;
+        self.write(ClientEvent::Identity(identity)).await
+    }
+}
+
 impl RemoteSession {
+    async fn identity(&self, system: &RemoteActorSystem) -> NodeIdentity {
+        let peers = system
+            .get_nodes()
+            .await
+            .into_iter()
+            .map(|node| node.into())
+            .collect::<Vec<RemoteNodeProto>>();
+
+        let protocol = ProtocolVersions::current();
+        let capabilities = system.config().get_capabilities();
+        let capabilities = Some(SystemCapabilities {
+            actors: capabilities.actors,
+            messages: capabilities.messages,
+            ..Default::default()
+        });
+
+        NodeIdentity {
+            node_id: system.node_id(),
+            node_tag: system.node_tag().to_string(),
+            application_version: format!(
+                "pkg_version={},protocol_version={}",
+                CARGO_PKG_VERSION, PROTOCOL_VERSION
+            ),
+            addr: self.remote_server_config.external_node_addr.to_string(),
+            node_started_at: Some(datetime_to_timestamp(system.started_at())).into(),
+            peers,
+            capabilities: capabilities.into(),
+            attributes: system
+                .config()
+                .get_attributes()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            roles: system.node_roles().to_vec(),
+            min_protocol_version: protocol.min,
+            max_protocol_version: protocol.max,
+            compression: self.compression,
+            crate_version: CARGO_PKG_VERSION.to_string(),
+            features: enabled_features().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
     pub async fn write(&mut self, message: ClientEvent) {
-        match message.write_to_bytes() {
+        // the identity is always written as protobuf, so nodes that don't support the configured
+        // wire format can still identify this node, and report why they can't communicate with it
+        let codec: &dyn MessageCodec = match &message {
+            ClientEvent::Identity(_) => &ProtobufCodec,
+            _ => self.codec,
+        };
+
+        let frame =
+            BufferPool::global().encode_with(|buffer| message.write_to_buffer_with(codec, buffer));
+
+        match frame {
             Some(msg) => {
                 trace!("message encoded");
-                if self.write.send(Bytes::from(msg)).await.is_ok() {
+                if write_bytes(msg, &mut self.write).await.is_ok() {
                     trace!("message sent");
                 } else {
                     error!("failed to send message");
@@ -224,11 +374,18 @@ pub struct SessionMessageReceiver {
     session_id: i64,
     session: LocalActorRef<RemoteSession>,
     node_id: Option<NodeId>,
+    node_addr: String,
     addr: SocketAddr,
     should_close: bool,
     server_config: RemoteServerConfigRef,
+    state: SessionStateMachine,
+    deferred_events: Vec<SessionEvent>,
 }
 
+/// The maximum number of events buffered whilst a session is waiting to be authenticated,
+/// the session is closed if the peer sends more
+const MAX_DEFERRED_EVENTS: usize = 1024;
+
 #[derive(Debug)]
 pub enum RemoteSessionErr {
     Encoding,
@@ -241,16 +398,68 @@ impl SessionMessageReceiver {
         session: LocalActorRef<RemoteSession>,
         addr: SocketAddr,
         server_config: RemoteServerConfigRef,
+        state: SessionStateMachine,
     ) -> SessionMessageReceiver {
         SessionMessageReceiver {
             session_id,
             session,
             addr,
             server_config,
+            state,
+            deferred_events: vec![],
             node_id: None,
+            node_addr: String::new(),
             should_close: false,
         }
     }
+
+    async fn authenticate(&mut self, handshake: SessionHandshake, sys: &RemoteActorSystem) {
+        let result = sys
+            .config()
+            .security()
+            .authenticator()
+            .map_or(AuthResult::Accepted, |authenticator| {
+                authenticator.authenticate(&handshake)
+            });
+
+        match result {
+            AuthResult::Accepted => {
+                debug!(
+                    "handshake authenticated (addr={}, session_id={}, node_id={})",
+                    &self.addr, &self.session_id, handshake.node_id
+                );
+
+                self.state.on_authenticated();
+                self.handle_event(SessionEvent::Handshake(handshake), sys)
+                    .await;
+
+                for event in std::mem::take(&mut self.deferred_events) {
+                    self.handle_event(event, sys).await;
+                }
+            }
+
+            AuthResult::Rejected(reason) => {
+                warn!(
+                    "handshake rejected (addr={}, session_id={}, node_id={}), reason: {}",
+                    &self.addr, &self.session_id, handshake.node_id, reason
+                );
+
+                self.close_session("handshake authentication failed").await;
+            }
+        }
+    }
+
+    async fn close_session(&mut self, reason: &'static str) {
+        warn!(
+            "closing session (addr={}, session_id={}), reason: {}",
+            &self.addr, &self.session_id, reason
+        );
+
+        self.state.close();
+
+        let _ = self.session.notify_stop();
+        self.close().await;
+    }
 }
 
 #[async_trait]
@@ -258,18 +467,123 @@ impl StreamReceiver for SessionMessageReceiver {
     type Message = SessionEvent;
 
     async fn on_receive(&mut self, msg: SessionEvent, sys: &RemoteActorSystem) {
+        // the node may have been downed since its handshake was handled
+        if let Some(node_id) = self.node_id {
+            if sys.is_node_blocked(node_id, &self.node_addr) {
+                self.close_session("node is blocked").await;
+                return;
+            }
+        }
+
+        // blocked nodes are refused at handshake, whether or not handshakes are authenticated
+        if let SessionEvent::Handshake(handshake) = &msg {
+            let node_addr = handshake_node_addr(handshake);
+            if sys.is_node_blocked(handshake.node_id, &node_addr) {
+                warn!(
+                    "handshake refused, node is blocked (addr={}, session_id={}, node_id={}, node_addr={})",
+                    &self.addr, &self.session_id, handshake.node_id, &node_addr
+                );
+
+                self.close_session("node is blocked").await;
+                return;
+            }
+        }
+
+        match self.state.on_event(msg) {
+            SessionAction::Handle(msg) => self.handle_event(msg, sys).await,
+
+            SessionAction::Discard(msg, reason) => {
+                warn!(
+                    "discarding event (addr={}, session_id={}, status={:?}), reason: {}, event={:?}",
+                    &self.addr,
+                    &self.session_id,
+                    self.state.status(),
+                    reason,
+                    msg
+                );
+            }
+
+            SessionAction::Close(reason) => self.close_session(reason).await,
+
+            SessionAction::Authenticate(handshake) => self.authenticate(handshake, sys).await,
+
+            SessionAction::Defer(msg) => {
+                if self.deferred_events.len() >= MAX_DEFERRED_EVENTS {
+                    self.close_session("too many events received before authentication")
+                        .await;
+                } else {
+                    self.deferred_events.push(msg);
+                }
+            }
+        }
+    }
+
+    async fn on_close(&mut self, _ctx: &RemoteActorSystem) {
+        self.state.close();
+        let _ = self.session.notify_stop();
+    }
+
+    fn on_deserialisation_failed(&mut self, frame: &[u8], sys: &RemoteActorSystem) {
+        if let Some(hook) = sys.config().unhandled_frame_hook() {
+            if hook.on_undecodable_frame(&self.addr.to_string(), frame, sys) {
+                return;
+            }
+        }
+
+        warn!(
+            "message serialisation failed (addr={}, session_id={})",
+            &self.addr, &self.session_id
+        );
+
+        NetworkMetrics::incr_decode_failures(&self.addr.to_string());
+
+        let _ = self.session.notify(SessionWrite(
+            self.session_id,
+            ClientEvent::Err(decode_failure_frame()),
+        ));
+    }
+
+    fn ordering_key(&self, frame: &[u8]) -> u64 {
+        SessionEvent::ordering_key(frame)
+    }
+
+    fn on_stream_lost(&mut self, error: Error) {
+        warn!(
+            "stream connection lost (addr={}, session_id={}) - error: {}",
+            &self.addr, self.session_id, error
+        );
+    }
+
+    async fn close(&mut self) {
+        self.should_close = true;
+    }
+
+    fn should_close(&self) -> bool {
+        self.should_close
+    }
+}
+
+/// The address the node that sent the handshake is listening on, if it included itself
+fn handshake_node_addr(handshake: &SessionHandshake) -> String {
+    handshake
+        .nodes
+        .iter()
+        .find(|n| n.node_id == handshake.node_id)
+        .map_or_else(String::new, |n| n.addr.clone())
+}
+
+impl SessionMessageReceiver {
+    async fn handle_event(&mut self, msg: SessionEvent, sys: &RemoteActorSystem) {
         match msg {
             SessionEvent::Identify(identify) => {
-                let _session_id = self.session_id;
-                let _system = sys.clone();
-                let _session = self.session.clone();
-
-                trace!(
-                    "received identify from node (id={}, tag={}), session_id={}",
+                debug!(
+                    "received repeated identify from node (id={}, tag={}), session_id={}, re-sending identity",
                     &identify.source_node_id,
                     &identify.source_node_tag,
                     &self.session_id
                 );
+
+                let _ = self.session.notify(WriteIdentity);
             }
 
             SessionEvent::Handshake(msg) => {
@@ -280,6 +594,9 @@ impl StreamReceiver for SessionMessageReceiver {
                     &msg.client_type
                 );
 
+                self.node_id = Some(msg.node_id);
+                self.node_addr = handshake_node_addr(&msg);
+
                 tokio::spawn(session_handshake(
                     sys.clone(),
                     msg,
@@ -296,8 +613,20 @@ impl StreamReceiver for SessionMessageReceiver {
                     &self.session_id,
                     &find_actor.actor_id
                 );
+
+                let message_id = match Uuid::from_str(&find_actor.message_id) {
+                    Ok(message_id) => message_id,
+                    Err(_) => {
+                        warn!(
+                            "invalid actor lookup message_id={} (session_id={})",
+                            &find_actor.message_id, &self.session_id
+                        );
+                        return;
+                    }
+                };
+
                 tokio::spawn(session_handle_lookup(
-                    Uuid::from_str(&find_actor.message_id).unwrap(),
+                    message_id,
                     find_actor.actor_id.into_actor_id(),
                     self.session_id,
                     sys.clone(),
@@ -314,20 +643,25 @@ impl StreamReceiver for SessionMessageReceiver {
             }
 
             SessionEvent::NotifyActor(msg) => {
-                tokio::spawn(session_handle_message(
-                    msg,
-                    self.session_id,
-                    sys.clone(),
-                    self.session.clone(),
-                ));
+                // reads are paused once the pool is saturated, applying backpressure to the sender
+                let permit = sys.handler_pool().acquire().await;
+                let session_id = self.session_id;
+                let sys = sys.clone();
+                let session = self.session.clone();
+
+                tokio::spawn(async move {
+                    let _handler_permit =
+                        sys.handler_pool().acquire_handler(&msg.handler_type).await;
+
+                    session_handle_message(msg, session_id, sys, session).await;
+                    drop(permit);
+                });
             }
 
             SessionEvent::Ping(ping) => {
                 if sys.actor_system().is_terminated() {
-                    warn!("Ping received but system is terminated. Closing connection (session_id={})", self.session_id);
-
-                    let _ = self.session.notify_stop();
-                    self.close().await;
+                    self.close_session("ping received but system is terminated")
+                        .await;
                     return;
                 }
 
@@ -338,7 +672,8 @@ impl StreamReceiver for SessionMessageReceiver {
                         "Notified registry - node_id={} is terminated (session_id={})",
                         &ping.node_id, &self.session_id
                     );
-                    self.close();
+
+                    self.close_session("node terminated").await;
                 } else {
                     trace!("ping received, sending pong");
                     self.session
@@ -346,6 +681,7 @@ impl StreamReceiver for SessionMessageReceiver {
                             self.session_id,
                             ClientEvent::Pong(PongEvent {
                                 message_id: ping.message_id,
+                                maintenance: sys.is_in_maintenance(),
                                 ..Default::default()
                             }),
                         ))
@@ -368,29 +704,38 @@ impl StreamReceiver for SessionMessageReceiver {
 
             SessionEvent::StreamPublish(msg) => {
                 trace!("stream publish {}, {:?}", self.session_id, &msg);
-                tokio::spawn(session_stream_publish(msg, sys.clone()));
+                // published in-line, rather than spawned, so messages are delivered in the order
+                // they were received
+                session_stream_publish(msg, sys);
             }
 
             SessionEvent::Raft(_req) => {}
 
-            SessionEvent::Result(res) => {
-                match sys.pop_request(Uuid::from_str(&res.message_id).unwrap()) {
-                    Some(res_tx) => {
-                        let _ = res_tx.send(RemoteResponse::Ok(res.result));
-                    }
-                    None => {
-                        warn!(
-                            "node_tag={}, node_id={}, received unknown request result (id={})",
-                            sys.node_tag(),
-                            sys.node_id(),
-                            res.message_id
-                        );
-                    }
+            SessionEvent::Result(res) => match pop_request(sys, &res.message_id) {
+                Some(res_tx) => {
+                    let _ = res_tx.send(RemoteResponse::Ok(res.result));
                 }
-            }
+                None => {
+                    warn!(
+                        "node_tag={}, node_id={}, received unknown request result (id={})",
+                        sys.node_tag(),
+                        sys.node_id(),
+                        res.message_id
+                    );
+                }
+            },
             SessionEvent::Err(err) => {
-                let e = err.error.unwrap().into();
-                match sys.pop_request(Uuid::from_str(&err.message_id).unwrap()) {
+                if is_decode_failure_frame(&err) {
+                    warn!(
+                        "node (addr={}, session_id={}) failed to decode a frame sent by this node",
+                        &self.addr, &self.session_id
+                    );
+                    return;
+                }
+
+                let message_id = err.message_id.clone();
+                let e: ActorRefErr = err.into();
+                match pop_request(sys, &message_id) {
                     Some(res_tx) => {
                         let _ = res_tx.send(RemoteResponse::Err(e));
                     }
@@ -406,32 +751,6 @@ impl StreamReceiver for SessionMessageReceiver {
             }
         }
     }
-
-    async fn on_close(&mut self, _ctx: &RemoteActorSystem) {
-        let _ = self.session.notify_stop();
-    }
-
-    fn on_deserialisation_failed(&mut self) {
-        warn!(
-            "message serialisation failed (addr={}, session_id={})",
-            &self.addr, &self.session_id
-        )
-    }
-
-    fn on_stream_lost(&mut self, error: Error) {
-        warn!(
-            "stream connection lost (addr={}, session_id={}) - error: {}",
-            &self.addr, self.session_id, error
-        );
-    }
-
-    async fn close(&mut self) {
-        self.should_close = true;
-    }
-
-    fn should_close(&self) -> bool {
-        self.should_close
-    }
 }
 
 async fn session_handshake(
@@ -489,7 +808,23 @@ async fn session_handshake(
                 .into_iter()
                 .map(|(k, v)| (k.into(), v.into()))
                 .collect();
-            RemoteNode::new(n.node_id, addr, n.tag, started_at, attributes.into())
+            let mut node = RemoteNode::new(
+                n.node_id,
+                addr,
+                n.tag,
+                started_at,
+                attributes.into(),
+                n.roles,
+            );
+
+            node.version = NodeVersion::from_wire(
+                n.crate_version,
+                n.min_protocol_version,
+                n.max_protocol_version,
+                n.features,
+            );
+
+            node
         })
         .collect();
 
@@ -540,10 +875,21 @@ async fn session_handle_message(
     // let _enter = span.enter();
 
     let actor_id = msg.actor_id.into_actor_id();
+    let message_id = match Uuid::from_str(&msg.message_id) {
+        Ok(message_id) => message_id,
+        Err(_) => {
+            warn!(
+                "invalid message_id={} (handler_type={}, target_actor_id={}), message discarded",
+                &msg.message_id, &msg.handler_type, &actor_id
+            );
+            return;
+        }
+    };
 
     match ctx
-        .handle_message(
+        .handle_versioned_message(
             msg.handler_type.as_str(),
+            msg.message_version,
             actor_id.clone(),
             msg.message.as_slice(),
         )
@@ -551,14 +897,17 @@ async fn session_handle_message(
     {
         Ok(buf) => {
             if msg.requires_response {
-                send_result(msg.message_id.parse().unwrap(), buf, session_id, session).await;
+                send_result(message_id, buf, session_id, session).await;
             }
         }
         Err(e) => {
             error!("[node={}] failed to handle message (handler_type={}, target_actor_id={}), error={:?}", ctx.node_id(), &msg.handler_type, &actor_id, e);
-            let _ = ctx
-                .notify_rpc_err(msg.message_id.parse().unwrap(), e, msg.origin_node_id)
-                .await;
+
+            // replied via the session the message was received from, like results, so pending
+            // requests are resolved even if this node has no client connected to the sender
+            if msg.requires_response {
+                send_err(message_id, e, session_id, session).await;
+            }
         }
     }
 }
@@ -593,11 +942,18 @@ async fn session_create_actor(
     ctx: RemoteActorSystem,
     session: LocalActorRef<RemoteSession>,
 ) {
-    let msg_id = msg.message_id.clone();
+    let msg_id = match Uuid::from_str(&msg.message_id) {
+        Ok(msg_id) => msg_id,
+        Err(_) => {
+            warn!("invalid create actor message_id={}", &msg.message_id);
+            return;
+        }
+    };
+
     let actor_id = if msg.actor_id.is_empty() {
         None
     } else {
-        Some(msg.actor_id.into_actor_id())
+        Some(msg.actor_id.clone().into_actor_id())
     };
 
     trace!(
@@ -608,17 +964,24 @@ async fn session_create_actor(
         actor_id.as_ref().map_or_else(|| "N/A", |s| s)
     );
     match ctx
-        .handle_create_actor(actor_id, msg.actor_type, msg.recipe)
+        .handle_create_actor(actor_id.clone(), msg.actor_type.clone(), msg.recipe)
         .await
     {
-        Ok(buf) => send_result(msg_id.parse().unwrap(), buf.to_vec(), session_id, session).await,
-        Err(_) => {
-            error!("failed to handle message, todo: send err");
+        Ok(buf) => send_result(msg_id, buf.to_vec(), session_id, session).await,
+        Err(e) => {
+            warn!(
+                "failed to create actor (actor_id={:?}, actor_type={}), error={:?}",
+                &actor_id, &msg.actor_type, &e
+            );
+
+            let actor_id = actor_id.unwrap_or_else(|| msg.actor_id.into_actor_id());
+            let err = e.into_actor_ref_err(actor_id, msg.actor_type);
+            send_err(msg_id, err, session_id, session).await;
         }
     }
 }
 
-async fn session_stream_publish(msg: Arc<StreamPublishEvent>, sys: RemoteActorSystem) {
+fn session_stream_publish(msg: Arc<StreamPublishEvent>, sys: &RemoteActorSystem) {
     // TODO: node should acknowledge the message
     if let Some(mediator) = sys.stream_mediator() {
         mediator.notify::<PublishRaw>(msg.into()).unwrap()
@@ -645,3 +1008,22 @@ async fn send_result(
         error!("failed to send result");
     }
 }
+
+async fn send_err(
+    msg_id: Uuid,
+    err: ActorRefErr,
+    session_id: i64,
+    session: LocalActorRef<RemoteSession>,
+) {
+    trace!("sending error");
+
+    let event = ClientEvent::Err(ClientErr {
+        message_id: msg_id.to_string(),
+        error: Some(err.into()).into(),
+        ..ClientErr::default()
+    });
+
+    if session.send(SessionWrite(session_id, event)).await.is_err() {
+        error!("failed to send error");
+    }
+}
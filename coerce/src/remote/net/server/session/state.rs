@@ -0,0 +1,142 @@
+//! Inbound session protocol state
+//!
+//! Every inbound [`RemoteSession`] moves through the following states:
+//!
+//! `AwaitingIdentity` → `AwaitingHandshake` → `Active` → `Closing`
+//!
+//! The [`SessionStateMachine`] decides what happens to each [`SessionEvent`] based on the current
+//! state, so events that arrive out of order have well-defined behaviour:
+//!
+//! - Anything other than `Identify` whilst awaiting identity closes the session.
+//! - A repeated `Identify` is handled, the session writes its identity again, since the client
+//!   retries when the original identity never arrived.
+//! - Once identified, the session is authenticated and every other event is handled, the
+//!   handshake is only required for cluster membership, peers that connect solely to
+//!   exchange messages may never send one.
+//! - A repeated `Handshake` is handled, allowing the peer to re-run discovery.
+//! - Every event received once the session is closing is discarded.
+//!
+//! When the node is configured with an [`Authenticator`], every `Handshake` must be authenticated
+//! before it is handled, and events other than pings and replies are deferred until the first
+//! handshake has been authenticated.
+//!
+//! [`Authenticator`]: crate::remote::net::security::handshake::Authenticator
+//!
+//! [`RemoteSession`]: crate::remote::net::server::session::RemoteSession
+
+use crate::remote::net::message::SessionEvent;
+use crate::remote::net::proto::network::SessionHandshake;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SessionStatus {
+    AwaitingIdentity,
+    AwaitingHandshake,
+    Active,
+    Closing,
+}
+
+#[derive(Debug)]
+pub enum SessionAction {
+    /// The event should be handled
+    Handle(SessionEvent),
+
+    /// The event is not valid in the current state and should be discarded
+    Discard(SessionEvent, &'static str),
+
+    /// The event is a protocol violation, the session should be closed
+    Close(&'static str),
+
+    /// The handshake must be authenticated before it is handled, the outcome is reported via
+    /// [`SessionStateMachine::on_authenticated`] or [`SessionStateMachine::close`]
+    Authenticate(SessionHandshake),
+
+    /// The peer hasn't been authenticated yet, the event should be handled once it has been
+    Defer(SessionEvent),
+}
+
+pub struct SessionStateMachine {
+    status: SessionStatus,
+    requires_authentication: bool,
+}
+
+impl SessionStateMachine {
+    pub fn new() -> Self {
+        Self {
+            status: SessionStatus::AwaitingIdentity,
+            requires_authentication: false,
+        }
+    }
+
+    /// Creates a state machine for a session that must complete an authenticated handshake
+    /// before its events are handled
+    pub fn with_authentication() -> Self {
+        Self {
+            status: SessionStatus::AwaitingIdentity,
+            requires_authentication: true,
+        }
+    }
+
+    pub fn on_authenticated(&mut self) {
+        if self.status == SessionStatus::AwaitingHandshake {
+            self.status = SessionStatus::Active;
+        }
+    }
+
+    pub fn status(&self) -> SessionStatus {
+        self.status
+    }
+
+    pub fn close(&mut self) {
+        self.status = SessionStatus::Closing;
+    }
+
+    pub fn on_event(&mut self, event: SessionEvent) -> SessionAction {
+        match self.status {
+            SessionStatus::AwaitingIdentity => match event {
+                SessionEvent::Identify(_) => {
+                    self.status = SessionStatus::AwaitingHandshake;
+                    SessionAction::Handle(event)
+                }
+                _ => {
+                    self.close();
+                    SessionAction::Close("expected identify")
+                }
+            },
+
+            SessionStatus::AwaitingHandshake if self.requires_authentication => match event {
+                SessionEvent::Identify(_) => SessionAction::Handle(event),
+                SessionEvent::Handshake(handshake) => SessionAction::Authenticate(handshake),
+                SessionEvent::Ping(_)
+                | SessionEvent::Pong(_)
+                | SessionEvent::Result(_)
+                | SessionEvent::Err(_) => SessionAction::Handle(event),
+                _ => SessionAction::Defer(event),
+            },
+
+            SessionStatus::AwaitingHandshake => match event {
+                SessionEvent::Identify(_) => SessionAction::Handle(event),
+                SessionEvent::Handshake(_) => {
+                    self.status = SessionStatus::Active;
+                    SessionAction::Handle(event)
+                }
+                _ => SessionAction::Handle(event),
+            },
+
+            SessionStatus::Active => match event {
+                SessionEvent::Identify(_) => SessionAction::Handle(event),
+                SessionEvent::Handshake(handshake) if self.requires_authentication => {
+                    SessionAction::Authenticate(handshake)
+                }
+                _ => SessionAction::Handle(event),
+            },
+
+            SessionStatus::Closing => SessionAction::Discard(event, "session closing"),
+        }
+    }
+}
+
+impl Default for SessionStateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,97 @@
+use std::io::IoSlice;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+
+/// A connection between two nodes, either plaintext TCP or, when the cluster worker
+/// has been configured with TLS, a TLS stream wrapping the TCP connection
+pub enum NodeStream {
+    Tcp(TcpStream),
+
+    #[cfg(feature = "tls")]
+    Tls(Box<tokio_rustls::TlsStream<TcpStream>>),
+}
+
+impl From<TcpStream> for NodeStream {
+    fn from(stream: TcpStream) -> Self {
+        NodeStream::Tcp(stream)
+    }
+}
+
+#[cfg(feature = "tls")]
+impl From<tokio_rustls::TlsStream<TcpStream>> for NodeStream {
+    fn from(stream: tokio_rustls::TlsStream<TcpStream>) -> Self {
+        NodeStream::Tls(Box::new(stream))
+    }
+}
+
+impl AsyncRead for NodeStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            NodeStream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+
+            #[cfg(feature = "tls")]
+            NodeStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for NodeStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            NodeStream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+
+            #[cfg(feature = "tls")]
+            NodeStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            NodeStream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+
+            #[cfg(feature = "tls")]
+            NodeStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            NodeStream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+
+            #[cfg(feature = "tls")]
+            NodeStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            NodeStream::Tcp(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+
+            #[cfg(feature = "tls")]
+            NodeStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            NodeStream::Tcp(stream) => stream.is_write_vectored(),
+
+            #[cfg(feature = "tls")]
+            NodeStream::Tls(stream) => stream.is_write_vectored(),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! TLS for the connections between nodes, see [`ClusterWorkerBuilder::with_tls`]
+//!
+//! Peer certificates are verified by the [`ClientConfig`] of the connecting node, against the host
+//! of the peer's address unless a server name is provided. Mutual TLS is enabled by configuring the
+//! [`ServerConfig`] with a client certificate verifier, nodes that don't present a trusted
+//! certificate are then disconnected before the Coerce handshake begins, see [`mutual_tls`].
+//!
+//! [`ClusterWorkerBuilder::with_tls`]: crate::remote::cluster::builder::worker::ClusterWorkerBuilder::with_tls
+
+use crate::remote::net::stream::NodeStream;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+pub use tokio_rustls::rustls;
+
+/// How long an inbound connection has to complete the TLS handshake before it is dropped
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The TLS configuration used when connecting to other nodes
+#[derive(Clone)]
+pub struct ClientTls {
+    connector: TlsConnector,
+    server_name: Option<ServerName>,
+}
+
+#[derive(Debug)]
+pub enum TlsErr {
+    InvalidServerName(String),
+    HandshakeTimeout,
+    Handshake(std::io::Error),
+}
+
+impl Display for TlsErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsErr::InvalidServerName(name) => write!(f, "invalid TLS server name: {}", name),
+            TlsErr::HandshakeTimeout => write!(
+                f,
+                "TLS handshake timed out after {}s",
+                TLS_HANDSHAKE_TIMEOUT.as_secs()
+            ),
+            TlsErr::Handshake(e) => write!(f, "TLS handshake failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TlsErr {}
+
+impl ClientTls {
+    pub fn new(config: Arc<ClientConfig>, server_name: Option<String>) -> Result<Self, TlsErr> {
+        let server_name = match server_name {
+            Some(server_name) => Some(parse_server_name(&server_name)?),
+            None => None,
+        };
+
+        Ok(Self {
+            connector: TlsConnector::from(config),
+            server_name,
+        })
+    }
+
+    /// Performs the TLS handshake with the node at `addr`, verifying the node's certificate
+    pub async fn connect(&self, addr: &str, stream: TcpStream) -> Result<NodeStream, TlsErr> {
+        let server_name = match &self.server_name {
+            Some(server_name) => server_name.clone(),
+            None => parse_server_name(addr_host(addr))?,
+        };
+
+        let stream = tokio::time::timeout(
+            TLS_HANDSHAKE_TIMEOUT,
+            self.connector.connect(server_name, stream),
+        )
+        .await
+        .map_err(|_| TlsErr::HandshakeTimeout)?
+        .map_err(TlsErr::Handshake)?;
+
+        Ok(tokio_rustls::TlsStream::from(stream).into())
+    }
+}
+
+/// Performs the TLS handshake with an inbound connection, if the server is configured for
+/// mutual TLS, this fails unless the connecting node presents a trusted certificate
+pub async fn accept(config: Arc<ServerConfig>, stream: TcpStream) -> Result<NodeStream, TlsErr> {
+    let stream = tokio::time::timeout(
+        TLS_HANDSHAKE_TIMEOUT,
+        TlsAcceptor::from(config).accept(stream),
+    )
+    .await
+    .map_err(|_| TlsErr::HandshakeTimeout)?
+    .map_err(TlsErr::Handshake)?;
+
+    Ok(tokio_rustls::TlsStream::from(stream).into())
+}
+
+/// Creates the server and client configuration for mutual TLS, where each node presents
+/// `cert_chain` and only accepts connections from nodes presenting a certificate issued by one
+/// of the `roots`
+pub fn mutual_tls(
+    roots: RootCertStore,
+    cert_chain: Vec<Certificate>,
+    key: PrivateKey,
+) -> Result<(ServerConfig, ClientConfig), rustls::Error> {
+    let server_config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_client_cert_verifier(Arc::new(AllowAnyAuthenticatedClient::new(roots.clone())))
+        .with_single_cert(cert_chain.clone(), key.clone())?;
+
+    let client_config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_client_auth_cert(cert_chain, key)?;
+
+    Ok((server_config, client_config))
+}
+
+fn parse_server_name(server_name: &str) -> Result<ServerName, TlsErr> {
+    ServerName::try_from(server_name).map_err(|_| TlsErr::InvalidServerName(server_name.into()))
+}
+
+fn addr_host(addr: &str) -> &str {
+    let host = addr.rsplit_once(':').map_or(addr, |(host, _port)| host);
+    host.trim_start_matches('[').trim_end_matches(']')
+}
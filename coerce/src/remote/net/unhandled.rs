@@ -0,0 +1,47 @@
+//! Hooks for frames and messages this node doesn't understand
+//!
+//! By default, a frame that can't be decoded (for example, an event type introduced by a newer
+//! version of Coerce) is answered with a decode failure frame, and a message whose handler type
+//! isn't registered on this node fails with [`ActorRefErr::NotSupported`]. An
+//! [`UnhandledFrameHook`] allows applications to observe these, and optionally handle them,
+//! which is useful when gradually rolling out protocol extensions, or diagnosing mismatched nodes.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .with_handlers(|handlers| handlers.with_unhandled_frame_hook(LegacyHandlers))
+//!     .build()
+//!     .await;
+//! ```
+
+use crate::actor::{ActorId, ActorRefErr};
+use crate::remote::system::RemoteActorSystem;
+
+#[async_trait]
+pub trait UnhandledFrameHook: 'static + Send + Sync {
+    /// Called when a frame received from `addr` can't be decoded. The first byte of the frame is
+    /// the event type, followed by the protobuf-encoded event, see [`message`].
+    ///
+    /// Returning `true` marks the frame as handled, and no decode failure frame is sent back to
+    /// the peer.
+    ///
+    /// [`message`]: crate::remote::net::message
+    fn on_undecodable_frame(&self, _addr: &str, _frame: &[u8], _sys: &RemoteActorSystem) -> bool {
+        false
+    }
+
+    /// Called when a message is received for a handler type that isn't registered on this node.
+    ///
+    /// Returning `Some` handles the message, and the result is sent back to the sender (if a
+    /// response was requested), otherwise the message fails with [`ActorRefErr::NotSupported`].
+    async fn on_unknown_handler(
+        &self,
+        _handler_type: &str,
+        _actor_id: &ActorId,
+        _message: &[u8],
+        _sys: &RemoteActorSystem,
+    ) -> Option<Result<Vec<u8>, ActorRefErr>> {
+        None
+    }
+}
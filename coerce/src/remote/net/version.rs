@@ -0,0 +1,96 @@
+//! Wire protocol version negotiation
+//!
+//! Each node advertises the range of protocol versions it supports, the connecting node via its
+//! `Identify` event, and the accepting node via its `Identity` reply. Both nodes then agree on the
+//! highest version supported by both. If there's no such version, the accepting node closes
+//! the session after replying, and the connecting node fails with [`IncompatibleProtocol`],
+//! rather than the nodes exchanging frames that neither can decode.
+//!
+//! Nodes that predate negotiation don't advertise a range, and are assumed to only
+//! support version 1.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The newest protocol version supported by this node
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The oldest protocol version supported by this node
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// The protocol version spoken by nodes that don't advertise a supported range
+const LEGACY_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ProtocolVersions {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl ProtocolVersions {
+    /// The range of protocol versions supported by this node
+    pub const fn current() -> Self {
+        Self {
+            min: MIN_PROTOCOL_VERSION,
+            max: PROTOCOL_VERSION,
+        }
+    }
+
+    /// Reads a range advertised by another node, where 0 means the range wasn't advertised
+    pub(crate) fn from_wire(min: u32, max: u32) -> Self {
+        if max == 0 {
+            Self {
+                min: LEGACY_PROTOCOL_VERSION,
+                max: LEGACY_PROTOCOL_VERSION,
+            }
+        } else {
+            Self {
+                min: min.max(1),
+                max,
+            }
+        }
+    }
+
+    /// Returns the highest protocol version supported by both ranges
+    pub fn negotiate(&self, remote: &ProtocolVersions) -> Result<u32, IncompatibleProtocol> {
+        let version = self.max.min(remote.max);
+        if version >= self.min && version >= remote.min {
+            Ok(version)
+        } else {
+            Err(IncompatibleProtocol {
+                local: *self,
+                remote: *remote,
+            })
+        }
+    }
+}
+
+impl Default for ProtocolVersions {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+impl Display for ProtocolVersions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct IncompatibleProtocol {
+    pub local: ProtocolVersions,
+    pub remote: ProtocolVersions,
+}
+
+impl Display for IncompatibleProtocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "incompatible protocol versions (local={}, remote={})",
+            self.local, self.remote
+        )
+    }
+}
+
+impl Error for IncompatibleProtocol {}
@@ -0,0 +1,469 @@
+//! Durable pub/sub topics, backed by the persistence layer
+//!
+//! Once a topic is made durable on a node via [`PubSub::durable`], a [`TopicLog`] subscribes to
+//! the topic and writes every message published to it (from any node in the cluster) to the
+//! [`JournalStorage`] configured for the [`ActorSystem`], bounded by a [`RetentionPolicy`].
+//!
+//! Durable subscribers are identified by a consumer name rather than by actor. The offset of the
+//! last message processed by each consumer is stored alongside the messages, so when a consumer
+//! reconnects, any messages published while it was offline are delivered before new messages.
+//! Messages are delivered at-least-once, a message that was delivered but had not finished
+//! processing when the consumer went offline is delivered again on reconnect.
+//!
+//! Consumer offsets are tracked by the log of the node the consumer subscribes via, so consumers
+//! should reconnect via the same node to resume from where they left off.
+//!
+//! [`JournalStorage`]: crate::persistent::journal::storage::JournalStorage
+//! [`ActorSystem`]: crate::actor::system::ActorSystem
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::{Actor, ActorId, ActorRefErr, IntoActor, IntoActorId, LocalActorRef};
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::storage::{JournalEntry, JournalStorageRef};
+use crate::remote::net::StreamData;
+use crate::remote::stream::pubsub::{PubSub, Receive, Subscription, Topic};
+use crate::remote::system::RemoteActorSystem;
+use chrono::Utc;
+use std::any::TypeId;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const TOPIC_OFFSET_PAYLOAD_TYPE: &str = "coerce.TopicOffset";
+
+/// Limits how many messages a [`TopicLog`] retains, messages beyond the limits are deleted,
+/// even if there are consumers that have not yet received them
+#[derive(Clone, Debug, Default)]
+pub struct RetentionPolicy {
+    max_messages: Option<usize>,
+    max_age: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    /// Retains at most `max_messages` messages, deleting the oldest messages first
+    pub fn max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = Some(max_messages);
+        self
+    }
+
+    /// Retains messages for at most `max_age` after they were published
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum DurableTopicErr {
+    PersistenceNotConfigured,
+    NotDurable,
+    Storage(anyhow::Error),
+    ActorRef(ActorRefErr),
+}
+
+impl Display for DurableTopicErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DurableTopicErr::PersistenceNotConfigured => {
+                write!(f, "actor system is not configured for persistence")
+            }
+            DurableTopicErr::NotDurable => write!(f, "topic has not been made durable"),
+            DurableTopicErr::Storage(e) => write!(f, "topic storage error: {}", e),
+            DurableTopicErr::ActorRef(e) => write!(f, "topic log unavailable: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DurableTopicErr {}
+
+impl From<ActorRefErr> for DurableTopicErr {
+    fn from(e: ActorRefErr) -> Self {
+        DurableTopicErr::ActorRef(e)
+    }
+}
+
+impl From<anyhow::Error> for DurableTopicErr {
+    fn from(e: anyhow::Error) -> Self {
+        DurableTopicErr::Storage(e)
+    }
+}
+
+impl PubSub {
+    /// Makes the topic durable on this node, starting a [`TopicLog`] which stores every message
+    /// published to the topic, bounded by the `retention` policy. If the topic is already durable,
+    /// the existing log is returned.
+    pub async fn durable<T: Topic>(
+        topic: T,
+        retention: RetentionPolicy,
+        system: &RemoteActorSystem,
+    ) -> Result<LocalActorRef<TopicLog<T>>, DurableTopicErr> {
+        let actor_system = system.actor_system();
+        let actor_id = topic_log_id::<T>(&topic.key());
+        if let Some(log) = actor_system
+            .get_tracked_actor::<TopicLog<T>>(actor_id.clone())
+            .await
+        {
+            return Ok(log);
+        }
+
+        let storage = actor_system
+            .persistence()
+            .and_then(|p| p.provider(TypeId::of::<TopicLog<T>>()).journal_storage())
+            .ok_or(DurableTopicErr::PersistenceNotConfigured)?;
+
+        Ok(TopicLog::new(topic, storage, retention)
+            .into_actor(Some(actor_id), actor_system)
+            .await?)
+    }
+
+    /// Subscribes the actor to the durable topic as the consumer `consumer`. Any messages
+    /// published since the consumer last received a message are delivered first, followed by
+    /// every new message published to the topic.
+    pub async fn subscribe_durable<A, T: Topic>(
+        topic: T,
+        consumer: impl ToString,
+        ctx: &ActorContext,
+    ) -> Result<Subscription, DurableTopicErr>
+    where
+        A: Actor + Handler<Receive<T>>,
+    {
+        let log = ctx
+            .system()
+            .get_tracked_actor::<TopicLog<T>>(topic_log_id::<T>(&topic.key()))
+            .await
+            .ok_or(DurableTopicErr::NotDurable)?;
+
+        let consumer = consumer.to_string();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        log.send(ConnectConsumer {
+            consumer: consumer.clone(),
+            sender,
+        })
+        .await??;
+
+        let receiver_ref = ctx.actor_ref::<A>();
+        let task_handle = tokio::spawn(async move {
+            let mut receiver = receiver;
+            while let Some((sequence, message)) = receiver.recv().await {
+                if receiver_ref.send(Receive(message)).await.is_err() {
+                    break;
+                }
+
+                let _ = log.notify(CommitOffset {
+                    consumer: consumer.clone(),
+                    sequence,
+                });
+            }
+        });
+
+        Ok(Subscription::from_task(task_handle))
+    }
+}
+
+fn topic_log_id<T: Topic>(key: &str) -> ActorId {
+    format!("coerce-topic-log-{}-{}", T::topic_name(), key).into_actor_id()
+}
+
+type ConsumerSender<T> = mpsc::UnboundedSender<(i64, Arc<<T as Topic>::Message>)>;
+
+/// Stores every message published to a durable topic, and delivers missed messages to
+/// consumers as they reconnect.
+pub struct TopicLog<T: Topic> {
+    topic: Option<T>,
+    persistence_id: String,
+    storage: JournalStorageRef,
+    retention: RetentionPolicy,
+    last_sequence: i64,
+    retained: VecDeque<(i64, i64)>,
+    offsets: HashMap<String, i64>,
+    consumers: HashMap<String, ConsumerSender<T>>,
+    subscription: Option<Subscription>,
+}
+
+impl<T: Topic> TopicLog<T> {
+    pub fn new(topic: T, storage: JournalStorageRef, retention: RetentionPolicy) -> Self {
+        Self {
+            persistence_id: format!("coerce-topic-{}-{}", T::topic_name(), topic.key()),
+            topic: Some(topic),
+            storage,
+            retention,
+            last_sequence: 0,
+            retained: VecDeque::new(),
+            offsets: HashMap::new(),
+            consumers: HashMap::new(),
+            subscription: None,
+        }
+    }
+
+    fn offset_persistence_id(&self, consumer: &str) -> String {
+        format!("{}-offsets-{}", &self.persistence_id, consumer)
+    }
+
+    async fn read_offset(&self, consumer: &str) -> anyhow::Result<Option<i64>> {
+        Ok(self
+            .storage
+            .read_latest_snapshot(&self.offset_persistence_id(consumer))
+            .await?
+            .map(|entry| entry.sequence))
+    }
+
+    async fn write_offset(&self, consumer: &str, sequence: i64) -> anyhow::Result<()> {
+        let entry = JournalEntry {
+            sequence,
+            payload_type: TOPIC_OFFSET_PAYLOAD_TYPE.into(),
+            bytes: Arc::new(vec![]),
+            metadata: EventMetadata::default(),
+        };
+
+        self.storage
+            .write_snapshot(&self.offset_persistence_id(consumer), entry)
+            .await
+    }
+
+    /// Deletes any messages that fall outside of the retention policy
+    async fn apply_retention(&mut self) {
+        let now = Utc::now().timestamp_millis();
+        let mut delete_to = None;
+
+        while let Some((sequence, timestamp)) = self.retained.front().copied() {
+            let exceeds_count = self
+                .retention
+                .max_messages
+                .is_some_and(|max| self.retained.len() > max);
+
+            let expired = self
+                .retention
+                .max_age
+                .is_some_and(|max_age| now - timestamp > max_age.as_millis() as i64);
+
+            if !exceeds_count && !expired {
+                break;
+            }
+
+            self.retained.pop_front();
+            delete_to = Some(sequence);
+        }
+
+        if let Some(sequence) = delete_to {
+            if let Err(e) = self
+                .storage
+                .delete_messages_to(&self.persistence_id, sequence + 1)
+                .await
+            {
+                error!(
+                    persistence_id = &self.persistence_id,
+                    error = format!("{}", e),
+                    "failed to delete expired topic messages"
+                );
+            }
+        }
+    }
+}
+
+/// Messages are stored with the timestamp they were published at, followed by the message
+fn encode_entry(timestamp: i64, message: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(8 + message.len());
+    bytes.extend_from_slice(&timestamp.to_be_bytes());
+    bytes.extend_from_slice(message);
+    bytes
+}
+
+fn decode_entry(bytes: &[u8]) -> Option<(i64, &[u8])> {
+    if bytes.len() < 8 {
+        return None;
+    }
+
+    let (timestamp, message) = bytes.split_at(8);
+    Some((i64::from_be_bytes(timestamp.try_into().ok()?), message))
+}
+
+#[async_trait]
+impl<T: Topic> Actor for TopicLog<T> {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        match self
+            .storage
+            .read_latest_messages(&self.persistence_id, 0)
+            .await
+        {
+            Ok(entries) => {
+                for entry in entries.unwrap_or_default() {
+                    if let Some((timestamp, _)) = decode_entry(&entry.bytes) {
+                        self.retained.push_back((entry.sequence, timestamp));
+                    }
+
+                    self.last_sequence = entry.sequence;
+                }
+            }
+            Err(e) => {
+                error!(
+                    persistence_id = &self.persistence_id,
+                    error = format!("{}", e),
+                    "failed to load topic messages"
+                );
+            }
+        }
+
+        let topic = self.topic.take().unwrap();
+        self.subscription = Some(
+            PubSub::subscribe::<Self, T>(topic, ctx)
+                .await
+                .expect("subscribe to durable topic"),
+        );
+
+        self.apply_retention().await;
+
+        debug!(
+            persistence_id = &self.persistence_id,
+            last_sequence = self.last_sequence,
+            "topic log started"
+        );
+    }
+}
+
+#[async_trait]
+impl<T: Topic> Handler<Receive<T>> for TopicLog<T> {
+    async fn handle(&mut self, message: Receive<T>, _ctx: &mut ActorContext) {
+        let bytes = match message.0.write_to_bytes() {
+            Some(bytes) => bytes,
+            None => {
+                warn!(
+                    persistence_id = &self.persistence_id,
+                    "unable to serialise topic message, message will not be stored"
+                );
+                return;
+            }
+        };
+
+        let sequence = self.last_sequence + 1;
+        let timestamp = Utc::now().timestamp_millis();
+        let entry = JournalEntry {
+            sequence,
+            payload_type: T::topic_name().into(),
+            bytes: Arc::new(encode_entry(timestamp, &bytes)),
+            metadata: EventMetadata::default(),
+        };
+
+        if let Err(e) = self
+            .storage
+            .write_message(&self.persistence_id, entry)
+            .await
+        {
+            error!(
+                persistence_id = &self.persistence_id,
+                error = format!("{}", e),
+                "failed to store topic message"
+            );
+            return;
+        }
+
+        self.last_sequence = sequence;
+        self.retained.push_back((sequence, timestamp));
+
+        // consumers that have gone offline are removed, and catch up when they reconnect
+        self.consumers
+            .retain(|_, consumer| consumer.send((sequence, message.0.clone())).is_ok());
+
+        self.apply_retention().await;
+    }
+}
+
+struct ConnectConsumer<T: Topic> {
+    consumer: String,
+    sender: ConsumerSender<T>,
+}
+
+struct CommitOffset {
+    consumer: String,
+    sequence: i64,
+}
+
+impl<T: Topic> Message for ConnectConsumer<T> {
+    type Result = Result<(), DurableTopicErr>;
+}
+
+impl Message for CommitOffset {
+    type Result = ();
+}
+
+#[async_trait]
+impl<T: Topic> Handler<ConnectConsumer<T>> for TopicLog<T> {
+    async fn handle(
+        &mut self,
+        message: ConnectConsumer<T>,
+        _ctx: &mut ActorContext,
+    ) -> Result<(), DurableTopicErr> {
+        let consumer = message.consumer;
+        let offset = match self.offsets.get(&consumer) {
+            Some(offset) => *offset,
+            None => match self.read_offset(&consumer).await? {
+                Some(offset) => offset,
+                None => {
+                    // new consumers only receive messages published from now on
+                    self.write_offset(&consumer, self.last_sequence).await?;
+                    self.last_sequence
+                }
+            },
+        };
+
+        self.offsets.insert(consumer.clone(), offset);
+        self.apply_retention().await;
+
+        if offset < self.last_sequence {
+            let entries = self
+                .storage
+                .read_latest_messages(&self.persistence_id, offset)
+                .await?
+                .unwrap_or_default();
+
+            debug!(
+                persistence_id = &self.persistence_id,
+                consumer = &consumer,
+                offset,
+                missed_messages = entries.len(),
+                "delivering missed messages"
+            );
+
+            for entry in entries {
+                let missed = decode_entry(&entry.bytes)
+                    .and_then(|(_, bytes)| T::Message::read_from_bytes(bytes.to_vec()));
+
+                match missed {
+                    Some(missed) => {
+                        let _ = message.sender.send((entry.sequence, Arc::new(missed)));
+                    }
+                    None => warn!(
+                        persistence_id = &self.persistence_id,
+                        sequence = entry.sequence,
+                        "unable to deserialise topic message, message will be skipped"
+                    ),
+                }
+            }
+        }
+
+        self.consumers.insert(consumer, message.sender);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: Topic> Handler<CommitOffset> for TopicLog<T> {
+    async fn handle(&mut self, message: CommitOffset, _ctx: &mut ActorContext) {
+        let offset = self.offsets.entry(message.consumer.clone()).or_default();
+        if message.sequence <= *offset {
+            return;
+        }
+
+        *offset = message.sequence;
+        if let Err(e) = self.write_offset(&message.consumer, message.sequence).await {
+            error!(
+                persistence_id = &self.persistence_id,
+                consumer = &message.consumer,
+                error = format!("{}", e),
+                "failed to store consumer offset"
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Consumer groups, allowing a partitioned topic to be consumed by a group of actors across the
+//! cluster, where each message is delivered to exactly one member of the group.
+//!
+//! Every node publishes the members of each consumer group it hosts to the rest of the cluster,
+//! so each node has the same view of every group. Messages are published to every node as usual,
+//! and each node delivers only the messages belonging to partitions owned by its local members.
+//!
+//! While a group's membership is changing, nodes may briefly disagree on which member owns a
+//! partition, so a message published during a rebalance may be delivered more than once, or not
+//! at all.
+
+use crate::actor::ActorId;
+use crate::remote::net::StreamData;
+use crate::remote::stream::pubsub::{Receive, Topic};
+use crate::remote::system::NodeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Identifies a consumer group of a single topic
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq)]
+pub struct GroupId {
+    pub topic: String,
+    pub key: String,
+    pub group: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct GroupMember {
+    pub node_id: NodeId,
+    pub actor_id: ActorId,
+}
+
+pub struct ConsumerGroupTopic;
+
+/// The members of a consumer group hosted by a single node, published whenever the
+/// members change, and to any node that joins the cluster
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupMembership {
+    pub node_id: NodeId,
+    pub group: GroupId,
+    pub members: Vec<String>,
+}
+
+impl Topic for ConsumerGroupTopic {
+    type Message = GroupMembership;
+
+    fn topic_name() -> &'static str {
+        "coerce-consumer-groups"
+    }
+}
+
+impl StreamData for GroupMembership {
+    fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
+
+pub(crate) struct ConsumerGroup<T: Topic> {
+    node_id: NodeId,
+    partitioner: fn(&T::Message) -> u32,
+    members: Vec<GroupMember>,
+    local_members: HashMap<ActorId, mpsc::UnboundedSender<Receive<T>>>,
+}
+
+impl<T: Topic> ConsumerGroup<T> {
+    pub fn new(node_id: NodeId, partitioner: fn(&T::Message) -> u32) -> Self {
+        Self {
+            node_id,
+            partitioner,
+            members: vec![],
+            local_members: HashMap::new(),
+        }
+    }
+
+    pub fn join(&mut self, actor_id: ActorId, sender: mpsc::UnboundedSender<Receive<T>>) {
+        self.local_members.insert(actor_id, sender);
+    }
+
+    pub fn leave(&mut self, actor_id: &ActorId) {
+        self.local_members.remove(actor_id);
+    }
+
+    pub fn has_local_members(&self) -> bool {
+        !self.local_members.is_empty()
+    }
+
+    /// Sets every member of the group across the cluster, sorted so that every node
+    /// assigns partitions to the same members
+    pub fn set_members(&mut self, mut members: Vec<GroupMember>) {
+        members.sort();
+        self.members = members;
+    }
+
+    /// Delivers the message to the member that owns the message's partition,
+    /// if that member is hosted by this node
+    pub fn deliver(&self, message: &Arc<T::Message>) {
+        if self.members.is_empty() {
+            return;
+        }
+
+        let partition = (self.partitioner)(message) as usize;
+        let owner = &self.members[partition % self.members.len()];
+        if owner.node_id != self.node_id {
+            return;
+        }
+
+        if let Some(sender) = self.local_members.get(&owner.actor_id) {
+            let _ = sender.send(Receive(message.clone()));
+        }
+    }
+}
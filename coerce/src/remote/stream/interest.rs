@@ -0,0 +1,66 @@
+//! Subscriber-aware routing of messages published to the cluster.
+//!
+//! By default, every message published with [`PubSub::publish`] is sent to every node in the
+//! cluster, whether or not the node has any subscribers. With [`PubSubRouting::Subscribers`],
+//! each node publishes the topics its actors are subscribed to (including topics consumed by
+//! consumer groups) to the rest of the cluster, and messages are only sent to the nodes that
+//! have subscribers. Nodes that haven't published their subscriptions yet receive every message.
+//!
+//! Subscriptions are published asynchronously, so a message published shortly after an actor on
+//! another node subscribes may not be delivered to it. Nodes re-publish their subscriptions
+//! whenever they change, and whenever they receive a message they no longer have subscribers for.
+//!
+//! [`PubSub::publish`]: crate::remote::stream::pubsub::PubSub::publish
+
+use crate::remote::net::StreamData;
+use crate::remote::stream::pubsub::Topic;
+use crate::remote::system::NodeId;
+
+/// How messages published to the cluster are routed to other nodes,
+/// see [`RemoteSystemConfigBuilder::pubsub_routing`]
+///
+/// [`RemoteSystemConfigBuilder::pubsub_routing`]: crate::remote::system::builder::RemoteSystemConfigBuilder::pubsub_routing
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum PubSubRouting {
+    /// Messages are sent to every node in the cluster
+    #[default]
+    Broadcast,
+
+    /// Messages are only sent to nodes with subscribers to the message's topic (and key)
+    Subscribers,
+}
+
+/// A topic (and key) that a node has subscribers for
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TopicKey {
+    pub topic: String,
+    pub key: String,
+}
+
+pub struct SubscriberInterestTopic;
+
+/// Every topic a single node has subscribers for, published whenever the node's
+/// subscriptions change, and to any node that joins the cluster
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubscriberInterest {
+    pub node_id: NodeId,
+    pub topics: Vec<TopicKey>,
+}
+
+impl Topic for SubscriberInterestTopic {
+    type Message = SubscriberInterest;
+
+    fn topic_name() -> &'static str {
+        "coerce-subscriber-interest"
+    }
+}
+
+impl StreamData for SubscriberInterest {
+    fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
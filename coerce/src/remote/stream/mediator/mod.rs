@@ -1,19 +1,25 @@
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
-use crate::actor::{Actor, LocalActorRef};
+use crate::actor::{Actor, ActorId, LocalActorRef};
 use crate::remote::actor::message::SetRemote;
 use crate::remote::heartbeat::Heartbeat;
 use crate::remote::net::message::SessionEvent;
 use crate::remote::net::proto::network::StreamPublishEvent;
 use crate::remote::net::StreamData;
+use crate::remote::stream::group::{ConsumerGroupTopic, GroupId, GroupMember, GroupMembership};
+use crate::remote::stream::interest::{
+    PubSubRouting, SubscriberInterest, SubscriberInterestTopic, TopicKey,
+};
 use crate::remote::stream::pubsub::{
-    Receive, Subscription, Topic, TopicEmitter, TopicSubscriberStore,
+    partition_of, PartitionedTopic, Receive, Subscription, Topic, TopicEmitter,
+    TopicSubscriberStore,
 };
 use crate::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
 use crate::remote::system::{NodeId, RemoteActorSystem};
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
 pub struct MediatorTopic(Box<dyn TopicEmitter>);
 
@@ -22,7 +28,18 @@ pub struct StreamMediator {
     remote: Option<RemoteActorSystem>,
     nodes: HashSet<NodeId>,
     topics: HashMap<String, MediatorTopic>,
+    consumer_groups: HashMap<GroupId, BTreeMap<NodeId, BTreeSet<ActorId>>>,
+    node_interest: HashMap<NodeId, HashSet<TopicKey>>,
+    announced_interest: Option<BTreeSet<TopicKey>>,
     system_subscription: Option<Subscription>,
+    consumer_group_subscription: Option<Subscription>,
+    interest_subscription: Option<Subscription>,
+    remote_publisher: Option<mpsc::UnboundedSender<RemotePublish>>,
+}
+
+struct RemotePublish {
+    nodes: Vec<NodeId>,
+    publish: Arc<StreamPublishEvent>,
 }
 
 impl StreamMediator {
@@ -74,6 +91,33 @@ pub struct Publish<T: Topic> {
     pub reach: Reach,
 }
 
+pub struct SubscribeGroup<A: Actor, T: Topic> {
+    receiver_ref: LocalActorRef<A>,
+    topic: T,
+    group: String,
+}
+
+impl<A: Actor, T: Topic> SubscribeGroup<A, T> {
+    pub fn new(topic: T, group: String, receiver_ref: LocalActorRef<A>) -> Self {
+        SubscribeGroup {
+            receiver_ref,
+            topic,
+            group,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LeaveGroup {
+    pub group: GroupId,
+    pub actor_id: ActorId,
+}
+
+pub struct GetRemoteRecipients {
+    pub topic: String,
+    pub key: String,
+}
+
 pub struct PublishRaw {
     pub topic: String,
     pub key: String,
@@ -84,6 +128,14 @@ impl<A: Actor, T: Topic> Message for Subscribe<A, T> {
     type Result = Result<Subscription, SubscribeErr>;
 }
 
+impl<A: Actor, T: Topic> Message for SubscribeGroup<A, T> {
+    type Result = Result<Subscription, SubscribeErr>;
+}
+
+impl Message for LeaveGroup {
+    type Result = ();
+}
+
 impl<T: Topic> Message for Publish<T> {
     type Result = Result<(), PublishErr>;
 }
@@ -92,6 +144,10 @@ impl Message for PublishRaw {
     type Result = ();
 }
 
+impl Message for GetRemoteRecipients {
+    type Result = Vec<NodeId>;
+}
+
 impl StreamMediator {
     pub fn subscribe<A: Actor, T: Topic>(
         &mut self,
@@ -133,6 +189,155 @@ impl StreamMediator {
             Err(SubscribeErr::Err)
         }
     }
+
+    /// Pushes the current members of the group to the local topic, so every node assigns
+    /// partitions to the same members
+    fn update_group_members(&mut self, group_id: &GroupId) {
+        let members = self
+            .consumer_groups
+            .get(group_id)
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .flat_map(|(node_id, actor_ids)| {
+                        actor_ids.iter().map(|actor_id| GroupMember {
+                            node_id: *node_id,
+                            actor_id: actor_id.clone(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if let Some(topic) = self.topics.get_mut(&group_id.topic) {
+            topic
+                .0
+                .set_group_members(&group_id.key, &group_id.group, members);
+        }
+    }
+
+    /// Publishes the local members of the group to the rest of the cluster
+    fn announce_group(&self, group_id: &GroupId) {
+        let node_id = self.remote().node_id();
+        let members = self
+            .consumer_groups
+            .get(group_id)
+            .and_then(|nodes| nodes.get(&node_id))
+            .map(|actor_ids| actor_ids.iter().map(|id| id.to_string()).collect())
+            .unwrap_or_default();
+
+        let membership = GroupMembership {
+            node_id,
+            group: group_id.clone(),
+            members,
+        };
+
+        let _ = self.publish_remote(&ConsumerGroupTopic, &membership);
+    }
+
+    fn routing(&self) -> PubSubRouting {
+        // actors may subscribe before the mediator has been started by the remote system
+        self.remote
+            .as_ref()
+            .map_or(PubSubRouting::default(), |remote| {
+                remote.config().pubsub_routing()
+            })
+    }
+
+    /// Topics used by the mediators to coordinate with each other, which are always
+    /// sent to every node
+    fn is_internal_topic(topic: &str) -> bool {
+        topic == ConsumerGroupTopic::topic_name() || topic == SubscriberInterestTopic::topic_name()
+    }
+
+    /// Returns every topic (and key) with local subscribers
+    fn local_interest(&self) -> BTreeSet<TopicKey> {
+        self.topics
+            .iter()
+            .filter(|(topic, _)| !Self::is_internal_topic(topic))
+            .flat_map(|(topic, emitter)| {
+                emitter.0.subscribed_keys().into_iter().map(|key| TopicKey {
+                    topic: topic.clone(),
+                    key,
+                })
+            })
+            .collect()
+    }
+
+    /// Publishes the topics with local subscribers to the rest of the cluster, if they've changed
+    /// since they were last published, or if `force` is set
+    fn announce_interest(&mut self, force: bool) {
+        if self.routing() != PubSubRouting::Subscribers {
+            return;
+        }
+
+        let interest = self.local_interest();
+        if !force && self.announced_interest.as_ref() == Some(&interest) {
+            return;
+        }
+
+        let announcement = SubscriberInterest {
+            node_id: self.remote().node_id(),
+            topics: interest.iter().cloned().collect(),
+        };
+
+        trace!(
+            "announcing subscriber interest (topics={})",
+            announcement.topics.len()
+        );
+
+        let _ = self.publish_remote(&SubscriberInterestTopic, &announcement);
+        self.announced_interest = Some(interest);
+    }
+
+    /// Returns the nodes a message published to the topic should be sent to, nodes that haven't
+    /// published their subscriptions yet are assumed to have subscribers
+    fn remote_recipients(&self, topic: &str, key: &str) -> Vec<NodeId> {
+        if self.routing() == PubSubRouting::Broadcast || Self::is_internal_topic(topic) {
+            return self.nodes.iter().copied().collect();
+        }
+
+        let topic_key = TopicKey {
+            topic: topic.to_string(),
+            key: key.to_string(),
+        };
+
+        self.nodes
+            .iter()
+            .filter(|node_id| {
+                self.node_interest
+                    .get(node_id)
+                    .is_none_or(|interest| interest.contains(&topic_key))
+            })
+            .copied()
+            .collect()
+    }
+
+    fn publish_remote<T: Topic>(&self, topic: &T, msg: &T::Message) -> Result<(), PublishErr> {
+        let key = topic.key();
+        let nodes = self.remote_recipients(T::topic_name(), &key);
+        if nodes.is_empty() {
+            return Ok(());
+        }
+
+        match msg.write_to_bytes() {
+            Some(bytes) => {
+                let publish = Arc::new(StreamPublishEvent {
+                    topic: T::topic_name().to_string(),
+                    message: bytes,
+                    key,
+                    ..Default::default()
+                });
+
+                if let Some(remote_publisher) = &self.remote_publisher {
+                    let _ = remote_publisher.send(RemotePublish { nodes, publish });
+                }
+
+                Ok(())
+            }
+            None => Err(PublishErr::SerializationErr),
+        }
+    }
 }
 
 #[async_trait]
@@ -140,8 +345,19 @@ impl Handler<SetRemote> for StreamMediator {
     async fn handle(&mut self, message: SetRemote, ctx: &mut ActorContext) {
         Heartbeat::register(ctx.boxed_actor_ref(), &message.0);
 
+        self.remote_publisher = Some(start_remote_publisher(message.0.clone()));
         self.remote = Some(message.0);
-        self.system_subscription = Some(self.subscribe(SystemTopic, self.actor_ref(ctx)).unwrap())
+        self.system_subscription = Some(self.subscribe(SystemTopic, self.actor_ref(ctx)).unwrap());
+        self.consumer_group_subscription = Some(
+            self.subscribe(ConsumerGroupTopic, self.actor_ref(ctx))
+                .unwrap(),
+        );
+        self.interest_subscription = Some(
+            self.subscribe(SubscriberInterestTopic, self.actor_ref(ctx))
+                .unwrap(),
+        );
+
+        self.announce_interest(false);
     }
 }
 
@@ -153,6 +369,21 @@ impl Handler<Receive<SystemTopic>> for StreamMediator {
                 ClusterEvent::NodeAdded(new_node) => {
                     if new_node.id != self.remote().node_id() {
                         self.nodes.insert(new_node.id);
+
+                        // let the new node know about any consumer group members hosted here
+                        let node_id = self.remote().node_id();
+                        let local_groups: Vec<GroupId> = self
+                            .consumer_groups
+                            .iter()
+                            .filter(|(_, nodes)| nodes.contains_key(&node_id))
+                            .map(|(group_id, _)| group_id.clone())
+                            .collect();
+
+                        for group_id in local_groups {
+                            self.announce_group(&group_id);
+                        }
+
+                        self.announce_interest(true);
                     }
 
                     info!("node added (node_id={})", new_node.id);
@@ -166,6 +397,19 @@ impl Handler<Receive<SystemTopic>> for StreamMediator {
                     //       it will receive any messages it may have missed.
 
                     let _ = self.nodes.remove(&removed_node.id);
+                    let _ = self.node_interest.remove(&removed_node.id);
+
+                    let affected_groups: Vec<GroupId> = self
+                        .consumer_groups
+                        .iter_mut()
+                        .filter_map(|(group_id, nodes)| {
+                            nodes.remove(&removed_node.id).map(|_| group_id.clone())
+                        })
+                        .collect();
+
+                    for group_id in affected_groups {
+                        self.update_group_members(&group_id);
+                    }
 
                     info!("node removed (node_id={})", removed_node.id);
                 }
@@ -187,42 +431,70 @@ impl<T: Topic> Handler<Publish<T>> for StreamMediator {
             topic.0.emit(&message.topic.key(), msg.clone()).await;
         }
 
-        if message.reach.remote_publish() && !self.nodes.is_empty() {
-            match msg.write_to_bytes() {
-                Some(bytes) => {
-                    let remote = self.remote().clone();
-                    let topic = T::topic_name().to_string();
-                    let key = message.topic.key();
-                    let nodes: Vec<NodeId> = self.nodes.iter().copied().collect();
-
-                    tokio::spawn(async move {
-                        let message = bytes;
-                        let publish = Arc::new(StreamPublishEvent {
-                            topic,
-                            message,
-                            key,
-                            ..Default::default()
-                        });
-
-                        let node_count = nodes.len();
-                        for node in nodes {
-                            let _ = remote
-                                .notify_node(node, SessionEvent::StreamPublish(publish.clone()))
-                                .await;
-                        }
-
-                        debug!("notified {} nodes", node_count);
-                    });
-                    Ok(())
-                }
-                None => Err(PublishErr::SerializationErr),
-            }
+        if message.reach.remote_publish() {
+            self.publish_remote(&message.topic, &msg)
         } else {
             Ok(())
         }
     }
 }
 
+#[async_trait]
+impl Handler<GetRemoteRecipients> for StreamMediator {
+    async fn handle(
+        &mut self,
+        message: GetRemoteRecipients,
+        _ctx: &mut ActorContext,
+    ) -> Vec<NodeId> {
+        let mut nodes = self.remote_recipients(&message.topic, &message.key);
+        nodes.sort();
+        nodes
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<SubscriberInterestTopic>> for StreamMediator {
+    async fn handle(&mut self, message: Receive<SubscriberInterestTopic>, _ctx: &mut ActorContext) {
+        let interest = message.0.as_ref();
+        if interest.node_id == self.remote().node_id() {
+            return;
+        }
+
+        self.node_interest
+            .insert(interest.node_id, interest.topics.iter().cloned().collect());
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<ConsumerGroupTopic>> for StreamMediator {
+    async fn handle(&mut self, message: Receive<ConsumerGroupTopic>, _ctx: &mut ActorContext) {
+        let membership = message.0.as_ref();
+        if membership.node_id == self.remote().node_id() {
+            return;
+        }
+
+        let nodes = self
+            .consumer_groups
+            .entry(membership.group.clone())
+            .or_default();
+
+        if membership.members.is_empty() {
+            nodes.remove(&membership.node_id);
+        } else {
+            nodes.insert(
+                membership.node_id,
+                membership
+                    .members
+                    .iter()
+                    .map(|id| ActorId::from(id.as_str()))
+                    .collect(),
+            );
+        }
+
+        self.update_group_members(&membership.group);
+    }
+}
+
 impl From<Arc<StreamPublishEvent>> for PublishRaw {
     fn from(s: Arc<StreamPublishEvent>) -> Self {
         match Arc::<StreamPublishEvent>::try_unwrap(s) {
@@ -255,10 +527,21 @@ impl From<Arc<StreamPublishEvent>> for PublishRaw {
 #[async_trait]
 impl Handler<PublishRaw> for StreamMediator {
     async fn handle(&mut self, message: PublishRaw, _ctx: &mut ActorContext) {
-        if let Some(topic) = self.topics.get(&message.topic) {
-            topic.0.emit_serialised(&message.key, message.message).await;
-        } else {
-            trace!("no topic: {}", &message.topic)
+        let subscribed = match self.topics.get(&message.topic) {
+            Some(topic) => {
+                let subscribed = topic.0.subscribed_keys().contains(&message.key);
+                topic.0.emit_serialised(&message.key, message.message).await;
+                subscribed
+            }
+            None => {
+                trace!("no topic: {}", &message.topic);
+                false
+            }
+        };
+
+        // the sender thinks this node has subscribers, so lets it know that it no longer does
+        if !subscribed && !Self::is_internal_topic(&message.topic) {
+            self.announce_interest(false);
         }
     }
 }
@@ -273,10 +556,133 @@ where
         message: Subscribe<A, T>,
         _ctx: &mut ActorContext,
     ) -> Result<Subscription, SubscribeErr> {
-        self.subscribe(message.topic, message.receiver_ref)
+        let subscription = self.subscribe(message.topic, message.receiver_ref);
+        self.announce_interest(false);
+        subscription
+    }
+}
+
+#[async_trait]
+impl<A: Actor, T: PartitionedTopic> Handler<SubscribeGroup<A, T>> for StreamMediator
+where
+    A: Handler<Receive<T>>,
+{
+    async fn handle(
+        &mut self,
+        message: SubscribeGroup<A, T>,
+        ctx: &mut ActorContext,
+    ) -> Result<Subscription, SubscribeErr> {
+        let node_id = self.remote().node_id();
+        let actor_id = message.receiver_ref.actor_id().clone();
+        let group_id = GroupId {
+            topic: T::topic_name().to_string(),
+            key: message.topic.key(),
+            group: message.group,
+        };
+
+        let topic = self
+            .topics
+            .entry(group_id.topic.clone())
+            .or_insert_with(MediatorTopic::new::<T>);
+
+        let receiver = match topic.subscriber_store_mut::<T>() {
+            Some(store) => store.join_group(
+                &group_id.key,
+                &group_id.group,
+                node_id,
+                partition_of::<T>,
+                actor_id.clone(),
+            ),
+            None => {
+                error!(
+                    "actor_id={} failed to join consumer group {} of topic {} (key=\"{}\")",
+                    &actor_id, &group_id.group, &group_id.topic, &group_id.key
+                );
+
+                return Err(SubscribeErr::Err);
+            }
+        };
+
+        debug!(
+            "actor_id={} joined consumer group {} of topic {} (key=\"{}\")",
+            &actor_id, &group_id.group, &group_id.topic, &group_id.key
+        );
+
+        self.consumer_groups
+            .entry(group_id.clone())
+            .or_default()
+            .entry(node_id)
+            .or_default()
+            .insert(actor_id.clone());
+
+        self.update_group_members(&group_id);
+        self.announce_group(&group_id);
+        self.announce_interest(false);
+
+        Ok(Subscription::group_member(
+            receiver,
+            message.receiver_ref,
+            self.actor_ref(ctx),
+            LeaveGroup {
+                group: group_id,
+                actor_id,
+            },
+        ))
     }
 }
 
+#[async_trait]
+impl Handler<LeaveGroup> for StreamMediator {
+    async fn handle(&mut self, message: LeaveGroup, _ctx: &mut ActorContext) {
+        let node_id = self.remote().node_id();
+        let left = self
+            .consumer_groups
+            .get_mut(&message.group)
+            .and_then(|nodes| nodes.get_mut(&node_id))
+            .map_or(false, |actor_ids| actor_ids.remove(&message.actor_id));
+
+        if !left {
+            return;
+        }
+
+        if let Some(topic) = self.topics.get_mut(&message.group.topic) {
+            topic
+                .0
+                .leave_group(&message.group.key, &message.group.group, &message.actor_id);
+        }
+
+        debug!(
+            "actor_id={} left consumer group {} of topic {}",
+            &message.actor_id, &message.group.group, &message.group.topic
+        );
+
+        self.update_group_members(&message.group);
+        self.announce_group(&message.group);
+        self.announce_interest(false);
+    }
+}
+
+/// Publishes messages to remote nodes from a single task, so messages are
+/// sent to each node in the order they were published
+fn start_remote_publisher(remote: RemoteActorSystem) -> mpsc::UnboundedSender<RemotePublish> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<RemotePublish>();
+
+    tokio::spawn(async move {
+        while let Some(RemotePublish { nodes, publish }) = rx.recv().await {
+            let node_count = nodes.len();
+            for node in nodes {
+                let _ = remote
+                    .notify_node(node, SessionEvent::StreamPublish(publish.clone()))
+                    .await;
+            }
+
+            debug!("notified {} nodes", node_count);
+        }
+    });
+
+    tx
+}
+
 impl Reach {
     pub fn remote_publish(&self) -> bool {
         match &self {
@@ -1,4 +1,8 @@
 pub mod alerts;
+#[cfg(feature = "persistence")]
+pub mod durable;
+pub mod group;
+pub mod interest;
 pub mod mediator;
 pub mod pubsub;
 pub mod system;
@@ -1,19 +1,27 @@
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
 
-use crate::actor::{Actor, LocalActorRef};
+use crate::actor::{Actor, ActorId, LocalActorRef};
 use crate::remote::net::StreamData;
-use crate::remote::stream::mediator::{Publish, Reach, Subscribe, SubscribeErr};
+use crate::remote::stream::group::{ConsumerGroup, GroupMember};
+use crate::remote::stream::mediator::{
+    GetRemoteRecipients, LeaveGroup, Publish, Reach, StreamMediator, Subscribe, SubscribeErr,
+    SubscribeGroup,
+};
 
 use std::any::Any;
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-use crate::remote::system::RemoteActorSystem;
-use tokio::sync::broadcast;
+use crate::remote::system::{NodeId, RemoteActorSystem};
+use tokio::sync::{broadcast, mpsc};
 use tokio::task::JoinHandle;
 
+const DEFAULT_PARTITION_COUNT: u32 = 16;
+
 pub struct PubSub;
 
 pub trait Topic: 'static + Send + Sync {
@@ -26,6 +34,27 @@ pub trait Topic: 'static + Send + Sync {
     }
 }
 
+/// A topic whose messages carry a key, allowing the topic to be consumed by consumer groups,
+/// see [`PubSub::subscribe_group`]
+pub trait PartitionedTopic: Topic {
+    /// The number of partitions the topic is divided into. Each partition is consumed by a single
+    /// member of each consumer group, limiting how many members of a group receive messages
+    fn partition_count() -> u32 {
+        DEFAULT_PARTITION_COUNT
+    }
+
+    /// The key of the message, messages with the same key are assigned to the same partition,
+    /// and are delivered in the order they were published
+    fn message_key(message: &Self::Message) -> String;
+}
+
+pub(crate) fn partition_of<T: PartitionedTopic>(message: &T::Message) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    T::message_key(message).hash(&mut hasher);
+
+    (hasher.finish() % T::partition_count().max(1) as u64) as u32
+}
+
 pub struct Receive<T: Topic>(pub Arc<T::Message>);
 
 impl PubSub {
@@ -55,6 +84,37 @@ impl PubSub {
         }
     }
 
+    /// Subscribes the actor to the topic as a member of the consumer group `group`.
+    ///
+    /// Rather than every subscriber receiving every message, each message is delivered to exactly
+    /// one member of the group, across every node in the cluster. Messages with the same
+    /// [key][PartitionedTopic::message_key] are delivered to the same member, in the order
+    /// they were published.
+    pub async fn subscribe_group<A, T: PartitionedTopic>(
+        topic: T,
+        group: impl ToString,
+        ctx: &ActorContext,
+    ) -> Result<Subscription, SubscribeErr>
+    where
+        A: Actor + Handler<Receive<T>>,
+    {
+        let system = ctx.system().remote();
+        if let Some(mediator) = system.stream_mediator() {
+            mediator
+                .send(SubscribeGroup::<A, T>::new(
+                    topic,
+                    group.to_string(),
+                    ctx.actor_ref(),
+                ))
+                .await
+                .unwrap()
+        } else {
+            panic!("no stream mediator found, system not setup for distributed streams")
+        }
+    }
+
+    /// Publishes the message to the topic's subscribers across the cluster, see
+    /// [`interest`](crate::remote::stream::interest) for how messages are routed to other nodes
     pub async fn publish<T: Topic>(topic: T, message: T::Message, system: &RemoteActorSystem) {
         // let topic_data = format!("{}-{}", T::topic_name(), &topic.key());
         // let span = tracing::debug_span!("PubSub::publish", topic = topic_data.as_str());
@@ -93,6 +153,21 @@ impl PubSub {
             panic!("no stream mediator found, system not setup for distributed streams")
         }
     }
+
+    /// Returns the remote nodes that messages published to the topic are sent to
+    pub async fn remote_recipients<T: Topic>(topic: &T, system: &RemoteActorSystem) -> Vec<NodeId> {
+        if let Some(mediator) = system.stream_mediator() {
+            mediator
+                .send(GetRemoteRecipients {
+                    topic: T::topic_name().to_string(),
+                    key: topic.key(),
+                })
+                .await
+                .unwrap()
+        } else {
+            panic!("no stream mediator found, system not setup for distributed streams")
+        }
+    }
 }
 
 impl<T: Topic> Clone for Receive<T> {
@@ -111,6 +186,13 @@ pub trait TopicEmitter: 'static + Any + Send + Sync {
 
     async fn emit(&self, key: &str, msg: Arc<dyn Any + Sync + Send>);
 
+    fn set_group_members(&mut self, key: &str, group: &str, members: Vec<GroupMember>);
+
+    fn leave_group(&mut self, key: &str, group: &str, actor_id: &ActorId);
+
+    /// Returns the keys of the topic that have local subscribers, or local consumer group members
+    fn subscribed_keys(&self) -> Vec<String>;
+
     fn as_any(&self) -> &dyn Any;
 
     fn as_any_mut(&mut self) -> &mut dyn Any;
@@ -119,15 +201,37 @@ pub trait TopicEmitter: 'static + Any + Send + Sync {
 #[derive(Default)]
 pub struct TopicSubscriberStore<T: Topic> {
     channels: HashMap<String, broadcast::Sender<Receive<T>>>,
+    groups: HashMap<String, HashMap<String, ConsumerGroup<T>>>,
 }
 
 impl<T: Topic> TopicSubscriberStore<T> {
     pub fn new() -> Self {
         Self {
             channels: Default::default(),
+            groups: Default::default(),
         }
     }
 
+    pub(crate) fn join_group(
+        &mut self,
+        key: &str,
+        group: &str,
+        node_id: NodeId,
+        partitioner: fn(&T::Message) -> u32,
+        actor_id: ActorId,
+    ) -> mpsc::UnboundedReceiver<Receive<T>> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        self.groups
+            .entry(key.to_string())
+            .or_default()
+            .entry(group.to_string())
+            .or_insert_with(|| ConsumerGroup::new(node_id, partitioner))
+            .join(actor_id, sender);
+
+        receiver
+    }
+
     pub fn receiver(&mut self, key: &str) -> broadcast::Receiver<Receive<T>> {
         match self.channels.get(key) {
             Some(channel) => channel.subscribe(),
@@ -141,6 +245,12 @@ impl<T: Topic> TopicSubscriberStore<T> {
     }
 
     pub fn broadcast(&self, key: &str, msg: Arc<T::Message>) {
+        if let Some(groups) = self.groups.get(key) {
+            for group in groups.values() {
+                group.deliver(&msg);
+            }
+        }
+
         match self.channels.get(key) {
             Some(sender) => {
                 sender.send(Receive(msg));
@@ -181,6 +291,37 @@ impl<T: Topic + 'static> TopicEmitter for TopicSubscriberStore<T> {
         self.broadcast(key, msg);
     }
 
+    fn set_group_members(&mut self, key: &str, group: &str, members: Vec<GroupMember>) {
+        if let Some(group) = self.groups.get_mut(key).and_then(|g| g.get_mut(group)) {
+            group.set_members(members);
+        }
+    }
+
+    fn leave_group(&mut self, key: &str, group: &str, actor_id: &ActorId) {
+        if let Some(group) = self.groups.get_mut(key).and_then(|g| g.get_mut(group)) {
+            group.leave(actor_id);
+        }
+    }
+
+    fn subscribed_keys(&self) -> Vec<String> {
+        let subscribed = self
+            .channels
+            .iter()
+            .filter(|(_, sender)| sender.receiver_count() > 0)
+            .map(|(key, _)| key);
+
+        let grouped = self
+            .groups
+            .iter()
+            .filter(|(_, groups)| groups.values().any(|group| group.has_local_members()))
+            .map(|(key, _)| key);
+
+        let mut keys: Vec<String> = subscribed.chain(grouped).cloned().collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -192,6 +333,7 @@ impl<T: Topic + 'static> TopicEmitter for TopicSubscriberStore<T> {
 
 pub struct Subscription {
     task_handle: Option<JoinHandle<()>>,
+    on_unsubscribe: Option<Box<dyn FnOnce() + Send + Sync>>,
 }
 
 impl Subscription {
@@ -199,6 +341,10 @@ impl Subscription {
         if let Some(handle) = self.task_handle.take() {
             handle.abort();
         }
+
+        if let Some(on_unsubscribe) = self.on_unsubscribe.take() {
+            on_unsubscribe();
+        }
     }
 }
 
@@ -220,12 +366,55 @@ impl Subscription {
             let receiver_ref = receiver_ref;
             let mut stream_receiver = topic_receiver;
             while let Ok(message) = stream_receiver.recv().await {
-                receiver_ref
-                    .notify(message)
-                    .expect("unable to notify receiver ref");
+                // the receiver has stopped, dropping the channel so the topic is no longer
+                // considered to have subscribers on this node
+                if receiver_ref.notify(message).is_err() {
+                    break;
+                }
             }
         }));
 
-        Subscription { task_handle }
+        Subscription {
+            task_handle,
+            on_unsubscribe: None,
+        }
+    }
+
+    pub(crate) fn from_task(task_handle: JoinHandle<()>) -> Subscription {
+        Subscription {
+            task_handle: Some(task_handle),
+            on_unsubscribe: None,
+        }
+    }
+
+    pub(crate) fn group_member<A, T: Topic>(
+        group_receiver: mpsc::UnboundedReceiver<Receive<T>>,
+        receiver_ref: LocalActorRef<A>,
+        mediator: LocalActorRef<StreamMediator>,
+        leave: LeaveGroup,
+    ) -> Subscription
+    where
+        A: Actor + Handler<Receive<T>>,
+    {
+        let task_mediator = mediator.clone();
+        let task_leave = leave.clone();
+        let task_handle = Some(tokio::spawn(async move {
+            let mut group_receiver = group_receiver;
+            while let Some(message) = group_receiver.recv().await {
+                if receiver_ref.notify(message).is_err() {
+                    break;
+                }
+            }
+
+            // the member has stopped, so its partitions are reassigned to the rest of the group
+            let _ = task_mediator.notify(task_leave);
+        }));
+
+        Subscription {
+            task_handle,
+            on_unsubscribe: Some(Box::new(move || {
+                let _ = mediator.notify(leave);
+            })),
+        }
     }
 }
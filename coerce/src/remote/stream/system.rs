@@ -18,6 +18,26 @@ pub enum ClusterEvent {
     NodeAdded(RemoteNodeRef),
     NodeRemoved(RemoteNodeRef),
     LeaderChanged(NodeId),
+
+    /// The node was unable to join a cluster during startup and is running as a
+    /// standalone, single-node cluster. Only published locally.
+    Standalone,
+
+    /// The node could not be re-connected to and has been quarantined, see
+    /// [`NodeStatus::Quarantined`]. Only published locally, once the node rejoins the cluster,
+    /// it is published as [`ClusterEvent::NodeAdded`].
+    ///
+    /// [`NodeStatus::Quarantined`]: crate::remote::cluster::node::NodeStatus::Quarantined
+    NodeQuarantined(RemoteNodeRef),
+
+    /// The node's failure detector suspects the node has failed, see [`NodeHealth`].
+    /// Only published locally, since reachability is determined by each node independently.
+    ///
+    /// [`NodeHealth`]: crate::remote::cluster::node::NodeHealth
+    NodeUnreachable(RemoteNodeRef),
+
+    /// A node that was previously unreachable has become reachable again. Only published locally.
+    NodeReachable(RemoteNodeRef),
 }
 
 #[derive(Debug)]
@@ -136,6 +156,11 @@ impl StreamData for SystemEvent {
 
                     write_event(SysEvent::ClusterMemberUp, event.write_to_bytes())
                 }
+
+                ClusterEvent::Standalone
+                | ClusterEvent::NodeQuarantined(_)
+                | ClusterEvent::NodeUnreachable(_)
+                | ClusterEvent::NodeReachable(_) => None,
             },
         }
     }
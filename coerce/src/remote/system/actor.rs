@@ -1,12 +1,13 @@
-use crate::actor::message::Message;
+use crate::actor::message::{Message, MessageUnwrapErr};
 use crate::actor::{
     new_actor_id, Actor, ActorFactory, ActorId, ActorRecipe, ActorRef, ActorRefErr, IntoActorId,
 };
-use crate::remote::actor::message::{GetActorNode, RegisterActor};
+use crate::remote::actor::message::{GetActorNode, InvalidateActor, RegisterActor};
+use crate::remote::cluster::node::NodeLocation;
 use crate::remote::handler::send_proto_result;
 use crate::remote::net::message::SessionEvent;
 use crate::remote::net::proto::network::{ActorAddress, CreateActorEvent};
-use crate::remote::system::{NodeId, NodeRpcErr, RemoteActorSystem};
+use crate::remote::system::{NodeId, NodeRpcErr, RemoteActorSystem, SpawnOptions};
 use crate::remote::{RemoteActorRef, RemoteMessageHeader};
 use protobuf::well_known_types::wrappers::UInt64Value;
 use protobuf::{Message as ProtoMessage, MessageField};
@@ -24,6 +25,40 @@ pub enum RemoteActorErr {
     NodeErr(NodeRpcErr),
 }
 
+impl RemoteActorErr {
+    /// Converts the error into the [`ActorRefErr`] sent to the node that requested the actor
+    /// be created, see [`RemoteActorErr::from_deploy_err`]
+    pub fn into_actor_ref_err(self, actor_id: ActorId, actor_type: String) -> ActorRefErr {
+        match self {
+            RemoteActorErr::ActorExists => ActorRefErr::AlreadyExists(actor_id),
+            RemoteActorErr::ActorNotSupported => ActorRefErr::NotSupported {
+                actor_id,
+                message_type: String::default(),
+                actor_type,
+            },
+            RemoteActorErr::RecipeSerializationErr => {
+                ActorRefErr::Deserialisation(MessageUnwrapErr::DeserializationErr)
+            }
+            RemoteActorErr::NodeErr(NodeRpcErr::Err(e)) => e,
+            _ => ActorRefErr::ActorStartFailed,
+        }
+    }
+
+    /// Converts the error returned by a node that was asked to create an actor,
+    /// see [`RemoteActorErr::into_actor_ref_err`]
+    pub fn from_deploy_err(err: NodeRpcErr) -> RemoteActorErr {
+        match err {
+            NodeRpcErr::Err(ActorRefErr::AlreadyExists(_)) => RemoteActorErr::ActorExists,
+            NodeRpcErr::Err(ActorRefErr::NotSupported { .. }) => RemoteActorErr::ActorNotSupported,
+            NodeRpcErr::Err(ActorRefErr::Deserialisation(_)) => {
+                RemoteActorErr::RecipeSerializationErr
+            }
+            NodeRpcErr::Err(ActorRefErr::ActorStartFailed) => RemoteActorErr::ActorUnavailable,
+            err => RemoteActorErr::NodeErr(err),
+        }
+    }
+}
+
 impl RemoteActorSystem {
     pub fn register_actor(&self, actor_id: ActorId, node_id: Option<NodeId>) {
         let _ = self
@@ -32,6 +67,15 @@ impl RemoteActorSystem {
             .notify(RegisterActor::new(actor_id, node_id));
     }
 
+    /// Forgets that the actor is located on `node_id`, used once the node has told us the
+    /// actor is no longer available
+    pub fn invalidate_actor(&self, actor_id: ActorId, node_id: NodeId) {
+        let _ = self
+            .inner
+            .registry_ref
+            .notify(InvalidateActor { actor_id, node_id });
+    }
+
     pub async fn actor_ref<A: Actor>(&self, actor_id: ActorId) -> Option<ActorRef<A>> {
         // let actor_type_name = A::type_name();
         // let span = tracing::trace_span!(
@@ -112,15 +156,45 @@ impl RemoteActorSystem {
         }
     }
 
+    /// Locates the node that the provided actor is running on
+    pub async fn locate(&self, actor_id: impl IntoActorId) -> Option<NodeLocation> {
+        let node_id = self.locate_actor_node(actor_id.into_actor_id()).await?;
+        self.node_location(node_id).await
+    }
+
+    /// Creates an actor from the provided recipe, using the [`ActorFactory`] registered on the
+    /// target node (or a node chosen by placement, when `node` is `None`), returning a reference
+    /// to the deployed actor.
+    ///
+    /// Fails with [`RemoteActorErr::ActorExists`] if an actor with the provided id already
+    /// exists, or [`RemoteActorErr::ActorNotSupported`] if the node has no factory registered
+    /// for the actor type.
     pub async fn deploy_actor<F: ActorFactory>(
         &self,
         id: Option<ActorId>,
         recipe: F::Recipe,
         node: Option<NodeId>,
+    ) -> Result<ActorRef<F::Actor>, RemoteActorErr> {
+        let options = match node {
+            Some(node_id) => SpawnOptions::new().on_node(node_id),
+            None => SpawnOptions::new(),
+        };
+
+        self.deploy_actor_with_options::<F>(id, recipe, options)
+            .await
+    }
+
+    /// Deploys an actor onto the node resolved from the provided [`SpawnOptions`],
+    /// allowing placement hints such as co-locating with a related actor
+    pub async fn deploy_actor_with_options<F: ActorFactory>(
+        &self,
+        id: Option<ActorId>,
+        recipe: F::Recipe,
+        options: SpawnOptions,
     ) -> Result<ActorRef<F::Actor>, RemoteActorErr> {
         let self_id = self.node_id();
         let id = id.map_or_else(new_actor_id, |id| id);
-        let node = node.map_or_else(|| self_id, |n| n);
+        let node = self.resolve_placement(&id, &options).await;
         let actor_type: String = F::Actor::type_name().into();
 
         let recipe = recipe.write_to_bytes();
@@ -161,8 +235,12 @@ impl RemoteActorSystem {
                 )
                 .await
             {
-                Ok(address) => actor_addr = Some(address),
-                Err(e) => return Err(RemoteActorErr::NodeErr(e)),
+                Ok(address) => {
+                    // cached, so the actor can be located without asking its registry node
+                    self.register_actor(id.clone(), Some(node));
+                    actor_addr = Some(address)
+                }
+                Err(e) => return Err(RemoteActorErr::from_deploy_err(e)),
             }
         }
 
@@ -244,6 +322,21 @@ impl RemoteActorSystem {
         self.inner.config.handler_name::<A, M>()
     }
 
+    /// The version of the handler requested by messages this node sends with the provided
+    /// handler identifier, 0 if unversioned, see [`versioned`]
+    ///
+    /// [`versioned`]: crate::actor::message::versioned
+    pub fn message_version(&self, identifier: &str) -> u32 {
+        self.inner.config.message_version(identifier)
+    }
+
+    /// Changes the version of the handler requested by messages this node sends with the provided
+    /// handler identifier, `None` sends messages unversioned, so they're handled by the message's
+    /// [`Handler`](crate::actor::message::Handler)
+    pub fn set_message_version(&self, identifier: &str, version: Option<u32>) {
+        self.inner.config.set_message_version(identifier, version)
+    }
+
     pub fn create_header<A: Actor, M: Message>(
         &self,
         id: &ActorId,
@@ -252,6 +345,7 @@ impl RemoteActorSystem {
             .handler_name::<A, M>()
             .map(|handler_type| RemoteMessageHeader {
                 actor_id: id.clone(),
+                message_version: self.message_version(&handler_type),
                 handler_type,
             });
 
@@ -1,11 +1,13 @@
+use crate::actor::message::versioned::{Versioned, VersionedHandler};
 use crate::actor::message::{Handler, Message};
 use crate::actor::system::ActorSystem;
 use crate::actor::{Actor, ActorFactory, IntoActor};
 use crate::remote::actor::message::SetRemote;
 use crate::remote::actor::{
-    clients::RemoteClientRegistry, registry::RemoteRegistry, BoxedActorHandler,
-    BoxedMessageHandler, RemoteHandler,
+    clients::RemoteClientRegistry, registry::RemoteRegistry, watcher::RemoteWatcher,
+    BoxedActorHandler, BoxedMessageHandler, RemoteHandler,
 };
+use crate::remote::admin::{AdminConfig, NodeAdmin};
 use crate::remote::handler::{RemoteActorHandler, RemoteActorMessageHandler};
 use crate::remote::heartbeat::{Heartbeat, HeartbeatConfig};
 use crate::remote::stream::mediator::StreamMediator;
@@ -14,16 +16,31 @@ use crate::remote::system::{AtomicNodeId, NodeId, RemoteActorSystem, RemoteSyste
 use rand::RngCore;
 
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::actor::scheduler::ActorType;
+use crate::remote::cluster::blocklist::NodeBlocklist;
 use crate::remote::cluster::discovery::NodeDiscovery;
 
-use crate::remote::cluster::node::NodeAttributes;
+use crate::remote::cluster::node::{NodeAttributes, NODE_ROLE_ATTRIBUTE};
+use crate::remote::cluster::version::VersionSkewPolicy;
 use crate::remote::config::{RemoteSystemConfig, RemoteSystemSecurity};
 
+use crate::remote::net::chunk::ChunkingConfig;
+use crate::remote::net::client::buffer::WriteBufferConfig;
+use crate::remote::net::client::reconnect::ReconnectPolicy;
+use crate::remote::net::client::retry::ExchangeRetryPolicy;
+use crate::remote::net::compression::CompressionConfig;
+use crate::remote::net::decode::DecodeConfig;
+use crate::remote::net::encoding::{ProtobufCodec, WireFormat};
+use crate::remote::net::security::handshake::{Authenticator, CredentialsProvider, SharedToken};
 use crate::remote::net::security::ClientAuth;
+use crate::remote::net::server::pool::{HandlerExecutionConfig, HandlerExecutionPool};
+use crate::remote::net::unhandled::UnhandledFrameHook;
+use crate::remote::stream::interest::PubSubRouting;
 use chrono::Utc;
 use uuid::Uuid;
 
@@ -35,8 +52,11 @@ pub struct RemoteActorSystemBuilder {
     config_builders: Vec<ConfigBuilderFn>,
     mediator: Option<StreamMediator>,
     client_auth: Option<ClientAuth>,
+    authenticator: Option<Arc<dyn Authenticator>>,
+    credentials: Option<Arc<dyn CredentialsProvider>>,
     single_node_cluster: bool,
     node_attributes: HashMap<String, String>,
+    admin: Option<AdminConfig>,
 }
 
 impl RemoteActorSystemBuilder {
@@ -49,13 +69,19 @@ impl RemoteActorSystemBuilder {
             node_version: None,
             inner: None,
             config_builders: vec![
+                Box::new(crate::remote::actor::watcher::remote_watch),
+                Box::new(crate::remote::admin::node_admin),
+                Box::new(crate::remote::heartbeat::downing::downing),
                 #[cfg(feature = "sharding")]
                 Box::new(crate::sharding::sharding),
             ],
             mediator: Some(mediator),
             single_node_cluster: false,
             client_auth: None,
+            authenticator: None,
+            credentials: None,
             node_attributes: Default::default(),
+            admin: None,
         }
     }
 
@@ -100,6 +126,18 @@ impl RemoteActorSystemBuilder {
         self.configure(f)
     }
 
+    /// Compresses large frames sent to nodes that support one of the configured algorithms,
+    /// see [`compression`](crate::remote::net::compression)
+    pub fn with_compression(self, config: CompressionConfig) -> Self {
+        self.configure(move |c| c.compression(config))
+    }
+
+    /// Sets the format events written to other nodes are encoded in,
+    /// see [`encoding`](crate::remote::net::encoding)
+    pub fn with_wire_format(self, format: WireFormat) -> Self {
+        self.configure(move |c| c.wire_format(format))
+    }
+
     pub fn with_actor_system(mut self, sys: ActorSystem) -> Self {
         self.inner = Some(sys);
 
@@ -123,12 +161,47 @@ impl RemoteActorSystemBuilder {
         self
     }
 
+    /// Authenticates the handshake of every node connecting to this node, nodes that fail
+    /// authentication are disconnected and don't join the cluster.
+    pub fn with_authenticator(mut self, authenticator: impl Authenticator) -> Self {
+        self.authenticator = Some(Arc::new(authenticator));
+        self
+    }
+
+    /// Provides the credentials sent via the handshake when connecting to other nodes
+    pub fn with_credentials(mut self, credentials: impl CredentialsProvider) -> Self {
+        self.credentials = Some(Arc::new(credentials));
+        self
+    }
+
+    /// Sends `token` when connecting to other nodes, and only accepts nodes that send
+    /// the same token, see [`SharedToken`]
+    pub fn with_handshake_token(self, token: impl Into<Vec<u8>>) -> Self {
+        let token = SharedToken::new(token);
+        self.with_authenticator(token.clone())
+            .with_credentials(token)
+    }
+
     pub fn attribute<K: ToString, V: ToString>(mut self, key: K, value: V) -> Self {
         self.node_attributes
             .insert(key.to_string(), value.to_string());
         self
     }
 
+    /// Runs a [`NodeAdmin`] on this node, allowing authorised [`AdminCommand`]s to be sent to
+    /// it from anywhere in the cluster, see [`admin`](crate::remote::admin)
+    ///
+    /// [`AdminCommand`]: crate::remote::admin::AdminCommand
+    pub fn with_admin(mut self, config: AdminConfig) -> Self {
+        self.admin = Some(config);
+        self
+    }
+
+    /// Sets the role this node performs within the cluster, see [`RemoteActorSystem::wait_for_role`]
+    pub fn role<R: ToString>(self, role: R) -> Self {
+        self.attribute(NODE_ROLE_ATTRIBUTE, role)
+    }
+
     pub async fn build(self) -> RemoteActorSystem {
         // TODO: This needs cleaning up!
 
@@ -153,13 +226,18 @@ impl RemoteActorSystemBuilder {
             .node_tag
             .clone()
             .unwrap_or_else(|| inner.system_name().to_string());
+        let security = RemoteSystemSecurity::new(self.client_auth.unwrap_or_default())
+            .with_authenticator(self.authenticator)
+            .with_credentials(self.credentials);
+
         let config = config_builder.build(
             Some(system_tag.clone()),
             self.node_version,
-            self.client_auth,
+            security,
             self.node_attributes,
         );
 
+        let handler_pool = HandlerExecutionPool::new(config.handler_execution());
         let handler_ref = Arc::new(parking_lot::Mutex::new(RemoteHandler::new()));
         let registry_ref = RemoteRegistry::new(&inner).await;
         let clients_ref = RemoteClientRegistry::new(&mut inner).await;
@@ -171,6 +249,11 @@ impl RemoteActorSystemBuilder {
             .expect("unable to create NodeDiscovery actor");
 
         let heartbeat_ref = Heartbeat::start(&inner, config.heartbeat_config().clone()).await;
+        let watcher_ref = RemoteWatcher::new(&inner).await;
+        let admin_ref = match self.admin {
+            Some(config) => Some(NodeAdmin::new(config, &inner).await),
+            None => None,
+        };
 
         let mediator_ref = if let Some(mediator) = self.mediator {
             trace!("mediator set");
@@ -189,11 +272,13 @@ impl RemoteActorSystemBuilder {
             node_id,
             inner,
             handler_ref,
+            handler_pool,
             registry_ref,
             clients_ref,
             mediator_ref,
             discovery_ref,
             heartbeat_ref,
+            watcher_ref,
             started_at: Utc::now(),
             config,
             current_leader: Arc::new(AtomicNodeId::new(if self.single_node_cluster {
@@ -201,6 +286,11 @@ impl RemoteActorSystemBuilder {
             } else {
                 -1
             })),
+            maintenance: Arc::new(AtomicBool::new(false)),
+            node_roles: Arc::new(std::sync::OnceLock::new()),
+
+            #[cfg(feature = "tls")]
+            client_tls: Arc::new(std::sync::OnceLock::new()),
         };
 
         let inner = Arc::new(core.clone());
@@ -217,12 +307,31 @@ impl RemoteActorSystemBuilder {
             .await
             .expect("no system set");
 
+        system
+            .client_registry()
+            .send(SetRemote(system.clone()))
+            .await
+            .expect("no system set");
+
         system
             .heartbeat()
             .send(SetRemote(system.clone()))
             .await
             .expect("no system set");
 
+        system
+            .remote_watcher()
+            .send(SetRemote(system.clone()))
+            .await
+            .expect("no system set");
+
+        if let Some(admin_ref) = admin_ref {
+            admin_ref
+                .send(SetRemote(system.clone()))
+                .await
+                .expect("no system set");
+        }
+
         system
             .node_discovery()
             .send(SetRemote(system.clone()))
@@ -253,8 +362,22 @@ pub(crate) type ConfigBuilderFn =
 pub struct RemoteSystemConfigBuilder {
     system: ActorSystem,
     heartbeat: Option<HeartbeatConfig>,
+    reconnect_policy: Option<ReconnectPolicy>,
+    exchange_retry_policy: ExchangeRetryPolicy,
+    handler_execution: HandlerExecutionConfig,
     actors: HashMap<String, BoxedActorHandler>,
     handlers: HashMap<String, BoxedMessageHandler>,
+    versioned_handlers: HashMap<String, HashMap<u32, BoxedMessageHandler>>,
+    message_versions: HashMap<String, u32>,
+    unhandled_frame_hook: Option<Arc<dyn UnhandledFrameHook>>,
+    compression: Option<CompressionConfig>,
+    chunking: ChunkingConfig,
+    decoding: DecodeConfig,
+    wire_format: WireFormat,
+    write_buffer: WriteBufferConfig,
+    blocklist_path: Option<PathBuf>,
+    pubsub_routing: PubSubRouting,
+    version_skew_policy: VersionSkewPolicy,
 }
 
 impl RemoteSystemConfigBuilder {
@@ -262,8 +385,22 @@ impl RemoteSystemConfigBuilder {
         RemoteSystemConfigBuilder {
             actors: HashMap::new(),
             handlers: HashMap::new(),
+            versioned_handlers: HashMap::new(),
+            message_versions: HashMap::new(),
             system,
             heartbeat: None,
+            reconnect_policy: None,
+            exchange_retry_policy: ExchangeRetryPolicy::default(),
+            handler_execution: HandlerExecutionConfig::default(),
+            unhandled_frame_hook: None,
+            compression: None,
+            chunking: ChunkingConfig::default(),
+            decoding: DecodeConfig::default(),
+            wire_format: WireFormat::default(),
+            write_buffer: WriteBufferConfig::default(),
+            blocklist_path: None,
+            pubsub_routing: PubSubRouting::default(),
+            version_skew_policy: VersionSkewPolicy::default(),
         }
     }
 
@@ -277,6 +414,43 @@ impl RemoteSystemConfigBuilder {
         self
     }
 
+    /// Registers version `VERSION` of the handler registered with the provided identifier,
+    /// see [`versioned`](crate::actor::message::versioned)
+    ///
+    /// # Panics
+    /// Panics if `VERSION` is 0, which is reserved for messages that don't request a version
+    pub fn with_versioned_handler<A, M: Message, const VERSION: u32>(
+        &mut self,
+        identifier: impl ToString,
+    ) -> &mut Self
+    where
+        A: VersionedHandler<M, VERSION>,
+    {
+        assert_ne!(
+            VERSION, 0,
+            "handler version 0 is reserved for unversioned handlers"
+        );
+
+        let handler =
+            RemoteActorMessageHandler::<A, Versioned<M, VERSION>>::new(self.system.clone());
+
+        self.versioned_handlers
+            .entry(identifier.to_string())
+            .or_default()
+            .insert(VERSION, handler);
+
+        self
+    }
+
+    /// Sets the version of the handler requested by messages this node sends with the provided
+    /// handler identifier, the version can be changed at runtime via
+    /// [`RemoteActorSystem::set_message_version`]
+    pub fn message_version(&mut self, identifier: impl ToString, version: u32) -> &mut Self {
+        self.message_versions
+            .insert(identifier.to_string(), version);
+        self
+    }
+
     pub fn with_actor<F: ActorFactory>(&mut self, factory: F) -> &mut Self
     where
         F: 'static + ActorFactory + Send + Sync,
@@ -293,11 +467,105 @@ impl RemoteSystemConfigBuilder {
         self
     }
 
+    /// Sets how clients re-connect to nodes they lose their connection to, nodes that can't be
+    /// re-connected to within the policy's `max_attempts` are quarantined
+    pub fn reconnect_policy(&mut self, reconnect_policy: ReconnectPolicy) -> &mut Self {
+        self.reconnect_policy = Some(reconnect_policy);
+        self
+    }
+
+    /// Sets how long clients wait for nodes to reply to the identify and handshake exchanges,
+    /// and how many attempts are made before the connection is reset,
+    /// see [`retry`](crate::remote::net::client::retry)
+    pub fn exchange_retry_policy(&mut self, policy: ExchangeRetryPolicy) -> &mut Self {
+        self.exchange_retry_policy = policy;
+        self
+    }
+
+    /// Sets the maximum number of remote message handlers that can be executing concurrently
+    pub fn max_concurrent_handlers(&mut self, max_concurrent_handlers: usize) -> &mut Self {
+        self.handler_execution.max_concurrent_handlers = max_concurrent_handlers;
+        self
+    }
+
+    /// Limits the number of concurrent executions of the handler registered with the provided identifier
+    pub fn handler_concurrency_limit(
+        &mut self,
+        identifier: impl ToString,
+        limit: usize,
+    ) -> &mut Self {
+        self.handler_execution
+            .handler_limits
+            .insert(identifier.to_string(), limit);
+        self
+    }
+
+    /// Observes, and optionally handles, frames and messages this node doesn't understand,
+    /// see [`unhandled`](crate::remote::net::unhandled)
+    pub fn with_unhandled_frame_hook(&mut self, hook: impl UnhandledFrameHook) -> &mut Self {
+        self.unhandled_frame_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Compresses large frames sent to nodes that support one of the configured algorithms
+    pub fn compression(&mut self, config: CompressionConfig) -> &mut Self {
+        self.compression = Some(config);
+        self
+    }
+
+    /// Sets the size above which frames are split into chunks, and the largest message that
+    /// will be reassembled from chunks sent by other nodes, see [`chunk`](crate::remote::net::chunk)
+    pub fn chunking(&mut self, config: ChunkingConfig) -> &mut Self {
+        self.chunking = config;
+        self
+    }
+
+    /// Sets how frames received from other nodes are decoded, see [`decode`](crate::remote::net::decode)
+    pub fn decoding(&mut self, config: DecodeConfig) -> &mut Self {
+        self.decoding = config;
+        self
+    }
+
+    /// Sets the format events written to other nodes are encoded in, every node must support
+    /// the format, see [`encoding`](crate::remote::net::encoding)
+    pub fn wire_format(&mut self, format: WireFormat) -> &mut Self {
+        self.wire_format = format;
+        self
+    }
+
+    /// Limits the writes each client buffers whilst its node is unreachable,
+    /// see [`buffer`](crate::remote::net::client::buffer)
+    pub fn write_buffer(&mut self, config: WriteBufferConfig) -> &mut Self {
+        self.write_buffer = config;
+        self
+    }
+
+    /// Persists the node blocklist to the provided file, so blocked nodes remain blocked
+    /// when this node is restarted, see [`blocklist`](crate::remote::cluster::blocklist)
+    pub fn blocklist_path(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.blocklist_path = Some(path.into());
+        self
+    }
+
+    /// Sets how messages published to the cluster are routed to other nodes,
+    /// see [`interest`](crate::remote::stream::interest)
+    pub fn pubsub_routing(&mut self, routing: PubSubRouting) -> &mut Self {
+        self.pubsub_routing = routing;
+        self
+    }
+
+    /// Sets which differences between this node's version of Coerce and the versions of the
+    /// nodes that join the cluster are logged as warnings, see [`version`](crate::remote::cluster::version)
+    pub fn version_skew_policy(&mut self, policy: VersionSkewPolicy) -> &mut Self {
+        self.version_skew_policy = policy;
+        self
+    }
+
     pub fn build(
         self,
         tag: Option<String>,
         version: Option<String>,
-        client_auth: Option<ClientAuth>,
+        security: RemoteSystemSecurity,
         attributes: HashMap<String, String>,
     ) -> Arc<RemoteSystemConfig> {
         let mut handler_types = HashMap::new();
@@ -319,16 +587,40 @@ impl RemoteSystemConfigBuilder {
             .collect::<NodeAttributes>()
             .into();
 
+        let wire_codec = self.wire_format.codec().unwrap_or_else(|| {
+            warn!(
+                "wire format {:?} is not available, writing events as protobuf",
+                self.wire_format
+            );
+
+            &ProtobufCodec
+        });
+
         Arc::new(RemoteSystemConfig::new(
             node_tag,
             node_version,
             actor_types,
             handler_types,
             self.handlers,
+            self.versioned_handlers,
+            self.message_versions,
             self.actors,
             self.heartbeat.unwrap_or_default(),
+            self.reconnect_policy.unwrap_or_default(),
+            self.exchange_retry_policy,
+            self.handler_execution,
             attributes,
-            RemoteSystemSecurity::new(client_auth.unwrap_or_default()),
+            security,
+            self.unhandled_frame_hook,
+            self.compression,
+            self.chunking,
+            self.decoding,
+            wire_codec,
+            self.write_buffer,
+            self.blocklist_path
+                .map_or_else(NodeBlocklist::new, NodeBlocklist::persisted),
+            self.pubsub_routing,
+            self.version_skew_policy,
         ))
     }
 }
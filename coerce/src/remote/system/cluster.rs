@@ -1,9 +1,27 @@
-use crate::remote::actor::message::{ClientWrite, GetNodes, NewClient, RegisterNode, UpdateNodes};
-use crate::remote::cluster::node::{RemoteNode, RemoteNodeState};
+use crate::actor::message::DeliverySemantics;
+use crate::actor::ActorRefErr;
+use crate::remote::actor::message::{
+    ClientWrite, GetClients, GetNodes, NewClient, QuarantineNode, RegisterNode, RemoveClient,
+    UpdateNodes,
+};
+use crate::remote::cluster::blocklist::BlockedNode;
+use crate::remote::cluster::discovery::{Discover, Forget, Seed};
+use crate::remote::cluster::events::ClusterEventStream;
+use crate::remote::cluster::node::{
+    NodeHealth, NodeLocation, NodeSelector, NodeStatus, RemoteNode, RemoteNodeState,
+};
+use crate::remote::heartbeat::HeartbeatTick;
+use crate::remote::net::client::status::{ClientStatus, GetClientStatus};
 use crate::remote::net::client::{ClientType, RemoteClientRef};
 use crate::remote::net::message::SessionEvent;
 use crate::remote::system::{NodeId, RemoteActorSystem};
 use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+const CLUSTER_MEMBERSHIP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+const CLIENT_STATUS_TIMEOUT: Duration = Duration::from_secs(1);
 
 impl RemoteActorSystem {
     pub async fn register_node(&self, node: RemoteNode) {
@@ -22,6 +40,33 @@ impl RemoteActorSystem {
         self.inner.registry_ref.send(GetNodes).await.unwrap()
     }
 
+    /// Returns the location of the provided node, if the node is a known member of the cluster
+    pub async fn node_location(&self, node_id: NodeId) -> Option<NodeLocation> {
+        self.get_nodes()
+            .await
+            .iter()
+            .find(|node| node.id == node_id)
+            .map(NodeLocation::from)
+    }
+
+    /// Subscribes to cluster membership events, starting with the current members of the cluster,
+    /// see [`MemberEvent`].
+    ///
+    /// [`MemberEvent`]: crate::remote::cluster::events::MemberEvent
+    pub async fn subscribe_cluster_events(&self) -> ClusterEventStream {
+        ClusterEventStream::subscribe(self).await
+    }
+
+    /// Returns the reachability of the provided node, as seen by this node's failure detector,
+    /// or `None` if the node isn't known
+    pub async fn node_health(&self, node_id: NodeId) -> Option<NodeHealth> {
+        self.get_nodes()
+            .await
+            .into_iter()
+            .find(|node| node.id == node_id)
+            .map(|node| node.health)
+    }
+
     pub async fn update_nodes(&self, nodes: Vec<RemoteNodeState>) {
         self.inner
             .registry_ref
@@ -30,12 +75,218 @@ impl RemoteActorSystem {
             .unwrap()
     }
 
-    pub async fn notify_node(&self, node_id: NodeId, message: SessionEvent) {
+    /// Quarantines the node, excluding it from leadership, sharding and singleton placement
+    /// until it rejoins the cluster, see [`NodeStatus::Quarantined`].
+    ///
+    /// Returns `false` if the node isn't known, or is already quarantined.
+    pub async fn quarantine_node(&self, node_id: NodeId) -> bool {
         self.inner
-            .clients_ref
-            .send(ClientWrite(node_id, message))
+            .registry_ref
+            .send(QuarantineNode(node_id))
             .await
-            .unwrap()
+            .unwrap_or(false)
+    }
+
+    /// Re-runs discovery against a quarantined node, so it can rejoin the cluster.
+    ///
+    /// Quarantined nodes also rejoin automatically when they next connect to this node, or are
+    /// found by periodic rediscovery. Returns `false` if the node isn't quarantined.
+    pub async fn rejoin_node(&self, node_id: NodeId) -> bool {
+        let node = self
+            .get_nodes()
+            .await
+            .into_iter()
+            .find(|node| node.id == node_id && node.status == NodeStatus::Quarantined);
+
+        let node = match node {
+            Some(node) => node,
+            None => return false,
+        };
+
+        if self.is_node_blocked(node_id, &node.addr) {
+            warn!(
+                node_id = node_id,
+                addr = &node.addr,
+                "unable to rejoin blocked node"
+            );
+            return false;
+        }
+
+        info!(
+            node_id = node_id,
+            addr = &node.addr,
+            "rejoining quarantined node"
+        );
+
+        let (tx, rx) = oneshot::channel();
+        let _ = self.node_discovery().notify(Discover {
+            seed: Seed::Nodes(vec![node.into()]),
+            on_discovery_complete: Some(tx),
+        });
+
+        rx.await.unwrap_or(false)
+    }
+
+    /// Evicts the node from the cluster, the node is quarantined, disconnected from and added to
+    /// the blocklist (by both its id and address), so it's refused if it attempts to rejoin,
+    /// until it's removed with [`unblock_node`], see [`blocklist`].
+    ///
+    /// Returns `false` if the node is this node.
+    ///
+    /// [`unblock_node`]: RemoteActorSystem::unblock_node
+    /// [`blocklist`]: crate::remote::cluster::blocklist
+    pub async fn down(&self, node_id: NodeId) -> bool {
+        if node_id == self.node_id() {
+            warn!(node_id = node_id, "unable to down the current node");
+            return false;
+        }
+
+        let node = self
+            .get_nodes()
+            .await
+            .into_iter()
+            .find(|node| node.id == node_id);
+
+        let blocklist = self.config().blocklist();
+        blocklist.block(BlockedNode::Id(node_id));
+
+        if let Some(node) = &node {
+            blocklist.block(BlockedNode::Addr(node.addr.clone()));
+        }
+
+        warn!(
+            node_id = node_id,
+            addr = node.as_ref().map(|node| node.addr.as_str()),
+            "downing node"
+        );
+
+        self.quarantine_node(node_id).await;
+
+        if let Some(node) = node {
+            let clients = self
+                .client_registry()
+                .send(GetClients)
+                .await
+                .unwrap_or_default();
+
+            let _ = self
+                .client_registry()
+                .send(RemoveClient {
+                    addr: node.addr.clone(),
+                    node_id: Some(node_id),
+                })
+                .await;
+
+            for (addr, client) in clients {
+                if addr == node.addr {
+                    let _ = client.notify_stop();
+                }
+            }
+
+            let _ = self.node_discovery().notify(Forget(node.addr));
+        }
+
+        true
+    }
+
+    /// Adds the entry to the blocklist, returning `false` if it was already blocked.
+    ///
+    /// Unlike [`down`], nodes that are already members of the cluster aren't evicted.
+    ///
+    /// [`down`]: RemoteActorSystem::down
+    pub fn block_node(&self, entry: BlockedNode) -> bool {
+        self.config().blocklist().block(entry)
+    }
+
+    /// Removes the entry from the blocklist, allowing the node to rejoin the cluster,
+    /// returning `false` if it wasn't blocked
+    pub fn unblock_node(&self, entry: BlockedNode) -> bool {
+        self.config().blocklist().unblock(&entry)
+    }
+
+    pub fn blocked_nodes(&self) -> Vec<BlockedNode> {
+        self.config().blocklist().entries()
+    }
+
+    /// Whether a node with the provided id, or advertising the provided address, is blocked
+    pub fn is_node_blocked(&self, node_id: NodeId, addr: &str) -> bool {
+        self.config().blocklist().is_blocked(node_id, addr)
+    }
+
+    pub async fn notify_node(&self, node_id: NodeId, message: SessionEvent) {
+        self.write_to_node(node_id, message, None).await
+    }
+
+    /// Writes a message sent to an actor on the node, with the [`DeliverySemantics`] declared
+    /// by the message's type
+    pub async fn notify_node_with_semantics(
+        &self,
+        node_id: NodeId,
+        message: SessionEvent,
+        semantics: DeliverySemantics,
+    ) {
+        self.write_to_node(node_id, message, Some(semantics)).await
+    }
+
+    async fn write_to_node(
+        &self,
+        node_id: NodeId,
+        message: SessionEvent,
+        semantics: Option<DeliverySemantics>,
+    ) {
+        trace!("emitting message ({:?}) to node_id={}", &message, &node_id);
+
+        // encoded by the caller, rather than by the node's client
+        let write = match ClientWrite::encode(node_id, &message, self.config().wire_codec()) {
+            Some(write) => match semantics {
+                Some(semantics) => write.with_semantics(semantics),
+                None => write,
+            },
+            None => {
+                warn!(
+                    "failed to encode message (node_id={}, message={:?})",
+                    &node_id, &message
+                );
+                return;
+            }
+        };
+
+        self.inner.clients_ref.send(write).await.unwrap()
+    }
+
+    /// Puts this node into maintenance mode, the node remains a member of the cluster, and
+    /// continues to host the actors and shards it already has, but is excluded from new shard
+    /// allocations, singleton placement, placement-driven deployments and leader election.
+    ///
+    /// The state is advertised to the rest of the cluster with each heartbeat. Returns `false`
+    /// if the node was already in maintenance mode.
+    pub async fn enter_maintenance(&self) -> bool {
+        self.set_maintenance(true).await
+    }
+
+    /// Takes this node out of maintenance mode, making it eligible for new placements again,
+    /// returning `false` if the node wasn't in maintenance mode
+    pub async fn exit_maintenance(&self) -> bool {
+        self.set_maintenance(false).await
+    }
+
+    pub fn is_in_maintenance(&self) -> bool {
+        self.inner.maintenance.load(Ordering::SeqCst)
+    }
+
+    async fn set_maintenance(&self, maintenance: bool) -> bool {
+        if self.inner.maintenance.swap(maintenance, Ordering::SeqCst) == maintenance {
+            return false;
+        }
+
+        info!(
+            node_id = self.node_id(),
+            maintenance, "node maintenance mode changed"
+        );
+
+        // updates the local view of the node straight away, rather than on the next tick
+        let _ = self.heartbeat().send(HeartbeatTick).await;
+        true
     }
 
     pub fn current_leader(&self) -> Option<NodeId> {
@@ -70,4 +321,96 @@ impl RemoteActorSystem {
             .expect("get client from RemoteClientRegistry")
             .map(RemoteClientRef::from)
     }
+
+    /// Returns the state of each client this node uses to connect to other nodes, including
+    /// any writes buffered whilst the client isn't connected.
+    ///
+    /// Clients that don't respond within 1 second, for example whilst blocked on a write,
+    /// are reported as [`Unresponsive`].
+    ///
+    /// [`Unresponsive`]: crate::remote::net::client::status::ClientConnectionStatus::Unresponsive
+    pub async fn client_statuses(&self) -> Vec<ClientStatus> {
+        let clients = self
+            .client_registry()
+            .send(GetClients)
+            .await
+            .unwrap_or_default();
+
+        let statuses = clients.into_iter().map(|(addr, client)| async move {
+            match tokio::time::timeout(CLIENT_STATUS_TIMEOUT, client.send(GetClientStatus)).await {
+                Ok(Ok(status)) => status,
+                _ => ClientStatus::unresponsive(addr),
+            }
+        });
+
+        let mut statuses = futures::future::join_all(statuses).await;
+        statuses.sort_by(|a, b| a.addr.cmp(&b.addr));
+        statuses
+    }
+
+    /// Waits until at least `n` members of the cluster (including this node) are healthy,
+    /// returning the healthy members, or [`ActorRefErr::Timeout`] if the cluster has not formed
+    /// within the provided `timeout`.
+    ///
+    /// Useful for delaying serving external traffic until the cluster has formed.
+    pub async fn wait_for_members(
+        &self,
+        n: usize,
+        timeout: Duration,
+    ) -> Result<Vec<RemoteNodeState>, ActorRefErr> {
+        self.wait_for_nodes(NodeSelector::All, n, timeout).await
+    }
+
+    /// Waits until at least `n` healthy members of the cluster have the provided role
+    /// (see [`RemoteActorSystemBuilder::role`]).
+    ///
+    /// [`RemoteActorSystemBuilder::role`]: crate::remote::system::builder::RemoteActorSystemBuilder::role
+    pub async fn wait_for_role(
+        &self,
+        role: &str,
+        n: usize,
+        timeout: Duration,
+    ) -> Result<Vec<RemoteNodeState>, ActorRefErr> {
+        self.wait_for_nodes(NodeSelector::role(role), n, timeout)
+            .await
+    }
+
+    /// Waits until at least `n` healthy members of the cluster match the provided [`NodeSelector`]
+    pub async fn wait_for_nodes(
+        &self,
+        selector: NodeSelector,
+        n: usize,
+        timeout: Duration,
+    ) -> Result<Vec<RemoteNodeState>, ActorRefErr> {
+        let start = Instant::now();
+        loop {
+            let nodes: Vec<RemoteNodeState> = self
+                .get_nodes()
+                .await
+                .into_iter()
+                .filter(|node| {
+                    node.status == NodeStatus::Healthy && selector.includes(&node.clone().into())
+                })
+                .collect();
+
+            if nodes.len() >= n {
+                return Ok(nodes);
+            }
+
+            let time_taken = start.elapsed();
+            if time_taken >= timeout {
+                debug!(
+                    healthy_nodes = nodes.len(),
+                    required_nodes = n,
+                    "timed out waiting for cluster members"
+                );
+
+                return Err(ActorRefErr::Timeout {
+                    time_taken_millis: time_taken.as_millis() as u64,
+                });
+            }
+
+            tokio::time::sleep(CLUSTER_MEMBERSHIP_POLL_INTERVAL.min(timeout - time_taken)).await;
+        }
+    }
 }
@@ -1,27 +1,32 @@
 use chrono::{DateTime, Utc};
-use std::sync::atomic::AtomicI64;
+use std::sync::atomic::{AtomicBool, AtomicI64};
 use std::sync::Arc;
 
 use crate::actor::system::ActorSystem;
 use crate::actor::LocalActorRef;
 use crate::remote::actor::{
-    clients::RemoteClientRegistry, registry::RemoteRegistry, RemoteHandler,
+    clients::RemoteClientRegistry, registry::RemoteRegistry, watcher::RemoteWatcher, RemoteHandler,
 };
 use crate::remote::cluster::builder::client::ClusterClientBuilder;
 use crate::remote::cluster::builder::worker::ClusterWorkerBuilder;
 use crate::remote::cluster::discovery::NodeDiscovery;
 use crate::remote::heartbeat::Heartbeat;
+use crate::remote::net::server::pool::HandlerExecutionPool;
+#[cfg(feature = "tls")]
+use crate::remote::net::tls::ClientTls;
 use crate::remote::stream::mediator::StreamMediator;
 use crate::remote::system::builder::RemoteActorSystemBuilder;
 
 pub mod actor;
 pub mod builder;
 pub mod cluster;
+pub mod placement;
 pub mod rpc;
 
 use crate::remote::config::RemoteSystemConfig;
 pub use actor::*;
 pub use cluster::*;
+pub use placement::*;
 pub use rpc::*;
 
 #[derive(Clone)]
@@ -38,13 +43,20 @@ pub struct RemoteSystemCore {
     inner: ActorSystem,
     started_at: DateTime<Utc>,
     handler_ref: Arc<parking_lot::Mutex<RemoteHandler>>,
+    handler_pool: HandlerExecutionPool,
     registry_ref: LocalActorRef<RemoteRegistry>,
     clients_ref: LocalActorRef<RemoteClientRegistry>,
     discovery_ref: LocalActorRef<NodeDiscovery>,
     heartbeat_ref: LocalActorRef<Heartbeat>,
+    watcher_ref: LocalActorRef<RemoteWatcher>,
     mediator_ref: Option<LocalActorRef<StreamMediator>>,
     config: Arc<RemoteSystemConfig>,
     current_leader: Arc<AtomicNodeId>,
+    maintenance: Arc<AtomicBool>,
+    node_roles: Arc<std::sync::OnceLock<Vec<String>>>,
+
+    #[cfg(feature = "tls")]
+    client_tls: Arc<std::sync::OnceLock<ClientTls>>,
 }
 
 impl RemoteActorSystem {
@@ -55,15 +67,16 @@ impl RemoteActorSystem {
 
 impl RemoteSystemCore {
     pub async fn shutdown(&self) {
-        let _ = self.heartbeat_ref.stop().await;
-        let _ = self.clients_ref.stop().await;
+        let _ = self.heartbeat_ref.stop(false).await;
+        let _ = self.clients_ref.stop(false).await;
 
         if let Some(mediator_ref) = self.mediator_ref.as_ref() {
-            let _ = mediator_ref.stop().await;
+            let _ = mediator_ref.stop(false).await;
         }
 
-        let _ = self.discovery_ref.stop().await;
-        let _ = self.registry_ref.stop().await;
+        let _ = self.discovery_ref.stop(false).await;
+        let _ = self.watcher_ref.stop(false).await;
+        let _ = self.registry_ref.stop(false).await;
 
         info!("shutdown complete");
     }
@@ -102,6 +115,10 @@ impl RemoteActorSystem {
         &self.inner.started_at
     }
 
+    pub fn handler_pool(&self) -> &HandlerExecutionPool {
+        &self.inner.handler_pool
+    }
+
     pub fn heartbeat(&self) -> &LocalActorRef<Heartbeat> {
         &self.inner.heartbeat_ref
     }
@@ -110,6 +127,10 @@ impl RemoteActorSystem {
         &self.inner.registry_ref
     }
 
+    pub fn remote_watcher(&self) -> &LocalActorRef<RemoteWatcher> {
+        &self.inner.watcher_ref
+    }
+
     pub fn client_registry(&self) -> &LocalActorRef<RemoteClientRegistry> {
         &self.inner.clients_ref
     }
@@ -122,6 +143,35 @@ impl RemoteActorSystem {
         self.inner.mediator_ref.as_ref()
     }
 
+    /// The roles this node performs within the cluster,
+    /// see [`ClusterWorkerBuilder::with_roles`]
+    pub fn node_roles(&self) -> &[String] {
+        self.inner
+            .node_roles
+            .get()
+            .map_or(&[], |roles| roles.as_slice())
+    }
+
+    pub(crate) fn set_node_roles(&self, roles: Vec<String>) {
+        if self.inner.node_roles.set(roles).is_err() {
+            warn!("node roles have already been configured");
+        }
+    }
+
+    /// The TLS configuration used when connecting to other nodes, if the cluster worker
+    /// was configured with TLS
+    #[cfg(feature = "tls")]
+    pub fn client_tls(&self) -> Option<&ClientTls> {
+        self.inner.client_tls.get()
+    }
+
+    #[cfg(feature = "tls")]
+    pub(crate) fn set_client_tls(&self, client_tls: ClientTls) {
+        if self.inner.client_tls.set(client_tls).is_err() {
+            warn!("client TLS has already been configured");
+        }
+    }
+
     pub fn actor_system(&self) -> &ActorSystem {
         &self.inner.actor_system()
     }
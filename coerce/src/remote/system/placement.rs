@@ -0,0 +1,118 @@
+use crate::actor::{ActorId, IntoActorId};
+use crate::remote::cluster::node::{NodeAttribute, NodeSelector, NodeStatus};
+use crate::remote::system::{NodeId, RemoteActorSystem};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A hint used to decide which node an actor should be placed on
+#[derive(Debug, Clone)]
+pub enum PlacementHint {
+    /// Place the actor on the same node as the provided actor, reducing cross-node chatter
+    /// between tightly-coupled actors
+    CoLocateWith(ActorId),
+
+    /// Place the actor on a node with the provided attribute, preferring the local node
+    PreferNodeWithAttribute(NodeAttribute),
+}
+
+/// Options used when deploying an actor, see [`RemoteActorSystem::deploy_actor_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct SpawnOptions {
+    node: Option<NodeId>,
+    placement: Option<PlacementHint>,
+}
+
+impl SpawnOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deploys the actor onto the provided node, taking precedence over any placement hint
+    pub fn on_node(mut self, node_id: NodeId) -> Self {
+        self.node = Some(node_id);
+        self
+    }
+
+    /// Deploys the actor onto the node the provided actor is running on,
+    /// falling back to the local node if the actor cannot be located
+    pub fn co_locate_with(mut self, actor_id: impl IntoActorId) -> Self {
+        self.placement = Some(PlacementHint::CoLocateWith(actor_id.into_actor_id()));
+        self
+    }
+
+    /// Deploys the actor onto a healthy node with the provided attribute, excluding nodes in
+    /// maintenance mode, falling back to the local node if no such node exists
+    pub fn prefer_node_with_attr<K: ToString, V: ToString>(mut self, key: K, value: V) -> Self {
+        self.placement = Some(PlacementHint::PreferNodeWithAttribute((
+            key.to_string().into(),
+            value.to_string().into(),
+        )));
+        self
+    }
+
+    pub fn placement(&self) -> Option<&PlacementHint> {
+        self.placement.as_ref()
+    }
+}
+
+impl RemoteActorSystem {
+    /// Resolves which node an actor with the provided ID should be deployed onto
+    pub async fn resolve_placement(&self, actor_id: &ActorId, options: &SpawnOptions) -> NodeId {
+        if let Some(node_id) = options.node {
+            return node_id;
+        }
+
+        let node_id = match &options.placement {
+            Some(PlacementHint::CoLocateWith(related_actor_id)) => {
+                self.locate_actor_node(related_actor_id.clone()).await
+            }
+            Some(PlacementHint::PreferNodeWithAttribute(attribute)) => {
+                self.node_with_attribute(attribute.clone(), actor_id).await
+            }
+            None => None,
+        };
+
+        trace!(
+            "resolved placement (actor_id={}, placement={:?}, node_id={:?})",
+            actor_id,
+            &options.placement,
+            &node_id
+        );
+
+        node_id.unwrap_or_else(|| self.node_id())
+    }
+
+    async fn node_with_attribute(
+        &self,
+        attribute: NodeAttribute,
+        actor_id: &ActorId,
+    ) -> Option<NodeId> {
+        let selector = NodeSelector::Attribute(attribute);
+        let mut nodes: Vec<NodeId> = self
+            .get_nodes()
+            .await
+            .into_iter()
+            .filter(|node| {
+                node.status == NodeStatus::Healthy
+                    && !node.maintenance
+                    && selector.includes(&node.clone().into())
+            })
+            .map(|node| node.id)
+            .collect();
+
+        if nodes.contains(&self.node_id()) {
+            return Some(self.node_id());
+        }
+
+        if nodes.is_empty() {
+            return None;
+        }
+
+        // spread actors across the matching nodes, deterministically by actor ID
+        nodes.sort();
+
+        let mut hasher = DefaultHasher::new();
+        actor_id.hash(&mut hasher);
+        Some(nodes[hasher.finish() as usize % nodes.len()])
+    }
+}
@@ -4,10 +4,11 @@ use std::fmt::{Display, Formatter};
 use tokio::sync::oneshot;
 use uuid::Uuid;
 
+use crate::actor::dead_letter::{DeadLetter, DeadLetterReason};
 use crate::actor::{ActorId, ActorRefErr};
-use crate::remote::actor::{RemoteRequest, RemoteResponse};
+use crate::remote::actor::{RemoteRequest, RemoteResponse, RequestTarget};
 use crate::remote::net::message::SessionEvent;
-use crate::remote::net::proto::network::{ClientErr, ClientResult};
+use crate::remote::net::proto::network::{ClientErr, ClientResult, MessageRequest};
 use crate::remote::net::StreamData;
 use crate::remote::system::{NodeId, RemoteActorSystem};
 
@@ -20,8 +21,13 @@ pub enum NodeRpcErr {
 }
 
 impl Display for NodeRpcErr {
-    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeRpcErr::NodeUnreachable => write!(f, "node unreachable"),
+            NodeRpcErr::Serialisation => write!(f, "failed to decode the result"),
+            NodeRpcErr::ReceiveFailed => write!(f, "failed to receive the result"),
+            NodeRpcErr::Err(e) => write!(f, "{}", e),
+        }
     }
 }
 
@@ -50,7 +56,7 @@ impl RemoteActorSystem {
             },
             Err(e) => {
                 error!("failed to receive result, e={:?}", e);
-                Err(NodeRpcErr::ReceiveFailed)
+                Err(e)
             }
         }
     }
@@ -75,9 +81,9 @@ impl RemoteActorSystem {
                     Err(NodeRpcErr::Serialisation)
                 }
             },
-            _ => {
-                error!("failed to receive result");
-                Err(NodeRpcErr::ReceiveFailed)
+            Err(e) => {
+                error!("failed to receive result, e={:?}", e);
+                Err(e)
             }
         }
     }
@@ -94,7 +100,7 @@ impl RemoteActorSystem {
             "message_id={}, created channel, storing request",
             &message_id
         );
-        self.push_request(message_id, res_tx);
+        self.push_node_request(message_id, node_id, res_tx);
 
         trace!(
             "message_id={}, emitting event to node_id={}",
@@ -171,7 +177,25 @@ impl RemoteActorSystem {
         actor_id: ActorId,
         buffer: &[u8],
     ) -> Result<Vec<u8>, ActorRefErr> {
-        let handler = self.inner.config.message_handler(identifier);
+        self.handle_versioned_message(identifier, 0, actor_id, buffer)
+            .await
+    }
+
+    /// Handles the message with `version` of the handler registered with `identifier`, falling back
+    /// to the unversioned handler if there is no such version, see [`versioned`]
+    ///
+    /// [`versioned`]: crate::actor::message::versioned
+    pub async fn handle_versioned_message(
+        &self,
+        identifier: &str,
+        version: u32,
+        actor_id: ActorId,
+        buffer: &[u8],
+    ) -> Result<Vec<u8>, ActorRefErr> {
+        let handler = self
+            .inner
+            .config
+            .versioned_message_handler(identifier, version);
 
         if let Some(handler) = handler {
             let (tx, rx) = oneshot::channel();
@@ -182,6 +206,15 @@ impl RemoteActorSystem {
                 Err(_e) => Err(ActorRefErr::ResultChannelClosed),
             }
         } else {
+            if let Some(hook) = self.inner.config.unhandled_frame_hook() {
+                if let Some(res) = hook
+                    .on_unknown_handler(identifier, &actor_id, buffer, self)
+                    .await
+                {
+                    return res;
+                }
+            }
+
             Err(ActorRefErr::NotSupported {
                 actor_id,
                 message_type: identifier.to_string(),
@@ -190,9 +223,157 @@ impl RemoteActorSystem {
         }
     }
 
+    /// Sends a serialised message to the actor, wherever it is in the cluster, and returns the
+    /// serialised result. `identifier` is the name the message handler was registered with,
+    /// allowing clients that don't share the actor's types to interact with it.
+    pub async fn send_raw(
+        &self,
+        identifier: &str,
+        actor_id: ActorId,
+        message: Vec<u8>,
+    ) -> Result<Vec<u8>, ActorRefErr> {
+        let node_id = match self.locate_actor_node(actor_id.clone()).await {
+            Some(node_id) => node_id,
+            None => return Err(ActorRefErr::NotFound(actor_id)),
+        };
+
+        if node_id == self.node_id() {
+            let version = self.message_version(identifier);
+            return self
+                .handle_versioned_message(identifier, version, actor_id, &message)
+                .await;
+        }
+
+        let message_id = Uuid::new_v4();
+        let event = self.raw_message_request(message_id, identifier, &actor_id, message, true);
+
+        match self.node_rpc_raw(message_id, event, node_id).await {
+            Ok(res) => Ok(res),
+            Err(NodeRpcErr::Err(e)) => Err(e),
+            Err(_) => Err(ActorRefErr::ResultChannelClosed),
+        }
+    }
+
+    /// Sends a serialised message to the actor, wherever it is in the cluster,
+    /// without waiting for the result
+    pub async fn notify_raw(
+        &self,
+        identifier: &str,
+        actor_id: ActorId,
+        message: Vec<u8>,
+    ) -> Result<(), ActorRefErr> {
+        let node_id = match self.locate_actor_node(actor_id.clone()).await {
+            Some(node_id) => node_id,
+            None => return Err(ActorRefErr::NotFound(actor_id)),
+        };
+
+        if node_id == self.node_id() {
+            let version = self.message_version(identifier);
+            return self
+                .handle_versioned_message(identifier, version, actor_id, &message)
+                .await
+                .map(|_| ());
+        }
+
+        let message_id = Uuid::new_v4();
+        let event = self.raw_message_request(message_id, identifier, &actor_id, message, false);
+
+        self.notify_node(node_id, event).await;
+        Ok(())
+    }
+
+    fn raw_message_request(
+        &self,
+        message_id: Uuid,
+        identifier: &str,
+        actor_id: &ActorId,
+        message: Vec<u8>,
+        requires_response: bool,
+    ) -> SessionEvent {
+        SessionEvent::NotifyActor(MessageRequest {
+            message_id: message_id.to_string(),
+            handler_type: identifier.to_string(),
+            actor_id: actor_id.to_string(),
+            message,
+            requires_response,
+            origin_node_id: self.node_id(),
+            message_version: self.message_version(identifier),
+            ..Default::default()
+        })
+    }
+
     pub fn push_request(&self, id: Uuid, res_tx: oneshot::Sender<RemoteResponse>) {
         let mut handler = self.inner.handler_ref.lock();
-        handler.push_request(id, RemoteRequest { res_tx });
+        handler.push_request(
+            id,
+            RemoteRequest {
+                res_tx,
+                node_id: None,
+                target: None,
+            },
+        );
+    }
+
+    /// Stores a request sent to `node_id`, the request fails with [`ActorRefErr::ActorUnavailable`]
+    /// if the node is removed from the cluster before the result is received
+    pub fn push_node_request(
+        &self,
+        id: Uuid,
+        node_id: NodeId,
+        res_tx: oneshot::Sender<RemoteResponse>,
+    ) {
+        let mut handler = self.inner.handler_ref.lock();
+        handler.push_request(
+            id,
+            RemoteRequest {
+                res_tx,
+                node_id: Some(node_id),
+                target: None,
+            },
+        );
+    }
+
+    pub(crate) fn push_actor_request(
+        &self,
+        id: Uuid,
+        node_id: NodeId,
+        target: RequestTarget,
+        res_tx: oneshot::Sender<RemoteResponse>,
+    ) {
+        let mut handler = self.inner.handler_ref.lock();
+        handler.push_request(
+            id,
+            RemoteRequest {
+                res_tx,
+                node_id: Some(node_id),
+                target: Some(target),
+            },
+        );
+    }
+
+    /// Fails every request sent to `node_id` which is still waiting for a result, publishing
+    /// requests sent to actors as dead letters, returns the number of requests failed
+    pub(crate) fn fail_node_requests(&self, node_id: NodeId) -> usize {
+        let requests = self.inner.handler_ref.lock().pop_node_requests(node_id);
+        let failed = requests.len();
+
+        for request in requests {
+            if let Some(target) = request.target {
+                self.actor_system().dead_letters().publish(DeadLetter {
+                    actor_id: target.actor_id,
+                    actor_type: target.actor_type,
+                    message_type: target.message_type,
+                    reason: DeadLetterReason::NodeRemoved,
+                    payload: None,
+                });
+            }
+
+            let _ = request
+                .res_tx
+                .send(RemoteResponse::Err(ActorRefErr::ActorUnavailable));
+        }
+
+        failed
     }
 
     pub fn pop_request(&self, id: Uuid) -> Option<oneshot::Sender<RemoteResponse>> {
@@ -204,4 +385,10 @@ impl RemoteActorSystem {
         let handler = self.inner.handler_ref.lock();
         handler.inflight_request_count()
     }
+
+    /// The number of requests sent to `node_id` which are still waiting for a result
+    pub fn inflight_node_request_count(&self, node_id: NodeId) -> usize {
+        let handler = self.inner.handler_ref.lock();
+        handler.inflight_node_request_count(node_id)
+    }
 }
@@ -0,0 +1,279 @@
+//! Routing of requests received from external protocols to actors
+//!
+//! Ingresses, such as HTTP servers, gRPC services or message broker consumers, identify the
+//! target of each request they receive with an external identifier, a URL path, an MQTT topic,
+//! a Kafka key etc. A [`MessageRouter`] maps these identifiers to actors (or sharded entities)
+//! using a set of routing rules registered up front, so each ingress only needs to hand the
+//! router the identifier and the request's payload, rather than implementing its own routing.
+//!
+//! Each route pairs a [`RoutePattern`] with a [`RouteTarget`] and the message type it accepts.
+//! Routes are matched in the order they were registered, the first route whose pattern
+//! matches the identifier receives the request. Parameters captured by the pattern can be
+//! used to build the target's actor id, and to decode the message.
+//!
+//! [`MessageRouter::dispatch`] decodes the payload into the route's message type, and returns the
+//! serialised result, for ingresses that deal in raw bytes. [`MessageRouter::send`] takes
+//! an already decoded message, only matching routes that accept its type, so a single pattern
+//! can be shared by several message types.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let router = MessageRouter::builder(remote.clone())
+//!     .route::<Telemetry, _>(
+//!         "devices/{device_id}/telemetry",
+//!         ActorTarget::<Device>::new("device-{device_id}"),
+//!     )
+//!     .route_with(
+//!         "/orders/{order_id}",
+//!         EntityTarget::new(orders, "order-{order_id}"),
+//!         |params, payload| GetOrder::decode(params.get("order_id"), payload),
+//!     )
+//!     .build();
+//!
+//! // in an MQTT consumer
+//! router.dispatch(&publish.topic, publish.payload).await?;
+//!
+//! // in an HTTP handler
+//! let order = router.send(request.uri().path(), GetOrder::default()).await?;
+//! ```
+
+use crate::actor::message::{Message, MessageUnwrapErr, MessageWrapErr};
+use crate::actor::{ActorId, ActorRefErr};
+use crate::remote::system::RemoteActorSystem;
+use std::any::{Any, TypeId};
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::Arc;
+
+pub mod pattern;
+pub mod target;
+
+pub use pattern::{IdTemplate, RouteParams, RoutePattern};
+pub use target::{ActorTarget, RouteTarget};
+
+#[cfg(feature = "sharding")]
+pub use target::EntityTarget;
+
+#[derive(Debug)]
+pub enum RouteErr {
+    NoRoute(String),
+    MissingParam(String),
+    ActorNotFound(ActorId),
+    Serialisation(MessageWrapErr),
+    Deserialisation(MessageUnwrapErr),
+    ActorRef(ActorRefErr),
+}
+
+/// Maps external identifiers to actors, see [`router`](crate::router)
+#[derive(Clone)]
+pub struct MessageRouter {
+    core: Arc<RouterCore>,
+}
+
+struct RouterCore {
+    system: RemoteActorSystem,
+    routes: Vec<Route>,
+}
+
+struct Route {
+    pattern: RoutePattern,
+    handler: Box<dyn RouteHandler>,
+}
+
+pub struct MessageRouterBuilder {
+    system: RemoteActorSystem,
+    routes: Vec<Route>,
+}
+
+impl MessageRouter {
+    pub fn builder(system: RemoteActorSystem) -> MessageRouterBuilder {
+        MessageRouterBuilder {
+            system,
+            routes: vec![],
+        }
+    }
+
+    /// Decodes the payload into the message type of the first route matching the identifier,
+    /// sends it to the route's target and returns the serialised result
+    pub async fn dispatch(&self, identifier: &str, payload: Vec<u8>) -> Result<Vec<u8>, RouteErr> {
+        let (route, params) = self
+            .core
+            .routes
+            .iter()
+            .find_map(|route| {
+                route
+                    .pattern
+                    .matches(identifier)
+                    .map(|params| (route, params))
+            })
+            .ok_or_else(|| RouteErr::NoRoute(identifier.to_string()))?;
+
+        route
+            .handler
+            .dispatch(&self.core.system, &params, payload)
+            .await
+    }
+
+    /// Sends the message to the target of the first route matching the identifier
+    /// that accepts messages of type `M`
+    pub async fn send<M: Message>(
+        &self,
+        identifier: &str,
+        message: M,
+    ) -> Result<M::Result, RouteErr> {
+        let message_type = TypeId::of::<M>();
+        let (route, params) = self
+            .core
+            .routes
+            .iter()
+            .filter(|route| route.handler.message_type() == message_type)
+            .find_map(|route| {
+                route
+                    .pattern
+                    .matches(identifier)
+                    .map(|params| (route, params))
+            })
+            .ok_or_else(|| RouteErr::NoRoute(identifier.to_string()))?;
+
+        let result = route
+            .handler
+            .send(&self.core.system, &params, Box::new(message))
+            .await?;
+
+        Ok(*result
+            .downcast::<M::Result>()
+            .expect("route result type mismatch"))
+    }
+
+    /// Whether any route matches the identifier
+    pub fn matches(&self, identifier: &str) -> bool {
+        self.core
+            .routes
+            .iter()
+            .any(|route| route.pattern.matches(identifier).is_some())
+    }
+}
+
+impl MessageRouterBuilder {
+    /// Routes messages of type `M` to the target, decoding payloads with [`Message::from_bytes`]
+    ///
+    /// # Panics
+    /// Panics if the pattern is invalid, see [`RoutePattern`]
+    pub fn route<M: Message, T: RouteTarget<M>>(&mut self, pattern: &str, target: T) -> &mut Self {
+        self.route_with(pattern, target, |_params, payload| M::from_bytes(payload))
+    }
+
+    /// Routes messages of type `M` to the target, decoding payloads with the provided decoder,
+    /// allowing the message to be built from the route's parameters as well as the payload
+    ///
+    /// # Panics
+    /// Panics if the pattern is invalid, see [`RoutePattern`]
+    pub fn route_with<M: Message, T: RouteTarget<M>, D>(
+        &mut self,
+        pattern: &str,
+        target: T,
+        decoder: D,
+    ) -> &mut Self
+    where
+        D: Fn(&RouteParams, Vec<u8>) -> Result<M, MessageUnwrapErr> + 'static + Send + Sync,
+    {
+        let pattern = RoutePattern::parse(pattern).unwrap_or_else(|e| panic!("{}", e));
+
+        self.routes.push(Route {
+            pattern,
+            handler: Box::new(TypedRoute {
+                target,
+                decoder,
+                _m: PhantomData,
+            }),
+        });
+
+        self
+    }
+
+    pub fn build(&mut self) -> MessageRouter {
+        MessageRouter {
+            core: Arc::new(RouterCore {
+                system: self.system.clone(),
+                routes: mem::take(&mut self.routes),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+trait RouteHandler: 'static + Send + Sync {
+    fn message_type(&self) -> TypeId;
+
+    async fn dispatch(
+        &self,
+        system: &RemoteActorSystem,
+        params: &RouteParams,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, RouteErr>;
+
+    async fn send(
+        &self,
+        system: &RemoteActorSystem,
+        params: &RouteParams,
+        message: Box<dyn Any + Send>,
+    ) -> Result<Box<dyn Any + Send>, RouteErr>;
+}
+
+struct TypedRoute<M, T, D> {
+    target: T,
+    decoder: D,
+    _m: PhantomData<fn() -> M>,
+}
+
+#[async_trait]
+impl<M: Message, T: RouteTarget<M>, D> RouteHandler for TypedRoute<M, T, D>
+where
+    D: Fn(&RouteParams, Vec<u8>) -> Result<M, MessageUnwrapErr> + 'static + Send + Sync,
+{
+    fn message_type(&self) -> TypeId {
+        TypeId::of::<M>()
+    }
+
+    async fn dispatch(
+        &self,
+        system: &RemoteActorSystem,
+        params: &RouteParams,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, RouteErr> {
+        let message = (self.decoder)(params, payload).map_err(RouteErr::Deserialisation)?;
+        let result = self.target.send(system, params, message).await?;
+
+        M::write_remote_result(result).map_err(RouteErr::Serialisation)
+    }
+
+    async fn send(
+        &self,
+        system: &RemoteActorSystem,
+        params: &RouteParams,
+        message: Box<dyn Any + Send>,
+    ) -> Result<Box<dyn Any + Send>, RouteErr> {
+        let message = *message
+            .downcast::<M>()
+            .expect("route message type mismatch");
+
+        let result = self.target.send(system, params, message).await?;
+        Ok(Box::new(result))
+    }
+}
+
+impl Display for RouteErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RouteErr::NoRoute(identifier) => write!(f, "no route matches `{}`", identifier),
+            RouteErr::MissingParam(name) => write!(f, "route parameter `{}` not captured", name),
+            RouteErr::ActorNotFound(actor_id) => write!(f, "actor (id={}) not found", actor_id),
+            RouteErr::Serialisation(e) => write!(f, "failed to serialise result: {}", e),
+            RouteErr::Deserialisation(e) => write!(f, "failed to deserialise message: {}", e),
+            RouteErr::ActorRef(e) => write!(f, "failed to send message: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RouteErr {}
@@ -0,0 +1,184 @@
+use crate::actor::{ActorId, IntoActorId};
+use crate::router::RouteErr;
+use std::fmt::{Display, Formatter};
+
+const SEPARATOR: char = '/';
+
+/// Matches external identifiers, such as URL paths, MQTT topics or Kafka keys
+///
+/// Identifiers are split into segments on `/`, each segment of the pattern is either:
+/// - a literal, which must match the identifier's segment exactly
+/// - `{name}`, which matches any single segment, capturing it as the parameter `name`
+/// - `*`, which matches any single segment
+/// - `#`, which matches the rest of the identifier (including no segments at all), and must
+///   be the last segment of the pattern
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RoutePattern {
+    pattern: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Segment {
+    Literal(String),
+    Param(String),
+    Any,
+    Rest,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InvalidPattern {
+    pub pattern: String,
+    pub reason: &'static str,
+}
+
+impl RoutePattern {
+    pub fn parse(pattern: &str) -> Result<Self, InvalidPattern> {
+        let invalid = |reason| InvalidPattern {
+            pattern: pattern.to_string(),
+            reason,
+        };
+
+        let mut segments = vec![];
+        for segment in pattern.split(SEPARATOR) {
+            if segments.last() == Some(&Segment::Rest) {
+                return Err(invalid("`#` must be the last segment"));
+            }
+
+            segments.push(match segment {
+                "*" => Segment::Any,
+                "#" => Segment::Rest,
+                _ => match parse_param(segment) {
+                    Some("") => return Err(invalid("parameter names cannot be empty")),
+                    Some(name) => {
+                        if segments.contains(&Segment::Param(name.to_string())) {
+                            return Err(invalid("parameter names must be unique"));
+                        }
+
+                        Segment::Param(name.to_string())
+                    }
+                    None if segment.contains(['{', '}']) => {
+                        return Err(invalid("parameters must span a whole segment"))
+                    }
+                    None => Segment::Literal(segment.to_string()),
+                },
+            });
+        }
+
+        Ok(RoutePattern {
+            pattern: pattern.to_string(),
+            segments,
+        })
+    }
+
+    /// Matches the identifier against the pattern, returning the captured parameters
+    /// if the identifier matches
+    pub fn matches(&self, identifier: &str) -> Option<RouteParams> {
+        let mut params = RouteParams::default();
+        let mut parts = identifier.split(SEPARATOR);
+
+        for segment in &self.segments {
+            if segment == &Segment::Rest {
+                return Some(params);
+            }
+
+            let part = parts.next()?;
+            match segment {
+                Segment::Literal(literal) if literal != part => return None,
+                Segment::Param(name) => params.0.push((name.clone(), part.to_string())),
+                _ => {}
+            }
+        }
+
+        parts.next().is_none().then_some(params)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+fn parse_param(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{')?.strip_suffix('}')
+}
+
+/// Parameters captured from an identifier by a [`RoutePattern`]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RouteParams(Vec<(String, String)>);
+
+impl RouteParams {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// Builds an actor id from a route's parameters, for example `order-{order_id}`
+#[derive(Debug, Clone)]
+pub struct IdTemplate {
+    template: String,
+}
+
+impl IdTemplate {
+    pub fn new(template: impl ToString) -> Self {
+        IdTemplate {
+            template: template.to_string(),
+        }
+    }
+
+    pub fn render(&self, params: &RouteParams) -> Result<ActorId, RouteErr> {
+        let mut id = String::with_capacity(self.template.len());
+        let mut rest = self.template.as_str();
+
+        while let Some(start) = rest.find('{') {
+            let end = match rest[start..].find('}') {
+                Some(end) => start + end,
+                None => break,
+            };
+
+            let name = &rest[start + 1..end];
+            let value = params
+                .get(name)
+                .ok_or_else(|| RouteErr::MissingParam(name.to_string()))?;
+
+            id.push_str(&rest[..start]);
+            id.push_str(value);
+            rest = &rest[end + 1..];
+        }
+
+        id.push_str(rest);
+        Ok(id.into_actor_id())
+    }
+}
+
+impl From<&str> for IdTemplate {
+    fn from(template: &str) -> Self {
+        IdTemplate::new(template)
+    }
+}
+
+impl From<String> for IdTemplate {
+    fn from(template: String) -> Self {
+        IdTemplate { template }
+    }
+}
+
+impl Display for InvalidPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid route pattern `{}`: {}",
+            self.pattern, self.reason
+        )
+    }
+}
+
+impl std::error::Error for InvalidPattern {}
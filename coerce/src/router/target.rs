@@ -0,0 +1,156 @@
+use crate::actor::message::{Handler, Message};
+use crate::actor::Actor;
+use crate::remote::system::RemoteActorSystem;
+use crate::router::pattern::{IdTemplate, RouteParams};
+use crate::router::RouteErr;
+use std::marker::PhantomData;
+
+#[cfg(feature = "sharding")]
+use crate::actor::ActorFactory;
+#[cfg(feature = "sharding")]
+use crate::sharding::extractor::{ExtractorRef, MessageExtractor};
+#[cfg(feature = "sharding")]
+use crate::sharding::Sharding;
+#[cfg(feature = "sharding")]
+use std::sync::Arc;
+
+/// Where the messages received by a route are sent
+#[async_trait]
+pub trait RouteTarget<M: Message>: 'static + Send + Sync {
+    async fn send(
+        &self,
+        system: &RemoteActorSystem,
+        params: &RouteParams,
+        message: M,
+    ) -> Result<M::Result, RouteErr>;
+}
+
+/// Sends messages to an actor, which may be running on any node in the cluster
+pub struct ActorTarget<A: Actor> {
+    actor_id: IdTemplate,
+    _a: PhantomData<A>,
+}
+
+impl<A: Actor> ActorTarget<A> {
+    pub fn new(actor_id: impl Into<IdTemplate>) -> Self {
+        ActorTarget {
+            actor_id: actor_id.into(),
+            _a: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Actor, M: Message> RouteTarget<M> for ActorTarget<A>
+where
+    A: Handler<M>,
+{
+    async fn send(
+        &self,
+        system: &RemoteActorSystem,
+        params: &RouteParams,
+        message: M,
+    ) -> Result<M::Result, RouteErr> {
+        let actor_id = self.actor_id.render(params)?;
+        let actor = system
+            .actor_ref::<A>(actor_id.clone())
+            .await
+            .ok_or(RouteErr::ActorNotFound(actor_id))?;
+
+        actor.send(message).await.map_err(RouteErr::ActorRef)
+    }
+}
+
+impl<A: Actor> Clone for ActorTarget<A> {
+    fn clone(&self) -> Self {
+        ActorTarget::new(self.actor_id.clone())
+    }
+}
+
+#[cfg(feature = "sharding")]
+type RecipeFn<F> = Arc<dyn Fn(&RouteParams) -> Option<<F as ActorFactory>::Recipe> + Send + Sync>;
+
+/// Sends messages to a sharded entity, creating the entity with the recipe built from
+/// the route's parameters (if any) if it isn't already running
+#[cfg(feature = "sharding")]
+pub struct EntityTarget<F: ActorFactory> {
+    sharding: Sharding<F>,
+    entity_id: IdTemplate,
+    recipe: Option<RecipeFn<F>>,
+}
+
+#[cfg(feature = "sharding")]
+impl<F: ActorFactory> EntityTarget<F> {
+    pub fn new(sharding: Sharding<F>, entity_id: impl Into<IdTemplate>) -> Self {
+        EntityTarget {
+            sharding,
+            entity_id: entity_id.into(),
+            recipe: None,
+        }
+    }
+
+    pub fn with_recipe(
+        mut self,
+        recipe: impl Fn(&RouteParams) -> Option<F::Recipe> + 'static + Send + Sync,
+    ) -> Self {
+        self.recipe = Some(Arc::new(recipe));
+        self
+    }
+}
+
+#[cfg(feature = "sharding")]
+#[async_trait]
+impl<F: ActorFactory, M: Message> RouteTarget<M> for EntityTarget<F>
+where
+    F: 'static + Send + Sync,
+    F::Actor: Handler<M>,
+{
+    async fn send(
+        &self,
+        _system: &RemoteActorSystem,
+        params: &RouteParams,
+        message: M,
+    ) -> Result<M::Result, RouteErr> {
+        let entity_id = self.entity_id.render(params)?;
+        let recipe = self.recipe.as_ref().and_then(|recipe| recipe(params));
+
+        self.sharding
+            .get(entity_id, recipe)
+            .send(message)
+            .await
+            .map_err(RouteErr::ActorRef)
+    }
+}
+
+#[cfg(feature = "sharding")]
+impl<F: ActorFactory> Clone for EntityTarget<F> {
+    fn clone(&self) -> Self {
+        EntityTarget {
+            sharding: self.sharding.clone(),
+            entity_id: self.entity_id.clone(),
+            recipe: self.recipe.clone(),
+        }
+    }
+}
+
+/// Sends messages to the sharded entity chosen by the extractor, the route's parameters
+/// are ignored
+#[cfg(feature = "sharding")]
+#[async_trait]
+impl<F: ActorFactory, E, M: Message> RouteTarget<M> for ExtractorRef<F, E>
+where
+    F: 'static + Send + Sync,
+    E: MessageExtractor<F, M>,
+    F::Actor: Handler<M>,
+{
+    async fn send(
+        &self,
+        _system: &RemoteActorSystem,
+        _params: &RouteParams,
+        message: M,
+    ) -> Result<M::Result, RouteErr> {
+        ExtractorRef::send(self, message)
+            .await
+            .map_err(RouteErr::ActorRef)
+    }
+}
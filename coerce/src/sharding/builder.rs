@@ -1,12 +1,25 @@
 use crate::actor::{Actor, ActorFactory};
+use crate::remote::cluster::node::NodeAttribute;
 use crate::remote::system::RemoteActorSystem;
+use crate::sharding::coordinator::strategy::ShardAllocationStrategy;
+use crate::sharding::host::warmup::WarmUpProvider;
 use crate::sharding::host::ShardAllocator;
+use crate::sharding::shard::passivation::PassivationConfig;
 use crate::sharding::Sharding;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 pub struct ShardingBuilder<A: ActorFactory> {
     shard_allocator: Option<Box<dyn ShardAllocator>>,
     shard_entity: Option<String>,
+    preferred_node_attribute: Option<NodeAttribute>,
+    role: Option<String>,
+    snapshot_shipping: bool,
+    remember_entities: bool,
+    warm_up: Option<Arc<dyn WarmUpProvider>>,
+    allocation_strategy: Option<Arc<dyn ShardAllocationStrategy>>,
+    max_rebalance_shards: Option<usize>,
+    passivation: Option<PassivationConfig>,
     system: Option<RemoteActorSystem>,
     _a: PhantomData<A>,
 }
@@ -16,6 +29,14 @@ impl<A: ActorFactory> Sharding<A> {
         ShardingBuilder {
             shard_allocator: None,
             shard_entity: None,
+            preferred_node_attribute: None,
+            role: None,
+            snapshot_shipping: false,
+            remember_entities: true,
+            warm_up: None,
+            allocation_strategy: None,
+            max_rebalance_shards: None,
+            passivation: None,
             system: Some(system),
             _a: PhantomData,
         }
@@ -33,6 +54,95 @@ impl<A: ActorFactory> ShardingBuilder<A> {
         self
     }
 
+    /// Prefers allocating shards to nodes with the provided attribute, co-locating the
+    /// sharded entities with the data or actors that live on those nodes
+    pub fn prefer_node_with_attr<K: ToString, V: ToString>(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> &mut Self {
+        self.preferred_node_attribute = Some((key.to_string().into(), value.to_string().into()));
+        self
+    }
+
+    /// Only allocates shards to nodes with the provided role (see [`ClusterWorkerBuilder::with_roles`]),
+    /// shards are left unallocated until a node with the role is available.
+    ///
+    /// Every node should still start sharding, so entities can be reached from any node.
+    ///
+    /// [`ClusterWorkerBuilder::with_roles`]: crate::remote::cluster::builder::worker::ClusterWorkerBuilder::with_roles
+    pub fn with_role<R: ToString>(&mut self, role: R) -> &mut Self {
+        self.role = Some(role.to_string());
+        self
+    }
+
+    /// Ships a snapshot of each persistent entity to the node its shard is moved to when shards
+    /// are rebalanced, rather than the entity replaying its journal from the storage backend.
+    ///
+    /// Entities provide the snapshot via [`PersistentActor::on_migrate`], entities that don't are
+    /// recovered from storage as usual.
+    ///
+    /// [`PersistentActor::on_migrate`]: crate::persistent::PersistentActor::on_migrate
+    pub fn with_snapshot_shipping(&mut self) -> &mut Self {
+        self.snapshot_shipping = true;
+        self
+    }
+
+    /// Whether shards remember which entities are running (via the persistence layer), and
+    /// restart them as soon as the shard is started again, whether the shard was moved to another
+    /// node when the cluster was rebalanced, or the node hosting it was lost or restarted.
+    ///
+    /// Defaults to `true`, so recovered entities are restarted straight away. When `false`,
+    /// entities are only restarted once they next receive a message, from the recipe they
+    /// were created with.
+    pub fn with_remember_entities(&mut self, remember_entities: bool) -> &mut Self {
+        self.remember_entities = remember_entities;
+        self
+    }
+
+    /// Starts the entities returned by the provider once a shard has been allocated to this node
+    /// and has recovered, reducing the latency of the first requests after a deploy.
+    ///
+    /// See [`warmup`] for more details.
+    ///
+    /// [`warmup`]: crate::sharding::host::warmup
+    pub fn with_warm_up<P: WarmUpProvider>(&mut self, provider: P) -> &mut Self {
+        self.warm_up = Some(Arc::new(provider));
+        self
+    }
+
+    /// Decides which node shards are allocated to, and which shards are moved when the cluster
+    /// is rebalanced, shards are allocated to the node with the fewest shards by default.
+    ///
+    /// See [`strategy`] for more details.
+    ///
+    /// [`strategy`]: crate::sharding::coordinator::strategy
+    pub fn with_allocation_strategy<S: ShardAllocationStrategy>(
+        &mut self,
+        strategy: S,
+    ) -> &mut Self {
+        self.allocation_strategy = Some(Arc::new(strategy));
+        self
+    }
+
+    /// The maximum number of shards moved at a time when the cluster is rebalanced,
+    /// further shards are moved once the previous shards have been reallocated
+    pub fn with_max_rebalance_shards(&mut self, max_rebalance_shards: usize) -> &mut Self {
+        self.max_rebalance_shards = Some(max_rebalance_shards);
+        self
+    }
+
+    /// Stops entities that haven't received a message for [`PassivationConfig::idle_timeout`],
+    /// re-creating them when they next receive a message.
+    ///
+    /// See [`passivation`] for more details.
+    ///
+    /// [`passivation`]: crate::sharding::shard::passivation
+    pub fn with_passivation(&mut self, passivation: PassivationConfig) -> &mut Self {
+        self.passivation = Some(passivation);
+        self
+    }
+
     pub async fn build(&mut self) -> Sharding<A> {
         Sharding::start(
             self.shard_entity
@@ -40,6 +150,14 @@ impl<A: ActorFactory> ShardingBuilder<A> {
                 .unwrap_or_else(|| A::Actor::type_name().to_string()),
             self.system.take().unwrap(),
             self.shard_allocator.take(),
+            self.preferred_node_attribute.take(),
+            self.role.take(),
+            self.snapshot_shipping,
+            self.remember_entities,
+            self.warm_up.take(),
+            self.allocation_strategy.take(),
+            self.max_rebalance_shards.take(),
+            self.passivation.take(),
         )
         .await
     }
@@ -0,0 +1,336 @@
+//! Bulk operations over sharded entities
+//!
+//! [`Sharding::broadcast_to_entities`] sends a message to every selected entity, either a list
+//! of entity ids or the entities matching an [`IndexQuery`] against the entity type's
+//! [`EntityIndex`], allowing administrative sweeps (recomputing state, migrating schemas,
+//! expiring entities etc.) to be driven by the framework.
+//!
+//! The message is serialised once and sent to each entity via the local [`ShardHost`], entities
+//! which aren't running are started, as they would be by a regular request. The number of in-flight requests and the rate
+//! requests are sent at can both be limited, so a sweep doesn't overwhelm the cluster, and the
+//! operation reports its progress as each entity completes.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let operation = sharding
+//!     .broadcast_to_entities(EntitySelector::query(index, IndexQuery::eq("status", "overdue")), Expire)
+//!     .max_in_flight(50)
+//!     .rate_limit(1000)
+//!     .start();
+//!
+//! let mut progress = operation.progress();
+//! while progress.changed().await.is_ok() {
+//!     info!("{}", *progress.borrow());
+//! }
+//!
+//! let report = operation.wait().await;
+//! ```
+//!
+//! [`ShardHost`]: crate::sharding::host::ShardHost
+
+use crate::actor::message::{Handler, Message};
+use crate::actor::{Actor, ActorFactory, ActorId, ActorRecipe, ActorRefErr, IntoActorId};
+use crate::sharding::host::request::EntityRequest;
+use crate::sharding::index::{EntityIndex, IndexQuery};
+use crate::sharding::{Sharding, ShardingCore};
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{oneshot, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, MissedTickBehavior};
+
+const DEFAULT_MAX_IN_FLIGHT: usize = 100;
+
+/// The entities a bulk operation is applied to
+pub enum EntitySelector<A: Actor> {
+    Ids(Vec<ActorId>),
+    Query(EntityIndex<A>, IndexQuery),
+}
+
+impl<A: Actor> EntitySelector<A> {
+    pub fn ids<I: IntoActorId>(ids: impl IntoIterator<Item = I>) -> Self {
+        Self::Ids(ids.into_iter().map(|id| id.into_actor_id()).collect())
+    }
+
+    /// Selects the entities matching the query, the query is run once, when the operation starts
+    pub fn query(index: EntityIndex<A>, query: IndexQuery) -> Self {
+        Self::Query(index, query)
+    }
+
+    async fn resolve(self) -> Result<Vec<ActorId>, ActorRefErr> {
+        match self {
+            Self::Ids(ids) => Ok(ids),
+            Self::Query(index, query) => index.find(query).await,
+        }
+    }
+}
+
+impl<A: Actor> From<Vec<ActorId>> for EntitySelector<A> {
+    fn from(ids: Vec<ActorId>) -> Self {
+        Self::Ids(ids)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct BulkProgress {
+    /// The number of selected entities, known once the selection has been resolved
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl BulkProgress {
+    pub fn completed(&self) -> usize {
+        self.succeeded + self.failed
+    }
+}
+
+impl Display for BulkProgress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} entities complete ({} failed)",
+            self.completed(),
+            self.total,
+            self.failed
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct BulkReport {
+    pub progress: BulkProgress,
+
+    /// The entities the message could not be delivered to, or that failed to handle it
+    pub failures: Vec<(ActorId, ActorRefErr)>,
+
+    /// Whether the operation was cancelled before every entity was sent the message
+    pub cancelled: bool,
+}
+
+impl BulkReport {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty() && !self.cancelled
+    }
+}
+
+/// Configures a bulk operation, created via [`Sharding::broadcast_to_entities`]
+pub struct BulkOperationBuilder<A: ActorFactory> {
+    selector: EntitySelector<A::Actor>,
+    recipe: Option<A::Recipe>,
+    task: BulkTask,
+}
+
+struct BulkTask {
+    sharding: Arc<ShardingCore>,
+    message_type: Result<String, ActorRefErr>,
+    message: Result<Vec<u8>, ActorRefErr>,
+    recipe: Option<Arc<Vec<u8>>>,
+    max_in_flight: usize,
+    rate_limit: Option<u32>,
+}
+
+/// A running bulk operation
+pub struct BulkOperation {
+    progress: watch::Receiver<BulkProgress>,
+    cancelled: Arc<AtomicBool>,
+    task: JoinHandle<Result<BulkReport, ActorRefErr>>,
+}
+
+impl<A: ActorFactory> Sharding<A> {
+    /// Sends the message to every selected entity, see [`bulk`](crate::sharding::bulk)
+    pub fn broadcast_to_entities<M: Message>(
+        &self,
+        entities: impl Into<EntitySelector<A::Actor>>,
+        message: M,
+    ) -> BulkOperationBuilder<A>
+    where
+        A::Actor: Handler<M>,
+    {
+        let message_type =
+            self.system()
+                .handler_name::<A::Actor, M>()
+                .ok_or_else(|| ActorRefErr::NotSupported {
+                    actor_id: "*".into(),
+                    message_type: M::type_name().to_string(),
+                    actor_type: A::Actor::type_name().to_string(),
+                });
+
+        BulkOperationBuilder {
+            selector: entities.into(),
+            recipe: None,
+            task: BulkTask {
+                sharding: self.core.clone(),
+                message_type,
+                message: message.as_bytes().map_err(ActorRefErr::Serialisation),
+                recipe: None,
+                max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+                rate_limit: None,
+            },
+        }
+    }
+}
+
+impl<A: ActorFactory> BulkOperationBuilder<A> {
+    /// The maximum number of entities handling the message at once, defaults to 100
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.task.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// The maximum number of entities sent the message per second, unlimited by default
+    pub fn rate_limit(mut self, per_second: u32) -> Self {
+        self.task.rate_limit = Some(per_second.max(1));
+        self
+    }
+
+    /// Creates any selected entities that don't exist yet from the provided recipe,
+    /// otherwise the message is only sent to existing entities, and the remaining entities
+    /// fail with [`ActorRefErr::NotFound`]
+    pub fn with_recipe(mut self, recipe: A::Recipe) -> Self {
+        self.recipe = Some(recipe);
+        self
+    }
+
+    pub fn start(self) -> BulkOperation {
+        let mut task = self.task;
+        if let Some(recipe) = self.recipe {
+            task.recipe = recipe.write_to_bytes().map(Arc::new);
+        }
+
+        let (progress_tx, progress) = watch::channel(BulkProgress::default());
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let task = tokio::spawn(task.run(self.selector.resolve(), progress_tx, cancelled.clone()));
+
+        BulkOperation {
+            progress,
+            cancelled,
+            task,
+        }
+    }
+}
+
+impl BulkTask {
+    async fn run(
+        self,
+        entity_ids: impl Future<Output = Result<Vec<ActorId>, ActorRefErr>>,
+        progress: watch::Sender<BulkProgress>,
+        cancelled: Arc<AtomicBool>,
+    ) -> Result<BulkReport, ActorRefErr> {
+        let message_type = self.message_type?;
+        let message_version = self.sharding.system.message_version(&message_type);
+        let message = self.message?;
+        let entity_ids = entity_ids.await?;
+
+        let mut report = BulkReport {
+            progress: BulkProgress::default(),
+            failures: vec![],
+            cancelled: false,
+        };
+
+        report.progress.total = entity_ids.len();
+        progress.send_replace(report.progress);
+
+        let mut rate_limiter = self.rate_limit.map(|per_second| {
+            let mut rate_limiter = interval(Duration::from_secs(1) / per_second);
+            rate_limiter.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            rate_limiter
+        });
+
+        let mut in_flight = FuturesUnordered::new();
+        for entity_id in entity_ids {
+            if cancelled.load(Ordering::Relaxed) {
+                report.cancelled = true;
+                break;
+            }
+
+            if in_flight.len() >= self.max_in_flight {
+                if let Some(result) = in_flight.next().await {
+                    report.complete(result, &progress);
+                }
+            }
+
+            if let Some(rate_limiter) = &mut rate_limiter {
+                rate_limiter.tick().await;
+            }
+
+            let (tx, rx) = oneshot::channel();
+            let sent = self.sharding.notify_host(EntityRequest {
+                actor_id: entity_id.clone(),
+                message_type: message_type.clone(),
+                message: message.clone(),
+                message_version,
+                recipe: self.recipe.clone(),
+                shard_id: None,
+                result_channel: Some(tx),
+            });
+
+            in_flight.push(async move {
+                let result = match sent {
+                    Ok(_) => match rx.await {
+                        Ok(result) => result.map(|_| ()),
+                        Err(_) => Err(ActorRefErr::ResultChannelClosed),
+                    },
+                    Err(e) => Err(e),
+                };
+
+                (entity_id, result)
+            });
+        }
+
+        while let Some(result) = in_flight.next().await {
+            report.complete(result, &progress);
+        }
+
+        info!(
+            shard_entity = self.sharding.shard_entity.as_str(),
+            cancelled = report.cancelled,
+            "bulk operation complete, {}",
+            &report.progress
+        );
+
+        Ok(report)
+    }
+}
+
+impl BulkReport {
+    fn complete(
+        &mut self,
+        (entity_id, result): (ActorId, Result<(), ActorRefErr>),
+        progress: &watch::Sender<BulkProgress>,
+    ) {
+        match result {
+            Ok(_) => self.progress.succeeded += 1,
+            Err(e) => {
+                self.progress.failed += 1;
+                self.failures.push((entity_id, e));
+            }
+        }
+
+        progress.send_replace(self.progress);
+    }
+}
+
+impl BulkOperation {
+    /// Receives the progress of the operation, updated as each entity completes
+    pub fn progress(&self) -> watch::Receiver<BulkProgress> {
+        self.progress.clone()
+    }
+
+    /// Stops sending the message to any more entities, entities already sent the message
+    /// are still waited for
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Waits for the operation to complete, returning an error if the operation couldn't be
+    /// started, for example if the entities couldn't be selected from the index
+    pub async fn wait(self) -> Result<BulkReport, ActorRefErr> {
+        self.task.await.expect("bulk operation task")
+    }
+}
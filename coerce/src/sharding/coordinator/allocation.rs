@@ -2,17 +2,19 @@ use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
 use crate::actor::{ActorId, ActorRef};
 use crate::persistent::{PersistentActor, Recover};
+use crate::remote::cluster::node::NodeSelector;
 use crate::remote::system::NodeId;
 use crate::sharding::coordinator::{ShardCoordinator, ShardHostState, ShardId};
+use crate::sharding::extractor::HashShardId;
+use crate::sharding::host::migration::EntitySnapshots;
 use crate::sharding::host::{ShardAllocated, ShardAllocator, ShardHost, ShardReallocating};
 use crate::sharding::proto::sharding as proto;
 use futures::future::join_all;
 use protobuf::Message as ProtoMessage;
-use std::collections::hash_map::{DefaultHasher, Entry, VacantEntry};
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 
 use crate::sharding::proto::sharding::allocate_shard_result;
-use std::hash::{Hash, Hasher};
 
 pub struct AllocateShard {
     pub shard_id: ShardId,
@@ -43,21 +45,109 @@ impl ShardCoordinator {
     pub async fn allocate_shard(
         &mut self,
         shard_id: ShardId,
-        _ctx: &mut ActorContext,
+        ctx: &mut ActorContext,
     ) -> AllocateShardResult {
-        let shard_entry = self.shards.entry(shard_id);
+        self.allocate_shard_with_snapshots(shard_id, None, ctx)
+            .await
+    }
 
-        match shard_entry {
-            Entry::Occupied(node) => AllocateShardResult::AlreadyAllocated(shard_id, *node.get()),
-            Entry::Vacant(vacant) => {
-                allocate(
-                    shard_id,
-                    self.hosts.values_mut().filter(|n| n.is_ready()).collect(),
-                    vacant,
-                )
-                .await
-            }
+    /// Allocates the shard, shipping the snapshots of the shard's entities to the node the shard
+    /// is allocated to, before the allocation is broadcast
+    pub async fn allocate_shard_with_snapshots(
+        &mut self,
+        shard_id: ShardId,
+        snapshots: Option<EntitySnapshots>,
+        ctx: &mut ActorContext,
+    ) -> AllocateShardResult {
+        if let Some(node_id) = self.shards.get(&shard_id) {
+            return AllocateShardResult::AlreadyAllocated(shard_id, *node_id);
         }
+
+        let preferred_nodes = self.preferred_nodes(ctx).await;
+        let eligible_nodes = self.eligible_nodes(ctx).await;
+        let (candidates, _) = allocation_candidates(&self.hosts, &eligible_nodes, &preferred_nodes);
+
+        debug!(
+            "shard#{} allocating - available nodes={:#?}, preferred nodes={:?}",
+            shard_id, &candidates, &preferred_nodes
+        );
+
+        let target = self.allocation_strategy.allocate(shard_id, &candidates);
+
+        // every ready host is notified, so requests they buffered while the shard
+        // was unallocated (or being rebalanced) can be delivered
+        let hosts: Vec<ActorRef<ShardHost>> = self
+            .hosts
+            .values()
+            .filter(|h| h.is_ready())
+            .map(|h| h.actor.clone())
+            .collect();
+
+        let host = match target.and_then(|node_id| self.hosts.get_mut(&node_id)) {
+            Some(host) => host,
+            None => return AllocateShardResult::NotAllocated,
+        };
+
+        let node_id = host.node_id;
+
+        trace!("shard#{} allocated, target_node={}", shard_id, node_id);
+
+        self.shards.insert(shard_id, node_id);
+        if host.shards.insert(shard_id) {
+            let target = host.actor.clone();
+            tokio::spawn(async move {
+                if let Some(snapshots) = snapshots {
+                    ship_snapshots(snapshots, node_id, target).await;
+                }
+
+                broadcast_allocation(shard_id, node_id, hosts).await;
+            });
+        }
+
+        AllocateShardResult::Allocated(shard_id, node_id)
+    }
+
+    pub(crate) async fn preferred_nodes(&self, ctx: &ActorContext) -> Option<HashSet<NodeId>> {
+        let attribute = self.preferred_node_attribute.clone()?;
+        Some(
+            self.nodes_matching(NodeSelector::Attribute(attribute), ctx)
+                .await,
+        )
+    }
+
+    /// Returns the nodes shards can be allocated to, `None` if shards
+    /// can be allocated to any node.
+    ///
+    /// Nodes in maintenance mode keep the shards they host, but aren't allocated new shards.
+    pub(crate) async fn eligible_nodes(&self, ctx: &ActorContext) -> Option<HashSet<NodeId>> {
+        let nodes = ctx.system().remote().get_nodes().await;
+        if self.role.is_none() && !nodes.iter().any(|node| node.maintenance) {
+            return None;
+        }
+
+        let selector = self
+            .role
+            .as_ref()
+            .map_or(NodeSelector::All, |role| NodeSelector::role(role));
+
+        Some(
+            nodes
+                .into_iter()
+                .filter(|node| !node.maintenance && selector.includes(&node.clone().into()))
+                .map(|node| node.id)
+                .collect(),
+        )
+    }
+
+    async fn nodes_matching(&self, selector: NodeSelector, ctx: &ActorContext) -> HashSet<NodeId> {
+        ctx.system()
+            .remote()
+            .get_nodes()
+            .await
+            .into_iter()
+            .filter(|node| selector.includes(&node.clone().into()))
+            .map(|node| node.id)
+            .collect()
     }
 }
 
@@ -72,8 +162,19 @@ impl Handler<AllocateShard> for ShardCoordinator {
             return AllocateShardResult::AlreadyAllocated(message.shard_id, *entry);
         }
 
+        if !message.rebalancing && self.reallocating_shards.contains(&message.shard_id) {
+            // the shard is still stopping on its previous node, the requesting host buffers its
+            // requests until the shard's new allocation is broadcast
+            return AllocateShardResult::NotAllocated;
+        }
+
         if message.rebalancing {
-            return self.allocate_shard(message.shard_id, ctx).await;
+            self.reallocating_shards.remove(&message.shard_id);
+
+            let snapshots = self.pending_snapshots.remove(&message.shard_id);
+            return self
+                .allocate_shard_with_snapshots(message.shard_id, snapshots, ctx)
+                .await;
         }
 
         match self.persist(&message, ctx).await {
@@ -98,37 +199,33 @@ impl Recover<AllocateShard> for ShardCoordinator {
     }
 }
 
-async fn allocate(
-    shard_id: ShardId,
-    mut hosts: Vec<&mut ShardHostState>,
-    shard_entry: VacantEntry<'_, ShardId, NodeId>,
-) -> AllocateShardResult {
-    // TODO: weighted ordering - shards with more entities should have a higher weight, the more shards*
-
-    hosts.sort_by(|h1, h2| h1.shards.len().cmp(&h2.shards.len()));
-
-    debug!(
-        "shard#{} allocating - available nodes={:#?}",
-        shard_id, &hosts
-    );
-
-    if let Some(host) = hosts.first_mut() {
-        let node_id = host.node_id;
-
-        trace!("shard#{} allocated, target_node={}", shard_id, node_id);
-
-        shard_entry.insert(node_id);
-        if host.shards.insert(shard_id) {
-            let node_id = host.node_id;
-            let hosts = hosts.iter().map(|h| h.actor.clone()).collect();
-            tokio::spawn(async move {
-                broadcast_allocation(shard_id, node_id, hosts).await;
-            });
+/// Returns the hosts shards can be allocated to, along with the rest of the ready and eligible
+/// hosts. If any of the preferred nodes are ready, only they can be allocated shards, otherwise
+/// shards can be allocated to any ready node.
+pub(crate) fn allocation_candidates<'a>(
+    hosts: &'a HashMap<NodeId, ShardHostState>,
+    eligible_nodes: &Option<HashSet<NodeId>>,
+    preferred_nodes: &Option<HashSet<NodeId>>,
+) -> (Vec<&'a ShardHostState>, Vec<&'a ShardHostState>) {
+    let mut hosts: Vec<&ShardHostState> = hosts
+        .values()
+        .filter(|h| {
+            h.is_ready()
+                && eligible_nodes
+                    .as_ref()
+                    .is_none_or(|nodes| nodes.contains(&h.node_id))
+        })
+        .collect();
+
+    hosts.sort_by_key(|h| h.node_id);
+
+    match preferred_nodes {
+        Some(preferred_nodes) if hosts.iter().any(|h| preferred_nodes.contains(&h.node_id)) => {
+            hosts
+                .into_iter()
+                .partition(|h| preferred_nodes.contains(&h.node_id))
         }
-
-        AllocateShardResult::Allocated(shard_id, node_id)
-    } else {
-        AllocateShardResult::NotAllocated
+        _ => (hosts, vec![]),
     }
 }
 
@@ -175,6 +272,22 @@ pub async fn broadcast_allocation(
     trace!("broadcast to all nodes complete");
 }
 
+async fn ship_snapshots(snapshots: EntitySnapshots, node_id: NodeId, host: ActorRef<ShardHost>) {
+    let shard_id = snapshots.shard_id;
+    let snapshot_count = snapshots.snapshots.len();
+
+    match host.send(snapshots).await {
+        Ok(_) => debug!(
+            "shipped {} entity snapshot(s) for shard#{} to node_id={}",
+            snapshot_count, shard_id, node_id
+        ),
+        Err(e) => warn!(
+            "failed to ship entity snapshots for shard#{} to node_id={}, entities will be recovered from storage, error={}",
+            shard_id, node_id, e
+        ),
+    }
+}
+
 pub async fn broadcast_reallocation(shard_id: ShardId, hosts: Vec<ActorRef<ShardHost>>) {
     trace!(
         "shard reallocating (shard=#{}), broadcasting to all shard hosts",
@@ -322,12 +435,6 @@ impl Default for DefaultAllocator {
 
 impl ShardAllocator for DefaultAllocator {
     fn allocate(&mut self, actor_id: &ActorId) -> ShardId {
-        let hashed_actor_id = {
-            let mut hasher = DefaultHasher::new();
-            actor_id.hash(&mut hasher);
-            hasher.finish()
-        };
-
-        (hashed_actor_id % self.max_shards as u64) as ShardId
+        HashShardId::new(self.max_shards).shard_id(actor_id)
     }
 }
@@ -1,9 +1,12 @@
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
-use crate::actor::{ActorRef, LocalActorRef};
+use crate::actor::{Actor, ActorRef, LocalActorRef};
 use crate::remote::system::{NodeId, RemoteActorSystem};
-use crate::sharding::coordinator::allocation::{broadcast_reallocation, AllocateShard};
+use crate::sharding::coordinator::allocation::{
+    allocation_candidates, broadcast_reallocation, AllocateShard,
+};
 use crate::sharding::coordinator::{ShardCoordinator, ShardHostStatus, ShardId};
+use crate::sharding::host::migration::EntitySnapshots;
 use crate::sharding::host::{ShardHost, ShardStopped, StopShard};
 use futures::future::join_all;
 
@@ -19,6 +22,9 @@ pub enum Rebalance {
     All,
 }
 
+/// Sent once every shard in a round of rebalancing has been stopped and reallocated
+pub struct RebalanceComplete(pub Vec<ShardId>);
+
 #[async_trait]
 impl Handler<Rebalance> for ShardCoordinator {
     async fn handle(&mut self, message: Rebalance, ctx: &mut ActorContext) {
@@ -27,34 +33,21 @@ impl Handler<Rebalance> for ShardCoordinator {
 
         match message {
             Rebalance::All => {
-                let self_ref = ctx.actor_ref();
-
-                let total_shards = self.shards.len();
-                let fair_shard_count_per_node = total_shards / self.hosts.len();
-
-                let mut shards_to_rebalance = vec![];
-                for (node_id, shard_host) in &self.hosts {
-                    if shard_host.shards.len() > fair_shard_count_per_node {
-                        let diff = shard_host.shards.len() - fair_shard_count_per_node;
-                        let mut i = 0;
-                        for shard in &shard_host.shards {
-                            if i >= diff {
-                                break;
-                            }
-                            shards_to_rebalance.push(*shard);
-
-                            i += 1;
-                        }
-
-                        debug!(
-                            "rebalancing {} shards from node={} - total={} - fair_shard_count_per_node={}",
-                            i, node_id, shard_host.shards.len(), fair_shard_count_per_node
-                        );
-                    }
+                if !self.reallocating_shards.is_empty() {
+                    // the cluster is rebalanced again once the shards that are moving have been reallocated
+                    debug!(
+                        "rebalance already in progress, {} shard(s) reallocating",
+                        self.reallocating_shards.len()
+                    );
+                    return;
                 }
 
-                self.rebalance_shards(shards_to_rebalance, self_ref, ctx.system().remote())
-                    .await
+                let shards_to_rebalance = self.shards_to_rebalance(ctx).await;
+                if !shards_to_rebalance.is_empty() {
+                    let self_ref = ctx.actor_ref();
+                    self.rebalance_shards(shards_to_rebalance, self_ref, ctx.system().remote())
+                        .await
+                }
             }
 
             Rebalance::Shards(shards) => {
@@ -78,6 +71,36 @@ impl Handler<Rebalance> for ShardCoordinator {
 }
 
 impl ShardCoordinator {
+    /// Returns the shards that should be moved, at most `max_rebalance_shards`. Shards hosted by
+    /// nodes that aren't preferred are moved first, once a preferred node is available, and the
+    /// rest are chosen by the allocation strategy.
+    async fn shards_to_rebalance(&self, ctx: &ActorContext) -> Vec<ShardId> {
+        let preferred_nodes = self.preferred_nodes(ctx).await;
+        let eligible_nodes = self.eligible_nodes(ctx).await;
+        let (candidates, others) =
+            allocation_candidates(&self.hosts, &eligible_nodes, &preferred_nodes);
+
+        let mut shards: Vec<ShardId> = others
+            .iter()
+            .flat_map(|host| host.shards.iter().copied())
+            .take(self.max_rebalance_shards)
+            .collect();
+
+        let remaining = self.max_rebalance_shards - shards.len();
+        if remaining > 0 {
+            shards.extend(self.allocation_strategy.rebalance(&candidates, remaining));
+        }
+
+        debug!(
+            "rebalancing {} shard(s) - total={}, max_rebalance_shards={}",
+            shards.len(),
+            self.shards.len(),
+            self.max_rebalance_shards
+        );
+
+        shards
+    }
+
     pub async fn rebalance_shards(
         &mut self,
         shards: Vec<ShardId>,
@@ -115,13 +138,17 @@ impl ShardCoordinator {
                         Ok(_) => {
                             let result = rx.await;
                             if let Ok(result) = result {
-                                let result = ShardStopped::from_bytes(result.into_result().unwrap());
+                                let result = result
+                                    .into_result()
+                                    .map_err(|e| e.to_string())
+                                    .and_then(|res| ShardStopped::from_bytes(res).map_err(|e| e.to_string()));
+
                                 match result {
                                     Ok(_res) => {
                                         let _ = self_ref.send(AllocateShard { shard_id: shard, rebalancing: true }).await;
                                     },
                                     Err(e) => {
-                                        error!("deserialization error: {}", e);
+                                        error!("error during shard re-balancing - failed to stop shard (shard_id={}, target_node={}) error={}", shard, &shard_host_actor, e);
                                     },
                                 }
                             } else {
@@ -137,11 +164,14 @@ impl ShardCoordinator {
             }
         }
 
+        self.reallocating_shards.extend(shards.iter().copied());
+
         let shard_reallocation_tasks = shard_reallocation_tasks;
         let _ = tokio::spawn(async move {
             join_all(shard_reallocation_tasks).await;
 
-            info!("rebalance of shards ({:?}) complete", &shards)
+            info!("rebalance of shards ({:?}) complete", &shards);
+            let _ = self_ref.notify(RebalanceComplete(shards));
         });
     }
 
@@ -174,6 +204,37 @@ impl ShardCoordinator {
     }
 }
 
+#[async_trait]
+impl Handler<RebalanceComplete> for ShardCoordinator {
+    async fn handle(&mut self, message: RebalanceComplete, ctx: &mut ActorContext) {
+        for shard_id in &message.0 {
+            self.reallocating_shards.remove(shard_id);
+        }
+
+        // keep rebalancing until the allocation strategy has no more shards to move
+        if !message.0.is_empty() && self.reallocating_shards.is_empty() {
+            let _ = self.actor_ref(ctx).notify(Rebalance::All);
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<EntitySnapshots> for ShardCoordinator {
+    async fn handle(&mut self, message: EntitySnapshots, _ctx: &mut ActorContext) {
+        debug!(
+            "holding {} entity snapshot(s) until shard#{} is reallocated",
+            message.snapshots.len(),
+            message.shard_id
+        );
+
+        self.pending_snapshots.insert(message.shard_id, message);
+    }
+}
+
 impl Message for Rebalance {
     type Result = ();
 }
+
+impl Message for RebalanceComplete {
+    type Result = ();
+}
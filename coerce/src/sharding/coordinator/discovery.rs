@@ -20,9 +20,17 @@ impl Handler<Receive<SystemTopic>> for ShardCoordinator {
                     self.on_node_discovered(node.as_ref(), ctx);
                 }
 
-                ClusterEvent::NodeRemoved(node) => {
+                ClusterEvent::NodeRemoved(node) | ClusterEvent::NodeQuarantined(node) => {
                     self.on_node_removed(node.id, ctx).await;
                 }
+
+                ClusterEvent::NodeUnreachable(node) => {
+                    self.on_node_reachability_changed(node.id, false);
+                }
+
+                ClusterEvent::NodeReachable(node) => {
+                    self.on_node_reachability_changed(node.id, true);
+                }
                 _ => {}
             },
             _ => {}
@@ -62,6 +70,24 @@ impl ShardCoordinator {
         }
     }
 
+    /// Unreachable hosts are excluded from new shard allocations, but keep the shards they
+    /// already host, since the node may only be unreachable temporarily. If the node doesn't
+    /// become reachable again, its shards are re-allocated once it's removed from the cluster.
+    pub fn on_node_reachability_changed(&mut self, node_id: NodeId, reachable: bool) {
+        if let Some(host) = self.hosts.get_mut(&node_id) {
+            host.status = match (host.status, reachable) {
+                (ShardHostStatus::Ready, false) => ShardHostStatus::Unavailable,
+                (ShardHostStatus::Unavailable, true) => ShardHostStatus::Ready,
+                (status, _) => status,
+            };
+
+            debug!(
+                "shard host reachability changed (node_id={}, reachable={}, status={:?})",
+                node_id, reachable, host.status
+            );
+        }
+    }
+
     pub async fn on_node_removed(&mut self, node_id: NodeId, ctx: &mut ActorContext) {
         match self.hosts.entry(node_id) {
             Entry::Occupied(_) => self.handle(Rebalance::NodeUnavailable(node_id), ctx).await,
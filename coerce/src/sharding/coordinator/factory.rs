@@ -1,11 +1,20 @@
 use crate::actor::LocalActorRef;
+use crate::remote::cluster::node::NodeAttribute;
+use crate::sharding::coordinator::strategy::{
+    LeastShardAllocationStrategy, ShardAllocationStrategy, DEFAULT_MAX_REBALANCE_SHARDS,
+};
 use crate::sharding::coordinator::ShardCoordinator;
 use crate::sharding::host::ShardHost;
 use crate::singleton::factory::SingletonFactory;
+use std::sync::Arc;
 
 pub struct CoordinatorFactory {
     shard_entity: String,
     local_shard_host: LocalActorRef<ShardHost>,
+    preferred_node_attribute: Option<NodeAttribute>,
+    role: Option<String>,
+    allocation_strategy: Arc<dyn ShardAllocationStrategy>,
+    max_rebalance_shards: usize,
 }
 
 impl CoordinatorFactory {
@@ -13,8 +22,32 @@ impl CoordinatorFactory {
         CoordinatorFactory {
             shard_entity,
             local_shard_host,
+            preferred_node_attribute: None,
+            role: None,
+            allocation_strategy: Arc::new(LeastShardAllocationStrategy),
+            max_rebalance_shards: DEFAULT_MAX_REBALANCE_SHARDS,
         }
     }
+
+    pub fn with_preferred_node_attribute(mut self, attribute: Option<NodeAttribute>) -> Self {
+        self.preferred_node_attribute = attribute;
+        self
+    }
+
+    pub fn with_role(mut self, role: Option<String>) -> Self {
+        self.role = role;
+        self
+    }
+
+    pub fn with_allocation_strategy(mut self, strategy: Arc<dyn ShardAllocationStrategy>) -> Self {
+        self.allocation_strategy = strategy;
+        self
+    }
+
+    pub fn with_max_rebalance_shards(mut self, max_rebalance_shards: usize) -> Self {
+        self.max_rebalance_shards = max_rebalance_shards;
+        self
+    }
 }
 
 impl SingletonFactory for CoordinatorFactory {
@@ -22,5 +55,9 @@ impl SingletonFactory for CoordinatorFactory {
 
     fn create(&self) -> Self::Actor {
         ShardCoordinator::new(self.shard_entity.clone(), self.local_shard_host.clone())
+            .with_preferred_node_attribute(self.preferred_node_attribute.clone())
+            .with_role(self.role.clone())
+            .with_allocation_strategy(self.allocation_strategy.clone())
+            .with_max_rebalance_shards(self.max_rebalance_shards)
     }
 }
@@ -4,17 +4,23 @@ use crate::actor::{Actor, ActorRef, LocalActorRef, ScheduledNotify};
 use crate::persistent::journal::types::JournalTypes;
 use crate::persistent::PersistentActor;
 use crate::sharding::coordinator::allocation::AllocateShard;
+use crate::sharding::host::migration::EntitySnapshots;
 use crate::sharding::host::ShardHost;
 
 use crate::remote::system::NodeId;
 
 use crate::actor::message::Handler;
+use crate::remote::cluster::node::NodeAttribute;
 use crate::remote::cluster::node::NodeStatus::{Healthy, Joining};
 use crate::remote::heartbeat::Heartbeat;
 use crate::remote::stream::pubsub::{PubSub, Subscription};
 use crate::remote::stream::system::SystemTopic;
 use crate::sharding::coordinator::balancing::Rebalance;
+use crate::sharding::coordinator::strategy::{
+    LeastShardAllocationStrategy, ShardAllocationStrategy, DEFAULT_MAX_REBALANCE_SHARDS,
+};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub mod allocation;
@@ -22,6 +28,7 @@ pub mod balancing;
 pub mod discovery;
 pub mod factory;
 pub mod stats;
+pub mod strategy;
 pub mod stream;
 
 pub type ShardId = u32;
@@ -58,6 +65,11 @@ pub struct ShardCoordinator {
     scheduled_rebalance: Option<ScheduledRebalance>,
     self_node_id: Option<NodeId>,
     system_event_subscription: Option<Subscription>,
+    preferred_node_attribute: Option<NodeAttribute>,
+    role: Option<String>,
+    pending_snapshots: HashMap<ShardId, EntitySnapshots>,
+    allocation_strategy: Arc<dyn ShardAllocationStrategy>,
+    max_rebalance_shards: usize,
 }
 
 type ScheduledRebalance = ScheduledNotify<ShardCoordinator, Rebalance>;
@@ -110,7 +122,9 @@ impl PersistentActor for ShardCoordinator {
                     node_tag: String::default(),
                     shards: HashSet::new(),
                     actor: ShardHost::remote_ref(&self.shard_entity, host.id, remote),
-                    status: if host.status == Healthy || host.status == Joining {
+                    status: if (host.status == Healthy || host.status == Joining)
+                        && host.health.reachable
+                    {
                         ShardHostStatus::Ready
                     } else {
                         ShardHostStatus::Unavailable
@@ -139,9 +153,39 @@ impl ShardCoordinator {
             scheduled_rebalance: None,
             self_node_id: None,
             system_event_subscription: None,
+            preferred_node_attribute: None,
+            role: None,
+            pending_snapshots: Default::default(),
+            allocation_strategy: Arc::new(LeastShardAllocationStrategy),
+            max_rebalance_shards: DEFAULT_MAX_REBALANCE_SHARDS,
         }
     }
 
+    /// Prefers allocating shards to nodes with the provided attribute,
+    /// falling back to any ready node if none of the nodes with the attribute are ready
+    pub fn with_preferred_node_attribute(mut self, attribute: Option<NodeAttribute>) -> Self {
+        self.preferred_node_attribute = attribute;
+        self
+    }
+
+    /// Only allocates shards to nodes with the provided role
+    pub fn with_role(mut self, role: Option<String>) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Decides which node shards are allocated to, and which shards are moved when rebalancing
+    pub fn with_allocation_strategy(mut self, strategy: Arc<dyn ShardAllocationStrategy>) -> Self {
+        self.allocation_strategy = strategy;
+        self
+    }
+
+    /// The maximum number of shards moved by a single round of rebalancing
+    pub fn with_max_rebalance_shards(mut self, max_rebalance_shards: usize) -> Self {
+        self.max_rebalance_shards = max_rebalance_shards.max(1);
+        self
+    }
+
     pub fn schedule_full_rebalance(&mut self, ctx: &ActorContext) {
         if let Some(scheduled_rebalance) = self.scheduled_rebalance.take() {
             scheduled_rebalance.cancel();
@@ -0,0 +1,95 @@
+//! Shard allocation strategies, see [`ShardingBuilder::with_allocation_strategy`]
+//!
+//! The [`ShardCoordinator`] consults its [`ShardAllocationStrategy`] when a shard is first
+//! allocated, and when the cluster is rebalanced, which happens whenever a node joins the cluster.
+//! Shards hosted by a node that leaves the cluster are re-allocated straight away.
+//!
+//! Rebalancing moves at most [`DEFAULT_MAX_REBALANCE_SHARDS`] shards at a time (configurable via
+//! [`ShardingBuilder::with_max_rebalance_shards`]). Each shard is stopped on the node that hosts it,
+//! requests for its entities are buffered while it's moving, and once the shard has been
+//! allocated to its new node, the buffered requests are delivered there, restarting the entities.
+//! Once every shard in a round has moved, the strategy is consulted again, until it has no more
+//! shards to move.
+//!
+//! [`ShardCoordinator`]: crate::sharding::coordinator::ShardCoordinator
+//! [`ShardingBuilder::with_allocation_strategy`]: crate::sharding::builder::ShardingBuilder::with_allocation_strategy
+//! [`ShardingBuilder::with_max_rebalance_shards`]: crate::sharding::builder::ShardingBuilder::with_max_rebalance_shards
+
+use crate::remote::system::NodeId;
+use crate::sharding::coordinator::{ShardHostState, ShardId};
+use std::collections::HashMap;
+
+/// The maximum number of shards moved by a single round of rebalancing, by default
+pub const DEFAULT_MAX_REBALANCE_SHARDS: usize = 10;
+
+/// Decides which node a shard is allocated to, and which shards are moved when rebalancing.
+///
+/// Strategies are only given the hosts shards can currently be allocated to: hosts that are
+/// ready, eligible (see [`ShardingBuilder::with_role`]) and, if any are available,
+/// preferred (see [`ShardingBuilder::prefer_node_with_attr`]).
+///
+/// [`ShardingBuilder::with_role`]: crate::sharding::builder::ShardingBuilder::with_role
+/// [`ShardingBuilder::prefer_node_with_attr`]: crate::sharding::builder::ShardingBuilder::prefer_node_with_attr
+pub trait ShardAllocationStrategy: 'static + Send + Sync {
+    /// Returns the node the shard should be allocated to, `None` if the shard
+    /// shouldn't be allocated to any of the hosts
+    fn allocate(&self, shard_id: ShardId, hosts: &[&ShardHostState]) -> Option<NodeId>;
+
+    /// Returns the shards that should be moved to another host, at most `max_shards`.
+    /// The shards that are returned are then allocated via [`ShardAllocationStrategy::allocate`].
+    fn rebalance(&self, hosts: &[&ShardHostState], max_shards: usize) -> Vec<ShardId>;
+}
+
+/// Allocates shards to the host with the fewest shards, and rebalances until the difference
+/// between the hosts with the most and fewest shards is at most one.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct LeastShardAllocationStrategy;
+
+impl ShardAllocationStrategy for LeastShardAllocationStrategy {
+    fn allocate(&self, _shard_id: ShardId, hosts: &[&ShardHostState]) -> Option<NodeId> {
+        hosts
+            .iter()
+            .min_by_key(|host| host.shards.len())
+            .map(|host| host.node_id)
+    }
+
+    fn rebalance(&self, hosts: &[&ShardHostState], max_shards: usize) -> Vec<ShardId> {
+        let mut shards: HashMap<NodeId, Vec<ShardId>> = hosts
+            .iter()
+            .map(|host| {
+                let mut shards: Vec<ShardId> = host.shards.iter().copied().collect();
+                shards.sort_unstable();
+                (host.node_id, shards)
+            })
+            .collect();
+
+        let mut shard_counts: Vec<(NodeId, usize)> = hosts
+            .iter()
+            .map(|host| (host.node_id, host.shards.len()))
+            .collect();
+
+        let mut rebalance = vec![];
+        while rebalance.len() < max_shards {
+            shard_counts.sort_by_key(|(node_id, count)| (*count, *node_id));
+
+            let (most, fewest) = match (shard_counts.last(), shard_counts.first()) {
+                (Some(most), Some(fewest)) if most.1 > fewest.1 + 1 => (most.0, fewest.0),
+                _ => break,
+            };
+
+            if let Some(shard_id) = shards.get_mut(&most).and_then(|shards| shards.pop()) {
+                rebalance.push(shard_id);
+            }
+
+            for (node_id, count) in shard_counts.iter_mut() {
+                if *node_id == most {
+                    *count -= 1;
+                } else if *node_id == fewest {
+                    *count += 1;
+                }
+            }
+        }
+
+        rebalance
+    }
+}
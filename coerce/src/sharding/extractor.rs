@@ -0,0 +1,153 @@
+//! Message extractors
+//!
+//! By default, a sharded entity is addressed explicitly, by passing its id (and optionally a recipe)
+//! to [`Sharding::get`], and the entity's shard is chosen by the [`ShardAllocator`]. A
+//! [`MessageExtractor`] instead derives the entity id, shard id and recipe from the message itself,
+//! allowing messages that already carry the identity of the entity they're for to be sent as they are,
+//! via an [`ExtractorRef`].
+//!
+//! Since entities are identified by their id within a shard, the shard an extractor chooses
+//! for an entity should agree with the [`ShardAllocator`] the sharding was started with, otherwise
+//! the entity can't be reached via [`Sharding::get`]. [`HashShardId`] can be used as both, so
+//! both sides use the same shard count.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! struct OrderExtractor {
+//!     shards: HashShardId,
+//! }
+//!
+//! impl MessageExtractor<OrderFactory, PlaceOrder> for OrderExtractor {
+//!     fn entity_id(&self, message: &PlaceOrder) -> ActorId {
+//!         message.order_id.clone().into_actor_id()
+//!     }
+//!
+//!     fn shard_id(&self, entity_id: &ActorId, _message: &PlaceOrder) -> Option<ShardId> {
+//!         Some(self.shards.shard_id(entity_id))
+//!     }
+//!
+//!     fn recipe(&self, message: &PlaceOrder) -> Option<OrderRecipe> {
+//!         Some(OrderRecipe::new(&message.customer_id))
+//!     }
+//! }
+//!
+//! let shards = HashShardId::new(64);
+//! let sharding = Sharding::<OrderFactory>::builder(remote)
+//!     .with_allocator(shards)
+//!     .build()
+//!     .await;
+//!
+//! let orders = sharding.with_extractor(OrderExtractor { shards });
+//! orders.send(PlaceOrder { order_id, customer_id }).await?;
+//! ```
+
+use crate::actor::message::{Handler, Message};
+use crate::actor::{ActorFactory, ActorId, ActorRefErr};
+use crate::sharding::coordinator::ShardId;
+use crate::sharding::host::ShardAllocator;
+use crate::sharding::Sharding;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Maps a message to the sharded entity it's for
+pub trait MessageExtractor<A: ActorFactory, M: Message>: 'static + Send + Sync {
+    /// The id of the entity the message is for
+    fn entity_id(&self, message: &M) -> ActorId;
+
+    /// The shard the entity belongs to, if `None`, the shard is chosen by the sharding's
+    /// [`ShardAllocator`]
+    fn shard_id(&self, _entity_id: &ActorId, _message: &M) -> Option<ShardId> {
+        None
+    }
+
+    /// The recipe used to create the entity if it isn't already running
+    fn recipe(&self, _message: &M) -> Option<A::Recipe> {
+        None
+    }
+}
+
+/// Assigns entities to a fixed number of shards, by hashing the entity id
+#[derive(Debug, Copy, Clone)]
+pub struct HashShardId {
+    shard_count: ShardId,
+}
+
+impl HashShardId {
+    pub fn new(shard_count: ShardId) -> Self {
+        HashShardId {
+            shard_count: shard_count.max(1),
+        }
+    }
+
+    pub fn shard_count(&self) -> ShardId {
+        self.shard_count
+    }
+
+    pub fn shard_id(&self, entity_id: &ActorId) -> ShardId {
+        let hashed_entity_id = {
+            let mut hasher = DefaultHasher::new();
+            entity_id.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        (hashed_entity_id % self.shard_count as u64) as ShardId
+    }
+}
+
+impl Default for HashShardId {
+    fn default() -> Self {
+        HashShardId::new(100)
+    }
+}
+
+impl ShardAllocator for HashShardId {
+    fn allocate(&mut self, actor_id: &ActorId) -> ShardId {
+        self.shard_id(actor_id)
+    }
+}
+
+/// Sends messages to the entities chosen by a [`MessageExtractor`]
+pub struct ExtractorRef<A: ActorFactory, E> {
+    sharding: Sharding<A>,
+    extractor: Arc<E>,
+}
+
+impl<A: ActorFactory> Sharding<A> {
+    /// Creates an [`ExtractorRef`] that sends each message to the entity chosen by the extractor
+    pub fn with_extractor<E>(&self, extractor: E) -> ExtractorRef<A, E> {
+        ExtractorRef {
+            sharding: self.clone(),
+            extractor: Arc::new(extractor),
+        }
+    }
+}
+
+impl<A: ActorFactory, E> ExtractorRef<A, E> {
+    pub async fn send<M: Message>(&self, message: M) -> Result<M::Result, ActorRefErr>
+    where
+        E: MessageExtractor<A, M>,
+        A::Actor: Handler<M>,
+    {
+        let entity_id = self.extractor.entity_id(&message);
+        let shard_id = self.extractor.shard_id(&entity_id, &message);
+        let recipe = self.extractor.recipe(&message);
+
+        let mut entity = self.sharding.get(entity_id, recipe);
+        entity.shard_id = shard_id;
+        entity.send(message).await
+    }
+
+    pub fn sharding(&self) -> &Sharding<A> {
+        &self.sharding
+    }
+}
+
+impl<A: ActorFactory, E> Clone for ExtractorRef<A, E> {
+    fn clone(&self) -> Self {
+        ExtractorRef {
+            sharding: self.sharding.clone(),
+            extractor: self.extractor.clone(),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use crate::actor::{ActorId, IntoActorId, LocalActorRef};
+use crate::remote::cluster::node::NodeLocation;
+use crate::remote::system::{NodeId, RemoteActorSystem};
+use crate::sharding::coordinator::stats::GetShardingStats;
+use crate::sharding::coordinator::ShardId;
+use crate::sharding::host::{GetCoordinator, ShardHost, ShardState};
+
+/// The location of a sharded entity, entities are started on demand, so the entity itself
+/// may not yet be running on the node hosting its shard.
+#[derive(Debug, Clone)]
+pub struct EntityLocation {
+    pub entity_id: ActorId,
+    pub shard_id: ShardId,
+    pub node: NodeLocation,
+}
+
+pub struct LocateShard(pub ActorId);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ShardLocation {
+    pub shard_id: ShardId,
+
+    /// The node hosting the shard, if the allocation of the shard is known by the shard host
+    pub node_id: Option<NodeId>,
+}
+
+impl Message for LocateShard {
+    type Result = ShardLocation;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(self.0.as_bytes().to_vec())
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        String::from_utf8(b)
+            .map(|entity_id| Self(entity_id.into_actor_id()))
+            .map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn read_remote_result(b: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        serde_json::from_slice(&b).map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn write_remote_result(res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&res).map_err(|_e| MessageWrapErr::SerializationErr)
+    }
+}
+
+#[async_trait]
+impl Handler<LocateShard> for ShardHost {
+    async fn handle(&mut self, message: LocateShard, ctx: &mut ActorContext) -> ShardLocation {
+        let shard_id = self.allocator.allocate(&message.0);
+        let node_id = match self.hosted_shards.get(&shard_id) {
+            Some(ShardState::Starting { .. } | ShardState::Ready(_)) => {
+                Some(ctx.system().remote().node_id())
+            }
+            _ => self
+                .remote_shards
+                .get(&shard_id)
+                .and_then(|shard| shard.node_id()),
+        };
+
+        ShardLocation { shard_id, node_id }
+    }
+}
+
+/// Locates the node hosting the shard that the provided entity belongs to, if the shard
+/// is not known by the local shard host, the shard coordinator is consulted. Locating an entity
+/// never causes its shard to be allocated.
+pub async fn locate_entity(
+    shard_host: &LocalActorRef<ShardHost>,
+    entity_id: ActorId,
+    remote: &RemoteActorSystem,
+) -> Option<EntityLocation> {
+    let shard_location = shard_host.send(LocateShard(entity_id.clone())).await.ok()?;
+    let shard_id = shard_location.shard_id;
+    let node_id = match shard_location.node_id {
+        Some(node_id) => node_id,
+        None => {
+            let coordinator = shard_host.send(GetCoordinator).await.ok()?;
+            let sharding_stats = coordinator.send(GetShardingStats).await.ok()?;
+
+            sharding_stats
+                .shards
+                .into_iter()
+                .find(|shard| shard.shard_id == shard_id)?
+                .node_id
+        }
+    };
+
+    let node = remote.node_location(node_id).await?;
+    Some(EntityLocation {
+        entity_id,
+        shard_id,
+        node,
+    })
+}
+
+/// Locates the shard that the provided entity belongs to, by asking the shard host of each of the
+/// other nodes in the cluster in turn, allowing nodes that don't host `entity_type`, such as
+/// clients, to locate entities.
+///
+/// Returns `None` if no other node hosts `entity_type`, if the shard hasn't been allocated,
+/// the returned location has no `node_id`.
+pub async fn locate_shard_in_cluster(
+    entity_type: &str,
+    entity_id: ActorId,
+    remote: &RemoteActorSystem,
+) -> Option<ShardLocation> {
+    let mut unallocated = None;
+    for node in remote.get_nodes().await {
+        if node.id == remote.node_id() || !node.status.is_member() {
+            continue;
+        }
+
+        let shard_host = ShardHost::remote_ref(entity_type, node.id, remote);
+        match shard_host.send(LocateShard(entity_id.clone())).await {
+            Ok(location) if location.node_id.is_some() => return Some(location),
+            Ok(location) => unallocated = Some(location),
+            Err(_) => continue,
+        }
+    }
+
+    unallocated
+}
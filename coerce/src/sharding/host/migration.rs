@@ -0,0 +1,91 @@
+//! Snapshot shipping for sharded entities, see [`ShardingBuilder::with_snapshot_shipping`]
+//!
+//! When a shard is stopped to be rebalanced, each active entity is stopped and asked for a
+//! [`MigrationSnapshot`] of its state. The snapshots are handed to the [`ShardCoordinator`], which
+//! ships them to the node the shard is allocated to, before the allocation is broadcast, so the
+//! entities can be recovered without replaying their journals.
+//!
+//! [`ShardingBuilder::with_snapshot_shipping`]: crate::sharding::builder::ShardingBuilder::with_snapshot_shipping
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use crate::actor::{CoreActorRef, LocalActorRef};
+use crate::persistent::migration::MigrationSnapshot;
+use crate::sharding::coordinator::ShardId;
+use crate::sharding::host::ShardHost;
+use crate::sharding::shard::message::GetActiveEntities;
+use crate::sharding::shard::Shard;
+use futures::future::join_all;
+
+/// Snapshots of the entities of a shard that is being migrated to another node
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EntitySnapshots {
+    pub shard_id: ShardId,
+    pub snapshots: Vec<MigrationSnapshot>,
+}
+
+impl Message for EntitySnapshots {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&self).map_err(|_e| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&b).map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn read_remote_result(_: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        Ok(())
+    }
+
+    fn write_remote_result(_res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait]
+impl Handler<EntitySnapshots> for ShardHost {
+    async fn handle(&mut self, message: EntitySnapshots, ctx: &mut ActorContext) {
+        let persistence = match ctx.system().persistence() {
+            Some(persistence) => persistence,
+            None => {
+                warn!(
+                    "received {} snapshot(s) for shard#{} but persistence is not configured",
+                    message.snapshots.len(),
+                    message.shard_id
+                );
+
+                return;
+            }
+        };
+
+        debug!(
+            "received {} snapshot(s) for shard#{}",
+            message.snapshots.len(),
+            message.shard_id
+        );
+
+        let shipped_snapshots = persistence.shipped_snapshots();
+        for snapshot in message.snapshots {
+            shipped_snapshots.insert(snapshot);
+        }
+    }
+}
+
+/// Stops every active entity of the shard, collecting the snapshots of those that support it
+pub async fn stop_entities_for_migration(
+    shard_id: ShardId,
+    shard: &LocalActorRef<Shard>,
+) -> EntitySnapshots {
+    let entities = shard.send(GetActiveEntities).await.unwrap_or_default();
+    let snapshots = join_all(entities.iter().map(|entity| entity.stop_for_migration())).await;
+
+    EntitySnapshots {
+        shard_id,
+        snapshots: snapshots
+            .into_iter()
+            .filter_map(|snapshot| snapshot.ok().flatten())
+            .collect(),
+    }
+}
@@ -5,8 +5,11 @@ use crate::remote::system::{NodeId, RemoteActorSystem};
 use crate::remote::RemoteActorRef;
 use crate::sharding::coordinator::allocation::DefaultAllocator;
 use crate::sharding::coordinator::{ShardCoordinator, ShardId};
+use crate::sharding::host::migration::stop_entities_for_migration;
 use crate::sharding::host::request::{handle_request, EntityRequest};
+use crate::sharding::host::warmup::WarmUpProvider;
 use crate::sharding::proto::sharding as proto;
+use crate::sharding::shard::passivation::PassivationConfig;
 use crate::sharding::shard::Shard;
 use protobuf::Message as ProtoMessage;
 
@@ -14,6 +17,7 @@ use crate::remote::actor::BoxedActorHandler;
 use crate::remote::heartbeat::Heartbeat;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 
 use crate::actor::scheduler::ActorType;
 use crate::sharding::coordinator::factory::CoordinatorFactory;
@@ -21,8 +25,11 @@ use crate::singleton::factory::SingletonFactory;
 use crate::singleton::Singleton;
 use uuid::Uuid;
 
+pub mod locate;
+pub mod migration;
 pub mod request;
 pub mod stats;
+pub mod warmup;
 
 pub enum ShardState {
     Starting {
@@ -47,6 +54,10 @@ pub struct ShardHost {
     requests_pending_shard_allocation: HashMap<ShardId, Vec<EntityRequest>>,
     allocator: Box<dyn ShardAllocator>,
     coordinator: Option<Singleton<ShardCoordinator, CoordinatorFactory>>,
+    snapshot_shipping: bool,
+    remember_entities: bool,
+    warm_up: Option<Arc<dyn WarmUpProvider>>,
+    passivation: Option<PassivationConfig>,
 }
 
 impl ShardHost {
@@ -102,9 +113,42 @@ impl ShardHost {
                 |s| s,
             ),
             coordinator: None,
+            snapshot_shipping: false,
+            remember_entities: true,
+            warm_up: None,
+            passivation: None,
         }
     }
 
+    /// Ships snapshots of the shard's entities to the node the shard is being moved to, when
+    /// a shard hosted by this node is rebalanced, see [`migration`]
+    pub fn with_snapshot_shipping(mut self, snapshot_shipping: bool) -> Self {
+        self.snapshot_shipping = snapshot_shipping;
+        self
+    }
+
+    /// Whether the entities that were running in each shard allocated to this node are
+    /// restarted once the shard has recovered (the default), rather than on their next message
+    pub fn with_remember_entities(mut self, remember_entities: bool) -> Self {
+        self.remember_entities = remember_entities;
+        self
+    }
+
+    /// Starts the entities returned by the provider once a shard has been allocated to this node,
+    /// see [`warmup`]
+    pub fn with_warm_up(mut self, warm_up: Option<Arc<dyn WarmUpProvider>>) -> Self {
+        self.warm_up = warm_up;
+        self
+    }
+
+    /// Passivates idle entities of the shards hosted by this node, see [`passivation`]
+    ///
+    /// [`passivation`]: crate::sharding::shard::passivation
+    pub fn with_passivation(mut self, passivation: Option<PassivationConfig>) -> Self {
+        self.passivation = passivation;
+        self
+    }
+
     pub fn get_coordinator(&self) -> Singleton<ShardCoordinator, CoordinatorFactory> {
         self.coordinator
             .as_ref()
@@ -147,6 +191,9 @@ pub struct StopShard {
     pub request_id: Uuid,
 }
 
+/// Stops every shard hosted by this node, returning the shards that are being stopped
+pub struct StopHostedShards;
+
 pub struct GetShards;
 
 pub struct HostedShards {
@@ -199,7 +246,9 @@ impl Handler<ShardAllocated> for ShardHost {
                     let system = ctx.system().clone();
 
                     let handler = self.actor_handler.new_boxed();
-                    let shard = Shard::new(shard_id, handler, true, self_ref);
+                    let shard = Shard::new(shard_id, handler, true, self_ref)
+                        .with_remember_entities(self.remember_entities)
+                        .with_passivation(self.passivation);
                     let actor_ref = system
                         .new_actor_deferred(shard_actor_id, shard, ActorType::Tracked)
                         .await;
@@ -267,13 +316,22 @@ impl Handler<ShardReady> for ShardHost {
                 ..
             }) => {
                 if let Some(stop_requested) = stop_requested {
-                    // TODO: move the request buffer over to `requests_pending_shard_allocation`
+                    if !request_buffer.is_empty() {
+                        self.requests_pending_shard_allocation
+                            .entry(shard_id)
+                            .or_default()
+                            .extend(request_buffer);
+                    }
+
+                    self.hosted_shards.insert(shard_id, ShardState::Stopping);
                     self.stop_shard(shard_id, actor_ref, ctx, Some(stop_requested));
                 } else {
                     let mut shard_state = ShardState::Ready(actor_ref);
                     for request in request_buffer {
                         handle_request(request, shard_id, &mut shard_state);
                     }
+
+                    self.warm_up_shard(shard_id, ctx);
                 }
             }
 
@@ -300,31 +358,69 @@ impl Handler<StopShard> for ShardHost {
         };
 
         match shard_entry {
-            Entry::Occupied(mut shard_entry) => {
-                // TODO: is this correct? if the status is starting, should we not update that rather than re-inserting `Stopping`?
-                match shard_entry.insert(ShardState::Stopping) {
-                    ShardState::Starting {
-                        mut stop_requested, ..
-                    } => {
-                        // actor has requested to be started so we can't stop it yet, once the actor
-                        // has finished starting, we can then stop it.
-                        stop_requested = Some(stop_request);
-                    }
+            Entry::Occupied(mut shard_entry) => match shard_entry.get_mut() {
+                ShardState::Starting { stop_requested, .. } => {
+                    // actor has requested to be started so we can't stop it yet, once the actor
+                    // has finished starting, we can then stop it.
+                    *stop_requested = Some(stop_request);
+                }
 
-                    ShardState::Ready(actor_ref) => {
+                ShardState::Ready(_) => {
+                    if let ShardState::Ready(actor_ref) = shard_entry.insert(ShardState::Stopping) {
                         self.stop_shard(shard_id, actor_ref, ctx, Some(stop_request))
                     }
+                }
 
-                    ShardState::Stopping => {
-                        // shard already stopping
-                    }
+                ShardState::Stopping => {
+                    // shard already stopping
                 }
+            },
+            Entry::Vacant(_) => {
+                // the shard isn't hosted here, so there's nothing to stop before it's reallocated
+                let remote_system = ctx.system().remote_owned();
+                tokio::spawn(async move {
+                    notify_shard_stopped(shard_id, true, stop_request, &remote_system).await
+                });
             }
-            Entry::Vacant(_) => {}
         }
     }
 }
 
+#[async_trait]
+impl Handler<StopHostedShards> for ShardHost {
+    async fn handle(
+        &mut self,
+        _message: StopHostedShards,
+        ctx: &mut ActorContext,
+    ) -> Vec<LocalActorRef<Shard>> {
+        let mut stopping = vec![];
+        let shard_ids: Vec<ShardId> = self.hosted_shards.keys().copied().collect();
+        for shard_id in shard_ids {
+            match self.hosted_shards.insert(shard_id, ShardState::Stopping) {
+                Some(ShardState::Ready(actor_ref)) => {
+                    self.stop_shard(shard_id, actor_ref.clone(), ctx, None);
+                    stopping.push(actor_ref);
+                }
+
+                Some(ShardState::Starting { actor_ref, .. }) => {
+                    let _ = actor_ref.notify_stop();
+                    stopping.push(actor_ref);
+                }
+
+                _ => {}
+            }
+        }
+
+        info!(
+            shard_entity = self.shard_entity.as_str(),
+            "stopping {} hosted shards",
+            stopping.len()
+        );
+
+        stopping
+    }
+}
+
 impl ShardHost {
     fn stop_shard(
         &self,
@@ -335,34 +431,67 @@ impl ShardHost {
     ) {
         let shard_host = self.actor_ref(ctx);
         let remote_system = ctx.system().remote_owned();
+        let coordinator = self
+            .coordinator
+            .clone()
+            .filter(|_| self.snapshot_shipping && stop_requested.is_some());
+
         tokio::spawn(async move {
-            let result = actor_ref.stop().await;
+            if let Some(coordinator) = coordinator {
+                let snapshots = stop_entities_for_migration(shard_id, &actor_ref).await;
+                if !snapshots.snapshots.is_empty() {
+                    debug!(
+                        "shipping {} entity snapshot(s) for shard#{}",
+                        snapshots.snapshots.len(),
+                        shard_id
+                    );
+
+                    if let Err(e) = coordinator.send(snapshots).await {
+                        warn!(
+                            "failed to ship entity snapshots for shard#{}, error={}",
+                            shard_id, e
+                        );
+                    }
+                }
+            }
+
+            let result = actor_ref.stop(false).await;
             let _ = shard_host.notify(ShardStopped {
                 shard_id,
                 stopped_successfully: result.is_ok(),
             });
 
             if let Some(stop_requested) = stop_requested {
-                let shard_stopped = ShardStopped {
-                    shard_id,
-                    stopped_successfully: result.is_ok(),
-                }
-                .into_envelope(EnvelopeType::Remote)
-                .unwrap()
-                .into_bytes();
-
-                let _ = remote_system
-                    .notify_raw_rpc_result(
-                        stop_requested.request_id,
-                        shard_stopped,
-                        stop_requested.origin_node_id,
-                    )
+                notify_shard_stopped(shard_id, result.is_ok(), stop_requested, &remote_system)
                     .await;
             }
         });
     }
 }
 
+async fn notify_shard_stopped(
+    shard_id: ShardId,
+    stopped_successfully: bool,
+    stop_requested: StopRequested,
+    remote_system: &RemoteActorSystem,
+) {
+    let shard_stopped = ShardStopped {
+        shard_id,
+        stopped_successfully,
+    }
+    .into_envelope(EnvelopeType::Remote)
+    .unwrap()
+    .into_bytes();
+
+    remote_system
+        .notify_raw_rpc_result(
+            stop_requested.request_id,
+            shard_stopped,
+            stop_requested.origin_node_id,
+        )
+        .await;
+}
+
 #[async_trait]
 impl Handler<ShardStopped> for ShardHost {
     async fn handle(&mut self, message: ShardStopped, _ctx: &mut ActorContext) {
@@ -375,6 +504,10 @@ pub fn shard_actor_id(shard_entity: &String, shard_id: ShardId) -> ActorId {
     format!("{}-Shard-{}", &shard_entity, shard_id).into_actor_id()
 }
 
+impl Message for StopHostedShards {
+    type Result = Vec<LocalActorRef<Shard>>;
+}
+
 impl Message for ShardAllocated {
     type Result = ();
 
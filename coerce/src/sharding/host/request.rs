@@ -19,7 +19,17 @@ pub struct EntityRequest {
     pub actor_id: ActorId,
     pub message_type: String,
     pub message: Vec<u8>,
+
+    /// The version of the message's handler requested by the sender, 0 if unversioned,
+    /// see [`versioned`](crate::actor::message::versioned)
+    pub message_version: u32,
+
     pub recipe: Option<Arc<Vec<u8>>>,
+
+    /// The shard the entity belongs to, if `None`, the shard is chosen by the
+    /// [`ShardAllocator`](crate::sharding::host::ShardAllocator)
+    pub shard_id: Option<ShardId>,
+
     pub result_channel: Option<Sender<Result<Vec<u8>, ActorRefErr>>>,
 }
 
@@ -28,6 +38,7 @@ pub struct RemoteEntityRequest {
     pub actor_id: ActorId,
     pub message_type: String,
     pub message: Vec<u8>,
+    pub message_version: u32,
     pub recipe: Option<Vec<u8>>,
     pub origin_node: NodeId,
 }
@@ -69,9 +80,27 @@ pub(crate) fn handle_request(
 #[async_trait]
 impl Handler<EntityRequest> for ShardHost {
     async fn handle(&mut self, message: EntityRequest, ctx: &mut ActorContext) {
-        let shard_id = self.allocator.allocate(&message.actor_id);
+        let shard_id = match message.shard_id {
+            Some(shard_id) => shard_id,
+            None => self.allocator.allocate(&message.actor_id),
+        };
+
+        if let Some(ShardState::Stopping) = self.hosted_shards.get(&shard_id) {
+            // the shard is being moved to another node, the request is delivered
+            // once the shard's new allocation is received
+            let buffered_requests = self
+                .requests_pending_shard_allocation
+                .entry(shard_id)
+                .or_default();
+
+            buffered_requests.push(message);
 
-        if let Some(shard) = self.hosted_shards.get_mut(&shard_id) {
+            debug!(
+                "shard#{} stopping, buffering request (buffered_requests={})",
+                shard_id,
+                buffered_requests.len()
+            );
+        } else if let Some(shard) = self.hosted_shards.get_mut(&shard_id) {
             handle_request(message, shard_id, shard);
         } else if let Some(shard) = self.remote_shards.get(&shard_id) {
             let shard_ref = shard.clone();
@@ -106,8 +135,11 @@ impl Handler<EntityRequest> for ShardHost {
                             let _ = host_ref.notify(ShardAllocated(shard_id, node_id));
                         }
                         AllocateShardResult::NotAllocated => {
-                            // No hosts available?
-                            error!("shard(#{}) not allocated, no hosts available?", shard_id);
+                            // requests stay buffered until the shard's allocation is broadcast
+                            warn!(
+                                "shard(#{}) not allocated, no hosts available or the shard is being rebalanced",
+                                shard_id
+                            );
                         }
                         AllocateShardResult::Err(e) => {
                             error!("shard(#{}) failed to allocate: {}", shard_id, e);
@@ -147,6 +179,7 @@ async fn remote_entity_request(
             actor_id: request.actor_id.into_actor_id(),
             message_type: request.message_type,
             message: request.message,
+            message_version: request.message_version,
             recipe: request.recipe.map(|r| r.as_ref().clone()),
         })
         .await
@@ -186,7 +219,9 @@ impl From<RemoteEntityRequest> for EntityRequest {
             actor_id: req.actor_id.into_actor_id(),
             message_type: req.message_type,
             message: req.message,
+            message_version: req.message_version,
             recipe: req.recipe.map(|r| Arc::new(r)),
+            shard_id: None,
             result_channel: None,
         }
     }
@@ -216,6 +251,7 @@ impl Message for RemoteEntityRequest {
                 },
             ),
             origin_node: self.origin_node,
+            message_version: self.message_version,
             ..Default::default()
         }
         .write_to_bytes()
@@ -231,6 +267,7 @@ impl Message for RemoteEntityRequest {
                     actor_id: proto.actor_id.into_actor_id(),
                     message_type: proto.message_type,
                     message: proto.message,
+                    message_version: proto.message_version,
                     recipe: proto
                         .recipe
                         .into_option()
@@ -0,0 +1,121 @@
+//! Warm-up of sharded entities, see [`ShardingBuilder::with_warm_up`]
+//!
+//! Once a shard has been allocated to this node and has finished recovering, the configured
+//! [`WarmUpProvider`] is asked for the entities that should be started ahead of their first
+//! request, for example the most frequently used entities, loaded from a persisted list.
+//! Entities that belong to the shard and aren't already running are then started, which avoids
+//! a spike in latency while a freshly deployed node starts every entity on demand.
+//!
+//! Entities can also be warmed up on demand via [`Sharding::warm_up`].
+//!
+//! [`ShardingBuilder::with_warm_up`]: crate::sharding::builder::ShardingBuilder::with_warm_up
+//! [`Sharding::warm_up`]: crate::sharding::Sharding::warm_up
+
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::{Actor, ActorId, ActorRecipe, IntoActorId};
+use crate::sharding::coordinator::ShardId;
+use crate::sharding::host::{ShardHost, ShardState};
+use crate::sharding::shard::message::WarmUp;
+use crate::sharding::shard::RecipeRef;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// An entity to start ahead of its first request
+#[derive(Debug, Clone)]
+pub struct WarmUpEntity {
+    pub actor_id: ActorId,
+    pub recipe: RecipeRef,
+}
+
+impl WarmUpEntity {
+    /// Creates a [`WarmUpEntity`], returns `None` if the recipe couldn't be serialised
+    pub fn new<R: ActorRecipe>(actor_id: impl IntoActorId, recipe: &R) -> Option<Self> {
+        recipe.write_to_bytes().map(|recipe| Self {
+            actor_id: actor_id.into_actor_id(),
+            recipe: Arc::new(recipe),
+        })
+    }
+}
+
+/// Provides the entities to start once a shard is allocated to this node
+#[async_trait]
+pub trait WarmUpProvider: 'static + Send + Sync {
+    /// Returns the entities to start for the provided shard. Entities that don't belong to
+    /// the shard are ignored, so providers are free to return the same list for every shard.
+    async fn entities(&self, shard_entity: &str, shard_id: ShardId) -> Vec<WarmUpEntity>;
+}
+
+#[async_trait]
+impl WarmUpProvider for Vec<WarmUpEntity> {
+    async fn entities(&self, _shard_entity: &str, _shard_id: ShardId) -> Vec<WarmUpEntity> {
+        self.clone()
+    }
+}
+
+/// Starts the provided entities that belong to shards hosted by this node, optionally
+/// only those that belong to `shard_id`
+pub struct WarmUpEntities {
+    pub shard_id: Option<ShardId>,
+    pub entities: Vec<WarmUpEntity>,
+}
+
+impl Message for WarmUpEntities {
+    type Result = ();
+}
+
+impl ShardHost {
+    pub(crate) fn warm_up_shard(&self, shard_id: ShardId, ctx: &ActorContext) {
+        let provider = match &self.warm_up {
+            Some(provider) => provider.clone(),
+            None => return,
+        };
+
+        let shard_entity = self.shard_entity.clone();
+        let host_ref = self.actor_ref(ctx);
+        tokio::spawn(async move {
+            let entities = provider.entities(&shard_entity, shard_id).await;
+            if !entities.is_empty() {
+                let _ = host_ref.notify(WarmUpEntities {
+                    shard_id: Some(shard_id),
+                    entities,
+                });
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl Handler<WarmUpEntities> for ShardHost {
+    async fn handle(&mut self, message: WarmUpEntities, _ctx: &mut ActorContext) {
+        let mut shard_entities: HashMap<ShardId, Vec<WarmUpEntity>> = HashMap::new();
+        for entity in message.entities {
+            let shard_id = self.allocator.allocate(&entity.actor_id);
+            if message.shard_id.is_none_or(|s| s == shard_id) {
+                shard_entities.entry(shard_id).or_default().push(entity);
+            }
+        }
+
+        for (shard_id, entities) in shard_entities {
+            match self.hosted_shards.get(&shard_id) {
+                Some(ShardState::Ready(shard)) => {
+                    debug!(
+                        "warming up {} entities of shard#{}",
+                        entities.len(),
+                        shard_id
+                    );
+
+                    let _ = shard.notify(WarmUp { entities });
+                }
+
+                _ => {
+                    trace!(
+                        "shard#{} not ready on this node, skipping warm-up of {} entities",
+                        shard_id,
+                        entities.len()
+                    );
+                }
+            }
+        }
+    }
+}
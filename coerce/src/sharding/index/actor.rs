@@ -0,0 +1,312 @@
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Envelope, Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use crate::actor::Actor;
+use crate::persistent::journal::snapshot::Snapshot;
+use crate::persistent::journal::types::JournalTypes;
+use crate::persistent::{PersistentActor, Recover, RecoverSnapshot};
+use crate::sharding::index::{EntityAttributes, IndexQuery};
+use crate::singleton::factory::SingletonFactory;
+use std::collections::{BTreeSet, HashMap};
+use std::marker::PhantomData;
+
+/// Creates the [`EntityIndexer`] singleton
+pub struct EntityIndexerFactory<A: Actor> {
+    pub(crate) snapshot_interval: u64,
+    pub(crate) _a: PhantomData<A>,
+}
+
+impl<A: Actor> SingletonFactory for EntityIndexerFactory<A> {
+    type Actor = EntityIndexer<A>;
+
+    fn create(&self) -> EntityIndexer<A> {
+        EntityIndexer {
+            documents: HashMap::new(),
+            index: HashMap::new(),
+            snapshot_interval: self.snapshot_interval,
+            events_since_snapshot: 0,
+            _a: PhantomData,
+        }
+    }
+}
+
+/// Holds the attribute documents of every indexed entity of type `A`, runs as a cluster
+/// singleton, persisting each change to the index, so the index is recovered when the
+/// singleton is moved to another node.
+pub struct EntityIndexer<A: Actor> {
+    documents: HashMap<String, EntityAttributes>,
+
+    /// attribute key -> attribute value -> entity ids
+    index: HashMap<String, HashMap<String, BTreeSet<String>>>,
+    snapshot_interval: u64,
+    events_since_snapshot: u64,
+    _a: PhantomData<A>,
+}
+
+impl<A: Actor> EntityIndexer<A> {
+    fn apply_update(&mut self, entity_id: String, attributes: EntityAttributes) {
+        self.apply_removal(&entity_id);
+
+        for (key, value) in attributes.iter() {
+            self.index
+                .entry(key.clone())
+                .or_default()
+                .entry(value.clone())
+                .or_default()
+                .insert(entity_id.clone());
+        }
+
+        self.documents.insert(entity_id, attributes);
+    }
+
+    fn apply_removal(&mut self, entity_id: &str) {
+        let attributes = match self.documents.remove(entity_id) {
+            Some(attributes) => attributes,
+            None => return,
+        };
+
+        for (key, value) in attributes.iter() {
+            if let Some(values) = self.index.get_mut(key) {
+                if let Some(entity_ids) = values.get_mut(value) {
+                    entity_ids.remove(entity_id);
+                    if entity_ids.is_empty() {
+                        values.remove(value);
+                    }
+                }
+
+                if values.is_empty() {
+                    self.index.remove(key);
+                }
+            }
+        }
+    }
+
+    fn find(&self, query: &IndexQuery) -> Vec<String> {
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut filters = query.filters.iter();
+
+        let candidates: Vec<&String> = match filters.next() {
+            Some((key, value)) => self
+                .index
+                .get(key)
+                .and_then(|values| values.get(value))
+                .map_or_else(Vec::new, |entity_ids| entity_ids.iter().collect()),
+            None => {
+                let mut entity_ids: Vec<&String> = self.documents.keys().collect();
+                entity_ids.sort();
+                entity_ids
+            }
+        };
+
+        let filters: Vec<&(String, String)> = filters.collect();
+        candidates
+            .into_iter()
+            .filter(|entity_id| {
+                let attributes = &self.documents[entity_id.as_str()];
+                filters
+                    .iter()
+                    .all(|(key, value)| attributes.get(key) == Some(value.as_str()))
+            })
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    async fn on_persisted(&mut self, ctx: &mut ActorContext) {
+        self.events_since_snapshot += 1;
+        if self.events_since_snapshot < self.snapshot_interval {
+            return;
+        }
+
+        let snapshot = IndexSnapshot {
+            documents: self.documents.clone(),
+        };
+
+        match self.snapshot(snapshot, ctx).await {
+            Ok(_) => self.events_since_snapshot = 0,
+            Err(e) => warn!(error = format!("{}", e), "failed to snapshot entity index"),
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Actor> PersistentActor for EntityIndexer<A> {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        format!("{}-EntityIndex", A::type_name())
+    }
+
+    fn configure(types: &mut JournalTypes<Self>) {
+        types
+            .message::<UpdateEntityAttributes>("UpdateEntityAttributes")
+            .message::<RemoveEntityAttributes>("RemoveEntityAttributes")
+            .snapshot::<IndexSnapshot>("IndexSnapshot");
+    }
+
+    async fn post_recovery(&mut self, _ctx: &mut ActorContext) {
+        info!(
+            entity_type = A::type_name(),
+            indexed_entities = self.documents.len(),
+            "entity index started"
+        );
+    }
+}
+
+/// Replaces the attribute document of the entity
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateEntityAttributes {
+    pub entity_id: String,
+    pub attributes: EntityAttributes,
+}
+
+/// Removes the entity from the index
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoveEntityAttributes {
+    pub entity_id: String,
+}
+
+/// Returns the ids of the entities matching the query, in entity id order
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FindEntities {
+    pub query: IndexQuery,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IndexSnapshot {
+    documents: HashMap<String, EntityAttributes>,
+}
+
+#[async_trait]
+impl<A: Actor> Handler<UpdateEntityAttributes> for EntityIndexer<A> {
+    async fn handle(&mut self, message: UpdateEntityAttributes, ctx: &mut ActorContext) {
+        if self.documents.get(&message.entity_id) == Some(&message.attributes) {
+            return;
+        }
+
+        if self.persist(&message, ctx).await.is_ok() {
+            self.apply_update(message.entity_id, message.attributes);
+            self.on_persisted(ctx).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Actor> Handler<RemoveEntityAttributes> for EntityIndexer<A> {
+    async fn handle(&mut self, message: RemoveEntityAttributes, ctx: &mut ActorContext) {
+        if !self.documents.contains_key(&message.entity_id) {
+            return;
+        }
+
+        if self.persist(&message, ctx).await.is_ok() {
+            self.apply_removal(&message.entity_id);
+            self.on_persisted(ctx).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Actor> Handler<FindEntities> for EntityIndexer<A> {
+    async fn handle(&mut self, message: FindEntities, _ctx: &mut ActorContext) -> Vec<String> {
+        self.find(&message.query)
+    }
+}
+
+#[async_trait]
+impl<A: Actor> Recover<UpdateEntityAttributes> for EntityIndexer<A> {
+    async fn recover(&mut self, message: UpdateEntityAttributes, _ctx: &mut ActorContext) {
+        self.apply_update(message.entity_id, message.attributes);
+        self.events_since_snapshot += 1;
+    }
+}
+
+#[async_trait]
+impl<A: Actor> Recover<RemoveEntityAttributes> for EntityIndexer<A> {
+    async fn recover(&mut self, message: RemoveEntityAttributes, _ctx: &mut ActorContext) {
+        self.apply_removal(&message.entity_id);
+        self.events_since_snapshot += 1;
+    }
+}
+
+#[async_trait]
+impl<A: Actor> RecoverSnapshot<IndexSnapshot> for EntityIndexer<A> {
+    async fn recover(&mut self, snapshot: IndexSnapshot, _ctx: &mut ActorContext) {
+        self.documents.clear();
+        self.index.clear();
+
+        for (entity_id, attributes) in snapshot.documents {
+            self.apply_update(entity_id, attributes);
+        }
+
+        self.events_since_snapshot = 0;
+    }
+}
+
+impl Message for UpdateEntityAttributes {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&self).map_err(|_e| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&b).map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn read_remote_result(_: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        Ok(())
+    }
+
+    fn write_remote_result(_res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+}
+
+impl Message for RemoveEntityAttributes {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&self).map_err(|_e| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&b).map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn read_remote_result(_: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        Ok(())
+    }
+
+    fn write_remote_result(_res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+}
+
+impl Message for FindEntities {
+    type Result = Vec<String>;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&self).map_err(|_e| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&b).map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn read_remote_result(b: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        serde_json::from_slice(&b).map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn write_remote_result(res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        serde_json::to_vec(&res).map_err(|_e| MessageWrapErr::SerializationErr)
+    }
+}
+
+impl Snapshot for IndexSnapshot {
+    fn into_remote_envelope(self) -> Result<Envelope<Self>, MessageWrapErr> {
+        serde_json::to_vec(&self)
+            .map(Envelope::Remote)
+            .map_err(|_e| MessageWrapErr::SerializationErr)
+    }
+
+    fn from_remote_envelope(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&b).map_err(|_e| MessageUnwrapErr::DeserializationErr)
+    }
+}
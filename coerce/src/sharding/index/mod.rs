@@ -0,0 +1,208 @@
+//! Secondary indexes over sharded entities
+//!
+//! Entities publish a small document of attributes (for example `status=overdue`) to the
+//! [`EntityIndex`] of their type, typically after persisting an event that changes one of
+//! the attributes. The index can then be queried by attribute, allowing common lookups without
+//! scanning, or starting, every entity.
+//!
+//! The index runs as a cluster [`Singleton`], changes to the index are persisted to the
+//! journal of the index, which must be shared by every node in the cluster, so the index is
+//! recovered if the singleton is moved to another node.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .configure(sharding)
+//!     .configure(entity_index::<Invoice>)
+//!     .build()
+//!     .await;
+//!
+//! let index = EntityIndex::<Invoice>::builder(remote.clone()).build().await;
+//!
+//! // within the invoice entity, once the invoice is overdue
+//! index.update("invoice-1", EntityAttributes::new().with("status", "overdue")).await?;
+//!
+//! let overdue = index.find(IndexQuery::eq("status", "overdue")).await?;
+//! ```
+
+use crate::actor::{Actor, ActorId, ActorRefErr, IntoActorId};
+use crate::remote::system::builder::RemoteSystemConfigBuilder;
+use crate::remote::system::RemoteActorSystem;
+use crate::sharding::index::actor::{
+    EntityIndexer, EntityIndexerFactory, FindEntities, RemoveEntityAttributes,
+    UpdateEntityAttributes,
+};
+use crate::singleton::{singleton, Singleton, SingletonBuilder};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+pub mod actor;
+
+const DEFAULT_SNAPSHOT_INTERVAL: u64 = 1000;
+
+/// The attributes of an entity, which the entity can be found by
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct EntityAttributes(HashMap<String, String>);
+
+impl EntityAttributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, key: impl ToString, value: impl ToString) -> Self {
+        self.0.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|v| v.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
+}
+
+impl From<HashMap<String, String>> for EntityAttributes {
+    fn from(attributes: HashMap<String, String>) -> Self {
+        Self(attributes)
+    }
+}
+
+/// Selects entities whose attributes are equal to every provided value,
+/// an empty query selects every indexed entity
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IndexQuery {
+    pub(crate) filters: Vec<(String, String)>,
+    pub(crate) limit: Option<usize>,
+}
+
+impl IndexQuery {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn eq(key: impl ToString, value: impl ToString) -> Self {
+        Self::all().and_eq(key, value)
+    }
+
+    pub fn and_eq(mut self, key: impl ToString, value: impl ToString) -> Self {
+        self.filters.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Limits the number of entity ids returned
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// A handle to the index of entities of type `A`, which can be cloned and provided to entities,
+/// via their [`ActorFactory`].
+///
+/// [`ActorFactory`]: crate::actor::ActorFactory
+pub struct EntityIndex<A: Actor> {
+    singleton: Singleton<EntityIndexer<A>, EntityIndexerFactory<A>>,
+}
+
+pub struct EntityIndexBuilder<A: Actor> {
+    system: RemoteActorSystem,
+    snapshot_interval: u64,
+    _a: PhantomData<A>,
+}
+
+impl<A: Actor> EntityIndex<A> {
+    pub fn builder(system: RemoteActorSystem) -> EntityIndexBuilder<A> {
+        EntityIndexBuilder {
+            system,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            _a: PhantomData,
+        }
+    }
+
+    /// Replaces the attributes of the entity, completes once the change has been persisted
+    pub async fn update(
+        &self,
+        entity_id: impl IntoActorId,
+        attributes: EntityAttributes,
+    ) -> Result<(), ActorRefErr> {
+        self.singleton
+            .send(UpdateEntityAttributes {
+                entity_id: entity_id.into_actor_id().to_string(),
+                attributes,
+            })
+            .await
+    }
+
+    /// Removes the entity from the index, for example when the entity is deleted
+    pub async fn remove(&self, entity_id: impl IntoActorId) -> Result<(), ActorRefErr> {
+        self.singleton
+            .send(RemoveEntityAttributes {
+                entity_id: entity_id.into_actor_id().to_string(),
+            })
+            .await
+    }
+
+    /// Returns the ids of the entities matching the query, in entity id order
+    pub async fn find(&self, query: IndexQuery) -> Result<Vec<ActorId>, ActorRefErr> {
+        let entity_ids = self.singleton.send(FindEntities { query }).await?;
+        Ok(entity_ids
+            .into_iter()
+            .map(|entity_id| entity_id.into_actor_id())
+            .collect())
+    }
+}
+
+impl<A: Actor> EntityIndexBuilder<A> {
+    /// How many changes are persisted between snapshots of the index, defaults to 1000
+    pub fn snapshot_interval(mut self, snapshot_interval: u64) -> Self {
+        self.snapshot_interval = snapshot_interval.max(1);
+        self
+    }
+
+    /// Starts the index, which should be done before the cluster worker is started,
+    /// since the underlying [`Singleton`] is only started once cluster membership is established
+    pub async fn build(self) -> EntityIndex<A> {
+        let factory = EntityIndexerFactory {
+            snapshot_interval: self.snapshot_interval,
+            _a: PhantomData,
+        };
+
+        let singleton = SingletonBuilder::new(self.system)
+            .factory(factory)
+            .build()
+            .await;
+
+        EntityIndex { singleton }
+    }
+}
+
+impl<A: Actor> Clone for EntityIndex<A> {
+    fn clone(&self) -> Self {
+        Self {
+            singleton: self.singleton.clone(),
+        }
+    }
+}
+
+/// Registers the remote handlers required by the [`EntityIndex`] of entities of type `A`
+pub fn entity_index<A: Actor>(
+    builder: &mut RemoteSystemConfigBuilder,
+) -> &mut RemoteSystemConfigBuilder {
+    let entity_type = A::type_name();
+    singleton::<EntityIndexerFactory<A>>(builder)
+        .with_handler::<EntityIndexer<A>, UpdateEntityAttributes>(format!(
+            "EntityIndex<{}>.UpdateEntityAttributes",
+            entity_type
+        ))
+        .with_handler::<EntityIndexer<A>, RemoveEntityAttributes>(format!(
+            "EntityIndex<{}>.RemoveEntityAttributes",
+            entity_type
+        ))
+        .with_handler::<EntityIndexer<A>, FindEntities>(format!(
+            "EntityIndex<{}>.FindEntities",
+            entity_type
+        ))
+}
@@ -1,32 +1,45 @@
 //! Coerce Distributed Sharding
 
 use crate::actor::message::{Handler, Message};
+use crate::actor::system::shutdown::ShutdownPhase;
 use crate::actor::{
     Actor, ActorFactory, ActorId, ActorRecipe, ActorRefErr, IntoActor, IntoActorId, LocalActorRef,
 };
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+use crate::remote::cluster::node::NodeAttribute;
 use crate::remote::system::builder::RemoteSystemConfigBuilder;
 use crate::remote::system::RemoteActorSystem;
 use crate::sharding::coordinator::allocation::AllocateShard;
 use crate::sharding::coordinator::factory::CoordinatorFactory;
 use crate::sharding::coordinator::stats::GetShardingStats;
-use crate::sharding::coordinator::ShardCoordinator;
+use crate::sharding::coordinator::strategy::{
+    LeastShardAllocationStrategy, ShardAllocationStrategy, DEFAULT_MAX_REBALANCE_SHARDS,
+};
+use crate::sharding::coordinator::{ShardCoordinator, ShardId};
+use crate::sharding::host::locate::{locate_entity, EntityLocation, LocateShard};
+use crate::sharding::host::migration::EntitySnapshots;
 use crate::sharding::host::request::{EntityRequest, RemoteEntityRequest};
+use crate::sharding::host::warmup::{WarmUpEntities, WarmUpEntity, WarmUpProvider};
 use crate::sharding::host::{
-    Init, ShardAllocated, ShardAllocator, ShardHost, ShardReallocating, StopShard,
+    Init, ShardAllocated, ShardAllocator, ShardHost, ShardReallocating, StopHostedShards, StopShard,
 };
+use crate::sharding::shard::passivation::PassivationConfig;
 use crate::sharding::shard::stats::GetShardStats;
 use crate::sharding::shard::Shard;
 use crate::singleton::{singleton, Singleton, SingletonBuilder};
+use futures::future::join_all;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use tokio::sync::oneshot;
 
 pub mod builder;
+pub mod bulk;
 pub mod coordinator;
+pub mod extractor;
 pub mod host;
+pub mod index;
 pub mod proto;
 pub mod shard;
 
@@ -47,6 +60,7 @@ pub struct Sharded<A: Actor> {
     sharding: Arc<ShardingCore>,
     actor_id: ActorId,
     recipe: Option<Arc<Vec<u8>>>,
+    shard_id: Option<ShardId>,
     _a: PhantomData<A>,
 }
 
@@ -67,10 +81,19 @@ pub enum StartupErr {
 }
 
 impl<A: ActorFactory> Sharding<A> {
+    #[allow(clippy::too_many_arguments)]
     pub async fn try_start(
         shard_entity: String,
         system: RemoteActorSystem,
         allocator: Option<Box<dyn ShardAllocator>>,
+        preferred_node_attribute: Option<NodeAttribute>,
+        role: Option<String>,
+        snapshot_shipping: bool,
+        remember_entities: bool,
+        warm_up: Option<Arc<dyn WarmUpProvider>>,
+        allocation_strategy: Option<Arc<dyn ShardAllocationStrategy>>,
+        max_rebalance_shards: Option<usize>,
+        passivation: Option<PassivationConfig>,
     ) -> Result<Self, StartupErr> {
         let actor_type = A::Actor::type_name();
         let actor_handler = system.config().actor_handler(actor_type).ok_or_else(|| {
@@ -81,6 +104,10 @@ impl<A: ActorFactory> Sharding<A> {
         })?;
 
         let host = ShardHost::new(shard_entity.clone(), actor_handler, allocator)
+            .with_snapshot_shipping(snapshot_shipping)
+            .with_remember_entities(remember_entities)
+            .with_warm_up(warm_up)
+            .with_passivation(passivation)
             .into_actor(
                 Some(ShardHost::actor_id(&shard_entity, system.node_id())),
                 system.actor_system(),
@@ -89,12 +116,33 @@ impl<A: ActorFactory> Sharding<A> {
             .map_err(|e| e.into_host_err(&shard_entity))?;
 
         let coordinator = SingletonBuilder::new(system.clone())
-            .factory(CoordinatorFactory::new(shard_entity.clone(), host.clone()))
+            .factory(
+                CoordinatorFactory::new(shard_entity.clone(), host.clone())
+                    .with_preferred_node_attribute(preferred_node_attribute)
+                    .with_role(role)
+                    .with_allocation_strategy(
+                        allocation_strategy
+                            .unwrap_or_else(|| Arc::new(LeastShardAllocationStrategy)),
+                    )
+                    .with_max_rebalance_shards(
+                        max_rebalance_shards.unwrap_or(DEFAULT_MAX_REBALANCE_SHARDS),
+                    ),
+            )
             .build()
             .await;
 
         let _ = host.send(Init(coordinator.clone())).await;
 
+        let shard_host = host.clone();
+        system.actor_system().add_shutdown_task(
+            ShutdownPhase::ShardHandoff,
+            format!("shard-handoff-{}", &shard_entity),
+            move || async move {
+                let shards = shard_host.send(StopHostedShards).await.unwrap_or_default();
+                join_all(shards.iter().map(|shard| shard.wait_for_stop())).await;
+            },
+        );
+
         Ok(Self {
             core: Arc::new(ShardingCore {
                 host,
@@ -106,14 +154,35 @@ impl<A: ActorFactory> Sharding<A> {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn start(
         shard_entity: String,
         system: RemoteActorSystem,
         allocator: Option<Box<dyn ShardAllocator>>,
+        preferred_node_attribute: Option<NodeAttribute>,
+        role: Option<String>,
+        snapshot_shipping: bool,
+        remember_entities: bool,
+        warm_up: Option<Arc<dyn WarmUpProvider>>,
+        allocation_strategy: Option<Arc<dyn ShardAllocationStrategy>>,
+        max_rebalance_shards: Option<usize>,
+        passivation: Option<PassivationConfig>,
     ) -> Self {
-        Self::try_start(shard_entity, system, allocator)
-            .await
-            .expect("start sharding")
+        Self::try_start(
+            shard_entity,
+            system,
+            allocator,
+            preferred_node_attribute,
+            role,
+            snapshot_shipping,
+            remember_entities,
+            warm_up,
+            allocation_strategy,
+            max_rebalance_shards,
+            passivation,
+        )
+        .await
+        .expect("start sharding")
     }
 
     pub fn get(&self, actor_id: impl IntoActorId, recipe: Option<A::Recipe>) -> Sharded<A::Actor> {
@@ -128,6 +197,7 @@ impl<A: ActorFactory> Sharding<A> {
             actor_id,
             recipe,
             sharding,
+            shard_id: None,
             _a: Default::default(),
         }
     }
@@ -143,6 +213,25 @@ impl<A: ActorFactory> Sharding<A> {
         self.core.notify_host(message)
     }
 
+    /// Locates the node hosting the provided entity
+    pub async fn locate(&self, entity_id: impl IntoActorId) -> Option<EntityLocation> {
+        locate_entity(
+            &self.core.host,
+            entity_id.into_actor_id(),
+            &self.core.system,
+        )
+        .await
+    }
+
+    /// Starts the provided entities that belong to shards hosted by this node and aren't
+    /// already running, entities belonging to shards hosted by other nodes are ignored
+    pub fn warm_up(&self, entities: Vec<WarmUpEntity>) -> Result<(), ActorRefErr> {
+        self.core.notify_host(WarmUpEntities {
+            shard_id: None,
+            entities,
+        })
+    }
+
     pub fn shard_host(&self) -> &LocalActorRef<ShardHost> {
         &self.core.host
     }
@@ -179,6 +268,7 @@ impl<A: Actor> Sharded<A> {
             }
         })?;
 
+        let message_version = self.sharding.system.message_version(&message_type);
         let (tx, rx) = oneshot::channel();
 
         let actor_id = self.actor_id.clone();
@@ -186,7 +276,9 @@ impl<A: Actor> Sharded<A> {
             actor_id,
             message_type,
             message,
+            message_version,
             recipe: self.recipe.clone(),
+            shard_id: self.shard_id,
             result_channel: Some(tx),
         });
 
@@ -206,9 +298,12 @@ pub fn sharding(builder: &mut RemoteSystemConfigBuilder) -> &mut RemoteSystemCon
     singleton::<CoordinatorFactory>(builder)
         .with_handler::<ShardCoordinator, AllocateShard>("ShardCoordinator.AllocateShard")
         .with_handler::<ShardCoordinator, GetShardingStats>("ShardCoordinator.GetShardingStats")
+        .with_handler::<ShardCoordinator, EntitySnapshots>("ShardCoordinator.EntitySnapshots")
         .with_handler::<ShardHost, ShardAllocated>("ShardHost.ShardAllocated")
         .with_handler::<ShardHost, ShardReallocating>("ShardHost.ShardReallocating")
         .with_handler::<ShardHost, StopShard>("ShardHost.StopShard")
+        .with_handler::<ShardHost, LocateShard>("ShardHost.LocateShard")
+        .with_handler::<ShardHost, EntitySnapshots>("ShardHost.EntitySnapshots")
         .with_handler::<Shard, RemoteEntityRequest>("Shard.RemoteEntityRequest")
         .with_handler::<Shard, GetShardStats>("Shard.GetShardStats")
 }
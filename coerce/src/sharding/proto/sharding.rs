@@ -1160,6 +1160,8 @@ pub struct RemoteEntityRequest {
     pub recipe: ::protobuf::MessageField<remote_entity_request::Recipe>,
     // @@protoc_insertion_point(field:coerce.sharding.RemoteEntityRequest.origin_node)
     pub origin_node: u64,
+    // @@protoc_insertion_point(field:coerce.sharding.RemoteEntityRequest.message_version)
+    pub message_version: u32,
     // special fields
     // @@protoc_insertion_point(special_field:coerce.sharding.RemoteEntityRequest.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -1177,7 +1179,7 @@ impl RemoteEntityRequest {
     }
 
     fn generated_message_descriptor_data() -> ::protobuf::reflect::GeneratedMessageDescriptorData {
-        let mut fields = ::std::vec::Vec::with_capacity(6);
+        let mut fields = ::std::vec::Vec::with_capacity(7);
         let mut oneofs = ::std::vec::Vec::with_capacity(0);
         fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
             "request_id",
@@ -1209,6 +1211,11 @@ impl RemoteEntityRequest {
             |m: &RemoteEntityRequest| { &m.origin_node },
             |m: &mut RemoteEntityRequest| { &mut m.origin_node },
         ));
+        fields.push(::protobuf::reflect::rt::v2::make_simpler_field_accessor::<_, _>(
+            "message_version",
+            |m: &RemoteEntityRequest| { &m.message_version },
+            |m: &mut RemoteEntityRequest| { &mut m.message_version },
+        ));
         ::protobuf::reflect::GeneratedMessageDescriptorData::new_2::<RemoteEntityRequest>(
             "RemoteEntityRequest",
             fields,
@@ -1245,6 +1252,9 @@ impl ::protobuf::Message for RemoteEntityRequest {
                 48 => {
                     self.origin_node = is.read_uint64()?;
                 },
+                56 => {
+                    self.message_version = is.read_uint32()?;
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -1276,6 +1286,9 @@ impl ::protobuf::Message for RemoteEntityRequest {
         if self.origin_node != 0 {
             my_size += ::protobuf::rt::uint64_size(6, self.origin_node);
         }
+        if self.message_version != 0 {
+            my_size += ::protobuf::rt::uint32_size(7, self.message_version);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -1300,6 +1313,9 @@ impl ::protobuf::Message for RemoteEntityRequest {
         if self.origin_node != 0 {
             os.write_uint64(6, self.origin_node)?;
         }
+        if self.message_version != 0 {
+            os.write_uint32(7, self.message_version)?;
+        }
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -1323,6 +1339,7 @@ impl ::protobuf::Message for RemoteEntityRequest {
         self.message.clear();
         self.recipe.clear();
         self.origin_node = 0;
+        self.message_version = 0;
         self.special_fields.clear();
     }
 
@@ -1334,6 +1351,7 @@ impl ::protobuf::Message for RemoteEntityRequest {
             message: ::std::vec::Vec::new(),
             recipe: ::protobuf::MessageField::none(),
             origin_node: 0,
+            message_version: 0,
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
@@ -3026,33 +3044,34 @@ static file_descriptor_proto_data: &'static [u8] = b"\
     g.AllocateShardResult.AllocateShardErrR\x03err\"H\n\x04Type\x12\r\n\tALL\
     OCATED\x10\0\x12\x15\n\x11ALREADY_ALLOCATED\x10\x01\x12\x11\n\rNOT_ALLOC\
     ATED\x10\x02\x12\x07\n\x03ERR\x10\x03\"0\n\x10AllocateShardErr\x12\x0b\n\
-    \x07UNKNOWN\x10\0\x12\x0f\n\x0bPERSISTENCE\x10\x01\"\x94\x02\n\x13Remote\
+    \x07UNKNOWN\x10\0\x12\x0f\n\x0bPERSISTENCE\x10\x01\"\xbd\x02\n\x13Remote\
     EntityRequest\x12\x1d\n\nrequest_id\x18\x01\x20\x01(\tR\trequestId\x12\
     \x19\n\x08actor_id\x18\x02\x20\x01(\tR\x07actorId\x12!\n\x0cmessage_type\
     \x18\x03\x20\x01(\tR\x0bmessageType\x12\x18\n\x07message\x18\x04\x20\x01\
     (\x0cR\x07message\x12C\n\x06recipe\x18\x05\x20\x01(\x0b2+.coerce.shardin\
     g.RemoteEntityRequest.RecipeR\x06recipe\x12\x1f\n\x0borigin_node\x18\x06\
     \x20\x01(\x04R\noriginNode\x1a\x20\n\x06Recipe\x12\x16\n\x06recipe\x18\
-    \x01\x20\x01(\x0cR\x06recipe\"@\n\x0bStartEntity\x12\x19\n\x08actor_id\
-    \x18\x01\x20\x01(\tR\x07actorId\x12\x16\n\x06recipe\x18\x02\x20\x01(\x0c\
-    R\x06recipe\",\n\x0fPassivateEntity\x12\x19\n\x08actor_id\x18\x01\x20\
-    \x01(\tR\x07actorId\")\n\x0cRemoveEntity\x12\x19\n\x08actor_id\x18\x01\
-    \x20\x01(\tR\x07actorId\"\x81\x02\n\x12ShardStateSnapshot\x12\x19\n\x08s\
-    hard_id\x18\x01\x20\x01(\rR\x07shardId\x12\x17\n\x07node_id\x18\x02\x20\
-    \x01(\x04R\x06nodeId\x12F\n\x08entities\x18\x03\x20\x03(\x0b2*.coerce.sh\
-    arding.ShardStateSnapshot.EntityR\x08entities\x1ao\n\x06Entity\x12\x19\n\
-    \x08actor_id\x18\x01\x20\x01(\tR\x07actorId\x12\x16\n\x06recipe\x18\x02\
-    \x20\x01(\x0cR\x06recipe\x122\n\x05state\x18\x03\x20\x01(\x0e2\x1c.coerc\
-    e.sharding.EntityStateR\x05state\"\x12\n\x10GetShardingStats\"\x7f\n\tNo\
-    deStats\x12\x17\n\x07node_id\x18\x01\x20\x01(\x04R\x06nodeId\x12\x1f\n\
-    \x0bshard_count\x18\x02\x20\x01(\x04R\nshardCount\x128\n\x06status\x18\
-    \x03\x20\x01(\x0e2\x20.coerce.sharding.ShardHostStatusR\x06status\"\xbb\
-    \x01\n\rShardingStats\x12\x1f\n\x0bentity_type\x18\x01\x20\x01(\tR\nenti\
-    tyType\x12!\n\x0ctotal_shards\x18\x02\x20\x01(\x04R\x0btotalShards\x124\
-    \n\x06shards\x18\x03\x20\x03(\x0b2\x1c.coerce.sharding.RemoteShardR\x06s\
-    hards\x120\n\x05nodes\x18\x04\x20\x03(\x0b2\x1a.coerce.sharding.NodeStat\
-    sR\x05nodes\"\x0f\n\rGetShardStats\"\\\n\nShardStats\x12\x19\n\x08shard_\
-    id\x18\x01\x20\x01(\rR\x07shardId\x12\x17\n\x07node_id\x18\x02\x20\x01(\
+    \x01\x20\x01(\x0cR\x06recipe\x12'\n\x0fmessage_version\x18\x07\x20\x01(\
+    \rR\x0emessageVersion\"@\n\x0bStartEntity\x12\x19\n\x08actor_id\x18\x01\
+    \x20\x01(\tR\x07actorId\x12\x16\n\x06recipe\x18\x02\x20\x01(\x0cR\x06rec\
+    ipe\",\n\x0fPassivateEntity\x12\x19\n\x08actor_id\x18\x01\x20\x01(\tR\
+    \x07actorId\")\n\x0cRemoveEntity\x12\x19\n\x08actor_id\x18\x01\x20\x01(\
+    \tR\x07actorId\"\x81\x02\n\x12ShardStateSnapshot\x12\x19\n\x08shard_id\
+    \x18\x01\x20\x01(\rR\x07shardId\x12\x17\n\x07node_id\x18\x02\x20\x01(\
+    \x04R\x06nodeId\x12F\n\x08entities\x18\x03\x20\x03(\x0b2*.coerce.shardin\
+    g.ShardStateSnapshot.EntityR\x08entities\x1ao\n\x06Entity\x12\x19\n\x08a\
+    ctor_id\x18\x01\x20\x01(\tR\x07actorId\x12\x16\n\x06recipe\x18\x02\x20\
+    \x01(\x0cR\x06recipe\x122\n\x05state\x18\x03\x20\x01(\x0e2\x1c.coerce.sh\
+    arding.EntityStateR\x05state\"\x12\n\x10GetShardingStats\"\x7f\n\tNodeSt\
+    ats\x12\x17\n\x07node_id\x18\x01\x20\x01(\x04R\x06nodeId\x12\x1f\n\x0bsh\
+    ard_count\x18\x02\x20\x01(\x04R\nshardCount\x128\n\x06status\x18\x03\x20\
+    \x01(\x0e2\x20.coerce.sharding.ShardHostStatusR\x06status\"\xbb\x01\n\rS\
+    hardingStats\x12\x1f\n\x0bentity_type\x18\x01\x20\x01(\tR\nentityType\
+    \x12!\n\x0ctotal_shards\x18\x02\x20\x01(\x04R\x0btotalShards\x124\n\x06s\
+    hards\x18\x03\x20\x03(\x0b2\x1c.coerce.sharding.RemoteShardR\x06shards\
+    \x120\n\x05nodes\x18\x04\x20\x03(\x0b2\x1a.coerce.sharding.NodeStatsR\
+    \x05nodes\"\x0f\n\rGetShardStats\"\\\n\nShardStats\x12\x19\n\x08shard_id\
+    \x18\x01\x20\x01(\rR\x07shardId\x12\x17\n\x07node_id\x18\x02\x20\x01(\
     \x04R\x06nodeId\x12\x1a\n\x08entities\x18\x03\x20\x03(\tR\x08entities*3\
     \n\x0bEntityState\x12\x08\n\x04IDLE\x10\0\x12\n\n\x06ACTIVE\x10\x01\x12\
     \x0e\n\nPASSIVATED\x10\x02*H\n\x0fShardHostStatus\x12\x0b\n\x07UNKNOWN\
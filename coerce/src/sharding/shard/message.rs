@@ -1,5 +1,6 @@
 use crate::actor::message::{Message, MessageUnwrapErr, MessageWrapErr};
 use crate::actor::{ActorId, ActorRefErr, BoxedActorRef, IntoActorId};
+use crate::sharding::host::warmup::WarmUpEntity;
 use crate::sharding::proto::sharding as proto;
 use crate::sharding::shard::RecipeRef;
 use protobuf::Message as ProtoMessage;
@@ -18,6 +19,14 @@ pub struct PassivateEntity {
     pub actor_id: ActorId,
 }
 
+/// Returns the entities hosted by the shard that are currently running
+pub struct GetActiveEntities;
+
+/// Starts the provided entities, unless they're already running
+pub struct WarmUp {
+    pub entities: Vec<WarmUpEntity>,
+}
+
 pub struct EntityStartResult {
     pub actor_id: ActorId,
     pub result: Result<BoxedActorRef, ActorRefErr>,
@@ -28,6 +37,14 @@ impl Message for EntityStartResult {
     type Result = ();
 }
 
+impl Message for WarmUp {
+    type Result = ();
+}
+
+impl Message for GetActiveEntities {
+    type Result = Vec<BoxedActorRef>;
+}
+
 impl Message for StartEntity {
     type Result = ();
 
@@ -1,5 +1,6 @@
 use crate::actor::context::ActorContext;
 use crate::actor::message::Handler;
+use crate::actor::scheduler::timer::Timer;
 use crate::actor::{Actor, ActorId, ActorRefErr, BoxedActorRef, CoreActorRef, LocalActorRef};
 
 use crate::persistent::journal::types::JournalTypes;
@@ -12,8 +13,9 @@ use crate::sharding::host::request::{EntityRequest, RemoteEntityRequest};
 
 use crate::sharding::host::{ShardHost, ShardReady};
 use crate::sharding::shard::message::{
-    EntityStartResult, PassivateEntity, RemoveEntity, StartEntity,
+    EntityStartResult, GetActiveEntities, PassivateEntity, RemoveEntity, StartEntity, WarmUp,
 };
+use crate::sharding::shard::passivation::{PassivationConfig, PassivationTimerTick};
 use crate::sharding::shard::recovery::ShardStateSnapshot;
 use chrono::{DateTime, Utc};
 use futures::SinkExt;
@@ -27,7 +29,7 @@ use tokio::sync::oneshot;
 use tokio::sync::oneshot::Sender;
 
 pub mod message;
-pub(crate) mod passivation;
+pub mod passivation;
 pub(crate) mod recovery;
 pub(crate) mod stats;
 
@@ -37,8 +39,10 @@ pub struct Shard {
     shard_id: ShardId,
     handler: BoxedActorHandler,
     persistent_entities: bool,
+    remember_entities: bool,
     recovered_snapshot: bool,
-    entity_passivation: bool,
+    passivation: Option<PassivationConfig>,
+    passivation_timer: Option<Timer>,
     entities: HashMap<ActorId, Entity>,
     host: LocalActorRef<ShardHost>,
 }
@@ -54,12 +58,27 @@ impl Shard {
             shard_id,
             handler,
             persistent_entities,
+            remember_entities: true,
             entities: HashMap::new(),
             recovered_snapshot: false,
-            entity_passivation: true,
+            passivation: None,
+            passivation_timer: None,
             host,
         }
     }
+
+    /// Whether the entities that were running when the shard last stopped are restarted once
+    /// the shard has recovered (the default), rather than waiting for them to receive a message
+    pub fn with_remember_entities(mut self, remember_entities: bool) -> Self {
+        self.remember_entities = remember_entities;
+        self
+    }
+
+    /// Passivates the shard's idle entities, see [`passivation`]
+    pub fn with_passivation(mut self, passivation: Option<PassivationConfig>) -> Self {
+        self.passivation = passivation;
+        self
+    }
 }
 
 impl Drop for Shard {
@@ -78,6 +97,7 @@ enum EntityState {
     Idle,
     Starting { request_buffer: Vec<BufferedReq> },
     Active(BoxedActorRef),
+    Passivating { request_buffer: Vec<BufferedReq> },
     Passivated,
 }
 
@@ -99,6 +119,11 @@ impl Debug for EntityState {
             EntityState::Active(actor_ref) => {
                 write!(f, "EntityState::Active(actor: {:?})", actor_ref)
             }
+            EntityState::Passivating { request_buffer } => write!(
+                f,
+                "EntityState::Passivating(request_buffer: {} messages)",
+                request_buffer.len()
+            ),
             EntityState::Passivated => write!(f, "EntityState::Passivated"),
         }
     }
@@ -111,6 +136,12 @@ impl EntityState {
         }
     }
 
+    pub fn passivating() -> EntityState {
+        EntityState::Passivating {
+            request_buffer: vec![],
+        }
+    }
+
     pub fn is_starting(&self) -> bool {
         matches!(&self, EntityState::Starting { .. })
     }
@@ -163,12 +194,23 @@ impl PersistentActor for Shard {
             self.shard_id, node_id, recovered_entities
         );
 
-        if recovered_entities > 0 {
+        if self.remember_entities {
             self.recover_entities(ctx).await;
+        } else {
+            // entities are re-created from their recorded recipe once they receive a message
+            for entity in self.entities.values_mut() {
+                if entity.state.is_starting() {
+                    entity.state = EntityState::Idle;
+                }
+            }
         }
 
-        if self.entity_passivation {
-            // TODO: Start entity passivation worker
+        if let Some(passivation) = &self.passivation {
+            self.passivation_timer = Some(Timer::start(
+                self.actor_ref(ctx),
+                passivation.check_interval,
+                PassivationTimerTick,
+            ));
         }
 
         let _ = self
@@ -176,7 +218,31 @@ impl PersistentActor for Shard {
             .notify(ShardReady(self.shard_id, self.actor_ref(ctx)));
     }
 
-    async fn on_child_stopped(&mut self, id: &ActorId, _ctx: &mut ActorContext) {}
+    async fn stopped(&mut self, _ctx: &mut ActorContext) {
+        if let Some(passivation_timer) = self.passivation_timer.take() {
+            let _ = passivation_timer.stop();
+        }
+    }
+
+    async fn on_child_stopped(&mut self, id: &ActorId, ctx: &mut ActorContext) {
+        let entity = match self.entities.get_mut(id) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        if let EntityState::Passivating { request_buffer } = &mut entity.state {
+            if request_buffer.is_empty() {
+                entity.state = EntityState::Passivated;
+            } else {
+                // the entity received requests while it was being passivated, so it's restarted
+                let request_buffer = mem::take(request_buffer);
+                let recipe = entity.recipe.clone();
+                entity.state = EntityState::Starting { request_buffer };
+
+                self.start_entity(id.clone(), recipe, ctx, false).await;
+            }
+        }
+    }
 }
 
 impl Shard {
@@ -319,6 +385,55 @@ impl Shard {
     }
 }
 
+#[async_trait]
+impl Handler<GetActiveEntities> for Shard {
+    async fn handle(
+        &mut self,
+        _message: GetActiveEntities,
+        _ctx: &mut ActorContext,
+    ) -> Vec<BoxedActorRef> {
+        self.entities
+            .values()
+            .filter_map(|entity| entity.state.get_actor_ref())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Handler<WarmUp> for Shard {
+    async fn handle(&mut self, message: WarmUp, ctx: &mut ActorContext) {
+        for entity in message.entities {
+            let is_running = self
+                .entities
+                .get(&entity.actor_id)
+                .is_some_and(|e| e.state.is_starting() || e.state.is_active());
+
+            if is_running {
+                continue;
+            }
+
+            trace!(
+                "warming up entity (id={}) of shard#{}",
+                &entity.actor_id,
+                self.shard_id
+            );
+
+            self.entities.insert(
+                entity.actor_id.clone(),
+                Entity {
+                    actor_id: entity.actor_id.clone(),
+                    recipe: entity.recipe.clone(),
+                    state: EntityState::starting(None),
+                    last_request: Utc::now(),
+                },
+            );
+
+            self.start_entity(entity.actor_id, entity.recipe, ctx, false)
+                .await;
+        }
+    }
+}
+
 #[async_trait]
 impl Handler<StartEntity> for Shard {
     async fn handle(&mut self, message: StartEntity, ctx: &mut ActorContext) {
@@ -344,13 +459,16 @@ impl Entity {
         message: Vec<u8>,
         result_channel: Option<Sender<Result<Vec<u8>, ActorRefErr>>>,
     ) {
+        self.last_request = Utc::now();
+
         match &mut self.state {
             EntityState::Passivated | EntityState::Idle => {
                 error!("request attempt for an actor that has not been marked as `Starting`");
                 result_channel.map(|c| c.send(Err(ActorRefErr::ActorUnavailable)));
             }
 
-            EntityState::Starting { request_buffer } => {
+            EntityState::Starting { request_buffer }
+            | EntityState::Passivating { request_buffer } => {
                 request_buffer.push(BufferedReq {
                     handler,
                     message,
@@ -358,7 +476,7 @@ impl Entity {
                 });
 
                 debug!(
-                    "entity(id={}) starting, request buffered (total_buffered={})",
+                    "entity(id={}) starting or passivating, request buffered (total_buffered={})",
                     &self.actor_id,
                     request_buffer.len()
                 );
@@ -381,7 +499,9 @@ impl Entity {
 impl Handler<EntityRequest> for Shard {
     async fn handle(&mut self, message: EntityRequest, ctx: &mut ActorContext) {
         let system = ctx.system().remote();
-        let handler = system.config().message_handler(&message.message_type);
+        let handler = system
+            .config()
+            .versioned_message_handler(&message.message_type, message.message_version);
 
         let actor_id = message.actor_id;
         let result_channel = message.result_channel;
@@ -424,7 +544,20 @@ impl Handler<EntityRequest> for Shard {
         match entity {
             Entry::Occupied(entity) => {
                 let entity = entity.into_mut();
-                entity.request(handler, message, result_channel);
+                if matches!(entity.state, EntityState::Passivated | EntityState::Idle) {
+                    // the entity isn't running, so it's re-created to handle the request
+                    let recipe = entity.recipe.clone();
+                    entity.last_request = Utc::now();
+                    entity.state = EntityState::starting(Some(BufferedReq {
+                        handler,
+                        message,
+                        result_channel,
+                    }));
+
+                    self.start_entity(actor_id, recipe, ctx, false).await;
+                } else {
+                    entity.request(handler, message, result_channel);
+                }
             }
 
             Entry::Vacant(entry) => {
@@ -1,36 +1,49 @@
+//! Entity passivation, see [`ShardingBuilder::with_passivation`]
+//!
+//! Each shard records when each of its entities last received a message, and every
+//! [`PassivationConfig::check_interval`], stops the entities that haven't received a message
+//! for at least [`PassivationConfig::idle_timeout`], freeing the memory they use.
+//!
+//! Messages sent to an entity while it's being stopped are buffered, and delivered once the entity
+//! has been re-created. Messages sent to a passivated entity re-create it, if the message is
+//! sent with a recipe. Persistent entities are recovered from storage when they're re-created,
+//! as usual.
+//!
+//! [`ShardingBuilder::with_passivation`]: crate::sharding::builder::ShardingBuilder::with_passivation
+
 use crate::actor::context::ActorContext;
 use crate::actor::message::{Handler, Message};
-use crate::actor::scheduler::timer::{Timer, TimerTick};
-use crate::actor::{Actor, LocalActorRef};
-use crate::sharding::shard::Shard;
-
+use crate::actor::scheduler::timer::TimerTick;
+use crate::actor::{ActorId, CoreActorRef};
+use crate::persistent::PersistentActor;
+use crate::sharding::shard::message::PassivateEntity;
+use crate::sharding::shard::{EntityState, Shard};
+use chrono::Utc;
+use std::mem;
 use std::time::Duration;
 
+#[derive(Debug, Copy, Clone)]
 pub struct PassivationConfig {
-    entity_passivation_tick: Duration,
-    entity_passivation_timeout: Duration,
-    entity_deletion_timeout: Option<Duration>,
-}
+    /// How long an entity can go without receiving a message before it's passivated
+    pub idle_timeout: Duration,
 
-pub struct PassivationWorker {
-    shard: LocalActorRef<Shard>,
-    config: PassivationConfig,
-    timer: Option<Timer>,
+    /// How often each shard checks for idle entities
+    pub check_interval: Duration,
 }
 
-impl PassivationWorker {
-    pub fn new(shard: LocalActorRef<Shard>, config: PassivationConfig) -> Self {
-        let timer = None;
-        PassivationWorker {
-            shard,
-            config,
-            timer,
+impl PassivationConfig {
+    /// Passivates entities that haven't received a message for `idle_timeout`,
+    /// checking for idle entities twice per `idle_timeout`
+    pub fn new(idle_timeout: Duration) -> Self {
+        PassivationConfig {
+            idle_timeout,
+            check_interval: idle_timeout / 2,
         }
     }
 }
 
 #[derive(Clone)]
-struct PassivationTimerTick;
+pub(crate) struct PassivationTimerTick;
 
 impl Message for PassivationTimerTick {
     type Result = ();
@@ -39,25 +52,60 @@ impl Message for PassivationTimerTick {
 impl TimerTick for PassivationTimerTick {}
 
 #[async_trait]
-impl Actor for PassivationWorker {
-    async fn started(&mut self, ctx: &mut ActorContext) {
-        self.timer = Some(Timer::start(
-            self.actor_ref(ctx),
-            self.config.entity_passivation_tick,
-            PassivationTimerTick,
-        ));
-    }
+impl Handler<PassivationTimerTick> for Shard {
+    async fn handle(&mut self, _message: PassivationTimerTick, ctx: &mut ActorContext) {
+        let idle_timeout = match &self.passivation {
+            Some(passivation) => passivation.idle_timeout,
+            None => return,
+        };
 
-    async fn stopped(&mut self, _ctx: &mut ActorContext) {
-        if let Some(entity_passivation_timer) = self.timer.take() {
-            let _ = entity_passivation_timer.stop();
+        let now = Utc::now();
+        let idle_entities: Vec<ActorId> = self
+            .entities
+            .values()
+            .filter(|entity| entity.state.is_active())
+            .filter(|entity| {
+                now.signed_duration_since(entity.last_request)
+                    .to_std()
+                    .is_ok_and(|idle| idle >= idle_timeout)
+            })
+            .map(|entity| entity.actor_id.clone())
+            .collect();
+
+        for actor_id in idle_entities {
+            self.passivate_entity(actor_id, ctx).await;
         }
     }
 }
-#[async_trait]
-impl Handler<PassivationTimerTick> for PassivationWorker {
-    async fn handle(&mut self, _message: PassivationTimerTick, _ctx: &mut ActorContext) {
-        // Still not sure if we should let entities themselves handle their own passivation
-        // or if we have some sort of shard-level passivation timer here..
+
+impl Shard {
+    async fn passivate_entity(&mut self, actor_id: ActorId, ctx: &mut ActorContext) {
+        let entity = match self.entities.get_mut(&actor_id) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        let state = mem::replace(&mut entity.state, EntityState::passivating());
+        let actor_ref = match state.get_actor_ref() {
+            Some(actor_ref) => actor_ref,
+            None => {
+                entity.state = state;
+                return;
+            }
+        };
+
+        debug!(
+            "passivating idle entity (id={}) of shard#{}",
+            &actor_id, self.shard_id
+        );
+
+        // the entity is marked as passivated once it has stopped, see `Shard::on_child_stopped`
+        if actor_ref.notify_stop().is_err() {
+            entity.state = EntityState::Passivated;
+        }
+
+        if self.persistent_entities {
+            let _ = self.persist(&PassivateEntity { actor_id }, ctx).await;
+        }
     }
 }
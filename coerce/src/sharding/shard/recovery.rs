@@ -109,7 +109,9 @@ impl Snapshot for ShardStateSnapshot {
                         EntityState::Active(_)
                         | EntityState::Idle
                         | EntityState::Starting { .. } => proto::EntityState::IDLE,
-                        EntityState::Passivated => proto::EntityState::PASSIVATED,
+                        EntityState::Passivating { .. } | EntityState::Passivated => {
+                            proto::EntityState::PASSIVATED
+                        }
                     }
                     .into(),
                     ..Default::default()
@@ -7,7 +7,7 @@ use crate::actor::message::{
     FromBytes, Handler, Message, MessageUnwrapErr, MessageWrapErr, ToBytes,
 };
 use crate::actor::{Actor, ActorFactory, ActorId, ActorRef, IntoActor, LocalActorRef, ToActorId};
-use crate::remote::cluster::node::NodeSelector;
+use crate::remote::cluster::node::{NodeSelector, NodeStatus, RemoteNodeState};
 use crate::remote::stream::pubsub::{PubSub, Receive, Subscription};
 use crate::remote::stream::system::{ClusterEvent, ClusterMemberUp, SystemEvent, SystemTopic};
 use crate::remote::system::{NodeId, RemoteActorSystem};
@@ -222,7 +222,49 @@ impl<F: SingletonFactory> Manager<F> {
     }
 
     pub async fn on_leader_changed(&mut self, new_leader_id: NodeId, ctx: &ActorContext) {
-        if new_leader_id == self.node_id && !self.state.is_running() {
+        if self.designated_node(Some(new_leader_id)).await == Some(self.node_id)
+            && !self.state.is_running()
+        {
+            self.begin_starting(ctx).await;
+        }
+    }
+
+    /// Returns the node the singleton should be running on, the cluster leader,
+    /// or the oldest node selected by the manager's [`NodeSelector`] that isn't in maintenance
+    async fn designated_node(&self, leader_id: Option<NodeId>) -> Option<NodeId> {
+        if let NodeSelector::All = &self.selector {
+            return leader_id;
+        }
+
+        // only nodes with a known manager are considered, nodes are removed from `managers`
+        // as soon as they leave the cluster, before their status is updated
+        let mut nodes: Vec<RemoteNodeState> = self
+            .sys
+            .get_nodes()
+            .await
+            .into_iter()
+            .filter(|node| node.id == self.node_id || self.managers.contains_key(&node.id))
+            .filter(|node| matches!(node.status, NodeStatus::Healthy | NodeStatus::Joining))
+            .filter(|node| !node.maintenance)
+            .filter(|node| self.selector.includes(&node.clone().into()))
+            .collect();
+
+        nodes.sort_by(RemoteNodeState::cmp_age);
+        nodes.first().map(|node| node.id)
+    }
+
+    /// When the singleton is restricted to a subset of nodes, the designated node can change
+    /// as nodes join or leave the cluster, without the cluster leader changing
+    async fn on_members_changed(&mut self, ctx: &ActorContext) {
+        if let NodeSelector::All = &self.selector {
+            return;
+        }
+
+        if self.state.is_joining() || self.state.is_running() {
+            return;
+        }
+
+        if self.designated_node(self.sys.current_leader()).await == Some(self.node_id) {
             self.begin_starting(ctx).await;
         }
     }
@@ -288,7 +330,7 @@ impl<F: SingletonFactory> Handler<Receive<SystemTopic>> for Manager<F> {
                     );
 
                     for node in nodes {
-                        if node.id == self.node_id || !self.selector.includes(node.as_ref()) {
+                        if node.id == self.node_id {
                             continue;
                         }
 
@@ -319,7 +361,7 @@ impl<F: SingletonFactory> Handler<Receive<SystemTopic>> for Manager<F> {
                         _ => {}
                     }
 
-                    if leader == &self.node_id {
+                    if self.designated_node(Some(*leader)).await == Some(self.node_id) {
                         self.begin_starting(ctx).await;
                     }
                 }
@@ -333,7 +375,7 @@ impl<F: SingletonFactory> Handler<Receive<SystemTopic>> for Manager<F> {
                 ClusterEvent::NodeAdded(node) => {
                     debug!(node_id = node.id, "node added");
 
-                    if node.id != self.node_id && self.selector.includes(node.as_ref()) {
+                    if node.id != self.node_id {
                         let mut entry = self.managers.entry(node.id);
                         if let Entry::Vacant(mut entry) = entry {
                             let remote_ref: ActorRef<Manager<F>> = RemoteActorRef::new(
@@ -365,9 +407,11 @@ impl<F: SingletonFactory> Handler<Receive<SystemTopic>> for Manager<F> {
                             entry.insert(remote_ref);
                         }
                     }
+
+                    self.on_members_changed(ctx).await;
                 }
 
-                ClusterEvent::NodeRemoved(node) => {
+                ClusterEvent::NodeRemoved(node) | ClusterEvent::NodeQuarantined(node) => {
                     self.managers.remove(&node.id);
 
                     debug!(node_id = node.id, "node removed");
@@ -380,6 +424,8 @@ impl<F: SingletonFactory> Handler<Receive<SystemTopic>> for Manager<F> {
                             }
                         }
                     }
+
+                    self.on_members_changed(ctx).await;
                 }
 
                 _ => {}
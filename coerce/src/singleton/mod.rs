@@ -17,6 +17,9 @@ pub mod manager;
 pub mod proto;
 pub mod proxy;
 
+#[cfg(feature = "scheduler")]
+pub mod scheduler;
+
 pub struct Singleton<A: Actor, F: SingletonFactory<Actor = A>> {
     manager: LocalActorRef<Manager<F>>,
     proxy: LocalActorRef<Proxy<A>>,
@@ -50,6 +53,17 @@ impl<F: SingletonFactory> SingletonBuilder<F> {
         self
     }
 
+    /// Only starts the singleton on nodes with the provided role (see [`ClusterWorkerBuilder::with_roles`]),
+    /// the singleton is started on the oldest node with the role, rather than the cluster leader.
+    ///
+    /// Every node should still build the singleton, so messages can be sent to it via the proxy.
+    ///
+    /// [`ClusterWorkerBuilder::with_roles`]: crate::remote::cluster::builder::worker::ClusterWorkerBuilder::with_roles
+    pub fn with_role<R: ToString>(mut self, role: R) -> Self {
+        self.node_selector = NodeSelector::role(&role.to_string());
+        self
+    }
+
     pub async fn build(mut self) -> Singleton<F::Actor, F> {
         let factory = self.factory.expect("factory");
 
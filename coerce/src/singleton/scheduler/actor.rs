@@ -0,0 +1,286 @@
+use crate::actor::context::ActorContext;
+use crate::actor::message::{Handler, Message};
+use crate::actor::{Actor, ScheduledNotify};
+use crate::persistent::journal::storage::JournalStorageRef;
+use crate::remote::system::RemoteActorSystem;
+use crate::singleton::factory::SingletonFactory;
+use crate::singleton::scheduler::history::{JobExecution, JobHistory, JobOutcome};
+use crate::singleton::scheduler::{JobContext, MissedFirePolicy, ScheduledJob};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Creates the [`JobScheduler`] singleton, the same jobs must be configured on every node
+pub struct JobSchedulerFactory {
+    pub(crate) jobs: Arc<Vec<ScheduledJob>>,
+    pub(crate) storage: JournalStorageRef,
+    pub(crate) history_limit: usize,
+    pub(crate) system: RemoteActorSystem,
+}
+
+impl SingletonFactory for JobSchedulerFactory {
+    type Actor = JobScheduler;
+
+    fn create(&self) -> JobScheduler {
+        JobScheduler {
+            jobs: self
+                .jobs
+                .iter()
+                .map(|job| {
+                    let state = JobState {
+                        job: job.clone(),
+                        history: JobHistory::new(&job.name, self.storage.clone()),
+                        cursor: None,
+                        running: false,
+                        timer: None,
+                    };
+
+                    (job.name.clone(), state)
+                })
+                .collect(),
+            history_limit: self.history_limit,
+            system: self.system.clone(),
+        }
+    }
+}
+
+struct JobState {
+    job: ScheduledJob,
+    history: JobHistory,
+
+    /// The most recent tick that has either been fired or skipped
+    cursor: Option<DateTime<Utc>>,
+    running: bool,
+    timer: Option<ScheduledNotify<JobScheduler, FireJob>>,
+}
+
+/// Fires scheduled jobs, runs as a cluster singleton so each tick is only fired on one node.
+///
+/// Ticks that pass while the job is still running, or while no node is running the scheduler,
+/// are missed and handled by the job's [`MissedFirePolicy`].
+pub struct JobScheduler {
+    jobs: HashMap<String, JobState>,
+    history_limit: usize,
+    system: RemoteActorSystem,
+}
+
+impl JobScheduler {
+    async fn schedule(&mut self, name: &str, ctx: &ActorContext) {
+        let actor_ref = self.actor_ref(ctx);
+        loop {
+            let state = match self.jobs.get_mut(name) {
+                Some(state) if !state.running => state,
+                _ => return,
+            };
+
+            let now = Utc::now();
+            if let Some(tick) = missed_tick(state, now) {
+                if self.fire(name, tick, ctx).await {
+                    return;
+                }
+
+                continue;
+            }
+
+            if let Some(next_tick) = state.job.schedule.after(&now).next() {
+                let delay = (next_tick - now).to_std().unwrap_or_default();
+                let fire = FireJob {
+                    job: name.to_string(),
+                    tick: next_tick,
+                };
+
+                if let Some(timer) = state.timer.take() {
+                    timer.cancel();
+                }
+
+                state.timer = Some(actor_ref.scheduled_notify(fire, delay));
+            }
+
+            return;
+        }
+    }
+
+    /// Claims the tick and runs the job, returns false if the tick couldn't be claimed
+    async fn fire(&mut self, name: &str, tick: DateTime<Utc>, ctx: &ActorContext) -> bool {
+        let node_id = self.system.node_id();
+        let state = match self.jobs.get_mut(name) {
+            Some(state) => state,
+            None => return false,
+        };
+
+        state.cursor = Some(tick);
+
+        let execution = JobExecution {
+            job: name.to_string(),
+            scheduled_at: tick,
+            started_at: Utc::now(),
+            node_id,
+            outcome: None,
+        };
+
+        // the tick is claimed before the job runs, so it can't be fired again, even if
+        // this node fails while the job is running
+        if let Err(e) = state.history.record(&execution).await {
+            error!(
+                job = name,
+                tick = tick.to_rfc3339(),
+                "failed to record job execution, tick skipped, error={}",
+                e
+            );
+
+            return false;
+        }
+
+        debug!(job = name, tick = tick.to_rfc3339(), "firing scheduled job");
+
+        state.running = true;
+
+        let job = state.job.job.clone();
+        let job_ctx = JobContext {
+            job: name.to_string(),
+            scheduled_at: tick,
+            system: self.system.clone(),
+        };
+
+        let actor_ref = self.actor_ref(ctx);
+        tokio::spawn(async move {
+            let outcome = match job.run(&job_ctx).await {
+                Ok(_) => JobOutcome::Succeeded,
+                Err(e) => JobOutcome::Failed(e.to_string()),
+            };
+
+            let _ = actor_ref.notify(JobFinished {
+                execution: JobExecution {
+                    outcome: Some(outcome),
+                    ..execution
+                },
+            });
+        });
+
+        true
+    }
+}
+
+/// Returns the missed tick that should be fired now, if any, advancing the cursor
+/// past any ticks that the policy skips
+fn missed_tick(state: &mut JobState, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let cursor = state.cursor?;
+    let mut missed = state
+        .job
+        .schedule
+        .after(&cursor)
+        .take_while(|tick| tick <= &now);
+
+    match state.job.missed_fire_policy {
+        MissedFirePolicy::Skip => {
+            if let Some(last_missed) = missed.last() {
+                state.cursor = Some(last_missed);
+            }
+
+            None
+        }
+        MissedFirePolicy::FireOnce => missed.last(),
+        MissedFirePolicy::FireAll => missed.next(),
+    }
+}
+
+#[async_trait]
+impl Actor for JobScheduler {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        let names: Vec<String> = self.jobs.keys().cloned().collect();
+        for name in &names {
+            let state = self.jobs.get_mut(name).unwrap();
+            match state.history.recover().await {
+                Ok(last_tick) => state.cursor = last_tick,
+                Err(e) => {
+                    error!(
+                        job = name.as_str(),
+                        "failed to recover job history, error={}", e
+                    );
+                }
+            }
+
+            self.schedule(name, ctx).await;
+        }
+
+        info!(jobs = names.len(), "job scheduler started");
+    }
+
+    async fn stopped(&mut self, _ctx: &mut ActorContext) {
+        for state in self.jobs.values_mut() {
+            if let Some(timer) = state.timer.take() {
+                timer.cancel();
+            }
+        }
+    }
+}
+
+pub(crate) struct FireJob {
+    job: String,
+    tick: DateTime<Utc>,
+}
+
+pub(crate) struct JobFinished {
+    execution: JobExecution,
+}
+
+impl Message for FireJob {
+    type Result = ();
+}
+
+impl Message for JobFinished {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<FireJob> for JobScheduler {
+    async fn handle(&mut self, message: FireJob, ctx: &mut ActorContext) {
+        let fire = match self.jobs.get_mut(&message.job) {
+            Some(state) => {
+                state.timer = None;
+                !state.running && state.cursor.is_none_or(|cursor| message.tick > cursor)
+            }
+            None => false,
+        };
+
+        if fire && !self.fire(&message.job, message.tick, ctx).await {
+            self.schedule(&message.job, ctx).await;
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<JobFinished> for JobScheduler {
+    async fn handle(&mut self, message: JobFinished, ctx: &mut ActorContext) {
+        let execution = message.execution;
+        let state = match self.jobs.get_mut(&execution.job) {
+            Some(state) => state,
+            None => return,
+        };
+
+        state.running = false;
+
+        if let Some(JobOutcome::Failed(e)) = &execution.outcome {
+            warn!(
+                job = execution.job.as_str(),
+                tick = execution.scheduled_at.to_rfc3339(),
+                "scheduled job failed, error={}",
+                e
+            );
+        }
+
+        if let Err(e) = state.history.record(&execution).await {
+            error!(
+                job = execution.job.as_str(),
+                "failed to record job outcome, error={}", e
+            );
+        } else if let Err(e) = state.history.truncate(self.history_limit).await {
+            warn!(
+                job = execution.job.as_str(),
+                "failed to truncate job history, error={}", e
+            );
+        }
+
+        self.schedule(&execution.job, ctx).await;
+    }
+}
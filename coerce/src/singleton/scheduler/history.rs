@@ -0,0 +1,119 @@
+use crate::persistent::journal::metadata::EventMetadata;
+use crate::persistent::journal::storage::{JournalEntry, JournalStorageRef};
+use crate::remote::system::NodeId;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+const JOB_EXECUTION_PAYLOAD_TYPE: &str = "coerce.JobExecution";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum JobOutcome {
+    Succeeded,
+    Failed(String),
+}
+
+/// A single execution of a scheduled job, identified by the tick it was scheduled for
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JobExecution {
+    pub job: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub started_at: DateTime<Utc>,
+    pub node_id: NodeId,
+
+    /// The outcome of the execution, or `None` if the execution hasn't finished,
+    /// or the node running the job failed before the outcome was recorded
+    pub outcome: Option<JobOutcome>,
+}
+
+/// Execution history of a single job, stored in the journal of `coerce-scheduler-{job}`.
+///
+/// Each execution is recorded twice: once when the tick is claimed, before the job runs,
+/// and again once the outcome is known. A tick that has been claimed is never fired again.
+pub(crate) struct JobHistory {
+    persistence_id: String,
+    storage: JournalStorageRef,
+    last_sequence: i64,
+}
+
+impl JobHistory {
+    pub fn new(job: &str, storage: JournalStorageRef) -> Self {
+        Self {
+            persistence_id: persistence_id(job),
+            storage,
+            last_sequence: 0,
+        }
+    }
+
+    /// Loads the sequence of the most recent record, returning the last tick that was claimed
+    pub async fn recover(&mut self) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let entries = self
+            .storage
+            .read_latest_messages(&self.persistence_id, 0)
+            .await?
+            .unwrap_or_default();
+
+        self.last_sequence = entries.last().map_or(0, |e| e.sequence);
+
+        Ok(read_executions(&entries)
+            .last()
+            .map(|execution| execution.scheduled_at))
+    }
+
+    pub async fn record(&mut self, execution: &JobExecution) -> anyhow::Result<()> {
+        let sequence = self.last_sequence + 1;
+        let entry = JournalEntry {
+            sequence,
+            payload_type: JOB_EXECUTION_PAYLOAD_TYPE.into(),
+            bytes: Arc::new(serde_json::to_vec(execution)?),
+            metadata: EventMetadata::default(),
+        };
+
+        self.storage
+            .write_message(&self.persistence_id, entry)
+            .await?;
+
+        self.last_sequence = sequence;
+        Ok(())
+    }
+
+    /// Removes all but the most recent `limit` executions
+    pub async fn truncate(&self, limit: usize) -> anyhow::Result<()> {
+        let retained_records = 2 * limit as i64;
+        if self.last_sequence <= retained_records {
+            return Ok(());
+        }
+
+        self.storage
+            .delete_messages_to(
+                &self.persistence_id,
+                self.last_sequence - retained_records + 1,
+            )
+            .await
+    }
+}
+
+pub(crate) fn persistence_id(job: &str) -> String {
+    format!("coerce-scheduler-{}", job)
+}
+
+/// Reads the executions from the journal entries, ordered by the tick they were scheduled for
+pub(crate) fn read_executions(entries: &[JournalEntry]) -> Vec<JobExecution> {
+    let mut executions = BTreeMap::new();
+    for entry in entries {
+        match serde_json::from_slice::<JobExecution>(entry.bytes.as_slice()) {
+            Ok(execution) => {
+                // later records of the same tick carry the outcome
+                executions.insert(execution.scheduled_at, execution);
+            }
+            Err(e) => {
+                warn!(
+                    sequence = entry.sequence,
+                    "unable to read job execution, error={}", e
+                );
+            }
+        }
+    }
+
+    executions.into_values().collect()
+}
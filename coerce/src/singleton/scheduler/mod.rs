@@ -0,0 +1,218 @@
+//! Cluster-wide job scheduling, driven by cron expressions.
+//!
+//! Jobs are fired by a [`JobScheduler`], which runs as a cluster [`Singleton`], so each tick of
+//! a job's schedule is fired on exactly one node. Before a job runs, the tick is claimed by
+//! recording it in the job's execution history, so a tick is never fired twice, even if the
+//! scheduler moves to another node while the job is running.
+//!
+//! Execution history is written to [`JournalStorage`], which must be shared by every node in
+//! the cluster.
+//!
+//! Cron expressions include seconds, for example `0 */5 * * * *` fires every 5 minutes.
+//!
+//! ## Example
+//! ```rust,compile_fail
+//! let remote = RemoteActorSystem::builder()
+//!     .with_actor_system(system)
+//!     .configure(scheduler)
+//!     .build()
+//!     .await;
+//!
+//! let scheduler = ClusterScheduler::builder(remote.clone())
+//!     .storage(storage)
+//!     .job(ScheduledJob::new("compact-journals", "0 0 3 * * *", CompactJournals)?)
+//!     .build()
+//!     .await;
+//!
+//! let executions = scheduler.history("compact-journals").await?;
+//! ```
+//!
+//! [`JournalStorage`]: crate::persistent::journal::storage::JournalStorage
+
+use crate::persistent::journal::storage::JournalStorageRef;
+use crate::remote::system::builder::RemoteSystemConfigBuilder;
+use crate::remote::system::RemoteActorSystem;
+use crate::singleton::scheduler::actor::{JobScheduler, JobSchedulerFactory};
+use crate::singleton::scheduler::history::{persistence_id, read_executions, JobExecution};
+use crate::singleton::{singleton, Singleton, SingletonBuilder};
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use std::any::TypeId;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub mod actor;
+pub mod history;
+
+const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// A job that is run on each tick of its schedule
+#[async_trait]
+pub trait Job: 'static + Send + Sync {
+    async fn run(&self, ctx: &JobContext) -> anyhow::Result<()>;
+}
+
+pub struct JobContext {
+    pub job: String,
+
+    /// The tick of the schedule the job was fired for, which may be in the past
+    /// if the tick was missed
+    pub scheduled_at: DateTime<Utc>,
+    pub system: RemoteActorSystem,
+}
+
+/// Decides what happens to ticks that were missed, either because the job was still running,
+/// or because no node was running the scheduler at the time
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum MissedFirePolicy {
+    /// Missed ticks are never fired
+    Skip,
+
+    /// The most recent missed tick is fired, the rest are skipped
+    #[default]
+    FireOnce,
+
+    /// Every missed tick is fired, oldest first
+    FireAll,
+}
+
+#[derive(Debug)]
+pub enum SchedulerErr {
+    InvalidSchedule(cron::error::Error),
+    Storage(anyhow::Error),
+}
+
+impl Display for SchedulerErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchedulerErr::InvalidSchedule(e) => write!(f, "invalid cron expression: {}", e),
+            SchedulerErr::Storage(e) => write!(f, "job history unavailable: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SchedulerErr {}
+
+#[derive(Clone)]
+pub struct ScheduledJob {
+    pub(crate) name: String,
+    pub(crate) schedule: Schedule,
+    pub(crate) missed_fire_policy: MissedFirePolicy,
+    pub(crate) job: Arc<dyn Job>,
+}
+
+impl ScheduledJob {
+    /// Creates a job that runs on each tick of the cron expression `schedule`,
+    /// job names must be unique within the cluster
+    pub fn new(
+        name: impl ToString,
+        schedule: &str,
+        job: impl Job,
+    ) -> Result<ScheduledJob, SchedulerErr> {
+        let schedule = Schedule::from_str(schedule).map_err(SchedulerErr::InvalidSchedule)?;
+
+        Ok(ScheduledJob {
+            name: name.to_string(),
+            schedule,
+            missed_fire_policy: MissedFirePolicy::default(),
+            job: Arc::new(job),
+        })
+    }
+
+    pub fn missed_fire_policy(mut self, missed_fire_policy: MissedFirePolicy) -> Self {
+        self.missed_fire_policy = missed_fire_policy;
+        self
+    }
+}
+
+pub struct ClusterScheduler {
+    storage: JournalStorageRef,
+    _singleton: Singleton<JobScheduler, JobSchedulerFactory>,
+}
+
+pub struct ClusterSchedulerBuilder {
+    system: RemoteActorSystem,
+    storage: Option<JournalStorageRef>,
+    jobs: Vec<ScheduledJob>,
+    history_limit: usize,
+}
+
+impl ClusterScheduler {
+    pub fn builder(system: RemoteActorSystem) -> ClusterSchedulerBuilder {
+        ClusterSchedulerBuilder {
+            system,
+            storage: None,
+            jobs: vec![],
+            history_limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+
+    /// Reads the execution history of the job, oldest first
+    pub async fn history(&self, job: &str) -> Result<Vec<JobExecution>, SchedulerErr> {
+        let entries = self
+            .storage
+            .read_latest_messages(&persistence_id(job), 0)
+            .await
+            .map_err(SchedulerErr::Storage)?
+            .unwrap_or_default();
+
+        Ok(read_executions(&entries))
+    }
+}
+
+impl ClusterSchedulerBuilder {
+    /// Where execution history is stored, by default the actor system's persistence provider
+    /// is used
+    pub fn storage(mut self, storage: JournalStorageRef) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    pub fn job(mut self, job: ScheduledJob) -> Self {
+        self.jobs.push(job);
+        self
+    }
+
+    /// How many executions of each job are kept in the history
+    pub fn history_limit(mut self, history_limit: usize) -> Self {
+        self.history_limit = history_limit;
+        self
+    }
+
+    /// Starts the scheduler, which should be done as soon as the cluster worker has been started,
+    /// since the underlying [`Singleton`] is only started once cluster membership is established
+    pub async fn build(self) -> ClusterScheduler {
+        let storage = self.storage.unwrap_or_else(|| {
+            self.system
+                .actor_system()
+                .persistence()
+                .expect("scheduler storage or actor system persistence")
+                .provider(TypeId::of::<JobScheduler>())
+                .journal_storage()
+                .expect("journal storage")
+        });
+
+        let factory = JobSchedulerFactory {
+            jobs: Arc::new(self.jobs),
+            storage: storage.clone(),
+            history_limit: self.history_limit,
+            system: self.system.clone(),
+        };
+
+        let singleton = SingletonBuilder::new(self.system)
+            .factory(factory)
+            .build()
+            .await;
+
+        ClusterScheduler {
+            storage,
+            _singleton: singleton,
+        }
+    }
+}
+
+/// Registers the remote handlers required by the [`ClusterScheduler`]
+pub fn scheduler(builder: &mut RemoteSystemConfigBuilder) -> &mut RemoteSystemConfigBuilder {
+    singleton::<JobSchedulerFactory>(builder)
+}
@@ -0,0 +1,62 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::hooks::{ActorDecorator, SystemHook};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{IntoActor, IntoActorId};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::Arc;
+use std::time::Duration;
+
+use util::*;
+
+pub mod util;
+
+#[derive(Default)]
+struct Counters {
+    spawned: AtomicUsize,
+    stopped: AtomicUsize,
+    handled: AtomicUsize,
+}
+
+struct CountingHook(Arc<Counters>);
+
+struct CountingDecorator(Arc<Counters>);
+
+impl SystemHook for CountingHook {
+    fn on_actor_spawn(&self, _ctx: &ActorContext) -> Option<Box<dyn ActorDecorator>> {
+        self.0.spawned.fetch_add(1, Relaxed);
+        Some(Box::new(CountingDecorator(self.0.clone())))
+    }
+
+    fn on_actor_stop(&self, _ctx: &ActorContext) {
+        self.0.stopped.fetch_add(1, Relaxed);
+    }
+}
+
+impl ActorDecorator for CountingDecorator {
+    fn after_handle(&mut self, _: &'static str, _: Duration, _: &ActorContext) {
+        self.0.handled.fetch_add(1, Relaxed);
+    }
+}
+
+#[tokio::test]
+pub async fn test_system_hooks_decorate_actors() {
+    let counters = Arc::new(Counters::default());
+    let system = ActorSystem::builder()
+        .add_hook(CountingHook(counters.clone()))
+        .build();
+
+    let actor = TestActor::new()
+        .into_actor(Some("test-actor".into_actor_id()), &system)
+        .await
+        .unwrap();
+
+    let _ = actor.send(GetCounterRequest()).await;
+    let _ = actor.send(GetCounterRequest()).await;
+    let _ = actor.stop(false).await;
+
+    assert_eq!(counters.spawned.load(Relaxed), 1);
+    // 2x GetCounterRequest + Stop
+    assert_eq!(counters.handled.load(Relaxed), 3);
+    assert_eq!(counters.stopped.load(Relaxed), 1);
+}
@@ -1,6 +1,12 @@
 use coerce::actor::context::ActorStatus;
+use coerce::actor::dead_letter::DeadLetterReason;
+use coerce::actor::lifecycle::{GracefulStop, Stop};
 use coerce::actor::system::ActorSystem;
 use coerce::actor::ActorRefErr;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::Arc;
+use tokio::sync::oneshot;
 
 use util::*;
 
@@ -21,7 +27,7 @@ pub async fn test_actor_lifecycle_started() {
 
     let status = actor_ref.status().await;
 
-    actor_ref.stop().await.expect("actor stop");
+    actor_ref.stop(false).await.expect("actor stop");
     assert_eq!(status, Ok(ActorStatus::Started))
 }
 
@@ -33,10 +39,97 @@ pub async fn test_actor_lifecycle_stopping() {
         .unwrap();
 
     let status = actor_ref.status().await;
-    let stopping = actor_ref.stop().await;
+    let stopping = actor_ref.stop(false).await;
     let msg_send = actor_ref.status().await;
 
     assert_eq!(status, Ok(ActorStatus::Started));
     assert_eq!(stopping, Ok(()));
     assert_eq!(msg_send, Err(ActorRefErr::InvalidRef));
 }
+
+#[tokio::test]
+pub async fn test_actor_graceful_stop_drains_mailbox() {
+    let actor_ref = ActorSystem::new()
+        .new_anon_actor(TestActor::new())
+        .await
+        .unwrap();
+
+    let processed = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = oneshot::channel();
+
+    let _ = actor_ref.notify(GracefulStop(Some(tx)));
+    for _ in 0..3 {
+        let processed = processed.clone();
+        let _ = actor_ref.notify_exec(move |_| {
+            processed.fetch_add(1, Relaxed);
+        });
+    }
+
+    rx.await.expect("actor stopped");
+
+    assert_eq!(processed.load(Relaxed), 3);
+    assert_eq!(actor_ref.status().await, Err(ActorRefErr::InvalidRef));
+}
+
+#[tokio::test]
+pub async fn test_actor_immediate_stop_discards_mailbox() {
+    let system = ActorSystem::new();
+    let mut dead_letters = system.dead_letters().subscribe();
+    let actor_ref = system.new_anon_actor(TestActor::new()).await.unwrap();
+
+    let processed = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = oneshot::channel();
+
+    let _ = actor_ref.notify(Stop(Some(tx)));
+    for _ in 0..3 {
+        let processed = processed.clone();
+        let _ = actor_ref.notify_exec(move |_| {
+            processed.fetch_add(1, Relaxed);
+        });
+    }
+
+    rx.await.expect("actor stopped");
+
+    assert_eq!(processed.load(Relaxed), 0);
+    for _ in 0..3 {
+        let dead_letter = dead_letters.recv().await.unwrap();
+        assert_eq!(dead_letter.actor_id, actor_ref.actor_id().clone());
+        assert_eq!(dead_letter.reason, DeadLetterReason::ActorStopped);
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_wait_for_stop() {
+    let actor_ref = ActorSystem::new()
+        .new_anon_actor(TestActor::new())
+        .await
+        .unwrap();
+
+    assert!(!actor_ref.is_stopped());
+
+    let _ = actor_ref.notify_stop();
+    actor_ref.wait_for_stop().await;
+
+    assert!(actor_ref.is_stopped());
+    assert!(!actor_ref.is_valid());
+}
+
+#[tokio::test]
+pub async fn test_system_wait_for_idle() {
+    let system = ActorSystem::new();
+    let actors = vec![
+        system.new_anon_actor(TestActor::new()).await.unwrap(),
+        system.new_anon_actor(TestActor::new()).await.unwrap(),
+    ];
+
+    assert_eq!(system.running_actor_count(), 2);
+
+    for actor in &actors {
+        let _ = actor.notify_stop();
+    }
+
+    system.wait_for_idle().await;
+
+    assert_eq!(system.running_actor_count(), 0);
+    assert!(actors.iter().all(|a| a.is_stopped()));
+}
@@ -0,0 +1,308 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::dead_letter::DeadLetterReason;
+use coerce::actor::mailbox::{MailboxConfig, OverflowPolicy};
+use coerce::actor::message::{Handler, Message};
+use coerce::actor::scheduler::ActorType;
+use coerce::actor::system::batch::{BatchDispatchErr, BatchMessage};
+use coerce::actor::system::{ActorOptions, ActorSystem};
+use coerce::actor::{Actor, ActorRefErr, IntoActor, LocalActorRef};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+use util::*;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+struct Block(Arc<Notify>);
+
+impl Message for Block {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<Block> for TestActor {
+    async fn handle(&mut self, message: Block, _ctx: &mut ActorContext) {
+        message.0.notified().await;
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_mailbox_snapshot() {
+    let system = ActorSystem::new();
+    let actor_ref = TestActor::new()
+        .into_actor(Some("mailbox-actor"), &system)
+        .await
+        .unwrap();
+
+    let release = Arc::new(Notify::new());
+    let _ = actor_ref.notify(Block(release.clone()));
+
+    for _ in 0..3 {
+        let _ = actor_ref.notify(GetStatusRequest);
+    }
+
+    let _ = actor_ref.notify(SetStatusRequest {
+        status: TestActorStatus::Active,
+    });
+
+    // the actor may not have received the `Block` message yet, so it's left out of the assertions
+    let snapshot = system
+        .mailbox_snapshot("mailbox-actor")
+        .await
+        .expect("mailbox snapshot");
+
+    assert_eq!(snapshot.count(GetStatusRequest::type_name()), 3);
+    assert_eq!(snapshot.count(SetStatusRequest::type_name()), 1);
+
+    release.notify_one();
+    let _ = actor_ref.status().await;
+
+    assert!(actor_ref.mailbox_snapshot().is_empty());
+    assert!(system.mailbox_snapshot("unknown-actor").await.is_none());
+}
+
+#[derive(Default)]
+struct SequencedActor {
+    received: Vec<String>,
+}
+
+impl Actor for SequencedActor {}
+
+struct Record(&'static str);
+
+impl Message for Record {
+    type Result = ();
+}
+
+struct TwoPhase;
+
+impl Message for TwoPhase {
+    type Result = ();
+}
+
+struct GetReceived;
+
+impl Message for GetReceived {
+    type Result = Vec<String>;
+}
+
+#[async_trait]
+impl Handler<Record> for SequencedActor {
+    async fn handle(&mut self, message: Record, _ctx: &mut ActorContext) {
+        self.received.push(message.0.to_string());
+    }
+}
+
+#[async_trait]
+impl Handler<TwoPhase> for SequencedActor {
+    async fn handle(&mut self, _message: TwoPhase, ctx: &mut ActorContext) {
+        self.received.push("phase-1".to_string());
+
+        // `Block` and `TwoPhase` have been received, `a`, `b` and `GetReceived` are queued
+        let token = ctx.notify_after_queued::<Self, _>(Record("phase-2"));
+        assert_eq!(token.virtual_time(), 5);
+
+        // enqueued after the token was taken, so is received after the deferred message
+        let _ = ctx.actor_ref::<Self>().notify(Record("late"));
+    }
+}
+
+#[async_trait]
+impl Handler<Block> for SequencedActor {
+    async fn handle(&mut self, message: Block, _ctx: &mut ActorContext) {
+        message.0.notified().await;
+    }
+}
+
+#[async_trait]
+impl Handler<GetReceived> for SequencedActor {
+    async fn handle(&mut self, _message: GetReceived, _ctx: &mut ActorContext) -> Vec<String> {
+        self.received.clone()
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_notify_after_queued() {
+    let system = ActorSystem::new();
+    let actor_ref = SequencedActor::default()
+        .into_anon_actor(Some("sequenced-actor"), &system)
+        .await
+        .unwrap();
+
+    let release = Arc::new(Notify::new());
+    let _ = actor_ref.notify(Block(release.clone()));
+    let _ = actor_ref.notify(TwoPhase);
+    let _ = actor_ref.notify(Record("a"));
+    let _ = actor_ref.notify(Record("b"));
+    release.notify_one();
+
+    let received = actor_ref.send(GetReceived).await.unwrap();
+    assert_eq!(received, vec!["phase-1", "a", "b"]);
+
+    let received = actor_ref.send(GetReceived).await.unwrap();
+    assert_eq!(received, vec!["phase-1", "a", "b", "phase-2", "late"]);
+
+    // Block, TwoPhase, a, b, GetReceived, late, GetReceived
+    assert_eq!(actor_ref.virtual_time(), 7);
+}
+
+async fn bounded_actor(
+    system: &ActorSystem,
+    capacity: usize,
+    overflow: OverflowPolicy,
+) -> (LocalActorRef<SequencedActor>, Arc<Notify>) {
+    let opts = ActorOptions::new()
+        .actor_type(ActorType::Anonymous)
+        .mailbox(MailboxConfig::bounded(capacity, overflow));
+
+    let actor_ref = system
+        .new_actor_with_opts("bounded-actor", SequencedActor::default(), opts)
+        .await
+        .unwrap();
+
+    // blocks the actor, so further messages stay queued until released
+    let release = Arc::new(Notify::new());
+    actor_ref.notify(Block(release.clone())).unwrap();
+    wait_for_empty_mailbox(&actor_ref).await;
+
+    (actor_ref, release)
+}
+
+async fn release_and_get_received(
+    actor_ref: &LocalActorRef<SequencedActor>,
+    release: Arc<Notify>,
+) -> Vec<String> {
+    release.notify_one();
+    wait_for_empty_mailbox(actor_ref).await;
+
+    actor_ref.send(GetReceived).await.unwrap()
+}
+
+async fn wait_for_empty_mailbox(actor_ref: &LocalActorRef<SequencedActor>) {
+    while !actor_ref.mailbox_snapshot().is_empty() {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_bounded_mailbox_fail_sender() {
+    let system = ActorSystem::new();
+    let (actor_ref, release) = bounded_actor(&system, 2, OverflowPolicy::FailSender).await;
+
+    assert!(actor_ref.notify(Record("a")).is_ok());
+    assert!(actor_ref.notify(Record("b")).is_ok());
+    assert!(matches!(
+        actor_ref.notify(Record("c")),
+        Err(ActorRefErr::MailboxFull)
+    ));
+
+    assert_eq!(actor_ref.mailbox_snapshot().len(), 2);
+
+    let received = release_and_get_received(&actor_ref, release).await;
+    assert_eq!(received, vec!["a", "b"]);
+}
+
+#[tokio::test]
+pub async fn test_actor_bounded_mailbox_drop_oldest() {
+    let system = ActorSystem::new();
+    let mut dead_letters = system.dead_letters().subscribe();
+    let (actor_ref, release) = bounded_actor(&system, 2, OverflowPolicy::DropOldest).await;
+
+    for record in ["a", "b", "c"] {
+        assert!(actor_ref.notify(Record(record)).is_ok());
+    }
+
+    let dead_letter = dead_letters.recv().await.unwrap();
+    assert_eq!(dead_letter.reason, DeadLetterReason::MailboxFull);
+    assert_eq!(dead_letter.message_type, Record::type_name());
+
+    let received = release_and_get_received(&actor_ref, release).await;
+    assert_eq!(received, vec!["b", "c"]);
+}
+
+#[tokio::test]
+pub async fn test_actor_bounded_mailbox_drop_newest() {
+    let system = ActorSystem::new();
+    let (actor_ref, release) = bounded_actor(&system, 2, OverflowPolicy::DropNewest).await;
+
+    for record in ["a", "b", "c"] {
+        assert!(actor_ref.notify(Record(record)).is_ok());
+    }
+
+    let received = release_and_get_received(&actor_ref, release).await;
+    assert_eq!(received, vec!["a", "b"]);
+}
+
+#[tokio::test]
+pub async fn test_actor_bounded_mailbox_backpressure() {
+    let system = ActorSystem::new();
+    let (actor_ref, release) = bounded_actor(&system, 1, OverflowPolicy::Backpressure).await;
+
+    assert!(actor_ref.notify(Record("a")).is_ok());
+    assert!(matches!(
+        actor_ref.notify(Record("b")),
+        Err(ActorRefErr::MailboxFull)
+    ));
+
+    let sender = actor_ref.clone();
+    let send = tokio::spawn(async move { sender.send(Record("c")).await });
+
+    tokio::task::yield_now().await;
+    assert!(!send.is_finished());
+
+    release.notify_one();
+    send.await.unwrap().unwrap();
+
+    let received = actor_ref.send(GetReceived).await.unwrap();
+    assert_eq!(received, vec!["a", "c"]);
+}
+
+#[tokio::test]
+pub async fn test_actor_send_all_or_nothing() {
+    let system = ActorSystem::new();
+    let unbounded = SequencedActor::default()
+        .into_anon_actor(Some("unbounded-actor"), &system)
+        .await
+        .unwrap();
+
+    let (bounded, release) = bounded_actor(&system, 1, OverflowPolicy::FailSender).await;
+    bounded.notify(Record("queued")).unwrap();
+
+    let res = system.send_all_or_nothing(vec![
+        BatchMessage::new(&unbounded, Record("a")),
+        (bounded.clone(), Record("b")).into(),
+    ]);
+
+    match res {
+        Err(BatchDispatchErr::Rejected {
+            index,
+            actor_id,
+            error,
+        }) => {
+            assert_eq!(index, 1);
+            assert_eq!(&actor_id, bounded.actor_id());
+            assert_eq!(error, ActorRefErr::MailboxFull);
+        }
+        res => panic!("expected batch to be rejected, received {:?}", res),
+    }
+
+    let received = release_and_get_received(&bounded, release).await;
+    assert_eq!(received, vec!["queued"]);
+    assert!(unbounded.send(GetReceived).await.unwrap().is_empty());
+
+    system
+        .send_all_or_nothing(vec![
+            BatchMessage::new(&unbounded, Record("a")),
+            BatchMessage::new(&bounded, Record("b")),
+        ])
+        .unwrap();
+
+    assert_eq!(unbounded.send(GetReceived).await.unwrap(), vec!["a"]);
+    assert_eq!(
+        bounded.send(GetReceived).await.unwrap(),
+        vec!["queued", "b"]
+    );
+}
@@ -1,8 +1,12 @@
 use coerce::actor::context::ActorContext;
-use coerce::actor::message::{Envelope, EnvelopeType, Handler, Message, MessageWrapErr};
+use coerce::actor::message::{
+    Envelope, EnvelopeType, Handler, IntoMessage, Message, MessageWrapErr,
+};
+use coerce::actor::scatter_gather::{scatter_gather, ScatterGatherErr};
 use coerce::actor::system::ActorSystem;
-use coerce::actor::{Actor, IntoActor, Receiver};
+use coerce::actor::{Actor, ActorRef, ActorRefErr, IntoActor, Receiver};
 use futures::FutureExt;
+use std::time::Duration;
 use tokio::sync::mpsc::{channel, Sender};
 use util::*;
 
@@ -298,3 +302,292 @@ pub async fn test_messages_into_remote_envelope() {
         Some(MessageWrapErr::NotTransmittable)
     );
 }
+
+struct Replica {
+    value: u64,
+    delay: Duration,
+}
+
+impl Actor for Replica {}
+
+#[derive(Clone)]
+struct Read;
+
+impl Message for Read {
+    type Result = u64;
+}
+
+#[async_trait]
+impl Handler<Read> for Replica {
+    async fn handle(&mut self, _message: Read, _ctx: &mut ActorContext) -> u64 {
+        tokio::time::sleep(self.delay).await;
+        self.value
+    }
+}
+
+async fn replicas(sys: &ActorSystem, delays: &[u64]) -> Vec<ActorRef<Replica>> {
+    let mut replicas = vec![];
+    for (value, delay) in delays.iter().enumerate() {
+        let replica = Replica {
+            value: value as u64,
+            delay: Duration::from_millis(*delay),
+        }
+        .into_actor(Some(format!("replica-{}", value)), sys)
+        .await
+        .unwrap();
+
+        replicas.push(replica.into());
+    }
+
+    replicas
+}
+
+#[tokio::test]
+pub async fn test_actor_scatter_gather() {
+    let sys = ActorSystem::new();
+    let targets = replicas(&sys, &[0, 10, 5_000]).await;
+
+    let gathered = scatter_gather(&targets, Read, 2, Duration::from_secs(1))
+        .await
+        .unwrap();
+
+    let mut values: Vec<u64> = gathered.replies.iter().map(|(_, v)| *v).collect();
+    values.sort();
+
+    assert_eq!(values, vec![0, 1]);
+    assert!(gathered.failures.is_empty());
+    assert_eq!(gathered.cancelled, vec![targets[2].actor_id().clone()]);
+
+    let res = scatter_gather(&targets, Read, 3, Duration::from_millis(50)).await;
+    match res {
+        Err(ScatterGatherErr::Timeout {
+            replies, pending, ..
+        }) => {
+            assert_eq!(replies, 2);
+            assert_eq!(pending, vec![targets[2].actor_id().clone()]);
+        }
+        res => panic!("expected timeout, got {:?}", res.map(|g| g.replies)),
+    }
+
+    let res = scatter_gather(&targets, Read, 0, Duration::from_millis(50)).await;
+    assert!(matches!(
+        res,
+        Err(ScatterGatherErr::InvalidQuorum {
+            quorum: 0,
+            targets: 3
+        })
+    ));
+}
+
+#[tokio::test]
+pub async fn test_actor_scatter_gather_quorum_unreachable() {
+    let sys = ActorSystem::new();
+    let targets = replicas(&sys, &[0, 0, 0]).await;
+
+    for target in &targets[1..] {
+        target.clone().unwrap_local().stop(true).await.unwrap();
+    }
+
+    let res = scatter_gather(&targets, Read, 2, Duration::from_secs(1)).await;
+    match res {
+        Err(ScatterGatherErr::QuorumUnreachable {
+            replies, failures, ..
+        }) => {
+            assert!(replies <= 1);
+            assert_eq!(failures.len(), 2);
+            assert!(failures
+                .iter()
+                .all(|(_, e)| matches!(e, ActorRefErr::InvalidRef)));
+        }
+        res => panic!(
+            "expected quorum unreachable, got {:?}",
+            res.map(|g| g.replies)
+        ),
+    }
+}
+
+#[derive(Default)]
+struct Directory {
+    names: Vec<String>,
+}
+
+impl Actor for Directory {}
+
+struct AddName {
+    name: String,
+}
+
+impl Message for AddName {
+    type Result = usize;
+}
+
+/// Borrows the name, which is only copied when the message is sent
+struct AddNameRef<'a> {
+    name: &'a str,
+}
+
+impl<'a> IntoMessage for AddNameRef<'a> {
+    type Message = AddName;
+
+    fn into_message(self) -> AddName {
+        AddName {
+            name: self.name.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<AddName> for Directory {
+    async fn handle(&mut self, message: AddName, _ctx: &mut ActorContext) -> usize {
+        self.names.push(message.name);
+        self.names.len()
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_send_borrowed_message() {
+    let sys = ActorSystem::new();
+    let directory = Directory::default()
+        .into_actor(Some("directory"), &sys)
+        .await
+        .unwrap();
+
+    let names = "alice,bob,carol".to_string();
+    let mut names = names.split(',');
+
+    let added = directory
+        .send_into(AddNameRef {
+            name: names.next().unwrap(),
+        })
+        .await;
+
+    assert_eq!(added, Ok(1));
+
+    directory
+        .notify_into(AddNameRef {
+            name: names.next().unwrap(),
+        })
+        .unwrap();
+
+    let actor_ref = ActorRef::from(directory.clone());
+    let added = actor_ref
+        .send_into(AddNameRef {
+            name: names.next().unwrap(),
+        })
+        .await;
+
+    assert_eq!(added, Ok(3));
+
+    // owned messages can be sent the same way
+    let added = actor_ref
+        .send_into(AddName {
+            name: "dave".to_string(),
+        })
+        .await;
+
+    assert_eq!(added, Ok(4));
+
+    let names = directory.exec(|d| d.names.clone()).await.unwrap();
+    assert_eq!(names, vec!["alice", "bob", "carol", "dave"]);
+}
+
+#[derive(Default)]
+struct Account {
+    balance: u64,
+}
+
+impl Actor for Account {}
+
+#[derive(Debug, Eq, PartialEq)]
+struct InsufficientFunds;
+
+struct Deposit(u64);
+
+impl Message for Deposit {
+    type Result = u64;
+}
+
+struct Withdraw(u64);
+
+impl Message for Withdraw {
+    type Result = Result<u64, InsufficientFunds>;
+}
+
+struct Audit;
+
+impl Message for Audit {
+    type Result = u64;
+}
+
+#[async_trait]
+impl Handler<Deposit> for Account {
+    async fn handle(&mut self, message: Deposit, ctx: &mut ActorContext) -> u64 {
+        self.balance += message.0;
+
+        // the reply was already sent, so the returned value is discarded
+        ctx.reply(self.balance);
+        0
+    }
+}
+
+#[async_trait]
+impl Handler<Withdraw> for Account {
+    async fn handle(
+        &mut self,
+        message: Withdraw,
+        ctx: &mut ActorContext,
+    ) -> Result<u64, InsufficientFunds> {
+        if message.0 > self.balance {
+            ctx.reply_err::<u64, _>(InsufficientFunds);
+        } else {
+            self.balance -= message.0;
+            ctx.reply(Ok::<_, InsufficientFunds>(self.balance));
+        }
+
+        Ok(0)
+    }
+}
+
+#[async_trait]
+impl Handler<Audit> for Account {
+    async fn handle(&mut self, _message: Audit, ctx: &mut ActorContext) -> u64 {
+        // only the message's result type can be replied with
+        assert!(ctx.defer_reply::<String>().is_none());
+
+        let reply = ctx.defer_reply::<u64>().unwrap();
+        let balance = self.balance;
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            reply.reply(balance);
+        });
+
+        0
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_context_reply() {
+    let sys = ActorSystem::new();
+    let account = Account::default()
+        .into_actor(Some("account"), &sys)
+        .await
+        .unwrap();
+
+    assert_eq!(account.send(Deposit(100)).await, Ok(100));
+    assert_eq!(account.send(Withdraw(30)).await, Ok(Ok(70)));
+    assert_eq!(account.send(Withdraw(80)).await, Ok(Err(InsufficientFunds)));
+
+    // notifications have no reply channel
+    account.notify(Deposit(10)).unwrap();
+
+    // the actor handles further messages while the deferred reply is pending
+    let audit = tokio::spawn({
+        let account = account.clone();
+        async move { account.send(Audit).await }
+    });
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(account.send(Deposit(20)).await, Ok(100));
+    assert!(!audit.is_finished());
+    assert_eq!(audit.await.unwrap(), Ok(80));
+}
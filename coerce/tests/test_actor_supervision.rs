@@ -1,11 +1,13 @@
 use coerce::actor::context::ActorContext;
 use coerce::actor::describe::Describe;
 use coerce::actor::message::{Handler, Message};
+use coerce::actor::supervised::{RestartPolicy, RestartReason, RestartStorm};
 use coerce::actor::system::ActorSystem;
-use coerce::actor::{Actor, ActorId, CoreActorRef, IntoActor, IntoActorId};
+use coerce::actor::{Actor, ActorId, CoreActorRef, IntoActor, IntoActorId, LocalActorRef};
 
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
 use tokio::time::timeout;
 
 pub mod util;
@@ -64,7 +66,7 @@ impl Handler<StopAll> for TestSupervisor {
     async fn handle(&mut self, _: StopAll, ctx: &mut ActorContext) {
         if let Some(supervised) = ctx.supervised() {
             for child in &supervised.children {
-                let _ = child.1.actor_ref().stop().await;
+                let _ = child.1.actor_ref().stop(false).await;
             }
         }
     }
@@ -158,3 +160,294 @@ pub async fn test_actor_child_spawn_and_stop() {
 
     system.shutdown().await;
 }
+
+struct RestartingSupervisor {
+    on_restart_storm: Option<oneshot::Sender<RestartStorm>>,
+}
+
+struct FlakyActor;
+
+impl Actor for FlakyActor {}
+
+struct Crash;
+
+impl Message for Crash {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<Crash> for FlakyActor {
+    async fn handle(&mut self, _: Crash, _ctx: &mut ActorContext) {
+        panic!("crash requested");
+    }
+}
+
+struct GetFlakyChild;
+
+impl Message for GetFlakyChild {
+    type Result = (Option<LocalActorRef<FlakyActor>>, u64);
+}
+
+#[async_trait]
+impl Actor for RestartingSupervisor {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        let policy = RestartPolicy {
+            max_restarts: 2,
+            within: Duration::from_secs(60),
+            stop_on_storm: true,
+        };
+
+        ctx.spawn_restartable("flaky".into_actor_id(), || FlakyActor, policy)
+            .await
+            .unwrap();
+    }
+
+    async fn on_restart_storm(&mut self, storm: &RestartStorm, _ctx: &mut ActorContext) {
+        if let Some(on_restart_storm) = self.on_restart_storm.take() {
+            let _ = on_restart_storm.send(storm.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<GetFlakyChild> for RestartingSupervisor {
+    async fn handle(
+        &mut self,
+        _: GetFlakyChild,
+        ctx: &mut ActorContext,
+    ) -> (Option<LocalActorRef<FlakyActor>>, u64) {
+        let id = "flaky".into_actor_id();
+        let restarts = ctx
+            .supervised()
+            .and_then(|s| s.restart_stats(&id))
+            .map_or(0, |s| s.total_restarts());
+
+        (ctx.child_ref(&id), restarts)
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_child_restart_storm() {
+    util::create_trace_logger();
+
+    let system = ActorSystem::new();
+    let (tx, rx) = oneshot::channel();
+    let supervisor = RestartingSupervisor {
+        on_restart_storm: Some(tx),
+    }
+    .into_actor(Some("supervisor"), &system)
+    .await
+    .unwrap();
+
+    for expected_restarts in 0..3 {
+        let (child, restarts) = supervisor.send(GetFlakyChild).await.unwrap();
+        assert_eq!(restarts, expected_restarts);
+
+        let child = child.expect("child should be running");
+        let _ = child.notify(Crash);
+        child.wait_for_stop().await;
+    }
+
+    let storm = timeout(Duration::from_secs(5), rx)
+        .await
+        .expect("restart storm not raised")
+        .unwrap();
+
+    assert_eq!(storm.restarts, 3);
+    assert!(storm.stopped);
+
+    let (child, _) = supervisor.send(GetFlakyChild).await.unwrap();
+    assert!(child.is_none());
+
+    system.shutdown().await;
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum LifecycleEvent {
+    Started,
+    PreRestart {
+        message_type: String,
+        panic_message: Option<String>,
+    },
+    PostRestart,
+}
+
+struct LifecycleActor {
+    events: mpsc::UnboundedSender<LifecycleEvent>,
+}
+
+#[async_trait]
+impl Actor for LifecycleActor {
+    async fn started(&mut self, _ctx: &mut ActorContext) {
+        let _ = self.events.send(LifecycleEvent::Started);
+    }
+
+    async fn pre_restart(&mut self, reason: &RestartReason, _ctx: &mut ActorContext) {
+        if let RestartReason::Panicked {
+            message_type,
+            panic_message,
+        } = reason
+        {
+            let _ = self.events.send(LifecycleEvent::PreRestart {
+                message_type: message_type.to_string(),
+                panic_message: panic_message.clone(),
+            });
+        }
+    }
+
+    async fn post_restart(&mut self, _ctx: &mut ActorContext) {
+        let _ = self.events.send(LifecycleEvent::PostRestart);
+    }
+}
+
+#[async_trait]
+impl Handler<Crash> for LifecycleActor {
+    async fn handle(&mut self, _: Crash, _ctx: &mut ActorContext) {
+        panic!("crash requested");
+    }
+}
+
+struct LifecycleSupervisor {
+    events: mpsc::UnboundedSender<LifecycleEvent>,
+}
+
+#[async_trait]
+impl Actor for LifecycleSupervisor {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        let events = self.events.clone();
+        ctx.spawn_restartable(
+            "lifecycle".into_actor_id(),
+            move || LifecycleActor {
+                events: events.clone(),
+            },
+            RestartPolicy::default(),
+        )
+        .await
+        .unwrap();
+    }
+}
+
+struct GetLifecycleChild;
+
+impl Message for GetLifecycleChild {
+    type Result = Option<LocalActorRef<LifecycleActor>>;
+}
+
+#[async_trait]
+impl Handler<GetLifecycleChild> for LifecycleSupervisor {
+    async fn handle(
+        &mut self,
+        _: GetLifecycleChild,
+        ctx: &mut ActorContext,
+    ) -> Option<LocalActorRef<LifecycleActor>> {
+        ctx.child_ref(&"lifecycle".into_actor_id())
+    }
+}
+
+async fn next_event(
+    events: &mut mpsc::UnboundedReceiver<LifecycleEvent>,
+) -> Option<LifecycleEvent> {
+    timeout(Duration::from_secs(5), events.recv())
+        .await
+        .expect("lifecycle event not received")
+}
+
+#[tokio::test]
+pub async fn test_actor_child_restart_lifecycle_hooks() {
+    util::create_trace_logger();
+
+    let system = ActorSystem::new();
+    let (tx, mut events) = mpsc::unbounded_channel();
+    let supervisor = LifecycleSupervisor { events: tx }
+        .into_actor(Some("lifecycle-supervisor"), &system)
+        .await
+        .unwrap();
+
+    assert_eq!(events.recv().await, Some(LifecycleEvent::Started));
+
+    let child = supervisor.send(GetLifecycleChild).await.unwrap().unwrap();
+    let _ = child.notify(Crash);
+    child.wait_for_stop().await;
+
+    match next_event(&mut events).await {
+        Some(LifecycleEvent::PreRestart {
+            message_type,
+            panic_message,
+        }) => {
+            assert!(message_type.contains("Crash"));
+            assert_eq!(panic_message.as_deref(), Some("crash requested"));
+        }
+        event => panic!("expected PreRestart, received {:?}", event),
+    }
+
+    assert_eq!(next_event(&mut events).await, Some(LifecycleEvent::Started));
+    assert_eq!(
+        next_event(&mut events).await,
+        Some(LifecycleEvent::PostRestart)
+    );
+
+    let restarted = supervisor.send(GetLifecycleChild).await.unwrap().unwrap();
+    assert!(!restarted.is_stopped());
+
+    system.shutdown().await;
+}
+
+struct HierarchyActor {
+    depth: usize,
+    stopped: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Actor for HierarchyActor {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        if self.depth == 0 {
+            return;
+        }
+
+        for i in 0..2 {
+            let child = HierarchyActor {
+                depth: self.depth - 1,
+                stopped: self.stopped.clone(),
+            };
+
+            ctx.spawn_child(child, format!("{}-{}", ctx.id(), i))
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn stopped(&mut self, ctx: &mut ActorContext) {
+        assert_eq!(ctx.supervised_count(), 0);
+
+        self.stopped.lock().unwrap().push(ctx.id().to_string());
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_child_stop_propagation() {
+    util::create_trace_logger();
+
+    let system = ActorSystem::new();
+    let stopped = Arc::new(Mutex::new(vec![]));
+    let root = HierarchyActor {
+        depth: 2,
+        stopped: stopped.clone(),
+    }
+    .into_actor(Some("root"), &system)
+    .await
+    .unwrap();
+
+    root.stop(false).await.unwrap();
+
+    let stopped = stopped.lock().unwrap().clone();
+    assert_eq!(stopped.len(), 7);
+    assert_eq!(stopped.last().map(|id| id.as_str()), Some("root"));
+
+    let position = |id: &str| stopped.iter().position(|s| s == id).unwrap();
+    for child in ["root-0", "root-1"] {
+        assert!(position(&format!("{}-0", child)) < position(child));
+        assert!(position(&format!("{}-1", child)) < position(child));
+    }
+
+    system.shutdown().await;
+}
@@ -1,5 +1,8 @@
+use coerce::actor::system::shutdown::{ShutdownPhase, ShutdownReason, ShutdownSignal};
 use coerce::actor::system::ActorSystem;
 use coerce::actor::{get_actor, new_actor, new_actor_id};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use util::*;
 
@@ -79,7 +82,7 @@ pub async fn test_system_stop_tracked_actor_get_not_found() {
         })
         .await;
 
-    let _stop = actor_ref.stop().await;
+    let _stop = actor_ref.stop(false).await;
 
     let actor = ctx
         .get_tracked_actor::<TestActor>(actor_ref.actor_id().clone())
@@ -87,3 +90,81 @@ pub async fn test_system_stop_tracked_actor_get_not_found() {
 
     assert_eq!(actor.is_none(), true);
 }
+
+#[tokio::test]
+pub async fn test_system_coordinated_shutdown() {
+    create_trace_logger();
+
+    let system = ActorSystem::builder()
+        .shutdown_phase_timeout(Duration::from_millis(100))
+        .build();
+
+    let actor_ref = system.new_tracked_actor(TestActor::new()).await.unwrap();
+    let completed = Arc::new(Mutex::new(vec![]));
+
+    for (phase, name) in [
+        (ShutdownPhase::LeaveCluster, "leave"),
+        (ShutdownPhase::Drain, "drain"),
+    ] {
+        let completed = completed.clone();
+        system.add_shutdown_task(phase, name, move || async move {
+            completed.lock().unwrap().push(name);
+        });
+    }
+
+    system.add_shutdown_task(ShutdownPhase::Drain, "stuck", || async {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    });
+
+    let summary = system.coordinated_shutdown(ShutdownReason::Requested).await;
+
+    assert_eq!(*completed.lock().unwrap(), ["drain", "leave"]);
+    assert_eq!(summary.reason, ShutdownReason::Requested);
+    assert_eq!(summary.tasks.len(), 3);
+    assert_eq!(summary.actors_remaining, 0);
+    assert!(!summary.is_clean());
+
+    let stuck = summary.tasks.iter().find(|t| t.name == "stuck").unwrap();
+    assert!(stuck.timed_out);
+    assert_eq!(stuck.phase, ShutdownPhase::Drain);
+
+    assert!(actor_ref.is_stopped());
+    assert!(system.is_terminated());
+
+    // tasks are only run once
+    let summary = system.coordinated_shutdown(ShutdownReason::Requested).await;
+    assert!(summary.tasks.is_empty());
+    assert!(summary.is_clean());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+pub async fn test_system_run_until_shutdown_signal() {
+    create_trace_logger();
+
+    let system = ActorSystem::new();
+    let actor_ref = system.new_tracked_actor(TestActor::new()).await.unwrap();
+
+    let shutdown = tokio::spawn({
+        let system = system.clone();
+        async move { system.run_until_shutdown_signal().await }
+    });
+
+    // give the task a chance to install the signal handlers
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let status = std::process::Command::new("kill")
+        .args(["-TERM", &std::process::id().to_string()])
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+
+    let summary = shutdown.await.unwrap();
+    assert_eq!(
+        summary.reason,
+        ShutdownReason::Signal(ShutdownSignal::Terminate)
+    );
+    assert!(summary.is_clean());
+    assert!(actor_ref.is_stopped());
+}
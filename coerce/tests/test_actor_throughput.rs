@@ -0,0 +1,149 @@
+use coerce::actor::metrics::throughput::ThroughputReporter;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActor;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+
+use util::*;
+
+pub mod util;
+
+type Summary = HashMap<String, String>;
+
+/// Captures the fields of every throughput summary event
+#[derive(Clone, Default)]
+struct SummaryCapture(Arc<Mutex<Vec<Summary>>>);
+
+impl SummaryCapture {
+    fn summaries(&self) -> Vec<Summary> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut Summary);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SummaryCapture {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != "coerce::actor::throughput" {
+            return;
+        }
+
+        let mut summary = Summary::new();
+        event.record(&mut FieldVisitor(&mut summary));
+        self.0.lock().unwrap().push(summary);
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_throughput_summaries() {
+    let capture = SummaryCapture::default();
+    let _subscriber =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+    let system = ActorSystem::builder()
+        .add_hook(
+            ThroughputReporter::new(Duration::from_millis(200))
+                .with_filter(|ctx| ctx.id().starts_with("throughput-")),
+        )
+        .build();
+
+    let actor = TestActor::new()
+        .into_actor(Some("throughput-actor"), &system)
+        .await
+        .unwrap();
+
+    for _ in 0..5 {
+        let _ = actor.send(GetCounterRequest()).await;
+    }
+
+    // the summary is emitted once a message is handled after the interval has elapsed
+    assert!(capture.summaries().is_empty());
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let _ = actor.send(GetCounterRequest()).await;
+
+    let summaries = capture.summaries();
+    assert_eq!(summaries.len(), 1);
+
+    let summary = &summaries[0];
+    assert_eq!(summary["messages"], "6");
+    assert!(summary["actor"].ends_with("/throughput-actor"));
+    assert!(summary["actor_type"].contains("TestActor"));
+    assert!(summary.contains_key("messages_per_sec"));
+    assert!(summary.contains_key("p50_handle_time_us"));
+    assert!(summary.contains_key("p99_handle_time_us"));
+    assert!(summary.contains_key("max_handle_time_us"));
+    assert!(summary["window_ms"].parse::<u64>().unwrap() >= 200);
+
+    // the remaining summary (the `Stop` message) is emitted when the actor stops
+    let _ = actor.stop(false).await;
+
+    let summaries = capture.summaries();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[1]["messages"], "1");
+
+    // actors excluded by the filter aren't reported
+    let other = TestActor::new()
+        .into_actor(Some("other-actor"), &system)
+        .await
+        .unwrap();
+
+    let _ = other.send(GetCounterRequest()).await;
+    let _ = other.stop(false).await;
+
+    assert_eq!(capture.summaries().len(), 2);
+}
+
+#[tokio::test]
+pub async fn test_actor_throughput_min_messages() {
+    let capture = SummaryCapture::default();
+    let _subscriber =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+    let system = ActorSystem::builder()
+        .add_hook(
+            ThroughputReporter::new(Duration::from_secs(60))
+                .min_messages(10)
+                .with_filter(|ctx| ctx.id().starts_with("throughput-")),
+        )
+        .build();
+
+    let quiet = TestActor::new()
+        .into_actor(Some("throughput-quiet"), &system)
+        .await
+        .unwrap();
+
+    let busy = TestActor::new()
+        .into_actor(Some("throughput-busy"), &system)
+        .await
+        .unwrap();
+
+    let _ = quiet.send(GetCounterRequest()).await;
+    for _ in 0..10 {
+        let _ = busy.send(GetCounterRequest()).await;
+    }
+
+    let _ = quiet.stop(false).await;
+    let _ = busy.stop(false).await;
+
+    let summaries = capture.summaries();
+    assert_eq!(summaries.len(), 1);
+    assert!(summaries[0]["actor"].ends_with("/throughput-busy"));
+    assert_eq!(summaries[0]["messages"], "11");
+}
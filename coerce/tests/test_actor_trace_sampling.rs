@@ -0,0 +1,124 @@
+use coerce::actor::sampling::{SamplingRule, TraceSampling};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{IntoActor, IntoActorId};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+
+use util::*;
+
+pub mod util;
+
+/// Records the message type of every `actor.recv` span created
+#[derive(Clone, Default)]
+struct RecvSpans(Arc<Mutex<Vec<String>>>);
+
+impl RecvSpans {
+    fn count(&self, message_type: &str) -> usize {
+        let spans = self.0.lock().unwrap();
+        spans.iter().filter(|t| t.ends_with(message_type)).count()
+    }
+
+    fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+struct MessageTypeVisitor(Option<String>);
+
+impl Visit for MessageTypeVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message_type" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+impl<S: Subscriber> Layer<S> for RecvSpans {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "actor.recv" {
+            return;
+        }
+
+        let mut visitor = MessageTypeVisitor(None);
+        attrs.record(&mut visitor);
+        if let Some(message_type) = visitor.0 {
+            self.0.lock().unwrap().push(message_type);
+        }
+    }
+}
+
+#[test]
+pub fn test_trace_sampling_rules() {
+    let message_type = "test_actor_trace_sampling::util::GetCounterRequest";
+
+    assert!(TraceSampling::default().is_sampled("tenant-a/1", message_type));
+    assert!(!TraceSampling::None.is_sampled("tenant-a/1", message_type));
+    assert!(!TraceSampling::rules().is_sampled("tenant-a/1", message_type));
+
+    let sampling = TraceSampling::rules()
+        .with_rule(SamplingRule::actor_id_prefix("tenant-a/"))
+        .with_rule(SamplingRule::actor_id("tenant-b/1").and_message_type("GetCounterRequest"));
+
+    assert!(sampling.is_sampled("tenant-a/1", message_type));
+    assert!(sampling.is_sampled("tenant-b/1", message_type));
+    assert!(!sampling.is_sampled("tenant-b/1", "util::SetStatusRequest"));
+    assert!(!sampling.is_sampled("tenant-b/2", message_type));
+    assert!(!sampling.is_sampled("tenant-c/1", message_type));
+
+    let by_type = SamplingRule::message_type("GetCounterRequest");
+    assert!(by_type.matches("any", message_type));
+    assert!(by_type.matches("any", "GetCounterRequest"));
+    assert!(!by_type.matches("any", "util::NotGetCounterRequest"));
+}
+
+#[tokio::test]
+pub async fn test_trace_sampling_limits_actor_spans() {
+    let spans = RecvSpans::default();
+    let _subscriber =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(spans.clone()));
+
+    let system = ActorSystem::builder()
+        .trace_sampling(
+            TraceSampling::rules().with_rule(SamplingRule::actor_id_prefix("tenant-a/")),
+        )
+        .build();
+
+    let tenant_a = TestActor::new()
+        .into_actor(Some("tenant-a/1".into_actor_id()), &system)
+        .await
+        .unwrap();
+
+    let tenant_b = TestActor::new()
+        .into_actor(Some("tenant-b/1".into_actor_id()), &system)
+        .await
+        .unwrap();
+
+    for _ in 0..3 {
+        let _ = tenant_b.send(GetCounterRequest()).await;
+    }
+
+    assert_eq!(spans.count("GetCounterRequest"), 0);
+
+    let _ = tenant_a.send(GetCounterRequest()).await;
+    assert!(spans.count("GetCounterRequest") > 0);
+
+    // sampling can be changed while actors are running
+    spans.clear();
+    system.set_trace_sampling(TraceSampling::None);
+
+    let _ = tenant_a.send(GetCounterRequest()).await;
+    assert_eq!(spans.count("GetCounterRequest"), 0);
+
+    system.set_trace_sampling(TraceSampling::All);
+
+    let _ = tenant_b.send(GetCounterRequest()).await;
+    assert!(spans.count("GetCounterRequest") > 0);
+
+    system.shutdown().await;
+}
@@ -1,12 +1,12 @@
 use crate::util::TestActor;
 use async_trait::async_trait;
 use coerce::actor::context::ActorContext;
-use coerce::actor::message::Handler;
+use coerce::actor::message::{Handler, Message};
 use coerce::actor::system::ActorSystem;
 use coerce::actor::watch::{ActorTerminated, ActorWatch};
 use coerce::actor::{Actor, ActorId, CoreActorRef, IntoActor, LocalActorRef};
-use tokio::sync::oneshot;
 use tokio::sync::oneshot::Sender;
+use tokio::sync::{mpsc, oneshot};
 
 pub mod util;
 
@@ -49,8 +49,84 @@ pub async fn test_actor_watch_notifications() {
 
     let _ = actor.notify_stop();
     let actor_terminated = rx.await.unwrap();
-    let terminated_actor = actor_terminated.actor_ref();
+    let terminated_actor = actor_terminated.actor_ref().unwrap();
 
     assert_eq!(terminated_actor.actor_id(), actor.actor_id());
     assert_eq!(terminated_actor.is_valid(), false);
 }
+
+pub struct Monitor {
+    terminated: mpsc::UnboundedSender<ActorId>,
+}
+
+impl Actor for Monitor {}
+
+pub struct WatchTarget(LocalActorRef<TestActor>);
+
+impl Message for WatchTarget {
+    type Result = ();
+}
+
+pub struct UnwatchTarget(LocalActorRef<TestActor>);
+
+impl Message for UnwatchTarget {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<WatchTarget> for Monitor {
+    async fn handle(&mut self, message: WatchTarget, ctx: &mut ActorContext) {
+        ctx.watch::<Self>(&message.0);
+    }
+}
+
+#[async_trait]
+impl Handler<UnwatchTarget> for Monitor {
+    async fn handle(&mut self, message: UnwatchTarget, ctx: &mut ActorContext) {
+        ctx.unwatch(&message.0);
+    }
+}
+
+#[async_trait]
+impl Handler<ActorTerminated> for Monitor {
+    async fn handle(&mut self, message: ActorTerminated, _ctx: &mut ActorContext) {
+        let _ = self.terminated.send(message.actor_id().clone());
+    }
+}
+
+#[tokio::test]
+pub async fn test_actor_context_watch() {
+    let system = ActorSystem::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let monitor = Monitor { terminated: tx }
+        .into_actor(Some("monitor"), &system)
+        .await
+        .unwrap();
+
+    let watched = TestActor::new()
+        .into_actor(Some("watched"), &system)
+        .await
+        .unwrap();
+
+    let unwatched = TestActor::new()
+        .into_actor(Some("unwatched"), &system)
+        .await
+        .unwrap();
+
+    monitor.send(WatchTarget(watched.clone())).await.unwrap();
+    monitor.send(WatchTarget(unwatched.clone())).await.unwrap();
+    monitor
+        .send(UnwatchTarget(unwatched.clone()))
+        .await
+        .unwrap();
+
+    unwatched.stop(true).await.unwrap();
+    watched.stop(true).await.unwrap();
+
+    assert_eq!(rx.recv().await.unwrap(), *watched.actor_id());
+
+    // watching an actor that has already stopped is notified immediately
+    monitor.send(WatchTarget(unwatched.clone())).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), *unwatched.actor_id());
+    assert!(rx.try_recv().is_err());
+}
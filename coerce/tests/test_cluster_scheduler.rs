@@ -0,0 +1,158 @@
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::storage::JournalStorageRef;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::singleton::scheduler::history::JobOutcome;
+use coerce::singleton::scheduler::{
+    scheduler, ClusterScheduler, Job, JobContext, MissedFirePolicy, ScheduledJob,
+};
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+const EVERY_SECOND: &str = "* * * * * *";
+
+#[derive(Clone)]
+struct RecordingJob {
+    ticks: mpsc::UnboundedSender<i64>,
+    delay: Duration,
+}
+
+#[async_trait]
+impl Job for RecordingJob {
+    async fn run(&self, ctx: &JobContext) -> anyhow::Result<()> {
+        let _ = self.ticks.send(ctx.scheduled_at.timestamp());
+        tokio::time::sleep(self.delay).await;
+        Ok(())
+    }
+}
+
+async fn start_scheduler(
+    remote: &RemoteActorSystem,
+    storage: &JournalStorageRef,
+    job: ScheduledJob,
+) -> ClusterScheduler {
+    ClusterScheduler::builder(remote.clone())
+        .storage(storage.clone())
+        .job(job)
+        .build()
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_cluster_scheduler_fires_each_tick_once() {
+    util::create_trace_logger();
+
+    let storage = InMemoryStorageProvider::new().journal_storage().unwrap();
+    let (tx, mut ticks) = mpsc::unbounded_channel();
+    let job = RecordingJob {
+        ticks: tx,
+        delay: Duration::ZERO,
+    };
+
+    let remote_a = util::create_cluster_node(1, "localhost:30901", None, scheduler).await;
+    let scheduler_a = start_scheduler(
+        &remote_a,
+        &storage,
+        ScheduledJob::new("heartbeat", EVERY_SECOND, job.clone()).unwrap(),
+    )
+    .await;
+
+    let remote_b =
+        util::create_cluster_node(2, "localhost:30902", Some("localhost:30901"), scheduler).await;
+    let _scheduler_b = start_scheduler(
+        &remote_b,
+        &storage,
+        ScheduledJob::new("heartbeat", EVERY_SECOND, job).unwrap(),
+    )
+    .await;
+
+    let mut fired = vec![];
+    while fired.len() < 3 {
+        let tick = tokio::time::timeout(Duration::from_secs(5), ticks.recv())
+            .await
+            .expect("job fired")
+            .unwrap();
+
+        fired.push(tick);
+    }
+
+    let unique: HashSet<_> = fired.iter().collect();
+    assert_eq!(unique.len(), fired.len());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let history = scheduler_a.history("heartbeat").await.unwrap();
+    assert!(history.len() >= 3);
+    assert!(history
+        .iter()
+        .take(3)
+        .all(|e| e.outcome == Some(JobOutcome::Succeeded)));
+
+    assert!(ScheduledJob::new(
+        "invalid",
+        "not a cron expression",
+        RecordingJob {
+            ticks: mpsc::unbounded_channel().0,
+            delay: Duration::ZERO,
+        }
+    )
+    .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_cluster_scheduler_missed_fire_policy() {
+    util::create_trace_logger();
+
+    let storage = InMemoryStorageProvider::new().journal_storage().unwrap();
+    let remote = util::create_cluster_node(1, "localhost:30911", None, scheduler).await;
+
+    async fn fired_ticks(ticks: &mut mpsc::UnboundedReceiver<i64>, count: usize) -> Vec<i64> {
+        let mut fired = vec![];
+        while fired.len() < count {
+            let tick = tokio::time::timeout(Duration::from_secs(10), ticks.recv())
+                .await
+                .expect("job fired")
+                .unwrap();
+
+            fired.push(tick);
+        }
+
+        fired
+    }
+
+    // each run takes long enough to miss the following ticks
+    let (skip_tx, mut skip_ticks) = mpsc::unbounded_channel();
+    let (fire_all_tx, mut fire_all_ticks) = mpsc::unbounded_channel();
+    let slow_job = |ticks| RecordingJob {
+        ticks,
+        delay: Duration::from_millis(2500),
+    };
+
+    let _scheduler = ClusterScheduler::builder(remote.clone())
+        .storage(storage.clone())
+        .job(
+            ScheduledJob::new("skip", EVERY_SECOND, slow_job(skip_tx))
+                .unwrap()
+                .missed_fire_policy(MissedFirePolicy::Skip),
+        )
+        .job(
+            ScheduledJob::new("fire-all", EVERY_SECOND, slow_job(fire_all_tx))
+                .unwrap()
+                .missed_fire_policy(MissedFirePolicy::FireAll),
+        )
+        .build()
+        .await;
+
+    let fired = fired_ticks(&mut skip_ticks, 2).await;
+    assert!(fired[1] - fired[0] >= 3);
+
+    let fired = fired_ticks(&mut fire_all_ticks, 3).await;
+    assert_eq!(fired[1] - fired[0], 1);
+    assert_eq!(fired[2] - fired[1], 1);
+}
@@ -0,0 +1,226 @@
+use coerce::actor::system::ActorSystem;
+use coerce::http::breaker::{CircuitBreakerConfig, CircuitState};
+use coerce::http::{HttpClient, HttpClientErr, HttpRequest, RetryPolicy};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub mod util;
+
+#[macro_use]
+extern crate serde;
+
+#[derive(Deserialize)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+#[derive(Default)]
+struct ServerStats {
+    hits: AtomicUsize,
+    flaky_hits: AtomicUsize,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+async fn start_server() -> (SocketAddr, Arc<ServerStats>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let stats = Arc::new(ServerStats::default());
+
+    let server_stats = stats.clone();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(handle_connection(stream, server_stats.clone()));
+        }
+    });
+
+    (addr, stats)
+}
+
+async fn handle_connection(mut stream: TcpStream, stats: Arc<ServerStats>) {
+    let mut buf = vec![];
+    let mut chunk = [0; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let len = stream.read(&mut chunk).await.unwrap();
+        if len == 0 {
+            return;
+        }
+
+        buf.extend_from_slice(&chunk[..len]);
+    }
+
+    let request = String::from_utf8(buf).unwrap();
+    let path = request.split(' ').nth(1).unwrap().to_string();
+
+    stats.hits.fetch_add(1, Ordering::SeqCst);
+
+    let (status, body) = match path.as_str() {
+        "/ok" => ("200 OK", "ok".to_string()),
+        "/user" => ("200 OK", r#"{"id":1,"name":"coerce"}"#.to_string()),
+        "/fail" => ("500 Internal Server Error", "".to_string()),
+        "/flaky" => {
+            if stats.flaky_hits.fetch_add(1, Ordering::SeqCst) < 2 {
+                ("503 Service Unavailable", "".to_string())
+            } else {
+                ("200 OK", "recovered".to_string())
+            }
+        }
+        "/slow" => {
+            let in_flight = stats.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            stats.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            stats.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            ("200 OK", "slow".to_string())
+        }
+        _ => ("404 Not Found", "".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\ncontent-length: {}\r\nx-path: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        path,
+        body
+    );
+
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+#[tokio::test]
+pub async fn test_http_client_request() {
+    util::create_trace_logger();
+
+    let (addr, _) = start_server().await;
+    let system = ActorSystem::new();
+    let client = HttpClient::builder(system).build().await.unwrap();
+
+    let res = client
+        .send(HttpRequest::get(format!("http://{}/ok", addr)))
+        .await
+        .unwrap();
+
+    assert!(res.is_success());
+    assert_eq!(res.text().unwrap(), "ok");
+    assert_eq!(res.header("X-Path"), Some("/ok"));
+
+    let user: User = client
+        .send(HttpRequest::get(format!("http://{}/user", addr)))
+        .await
+        .unwrap()
+        .json()
+        .unwrap();
+
+    assert_eq!(user.id, 1);
+    assert_eq!(user.name, "coerce");
+
+    let res = client.send(HttpRequest::get("not a url")).await;
+    assert!(matches!(res, Err(HttpClientErr::InvalidUrl(_))));
+}
+
+#[tokio::test]
+pub async fn test_http_client_retries_idempotent_requests() {
+    util::create_trace_logger();
+
+    let (addr, stats) = start_server().await;
+    let system = ActorSystem::new();
+    let client = HttpClient::builder(system)
+        .retry(RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        })
+        .build()
+        .await
+        .unwrap();
+
+    let res = client
+        .send(HttpRequest::post(format!("http://{}/flaky", addr)))
+        .await
+        .unwrap();
+
+    assert_eq!(res.status, 503);
+    assert_eq!(stats.hits.load(Ordering::SeqCst), 1);
+
+    let res = client
+        .send(HttpRequest::get(format!("http://{}/flaky", addr)))
+        .await
+        .unwrap();
+
+    assert_eq!(res.status, 200);
+    assert_eq!(res.text().unwrap(), "recovered");
+    assert_eq!(stats.hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+pub async fn test_http_client_circuit_breaker() {
+    util::create_trace_logger();
+
+    let (addr, stats) = start_server().await;
+    let system = ActorSystem::new();
+    let client = HttpClient::builder(system)
+        .retry(RetryPolicy::none())
+        .circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 2,
+            reset_timeout: Duration::from_millis(200),
+        })
+        .build()
+        .await
+        .unwrap();
+
+    let fail = format!("http://{}/fail", addr);
+    let ok = format!("http://{}/ok", addr);
+
+    assert_eq!(client.circuit_state(&ok).await.unwrap(), None);
+
+    for _ in 0..2 {
+        let res = client.send(HttpRequest::get(&fail)).await.unwrap();
+        assert_eq!(res.status, 500);
+    }
+
+    assert_eq!(
+        client.circuit_state(&ok).await.unwrap(),
+        Some(CircuitState::Open)
+    );
+
+    let res = client.send(HttpRequest::get(&ok)).await;
+    assert!(matches!(res, Err(HttpClientErr::CircuitOpen { .. })));
+    assert_eq!(stats.hits.load(Ordering::SeqCst), 2);
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    let res = client.send(HttpRequest::get(&ok)).await.unwrap();
+    assert_eq!(res.status, 200);
+    assert_eq!(
+        client.circuit_state(&ok).await.unwrap(),
+        Some(CircuitState::Closed)
+    );
+}
+
+#[tokio::test]
+pub async fn test_http_client_limits_concurrency_per_host() {
+    util::create_trace_logger();
+
+    let (addr, stats) = start_server().await;
+    let system = ActorSystem::new();
+    let client = HttpClient::builder(system)
+        .max_concurrency_per_host(2)
+        .build()
+        .await
+        .unwrap();
+
+    let requests = (0..6).map(|_| client.send(HttpRequest::get(format!("http://{}/slow", addr))));
+    for res in futures::future::join_all(requests).await {
+        assert_eq!(res.unwrap().status, 200);
+    }
+
+    assert_eq!(stats.hits.load(Ordering::SeqCst), 6);
+    assert_eq!(stats.max_in_flight.load(Ordering::SeqCst), 2);
+}
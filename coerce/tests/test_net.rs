@@ -0,0 +1,144 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::Actor;
+use coerce::net::tcp::{FrameWriter, TcpServer};
+use coerce::net::udp::{Datagram, DatagramWriter, UdpEndpoint};
+use coerce::net::Received;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio_util::codec::{Framed, LinesCodec};
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+struct EchoConnection {
+    writer: FrameWriter<LinesCodec>,
+}
+
+impl Actor for EchoConnection {}
+
+#[async_trait]
+impl Handler<Received<String>> for EchoConnection {
+    async fn handle(&mut self, message: Received<String>, ctx: &mut ActorContext) {
+        if message.0 == "quit" {
+            ctx.stop(None);
+            return;
+        }
+
+        let _ = self.writer.send(format!("echo: {}", message.0)).await;
+    }
+}
+
+struct EchoEndpoint {
+    writer: DatagramWriter<LinesCodec>,
+}
+
+impl Actor for EchoEndpoint {}
+
+#[async_trait]
+impl Handler<Datagram<String>> for EchoEndpoint {
+    async fn handle(&mut self, message: Datagram<String>, _ctx: &mut ActorContext) {
+        let _ = self
+            .writer
+            .send_to(format!("echo: {}", message.frame), message.addr)
+            .await;
+    }
+}
+
+async fn wait_for_connections(server: &TcpServer, n: usize) {
+    for _ in 0..100 {
+        if server.connections() == n {
+            return;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert_eq!(server.connections(), n);
+}
+
+#[tokio::test]
+pub async fn test_tcp_server_connection_lifecycle() {
+    util::create_trace_logger();
+
+    let system = ActorSystem::new();
+    let server = TcpServer::builder(system.clone())
+        .name("echo")
+        .max_connections(1)
+        .start(LinesCodec::new(), |conn| EchoConnection {
+            writer: conn.writer,
+        })
+        .await
+        .unwrap();
+
+    let addr = server.local_addr();
+    let mut client = Framed::new(TcpStream::connect(addr).await.unwrap(), LinesCodec::new());
+
+    client.send("hello").await.unwrap();
+    assert_eq!(client.next().await.unwrap().unwrap(), "echo: hello");
+    assert_eq!(server.connections(), 1);
+
+    // the second connection isn't accepted until the first is closed
+    let mut second = Framed::new(TcpStream::connect(addr).await.unwrap(), LinesCodec::new());
+    second.send("waiting").await.unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), second.next())
+            .await
+            .is_err()
+    );
+
+    // stopping the connection actor closes the socket
+    client.send("quit").await.unwrap();
+    assert!(client.next().await.is_none());
+
+    assert_eq!(second.next().await.unwrap().unwrap(), "echo: waiting");
+
+    // the peer disconnecting stops the connection actor
+    drop(second);
+    wait_for_connections(&server, 0).await;
+
+    let mut third = Framed::new(TcpStream::connect(addr).await.unwrap(), LinesCodec::new());
+    third.send("hello").await.unwrap();
+    assert_eq!(third.next().await.unwrap().unwrap(), "echo: hello");
+
+    server.stop().await.unwrap();
+    assert!(third.next().await.is_none());
+    assert!(TcpStream::connect(addr).await.is_err());
+}
+
+#[tokio::test]
+pub async fn test_udp_endpoint_echo() {
+    util::create_trace_logger();
+
+    let system = ActorSystem::new();
+    let endpoint = UdpEndpoint::bind(
+        &system,
+        "udp-echo",
+        "127.0.0.1:0",
+        LinesCodec::new(),
+        |writer| EchoEndpoint { writer },
+    )
+    .await
+    .unwrap();
+
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    client.connect(endpoint.local_addr()).await.unwrap();
+
+    let mut buf = [0; 64];
+    for message in ["hello", "world"] {
+        client.send(message.as_bytes()).await.unwrap();
+
+        let len = tokio::time::timeout(Duration::from_secs(1), client.recv(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(&buf[..len], format!("echo: {}\n", message).as_bytes());
+    }
+
+    endpoint.stop().await.unwrap();
+}
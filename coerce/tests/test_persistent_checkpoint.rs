@@ -0,0 +1,165 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, IntoActor};
+use coerce::persistent::checkpoint::Checkpoint;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::storage::JournalStorageRef;
+use coerce::persistent::Persistence;
+use std::time::Duration;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+#[derive(Default)]
+struct Counter {
+    count: u64,
+    restored_from: Option<i64>,
+}
+
+#[async_trait]
+impl Actor for Counter {
+    const CHECKPOINT_INTERVAL: Option<Duration> = Some(Duration::from_millis(250));
+
+    async fn restore(&mut self, checkpoint: Checkpoint, _ctx: &mut ActorContext) {
+        self.count = u64::from_le_bytes(checkpoint.bytes.try_into().unwrap());
+        self.restored_from = Some(checkpoint.sequence);
+    }
+}
+
+/// Stages checkpoints, but has no checkpoint interval, so they're never written
+#[derive(Default)]
+struct Uncheckpointed {
+    count: u64,
+}
+
+#[async_trait]
+impl Actor for Uncheckpointed {
+    async fn restore(&mut self, _checkpoint: Checkpoint, _ctx: &mut ActorContext) {
+        panic!("actor has no checkpoint interval")
+    }
+}
+
+struct Increment;
+
+impl Message for Increment {
+    type Result = u64;
+}
+
+struct GetRestoredFrom;
+
+impl Message for GetRestoredFrom {
+    type Result = Option<i64>;
+}
+
+#[async_trait]
+impl Handler<Increment> for Counter {
+    async fn handle(&mut self, _message: Increment, ctx: &mut ActorContext) -> u64 {
+        self.count += 1;
+        ctx.checkpoint(self.count.to_le_bytes());
+        self.count
+    }
+}
+
+#[async_trait]
+impl Handler<GetRestoredFrom> for Counter {
+    async fn handle(&mut self, _message: GetRestoredFrom, _ctx: &mut ActorContext) -> Option<i64> {
+        self.restored_from
+    }
+}
+
+#[async_trait]
+impl Handler<Increment> for Uncheckpointed {
+    async fn handle(&mut self, _message: Increment, ctx: &mut ActorContext) -> u64 {
+        self.count += 1;
+        ctx.checkpoint(self.count.to_le_bytes());
+        self.count
+    }
+}
+
+async fn latest_checkpoint(storage: &JournalStorageRef, actor_id: &str) -> Option<(i64, u64)> {
+    storage
+        .read_latest_snapshot(&format!("checkpoint-{actor_id}"))
+        .await
+        .unwrap()
+        .map(|entry| {
+            let count = u64::from_le_bytes(entry.bytes.as_slice().try_into().unwrap());
+            (entry.sequence, count)
+        })
+}
+
+#[tokio::test]
+pub async fn test_checkpoint_written_periodically_and_restored() {
+    util::create_trace_logger();
+
+    let provider = InMemoryStorageProvider::new();
+    let storage = provider.journal_storage().unwrap();
+    let system = ActorSystem::new().to_persistent(Persistence::from(provider));
+
+    let counter = Counter::default()
+        .into_actor(Some("counter"), &system)
+        .await
+        .unwrap();
+
+    // the first checkpoint is written immediately
+    assert_eq!(counter.send(Increment).await, Ok(1));
+    assert_eq!(latest_checkpoint(&storage, "counter").await, Some((1, 1)));
+
+    // further checkpoints within the interval are coalesced into a single write
+    assert_eq!(counter.send(Increment).await, Ok(2));
+    assert_eq!(counter.send(Increment).await, Ok(3));
+    assert_eq!(latest_checkpoint(&storage, "counter").await, Some((1, 1)));
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(latest_checkpoint(&storage, "counter").await, Some((2, 3)));
+
+    // any staged checkpoint is written when the actor stops
+    assert_eq!(counter.send(Increment).await, Ok(4));
+    counter.stop(false).await.unwrap();
+    assert_eq!(latest_checkpoint(&storage, "counter").await, Some((3, 4)));
+
+    let counter = Counter::default()
+        .into_actor(Some("counter"), &system)
+        .await
+        .unwrap();
+
+    assert_eq!(counter.send(GetRestoredFrom).await, Ok(Some(3)));
+    assert_eq!(counter.send(Increment).await, Ok(5));
+
+    // actors with different ids have their own checkpoints
+    let other = Counter::default()
+        .into_actor(Some("other-counter"), &system)
+        .await
+        .unwrap();
+
+    assert_eq!(other.send(GetRestoredFrom).await, Ok(None));
+    assert_eq!(other.send(Increment).await, Ok(1));
+}
+
+#[tokio::test]
+pub async fn test_checkpoint_disabled_by_default() {
+    util::create_trace_logger();
+
+    let provider = InMemoryStorageProvider::new();
+    let storage = provider.journal_storage().unwrap();
+    let system = ActorSystem::new().to_persistent(Persistence::from(provider));
+
+    let actor = Uncheckpointed::default()
+        .into_actor(Some("uncheckpointed"), &system)
+        .await
+        .unwrap();
+
+    assert_eq!(actor.send(Increment).await, Ok(1));
+    actor.stop(false).await.unwrap();
+    assert_eq!(latest_checkpoint(&storage, "uncheckpointed").await, None);
+
+    let actor = Uncheckpointed::default()
+        .into_actor(Some("uncheckpointed"), &system)
+        .await
+        .unwrap();
+
+    assert_eq!(actor.send(Increment).await, Ok(1));
+}
@@ -0,0 +1,88 @@
+use coerce::actor::lifecycle::Stop;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActor;
+use coerce::actor::LocalActorRef;
+use coerce::persistent::dead_letter::{
+    DeadLetterStore, ListDeadLetters, PurgeDeadLetters, ReplayDeadLetters, StoredDeadLetter,
+};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::provider::StorageProvider;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+use util::*;
+
+pub mod util;
+
+async fn wait_for_dead_letters(
+    store: &LocalActorRef<DeadLetterStore>,
+    count: usize,
+) -> Vec<StoredDeadLetter> {
+    for _ in 0..50 {
+        let dead_letters = store.send(ListDeadLetters).await.unwrap().unwrap();
+        if dead_letters.len() >= count {
+            return dead_letters;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    panic!("dead letters were not stored");
+}
+
+#[tokio::test]
+pub async fn test_dead_letter_store_replay_and_purge() {
+    util::create_trace_logger();
+
+    let system = ActorSystem::new();
+    let storage = InMemoryStorageProvider::new().journal_storage().unwrap();
+    let store = DeadLetterStore::new(storage)
+        .into_actor(Some("dead-letter-store"), &system)
+        .await
+        .unwrap();
+
+    let actor = system.new_anon_actor(TestActor::new()).await.unwrap();
+    let (tx, rx) = oneshot::channel();
+
+    let _ = actor.notify(Stop(Some(tx)));
+    let _ = actor.notify(SetStatusRequest {
+        status: TestActorStatus::Active,
+    });
+    let _ = actor.notify(GetCounterRequest());
+
+    rx.await.expect("actor stopped");
+
+    let dead_letters = wait_for_dead_letters(&store, 2).await;
+    assert_eq!(dead_letters.len(), 2);
+    assert_eq!(dead_letters[0].actor_id, actor.actor_id().clone());
+    assert!(dead_letters[0].payload.is_some());
+    assert!(dead_letters[1].payload.is_none());
+
+    let target = system.new_anon_actor(TestActor::new()).await.unwrap();
+    let result = store
+        .send(ReplayDeadLetters::<TestActor, SetStatusRequest>::new(
+            dead_letters.iter().map(|d| d.sequence).collect(),
+            target.clone(),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(result.replayed, vec![dead_letters[0].sequence]);
+    assert_eq!(result.skipped, vec![dead_letters[1].sequence]);
+    assert_eq!(
+        target.send(GetStatusRequest).await.unwrap(),
+        GetStatusResponse::Ok(TestActorStatus::Active)
+    );
+
+    store
+        .send(PurgeDeadLetters {
+            to_sequence: dead_letters[1].sequence,
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let dead_letters = store.send(ListDeadLetters).await.unwrap().unwrap();
+    assert!(dead_letters.is_empty());
+}
@@ -0,0 +1,213 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, IntoActor, LocalActorRef, ToActorId};
+use coerce::persistent::delivery::{
+    AtLeastOnceDelivery, DeliveryId, DeliveryState, UnconfirmedWarning,
+};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::types::JournalTypes;
+use coerce::persistent::{Persistence, PersistentActor};
+use coerce_macros::JsonMessage;
+use std::time::Duration;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+const REDELIVER_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+struct Payments {
+    received: Vec<DeliveryId>,
+}
+
+impl Actor for Payments {}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct TakePayment {
+    delivery_id: DeliveryId,
+    amount: i64,
+}
+
+#[async_trait]
+impl Handler<TakePayment> for Payments {
+    async fn handle(&mut self, message: TakePayment, _ctx: &mut ActorContext) {
+        assert_eq!(message.amount, 100);
+        self.received.push(message.delivery_id);
+    }
+}
+
+struct Orders {
+    deliveries: DeliveryState,
+    warnings: Vec<UnconfirmedWarning>,
+}
+
+impl Orders {
+    fn new() -> Self {
+        Self {
+            deliveries: DeliveryState::default()
+                .with_redeliver_interval(REDELIVER_INTERVAL)
+                .with_warn_after_attempts(3),
+            warnings: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl PersistentActor for Orders {
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal.delivery::<Payments, TakePayment>("take-payment");
+    }
+}
+
+#[async_trait]
+impl AtLeastOnceDelivery for Orders {
+    fn delivery_state(&mut self) -> &mut DeliveryState {
+        &mut self.deliveries
+    }
+
+    async fn on_unconfirmed(&mut self, warning: UnconfirmedWarning, _ctx: &mut ActorContext) {
+        self.warnings.push(warning);
+    }
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("DeliveryId")]
+struct PlaceOrder;
+
+#[async_trait]
+impl Handler<PlaceOrder> for Orders {
+    async fn handle(&mut self, _message: PlaceOrder, ctx: &mut ActorContext) -> DeliveryId {
+        self.deliver::<Payments, _>(
+            "payments",
+            |delivery_id| TakePayment {
+                delivery_id,
+                amount: 100,
+            },
+            ctx,
+        )
+        .await
+        .expect("delivery persisted")
+    }
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("bool")]
+struct PaymentTaken(DeliveryId);
+
+#[async_trait]
+impl Handler<PaymentTaken> for Orders {
+    async fn handle(&mut self, message: PaymentTaken, ctx: &mut ActorContext) -> bool {
+        self.confirm_delivery(message.0, ctx)
+            .await
+            .expect("confirmation persisted")
+    }
+}
+
+async fn received(payments: &LocalActorRef<Payments>, delivery_id: DeliveryId) -> usize {
+    payments
+        .exec(move |p| p.received.iter().filter(|id| **id == delivery_id).count())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+pub async fn test_delivery_redelivered_until_confirmed() {
+    util::create_trace_logger();
+
+    let system =
+        ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+
+    let payments = system
+        .new_actor("payments".to_actor_id(), Payments::default(), Tracked)
+        .await
+        .unwrap();
+
+    let orders = Orders::new()
+        .into_actor(Some("orders".to_string()), &system)
+        .await
+        .unwrap();
+
+    let delivery_id = orders.send(PlaceOrder).await.unwrap();
+    assert_eq!(delivery_id, 1);
+
+    while received(&payments, delivery_id).await < 3 {
+        tokio::time::sleep(REDELIVER_INTERVAL).await;
+    }
+
+    let warnings = orders.exec(|o| o.warnings.clone()).await.unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].deliveries[0].delivery_id, delivery_id);
+    assert_eq!(warnings[0].deliveries[0].attempts, 3);
+
+    assert!(orders.send(PaymentTaken(delivery_id)).await.unwrap());
+    assert!(!orders.send(PaymentTaken(delivery_id)).await.unwrap());
+    assert_eq!(
+        orders
+            .exec(|o| o.deliveries.unconfirmed_count())
+            .await
+            .unwrap(),
+        0
+    );
+
+    // once confirmed, the message is no longer redelivered
+    let attempts = received(&payments, delivery_id).await;
+    tokio::time::sleep(REDELIVER_INTERVAL * 4).await;
+    assert_eq!(received(&payments, delivery_id).await, attempts);
+
+    system.shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_unconfirmed_delivery_redelivered_after_recovery() {
+    util::create_trace_logger();
+
+    let system =
+        ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+
+    let orders = Orders::new()
+        .into_actor(Some("orders".to_string()), &system)
+        .await
+        .unwrap();
+
+    // the destination doesn't exist yet, so neither delivery can be received
+    let confirmed = orders.send(PlaceOrder).await.unwrap();
+    let unconfirmed = orders.send(PlaceOrder).await.unwrap();
+    assert!(orders.send(PaymentTaken(confirmed)).await.unwrap());
+
+    orders.stop(false).await.unwrap();
+
+    let payments = system
+        .new_actor("payments".to_actor_id(), Payments::default(), Tracked)
+        .await
+        .unwrap();
+
+    let orders = Orders::new()
+        .into_actor(Some("orders".to_string()), &system)
+        .await
+        .unwrap();
+
+    while received(&payments, unconfirmed).await == 0 {
+        tokio::time::sleep(REDELIVER_INTERVAL).await;
+    }
+
+    assert_eq!(received(&payments, confirmed).await, 0);
+
+    let pending = orders.exec(|o| o.deliveries.unconfirmed()).await.unwrap();
+
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].delivery_id, unconfirmed);
+    assert_eq!(pending[0].destination, "payments".to_actor_id());
+
+    // delivery ids continue from the recovered deliveries
+    assert_eq!(orders.send(PlaceOrder).await.unwrap(), unconfirmed + 1);
+
+    system.shutdown().await;
+}
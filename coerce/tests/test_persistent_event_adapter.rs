@@ -0,0 +1,233 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActor;
+use coerce::persistent::journal::adapter::PersistedEvent;
+use coerce::persistent::journal::metadata::EventMetadata;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::types::JournalTypes;
+use coerce::persistent::journal::RecoveryErr;
+use coerce::persistent::{Persistence, PersistentActor, Recover};
+use coerce_macros::JsonMessage;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+const PERSISTENCE_KEY: &str = "order-1";
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct OrderCreatedV1 {
+    order_id: String,
+    amount: i32,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[result("()")]
+struct OrderCreated {
+    order_id: String,
+    amount_pence: i64,
+    currency: String,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct NoteAdded(String);
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct CommentAdded {
+    comment: String,
+}
+
+#[derive(Default)]
+struct OrderV1;
+
+#[derive(Default)]
+struct Order {
+    created: Vec<OrderCreated>,
+    comments: Vec<String>,
+}
+
+#[async_trait]
+impl PersistentActor for OrderV1 {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        PERSISTENCE_KEY.to_string()
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal
+            .message::<OrderCreatedV1>("order-created")
+            .message::<NoteAdded>("note-added");
+    }
+}
+
+#[async_trait]
+impl Handler<OrderCreatedV1> for OrderV1 {
+    async fn handle(&mut self, message: OrderCreatedV1, ctx: &mut ActorContext) {
+        self.persist(&message, ctx).await.expect("event persisted");
+    }
+}
+
+#[async_trait]
+impl Handler<NoteAdded> for OrderV1 {
+    async fn handle(&mut self, message: NoteAdded, ctx: &mut ActorContext) {
+        self.persist(&message, ctx).await.expect("event persisted");
+    }
+}
+
+#[async_trait]
+impl Recover<OrderCreatedV1> for OrderV1 {
+    async fn recover(&mut self, _message: OrderCreatedV1, _ctx: &mut ActorContext) {}
+}
+
+#[async_trait]
+impl Recover<NoteAdded> for OrderV1 {
+    async fn recover(&mut self, _message: NoteAdded, _ctx: &mut ActorContext) {}
+}
+
+fn upcast_note(note: NoteAdded) -> CommentAdded {
+    CommentAdded { comment: note.0 }
+}
+
+#[async_trait]
+impl PersistentActor for Order {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        PERSISTENCE_KEY.to_string()
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal
+            .message::<OrderCreated>("order-created.v2")
+            .message::<CommentAdded>("comment-added.v2")
+            .upcast_event::<OrderCreatedV1, OrderCreated>("order-created", |v1| OrderCreated {
+                order_id: v1.order_id,
+                amount_pence: v1.amount as i64 * 100,
+                currency: "GBP".to_string(),
+            })
+            // notes were renamed to comments, then the comment's structure changed
+            .event_adapter("note-added", |event: PersistedEvent| {
+                Ok(PersistedEvent {
+                    payload_type: "comment-added".into(),
+                    ..event
+                })
+            })
+            .upcast_event::<NoteAdded, CommentAdded>("comment-added", upcast_note);
+    }
+}
+
+#[async_trait]
+impl Recover<OrderCreated> for Order {
+    async fn recover(&mut self, message: OrderCreated, _ctx: &mut ActorContext) {
+        self.created.push(message);
+    }
+}
+
+#[async_trait]
+impl Recover<CommentAdded> for Order {
+    async fn recover(&mut self, message: CommentAdded, _ctx: &mut ActorContext) {
+        self.comments.push(message.comment);
+    }
+}
+
+#[tokio::test]
+pub async fn test_event_upcast_on_recovery() {
+    util::create_trace_logger();
+
+    let system =
+        ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+
+    let order = OrderV1
+        .into_actor(Some("order".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    order
+        .send(OrderCreatedV1 {
+            order_id: "1".to_string(),
+            amount: 25,
+        })
+        .await
+        .unwrap();
+
+    order.send(NoteAdded("fragile".to_string())).await.unwrap();
+    order.stop(false).await.unwrap();
+
+    let order = Order::default()
+        .into_actor(Some("order".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    let (created, comments) = order
+        .exec(|a| (a.created.clone(), a.comments.clone()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        created,
+        vec![OrderCreated {
+            order_id: "1".to_string(),
+            amount_pence: 2500,
+            currency: "GBP".to_string(),
+        }]
+    );
+
+    assert_eq!(comments, vec!["fragile".to_string()]);
+
+    system.shutdown().await;
+}
+
+#[test]
+pub fn test_event_adapter_errors() {
+    let event = |payload_type: &str, bytes: &[u8]| PersistedEvent {
+        payload_type: payload_type.into(),
+        bytes: bytes.to_vec(),
+        metadata: EventMetadata::default(),
+    };
+
+    let mut types = JournalTypes::<Order>::default();
+    types
+        .message::<OrderCreated>("order-created.v2")
+        .upcast_event::<OrderCreatedV1, OrderCreated>("order-created", |v1| OrderCreated {
+            order_id: v1.order_id,
+            amount_pence: v1.amount as i64,
+            currency: "GBP".to_string(),
+        });
+
+    // events without an adapter are recovered as they were persisted
+    let adapted = types
+        .adapt_event(1, event("order-created.v2", b"{}"))
+        .unwrap();
+
+    assert_eq!(adapted.payload_type.as_ref(), "order-created.v2");
+    assert_eq!(adapted.bytes, b"{}".to_vec());
+
+    // the persisted event couldn't be deserialised as the old event type
+    assert!(matches!(
+        types.adapt_event(2, event("order-created", b"{}")),
+        Err(RecoveryErr::EventAdapter {
+            message_sequence_id: 2,
+            ..
+        })
+    ));
+
+    let rename = |payload_type: &'static str| {
+        move |event: PersistedEvent| {
+            Ok(PersistedEvent {
+                payload_type: payload_type.into(),
+                ..event
+            })
+        }
+    };
+
+    types
+        .event_adapter("a", rename("b"))
+        .event_adapter("b", rename("a"));
+
+    assert!(types.adapt_event(3, event("a", b"{}")).is_err());
+}
@@ -0,0 +1,318 @@
+use coerce::persistent::journal::failover::{
+    FailoverConfig, FailoverEvent, FailoverStorageProvider, ProviderStatus, UnavailablePolicy,
+};
+use coerce::persistent::journal::metadata::EventMetadata;
+use coerce::persistent::journal::provider::inmemory::{
+    InMemoryJournalStorage, InMemoryStorageProvider,
+};
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::storage::{JournalEntry, JournalStorage, JournalStorageRef};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+#[derive(Clone, Default)]
+struct FlakyStorageProvider {
+    storage: Arc<FlakyStorage>,
+}
+
+#[derive(Default)]
+struct FlakyStorage {
+    unavailable: AtomicBool,
+    inner: InMemoryJournalStorage,
+}
+
+impl FlakyStorageProvider {
+    fn set_available(&self, available: bool) {
+        self.storage
+            .unavailable
+            .store(!available, Ordering::Relaxed);
+    }
+}
+
+impl StorageProvider for FlakyStorageProvider {
+    fn journal_storage(&self) -> Option<JournalStorageRef> {
+        Some(self.storage.clone())
+    }
+}
+
+impl FlakyStorage {
+    fn check(&self) -> anyhow::Result<()> {
+        if self.unavailable.load(Ordering::Relaxed) {
+            Err(anyhow::anyhow!("storage unavailable"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl JournalStorage for FlakyStorage {
+    async fn write_snapshot(
+        &self,
+        persistence_id: &str,
+        entry: JournalEntry,
+    ) -> anyhow::Result<()> {
+        self.check()?;
+        self.inner.write_snapshot(persistence_id, entry).await
+    }
+
+    async fn write_message(&self, persistence_id: &str, entry: JournalEntry) -> anyhow::Result<()> {
+        self.check()?;
+        self.inner.write_message(persistence_id, entry).await
+    }
+
+    async fn write_message_batch(
+        &self,
+        persistence_id: &str,
+        entries: Vec<JournalEntry>,
+    ) -> anyhow::Result<()> {
+        self.check()?;
+        self.inner
+            .write_message_batch(persistence_id, entries)
+            .await
+    }
+
+    async fn read_latest_snapshot(
+        &self,
+        persistence_id: &str,
+    ) -> anyhow::Result<Option<JournalEntry>> {
+        self.check()?;
+        self.inner.read_latest_snapshot(persistence_id).await
+    }
+
+    async fn read_latest_messages(
+        &self,
+        persistence_id: &str,
+        from_sequence: i64,
+    ) -> anyhow::Result<Option<Vec<JournalEntry>>> {
+        self.check()?;
+        self.inner
+            .read_latest_messages(persistence_id, from_sequence)
+            .await
+    }
+
+    async fn read_message(
+        &self,
+        persistence_id: &str,
+        sequence_id: i64,
+    ) -> anyhow::Result<Option<JournalEntry>> {
+        self.check()?;
+        self.inner.read_message(persistence_id, sequence_id).await
+    }
+
+    async fn read_messages(
+        &self,
+        persistence_id: &str,
+        from_sequence: i64,
+        to_sequence: i64,
+    ) -> anyhow::Result<Option<Vec<JournalEntry>>> {
+        self.check()?;
+        self.inner
+            .read_messages(persistence_id, from_sequence, to_sequence)
+            .await
+    }
+
+    async fn delete_messages_to(
+        &self,
+        persistence_id: &str,
+        to_sequence: i64,
+    ) -> anyhow::Result<()> {
+        self.check()?;
+        self.inner
+            .delete_messages_to(persistence_id, to_sequence)
+            .await
+    }
+
+    async fn delete_all(&self, persistence_id: &str) -> anyhow::Result<()> {
+        self.check()?;
+        self.inner.delete_all(persistence_id).await
+    }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        self.check()
+    }
+}
+
+fn entry(sequence: i64) -> JournalEntry {
+    JournalEntry {
+        sequence,
+        payload_type: "test".into(),
+        bytes: Arc::new(vec![sequence as u8]),
+        metadata: EventMetadata::default(),
+    }
+}
+
+fn config(policy: UnavailablePolicy) -> FailoverConfig {
+    FailoverConfig {
+        probe_interval: Duration::from_millis(50),
+        failure_threshold: 1,
+        recovery_threshold: 1,
+        policy,
+    }
+}
+
+async fn wait_for_event(
+    events: &mut broadcast::Receiver<FailoverEvent>,
+    matches: impl Fn(&FailoverEvent) -> bool,
+) -> FailoverEvent {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let event = events.recv().await.unwrap();
+            if matches(&event) {
+                return event;
+            }
+        }
+    })
+    .await
+    .expect("failover event")
+}
+
+#[tokio::test]
+pub async fn test_journal_failover_to_secondary() {
+    util::create_trace_logger();
+
+    let primary = FlakyStorageProvider::default();
+    let secondary = InMemoryStorageProvider::new();
+    let secondary_storage = secondary.journal_storage().unwrap();
+
+    let provider = FailoverStorageProvider::new(
+        primary.clone(),
+        secondary,
+        config(UnavailablePolicy::Failover),
+    );
+
+    let mut events = provider.subscribe();
+    let storage = provider.journal_storage().unwrap();
+
+    storage.write_message("actor", entry(1)).await.unwrap();
+    assert_eq!(provider.status(), ProviderStatus::Healthy);
+
+    primary.set_available(false);
+    wait_for_event(&mut events, |e| matches!(e, FailoverEvent::FailedOver)).await;
+    assert_eq!(provider.status(), ProviderStatus::FailedOver);
+    assert!(!provider.fail_back());
+
+    storage.write_message("actor", entry(2)).await.unwrap();
+    let messages = secondary_storage
+        .read_latest_messages("actor", 0)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].sequence, 2);
+
+    primary.set_available(true);
+    wait_for_event(&mut events, |e| {
+        matches!(e, FailoverEvent::PrimaryRecovered)
+    })
+    .await;
+
+    // the secondary is used until the provider is explicitly failed back
+    assert_eq!(provider.status(), ProviderStatus::FailedOver);
+    assert!(provider.fail_back());
+    assert_eq!(provider.status(), ProviderStatus::Healthy);
+
+    let messages = storage
+        .read_latest_messages("actor", 0)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].sequence, 1);
+}
+
+#[tokio::test]
+pub async fn test_journal_failover_queues_writes() {
+    util::create_trace_logger();
+
+    let primary = FlakyStorageProvider::default();
+    let provider = FailoverStorageProvider::new(
+        primary.clone(),
+        InMemoryStorageProvider::new(),
+        config(UnavailablePolicy::Queue { max_queued: 2 }),
+    );
+
+    let mut events = provider.subscribe();
+    let storage = provider.journal_storage().unwrap();
+
+    primary.set_available(false);
+    wait_for_event(&mut events, |e| {
+        matches!(e, FailoverEvent::PrimaryUnavailable { .. })
+    })
+    .await;
+
+    assert_eq!(provider.status(), ProviderStatus::Unavailable);
+
+    storage.write_message("actor", entry(1)).await.unwrap();
+    storage
+        .write_message_batch("actor", vec![entry(2), entry(3)])
+        .await
+        .unwrap();
+
+    assert!(storage.write_message("actor", entry(4)).await.is_err());
+    assert!(storage.read_latest_messages("actor", 0).await.is_err());
+    assert_eq!(provider.queued_writes().await, 2);
+
+    primary.set_available(true);
+    let flushed = wait_for_event(&mut events, |e| {
+        matches!(e, FailoverEvent::QueueFlushed { .. })
+    })
+    .await;
+
+    assert!(matches!(flushed, FailoverEvent::QueueFlushed { writes: 2 }));
+    assert_eq!(provider.status(), ProviderStatus::Healthy);
+    assert_eq!(provider.queued_writes().await, 0);
+
+    let sequences: Vec<i64> = storage
+        .read_latest_messages("actor", 0)
+        .await
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|e| e.sequence)
+        .collect();
+
+    assert_eq!(sequences, vec![1, 2, 3]);
+}
+
+#[tokio::test]
+pub async fn test_journal_failover_fails_fast() {
+    util::create_trace_logger();
+
+    let primary = FlakyStorageProvider::default();
+    let provider = FailoverStorageProvider::new(
+        primary.clone(),
+        InMemoryStorageProvider::new(),
+        config(UnavailablePolicy::FailFast),
+    );
+
+    let mut events = provider.subscribe();
+    let storage = provider.journal_storage().unwrap();
+
+    primary.set_available(false);
+    wait_for_event(&mut events, |e| {
+        matches!(e, FailoverEvent::PrimaryUnavailable { .. })
+    })
+    .await;
+
+    assert!(storage.write_message("actor", entry(1)).await.is_err());
+    assert!(storage.read_latest_snapshot("actor").await.is_err());
+
+    primary.set_available(true);
+    wait_for_event(&mut events, |e| {
+        matches!(e, FailoverEvent::PrimaryRecovered)
+    })
+    .await;
+
+    storage.write_message("actor", entry(1)).await.unwrap();
+    assert!(storage.read_message("actor", 1).await.unwrap().is_some());
+}
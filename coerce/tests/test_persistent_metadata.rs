@@ -0,0 +1,195 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActor;
+use coerce::persistent::batch::EventBatch;
+use coerce::persistent::journal::metadata::EventMetadata;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::storage::JournalEntry;
+use coerce::persistent::journal::types::JournalTypes;
+use coerce::persistent::{Persistence, PersistentActor, Recover};
+use coerce_macros::JsonMessage;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+struct AuditedActor {
+    values: Vec<i32>,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct Deposit {
+    value: i32,
+    request_id: String,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct DepositBatch(Vec<i32>);
+
+#[async_trait]
+impl PersistentActor for AuditedActor {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        "audited-actor".to_string()
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal.message::<Deposit>("deposit");
+    }
+}
+
+#[async_trait]
+impl Handler<Deposit> for AuditedActor {
+    async fn handle(&mut self, message: Deposit, ctx: &mut ActorContext) {
+        let metadata = EventMetadata::new()
+            .with_correlation_id(message.request_id.clone())
+            .with_header("source", "test");
+
+        if self
+            .persist_with_metadata(&message, metadata, ctx)
+            .await
+            .is_ok()
+        {
+            self.values.push(message.value);
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<DepositBatch> for AuditedActor {
+    async fn handle(&mut self, message: DepositBatch, ctx: &mut ActorContext) {
+        let mut batch = EventBatch::create(ctx);
+        for value in &message.0 {
+            batch.message_with_metadata(
+                Deposit {
+                    value: *value,
+                    request_id: "batch".to_string(),
+                },
+                EventMetadata::new()
+                    .with_correlation_id("batch")
+                    .with_causation_id(format!("deposit-{}", value)),
+            );
+        }
+
+        if self.persist_batch(batch, ctx).await.is_ok() {
+            self.values.extend(message.0);
+        }
+    }
+}
+
+#[async_trait]
+impl Recover<Deposit> for AuditedActor {
+    async fn recover(&mut self, message: Deposit, _ctx: &mut ActorContext) {
+        self.values.push(message.value);
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[tokio::test]
+pub async fn test_persistent_event_metadata() {
+    util::create_trace_logger();
+
+    let storage = InMemoryStorageProvider::new();
+    let journal = storage.journal_storage().unwrap();
+    let system = ActorSystem::new().to_persistent(Persistence::from(storage));
+
+    let started_at = now_millis();
+    let actor = AuditedActor { values: vec![] }
+        .into_actor(Some("audited-actor"), &system)
+        .await
+        .unwrap();
+
+    actor
+        .send(Deposit {
+            value: 1,
+            request_id: "request-1".to_string(),
+        })
+        .await
+        .unwrap();
+
+    actor.send(DepositBatch(vec![2, 3])).await.unwrap();
+
+    let messages = journal
+        .read_latest_messages("audited-actor", 0)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(messages.len(), 3);
+    for message in &messages {
+        let timestamp = message.metadata.timestamp.expect("write timestamp");
+        assert!(timestamp >= started_at && timestamp <= now_millis());
+    }
+
+    let metadata = &messages[0].metadata;
+    assert_eq!(metadata.correlation_id.as_deref(), Some("request-1"));
+    assert_eq!(metadata.causation_id, None);
+    assert_eq!(metadata.header("source"), Some("test"));
+
+    let metadata = &messages[2].metadata;
+    assert_eq!(metadata.correlation_id.as_deref(), Some("batch"));
+    assert_eq!(metadata.causation_id.as_deref(), Some("deposit-3"));
+    assert!(metadata.headers.is_empty());
+
+    // metadata doesn't affect recovery
+    actor.stop(false).await.unwrap();
+    let actor = AuditedActor { values: vec![] }
+        .into_actor(Some("audited-actor"), &system)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        actor.exec(|a| a.values.clone()).await.unwrap(),
+        vec![1, 2, 3]
+    );
+
+    system.shutdown().await;
+}
+
+#[test]
+pub fn test_journal_entry_metadata_serialisation() {
+    let entry = JournalEntry {
+        sequence: 1,
+        payload_type: "deposit".into(),
+        bytes: Arc::new(vec![1, 2, 3]),
+        metadata: EventMetadata {
+            timestamp: Some(1_700_000_000_000),
+            ..EventMetadata::new()
+        }
+        .with_correlation_id("request-1")
+        .with_causation_id("command-1")
+        .with_header("tenant", "acme")
+        .with_tag("deposits"),
+    };
+
+    let bytes = entry.write_to_bytes().unwrap();
+    let decoded = JournalEntry::read_from_bytes(bytes).unwrap();
+    assert_eq!(decoded.sequence, 1);
+    assert_eq!(decoded.bytes, entry.bytes);
+    assert_eq!(decoded.metadata, entry.metadata);
+
+    // entries without metadata are encoded without it
+    let entry = JournalEntry {
+        metadata: EventMetadata::default(),
+        ..entry
+    };
+
+    let bytes = entry.write_to_bytes().unwrap();
+    let decoded = JournalEntry::read_from_bytes(bytes).unwrap();
+    assert!(decoded.metadata.is_empty());
+}
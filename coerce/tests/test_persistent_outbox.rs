@@ -0,0 +1,168 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActor;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::types::JournalTypes;
+use coerce::persistent::outbox::{Flush, GetOffsets, OutboxRecord, OutboxRelay, OutboxSink, Track};
+use coerce::persistent::{Persistence, PersistentActor, Recover};
+use coerce_macros::JsonMessage;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+struct OrderActor {
+    persistence_id: String,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct OrderPlaced {
+    order_id: u32,
+}
+
+#[async_trait]
+impl PersistentActor for OrderActor {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        self.persistence_id.clone()
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal.message::<OrderPlaced>("order-placed");
+    }
+}
+
+#[async_trait]
+impl Handler<OrderPlaced> for OrderActor {
+    async fn handle(&mut self, message: OrderPlaced, ctx: &mut ActorContext) {
+        let _ = self.persist(&message, ctx).await;
+    }
+}
+
+#[async_trait]
+impl Recover<OrderPlaced> for OrderActor {
+    async fn recover(&mut self, _message: OrderPlaced, _ctx: &mut ActorContext) {}
+}
+
+#[derive(Clone, Default)]
+struct TestSink {
+    published: Arc<Mutex<Vec<OutboxRecord>>>,
+    failing: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl OutboxSink for TestSink {
+    async fn publish(&self, records: &[OutboxRecord]) -> anyhow::Result<()> {
+        if self.failing.load(Ordering::Relaxed) {
+            return Err(anyhow::anyhow!("broker unavailable"));
+        }
+
+        self.published.lock().extend_from_slice(records);
+        Ok(())
+    }
+}
+
+impl TestSink {
+    fn published(&self) -> Vec<(String, i64)> {
+        self.published
+            .lock()
+            .iter()
+            .map(|r| (r.persistence_id.to_string(), r.sequence))
+            .collect()
+    }
+}
+
+#[tokio::test]
+pub async fn test_outbox_relays_persisted_events() {
+    util::create_trace_logger();
+
+    let storage = InMemoryStorageProvider::new();
+    let journal = storage.journal_storage().unwrap();
+    let system = ActorSystem::new().to_persistent(Persistence::from(storage));
+
+    let actor = OrderActor {
+        persistence_id: "order-1".to_string(),
+    }
+    .into_actor(Some("order-1"), &system)
+    .await
+    .unwrap();
+
+    for order_id in 0..3 {
+        actor.send(OrderPlaced { order_id }).await.unwrap();
+    }
+
+    let sink = TestSink::default();
+    let relay = OutboxRelay::new(journal.clone(), sink.clone())
+        .with_poll_interval(Duration::from_secs(3600))
+        .with_batch_size(2)
+        .start(&system)
+        .await
+        .unwrap();
+
+    // events persisted before the journal was tracked are relayed too
+    relay.send(Track("order-1".to_string())).await.unwrap();
+    assert_eq!(relay.send(Flush).await.unwrap().unwrap(), 3);
+    assert_eq!(
+        sink.published(),
+        vec![
+            ("order-1".to_string(), 1),
+            ("order-1".to_string(), 2),
+            ("order-1".to_string(), 3)
+        ]
+    );
+
+    let record = sink.published.lock()[0].clone();
+    assert_eq!(record.payload_type.as_ref(), "order-placed");
+    assert!(record.metadata.timestamp.is_some());
+
+    // nothing is published until the broker is available again
+    sink.failing.store(true, Ordering::Relaxed);
+    actor.send(OrderPlaced { order_id: 3 }).await.unwrap();
+    assert!(relay.send(Flush).await.unwrap().is_err());
+    assert_eq!(sink.published().len(), 3);
+
+    sink.failing.store(false, Ordering::Relaxed);
+    assert_eq!(relay.send(Flush).await.unwrap().unwrap(), 1);
+    assert_eq!(sink.published().last(), Some(&("order-1".to_string(), 4)));
+    assert_eq!(
+        relay.send(GetOffsets).await.unwrap().get("order-1"),
+        Some(&Some(4))
+    );
+
+    // a restarted relay resumes from the stored offsets
+    relay.stop(false).await.unwrap();
+    actor.send(OrderPlaced { order_id: 4 }).await.unwrap();
+
+    let sink = TestSink::default();
+    let relay = OutboxRelay::new(journal, sink.clone())
+        .with_poll_interval(Duration::from_millis(50))
+        .start(&system)
+        .await
+        .unwrap();
+
+    for _ in 0..20 {
+        if !sink.published().is_empty() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(sink.published(), vec![("order-1".to_string(), 5)]);
+    assert_eq!(
+        relay.send(GetOffsets).await.unwrap().get("order-1"),
+        Some(&Some(5))
+    );
+
+    system.shutdown().await;
+}
@@ -0,0 +1,211 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{IntoActor, LocalActorRef};
+use coerce::persistent::journal::metadata::EventMetadata;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::storage::TaggedEntry;
+use coerce::persistent::journal::types::JournalTypes;
+use coerce::persistent::{Persistence, PersistentActor, Recover};
+use coerce_macros::JsonMessage;
+use futures::StreamExt;
+use std::time::Duration;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+struct Order {
+    id: String,
+    items: Vec<String>,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct ItemAdded {
+    order_id: String,
+    item: String,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct NoteAdded(String);
+
+#[async_trait]
+impl PersistentActor for Order {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        format!("order-{}", &self.id)
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal
+            .message::<ItemAdded>("item-added")
+            .message::<NoteAdded>("note-added");
+    }
+}
+
+#[async_trait]
+impl Handler<ItemAdded> for Order {
+    async fn handle(&mut self, message: ItemAdded, ctx: &mut ActorContext) {
+        let metadata = EventMetadata::new().with_tag("order");
+        if self
+            .persist_with_metadata(&message, metadata, ctx)
+            .await
+            .is_ok()
+        {
+            self.items.push(message.item);
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<NoteAdded> for Order {
+    async fn handle(&mut self, message: NoteAdded, ctx: &mut ActorContext) {
+        let _ = self.persist(&message, ctx).await;
+    }
+}
+
+#[async_trait]
+impl Recover<ItemAdded> for Order {
+    async fn recover(&mut self, message: ItemAdded, _ctx: &mut ActorContext) {
+        self.items.push(message.item);
+    }
+}
+
+#[async_trait]
+impl Recover<NoteAdded> for Order {
+    async fn recover(&mut self, _message: NoteAdded, _ctx: &mut ActorContext) {}
+}
+
+async fn order(id: &str, system: &ActorSystem) -> LocalActorRef<Order> {
+    Order {
+        id: id.to_string(),
+        items: vec![],
+    }
+    .into_actor(Some(format!("order-{}", id)), system)
+    .await
+    .unwrap()
+}
+
+async fn add_item(order: &LocalActorRef<Order>, order_id: &str, item: &str) {
+    order
+        .send(ItemAdded {
+            order_id: order_id.to_string(),
+            item: item.to_string(),
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+pub async fn test_persistent_events_by_tag() {
+    util::create_trace_logger();
+
+    let system =
+        ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let order_1 = order("1", &system).await;
+    let order_2 = order("2", &system).await;
+
+    add_item(&order_1, "1", "apple").await;
+    order_1.send(NoteAdded("untagged".into())).await.unwrap();
+    add_item(&order_2, "2", "banana").await;
+    add_item(&order_1, "1", "cherry").await;
+
+    let read_journal = system
+        .persistence()
+        .unwrap()
+        .read_journal()
+        .unwrap()
+        .with_batch_size(2);
+
+    let events: Vec<_> = read_journal
+        .current_events_by_tag("order", 0)
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    let read = |events: &[TaggedEntry]| {
+        events
+            .iter()
+            .map(|e| {
+                let item = e.message::<ItemAdded>().unwrap();
+                (e.persistence_id.clone(), item.order_id, item.item)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        read(&events),
+        vec![
+            ("order-1".to_string(), "1".to_string(), "apple".to_string()),
+            ("order-2".to_string(), "2".to_string(), "banana".to_string()),
+            ("order-1".to_string(), "1".to_string(), "cherry".to_string()),
+        ]
+    );
+
+    assert!(events
+        .iter()
+        .all(|e| e.entry.payload_type.as_ref() == "item-added"));
+
+    // resuming from the offset of an event only reads the events after it
+    let resumed: Vec<_> = read_journal
+        .current_events_by_tag("order", events[0].offset)
+        .map(|e| e.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(read(&resumed), read(&events[1..]));
+
+    let none: Vec<_> = read_journal
+        .current_events_by_tag("shipment", 0)
+        .collect()
+        .await;
+
+    assert!(none.is_empty());
+
+    system.shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_persistent_events_by_tag_live() {
+    util::create_trace_logger();
+
+    let system =
+        ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let order_1 = order("1", &system).await;
+    add_item(&order_1, "1", "apple").await;
+
+    let read_journal = system
+        .persistence()
+        .unwrap()
+        .read_journal_for::<Order>()
+        .unwrap()
+        .with_poll_interval(Duration::from_millis(10));
+
+    let mut events = Box::pin(read_journal.events_by_tag("order", 0));
+    let first = events.next().await.unwrap().unwrap();
+    assert_eq!(first.message::<ItemAdded>().unwrap().item, "apple");
+
+    // the stream waits for events persisted after it caught up
+    let order_2 = order("2", &system).await;
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        add_item(&order_2, "2", "banana").await;
+    });
+
+    let next = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("event persisted after the stream caught up")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(next.persistence_id, "order-2");
+    assert_eq!(next.message::<ItemAdded>().unwrap().item, "banana");
+    assert!(next.offset > first.offset);
+
+    system.shutdown().await;
+}
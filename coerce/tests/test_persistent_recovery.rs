@@ -5,6 +5,7 @@ use coerce::actor::message::Handler;
 use coerce::actor::system::ActorSystem;
 use coerce::actor::IntoActor;
 
+use coerce::persistent::journal::metadata::EventMetadata;
 use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
 use coerce::persistent::journal::provider::StorageProvider;
 use coerce::persistent::journal::storage::JournalEntry;
@@ -87,6 +88,7 @@ pub async fn test_in_memory_delete_messages() {
                 sequence: 1,
                 payload_type: "hello".into(),
                 bytes: Arc::new(vec![]),
+                metadata: EventMetadata::default(),
             },
         )
         .await
@@ -99,6 +101,7 @@ pub async fn test_in_memory_delete_messages() {
                 sequence: 2,
                 payload_type: "hello".into(),
                 bytes: Arc::new(vec![]),
+                metadata: EventMetadata::default(),
             },
         )
         .await
@@ -111,6 +114,7 @@ pub async fn test_in_memory_delete_messages() {
                 sequence: 3,
                 payload_type: "hello".into(),
                 bytes: Arc::new(vec![]),
+                metadata: EventMetadata::default(),
             },
         )
         .await
@@ -158,7 +162,7 @@ pub async fn test_persistent_actor_message_recovery() {
         .await
         .unwrap());
 
-    actor.stop().await.unwrap();
+    actor.stop(false).await.unwrap();
 
     let actor = create_empty_actor()
         .into_actor(Some("hi".to_string()), &system)
@@ -175,3 +179,59 @@ pub async fn test_persistent_actor_message_recovery() {
 
     system.shutdown().await;
 }
+
+#[tokio::test]
+pub async fn test_persistent_actor_sequence_after_recovery() {
+    util::create_trace_logger();
+
+    let provider = InMemoryStorageProvider::new();
+    let journal = provider.journal_storage().expect("journal storage");
+    let system = ActorSystem::new().to_persistent(Persistence::from(provider));
+
+    let id = 2;
+    let create_empty_actor = || TestActor {
+        id,
+        received_numbers: vec![],
+    };
+
+    let actor = create_empty_actor()
+        .into_actor(Some("sequence".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    actor.send(Msg(1)).await.unwrap();
+    actor.send(Msg(2)).await.unwrap();
+    actor.stop(false).await.unwrap();
+
+    // the recovered actor continues from the last recovered sequence, rather than re-using it
+    let actor = create_empty_actor()
+        .into_actor(Some("sequence".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    actor.send(Msg(3)).await.unwrap();
+    actor.stop(false).await.unwrap();
+
+    let sequences: Vec<i64> = journal
+        .read_latest_messages("test-actor-2", 0)
+        .await
+        .unwrap()
+        .unwrap()
+        .iter()
+        .map(|entry| entry.sequence)
+        .collect();
+
+    assert_eq!(sequences, vec![1, 2, 3]);
+
+    let actor = create_empty_actor()
+        .into_actor(Some("sequence".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    assert_eq!(
+        actor.exec(|a| a.received_numbers.clone()).await.unwrap(),
+        vec![1, 2, 3]
+    );
+
+    system.shutdown().await;
+}
@@ -0,0 +1,160 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActor;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::snapshot::SnapshotPolicy;
+use coerce::persistent::journal::types::JournalTypes;
+use coerce::persistent::journal::PersistErr;
+use coerce::persistent::{Persistence, PersistentActor, Recover, RecoverSnapshot};
+use coerce_macros::{JsonMessage, JsonSnapshot};
+use std::time::Duration;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+const PERSISTENCE_KEY: &str = "snapshot-policy-counter";
+
+#[derive(Default)]
+struct Counter {
+    count: i32,
+    recovered_snapshot: Option<i32>,
+    recovered_messages: Vec<i32>,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct Increment(i32);
+
+#[derive(JsonSnapshot, Serialize, Deserialize)]
+struct CounterSnapshot {
+    count: i32,
+}
+
+#[async_trait]
+impl PersistentActor for Counter {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        PERSISTENCE_KEY.to_string()
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal
+            .snapshot::<CounterSnapshot>("counter-snapshot")
+            .message::<Increment>("increment");
+    }
+
+    fn snapshot_policy(&self) -> SnapshotPolicy {
+        SnapshotPolicy::every(3).keep_snapshots(1).delete_messages()
+    }
+
+    async fn on_snapshot_due(&mut self, ctx: &mut ActorContext) -> Result<(), PersistErr> {
+        self.snapshot(CounterSnapshot { count: self.count }, ctx)
+            .await
+    }
+}
+
+#[async_trait]
+impl Handler<Increment> for Counter {
+    async fn handle(&mut self, message: Increment, ctx: &mut ActorContext) {
+        if self.persist(&message, ctx).await.is_ok() {
+            self.count += message.0;
+        }
+    }
+}
+
+#[async_trait]
+impl Recover<Increment> for Counter {
+    async fn recover(&mut self, message: Increment, _ctx: &mut ActorContext) {
+        self.count += message.0;
+        self.recovered_messages.push(message.0);
+    }
+}
+
+#[async_trait]
+impl RecoverSnapshot<CounterSnapshot> for Counter {
+    async fn recover(&mut self, snapshot: CounterSnapshot, _ctx: &mut ActorContext) {
+        self.count = snapshot.count;
+        self.recovered_snapshot = Some(snapshot.count);
+    }
+}
+
+#[tokio::test]
+pub async fn test_snapshot_every_n_events() {
+    util::create_trace_logger();
+
+    let provider = InMemoryStorageProvider::new();
+    let storage = provider.journal_storage().unwrap();
+    let system = ActorSystem::new().to_persistent(Persistence::from(provider));
+
+    let counter = Counter::default()
+        .into_actor(Some("counter".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    for i in 1..=7 {
+        counter.send(Increment(i)).await.unwrap();
+    }
+
+    // snapshots are taken once the message that made them due has been handled
+    let mut snapshot = None;
+    for _ in 0..50 {
+        snapshot = storage.read_latest_snapshot(PERSISTENCE_KEY).await.unwrap();
+        if snapshot.is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert!(snapshot.is_some());
+
+    // messages covered by the latest snapshot have been deleted
+    let messages = storage
+        .read_latest_messages(PERSISTENCE_KEY, 0)
+        .await
+        .unwrap()
+        .unwrap_or_default();
+
+    assert_eq!(messages.len(), 1);
+
+    counter.stop(false).await.unwrap();
+
+    let counter = Counter::default()
+        .into_actor(Some("counter".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    let (count, recovered_snapshot, recovered_messages) = counter
+        .exec(|c| (c.count, c.recovered_snapshot, c.recovered_messages.clone()))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 28);
+    assert_eq!(recovered_snapshot, Some(21));
+    assert_eq!(recovered_messages, vec![7]);
+
+    system.shutdown().await;
+}
+
+#[test]
+pub fn test_snapshot_policy_is_snapshot_due() {
+    let policy = SnapshotPolicy::default();
+    assert!(!policy.is_snapshot_due(1000));
+
+    let policy = SnapshotPolicy::every(3);
+    assert!(!policy.is_snapshot_due(2));
+    assert!(policy.is_snapshot_due(3));
+    assert!(policy.is_snapshot_due(4));
+
+    assert!(!SnapshotPolicy::every(0).is_snapshot_due(1));
+    assert_eq!(
+        SnapshotPolicy::every(3).keep_snapshots(0).keep_snapshots,
+        Some(1)
+    );
+}
@@ -0,0 +1,228 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Envelope, Handler, MessageUnwrapErr, MessageWrapErr};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActor;
+use coerce::persistent::journal::metadata::EventMetadata;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::snapshot::{Snapshot, SnapshotEnvelope};
+use coerce::persistent::journal::storage::JournalEntry;
+use coerce::persistent::journal::types::JournalTypes;
+use coerce::persistent::journal::RecoveryErr;
+use coerce::persistent::{Persistence, PersistentActor, RecoverSnapshot};
+use coerce_macros::{JsonMessage, JsonSnapshot};
+use std::sync::Arc;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+const PERSISTENCE_KEY: &str = "counter";
+const SNAPSHOT_TYPE: &str = "counter-snapshot";
+
+#[derive(JsonSnapshot, Serialize, Deserialize)]
+struct CounterSnapshotV1 {
+    count: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct CounterSnapshotV2 {
+    total: i64,
+    label: String,
+}
+
+impl Snapshot for CounterSnapshotV2 {
+    fn into_remote_envelope(self) -> Result<Envelope<Self>, MessageWrapErr> {
+        serde_json::to_vec(&self)
+            .map_err(|_| MessageWrapErr::SerializationErr)
+            .map(Envelope::Remote)
+    }
+
+    fn from_remote_envelope(bytes: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        serde_json::from_slice(&bytes).map_err(|_| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn schema_version() -> u32 {
+        2
+    }
+
+    fn serializer_id() -> &'static str {
+        "json"
+    }
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct TakeSnapshot(i32);
+
+#[derive(Default)]
+struct CounterV1;
+
+#[derive(Default)]
+struct CounterV2 {
+    snapshot: Option<CounterSnapshotV2>,
+}
+
+#[async_trait]
+impl PersistentActor for CounterV1 {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        PERSISTENCE_KEY.to_string()
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal.snapshot::<CounterSnapshotV1>(SNAPSHOT_TYPE);
+    }
+}
+
+#[async_trait]
+impl RecoverSnapshot<CounterSnapshotV1> for CounterV1 {
+    async fn recover(&mut self, _snapshot: CounterSnapshotV1, _ctx: &mut ActorContext) {}
+}
+
+#[async_trait]
+impl Handler<TakeSnapshot> for CounterV1 {
+    async fn handle(&mut self, message: TakeSnapshot, ctx: &mut ActorContext) {
+        self.snapshot(CounterSnapshotV1 { count: message.0 }, ctx)
+            .await
+            .expect("snapshot persisted");
+    }
+}
+
+fn upcast_v1(envelope: SnapshotEnvelope) -> anyhow::Result<Vec<u8>> {
+    assert_eq!(envelope.schema_version, 1);
+
+    let v1: serde_json::Value = serde_json::from_slice(&envelope.payload)?;
+    let v2 = CounterSnapshotV2 {
+        total: v1["count"].as_i64().unwrap_or_default(),
+        label: "upcast".to_string(),
+    };
+
+    Ok(serde_json::to_vec(&v2)?)
+}
+
+#[async_trait]
+impl PersistentActor for CounterV2 {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        PERSISTENCE_KEY.to_string()
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal
+            .snapshot::<CounterSnapshotV2>(SNAPSHOT_TYPE)
+            .snapshot_upcaster::<CounterSnapshotV2>(1, upcast_v1);
+    }
+}
+
+#[async_trait]
+impl RecoverSnapshot<CounterSnapshotV2> for CounterV2 {
+    async fn recover(&mut self, snapshot: CounterSnapshotV2, _ctx: &mut ActorContext) {
+        self.snapshot = Some(snapshot);
+    }
+}
+
+#[tokio::test]
+pub async fn test_snapshot_upcast_on_recovery() {
+    util::create_trace_logger();
+
+    let system =
+        ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+
+    let counter = CounterV1
+        .into_actor(Some("counter".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    counter.send(TakeSnapshot(42)).await.unwrap();
+    counter.stop(false).await.unwrap();
+
+    let counter = CounterV2::default()
+        .into_actor(Some("counter".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    let snapshot = counter.exec(|a| a.snapshot.clone()).await.unwrap();
+    assert_eq!(
+        snapshot,
+        Some(CounterSnapshotV2 {
+            total: 42,
+            label: "upcast".to_string(),
+        })
+    );
+
+    system.shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_legacy_snapshot_read_as_first_schema_version() {
+    util::create_trace_logger();
+
+    let storage = InMemoryStorageProvider::new();
+    storage
+        .journal_storage()
+        .unwrap()
+        .write_snapshot(
+            PERSISTENCE_KEY,
+            JournalEntry {
+                sequence: 1,
+                payload_type: SNAPSHOT_TYPE.into(),
+                bytes: Arc::new(br#"{"count":7}"#.to_vec()),
+                metadata: EventMetadata::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+    let system = ActorSystem::new().to_persistent(Persistence::from(storage));
+    let counter = CounterV2::default()
+        .into_actor(Some("counter".to_string()), &system)
+        .await
+        .expect("create actor");
+
+    let snapshot = counter.exec(|a| a.snapshot.clone()).await.unwrap();
+    assert_eq!(
+        snapshot,
+        Some(CounterSnapshotV2 {
+            total: 7,
+            label: "upcast".to_string(),
+        })
+    );
+
+    system.shutdown().await;
+}
+
+#[test]
+pub fn test_snapshot_envelope_schema_versions() {
+    let envelope = SnapshotEnvelope::new::<CounterSnapshotV2>(b"{}".to_vec());
+    assert_eq!(envelope.schema_version, CounterSnapshotV2::schema_version());
+    assert_eq!(envelope.serializer_id, "json");
+    assert_eq!(
+        SnapshotEnvelope::from_bytes(envelope.clone().into_bytes()),
+        envelope
+    );
+
+    let mut types = JournalTypes::<CounterV2>::default();
+    types.snapshot::<CounterSnapshotV2>(SNAPSHOT_TYPE);
+
+    // no upcaster from version 1 is registered
+    let v1 = SnapshotEnvelope::new::<CounterSnapshotV1>(b"{}".to_vec());
+    assert!(matches!(
+        types.upcast_snapshot(SNAPSHOT_TYPE, v1),
+        Err(RecoveryErr::SnapshotSchema {
+            schema_version: 1,
+            current_schema_version: 2,
+            ..
+        })
+    ));
+
+    // snapshots written by a newer schema version can't be read
+    let v3 = SnapshotEnvelope {
+        schema_version: 3,
+        ..envelope
+    };
+
+    assert!(types.upcast_snapshot(SNAPSHOT_TYPE, v3).is_err());
+}
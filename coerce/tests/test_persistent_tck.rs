@@ -0,0 +1,19 @@
+use coerce::journal_provider_tck;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+
+journal_provider_tck!(InMemoryStorageProvider);
+
+mod failover {
+    use coerce::journal_provider_tck;
+    use coerce::persistent::journal::failover::{FailoverConfig, FailoverStorageProvider};
+    use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+
+    journal_provider_tck!(
+        FailoverStorageProvider,
+        FailoverStorageProvider::new(
+            InMemoryStorageProvider::new(),
+            InMemoryStorageProvider::new(),
+            FailoverConfig::default(),
+        )
+    );
+}
@@ -6,8 +6,11 @@ extern crate serde;
 #[macro_use]
 extern crate async_trait;
 
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
 use coerce::actor::system::ActorSystem;
 use coerce::actor::{new_actor_id, Actor, ActorCreationErr, ActorFactory, ActorRecipe, ToActorId};
+use coerce_macros::JsonMessage;
 
 use coerce::remote::system::{RemoteActorErr, RemoteActorSystem};
 use protobuf::Message;
@@ -48,6 +51,17 @@ impl ActorFactory for TestActorFactory {
 
 impl Actor for TestActor {}
 
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("String")]
+pub struct GetName;
+
+#[async_trait]
+impl Handler<GetName> for TestActor {
+    async fn handle(&mut self, _: GetName, _: &mut ActorContext) -> String {
+        self.name.clone()
+    }
+}
+
 #[tokio::test]
 pub async fn test_remote_actor_deploy_remotely() {
     util::create_trace_logger();
@@ -58,7 +72,11 @@ pub async fn test_remote_actor_deploy_remotely() {
     let sys = ActorSystem::new();
     let remote = RemoteActorSystem::builder()
         .with_actor_system(sys)
-        .with_actors(|builder| builder.with_actor::<TestActorFactory>(TestActorFactory {}))
+        .with_actors(|builder| {
+            builder
+                .with_actor::<TestActorFactory>(TestActorFactory {})
+                .with_handler::<TestActor, GetName>("TestActor.GetName")
+        })
         .with_tag("system-a")
         .with_id(1)
         .build()
@@ -67,6 +85,7 @@ pub async fn test_remote_actor_deploy_remotely() {
     let sys = ActorSystem::new();
     let remote_b = RemoteActorSystem::builder()
         .with_actor_system(sys)
+        .with_handlers(|builder| builder.with_handler::<TestActor, GetName>("TestActor.GetName"))
         .with_tag("system-b")
         .with_id(2)
         .build()
@@ -88,7 +107,7 @@ pub async fn test_remote_actor_deploy_remotely() {
         .await;
 
     let recipe = TestActorRecipe {
-        name: expected_actor_name,
+        name: expected_actor_name.clone(),
     };
 
     let deployment_result = remote_b
@@ -99,7 +118,37 @@ pub async fn test_remote_actor_deploy_remotely() {
         )
         .await;
 
-    assert_eq!(deployment_result.is_ok(), true);
+    let actor_ref = deployment_result.expect("actor deployed");
+    assert_eq!(actor_ref.send(GetName).await.unwrap(), expected_actor_name);
+    assert_eq!(
+        remote_b.locate_actor_node(actor_id.to_actor_id()).await,
+        Some(remote.node_id())
+    );
+
+    let duplicate = remote_b
+        .deploy_actor::<TestActorFactory>(
+            Some(actor_id.to_actor_id()),
+            TestActorRecipe {
+                name: "duplicate".to_string(),
+            },
+            Some(remote.node_id()),
+        )
+        .await;
+
+    assert_eq!(duplicate.err(), Some(RemoteActorErr::ActorExists));
+
+    // node b has no factory registered for the actor
+    let unsupported = remote
+        .deploy_actor::<TestActorFactory>(
+            None,
+            TestActorRecipe {
+                name: "unsupported".to_string(),
+            },
+            Some(remote_b.node_id()),
+        )
+        .await;
+
+    assert_eq!(unsupported.err(), Some(RemoteActorErr::ActorNotSupported));
 }
 
 #[tokio::test]
@@ -66,10 +66,10 @@ pub async fn test_remote_actor_err() {
     let status = actor_ref.send(GetStatusRequest).await;
     assert!(status.is_ok());
 
-    let _ = local_ref.stop().await;
+    let _ = local_ref.stop(false).await;
 
     let status = actor_ref.send(GetStatusRequest).await;
-    assert_eq!(status.unwrap_err(), ActorRefErr::NotFound(actor_id.clone()));
+    assert_eq!(status.unwrap_err(), ActorRefErr::ActorUnavailable);
 
     let status = actor_ref.send(NotSerialisable).await;
     assert_eq!(
@@ -92,3 +92,36 @@ pub async fn test_remote_actor_err() {
         }
     );
 }
+
+#[tokio::test]
+pub async fn test_remote_actor_stopped_invalidates_location() {
+    util::create_trace_logger();
+    let node_1 = util::create_cluster_node(1, "localhost:30102", None, |handlers| {
+        remote_handlers(handlers)
+    })
+    .await;
+
+    let node_2 =
+        util::create_cluster_node(2, "localhost:30202", Some("localhost:30102"), |handlers| {
+            remote_handlers(handlers)
+        })
+        .await;
+
+    // node 2 believes the actor is on node 1, but node 1 has no such actor
+    let actor_id = "stopped_actor".to_actor_id();
+    node_2.register_actor(actor_id.clone(), Some(node_1.node_id()));
+    assert_eq!(
+        node_2.locate_actor_node(actor_id.clone()).await,
+        Some(node_1.node_id())
+    );
+
+    let actor_ref = ActorRef::from(RemoteActorRef::<TestActor>::new(
+        actor_id.clone(),
+        node_1.node_id(),
+        node_2.clone(),
+    ));
+
+    let status = actor_ref.send(GetStatusRequest).await;
+    assert_eq!(status.unwrap_err(), ActorRefErr::ActorUnavailable);
+    assert_eq!(node_2.locate_actor_node(actor_id).await, None);
+}
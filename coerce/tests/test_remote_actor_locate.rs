@@ -109,4 +109,13 @@ pub async fn test_remote_actor_locate_remotely() {
 
     assert_eq!(remote_ref.is_remote(), true);
     assert_eq!(local_ref.is_local(), true);
+
+    let location = remote_b
+        .locate("leon")
+        .await
+        .expect("unable to locate actor");
+
+    assert_eq!(location.node_id, 1);
+    assert_eq!(location.addr, "localhost:30101");
+    assert_eq!(location.node_tag, "remote-a");
 }
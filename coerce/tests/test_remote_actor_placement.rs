@@ -0,0 +1,149 @@
+pub mod util;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorRecipe, ToActorId};
+use coerce::remote::system::{RemoteActorSystem, SpawnOptions};
+use std::time::Duration;
+
+pub struct TestActor;
+
+#[derive(Serialize, Deserialize)]
+pub struct TestActorRecipe;
+
+impl ActorRecipe for TestActorRecipe {
+    fn read_from_bytes(bytes: &Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
+
+#[derive(Clone)]
+pub struct TestActorFactory;
+
+#[async_trait]
+impl ActorFactory for TestActorFactory {
+    type Actor = TestActor;
+    type Recipe = TestActorRecipe;
+
+    async fn create(&self, _recipe: Self::Recipe) -> Result<TestActor, ActorCreationErr> {
+        Ok(TestActor)
+    }
+}
+
+impl Actor for TestActor {}
+
+async fn create_system(id: u64, zone: &str) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_actors(|builder| builder.with_actor::<TestActorFactory>(TestActorFactory))
+        .with_tag(format!("system-{}", id))
+        .with_id(id)
+        .attribute("zone", zone)
+        .build()
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_actor_placement_hints() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1, "zone-a").await;
+    let remote_b = create_system(2, "zone-b").await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30601")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30602")
+        .with_seed_addr("localhost:30601")
+        .start()
+        .await;
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    let anchor = remote_b
+        .deploy_actor_with_options::<TestActorFactory>(
+            Some("anchor".to_actor_id()),
+            TestActorRecipe,
+            SpawnOptions::new().on_node(remote_a.node_id()),
+        )
+        .await
+        .expect("deploy anchor");
+
+    assert_eq!(anchor.node_id(), Some(remote_a.node_id()));
+
+    // the actor registry is updated asynchronously, wait for the anchor to be locatable from node 2
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while remote_b
+            .locate_actor_node("anchor".to_actor_id())
+            .await
+            .is_none()
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("anchor registered");
+
+    let co_located = remote_b
+        .deploy_actor_with_options::<TestActorFactory>(
+            Some("co-located".to_actor_id()),
+            TestActorRecipe,
+            SpawnOptions::new().co_locate_with("anchor"),
+        )
+        .await
+        .expect("deploy co-located actor");
+
+    assert_eq!(co_located.node_id(), Some(remote_a.node_id()));
+
+    let in_zone_a = remote_b
+        .deploy_actor_with_options::<TestActorFactory>(
+            Some("in-zone-a".to_actor_id()),
+            TestActorRecipe,
+            SpawnOptions::new().prefer_node_with_attr("zone", "zone-a"),
+        )
+        .await
+        .expect("deploy actor with preferred attribute");
+
+    assert_eq!(in_zone_a.node_id(), Some(remote_a.node_id()));
+
+    // no node matches the hints, so the actors are deployed locally
+    let unmatched_attribute = remote_b
+        .deploy_actor_with_options::<TestActorFactory>(
+            Some("unmatched-attribute".to_actor_id()),
+            TestActorRecipe,
+            SpawnOptions::new().prefer_node_with_attr("zone", "zone-c"),
+        )
+        .await
+        .expect("deploy actor with unmatched attribute");
+
+    let unknown_actor = remote_b
+        .deploy_actor_with_options::<TestActorFactory>(
+            Some("unknown-actor".to_actor_id()),
+            TestActorRecipe,
+            SpawnOptions::new().co_locate_with("does-not-exist"),
+        )
+        .await
+        .expect("deploy actor co-located with unknown actor");
+
+    assert_eq!(unmatched_attribute.node_id(), Some(remote_b.node_id()));
+    assert_eq!(unknown_actor.node_id(), Some(remote_b.node_id()));
+}
@@ -0,0 +1,187 @@
+pub mod util;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorRef, IntoActorId};
+use coerce::remote::system::RemoteActorSystem;
+use coerce::remote::SerializableActorRef;
+use coerce_macros::JsonMessage;
+use std::time::Duration;
+
+#[derive(Default)]
+pub struct Collector {
+    replies: Vec<String>,
+}
+
+impl Actor for Collector {}
+
+pub struct Responder;
+
+impl Actor for Responder {}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+pub struct Request {
+    value: String,
+    reply_to: SerializableActorRef<Collector>,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+pub struct Reply(String);
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("Vec<String>")]
+pub struct GetReplies;
+
+#[async_trait]
+impl Handler<Request> for Responder {
+    async fn handle(&mut self, message: Request, ctx: &mut ActorContext) {
+        let reply_to = message
+            .reply_to
+            .resolve(ctx.system().remote())
+            .await
+            .expect("resolved reply_to");
+
+        reply_to
+            .send(Reply(format!("reply: {}", message.value)))
+            .await
+            .expect("reply sent");
+    }
+}
+
+#[async_trait]
+impl Handler<Reply> for Collector {
+    async fn handle(&mut self, message: Reply, _ctx: &mut ActorContext) {
+        self.replies.push(message.0);
+    }
+}
+
+#[async_trait]
+impl Handler<GetReplies> for Collector {
+    async fn handle(&mut self, _message: GetReplies, _ctx: &mut ActorContext) -> Vec<String> {
+        self.replies.clone()
+    }
+}
+
+async fn create_system(id: u64) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(id)
+        .with_tag(format!("remote-{}", id))
+        .with_handlers(|handlers| {
+            handlers
+                .with_handler::<Responder, Request>("Responder.Request")
+                .with_handler::<Collector, Reply>("Collector.Reply")
+        })
+        .build()
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_actor_ref_embedded_in_message() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1).await;
+    let remote_b = create_system(2).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35221")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35222")
+        .with_seed_addr("localhost:35221")
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    let collector: ActorRef<Collector> = remote_a
+        .actor_system()
+        .new_actor("collector", Collector::default(), Tracked)
+        .await
+        .expect("collector")
+        .into();
+
+    let _ = remote_b
+        .actor_system()
+        .new_actor("responder", Responder, Tracked)
+        .await
+        .expect("responder");
+
+    let responder = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(responder) = remote_a
+                .actor_ref::<Responder>("responder".into_actor_id())
+                .await
+            {
+                break responder;
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("responder located");
+
+    let reply_to = SerializableActorRef::new(&collector, &remote_a);
+    assert_eq!(reply_to.node_id(), remote_a.node_id());
+
+    responder
+        .send(Request {
+            value: "hello".to_string(),
+            reply_to,
+        })
+        .await
+        .expect("request sent");
+
+    let replies = collector.send(GetReplies).await.expect("replies");
+    assert_eq!(replies, vec!["reply: hello".to_string()]);
+}
+
+#[tokio::test]
+pub async fn test_serializable_actor_ref_resolve() {
+    util::create_trace_logger();
+
+    let remote = create_system(1).await;
+    let collector = remote
+        .actor_system()
+        .new_actor("collector", Collector::default(), Tracked)
+        .await
+        .expect("collector");
+
+    let actor_ref = SerializableActorRef::<Collector>::new(&collector.into(), &remote);
+    let json = serde_json::to_vec(&actor_ref).unwrap();
+    let deserialized: SerializableActorRef<Collector> = serde_json::from_slice(&json).unwrap();
+    assert_eq!(deserialized, actor_ref);
+
+    let local = deserialized.resolve(&remote).await.expect("local actor");
+    assert_eq!(local.node_id(), None);
+
+    let remote_ref = SerializableActorRef::<Collector>::from_parts("collector".into_actor_id(), 2)
+        .resolve(&remote)
+        .await
+        .expect("remote actor");
+
+    assert_eq!(remote_ref.node_id(), Some(2));
+
+    let unknown = SerializableActorRef::<Collector>::from_parts("unknown".into_actor_id(), 1);
+    assert!(unknown.resolve(&remote).await.is_none());
+}
@@ -0,0 +1,135 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message};
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::watch::{ActorTerminated, TerminationReason};
+use coerce::actor::{Actor, ActorId, ActorRef, IntoActor, IntoActorId};
+use coerce::remote::actor::message::NodeTerminated;
+use coerce::remote::system::RemoteActorSystem;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+struct Monitor {
+    terminated: mpsc::UnboundedSender<(ActorId, bool, TerminationReason)>,
+}
+
+impl Actor for Monitor {}
+
+struct WatchTarget(ActorRef<util::TestActor>);
+
+impl Message for WatchTarget {
+    type Result = ();
+}
+
+#[async_trait]
+impl Handler<WatchTarget> for Monitor {
+    async fn handle(&mut self, message: WatchTarget, ctx: &mut ActorContext) {
+        ctx.watch::<Self>(&message.0);
+    }
+}
+
+#[async_trait]
+impl Handler<ActorTerminated> for Monitor {
+    async fn handle(&mut self, message: ActorTerminated, _ctx: &mut ActorContext) {
+        let _ = self.terminated.send((
+            message.actor_id().clone(),
+            message.actor_ref().is_some(),
+            message.reason(),
+        ));
+    }
+}
+
+async fn remote_ref(remote: &RemoteActorSystem, actor_id: &str) -> ActorRef<util::TestActor> {
+    // the actor is registered with the remote registry asynchronously
+    for _ in 0..50 {
+        if let Some(actor_ref) = remote
+            .actor_ref::<util::TestActor>(actor_id.into_actor_id())
+            .await
+        {
+            return actor_ref;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    panic!("unable to locate actor {}", actor_id);
+}
+
+#[tokio::test]
+pub async fn test_remote_actor_watch() {
+    util::create_trace_logger();
+
+    let system_a = ActorSystem::new();
+    let system_b = ActorSystem::new();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(system_a.clone())
+        .with_id(1)
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(system_b.clone())
+        .with_id(2)
+        .build()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30151")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30152")
+        .with_seed_addr("localhost:30151")
+        .start()
+        .await;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let monitor = Monitor { terminated: tx }
+        .into_actor(Some("monitor"), &system_a)
+        .await
+        .unwrap();
+
+    let stopped = system_b
+        .new_actor("stopped", util::TestActor::new(), Tracked)
+        .await
+        .unwrap();
+
+    let _lost = system_b
+        .new_actor("lost", util::TestActor::new(), Tracked)
+        .await
+        .unwrap();
+
+    let stopped_ref = remote_ref(&remote_a, "stopped").await;
+    let lost_ref = remote_ref(&remote_a, "lost").await;
+    assert!(stopped_ref.is_remote());
+
+    monitor.send(WatchTarget(stopped_ref)).await.unwrap();
+    monitor.send(WatchTarget(lost_ref)).await.unwrap();
+
+    // give the watches time to be registered with node 2
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    stopped.stop(true).await.unwrap();
+
+    let (actor_id, has_ref, reason) = rx.recv().await.unwrap();
+    assert_eq!(actor_id, "stopped".into_actor_id());
+    assert!(!has_ref);
+    assert_eq!(reason, TerminationReason::Stopped);
+
+    let _ = remote_a.heartbeat().notify(NodeTerminated(2));
+
+    let (actor_id, _, reason) = rx.recv().await.unwrap();
+    assert_eq!(actor_id, "lost".into_actor_id());
+    assert_eq!(reason, TerminationReason::NodeLost(2));
+}
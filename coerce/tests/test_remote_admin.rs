@@ -0,0 +1,148 @@
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::remote::admin::{AdminCommand, AdminConfig, AdminErr, AdminRequest, AdminResponse};
+use coerce::remote::system::RemoteActorSystem;
+use std::sync::{Arc, Mutex};
+
+pub mod util;
+
+const TOKEN: &str = "admin-token";
+
+#[tokio::test]
+pub async fn test_remote_admin_commands() {
+    util::create_trace_logger();
+
+    let log_level = Arc::new(Mutex::new(None));
+    let admin_config = AdminConfig::new(TOKEN).on_set_log_level({
+        let log_level = log_level.clone();
+        move |level| match level {
+            "TRACE" | "DEBUG" | "INFO" | "WARN" | "ERROR" => {
+                *log_level.lock().unwrap() = Some(level.to_string());
+                Ok(())
+            }
+            _ => Err(format!("invalid log level {}", level)),
+        }
+    });
+
+    let system_a = ActorSystem::new();
+    let system_b = ActorSystem::new();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(system_a)
+        .with_id(1)
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(system_b.clone())
+        .with_id(2)
+        .with_admin(admin_config)
+        .build()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30153")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30154")
+        .with_seed_addr("localhost:30153")
+        .start()
+        .await;
+
+    let actor = system_b
+        .new_actor("test-actor", util::TestActor::new(), Tracked)
+        .await
+        .unwrap();
+
+    let admin = |token: &str, command| remote_a.node_admin(2, AdminRequest::new(token, command));
+
+    let response = admin("incorrect-token", AdminCommand::ListActors)
+        .await
+        .unwrap();
+    assert_eq!(response, AdminResponse::Err(AdminErr::Unauthorized));
+
+    let AdminResponse::Actors(actors) = admin(TOKEN, AdminCommand::ListActors).await.unwrap()
+    else {
+        panic!("expected actors");
+    };
+
+    let test_actor = actors.iter().find(|a| a.actor_id == "test-actor").unwrap();
+    assert_eq!(test_actor.actor_type, "test_remote_admin::util::TestActor");
+    assert!(actors.iter().any(|a| a.actor_id == "node-admin"));
+
+    let AdminResponse::Metrics(metrics) = admin(TOKEN, AdminCommand::DumpMetrics).await.unwrap()
+    else {
+        panic!("expected metrics");
+    };
+
+    assert_eq!(metrics.node_id, 2);
+    assert_eq!(metrics.tracked_actors, actors.len());
+
+    let response = admin(
+        TOKEN,
+        AdminCommand::SetLogLevel {
+            level: "DEBUG".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        response,
+        AdminResponse::LogLevelSet {
+            level: "DEBUG".to_string()
+        }
+    );
+    assert_eq!(log_level.lock().unwrap().as_deref(), Some("DEBUG"));
+
+    let response = admin(
+        TOKEN,
+        AdminCommand::SetLogLevel {
+            level: "LOUD".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    assert!(matches!(response, AdminResponse::Err(AdminErr::Failed(_))));
+
+    let response = admin(
+        TOKEN,
+        AdminCommand::StopActor {
+            actor_id: "test-actor".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        response,
+        AdminResponse::StopRequested {
+            actor_id: "test-actor".to_string()
+        }
+    );
+
+    actor.wait_for_stop().await;
+
+    let response = admin(
+        TOKEN,
+        AdminCommand::StopActor {
+            actor_id: "test-actor".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        response,
+        AdminResponse::Err(AdminErr::ActorNotFound("test-actor".to_string()))
+    );
+
+    // admin isn't enabled on node 1
+    let response = remote_b
+        .node_admin(1, AdminRequest::new(TOKEN, AdminCommand::ListActors))
+        .await;
+    assert!(response.is_err());
+}
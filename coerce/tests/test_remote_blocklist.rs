@@ -0,0 +1,158 @@
+use coerce::actor::system::ActorSystem;
+use coerce::remote::cluster::blocklist::{BlockedNode, NodeBlocklist};
+use coerce::remote::cluster::builder::worker::BootstrapFallback;
+use coerce::remote::cluster::node::NodeStatus;
+use coerce::remote::system::RemoteActorSystem;
+use std::time::Duration;
+
+pub mod util;
+
+#[test]
+pub fn test_blocklist_persisted() {
+    let path = std::env::temp_dir().join(format!("coerce-blocklist-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let blocklist = NodeBlocklist::persisted(&path);
+    assert!(blocklist.block(BlockedNode::Id(2)));
+    assert!(blocklist.block(BlockedNode::Addr("127.0.0.1:35202".to_string())));
+    assert!(!blocklist.block(BlockedNode::Id(2)));
+
+    assert!(blocklist.is_blocked(2, "127.0.0.1:1234"));
+    assert!(blocklist.is_blocked(3, "127.0.0.1:35202"));
+    assert!(!blocklist.is_blocked(3, "127.0.0.1:1234"));
+
+    let reloaded = NodeBlocklist::persisted(&path);
+    assert_eq!(reloaded.entries(), blocklist.entries());
+
+    assert!(reloaded.unblock(&BlockedNode::Id(2)));
+    assert!(!reloaded.unblock(&BlockedNode::Id(2)));
+    assert_eq!(
+        NodeBlocklist::persisted(&path).entries(),
+        vec![BlockedNode::Addr("127.0.0.1:35202".to_string())]
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+pub async fn test_remote_node_downed_and_unblocked() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_tag("remote-a")
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .build()
+        .await;
+
+    let _server_a = remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35201")
+        .start()
+        .await;
+
+    let _server_b = remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35202")
+        .with_seed_addr("127.0.0.1:35201")
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    assert!(!remote_a.down(1).await);
+    assert!(remote_a.down(2).await);
+
+    assert_eq!(
+        remote_a.blocked_nodes(),
+        vec![
+            BlockedNode::Id(2),
+            BlockedNode::Addr("127.0.0.1:35202".to_string())
+        ]
+    );
+
+    let node_b = remote_a
+        .get_nodes()
+        .await
+        .into_iter()
+        .find(|n| n.id == 2)
+        .unwrap();
+
+    assert_eq!(node_b.status, NodeStatus::Quarantined);
+
+    // the node is refused whilst it's blocked, even though it's still running
+    assert!(!remote_a.rejoin_node(2).await);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(remote_a
+        .wait_for_members(2, Duration::from_millis(100))
+        .await
+        .is_err());
+
+    assert!(remote_a.unblock_node(BlockedNode::Id(2)));
+    assert!(remote_a.unblock_node(BlockedNode::Addr("127.0.0.1:35202".to_string())));
+    assert!(remote_a.blocked_nodes().is_empty());
+
+    assert!(remote_a.rejoin_node(2).await);
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("unblocked node rejoined");
+}
+
+#[tokio::test]
+pub async fn test_remote_blocked_node_refused_without_authenticator() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_tag("remote-a")
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .build()
+        .await;
+
+    assert!(remote_a.block_node(BlockedNode::Id(2)));
+
+    let _server_a = remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35203")
+        .start()
+        .await;
+
+    // neither node has an authenticator, the blocked node's handshake is still refused
+    let server_b = remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35204")
+        .with_seed_addr("127.0.0.1:35203")
+        .bootstrap_timeout(Duration::from_secs(1), BootstrapFallback::Abort)
+        .try_start()
+        .await;
+
+    assert!(server_b.is_err());
+    assert!(remote_a
+        .get_nodes()
+        .await
+        .into_iter()
+        .all(|node| node.id != 2));
+}
@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use bytes::{BufMut, Bytes, BytesMut};
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorRef, IntoActorId};
+use coerce::remote::net::chunk::ChunkingConfig;
+use coerce::remote::net::NetworkCodec;
+use coerce::remote::system::RemoteActorSystem;
+use coerce_macros::JsonMessage;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_util::codec::{Decoder, Encoder};
+
+pub mod util;
+
+#[derive(JsonMessage, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[result("usize")]
+pub struct Payload(String);
+
+#[derive(JsonMessage, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[result("String")]
+pub struct Echo(String);
+
+pub struct PayloadActor;
+
+impl Actor for PayloadActor {}
+
+#[async_trait]
+impl Handler<Payload> for PayloadActor {
+    async fn handle(&mut self, message: Payload, _ctx: &mut ActorContext) -> usize {
+        message.0.len()
+    }
+}
+
+#[async_trait]
+impl Handler<Echo> for PayloadActor {
+    async fn handle(&mut self, message: Echo, _ctx: &mut ActorContext) -> String {
+        message.0
+    }
+}
+
+async fn create_system(node_id: u64, chunking: ChunkingConfig) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_handlers(move |handlers| {
+            handlers
+                .with_handler::<PayloadActor, Payload>("PayloadActor.Payload")
+                .with_handler::<PayloadActor, Echo>("PayloadActor.Echo")
+                .chunking(chunking)
+        })
+        .build()
+        .await
+}
+
+/// Creates a 2 node cluster, with an actor on the first node, returning a reference to the actor
+/// from the second node
+async fn create_cluster(
+    chunking_a: ChunkingConfig,
+    chunking_b: ChunkingConfig,
+    addr_a: &str,
+    addr_b: &str,
+) -> (RemoteActorSystem, RemoteActorSystem, ActorRef<PayloadActor>) {
+    let remote_a = create_system(1, chunking_a).await;
+    let remote_b = create_system(2, chunking_b).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr(addr_a)
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr(addr_b)
+        .with_seed_addr(addr_a)
+        .start()
+        .await;
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let _ = remote_a
+        .actor_system()
+        .new_actor("payload-actor", PayloadActor, Tracked)
+        .await
+        .unwrap();
+
+    // the actor is registered with the second node asynchronously
+    let mut actor = None;
+    for _ in 0..50 {
+        actor = remote_b
+            .actor_ref::<PayloadActor>("payload-actor".into_actor_id())
+            .await;
+
+        if actor.is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let actor = actor.expect("unable to get remote ref");
+    assert!(actor.is_remote());
+
+    (remote_a, remote_b, actor)
+}
+
+#[tokio::test]
+pub async fn test_remote_chunked_messages() {
+    util::create_trace_logger();
+
+    let chunking = ChunkingConfig::default().threshold(64 * 1024);
+    let (remote_a, remote_b, actor) =
+        create_cluster(chunking, chunking, "localhost:35171", "localhost:35172").await;
+
+    // larger than the threshold, in both directions
+    let payload: String = (0..1024 * 1024)
+        .map(|i| char::from(b'a' + (i % 26) as u8))
+        .collect();
+    assert_eq!(actor.send(Echo(payload.clone())).await, Ok(payload));
+
+    // smaller than the threshold
+    assert_eq!(actor.send(Payload("abc".to_string())).await, Ok(3));
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_message_larger_than_frame_limit() {
+    util::create_trace_logger();
+
+    let (remote_a, remote_b, actor) = create_cluster(
+        ChunkingConfig::default(),
+        ChunkingConfig::default(),
+        "localhost:35173",
+        "localhost:35174",
+    )
+    .await;
+
+    // larger than the maximum length of a single frame (8MB)
+    let payload = "a".repeat(10 * 1024 * 1024);
+    assert_eq!(actor.send(Payload(payload)).await, Ok(10 * 1024 * 1024));
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_chunked_message_exceeding_max_message_size() {
+    util::create_trace_logger();
+
+    let (remote_a, remote_b, actor) = create_cluster(
+        ChunkingConfig::default()
+            .threshold(64 * 1024)
+            .max_message_size(1024 * 1024),
+        ChunkingConfig::default().threshold(64 * 1024),
+        "localhost:35175",
+        "localhost:35176",
+    )
+    .await;
+
+    // the receiving node refuses to reassemble the message, and closes the connection
+    let res = tokio::time::timeout(
+        Duration::from_secs(2),
+        actor.send(Payload("a".repeat(2 * 1024 * 1024))),
+    )
+    .await;
+
+    assert!(!matches!(res, Ok(Ok(_))));
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
+
+#[test]
+pub fn test_remote_too_many_partial_chunked_messages() {
+    let mut codec = NetworkCodec::new();
+    let mut buf = BytesMut::new();
+
+    // the first chunk of a message (chunk flag, message id, not the last chunk), which is never
+    // followed by the rest of the message
+    let mut partial_chunk = |id: u32| {
+        let mut chunk = BytesMut::new();
+        chunk.put_u8(0x40);
+        chunk.put_u32(id);
+        chunk.put_u8(0);
+        chunk.put_slice(b"partial");
+
+        codec.encode(Bytes::from(chunk), &mut buf).unwrap();
+        codec.decode(&mut buf)
+    };
+
+    // up to 64 messages can be partially received by default
+    for id in 0..64 {
+        assert!(matches!(partial_chunk(id), Ok(None)));
+    }
+
+    // and starting any more fails, which closes the connection
+    assert!(partial_chunk(64).is_err());
+}
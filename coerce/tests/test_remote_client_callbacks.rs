@@ -0,0 +1,202 @@
+use bytes::Bytes;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::LocalActorRef;
+use coerce::remote::actor::message::NewClient;
+use coerce::remote::heartbeat::HeartbeatConfig;
+use coerce::remote::net::client::reconnect::ReconnectPolicy;
+use coerce::remote::net::client::retry::ExchangeRetryPolicy;
+use coerce::remote::net::client::status::GetClientStatus;
+use coerce::remote::net::client::{ClientType, HandshakeErr, RemoteClient, RemoteClientRef};
+use coerce::remote::net::message::{ClientEvent, SessionEvent};
+use coerce::remote::net::proto::network::NodeIdentity;
+use coerce::remote::net::version::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use futures::{SinkExt, StreamExt};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::Notify;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use uuid::Uuid;
+
+pub mod util;
+
+/// Long enough that a handshake is only resolved by the client, never by timing out
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn retry_policy() -> ExchangeRetryPolicy {
+    ExchangeRetryPolicy {
+        handshake_timeout: HANDSHAKE_TIMEOUT,
+        handshake_attempts: 1,
+        ..Default::default()
+    }
+}
+
+#[derive(Default)]
+struct TestNode {
+    /// Notified once the client has pinged the node
+    pinged: Notify,
+
+    /// The node drops the connection once notified
+    disconnect: Notify,
+}
+
+/// A node that identifies itself but never acknowledges handshakes
+async fn start_node(addr: &'static str) -> Arc<TestNode> {
+    let listener = TcpListener::bind(addr).await.unwrap();
+    let node = Arc::new(TestNode::default());
+    let test_node = node.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+            let _identify = framed.next().await;
+
+            let identity = ClientEvent::Identity(NodeIdentity {
+                node_id: 2,
+                addr: addr.to_string(),
+                min_protocol_version: MIN_PROTOCOL_VERSION,
+                max_protocol_version: PROTOCOL_VERSION,
+                ..Default::default()
+            });
+
+            let identity = Bytes::from(identity.write_to_bytes().unwrap());
+            let _ = framed.send(identity).await;
+
+            let node = test_node.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        frame = framed.next() => match frame {
+                            Some(Ok(frame)) => {
+                                if let Some(SessionEvent::Ping(_)) =
+                                    SessionEvent::read_from_slice(&frame)
+                                {
+                                    node.pinged.notify_one();
+                                }
+                            }
+                            _ => return,
+                        },
+                        _ = node.disconnect.notified() => return,
+                    }
+                }
+            });
+        }
+    });
+
+    node
+}
+
+async fn create_client(addr: &str) -> (RemoteActorSystem, LocalActorRef<RemoteClient>) {
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .configure(|c| {
+            // the client pings the node as soon as it connects, then not again during the test
+            c.exchange_retry_policy(retry_policy())
+                .reconnect_policy(ReconnectPolicy::fixed(Duration::from_secs(30), None))
+                .heartbeat(HeartbeatConfig {
+                    interval: Duration::from_secs(30),
+                    ..Default::default()
+                })
+        })
+        .build()
+        .await;
+
+    let client = remote
+        .client_registry()
+        .send(NewClient {
+            addr: addr.to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    (remote, client)
+}
+
+#[tokio::test]
+pub async fn test_remote_client_handshake_resolved_on_disconnect() {
+    util::create_trace_logger();
+    let node = start_node("127.0.0.1:35265").await;
+
+    let (remote, client) = create_client("127.0.0.1:35265").await;
+    let client_ref = RemoteClientRef::from(client.clone());
+
+    // a client that pings a node after being disconnected forgets the node, stopping the client
+    node.pinged.notified().await;
+
+    let handshake = {
+        let client_ref = RemoteClientRef::from(client.clone());
+        tokio::spawn(async move {
+            client_ref
+                .handshake(Uuid::new_v4(), vec![], &retry_policy())
+                .await
+        })
+    };
+
+    // wait for the handshake to be pending, before the node drops the connection
+    let _ = client.send(GetClientStatus).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let start = Instant::now();
+    node.disconnect.notify_one();
+
+    let result = handshake.await.unwrap();
+    assert!(matches!(result, Err(HandshakeErr::Disconnected)));
+    assert!(start.elapsed() < HANDSHAKE_TIMEOUT);
+
+    // the client waits to re-connect, handshakes fail rather than waiting to time out
+    let result = client_ref
+        .handshake(Uuid::new_v4(), vec![], &retry_policy())
+        .await;
+
+    assert!(matches!(result, Err(HandshakeErr::NotConnected)));
+
+    remote.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_client_handshake_resolved_on_stop() {
+    util::create_trace_logger();
+    start_node("127.0.0.1:35266").await;
+
+    let (remote, client) = create_client("127.0.0.1:35266").await;
+    let client_ref = RemoteClientRef::from(client.clone());
+
+    let handshakes = (0..3)
+        .map(|_| {
+            let client_ref = RemoteClientRef::from(client.clone());
+            tokio::spawn(async move {
+                client_ref
+                    .handshake(Uuid::new_v4(), vec![], &retry_policy())
+                    .await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // wait for every handshake to be pending, before the client is stopped
+    let _ = client.send(GetClientStatus).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let start = Instant::now();
+    client.notify_stop().unwrap();
+
+    for handshake in handshakes {
+        let result = handshake.await.unwrap();
+        assert!(matches!(result, Err(HandshakeErr::ClientStopped)));
+    }
+
+    assert!(start.elapsed() < HANDSHAKE_TIMEOUT);
+
+    // once stopped, handshakes fail immediately rather than waiting to time out
+    let result = client_ref
+        .handshake(Uuid::new_v4(), vec![], &retry_policy())
+        .await;
+
+    assert!(matches!(result, Err(HandshakeErr::Actor(_))));
+
+    remote.actor_system().shutdown().await;
+}
@@ -0,0 +1,133 @@
+use coerce::actor::system::ActorSystem;
+use coerce::actor::LocalActorRef;
+use coerce::remote::actor::message::{GetClients, NewClient};
+use coerce::remote::net::client::{ClientType, RemoteClient, RemoteClientRef};
+use coerce::remote::system::RemoteActorSystem;
+use std::time::Duration;
+
+pub mod util;
+
+async fn new_client(remote: &RemoteActorSystem, addr: &str) -> LocalActorRef<RemoteClient> {
+    remote
+        .client_registry()
+        .send(NewClient {
+            addr: addr.to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .expect("client registry")
+        .expect("client")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_client_duplicate_connections_deduped() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_tag("remote-a")
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .build()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30401")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30402")
+        .start()
+        .await;
+
+    // node 1 is dialled via an address other than the one it advertises, and then again via
+    // the address it advertises, resulting in two connections to the same node.
+    let alias_client = new_client(&remote_b, "127.0.0.1:30401").await;
+    let identity = RemoteClientRef::from(alias_client.clone()).identify().await;
+    assert_eq!(identity.unwrap().node.id, 1);
+
+    let client = new_client(&remote_b, "localhost:30401").await;
+    let identity = RemoteClientRef::from(client.clone()).identify().await;
+    assert_eq!(identity.unwrap().node.id, 1);
+
+    // the connection made via the advertised address is kept, the other is closed
+    alias_client.wait_for_stop().await;
+    assert!(client.is_valid());
+
+    let client_via_alias = new_client(&remote_b, "127.0.0.1:30401").await;
+    assert_eq!(client_via_alias.actor_id(), client.actor_id());
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(client.is_valid());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_client_simultaneous_dial() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_tag("remote-a")
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .build()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30403")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30404")
+        .start()
+        .await;
+
+    // both nodes dial each other at the same time
+    let (client_a, client_b) = tokio::join!(
+        new_client(&remote_a, "localhost:30404"),
+        new_client(&remote_b, "localhost:30403")
+    );
+
+    let identity_b = RemoteClientRef::from(client_a.clone()).identify().await;
+    let identity_a = RemoteClientRef::from(client_b.clone()).identify().await;
+
+    assert_eq!(identity_b.unwrap().node.id, 2);
+    assert_eq!(identity_a.unwrap().node.id, 1);
+
+    // each node writes to its peer via its own outbound connection, the inbound connection is
+    // served by a session and is never registered as a client, so neither connection is closed
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let clients_a = remote_a.client_registry().send(GetClients).await.unwrap();
+    let clients_b = remote_b.client_registry().send(GetClients).await.unwrap();
+
+    assert_eq!(clients_a.len(), 1);
+    assert_eq!(clients_a[0].1.actor_id(), client_a.actor_id());
+    assert_eq!(clients_b.len(), 1);
+    assert_eq!(clients_b[0].1.actor_id(), client_b.actor_id());
+    assert!(client_a.is_valid());
+    assert!(client_b.is_valid());
+}
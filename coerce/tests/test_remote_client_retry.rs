@@ -0,0 +1,170 @@
+use bytes::Bytes;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::LocalActorRef;
+use coerce::remote::actor::message::NewClient;
+use coerce::remote::net::client::reconnect::ReconnectPolicy;
+use coerce::remote::net::client::retry::ExchangeRetryPolicy;
+use coerce::remote::net::client::status::{ClientConnectionStatus, GetClientStatus};
+use coerce::remote::net::client::watchdog::DisconnectCause;
+use coerce::remote::net::client::{ClientType, RemoteClient};
+use coerce::remote::net::message::{ClientEvent, SessionEvent};
+use coerce::remote::net::proto::network::{IdentifyEvent, NodeIdentity};
+use coerce::remote::net::version::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+pub mod util;
+
+fn retry_policy() -> ExchangeRetryPolicy {
+    ExchangeRetryPolicy {
+        identify_timeout: Duration::from_millis(200),
+        identify_attempts: 3,
+        ..Default::default()
+    }
+}
+
+async fn create_client_system() -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .configure(|c| {
+            c.exchange_retry_policy(retry_policy())
+                .reconnect_policy(ReconnectPolicy::fixed(Duration::from_secs(30), None))
+        })
+        .build()
+        .await
+}
+
+async fn new_client(remote: &RemoteActorSystem, addr: &str) -> LocalActorRef<RemoteClient> {
+    remote
+        .client_registry()
+        .send(NewClient {
+            addr: addr.to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+fn identity(addr: &str) -> ClientEvent {
+    ClientEvent::Identity(NodeIdentity {
+        node_id: 2,
+        addr: addr.to_string(),
+        min_protocol_version: MIN_PROTOCOL_VERSION,
+        max_protocol_version: PROTOCOL_VERSION,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+pub async fn test_remote_client_identify_retried() {
+    util::create_trace_logger();
+
+    // a node that ignores the first identify, as if it was lost, and replies to the retry
+    let listener = TcpListener::bind("127.0.0.1:35262").await.unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+            let _first_identify = framed.next().await;
+            let _second_identify = framed.next().await;
+
+            let identity = identity("127.0.0.1:35262");
+            let _ = framed
+                .send(Bytes::from(identity.write_to_bytes().unwrap()))
+                .await;
+
+            tokio::spawn(async move { while framed.next().await.is_some() {} });
+        }
+    });
+
+    let remote = create_client_system().await;
+    let client = new_client(&remote, "127.0.0.1:35262").await;
+
+    let status = client.send(GetClientStatus).await.unwrap();
+    assert_eq!(status.status, ClientConnectionStatus::Connected);
+    assert_eq!(status.node_id, Some(2));
+    assert_eq!(status.last_disconnect, None);
+
+    remote.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_client_identify_attempts_exhausted() {
+    util::create_trace_logger();
+
+    // a node that accepts the connection but never identifies itself
+    let listener = TcpListener::bind("127.0.0.1:35263").await.unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+            tokio::spawn(async move { while framed.next().await.is_some() {} });
+        }
+    });
+
+    let remote = create_client_system().await;
+    let client = new_client(&remote, "127.0.0.1:35263").await;
+
+    let status = client.send(GetClientStatus).await.unwrap();
+    assert_ne!(status.status, ClientConnectionStatus::Connected);
+    assert_eq!(
+        status.last_disconnect,
+        Some(DisconnectCause::IdentifyTimedOut)
+    );
+
+    remote.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_session_repeated_identify_rewrites_identity() {
+    util::create_trace_logger();
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(3)
+        .build()
+        .await;
+
+    let _ = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35264")
+        .start()
+        .await;
+
+    let stream = TcpStream::connect("127.0.0.1:35264").await.unwrap();
+    let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+
+    let identify = SessionEvent::Identify(IdentifyEvent {
+        source_node_id: 4,
+        source_node_tag: "node-4".to_string(),
+        min_protocol_version: MIN_PROTOCOL_VERSION,
+        max_protocol_version: PROTOCOL_VERSION,
+        ..Default::default()
+    });
+
+    for _ in 0..2 {
+        framed
+            .send(Bytes::from(identify.write_to_bytes().unwrap()))
+            .await
+            .unwrap();
+
+        let frame = tokio::time::timeout(Duration::from_secs(5), framed.next())
+            .await
+            .expect("identity not received")
+            .unwrap()
+            .unwrap();
+
+        match ClientEvent::read_from_bytes(frame.to_vec()) {
+            Some(ClientEvent::Identity(identity)) => assert_eq!(identity.node_id, 3),
+            _ => panic!("expected identity"),
+        }
+    }
+
+    remote.actor_system().shutdown().await;
+}
@@ -0,0 +1,157 @@
+use coerce::actor::describe::Describe;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::ActorTags;
+use coerce::remote::actor::message::NewClient;
+use coerce::remote::net::client::reconnect::ReconnectPolicy;
+use coerce::remote::net::client::status::{ClientConnectionStatus, ClientStatus};
+use coerce::remote::net::client::ClientType;
+use coerce::remote::net::message::SessionEvent;
+use coerce::remote::net::proto::network::{FindActorEvent, MessageRequest};
+use coerce::remote::system::RemoteActorSystem;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+pub mod util;
+
+async fn create_system(node_id: u64) -> RemoteActorSystem {
+    // the client waits before re-connecting, so its state can be observed whilst disconnected
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .configure(|c| c.reconnect_policy(ReconnectPolicy::fixed(Duration::from_secs(30), None)))
+        .build()
+        .await
+}
+
+async fn node_client_status(remote: &RemoteActorSystem, node_id: u64) -> Option<ClientStatus> {
+    remote
+        .client_statuses()
+        .await
+        .into_iter()
+        .find(|client| client.node_id == Some(node_id))
+}
+
+/// Polls the status of the client for `node_id` until it exists and matches `condition`,
+/// panicking if it doesn't within 10 seconds
+async fn wait_for_client_status(
+    remote: &RemoteActorSystem,
+    node_id: u64,
+    condition: impl Fn(&ClientStatus) -> bool,
+) -> ClientStatus {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match node_client_status(remote, node_id).await {
+                Some(status) if condition(&status) => return status,
+                _ => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    })
+    .await
+    .expect("client status not reached within timeout")
+}
+
+#[tokio::test]
+pub async fn test_remote_client_status() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1).await;
+    let remote_b = create_system(2).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35161")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35162")
+        .with_seed_addr("localhost:35161")
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let status = node_client_status(&remote_a, 2).await.unwrap();
+    assert_eq!(status.addr, "localhost:35162");
+    assert_eq!(status.status, ClientConnectionStatus::Connected);
+    assert_eq!(status.buffered_messages, 0);
+    assert_eq!(status.connection_attempts, 0);
+    assert!(status.last_write.is_some());
+
+    // the client's state is also included when describing the client
+    let client = remote_a
+        .client_registry()
+        .send(NewClient {
+            addr: status.addr.clone(),
+            client_type: ClientType::Worker,
+            system: remote_a.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let (tx, rx) = oneshot::channel();
+    let _ = client.describe(Describe {
+        sender: Some(tx),
+        ..Default::default()
+    });
+
+    let tags = match rx.await.unwrap().tags {
+        ActorTags::Tags(tags) => tags,
+        tags => panic!("unexpected tags: {:?}", tags),
+    };
+
+    assert!(tags.iter().any(|t| t.as_ref() == "status=Connected"));
+    assert!(tags.iter().any(|t| t.as_ref() == "node_id=2"));
+
+    // stop node 2's clients before its actor system, so node 1 isn't told node 2 terminated
+    // (which would remove the client) and only sees the connection drop
+    remote_b.shutdown().await;
+    remote_b.actor_system().shutdown().await;
+
+    let status =
+        wait_for_client_status(&remote_a, 2, |s| s.status == ClientConnectionStatus::Idle).await;
+    assert_eq!(status.connection_attempts, 1);
+
+    // writes to the node are buffered until the client re-connects
+    remote_a
+        .notify_node(
+            2,
+            SessionEvent::NotifyActor(MessageRequest {
+                handler_type: "TestActor.Unknown".to_string(),
+                actor_id: "test-actor".to_string(),
+                message: vec![0; 64],
+                ..Default::default()
+            }),
+        )
+        .await;
+
+    // the write is buffered asynchronously by the client
+    let status = wait_for_client_status(&remote_a, 2, |s| s.buffered_messages == 1).await;
+    assert_eq!(status.buffered_system_messages, 0);
+    assert!(status.buffered_bytes > 64);
+
+    // system writes are buffered separately, and written first once the client re-connects
+    remote_a
+        .notify_node(
+            2,
+            SessionEvent::FindActor(FindActorEvent {
+                message_id: "find-actor".to_string(),
+                actor_id: "test-actor".to_string(),
+                ..Default::default()
+            }),
+        )
+        .await;
+
+    let status = wait_for_client_status(&remote_a, 2, |s| s.buffered_system_messages == 1).await;
+    assert_eq!(status.buffered_messages, 1);
+    assert_eq!(status.priority_messages, 0);
+
+    remote_a.actor_system().shutdown().await;
+}
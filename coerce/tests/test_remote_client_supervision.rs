@@ -0,0 +1,83 @@
+use coerce::actor::system::ActorSystem;
+use coerce::remote::actor::message::NewClient;
+use coerce::remote::net::client::{ClientType, RemoteClientRef};
+use coerce::remote::system::RemoteActorSystem;
+use std::time::Duration;
+
+pub mod util;
+
+async fn get_client(remote: &RemoteActorSystem, addr: &str) -> RemoteClientRef {
+    remote
+        .client_registry()
+        .send(NewClient {
+            addr: addr.to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .expect("client registry")
+        .expect("client")
+        .into()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_client_restarted_after_failure() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_tag("remote-a")
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .build()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30301")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30302")
+        .with_seed_addr("localhost:30301")
+        .start()
+        .await;
+
+    let client = remote_b
+        .client_registry()
+        .send(NewClient {
+            addr: "localhost:30301".to_string(),
+            client_type: ClientType::Worker,
+            system: remote_b.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let _ = client.notify_exec(|_| panic!("remote client failure"));
+    client.wait_for_stop().await;
+
+    let mut identity = None;
+    for _ in 0..10 {
+        let client = get_client(&remote_b, "localhost:30301").await;
+        if let Ok(node) = client.identify().await {
+            identity = Some(node);
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let identity = identity.expect("client was not restarted");
+    assert_eq!(identity.node.id, 1);
+}
@@ -0,0 +1,101 @@
+use bytes::Bytes;
+use coerce::actor::system::ActorSystem;
+use coerce::remote::actor::message::NewClient;
+use coerce::remote::net::client::reconnect::ReconnectPolicy;
+use coerce::remote::net::client::status::GetClientStatus;
+use coerce::remote::net::client::watchdog::DisconnectCause;
+use coerce::remote::net::client::ClientType;
+use coerce::remote::net::message::ClientEvent;
+use coerce::remote::net::proto::network::NodeIdentity;
+use coerce::remote::net::unhandled::UnhandledFrameHook;
+use coerce::remote::net::version::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+pub mod util;
+
+/// Panics within the client's receive task whenever a frame can't be decoded
+struct PanickingHook;
+
+#[async_trait::async_trait]
+impl UnhandledFrameHook for PanickingHook {
+    fn on_undecodable_frame(&self, _addr: &str, _frame: &[u8], _sys: &RemoteActorSystem) -> bool {
+        panic!("undecodable frame")
+    }
+}
+
+#[tokio::test]
+pub async fn test_remote_client_receive_task_panic_disconnects() {
+    util::create_trace_logger();
+
+    // a node that identifies itself, then sends a frame the client can't decode
+    let listener = TcpListener::bind("127.0.0.1:35261").await.unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+            let _identify = framed.next().await;
+
+            let identity = ClientEvent::Identity(NodeIdentity {
+                node_id: 2,
+                addr: "127.0.0.1:35261".to_string(),
+                min_protocol_version: MIN_PROTOCOL_VERSION,
+                max_protocol_version: PROTOCOL_VERSION,
+                ..Default::default()
+            });
+
+            let _ = framed
+                .send(Bytes::from(identity.write_to_bytes().unwrap()))
+                .await;
+
+            let _ = framed.send(Bytes::from(vec![250, 1, 2, 3])).await;
+
+            // keep the connection open, the client shouldn't rely on the stream closing
+            tokio::spawn(async move {
+                let _ = framed.next().await;
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            });
+        }
+    });
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_handlers(|handlers| handlers.with_unhandled_frame_hook(PanickingHook))
+        .configure(|c| c.reconnect_policy(ReconnectPolicy::fixed(Duration::from_millis(50), None)))
+        .build()
+        .await;
+
+    let client = remote
+        .client_registry()
+        .send(NewClient {
+            addr: "127.0.0.1:35261".to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut last_disconnect = None;
+    for _ in 0..100 {
+        last_disconnect = client.send(GetClientStatus).await.unwrap().last_disconnect;
+        if last_disconnect.is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    assert_eq!(
+        last_disconnect,
+        Some(DisconnectCause::ReceiveTaskPanicked {
+            panic_message: Some("undecodable frame".to_string())
+        })
+    );
+
+    remote.actor_system().shutdown().await;
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, IntoActorId};
+use coerce::remote::actor::message::ClientWrite;
+use coerce::remote::net::client::WriteLane;
+use coerce::remote::net::message::SessionEvent;
+use coerce::remote::net::proto::network::{MessageRequest, PingEvent};
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use coerce_macros::JsonMessage;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub mod util;
+
+#[derive(JsonMessage, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[result("u64")]
+pub struct Increment(u64);
+
+#[derive(Default)]
+pub struct CounterActor {
+    count: u64,
+}
+
+impl Actor for CounterActor {}
+
+#[async_trait]
+impl Handler<Increment> for CounterActor {
+    async fn handle(&mut self, message: Increment, _ctx: &mut ActorContext) -> u64 {
+        self.count += message.0;
+        self.count
+    }
+}
+
+#[test]
+pub fn test_remote_client_write_encoded_by_sender() {
+    let message = SessionEvent::NotifyActor(MessageRequest {
+        handler_type: "CounterActor.Increment".to_string(),
+        actor_id: "counter".to_string(),
+        message: vec![1, 2, 3],
+        ..Default::default()
+    });
+
+    let write = ClientWrite::new(2, &message).unwrap();
+    assert_eq!(write.node_id, 2);
+    assert_eq!(write.lane, WriteLane::User);
+    assert_eq!(write.frame.to_vec(), message.write_to_bytes().unwrap());
+
+    let ping = SessionEvent::Ping(PingEvent {
+        message_id: "ping".to_string(),
+        ..Default::default()
+    });
+
+    let write = ClientWrite::new(2, &ping).unwrap();
+    assert_eq!(write.lane, WriteLane::System);
+    assert_eq!(write.frame.to_vec(), ping.write_to_bytes().unwrap());
+}
+
+async fn create_system(node_id: u64) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_handlers(|handlers| {
+            handlers.with_handler::<CounterActor, Increment>("CounterActor.Increment")
+        })
+        .build()
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_concurrent_writes_to_node() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1).await;
+    let remote_b = create_system(2).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35181")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35182")
+        .with_seed_addr("localhost:35181")
+        .start()
+        .await;
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let _ = remote_a
+        .actor_system()
+        .new_actor("counter", CounterActor::default(), Tracked)
+        .await
+        .unwrap();
+
+    // the actor is registered with the second node asynchronously
+    let mut actor = None;
+    for _ in 0..50 {
+        actor = remote_b
+            .actor_ref::<CounterActor>("counter".into_actor_id())
+            .await;
+
+        if actor.is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let actor = actor.expect("unable to get remote ref");
+    assert!(actor.is_remote());
+
+    // each task encodes its own messages before they're written by the node's client
+    const TASKS: u64 = 16;
+    const MESSAGES: u64 = 50;
+
+    let tasks: Vec<_> = (0..TASKS)
+        .map(|_| {
+            let actor = actor.clone();
+            tokio::spawn(async move {
+                for _ in 0..MESSAGES {
+                    actor.send(Increment(1)).await.unwrap();
+                }
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert_eq!(actor.send(Increment(0)).await, Ok(TASKS * MESSAGES));
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
@@ -0,0 +1,160 @@
+use coerce::actor::system::ActorSystem;
+use coerce::actor::ActorRefErr;
+use coerce::remote::net::client::buffer::{WriteBufferConfig, WriteBufferOverflow};
+use coerce::remote::net::client::reconnect::ReconnectPolicy;
+use coerce::remote::net::client::status::{ClientConnectionStatus, ClientStatus};
+use coerce::remote::net::message::SessionEvent;
+use coerce::remote::net::proto::network::MessageRequest;
+use coerce::remote::system::rpc::NodeRpcErr;
+use coerce::remote::system::RemoteActorSystem;
+use std::time::Duration;
+use uuid::Uuid;
+
+pub mod util;
+
+async fn create_system(node_id: u64, write_buffer: WriteBufferConfig) -> RemoteActorSystem {
+    // the client waits before re-connecting, so writes are buffered for the rest of the test
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .configure(move |c| {
+            c.reconnect_policy(ReconnectPolicy::fixed(Duration::from_secs(30), None))
+                .write_buffer(write_buffer)
+        })
+        .build()
+        .await
+}
+
+async fn node_client_status(remote: &RemoteActorSystem, node_id: u64) -> Option<ClientStatus> {
+    remote
+        .client_statuses()
+        .await
+        .into_iter()
+        .find(|client| client.node_id == Some(node_id))
+}
+
+/// Creates a 2 node cluster, then stops the second node, returning the first node once its
+/// client to the second node is no longer connected
+async fn create_disconnected_cluster(
+    write_buffer: WriteBufferConfig,
+    addr_a: &str,
+    addr_b: &str,
+) -> RemoteActorSystem {
+    let remote_a = create_system(1, write_buffer).await;
+    let remote_b = create_system(2, WriteBufferConfig::default()).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr(addr_a)
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr(addr_b)
+        .with_seed_addr(addr_a)
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    remote_b.actor_system().shutdown().await;
+
+    for _ in 0..100 {
+        let status = node_client_status(&remote_a, 2).await.map(|s| s.status);
+        if status == Some(ClientConnectionStatus::Idle) {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    remote_a
+}
+
+fn message(message_id: Uuid, len: usize) -> SessionEvent {
+    SessionEvent::NotifyActor(MessageRequest {
+        message_id: message_id.to_string(),
+        handler_type: "TestActor.Unknown".to_string(),
+        actor_id: "test-actor".to_string(),
+        message: vec![0; len],
+        requires_response: true,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+pub async fn test_remote_client_write_buffer_fail_sender() {
+    util::create_trace_logger();
+
+    let remote = create_disconnected_cluster(
+        WriteBufferConfig::default()
+            .max_messages(2)
+            .overflow(WriteBufferOverflow::FailSender),
+        "localhost:35191",
+        "localhost:35192",
+    )
+    .await;
+
+    for _ in 0..2 {
+        remote.notify_node(2, message(Uuid::new_v4(), 64)).await;
+    }
+
+    // the buffer is full, requests are failed rather than waiting for a reply
+    let message_id = Uuid::new_v4();
+    let res = tokio::time::timeout(
+        Duration::from_secs(2),
+        remote.node_rpc_raw(message_id, message(message_id, 64), 2),
+    )
+    .await
+    .expect("request failed by the client");
+
+    assert!(matches!(
+        res,
+        Err(NodeRpcErr::Err(ActorRefErr::WriteBufferFull))
+    ));
+
+    let status = node_client_status(&remote, 2).await.unwrap();
+    assert_eq!(status.buffered_messages, 2);
+    assert_eq!(status.dropped_messages, 1);
+
+    remote.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_client_write_buffer_drop_oldest() {
+    util::create_trace_logger();
+
+    let remote = create_disconnected_cluster(
+        WriteBufferConfig::default()
+            .max_bytes(1024)
+            .overflow(WriteBufferOverflow::DropOldest),
+        "localhost:35193",
+        "localhost:35194",
+    )
+    .await;
+
+    for _ in 0..3 {
+        remote.notify_node(2, message(Uuid::new_v4(), 256)).await;
+    }
+
+    let status = node_client_status(&remote, 2).await.unwrap();
+    assert_eq!(status.buffered_messages, 3);
+    assert_eq!(status.dropped_messages, 0);
+    assert!(status.buffered_bytes <= 1024);
+
+    // the oldest messages make room for the new message
+    remote.notify_node(2, message(Uuid::new_v4(), 512)).await;
+
+    let status = node_client_status(&remote, 2).await.unwrap();
+    assert_eq!(status.buffered_messages, 2);
+    assert_eq!(status.dropped_messages, 2);
+    assert!(status.buffered_bytes <= 1024);
+
+    remote.actor_system().shutdown().await;
+}
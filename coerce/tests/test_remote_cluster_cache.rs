@@ -0,0 +1,98 @@
+use coerce::remote::cluster::cache::{CacheLoader, ReplicatedCache};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+struct CountingLoader {
+    loads: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl CacheLoader<String, u32> for CountingLoader {
+    async fn load(&self, key: &String) -> Option<u32> {
+        if key == "missing" {
+            return None;
+        }
+
+        Some(self.loads.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_cluster_cache_read_through() {
+    util::create_trace_logger();
+
+    let remote = util::create_cluster_node(1, "localhost:30801", None, |handlers| handlers).await;
+    let loads = Arc::new(AtomicU32::new(0));
+
+    let cache: ReplicatedCache<String, u32> = ReplicatedCache::builder("counts", remote.clone())
+        .loader(CountingLoader {
+            loads: loads.clone(),
+        })
+        .ttl(Duration::from_millis(100))
+        .build()
+        .await;
+
+    assert_eq!(cache.get(&"a".to_string()).await.unwrap(), Some(1));
+    assert_eq!(cache.get(&"a".to_string()).await.unwrap(), Some(1));
+    assert_eq!(cache.get(&"missing".to_string()).await.unwrap(), None);
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    // expired values are loaded again
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(cache.get(&"a".to_string()).await.unwrap(), Some(2));
+
+    cache.invalidate(&"a".to_string()).await.unwrap();
+    assert_eq!(cache.get(&"a".to_string()).await.unwrap(), Some(3));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_cluster_cache_invalidation_broadcast() {
+    util::create_trace_logger();
+
+    let remote_a = util::create_cluster_node(1, "localhost:30811", None, |handlers| handlers).await;
+    let remote_b =
+        util::create_cluster_node(2, "localhost:30812", Some("localhost:30811"), |handlers| {
+            handlers
+        })
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    let cache_a: ReplicatedCache<String, u32> = ReplicatedCache::builder("users", remote_a.clone())
+        .build()
+        .await;
+    let cache_b: ReplicatedCache<String, u32> = ReplicatedCache::builder("users", remote_b.clone())
+        .build()
+        .await;
+
+    cache_a.insert("probe".to_string(), 0).await.unwrap();
+    cache_b.insert("user-1".to_string(), 1).await.unwrap();
+    cache_b.insert("user-2".to_string(), 2).await.unwrap();
+    assert_eq!(cache_b.get(&"user-1".to_string()).await.unwrap(), Some(1));
+
+    // invalidations from B reach A in order, so once the probe is evicted from A, the
+    // invalidations published by B's inserts have been received too
+    cache_b.invalidate(&"probe".to_string()).await.unwrap();
+    util::eventually(|| async { cache_a.get(&"probe".to_string()).await.unwrap().is_none() }).await;
+
+    // writing a key on one node evicts the stale value on every other node
+    cache_a.insert("user-1".to_string(), 10).await.unwrap();
+    assert_eq!(cache_a.get(&"user-1".to_string()).await.unwrap(), Some(10));
+    util::eventually(|| async { cache_b.get(&"user-1".to_string()).await.unwrap().is_none() })
+        .await;
+    assert_eq!(cache_b.get(&"user-2".to_string()).await.unwrap(), Some(2));
+
+    cache_a.invalidate_all().await.unwrap();
+    util::eventually(|| async { cache_b.get(&"user-2".to_string()).await.unwrap().is_none() })
+        .await;
+    assert_eq!(cache_a.get(&"user-1".to_string()).await.unwrap(), None);
+}
@@ -0,0 +1,103 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::{Actor, IntoActor};
+use coerce::remote::cluster::config::{ClusterConfig, ConfigChanged};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+struct FeatureFlagWatcher {
+    changes: mpsc::UnboundedSender<Option<bool>>,
+}
+
+impl Actor for FeatureFlagWatcher {}
+
+#[async_trait]
+impl Handler<ConfigChanged<bool>> for FeatureFlagWatcher {
+    async fn handle(&mut self, message: ConfigChanged<bool>, _ctx: &mut ActorContext) {
+        let _ = self.changes.send(message.value);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_cluster_config_replication() {
+    util::create_trace_logger();
+
+    let remote_a = util::create_cluster_node(1, "localhost:30701", None, |handlers| handlers).await;
+    let remote_b =
+        util::create_cluster_node(2, "localhost:30702", Some("localhost:30701"), |handlers| {
+            handlers
+        })
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    let config_a = ClusterConfig::builder(remote_a.clone()).build().await;
+    let config_b = ClusterConfig::builder(remote_b.clone()).build().await;
+
+    let (tx, mut changes) = mpsc::unbounded_channel();
+    let watcher = FeatureFlagWatcher { changes: tx }
+        .into_actor(Some("feature-flag-watcher"), remote_b.actor_system())
+        .await
+        .unwrap();
+
+    config_b
+        .watch::<_, bool>("feature.enabled", watcher)
+        .await
+        .unwrap();
+
+    config_a.set("feature.enabled", &true).await.unwrap();
+    assert_eq!(changes.recv().await, Some(Some(true)));
+    assert_eq!(
+        config_b.get::<bool>("feature.enabled").await.unwrap(),
+        Some(true)
+    );
+
+    // the most recent write wins, regardless of which node it was written on
+    config_b.set("feature.enabled", &false).await.unwrap();
+    assert_eq!(changes.recv().await, Some(Some(false)));
+    util::eventually(|| async {
+        config_a.get::<bool>("feature.enabled").await.unwrap() == Some(false)
+    })
+    .await;
+
+    config_a.remove("feature.enabled").await.unwrap();
+    assert_eq!(changes.recv().await, Some(None));
+    assert_eq!(config_a.get::<bool>("feature.enabled").await.unwrap(), None);
+
+    // values that can't be read as the requested type are reported as errors
+    config_a.set("feature.name", &"checkout").await.unwrap();
+    assert!(config_a.get::<bool>("feature.name").await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_cluster_config_syncs_new_nodes() {
+    util::create_trace_logger();
+
+    let remote_a = util::create_cluster_node(1, "localhost:30711", None, |handlers| handlers).await;
+    let config_a = ClusterConfig::builder(remote_a.clone())
+        .sync_interval(Duration::from_millis(100))
+        .build()
+        .await;
+
+    config_a.set("max-connections", &128u32).await.unwrap();
+
+    let remote_b =
+        util::create_cluster_node(2, "localhost:30712", Some("localhost:30711"), |handlers| {
+            handlers
+        })
+        .await;
+    let config_b = ClusterConfig::builder(remote_b.clone()).build().await;
+
+    util::eventually(|| async {
+        config_b.get::<u32>("max-connections").await.unwrap() == Some(128)
+    })
+    .await;
+}
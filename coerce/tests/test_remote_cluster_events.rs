@@ -0,0 +1,96 @@
+use coerce::actor::system::ActorSystem;
+use coerce::remote::cluster::events::{ClusterEventStream, MemberEvent};
+use coerce::remote::system::RemoteActorSystem;
+use futures::StreamExt;
+use std::time::Duration;
+
+pub mod util;
+
+async fn next_event(events: &mut ClusterEventStream) -> MemberEvent {
+    tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("cluster event")
+        .expect("cluster event stream ended")
+}
+
+#[tokio::test]
+pub async fn test_remote_cluster_event_stream() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_tag("remote-a")
+        .build()
+        .await;
+
+    let _server_a = remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:30195")
+        .start()
+        .await;
+
+    let mut events = remote_a.subscribe_cluster_events().await;
+
+    // the stream starts with the current members of the cluster
+    match next_event(&mut events).await {
+        MemberEvent::MemberUp(node) => assert_eq!(node.id, 1),
+        event => panic!("unexpected event: {:?}", event),
+    }
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .build()
+        .await;
+
+    let server_b = remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:30196")
+        .with_seed_addr("127.0.0.1:30195")
+        .start()
+        .await;
+
+    loop {
+        match next_event(&mut events).await {
+            MemberEvent::MemberUp(node) => {
+                assert_eq!(node.id, 2);
+                break;
+            }
+            MemberEvent::LeaderChanged(_) => continue,
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    let mut late_events = remote_a.subscribe_cluster_events().await;
+    let mut members = vec![];
+    for _ in 0..2 {
+        match next_event(&mut late_events).await {
+            MemberEvent::MemberUp(node) => members.push(node.id),
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    members.sort();
+    assert_eq!(members, vec![1, 2]);
+    drop(late_events);
+
+    server_b.stop();
+    remote_b.actor_system().shutdown().await;
+
+    loop {
+        match next_event(&mut events).await {
+            MemberEvent::MemberRemoved(node) => {
+                assert_eq!(node.id, 2);
+                break;
+            }
+            MemberEvent::LeaderChanged(_)
+            | MemberEvent::MemberUnreachable(_)
+            | MemberEvent::MemberReachable(_) => continue,
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+}
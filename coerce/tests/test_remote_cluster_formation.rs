@@ -17,6 +17,15 @@ use coerce::actor::{ActorCreationErr, ActorFactory, ActorRecipe};
 
 use util::*;
 
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::{Actor, ActorRefErr};
+use coerce::remote::cluster::builder::worker::BootstrapFallback;
+use coerce::remote::stream::pubsub::{PubSub, Receive, Subscription};
+use coerce::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
 #[derive(Serialize, Deserialize)]
 pub struct TestActorRecipe {
     name: String,
@@ -160,3 +169,224 @@ pub async fn test_remote_cluster_workers() {
     assert_eq!(nodes_c_in_a, nodes_c.len());
     assert_eq!(nodes_c_in_b, nodes_c.len());
 }
+
+struct StandaloneListener {
+    subscription: Option<Subscription>,
+    on_standalone: Option<oneshot::Sender<()>>,
+}
+
+#[async_trait]
+impl Actor for StandaloneListener {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.subscription = Some(
+            PubSub::subscribe::<Self, SystemTopic>(SystemTopic, ctx)
+                .await
+                .unwrap(),
+        );
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<SystemTopic>> for StandaloneListener {
+    async fn handle(&mut self, message: Receive<SystemTopic>, _ctx: &mut ActorContext) {
+        if let SystemEvent::Cluster(ClusterEvent::Standalone) = message.0.as_ref() {
+            if let Some(on_standalone) = self.on_standalone.take() {
+                let _ = on_standalone.send(());
+            }
+        }
+    }
+}
+
+#[tokio::test]
+pub async fn test_remote_wait_for_cluster_members() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_tag("remote-a")
+        .role("frontend")
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .role("backend")
+        .build()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30501")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30502")
+        .with_seed_addr("localhost:30501")
+        .start()
+        .await;
+
+    let members = remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_eq!(members.len(), 2);
+
+    let backends = remote_a
+        .wait_for_role("backend", 1, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_eq!(backends.len(), 1);
+    assert_eq!(backends[0].id, 2);
+
+    let members = remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_eq!(members.len(), 2);
+
+    let result = remote_a
+        .wait_for_role("backend", 2, Duration::from_millis(200))
+        .await;
+
+    assert!(matches!(result, Err(ActorRefErr::Timeout { .. })));
+}
+
+#[tokio::test]
+pub async fn test_remote_bootstrap_timeout_standalone() {
+    util::create_trace_logger();
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .build()
+        .await;
+
+    let (tx, rx) = oneshot::channel();
+    let _listener = remote
+        .actor_system()
+        .new_anon_actor(StandaloneListener {
+            subscription: None,
+            on_standalone: Some(tx),
+        })
+        .await
+        .unwrap();
+
+    // nothing is listening on the seed address
+    let server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30511")
+        .with_seed_addr("localhost:30519")
+        .bootstrap_timeout(Duration::from_secs(3), BootstrapFallback::Standalone)
+        .try_start()
+        .await;
+
+    assert!(server.is_ok());
+
+    tokio::time::timeout(Duration::from_secs(1), rx)
+        .await
+        .expect("standalone event not received")
+        .unwrap();
+
+    let members = remote
+        .wait_for_members(1, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].id, 1);
+}
+
+#[tokio::test]
+pub async fn test_remote_bootstrap_timeout_abort() {
+    util::create_trace_logger();
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .build()
+        .await;
+
+    let server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30521")
+        .with_seed_addr("localhost:30529")
+        .bootstrap_timeout(Duration::from_millis(500), BootstrapFallback::Abort)
+        .try_start()
+        .await;
+
+    assert!(server.is_err());
+}
+
+#[tokio::test]
+pub async fn test_remote_rediscovery_after_cluster_replaced() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .build()
+        .await;
+
+    let server_b = remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30532")
+        .start()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30531")
+        .with_seed_addrs(["localhost:30532", "localhost:30533"])
+        .rediscovery_interval(Duration::from_millis(500))
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    // the original seed is replaced by a new node, at a different address
+    server_b.stop();
+    remote_b.actor_system().shutdown().await;
+
+    let remote_c = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(3)
+        .build()
+        .await;
+
+    remote_c
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30533")
+        .start()
+        .await;
+
+    let nodes = remote_a
+        .wait_for_members(2, Duration::from_secs(10))
+        .await
+        .unwrap();
+
+    assert!(nodes.iter().any(|n| n.id == 3));
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use coerce::actor::system::ActorSystem;
+use coerce::remote::cluster::discovery::dns::DnsSeed;
+use coerce::remote::cluster::discovery::seed::{ClusterSeed, ClusterSeedErr};
+use coerce::remote::system::{NodeId, RemoteActorSystem};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub mod util;
+
+/// A seed whose addresses can be changed while the node is running
+#[derive(Clone, Default)]
+struct TestSeed {
+    addrs: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl ClusterSeed for TestSeed {
+    async fn seed_addrs(&mut self) -> Result<Vec<String>, ClusterSeedErr> {
+        Ok(self.addrs.lock().unwrap().clone())
+    }
+
+    fn refresh_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(100))
+    }
+}
+
+async fn create_system(node_id: NodeId) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_tag(format!("node-{node_id}"))
+        .build()
+        .await
+}
+
+#[tokio::test]
+pub async fn test_dns_seed_a_records() {
+    let mut seed = DnsSeed::a("localhost", 35121);
+
+    let addrs = seed.seed_addrs().await.unwrap();
+    assert_eq!(addrs, vec!["127.0.0.1:35121".to_string()]);
+    assert_eq!(seed.refresh_interval(), Some(Duration::from_secs(30)));
+
+    let mut seed = DnsSeed::a("unresolvable.invalid", 35121);
+    assert!(seed.seed_addrs().await.is_err());
+}
+
+#[tokio::test]
+pub async fn test_remote_cluster_seed_refresh() {
+    util::create_trace_logger();
+
+    let seed = TestSeed::default();
+
+    let remote = create_system(1).await;
+    let _ = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35121")
+        .with_seed(seed.clone())
+        .start()
+        .await;
+
+    let remote_2 = create_system(2).await;
+    let _ = remote_2
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35122")
+        .start()
+        .await;
+
+    assert_eq!(remote.get_nodes().await.len(), 1);
+
+    // once the seed resolves node 2's address, node 1 discovers it
+    seed.addrs
+        .lock()
+        .unwrap()
+        .extend(["127.0.0.1:35121".to_string(), "127.0.0.1:35122".to_string()]);
+
+    remote
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("node 2 discovered");
+
+    remote_2
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("node 1 discovered");
+
+    remote.actor_system().shutdown().await;
+    remote_2.actor_system().shutdown().await;
+}
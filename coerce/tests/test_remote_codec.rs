@@ -1,5 +1,24 @@
+use bytes::{Bytes, BytesMut};
 use coerce::actor::system::ActorSystem;
+use coerce::remote::actor::message::{ClientWrite, NewClient};
+use coerce::remote::net::buffer::BufferPool;
+use coerce::remote::net::client::status::GetClientStatus;
+use coerce::remote::net::client::{ClientType, WriteLane};
+use coerce::remote::net::codec::{FrameCodec, FrameErr};
+use coerce::remote::net::message::{
+    decode_failure_frame, is_decode_failure_frame, ClientEvent, SessionEvent,
+};
+use coerce::remote::net::proto::network::{
+    ActorAddress, ClientResult, FindActorEvent, MessageRequest, NodeIdentity, PingEvent,
+};
+use coerce::remote::net::version::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use coerce::remote::net::StreamData;
 use coerce::remote::system::RemoteActorSystem;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_util::codec::{Decoder, Encoder, Framed, LengthDelimitedCodec};
 
 use util::*;
 
@@ -43,3 +62,264 @@ pub async fn test_remote_create_message() {
         "TestActor.SetStatusRequest".to_string()
     );
 }
+
+#[test]
+pub fn test_remote_replies_use_priority_lane() {
+    let result = SessionEvent::Result(ClientResult::default());
+    let ping = SessionEvent::Ping(PingEvent::default());
+    let notify = SessionEvent::NotifyActor(MessageRequest::default());
+
+    assert!(result.is_priority());
+    assert!(ping.is_priority());
+    assert!(!notify.is_priority());
+}
+
+#[tokio::test]
+pub async fn test_remote_reply_overtakes_queued_writes() {
+    util::create_trace_logger();
+
+    const ADDR: &str = "127.0.0.1:35280";
+    const QUEUED_WRITES: usize = 256;
+
+    // a node that doesn't read anything until a backlog of writes has built up in the client
+    let (frames_tx, mut frames_rx) = mpsc::unbounded_channel();
+    let listener = TcpListener::bind(ADDR).await.unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+        let _identify = framed.next().await;
+
+        let identity = ClientEvent::Identity(NodeIdentity {
+            node_id: 2,
+            addr: ADDR.to_string(),
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            max_protocol_version: PROTOCOL_VERSION,
+            ..Default::default()
+        });
+
+        let identity = Bytes::from(identity.write_to_bytes().unwrap());
+        let _ = framed.send(identity).await;
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        while let Some(Ok(frame)) = framed.next().await {
+            let _ = frames_tx.send(SessionEvent::read_from_slice(&frame));
+        }
+    });
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .build()
+        .await;
+
+    let client = remote
+        .client_registry()
+        .send(NewClient {
+            addr: ADDR.to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    // wait for the client to connect, and register itself against the node's id
+    let _ = client.send(GetClientStatus).await;
+
+    // encoded once, so the writes are queued faster than the client can write them
+    let write = ClientWrite::new(
+        2,
+        &SessionEvent::NotifyActor(MessageRequest {
+            actor_id: "test-actor".to_string(),
+            message: vec![0; 256 * 1024],
+            ..Default::default()
+        }),
+    )
+    .unwrap();
+
+    for _ in 0..QUEUED_WRITES {
+        let _ = remote.client_registry().notify(ClientWrite {
+            node_id: 2,
+            frame: write.frame.clone(),
+            lane: write.lane,
+            semantics: write.semantics,
+        });
+    }
+
+    remote
+        .notify_node(2, SessionEvent::Result(ClientResult::default()))
+        .await;
+
+    let mut position = None;
+    let mut received = 0;
+    while received <= QUEUED_WRITES {
+        // pings and the discovery handshake are written alongside the test's writes
+        match frames_rx.recv().await.unwrap() {
+            Some(SessionEvent::Result(_)) => position = Some(received),
+            Some(SessionEvent::NotifyActor(_)) => {}
+            Some(_) => continue,
+            None => panic!("failed to decode frame"),
+        }
+
+        received += 1;
+    }
+
+    // the reply is written as soon as the client finishes its current write, rather
+    // than waiting for every queued write to be written first
+    let position = position.expect("reply was not received");
+    assert!(
+        position < QUEUED_WRITES / 2,
+        "reply was written after {} queued writes",
+        position
+    );
+
+    remote.actor_system().shutdown().await;
+}
+
+#[test]
+pub fn test_remote_system_events_use_system_lane() {
+    let ping = SessionEvent::Ping(PingEvent::default());
+    let find_actor = SessionEvent::FindActor(FindActorEvent::default());
+    let register_actor = SessionEvent::RegisterActor(ActorAddress::default());
+    let notify = SessionEvent::NotifyActor(MessageRequest::default());
+
+    assert_eq!(ping.lane(), WriteLane::System);
+    assert_eq!(find_actor.lane(), WriteLane::System);
+    assert_eq!(register_actor.lane(), WriteLane::System);
+    assert_eq!(notify.lane(), WriteLane::User);
+}
+
+#[test]
+pub fn test_remote_codec_malformed_frames() {
+    // xorshift, so failures are reproducible
+    let mut seed = 0x2545F4914F6CDD1Du64;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+
+    let valid_frame = SessionEvent::NotifyActor(MessageRequest {
+        message_id: "d2e8a6a0-4b7e-4b8c-9d6b-0d9e0c7c6a10".to_string(),
+        handler_type: "TestActor.SetStatusRequest".to_string(),
+        actor_id: "test-actor".to_string(),
+        message: vec![1, 2, 3, 4],
+        requires_response: true,
+        ..Default::default()
+    })
+    .write_to_bytes()
+    .unwrap();
+
+    for _ in 0..10_000 {
+        let len = (next() % 64) as usize;
+        let frame: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+
+        let _ = SessionEvent::read_from_bytes(frame.clone());
+        let _ = ClientEvent::read_from_bytes(frame);
+
+        let mut corrupted = valid_frame.clone();
+        let idx = 1 + (next() as usize % (corrupted.len() - 1));
+        corrupted[idx] = next() as u8;
+        corrupted.truncate(1 + (next() as usize % corrupted.len()));
+
+        let _ = SessionEvent::read_from_bytes(corrupted);
+    }
+
+    let truncated = valid_frame[..valid_frame.len() / 2].to_vec();
+    assert!(SessionEvent::read_from_bytes(truncated).is_none());
+}
+
+#[test]
+pub fn test_remote_codec_decode_failure_frame() {
+    let frame = ClientEvent::Err(decode_failure_frame())
+        .write_to_bytes()
+        .unwrap();
+
+    match ClientEvent::read_from_bytes(frame) {
+        Some(ClientEvent::Err(err)) => assert!(is_decode_failure_frame(&err)),
+        _ => panic!("expected error frame"),
+    }
+}
+
+#[test]
+pub fn test_remote_buffer_pool_encode() {
+    let pool = BufferPool::new(1);
+    let message = SessionEvent::NotifyActor(MessageRequest {
+        handler_type: "TestActor.SetStatusRequest".to_string(),
+        actor_id: "test-actor".to_string(),
+        message: vec![1; 512],
+        ..Default::default()
+    });
+
+    // frames encoded via the pool are identical to those encoded without it
+    let frame = pool.encode(&message).unwrap();
+    assert_eq!(frame.to_vec(), message.write_to_bytes().unwrap());
+
+    match SessionEvent::read_from_slice(&frame) {
+        Some(SessionEvent::NotifyActor(request)) => {
+            assert_eq!(request.actor_id, "test-actor");
+            assert_eq!(request.message, vec![1; 512]);
+        }
+        _ => panic!("unexpected event"),
+    }
+
+    drop(frame);
+
+    let _ = pool.encode(&message).unwrap();
+    let stats = pool.stats();
+
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.pooled_buffers, 1);
+    assert_eq!(stats.hit_rate(), 0.5);
+}
+
+#[test]
+pub fn test_remote_frame_codec_matches_runtime_framing() {
+    let message = SessionEvent::NotifyActor(MessageRequest {
+        handler_type: "TestActor.SetStatusRequest".to_string(),
+        actor_id: "test-actor".to_string(),
+        message: vec![1; 512],
+        ..Default::default()
+    });
+
+    // frames encoded by constrained clients can be read by the runtime, and vice versa
+    let codec = FrameCodec::new();
+    let mut buffer = BytesMut::new();
+    codec.encode_message(&message, &mut buffer).unwrap();
+
+    let mut runtime_codec = LengthDelimitedCodec::new();
+    let frame = runtime_codec.decode(&mut buffer).unwrap().unwrap();
+    assert_eq!(frame.to_vec(), message.write_to_bytes().unwrap());
+
+    let mut buffer = BytesMut::new();
+    runtime_codec
+        .encode(Bytes::from(message.write_to_bytes().unwrap()), &mut buffer)
+        .unwrap();
+
+    // partial frames are left in the buffer until the rest of the frame arrives
+    let mut partial = buffer.split_to(buffer.len() - 1);
+    assert!(codec.decode(&mut partial).unwrap().is_none());
+    partial.unsplit(buffer);
+
+    match codec.decode_message::<SessionEvent>(&mut partial) {
+        Ok(Some(SessionEvent::NotifyActor(request))) => {
+            assert_eq!(request.actor_id, "test-actor");
+        }
+        _ => panic!("unexpected event"),
+    }
+
+    assert!(partial.is_empty());
+
+    let codec = FrameCodec::with_max_frame_length(16);
+    let mut buffer = BytesMut::new();
+    assert_eq!(
+        codec.encode_message(&message, &mut buffer),
+        Err(FrameErr::FrameTooLarge {
+            length: message.write_to_bytes().unwrap().len(),
+            max_frame_length: 16,
+        })
+    );
+    assert!(buffer.is_empty());
+}
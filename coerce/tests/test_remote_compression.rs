@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, IntoActorId};
+use coerce::remote::actor::message::NewClient;
+use coerce::remote::net::client::{ClientType, RemoteClientRef};
+use coerce::remote::net::compression::{Compression, CompressionConfig};
+use coerce::remote::system::RemoteActorSystem;
+use coerce_macros::JsonMessage;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub mod util;
+
+#[derive(JsonMessage, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[result("String")]
+pub struct Echo(String);
+
+pub struct EchoActor;
+
+impl Actor for EchoActor {}
+
+#[async_trait]
+impl Handler<Echo> for EchoActor {
+    async fn handle(&mut self, message: Echo, _ctx: &mut ActorContext) -> String {
+        message.0
+    }
+}
+
+async fn create_system(node_id: u64, compression: Option<CompressionConfig>) -> RemoteActorSystem {
+    let mut builder = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_handlers(|handlers| handlers.with_handler::<EchoActor, Echo>("EchoActor.Echo"));
+
+    if let Some(compression) = compression {
+        builder = builder.with_compression(compression);
+    }
+
+    builder.build().await
+}
+
+async fn create_cluster(
+    a: (RemoteActorSystem, &str),
+    b: (RemoteActorSystem, &str),
+) -> (RemoteActorSystem, RemoteActorSystem) {
+    let (remote_a, addr_a) = a;
+    let (remote_b, addr_b) = b;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr(addr_a)
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr(addr_b)
+        .with_seed_addr(addr_a)
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    (remote_a, remote_b)
+}
+
+async fn negotiated_compression(remote: &RemoteActorSystem, addr: &str) -> Option<Compression> {
+    let client = remote
+        .client_registry()
+        .send(NewClient {
+            addr: addr.to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    RemoteClientRef::from(client)
+        .identify()
+        .await
+        .unwrap()
+        .compression
+}
+
+/// Sends a large, compressible payload from `remote_b` to an actor on `remote_a`, and back again
+async fn assert_echo(remote_a: &RemoteActorSystem, remote_b: &RemoteActorSystem) {
+    let actor_id = format!("echo-actor-{}", remote_a.node_id());
+    let _ = remote_a
+        .actor_system()
+        .new_actor(actor_id.clone(), EchoActor, Tracked)
+        .await
+        .unwrap();
+
+    // the actor is registered with `remote_b` asynchronously
+    let mut actor = None;
+    for _ in 0..50 {
+        actor = remote_b
+            .actor_ref::<EchoActor>(actor_id.clone().into_actor_id())
+            .await;
+
+        if actor.is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let actor = actor.expect("unable to get remote ref");
+
+    assert!(actor.is_remote());
+
+    let payload = "coerce".repeat(64 * 1024);
+    assert_eq!(actor.send(Echo(payload.clone())).await, Ok(payload));
+
+    // frames below the threshold are still sent uncompressed
+    assert_eq!(
+        actor.send(Echo("hello".to_string())).await,
+        Ok("hello".to_string())
+    );
+}
+
+#[test]
+pub fn test_compression_config_algorithms() {
+    let config = CompressionConfig::new(Compression::Zstd)
+        .with_fallback(Compression::Lz4)
+        .with_fallback(Compression::Zstd);
+
+    assert_eq!(
+        config.algorithms().collect::<Vec<_>>(),
+        vec![Compression::Zstd, Compression::Lz4]
+    );
+}
+
+#[tokio::test]
+pub async fn test_remote_compression() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(
+        1,
+        Some(CompressionConfig::new(Compression::Lz4).with_fallback(Compression::Zstd)),
+    )
+    .await;
+
+    let remote_b = create_system(
+        2,
+        Some(
+            CompressionConfig::new(Compression::Zstd)
+                .with_fallback(Compression::Lz4)
+                .threshold(512),
+        ),
+    )
+    .await;
+
+    let (remote_a, remote_b) =
+        create_cluster((remote_a, "localhost:35151"), (remote_b, "localhost:35152")).await;
+
+    // the accepting node chooses the connecting node's most preferred algorithm
+    assert_eq!(
+        negotiated_compression(&remote_b, "localhost:35151").await,
+        Some(Compression::Zstd)
+    );
+
+    assert_eq!(
+        negotiated_compression(&remote_a, "localhost:35152").await,
+        Some(Compression::Lz4)
+    );
+
+    assert_echo(&remote_a, &remote_b).await;
+    assert_echo(&remote_b, &remote_a).await;
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_compression_with_uncompressed_node() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1, Some(CompressionConfig::new(Compression::Lz4))).await;
+    let remote_b = create_system(2, None).await;
+
+    let (remote_a, remote_b) =
+        create_cluster((remote_a, "localhost:35153"), (remote_b, "localhost:35154")).await;
+
+    assert_eq!(
+        negotiated_compression(&remote_b, "localhost:35153").await,
+        None
+    );
+
+    assert_eq!(
+        negotiated_compression(&remote_a, "localhost:35154").await,
+        None
+    );
+
+    assert_echo(&remote_a, &remote_b).await;
+    assert_echo(&remote_b, &remote_a).await;
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorRef, IntoActorId};
+use coerce::remote::net::decode::DecodeConfig;
+use coerce::remote::net::message::SessionEvent;
+use coerce::remote::net::proto::network::MessageRequest;
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use coerce_macros::JsonMessage;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub mod util;
+
+#[derive(JsonMessage, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[result("()")]
+pub struct Append(usize, String);
+
+#[derive(JsonMessage, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[result("Vec<usize>")]
+pub struct GetReceived;
+
+#[derive(Default)]
+pub struct OrderedActor {
+    received: Vec<usize>,
+}
+
+impl Actor for OrderedActor {}
+
+#[async_trait]
+impl Handler<Append> for OrderedActor {
+    async fn handle(&mut self, message: Append, _ctx: &mut ActorContext) {
+        self.received.push(message.0);
+    }
+}
+
+#[async_trait]
+impl Handler<GetReceived> for OrderedActor {
+    async fn handle(&mut self, _message: GetReceived, _ctx: &mut ActorContext) -> Vec<usize> {
+        self.received.clone()
+    }
+}
+
+async fn create_system(node_id: u64) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_handlers(move |handlers| {
+            handlers
+                .with_handler::<OrderedActor, Append>("OrderedActor.Append")
+                .with_handler::<OrderedActor, GetReceived>("OrderedActor.GetReceived")
+                .decoding(DecodeConfig::default().workers(2).inline_threshold(1024))
+        })
+        .build()
+        .await
+}
+
+#[test]
+pub fn test_remote_ordering_key() {
+    let message = |actor_id: &str, message: Vec<u8>| {
+        SessionEvent::NotifyActor(MessageRequest {
+            message_id: "message-id".to_string(),
+            handler_type: "OrderedActor.Append".to_string(),
+            actor_id: actor_id.to_string(),
+            message,
+            ..Default::default()
+        })
+        .write_to_bytes()
+        .unwrap()
+    };
+
+    let key_a = SessionEvent::ordering_key(&message("actor-a", vec![1; 16]));
+    assert_eq!(
+        key_a,
+        SessionEvent::ordering_key(&message("actor-a", vec![2; 64 * 1024]))
+    );
+
+    assert_ne!(
+        key_a,
+        SessionEvent::ordering_key(&message("actor-b", vec![1; 16]))
+    );
+
+    // events other than messages all share the same key
+    assert_eq!(SessionEvent::ordering_key(&[]), 0);
+    assert_eq!(SessionEvent::ordering_key(&[0xff, 0x01]), 0);
+}
+
+#[tokio::test]
+pub async fn test_remote_decoding_preserves_actor_ordering() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1).await;
+    let remote_b = create_system(2).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35181")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35182")
+        .with_seed_addr("localhost:35181")
+        .start()
+        .await;
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let _ = remote_a
+        .actor_system()
+        .new_actor("ordered-actor", OrderedActor::default(), Tracked)
+        .await
+        .unwrap();
+
+    let mut actor = None;
+    for _ in 0..50 {
+        actor = remote_b
+            .actor_ref::<OrderedActor>("ordered-actor".into_actor_id())
+            .await;
+
+        if actor.is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let actor: ActorRef<OrderedActor> = actor.expect("unable to get remote ref");
+
+    // large messages are decoded by a worker, and small messages in-line, neither overtakes
+    // messages sent to the same actor before it
+    for i in 0..20 {
+        let payload = if i % 3 == 0 {
+            "a".repeat(256 * 1024)
+        } else {
+            "a".to_string()
+        };
+
+        actor.notify(Append(i, payload)).await.unwrap();
+    }
+
+    assert_eq!(
+        actor.send(GetReceived).await.unwrap(),
+        (0..20).collect::<Vec<_>>()
+    );
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
@@ -0,0 +1,299 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{
+    DeliverySemantics, Handler, Message, MessageUnwrapErr, MessageWrapErr,
+};
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorRef, ActorRefErr, LocalActorRef, ToActorId};
+use coerce::remote::actor::message::{ClientWrite, NewClient};
+use coerce::remote::actor::RemoteResponse;
+use coerce::remote::net::client::buffer::{WriteBufferConfig, WriteBufferOverflow};
+use coerce::remote::net::client::reconnect::ReconnectPolicy;
+use coerce::remote::net::client::send::WriteFrame;
+use coerce::remote::net::client::status::GetClientStatus;
+use coerce::remote::net::client::{ClientType, RemoteClient, WriteLane};
+use coerce::remote::net::message::SessionEvent;
+use coerce::remote::net::proto::network::MessageRequest;
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::remote::RemoteActorRef;
+use coerce_macros::JsonMessage;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+pub mod util;
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+pub struct Update;
+
+/// Declares a message with the provided [`DeliverySemantics`], like `#[delivery(...)]`
+/// on a message deriving `JsonMessage`
+macro_rules! message {
+    ($name:ident, $semantics:expr) => {
+        #[derive(Serialize, Deserialize)]
+        pub struct $name;
+
+        impl Message for $name {
+            type Result = ();
+
+            fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+                serde_json::to_vec(&self).map_err(|_| MessageWrapErr::SerializationErr)
+            }
+
+            fn from_bytes(bytes: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+                serde_json::from_slice(&bytes).map_err(|_| MessageUnwrapErr::DeserializationErr)
+            }
+
+            fn read_remote_result(_: Vec<u8>) -> Result<(), MessageUnwrapErr> {
+                Ok(())
+            }
+
+            fn write_remote_result(_: ()) -> Result<Vec<u8>, MessageWrapErr> {
+                Ok(vec![])
+            }
+
+            fn delivery_semantics() -> DeliverySemantics {
+                $semantics
+            }
+        }
+    };
+}
+
+message!(Gossip, DeliverySemantics::FireAndForget);
+message!(Transfer, DeliverySemantics::Reliable);
+message!(Cancel, DeliverySemantics::Priority);
+
+#[derive(Default)]
+pub struct Account {
+    transfers: usize,
+}
+
+impl Actor for Account {}
+
+#[async_trait]
+impl Handler<Update> for Account {
+    async fn handle(&mut self, _: Update, _ctx: &mut ActorContext) {}
+}
+
+#[async_trait]
+impl Handler<Gossip> for Account {
+    async fn handle(&mut self, _: Gossip, _ctx: &mut ActorContext) {}
+}
+
+#[async_trait]
+impl Handler<Transfer> for Account {
+    async fn handle(&mut self, _: Transfer, _ctx: &mut ActorContext) {
+        // the sender's notification isn't acknowledged until the message is handled
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        self.transfers += 1;
+    }
+}
+
+#[async_trait]
+impl Handler<Cancel> for Account {
+    async fn handle(&mut self, _: Cancel, _ctx: &mut ActorContext) {}
+}
+
+#[test]
+pub fn test_message_delivery_semantics_declared_by_type() {
+    assert_eq!(Update::delivery_semantics(), DeliverySemantics::Ordered);
+    assert_eq!(
+        Gossip::delivery_semantics(),
+        DeliverySemantics::FireAndForget
+    );
+    assert_eq!(Transfer::delivery_semantics(), DeliverySemantics::Reliable);
+    assert_eq!(Cancel::delivery_semantics(), DeliverySemantics::Priority);
+
+    let message = SessionEvent::NotifyActor(MessageRequest {
+        handler_type: "Account.Update".to_string(),
+        actor_id: "account".to_string(),
+        ..Default::default()
+    });
+
+    let write = ClientWrite::new(2, &message).unwrap();
+    assert_eq!(write.semantics, DeliverySemantics::Ordered);
+    assert_eq!(write.lane, WriteLane::User);
+
+    // priority messages skip any traffic queued ahead of them
+    let write = write.with_semantics(DeliverySemantics::Priority);
+    assert_eq!(write.lane, WriteLane::System);
+
+    let write = write.with_semantics(DeliverySemantics::Reliable);
+    assert_eq!(write.lane, WriteLane::User);
+}
+
+async fn create_system(node_id: u64, write_buffer: WriteBufferConfig) -> RemoteActorSystem {
+    // the client waits before re-connecting, so writes are buffered for the rest of the test
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_handlers(|handlers| {
+            handlers
+                .with_handler::<Account, Update>("Account.Update")
+                .with_handler::<Account, Gossip>("Account.Gossip")
+                .with_handler::<Account, Transfer>("Account.Transfer")
+                .with_handler::<Account, Cancel>("Account.Cancel")
+        })
+        .configure(move |c| {
+            c.reconnect_policy(ReconnectPolicy::fixed(Duration::from_secs(30), None))
+                .write_buffer(write_buffer)
+        })
+        .build()
+        .await
+}
+
+async fn create_cluster(
+    write_buffer: WriteBufferConfig,
+    addr_a: &str,
+    addr_b: &str,
+) -> (RemoteActorSystem, RemoteActorSystem) {
+    let remote_a = create_system(1, write_buffer).await;
+    let remote_b = create_system(2, WriteBufferConfig::default()).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr(addr_a)
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr(addr_b)
+        .with_seed_addr(addr_a)
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    (remote_a, remote_b)
+}
+
+fn account_ref(remote: &RemoteActorSystem) -> ActorRef<Account> {
+    ActorRef::from(RemoteActorRef::<Account>::new(
+        "account".to_actor_id(),
+        2,
+        remote.clone(),
+    ))
+}
+
+/// Writes a request to the client, with the provided semantics, returning the request's result
+fn write_request(
+    remote: &RemoteActorSystem,
+    client: &LocalActorRef<RemoteClient>,
+    semantics: DeliverySemantics,
+) -> oneshot::Receiver<RemoteResponse> {
+    let message_id = Uuid::new_v4();
+    let message = SessionEvent::NotifyActor(MessageRequest {
+        message_id: message_id.to_string(),
+        handler_type: "Account.Update".to_string(),
+        actor_id: "account".to_string(),
+        message: vec![0; 64],
+        requires_response: true,
+        ..Default::default()
+    });
+
+    let (res_tx, res_rx) = oneshot::channel();
+    remote.push_request(message_id, res_tx);
+
+    let frame = Bytes::from(message.write_to_bytes().unwrap());
+    client.notify(WriteFrame(frame, semantics)).unwrap();
+    res_rx
+}
+
+fn is_err(result: Result<RemoteResponse, oneshot::error::TryRecvError>, err: ActorRefErr) -> bool {
+    matches!(result, Ok(RemoteResponse::Err(e)) if e == err)
+}
+
+#[tokio::test]
+pub async fn test_remote_client_buffers_by_delivery_semantics() {
+    util::create_trace_logger();
+
+    let remote = create_system(
+        1,
+        WriteBufferConfig::default()
+            .max_messages(2)
+            .overflow(WriteBufferOverflow::DropOldest),
+    )
+    .await;
+
+    // nothing listens on the address, so the client is never connected
+    let client = remote
+        .client_registry()
+        .send(NewClient {
+            addr: "127.0.0.1:35269".to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    // fire and forget messages aren't buffered, requests fail rather than waiting for a reply
+    let mut gossip = write_request(&remote, &client, DeliverySemantics::FireAndForget);
+
+    // the oldest message that isn't reliable is dropped to make room
+    let mut first_transfer = write_request(&remote, &client, DeliverySemantics::Reliable);
+    write_request(&remote, &client, DeliverySemantics::Ordered);
+    write_request(&remote, &client, DeliverySemantics::Ordered);
+    let mut second_transfer = write_request(&remote, &client, DeliverySemantics::Reliable);
+
+    // the buffer only contains reliable messages, so the sender is failed
+    let mut third_transfer = write_request(&remote, &client, DeliverySemantics::Reliable);
+
+    // priority messages are buffered alongside system traffic
+    write_request(&remote, &client, DeliverySemantics::Priority);
+
+    let status = client.send(GetClientStatus).await.unwrap();
+    assert_eq!(status.buffered_messages, 2);
+    assert_eq!(status.buffered_system_messages, 1);
+    assert_eq!(status.dropped_messages, 3);
+
+    assert!(is_err(gossip.try_recv(), ActorRefErr::ActorUnavailable));
+    assert!(is_err(
+        third_transfer.try_recv(),
+        ActorRefErr::WriteBufferFull
+    ));
+    assert!(first_transfer.try_recv().is_err());
+    assert!(second_transfer.try_recv().is_err());
+
+    remote.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_reliable_notify_acknowledged() {
+    util::create_trace_logger();
+
+    let (remote_a, remote_b) = create_cluster(
+        WriteBufferConfig::default(),
+        "localhost:35271",
+        "localhost:35272",
+    )
+    .await;
+
+    let local_account = remote_b
+        .actor_system()
+        .new_actor("account".to_actor_id(), Account::default(), Tracked)
+        .await
+        .unwrap();
+
+    let account = account_ref(&remote_a);
+    account.notify(Gossip).await.unwrap();
+    account.notify(Cancel).await.unwrap();
+    account.notify(Transfer).await.unwrap();
+
+    // the notification returned once the message was handled by the remote actor
+    assert_eq!(local_account.exec(|a| a.transfers).await.unwrap(), 1);
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
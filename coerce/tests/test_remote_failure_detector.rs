@@ -0,0 +1,225 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, IntoActor};
+use coerce::remote::heartbeat::failure_detector::{
+    FailureDetectorConfig, PhiAccrualFailureDetector,
+};
+use coerce::remote::heartbeat::HeartbeatConfig;
+use coerce::remote::stream::pubsub::{PubSub, Receive, Subscription};
+use coerce::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
+use coerce::remote::system::{NodeId, RemoteActorSystem};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+#[derive(Debug, Eq, PartialEq)]
+enum Reachability {
+    Unreachable(NodeId),
+    Reachable(NodeId),
+}
+
+struct ReachabilityListener {
+    subscription: Option<Subscription>,
+    events: UnboundedSender<Reachability>,
+}
+
+#[async_trait]
+impl Actor for ReachabilityListener {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.subscription = Some(
+            PubSub::subscribe::<Self, SystemTopic>(SystemTopic, ctx)
+                .await
+                .unwrap(),
+        );
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<SystemTopic>> for ReachabilityListener {
+    async fn handle(&mut self, message: Receive<SystemTopic>, _ctx: &mut ActorContext) {
+        match message.0.as_ref() {
+            SystemEvent::Cluster(ClusterEvent::NodeUnreachable(node)) => {
+                let _ = self.events.send(Reachability::Unreachable(node.id));
+            }
+            SystemEvent::Cluster(ClusterEvent::NodeReachable(node)) => {
+                let _ = self.events.send(Reachability::Reachable(node.id));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+pub fn test_phi_accrual_failure_detector() {
+    let mut detector = PhiAccrualFailureDetector::new(FailureDetectorConfig {
+        threshold: 8.0,
+        max_sample_size: 100,
+        min_std_deviation: Duration::from_millis(50),
+        acceptable_heartbeat_pause: Duration::ZERO,
+        first_heartbeat_estimate: Duration::from_millis(100),
+    });
+
+    let start = Instant::now();
+    let at = |millis: u64| start + Duration::from_millis(millis);
+
+    assert_eq!(detector.phi(at(10_000)), 0.0);
+    assert!(detector.is_available(at(10_000)));
+
+    for i in 0..20 {
+        detector.heartbeat(at(i * 100));
+    }
+
+    let last_heartbeat = 1900;
+    let phi_on_time = detector.phi(at(last_heartbeat + 100));
+    let phi_late = detector.phi(at(last_heartbeat + 200));
+    let phi_very_late = detector.phi(at(last_heartbeat + 500));
+
+    assert!(phi_on_time < 1.0);
+    assert!(phi_late > phi_on_time);
+    assert!(detector.is_available(at(last_heartbeat + 200)));
+    assert!(phi_very_late > 8.0);
+    assert!(!detector.is_available(at(last_heartbeat + 500)));
+
+    // the pause isn't recorded, so the detector isn't any slower to detect the next one
+    detector.heartbeat(at(last_heartbeat + 5000));
+    assert!(detector.is_available(at(last_heartbeat + 5100)));
+    assert!(!detector.is_available(at(last_heartbeat + 5500)));
+}
+
+#[test]
+pub fn test_remote_node_unreachable_and_reachable() {
+    util::create_trace_logger();
+
+    let heartbeat_config = HeartbeatConfig {
+        interval: Duration::from_millis(100),
+        ping_timeout: Duration::from_secs(30),
+        unhealthy_node_heartbeat_timeout: Duration::from_secs(10),
+        terminated_node_heartbeat_timeout: Duration::from_secs(30),
+        failure_detector: FailureDetectorConfig {
+            threshold: 8.0,
+            max_sample_size: 100,
+            min_std_deviation: Duration::from_millis(50),
+            acceptable_heartbeat_pause: Duration::from_millis(200),
+            first_heartbeat_estimate: Duration::from_millis(100),
+        },
+        ..Default::default()
+    };
+
+    // node B runs on its own single-threaded runtime, so it can be frozen, leaving its
+    // connections open but unable to respond to pings
+    let (freeze_tx, freeze_rx) = mpsc::channel::<Duration>();
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let node_b_heartbeat_config = heartbeat_config.clone();
+    let node_b = std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async move {
+                let remote_b = RemoteActorSystem::builder()
+                    .with_actor_system(ActorSystem::new())
+                    .with_id(2)
+                    .with_tag("remote-b")
+                    .configure(|c| c.heartbeat(node_b_heartbeat_config))
+                    .build()
+                    .await;
+
+                let _server_b = remote_b
+                    .clone()
+                    .cluster_worker()
+                    .listen_addr("127.0.0.1:30191")
+                    .start()
+                    .await;
+
+                ready_tx.send(()).unwrap();
+
+                loop {
+                    match freeze_rx.try_recv() {
+                        Ok(duration) => std::thread::sleep(duration),
+                        Err(mpsc::TryRecvError::Empty) => {
+                            tokio::time::sleep(Duration::from_millis(10)).await
+                        }
+                        Err(mpsc::TryRecvError::Disconnected) => break,
+                    }
+                }
+
+                remote_b.actor_system().shutdown().await;
+            });
+    });
+
+    ready_rx.recv().unwrap();
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let remote_a = RemoteActorSystem::builder()
+            .with_actor_system(ActorSystem::new())
+            .with_id(1)
+            .with_tag("remote-a")
+            .configure(|c| c.heartbeat(heartbeat_config))
+            .build()
+            .await;
+
+        let _server_a = remote_a
+            .clone()
+            .cluster_worker()
+            .listen_addr("127.0.0.1:30192")
+            .with_seed_addr("127.0.0.1:30191")
+            .start()
+            .await;
+
+        remote_a
+            .wait_for_members(2, Duration::from_secs(5))
+            .await
+            .expect("cluster formed");
+
+        let (events_tx, mut events) = unbounded_channel();
+        let _listener = ReachabilityListener {
+            subscription: None,
+            events: events_tx,
+        }
+        .into_anon_actor(Some("reachability-listener"), remote_a.actor_system())
+        .await
+        .unwrap();
+
+        // wait for a few heartbeats, so the detector has a history to compare against
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let health = remote_a.node_health(2).await.expect("node health");
+        assert!(health.reachable);
+        assert!(remote_a.node_health(3).await.is_none());
+
+        freeze_tx.send(Duration::from_secs(2)).unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .expect("node unreachable");
+
+        assert_eq!(event, Some(Reachability::Unreachable(2)));
+
+        let health = remote_a.node_health(2).await.unwrap();
+        assert!(!health.reachable);
+        assert!(health.phi >= 8.0);
+
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("node reachable");
+
+        assert_eq!(event, Some(Reachability::Reachable(2)));
+        assert!(remote_a.node_health(2).await.unwrap().reachable);
+
+        remote_a.actor_system().shutdown().await;
+    });
+
+    drop(freeze_tx);
+    node_b.join().unwrap();
+}
@@ -1,6 +1,13 @@
 use crate::util::create_trace_logger;
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
 use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorRef, IntoActor, IntoActorId};
 use coerce::remote::system::RemoteActorSystem;
+use coerce::remote::RemoteActorRef;
+use coerce_macros::JsonMessage;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 use util::*;
 
 pub mod util;
@@ -80,3 +87,139 @@ pub async fn test_remote_handle_from_json() {
         Ok(GetStatusResponse::Ok(TestActorStatus::Active))
     );
 }
+
+#[tokio::test]
+pub async fn test_remote_handler_concurrency_limits() {
+    let test_set_status = "TestActor.SetStatusRequest";
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_handlers(move |handlers| {
+            handlers
+                .with_handler::<TestActor, SetStatusRequest>(test_set_status)
+                .with_handler::<TestActor, GetStatusRequest>("TestActor.GetStatusRequest")
+                .max_concurrent_handlers(2)
+                .handler_concurrency_limit(test_set_status, 1)
+        })
+        .build()
+        .await;
+
+    let pool = remote.handler_pool();
+    assert_eq!(pool.available(), 2);
+    assert_eq!(pool.available_for_handler(test_set_status), Some(1));
+    assert_eq!(
+        pool.available_for_handler("TestActor.GetStatusRequest"),
+        None
+    );
+
+    let _permit = pool.acquire().await;
+    let handler_permit = pool.acquire_handler(test_set_status).await;
+
+    assert!(handler_permit.is_some());
+    assert_eq!(pool.available(), 1);
+    assert_eq!(pool.available_for_handler(test_set_status), Some(0));
+
+    drop(handler_permit);
+    assert_eq!(pool.available_for_handler(test_set_status), Some(1));
+}
+
+static EXECUTING: AtomicUsize = AtomicUsize::new(0);
+static MAX_EXECUTING: AtomicUsize = AtomicUsize::new(0);
+
+struct SlowActor;
+
+impl Actor for SlowActor {}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct SlowRequest;
+
+#[async_trait]
+impl Handler<SlowRequest> for SlowActor {
+    async fn handle(&mut self, _message: SlowRequest, _ctx: &mut ActorContext) {
+        let executing = EXECUTING.fetch_add(1, Ordering::SeqCst) + 1;
+        MAX_EXECUTING.fetch_max(executing, Ordering::SeqCst);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        EXECUTING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+pub async fn test_remote_handler_concurrency_limit_saturated() {
+    create_trace_logger();
+
+    const HANDLER_LIMIT: usize = 2;
+    const ACTORS: usize = 8;
+
+    let slow_request = "SlowActor.SlowRequest";
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_handlers(move |handlers| {
+            handlers
+                .with_handler::<SlowActor, SlowRequest>(slow_request)
+                .handler_concurrency_limit(slow_request, HANDLER_LIMIT)
+        })
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_handlers(move |handlers| {
+            handlers.with_handler::<SlowActor, SlowRequest>(slow_request)
+        })
+        .build()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:31033")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:31034")
+        .with_seed_addr("localhost:31033")
+        .start()
+        .await;
+
+    // each message is sent to a different actor, so the handlers only wait for the pool
+    let mut actor_refs = vec![];
+    for i in 0..ACTORS {
+        let actor_id = format!("slow-actor-{}", i).into_actor_id();
+        let _ = SlowActor
+            .into_actor(Some(actor_id.clone()), remote_a.actor_system())
+            .await
+            .unwrap();
+
+        actor_refs.push(ActorRef::from(RemoteActorRef::<SlowActor>::new(
+            actor_id,
+            1,
+            remote_b.clone(),
+        )));
+    }
+
+    let requests = actor_refs
+        .into_iter()
+        .map(|actor_ref| tokio::spawn(async move { actor_ref.send(SlowRequest).await }))
+        .collect::<Vec<_>>();
+
+    for request in requests {
+        assert!(request.await.unwrap().is_ok());
+    }
+
+    assert_eq!(MAX_EXECUTING.load(Ordering::SeqCst), HANDLER_LIMIT);
+    assert_eq!(
+        remote_a.handler_pool().available_for_handler(slow_request),
+        Some(HANDLER_LIMIT)
+    );
+
+    remote_b.actor_system().shutdown().await;
+    remote_a.actor_system().shutdown().await;
+}
@@ -0,0 +1,71 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message};
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorRef, ActorRefErr, ToActorId};
+use coerce::remote::system::RemoteActorSystem;
+use coerce::remote::RemoteActorRef;
+
+#[macro_use]
+extern crate async_trait;
+
+pub mod util;
+
+#[derive(Default)]
+pub struct Counter {
+    count: usize,
+}
+
+impl Actor for Counter {}
+
+/// Can't be serialised, so is only delivered if the message bypasses serialisation
+pub struct Increment;
+
+impl Message for Increment {
+    type Result = usize;
+}
+
+#[async_trait]
+impl Handler<Increment> for Counter {
+    async fn handle(&mut self, _message: Increment, _ctx: &mut ActorContext) -> usize {
+        self.count += 1;
+        self.count
+    }
+}
+
+#[tokio::test]
+pub async fn test_remote_ref_to_local_actor_bypasses_serialisation() {
+    util::create_trace_logger();
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .build()
+        .await;
+
+    let local_ref = remote
+        .actor_system()
+        .new_actor("counter".to_actor_id(), Counter::default(), Tracked)
+        .await
+        .unwrap();
+
+    let actor_ref = ActorRef::from(RemoteActorRef::<Counter>::new(
+        "counter".to_actor_id(),
+        remote.node_id(),
+        remote.clone(),
+    ));
+
+    assert!(actor_ref.is_remote());
+    assert_eq!(actor_ref.send(Increment).await, Ok(1));
+    assert_eq!(actor_ref.notify(Increment).await, Ok(()));
+    assert_eq!(local_ref.send(Increment).await, Ok(3));
+
+    local_ref.stop(false).await.unwrap();
+
+    assert_eq!(
+        actor_ref.send(Increment).await,
+        Err(ActorRefErr::ActorUnavailable)
+    );
+
+    remote.actor_system().shutdown().await;
+}
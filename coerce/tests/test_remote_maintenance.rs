@@ -0,0 +1,152 @@
+pub mod util;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorRecipe, ToActorId};
+use coerce::remote::system::{NodeId, RemoteActorSystem, SpawnOptions};
+use std::time::Duration;
+
+pub struct TestActor;
+
+#[derive(Serialize, Deserialize)]
+pub struct TestActorRecipe;
+
+impl ActorRecipe for TestActorRecipe {
+    fn read_from_bytes(bytes: &Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
+
+#[derive(Clone)]
+pub struct TestActorFactory;
+
+#[async_trait]
+impl ActorFactory for TestActorFactory {
+    type Actor = TestActor;
+    type Recipe = TestActorRecipe;
+
+    async fn create(&self, _recipe: Self::Recipe) -> Result<TestActor, ActorCreationErr> {
+        Ok(TestActor)
+    }
+}
+
+impl Actor for TestActor {}
+
+async fn create_system(id: u64, zone: &str) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_actors(|builder| builder.with_actor::<TestActorFactory>(TestActorFactory))
+        .with_tag(format!("system-{}", id))
+        .with_id(id)
+        .attribute("zone", zone)
+        .build()
+        .await
+}
+
+async fn wait_for_maintenance(system: &RemoteActorSystem, node_id: NodeId, maintenance: bool) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let node = system
+                .get_nodes()
+                .await
+                .into_iter()
+                .find(|n| n.id == node_id);
+
+            if node.map(|n| n.maintenance) == Some(maintenance) {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("maintenance state advertised");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_node_maintenance() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1, "zone-a").await;
+    let remote_b = create_system(2, "zone-b").await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35211")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35212")
+        .with_seed_addr("localhost:35211")
+        .start()
+        .await;
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    let before = remote_b
+        .deploy_actor_with_options::<TestActorFactory>(
+            Some("before-maintenance".to_actor_id()),
+            TestActorRecipe,
+            SpawnOptions::new().prefer_node_with_attr("zone", "zone-a"),
+        )
+        .await
+        .expect("deploy actor before maintenance");
+
+    assert_eq!(before.node_id(), Some(remote_a.node_id()));
+
+    assert!(remote_a.enter_maintenance().await);
+    assert!(!remote_a.enter_maintenance().await);
+    assert!(remote_a.is_in_maintenance());
+
+    wait_for_maintenance(&remote_a, 1, true).await;
+    wait_for_maintenance(&remote_b, 1, true).await;
+
+    // the node is still a member of the cluster, but isn't chosen for new placements
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("node in maintenance remains a member");
+
+    let during = remote_b
+        .deploy_actor_with_options::<TestActorFactory>(
+            Some("during-maintenance".to_actor_id()),
+            TestActorRecipe,
+            SpawnOptions::new().prefer_node_with_attr("zone", "zone-a"),
+        )
+        .await
+        .expect("deploy actor during maintenance");
+
+    assert_eq!(during.node_id(), Some(remote_b.node_id()));
+
+    assert!(remote_a.exit_maintenance().await);
+    assert!(!remote_a.is_in_maintenance());
+
+    wait_for_maintenance(&remote_b, 1, false).await;
+
+    let after = remote_b
+        .deploy_actor_with_options::<TestActorFactory>(
+            Some("after-maintenance".to_actor_id()),
+            TestActorRecipe,
+            SpawnOptions::new().prefer_node_with_attr("zone", "zone-a"),
+        )
+        .await
+        .expect("deploy actor after maintenance");
+
+    assert_eq!(after.node_id(), Some(remote_a.node_id()));
+}
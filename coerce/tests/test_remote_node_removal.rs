@@ -0,0 +1,112 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::dead_letter::DeadLetterReason;
+use coerce::actor::message::Handler;
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::{Actor, ActorRef, ActorRefErr, ToActorId};
+use coerce::remote::actor::message::GetClients;
+use coerce::remote::stream::pubsub::PubSub;
+use coerce::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
+use coerce::remote::RemoteActorRef;
+use coerce_macros::JsonMessage;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[macro_use]
+extern crate async_trait;
+
+#[macro_use]
+extern crate serde;
+
+mod util;
+
+struct SlowActor;
+
+impl Actor for SlowActor {}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+struct Hang;
+
+#[async_trait]
+impl Handler<Hang> for SlowActor {
+    async fn handle(&mut self, _: Hang, _: &mut ActorContext) {
+        tokio::time::sleep(Duration::from_secs(30)).await;
+    }
+}
+
+#[tokio::test]
+pub async fn test_remote_node_removed_clears_registry() {
+    util::create_trace_logger();
+
+    let node_1 = util::create_cluster_node(1, "127.0.0.1:35267", None, |handlers| {
+        handlers.with_handler::<SlowActor, Hang>("SlowActor.Hang")
+    })
+    .await;
+
+    let node_2 =
+        util::create_cluster_node(2, "127.0.0.1:35268", Some("127.0.0.1:35267"), |handlers| {
+            handlers.with_handler::<SlowActor, Hang>("SlowActor.Hang")
+        })
+        .await;
+
+    node_1
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    let actor_id = "slow-actor".to_actor_id();
+    node_2
+        .actor_system()
+        .new_actor(actor_id.clone(), SlowActor, Tracked)
+        .await
+        .expect("create SlowActor on node=2");
+
+    node_1.register_actor(actor_id.clone(), Some(2));
+    assert_eq!(node_1.locate_actor_node(actor_id.clone()).await, Some(2));
+
+    let mut dead_letters = node_1.actor_system().dead_letters().subscribe();
+    let actor_ref = ActorRef::from(RemoteActorRef::<SlowActor>::new(
+        actor_id.clone(),
+        2,
+        node_1.clone(),
+    ));
+
+    let pending = tokio::spawn(async move { actor_ref.send(Hang).await });
+    while node_1.inflight_node_request_count(2) == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let node = node_1
+        .get_nodes()
+        .await
+        .into_iter()
+        .find(|node| node.id == 2)
+        .unwrap();
+
+    PubSub::publish_locally(
+        SystemTopic,
+        SystemEvent::Cluster(ClusterEvent::NodeRemoved(Arc::new(node.into()))),
+        &node_1,
+    )
+    .await;
+
+    // the pending request fails, rather than waiting for a result that will never arrive
+    let result = tokio::time::timeout(Duration::from_secs(5), pending)
+        .await
+        .expect("request failed once the node was removed")
+        .unwrap();
+
+    assert_eq!(result.unwrap_err(), ActorRefErr::ActorUnavailable);
+    assert_eq!(node_1.inflight_node_request_count(2), 0);
+
+    let dead_letter = dead_letters.recv().await.unwrap();
+    assert_eq!(dead_letter.actor_id, actor_id);
+    assert_eq!(dead_letter.reason, DeadLetterReason::NodeRemoved);
+    assert_eq!(dead_letter.message_type, "test_remote_node_removal::Hang");
+
+    let clients = node_1.client_registry().send(GetClients).await.unwrap();
+    assert!(clients.iter().all(|(addr, _)| addr != "127.0.0.1:35268"));
+
+    // node 2 isn't shutdown, since it would wait for the slow actor to finish handling the message
+    node_1.actor_system().shutdown().await;
+}
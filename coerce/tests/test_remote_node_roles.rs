@@ -0,0 +1,211 @@
+use crate::util::{SetStatusRequest, TestActor, TestActorStatus};
+use async_trait::async_trait;
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorRecipe};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::net::server::RemoteServer;
+use coerce::remote::system::{NodeId, RemoteActorSystem};
+use coerce::sharding::Sharding;
+use coerce::singleton::factory::SingletonFactory;
+use coerce::singleton::{singleton, SingletonBuilder};
+use tracing::Level;
+
+pub mod util;
+
+struct SingletonActor;
+
+impl Actor for SingletonActor {}
+
+struct Factory;
+
+impl SingletonFactory for Factory {
+    type Actor = SingletonActor;
+
+    fn create(&self) -> Self::Actor {
+        SingletonActor
+    }
+}
+
+struct WhereAreYou;
+
+#[async_trait]
+impl Handler<WhereAreYou> for SingletonActor {
+    async fn handle(&mut self, _message: WhereAreYou, ctx: &mut ActorContext) -> NodeId {
+        ctx.system().remote().node_id()
+    }
+}
+
+impl Message for WhereAreYou {
+    type Result = NodeId;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+
+    fn from_bytes(_: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        Ok(Self)
+    }
+
+    fn read_remote_result(res: Vec<u8>) -> Result<Self::Result, MessageUnwrapErr> {
+        let bytes = res
+            .try_into()
+            .map_err(|_| MessageUnwrapErr::DeserializationErr)?;
+        Ok(NodeId::from_be_bytes(bytes))
+    }
+
+    fn write_remote_result(res: Self::Result) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(res.to_be_bytes().to_vec())
+    }
+}
+
+pub struct TestActorRecipe;
+
+impl ActorRecipe for TestActorRecipe {
+    fn read_from_bytes(_bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self)
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(vec![])
+    }
+}
+
+#[derive(Clone)]
+pub struct TestActorFactory;
+
+#[async_trait]
+impl ActorFactory for TestActorFactory {
+    type Actor = TestActor;
+    type Recipe = TestActorRecipe;
+
+    async fn create(&self, _recipe: TestActorRecipe) -> Result<TestActor, ActorCreationErr> {
+        Ok(TestActor {
+            status: None,
+            counter: 0,
+        })
+    }
+}
+
+async fn create_system(node_id: NodeId) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_tag(format!("node-{node_id}"))
+        .with_id(node_id)
+        .with_actor_system(
+            ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new())),
+        )
+        .configure(singleton::<Factory>)
+        .configure(|h| h.with_handler::<SingletonActor, WhereAreYou>("SingletonActor.WhereAreYou"))
+        .with_actors(|a| {
+            a.with_actor(TestActorFactory)
+                .with_handler::<TestActor, SetStatusRequest>("SetStatusRequest")
+        })
+        .build()
+        .await
+}
+
+async fn start_worker(
+    remote: &RemoteActorSystem,
+    listen_addr: &str,
+    seed_addr: Option<&str>,
+    roles: &[&str],
+) -> RemoteServer {
+    let mut worker = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr(listen_addr)
+        .with_roles(roles.iter().copied());
+
+    if let Some(seed_addr) = seed_addr {
+        worker = worker.with_seed_addr(seed_addr);
+    }
+
+    worker.start().await
+}
+
+#[tokio::test]
+pub async fn test_remote_node_roles_exchanged() {
+    util::create_logger(Some(Level::DEBUG));
+
+    let remote = create_system(1).await;
+    let remote2 = create_system(2).await;
+
+    let _server = start_worker(&remote, "localhost:30185", None, &[]).await;
+    let _server2 = start_worker(
+        &remote2,
+        "localhost:30186",
+        Some("localhost:30185"),
+        &["coordinator", "backend"],
+    )
+    .await;
+
+    assert!(remote.node_roles().is_empty());
+    assert_eq!(remote2.node_roles(), &["coordinator", "backend"]);
+
+    let nodes = remote.get_nodes().await;
+    let node_2 = nodes.iter().find(|n| n.id == 2).expect("node 2");
+    assert_eq!(node_2.roles, vec!["coordinator", "backend"]);
+
+    let nodes = remote2.get_nodes().await;
+    let node_1 = nodes.iter().find(|n| n.id == 1).expect("node 1");
+    assert!(node_1.roles.is_empty());
+}
+
+#[tokio::test]
+pub async fn test_remote_role_aware_placement() {
+    util::create_logger(Some(Level::DEBUG));
+
+    let remote = create_system(1).await;
+    let remote2 = create_system(2).await;
+
+    let singleton = SingletonBuilder::new(remote.clone())
+        .factory(Factory)
+        .with_role("coordinator")
+        .build()
+        .await;
+
+    let singleton2 = SingletonBuilder::new(remote2.clone())
+        .factory(Factory)
+        .with_role("coordinator")
+        .build()
+        .await;
+
+    let sharding = Sharding::<TestActorFactory>::builder(remote.clone())
+        .with_role("backend")
+        .build()
+        .await;
+
+    let _sharding2 = Sharding::<TestActorFactory>::builder(remote2.clone())
+        .with_role("backend")
+        .build()
+        .await;
+
+    let _server = start_worker(&remote, "localhost:30187", None, &[]).await;
+    let _server2 = start_worker(
+        &remote2,
+        "localhost:30188",
+        Some("localhost:30187"),
+        &["coordinator", "backend"],
+    )
+    .await;
+
+    // node 1 is the oldest node, but only node 2 has the coordinator role
+    assert_eq!(singleton.send(WhereAreYou).await, Ok(2));
+    assert_eq!(singleton2.send(WhereAreYou).await, Ok(2));
+
+    for i in 0..5 {
+        let entity_id = format!("entity-{i}");
+        let entity = sharding.get(entity_id.clone(), Some(TestActorRecipe));
+        entity
+            .send(SetStatusRequest {
+                status: TestActorStatus::Active,
+            })
+            .await
+            .expect("set status");
+
+        let location = sharding.locate(entity_id).await.expect("entity location");
+        assert_eq!(location.node.node_id, 2);
+    }
+}
@@ -0,0 +1,152 @@
+use bytes::Bytes;
+use coerce::actor::system::ActorSystem;
+use coerce::remote::actor::message::NewClient;
+use coerce::remote::net::client::reconnect::ReconnectPolicy;
+use coerce::remote::net::client::{ClientType, IdentifyErr, RemoteClientRef};
+use coerce::remote::net::message::{ClientEvent, SessionEvent};
+use coerce::remote::net::proto::network::{IdentifyEvent, NodeIdentity};
+use coerce::remote::net::version::{ProtocolVersions, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+pub mod util;
+
+async fn create_system(node_id: u64) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .build()
+        .await
+}
+
+#[test]
+pub fn test_protocol_version_negotiation() {
+    let versions = |min, max| ProtocolVersions { min, max };
+
+    assert_eq!(versions(1, 3).negotiate(&versions(2, 5)), Ok(3));
+    assert_eq!(versions(2, 5).negotiate(&versions(1, 3)), Ok(3));
+    assert_eq!(versions(1, 1).negotiate(&versions(1, 1)), Ok(1));
+
+    let err = versions(1, 2).negotiate(&versions(3, 4)).unwrap_err();
+    assert_eq!(err.local, versions(1, 2));
+    assert_eq!(err.remote, versions(3, 4));
+}
+
+#[tokio::test]
+pub async fn test_remote_server_refuses_incompatible_protocol() {
+    util::create_trace_logger();
+
+    let remote = create_system(1).await;
+    let _ = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35141")
+        .start()
+        .await;
+
+    let stream = TcpStream::connect("127.0.0.1:35141").await.unwrap();
+    let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+
+    let identify = SessionEvent::Identify(IdentifyEvent {
+        source_node_id: 2,
+        min_protocol_version: 99,
+        max_protocol_version: 99,
+        ..Default::default()
+    });
+
+    framed
+        .send(Bytes::from(identify.write_to_bytes().unwrap()))
+        .await
+        .unwrap();
+
+    // the node still identifies itself, so the client knows why it was refused
+    let frame = framed.next().await.unwrap().unwrap();
+    let identity = match ClientEvent::read_from_slice(&frame) {
+        Some(ClientEvent::Identity(identity)) => identity,
+        _ => panic!("expected identity"),
+    };
+
+    assert_eq!(identity.min_protocol_version, MIN_PROTOCOL_VERSION);
+    assert_eq!(identity.max_protocol_version, PROTOCOL_VERSION);
+
+    // and then closes the session
+    let next = tokio::time::timeout(Duration::from_secs(5), framed.next()).await;
+    assert!(matches!(next, Ok(None) | Ok(Some(Err(_)))));
+
+    remote.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_remote_client_refuses_incompatible_protocol() {
+    util::create_trace_logger();
+
+    // a node that only supports a protocol version this node doesn't
+    let listener = TcpListener::bind("127.0.0.1:35142").await.unwrap();
+    let (identify_tx, mut identify_rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+
+            let frame = framed.next().await.unwrap().unwrap();
+            match SessionEvent::read_from_slice(&frame) {
+                Some(SessionEvent::Identify(identify)) => {
+                    let _ = identify_tx.send(identify);
+                }
+                _ => panic!("expected identify"),
+            }
+
+            let identity = ClientEvent::Identity(NodeIdentity {
+                node_id: 2,
+                addr: "127.0.0.1:35142".to_string(),
+                min_protocol_version: 99,
+                max_protocol_version: 99,
+                ..Default::default()
+            });
+
+            framed
+                .send(Bytes::from(identity.write_to_bytes().unwrap()))
+                .await
+                .unwrap();
+        }
+    });
+
+    // the first connection attempt completes before the client is returned, so the client is
+    // identified via its next attempt
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .configure(|c| c.reconnect_policy(ReconnectPolicy::fixed(Duration::from_millis(50), None)))
+        .build()
+        .await;
+
+    let client = remote
+        .client_registry()
+        .send(NewClient {
+            addr: "127.0.0.1:35142".to_string(),
+            client_type: ClientType::Worker,
+            system: remote.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let identity = RemoteClientRef::from(client).identify().await;
+    match identity {
+        Err(IdentifyErr::IncompatibleProtocol(e)) => {
+            assert_eq!(e.local, ProtocolVersions::current());
+            assert_eq!(e.remote, ProtocolVersions { min: 99, max: 99 });
+        }
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("expected an incompatible protocol error"),
+    }
+
+    let identify = identify_rx.recv().await.unwrap();
+    assert_eq!(identify.min_protocol_version, MIN_PROTOCOL_VERSION);
+    assert_eq!(identify.max_protocol_version, PROTOCOL_VERSION);
+
+    remote.actor_system().shutdown().await;
+}
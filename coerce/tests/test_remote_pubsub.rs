@@ -2,11 +2,13 @@ use coerce::actor::context::ActorContext;
 
 use coerce::actor::message::Handler;
 use coerce::actor::system::ActorSystem;
-use coerce::actor::Actor;
+use coerce::actor::{Actor, LocalActorRef};
 
 use coerce::remote::net::StreamData;
-use coerce::remote::stream::pubsub::{PubSub, Receive, Subscription, Topic};
-use coerce::remote::system::RemoteActorSystem;
+use coerce::remote::stream::interest::PubSubRouting;
+use coerce::remote::stream::pubsub::{PartitionedTopic, PubSub, Receive, Subscription, Topic};
+use coerce::remote::system::{NodeId, RemoteActorSystem};
+use std::collections::HashMap;
 use tokio::sync::oneshot::{channel, Sender};
 use tokio::time::Duration;
 
@@ -147,6 +149,16 @@ pub async fn test_pubsub_distributed() {
         .start()
         .await;
 
+    remote
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
     let (sender_a, receiver_a) = channel::<u32>();
     let (sender_b, receiver_b) = channel::<u32>();
 
@@ -198,6 +210,360 @@ pub async fn test_pubsub_distributed() {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OrderEvent {
+    order_id: String,
+    sequence: u32,
+}
+
+pub struct OrderStream;
+
+impl Topic for OrderStream {
+    type Message = OrderEvent;
+
+    fn topic_name() -> &'static str {
+        "test-order-topic"
+    }
+}
+
+impl PartitionedTopic for OrderStream {
+    fn message_key(message: &OrderEvent) -> String {
+        message.order_id.clone()
+    }
+}
+
+impl StreamData for OrderEvent {
+    fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
+
+#[derive(Default)]
+pub struct OrderConsumer {
+    subscription: Option<Subscription>,
+    received: Vec<(String, u32)>,
+}
+
+#[async_trait]
+impl Actor for OrderConsumer {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.subscription = Some(
+            PubSub::subscribe_group::<Self, OrderStream>(OrderStream, "order-processors", ctx)
+                .await
+                .unwrap(),
+        );
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<OrderStream>> for OrderConsumer {
+    async fn handle(&mut self, message: Receive<OrderStream>, _ctx: &mut ActorContext) {
+        self.received
+            .push((message.0.order_id.clone(), message.0.sequence));
+    }
+}
+
+async fn publish_orders(orders: u32, events_per_order: u32, remote: &RemoteActorSystem) {
+    for sequence in 0..events_per_order {
+        for order in 0..orders {
+            let event = OrderEvent {
+                order_id: format!("order-{}", order),
+                sequence,
+            };
+
+            PubSub::publish(OrderStream, event, remote).await;
+        }
+    }
+}
+
+async fn received_orders(consumers: &[LocalActorRef<OrderConsumer>]) -> Vec<Vec<(String, u32)>> {
+    let mut received = vec![];
+    for consumer in consumers {
+        received.push(consumer.exec(|c| c.received.clone()).await.unwrap());
+    }
+
+    received
+}
+
+/// Asserts that every event was delivered exactly once, with every event for the same order
+/// delivered to the same consumer, in the order it was published
+fn assert_ordered_delivery(received: &[Vec<(String, u32)>], orders: u32, events_per_order: u32) {
+    let total: usize = received.iter().map(|r| r.len()).sum();
+    assert_eq!(total, (orders * events_per_order) as usize);
+
+    let mut consumer_by_order = HashMap::new();
+    for (consumer, events) in received.iter().enumerate() {
+        let mut last_sequence: HashMap<&String, u32> = HashMap::new();
+        for (order_id, sequence) in events {
+            assert_eq!(
+                *consumer_by_order
+                    .entry(order_id.clone())
+                    .or_insert(consumer),
+                consumer
+            );
+
+            if let Some(last) = last_sequence.insert(order_id, *sequence) {
+                assert_eq!(*sequence, last + 1);
+            }
+        }
+    }
+
+    assert_eq!(consumer_by_order.len(), orders as usize);
+}
+
+#[tokio::test]
+pub async fn test_pubsub_consumer_group_local() {
+    util::create_trace_logger();
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .build()
+        .await;
+
+    let mut consumers = vec![];
+    for _ in 0..3 {
+        consumers.push(
+            remote
+                .actor_system()
+                .new_anon_actor(OrderConsumer::default())
+                .await
+                .unwrap(),
+        );
+    }
+
+    publish_orders(20, 10, &remote).await;
+
+    tokio::time::timeout(Duration::from_secs(3), async {
+        loop {
+            let received = received_orders(&consumers).await;
+            if received.iter().map(|r| r.len()).sum::<usize>() >= 200 {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("all events received");
+
+    let received = received_orders(&consumers).await;
+    assert_ordered_delivery(&received, 20, 10);
+
+    // partitions are spread across the group
+    assert!(received.iter().filter(|r| !r.is_empty()).count() > 1);
+
+    // once a member leaves, its partitions are reassigned to the remaining members
+    consumers.pop().unwrap().stop(false).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let before = received_orders(&consumers).await;
+    let before: usize = before.iter().map(|r| r.len()).sum();
+
+    publish_orders(20, 1, &remote).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let after = received_orders(&consumers).await;
+    let after: usize = after.iter().map(|r| r.len()).sum();
+    assert_eq!(after - before, 20);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_pubsub_consumer_group_distributed() {
+    util::create_trace_logger();
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .build()
+        .await;
+
+    remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30111")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30112")
+        .with_seed_addr("localhost:30111")
+        .start()
+        .await;
+
+    remote
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    let consumers = vec![
+        remote
+            .actor_system()
+            .new_anon_actor(OrderConsumer::default())
+            .await
+            .unwrap(),
+        remote_b
+            .actor_system()
+            .new_anon_actor(OrderConsumer::default())
+            .await
+            .unwrap(),
+    ];
+
+    // wait until both nodes have the same view of the group, at which point a batch of events
+    // is delivered exactly once, split across both members
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let before = received_orders(&consumers).await;
+            publish_orders(16, 1, &remote).await;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let after = received_orders(&consumers).await;
+
+            let delivered: Vec<usize> = before
+                .iter()
+                .zip(&after)
+                .map(|(before, after)| after.len() - before.len())
+                .collect();
+
+            if delivered.iter().sum::<usize>() == 16 && delivered.iter().all(|d| *d > 0) {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("consumer group membership converged");
+
+    for consumer in &consumers {
+        consumer.exec(|c| c.received.clear()).await.unwrap();
+    }
+
+    publish_orders(10, 10, &remote).await;
+
+    tokio::time::timeout(Duration::from_secs(3), async {
+        loop {
+            let received = received_orders(&consumers).await;
+            if received.iter().map(|r| r.len()).sum::<usize>() >= 100 {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("all events received");
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_ordered_delivery(&received_orders(&consumers).await, 10, 10);
+}
+
+async fn wait_for_recipients(remote: &RemoteActorSystem, expected: Vec<NodeId>) {
+    tokio::time::timeout(Duration::from_secs(3), async {
+        while PubSub::remote_recipients(&StatusStream, remote).await != expected {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("subscriber interest published");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_pubsub_subscriber_routing() {
+    util::create_trace_logger();
+
+    let mut systems = vec![];
+    for id in 1..=2 {
+        let remote = RemoteActorSystem::builder()
+            .with_actor_system(ActorSystem::new())
+            .with_id(id)
+            .configure(|config| config.pubsub_routing(PubSubRouting::Subscribers))
+            .build()
+            .await;
+
+        systems.push(remote);
+    }
+
+    let (remote, remote_b) = (systems[0].clone(), systems[1].clone());
+
+    remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35231")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35232")
+        .with_seed_addr("localhost:35231")
+        .start()
+        .await;
+
+    remote
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    // node 2 has no subscribers, so messages aren't sent to it
+    wait_for_recipients(&remote, vec![]).await;
+
+    let (sender, receiver) = channel::<u32>();
+    let consumer = remote_b
+        .actor_system()
+        .new_anon_actor(TestStreamConsumer {
+            subscription: None,
+            expected_stream_messages: 10,
+            on_completion: Some(sender),
+            received_stream_messages: 0,
+        })
+        .await
+        .unwrap();
+
+    wait_for_recipients(&remote, vec![2]).await;
+
+    for _ in 0..10 {
+        PubSub::publish(StatusStream, StatusEvent::Online, &remote).await;
+    }
+
+    tokio::time::timeout(Duration::from_secs(3), receiver)
+        .await
+        .expect("all messages received")
+        .unwrap();
+
+    consumer.stop(true).await.unwrap();
+
+    // node 2 lets node 1 know it no longer has subscribers once it receives a message
+    tokio::time::timeout(Duration::from_secs(3), async {
+        loop {
+            PubSub::publish(StatusStream, StatusEvent::Offline, &remote).await;
+            if PubSub::remote_recipients(&StatusStream, &remote)
+                .await
+                .is_empty()
+            {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("subscriber interest withdrawn");
+}
+
 impl StreamData for StatusEvent {
     fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
         match data.first() {
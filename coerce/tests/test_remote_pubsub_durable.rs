@@ -0,0 +1,197 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, IntoActor, LocalActorRef};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::net::StreamData;
+use coerce::remote::stream::durable::{DurableTopicErr, RetentionPolicy};
+use coerce::remote::stream::pubsub::{PubSub, Receive, Subscription, Topic};
+use coerce::remote::system::RemoteActorSystem;
+use std::time::Duration;
+
+pub mod util;
+
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+extern crate async_trait;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaymentEvent {
+    payment_id: u32,
+}
+
+pub struct PaymentStream;
+
+impl Topic for PaymentStream {
+    type Message = PaymentEvent;
+
+    fn topic_name() -> &'static str {
+        "test-payment-topic"
+    }
+}
+
+impl StreamData for PaymentEvent {
+    fn read_from_bytes(data: Vec<u8>) -> Option<Self> {
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        serde_json::to_vec(&self).ok()
+    }
+}
+
+pub struct PaymentConsumer {
+    consumer: &'static str,
+    subscription: Option<Subscription>,
+    received: Vec<u32>,
+}
+
+impl PaymentConsumer {
+    fn new(consumer: &'static str) -> Self {
+        Self {
+            consumer,
+            subscription: None,
+            received: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl Actor for PaymentConsumer {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.subscription = Some(
+            PubSub::subscribe_durable::<Self, PaymentStream>(PaymentStream, self.consumer, ctx)
+                .await
+                .unwrap(),
+        );
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<PaymentStream>> for PaymentConsumer {
+    async fn handle(&mut self, message: Receive<PaymentStream>, _ctx: &mut ActorContext) {
+        self.received.push(message.0.payment_id);
+    }
+}
+
+async fn create_system() -> RemoteActorSystem {
+    let system =
+        ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+
+    RemoteActorSystem::builder()
+        .with_actor_system(system)
+        .build()
+        .await
+}
+
+async fn publish_payments(payment_ids: impl Iterator<Item = u32>, remote: &RemoteActorSystem) {
+    for payment_id in payment_ids {
+        PubSub::publish(PaymentStream, PaymentEvent { payment_id }, remote).await;
+    }
+}
+
+async fn wait_for_payments(consumer: &LocalActorRef<PaymentConsumer>, expected: Vec<u32>) {
+    let received = tokio::time::timeout(Duration::from_secs(3), async {
+        loop {
+            let received = consumer.exec(|c| c.received.clone()).await.unwrap();
+            if received.len() >= expected.len() {
+                break received;
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("payments received");
+
+    assert_eq!(received, expected);
+}
+
+async fn start_consumer(
+    consumer: &'static str,
+    remote: &RemoteActorSystem,
+) -> LocalActorRef<PaymentConsumer> {
+    PaymentConsumer::new(consumer)
+        .into_anon_actor(Option::<String>::None, remote.actor_system())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+pub async fn test_pubsub_durable_consumer_receives_missed_messages() {
+    util::create_trace_logger();
+
+    let remote = create_system().await;
+    PubSub::durable(PaymentStream, RetentionPolicy::default(), &remote)
+        .await
+        .unwrap();
+
+    let billing = start_consumer("billing", &remote).await;
+    publish_payments(1..=3, &remote).await;
+    wait_for_payments(&billing, vec![1, 2, 3]).await;
+
+    // messages published while the consumer is offline are delivered once it reconnects,
+    // followed by any new messages
+    billing.stop(false).await.unwrap();
+    publish_payments(4..=6, &remote).await;
+
+    let billing = start_consumer("billing", &remote).await;
+    wait_for_payments(&billing, vec![4, 5, 6]).await;
+
+    publish_payments(7..=7, &remote).await;
+    wait_for_payments(&billing, vec![4, 5, 6, 7]).await;
+
+    // new consumers start from the latest message
+    let audit = start_consumer("audit", &remote).await;
+    publish_payments(8..=8, &remote).await;
+    wait_for_payments(&audit, vec![8]).await;
+}
+
+#[tokio::test]
+pub async fn test_pubsub_durable_retention() {
+    util::create_trace_logger();
+
+    let remote = create_system().await;
+    PubSub::durable(
+        PaymentStream,
+        RetentionPolicy::default().max_messages(2),
+        &remote,
+    )
+    .await
+    .unwrap();
+
+    let billing = start_consumer("billing", &remote).await;
+    billing.stop(false).await.unwrap();
+
+    publish_payments(1..=5, &remote).await;
+
+    // only the most recent messages are retained
+    let billing = start_consumer("billing", &remote).await;
+    wait_for_payments(&billing, vec![4, 5]).await;
+}
+
+#[tokio::test]
+pub async fn test_pubsub_durable_requires_durable_topic() {
+    util::create_trace_logger();
+
+    let remote = create_system().await;
+    let consumer = PaymentConsumer::new("billing")
+        .into_anon_actor(Option::<String>::None, remote.actor_system())
+        .await;
+
+    // the consumer fails to start, since the topic hasn't been made durable
+    assert!(consumer.is_err());
+
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .build()
+        .await;
+
+    assert!(matches!(
+        PubSub::durable(PaymentStream, RetentionPolicy::default(), &remote).await,
+        Err(DurableTopicErr::PersistenceNotConfigured)
+    ));
+}
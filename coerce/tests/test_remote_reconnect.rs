@@ -0,0 +1,177 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, IntoActor};
+use coerce::remote::cluster::node::NodeStatus;
+use coerce::remote::net::client::reconnect::ReconnectPolicy;
+use coerce::remote::stream::pubsub::{PubSub, Receive, Subscription};
+use coerce::remote::stream::system::{ClusterEvent, SystemEvent, SystemTopic};
+use coerce::remote::system::{NodeId, RemoteActorSystem};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+struct QuarantineListener {
+    subscription: Option<Subscription>,
+    on_quarantined: Option<oneshot::Sender<NodeId>>,
+}
+
+#[async_trait]
+impl Actor for QuarantineListener {
+    async fn started(&mut self, ctx: &mut ActorContext) {
+        self.subscription = Some(
+            PubSub::subscribe::<Self, SystemTopic>(SystemTopic, ctx)
+                .await
+                .unwrap(),
+        );
+    }
+}
+
+#[async_trait]
+impl Handler<Receive<SystemTopic>> for QuarantineListener {
+    async fn handle(&mut self, message: Receive<SystemTopic>, _ctx: &mut ActorContext) {
+        if let SystemEvent::Cluster(ClusterEvent::NodeQuarantined(node)) = message.0.as_ref() {
+            if let Some(on_quarantined) = self.on_quarantined.take() {
+                let _ = on_quarantined.send(node.id);
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_reconnect_policy_backoff() {
+    let policy = ReconnectPolicy {
+        initial_delay: Duration::from_millis(100),
+        max_delay: Duration::from_secs(1),
+        multiplier: 2.0,
+        jitter: 0.0,
+        max_attempts: Some(3),
+    };
+
+    assert_eq!(policy.delay(1), Duration::from_millis(100));
+    assert_eq!(policy.delay(2), Duration::from_millis(200));
+    assert_eq!(policy.delay(4), Duration::from_millis(800));
+    assert_eq!(policy.delay(5), Duration::from_secs(1));
+    assert_eq!(policy.delay(100), Duration::from_secs(1));
+
+    assert!(policy.should_reconnect(3));
+    assert!(!policy.should_reconnect(4));
+
+    let policy = ReconnectPolicy {
+        jitter: 0.5,
+        ..policy
+    };
+
+    for _ in 0..100 {
+        let delay = policy.delay(1);
+        assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(150));
+    }
+
+    let policy = ReconnectPolicy::fixed(Duration::from_millis(250), None);
+    assert_eq!(policy.delay(1), Duration::from_millis(250));
+    assert_eq!(policy.delay(10), Duration::from_millis(250));
+    assert!(policy.should_reconnect(usize::MAX));
+}
+
+#[tokio::test]
+pub async fn test_remote_node_quarantined_and_rejoined() {
+    util::create_trace_logger();
+
+    let remote_a = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(1)
+        .with_tag("remote-a")
+        .configure(|c| {
+            c.reconnect_policy(ReconnectPolicy::fixed(Duration::from_millis(50), Some(2)))
+        })
+        .build()
+        .await;
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .build()
+        .await;
+
+    let _server_a = remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:30181")
+        .start()
+        .await;
+
+    let server_b = remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:30182")
+        .with_seed_addr("127.0.0.1:30181")
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    let (tx, rx) = oneshot::channel();
+    let _listener = QuarantineListener {
+        subscription: None,
+        on_quarantined: Some(tx),
+    }
+    .into_anon_actor(Some("quarantine-listener"), remote_a.actor_system())
+    .await
+    .unwrap();
+
+    server_b.stop();
+    remote_b.actor_system().shutdown().await;
+
+    let quarantined_node_id = tokio::time::timeout(Duration::from_secs(10), rx)
+        .await
+        .expect("node quarantined")
+        .unwrap();
+
+    assert_eq!(quarantined_node_id, 2);
+
+    let node_b = remote_a
+        .get_nodes()
+        .await
+        .into_iter()
+        .find(|n| n.id == 2)
+        .unwrap();
+
+    assert_eq!(node_b.status, NodeStatus::Quarantined);
+    assert!(!remote_a.quarantine_node(2).await);
+
+    // the node is quarantined until it rejoins the cluster
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(remote_a
+        .wait_for_members(2, Duration::from_millis(100))
+        .await
+        .is_err());
+
+    let remote_b = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .with_tag("remote-b")
+        .build()
+        .await;
+
+    let _server_b = remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:30182")
+        .start()
+        .await;
+
+    assert!(remote_a.rejoin_node(2).await);
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("quarantined node rejoined");
+}
@@ -0,0 +1,89 @@
+use coerce::remote::net::message::SessionEvent;
+use coerce::remote::net::proto::network::{
+    IdentifyEvent, MessageRequest, PingEvent, SessionHandshake,
+};
+use coerce::remote::net::server::session::state::{
+    SessionAction, SessionStateMachine, SessionStatus,
+};
+
+fn notify() -> SessionEvent {
+    SessionEvent::NotifyActor(MessageRequest::default())
+}
+
+#[test]
+pub fn test_session_state_transitions() {
+    let mut state = SessionStateMachine::new();
+    assert_eq!(state.status(), SessionStatus::AwaitingIdentity);
+
+    let action = state.on_event(SessionEvent::Identify(IdentifyEvent::default()));
+    assert!(matches!(
+        action,
+        SessionAction::Handle(SessionEvent::Identify(_))
+    ));
+    assert_eq!(state.status(), SessionStatus::AwaitingHandshake);
+
+    // peers aren't required to handshake before sending messages
+    assert!(matches!(state.on_event(notify()), SessionAction::Handle(_)));
+    assert!(matches!(
+        state.on_event(SessionEvent::Ping(PingEvent::default())),
+        SessionAction::Handle(_)
+    ));
+
+    // a repeated identify is handled, the client retries if the identity never arrived
+    assert!(matches!(
+        state.on_event(SessionEvent::Identify(IdentifyEvent::default())),
+        SessionAction::Handle(SessionEvent::Identify(_))
+    ));
+    assert_eq!(state.status(), SessionStatus::AwaitingHandshake);
+
+    let action = state.on_event(SessionEvent::Handshake(SessionHandshake::default()));
+    assert!(matches!(
+        action,
+        SessionAction::Handle(SessionEvent::Handshake(_))
+    ));
+    assert_eq!(state.status(), SessionStatus::Active);
+    assert!(matches!(state.on_event(notify()), SessionAction::Handle(_)));
+
+    state.close();
+    assert_eq!(state.status(), SessionStatus::Closing);
+    assert!(matches!(
+        state.on_event(notify()),
+        SessionAction::Discard(..)
+    ));
+}
+
+#[test]
+pub fn test_session_closed_when_not_identified() {
+    let mut state = SessionStateMachine::new();
+
+    assert!(matches!(state.on_event(notify()), SessionAction::Close(_)));
+    assert_eq!(state.status(), SessionStatus::Closing);
+}
+
+#[test]
+pub fn test_session_deferred_until_authenticated() {
+    let mut state = SessionStateMachine::with_authentication();
+
+    state.on_event(SessionEvent::Identify(IdentifyEvent::default()));
+    assert_eq!(state.status(), SessionStatus::AwaitingHandshake);
+
+    assert!(matches!(state.on_event(notify()), SessionAction::Defer(_)));
+    assert!(matches!(
+        state.on_event(SessionEvent::Ping(PingEvent::default())),
+        SessionAction::Handle(_)
+    ));
+
+    let action = state.on_event(SessionEvent::Handshake(SessionHandshake::default()));
+    assert!(matches!(action, SessionAction::Authenticate(_)));
+    assert_eq!(state.status(), SessionStatus::AwaitingHandshake);
+
+    state.on_authenticated();
+    assert_eq!(state.status(), SessionStatus::Active);
+    assert!(matches!(state.on_event(notify()), SessionAction::Handle(_)));
+
+    // repeated handshakes are authenticated too
+    assert!(matches!(
+        state.on_event(SessionEvent::Handshake(SessionHandshake::default())),
+        SessionAction::Authenticate(_)
+    ));
+}
@@ -3,9 +3,11 @@ use crate::util::{
 };
 
 use coerce::actor::message::Message;
+use coerce::actor::system::shutdown::ShutdownReason;
 use coerce::actor::system::ActorSystem;
 use coerce::actor::{
-    Actor, ActorCreationErr, ActorFactory, ActorRecipe, ActorRef, IntoActor, LocalActorRef,
+    Actor, ActorCreationErr, ActorFactory, ActorRecipe, ActorRef, IntoActor, IntoActorId,
+    LocalActorRef,
 };
 use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
 use coerce::persistent::Persistence;
@@ -15,8 +17,10 @@ use coerce::sharding::coordinator::{ShardCoordinator, ShardHostState, ShardHostS
 use coerce::remote::handler::{ActorHandler, RemoteActorHandler};
 use coerce::remote::net::server::RemoteServer;
 use coerce::remote::system::{NodeId, RemoteActorSystem};
+use coerce::sharding::host::locate::locate_shard_in_cluster;
 use coerce::sharding::host::stats::GetStats;
-use coerce::sharding::host::ShardHost;
+use coerce::sharding::host::{shard_actor_id, ShardHost};
+use coerce::sharding::shard::Shard;
 use coerce::sharding::Sharding;
 
 mod sharding;
@@ -129,7 +133,7 @@ pub async fn test_shard_coordinator_shard_allocation() {
         .await;
 
     let initial_allocation = allocation.expect("shard allocation");
-    let res = shard_coordinator.stop().await;
+    let res = shard_coordinator.stop(false).await;
     assert!(res.is_ok());
 
     let host_stats = shard_host
@@ -138,7 +142,7 @@ pub async fn test_shard_coordinator_shard_allocation() {
         .unwrap()
         .await
         .expect("get host stats");
-    let _ = shard_host.unwrap_local().stop().await;
+    let _ = shard_host.unwrap_local().stop(false).await;
 
     let shard_host: ActorRef<ShardHost> = ShardHost::new(
         TestActor::type_name().to_string(),
@@ -261,3 +265,237 @@ pub async fn test_shard_host_actor_request() {
 
     assert_eq!(res_after_system_restart.is_ok(), true);
 }
+
+#[tokio::test]
+pub async fn test_sharding_locate_entity() {
+    util::create_trace_logger();
+
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_tag("system-one")
+        .with_actors(|a| {
+            a.with_actor(TestActorFactory)
+                .with_handler::<TestActor, GetStatusRequest>("GetStatusRequest")
+        })
+        .with_id(1)
+        .build()
+        .await;
+
+    let _server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30121")
+        .start()
+        .await;
+
+    let sharding = Sharding::<TestActorFactory>::builder(remote.clone())
+        .build()
+        .await;
+
+    // locating an entity doesn't allocate its shard
+    assert!(sharding.locate("leon").await.is_none());
+
+    let sharded_actor = sharding.get("leon", Some(TestActorRecipe));
+    let _ = sharded_actor.send(GetStatusRequest).await;
+
+    let location = sharding.locate("leon").await.expect("entity location");
+    assert_eq!(location.node.node_id, 1);
+    assert_eq!(location.node.addr, "localhost:30121");
+    assert_eq!(location.node.node_tag, "system-one");
+}
+
+#[tokio::test]
+pub async fn test_sharding_locate_shard_in_cluster() {
+    util::create_trace_logger();
+
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_actors(|a| {
+            a.with_actor(TestActorFactory)
+                .with_handler::<TestActor, GetStatusRequest>("GetStatusRequest")
+        })
+        .with_id(1)
+        .build()
+        .await;
+
+    let client = RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(2)
+        .build()
+        .await;
+
+    remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30155")
+        .start()
+        .await;
+
+    let sharding = Sharding::<TestActorFactory>::builder(remote.clone())
+        .with_entity_type("TestActor")
+        .build()
+        .await;
+
+    let sharded_actor = sharding.get("leon", Some(TestActorRecipe));
+    let _ = sharded_actor.send(GetStatusRequest).await;
+
+    // node 2 joins once the shard coordinator is running and doesn't host any shards itself,
+    // so entities can only be located via node 1
+    client
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30156")
+        .with_seed_addr("localhost:30155")
+        .start()
+        .await;
+
+    let location = locate_shard_in_cluster("TestActor", "leon".into_actor_id(), &client)
+        .await
+        .expect("shard location");
+    assert_eq!(location.node_id, Some(1));
+
+    assert!(
+        locate_shard_in_cluster("UnknownActor", "leon".into_actor_id(), &client)
+            .await
+            .is_none()
+    );
+}
+
+#[tokio::test]
+pub async fn test_shard_coordinator_preferred_node_allocation() {
+    util::create_trace_logger();
+
+    let handler = RemoteActorHandler::<TestActor, TestActorFactory>::new(TestActorFactory {});
+
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_tag("system-one")
+        .with_actors(|a| a.with_actor(TestActorFactory))
+        .with_id(1)
+        .attribute("zone", "zone-a")
+        .build()
+        .await;
+
+    let _server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30131")
+        .start()
+        .await;
+
+    let mut hosts = vec![];
+    for host_id in ["shard-host-1", "shard-host-2"] {
+        let shard_host: ActorRef<ShardHost> = ShardHost::new(
+            TestActor::type_name().to_string(),
+            handler.new_boxed(),
+            None,
+        )
+        .into_actor(Some(host_id.to_string()), remote.actor_system())
+        .await
+        .expect("ShardHost start")
+        .into();
+
+        hosts.push(shard_host);
+    }
+
+    let mut shard_coordinator = ShardCoordinator::new(
+        TestActor::type_name().to_string(),
+        hosts[0].clone().unwrap_local(),
+    )
+    .with_preferred_node_attribute(Some(("zone".into(), "zone-a".into())));
+
+    // node 2 isn't a member of the cluster, so doesn't have the preferred attribute
+    for (node_id, shard_host) in [(1, hosts[0].clone()), (2, hosts[1].clone())] {
+        shard_coordinator.add_host(ShardHostState {
+            node_id,
+            node_tag: format!("system-{}", node_id),
+            shards: Default::default(),
+            actor: shard_host,
+            status: ShardHostStatus::Ready,
+        });
+    }
+
+    let shard_coordinator = shard_coordinator
+        .into_actor(Some("shard-coordinator".to_string()), remote.actor_system())
+        .await
+        .expect("ShardCoordinator start");
+
+    for shard_id in 0..4 {
+        let allocation = shard_coordinator
+            .send(AllocateShard {
+                shard_id,
+                rebalancing: false,
+            })
+            .await
+            .expect("shard allocation");
+
+        assert_eq!(allocation, AllocateShardResult::Allocated(shard_id, 1));
+    }
+}
+
+#[tokio::test]
+pub async fn test_sharding_coordinated_shutdown_stops_hosted_shards() {
+    util::create_trace_logger();
+
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_tag("system-one")
+        .with_actors(|a| {
+            a.with_actor(TestActorFactory)
+                .with_handler::<TestActor, GetStatusRequest>("GetStatusRequest")
+        })
+        .with_id(1)
+        .build()
+        .await;
+
+    let _server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30141")
+        .start()
+        .await;
+
+    let sharding = Sharding::<TestActorFactory>::builder(remote.clone())
+        .build()
+        .await;
+
+    let sharded_actor = sharding.get("leon", Some(TestActorRecipe));
+    let _ = sharded_actor.send(GetStatusRequest).await;
+
+    let host_stats = sharding
+        .shard_host()
+        .send(GetStats)
+        .await
+        .unwrap()
+        .await
+        .expect("get host stats");
+
+    let shard_id = *host_stats
+        .hosted_shards
+        .keys()
+        .next()
+        .expect("hosted shard");
+    let shard = remote
+        .actor_system()
+        .get_tracked_actor::<Shard>(shard_actor_id(sharding.shard_entity(), shard_id))
+        .await
+        .expect("shard actor");
+
+    let summary = remote
+        .actor_system()
+        .coordinated_shutdown(ShutdownReason::Requested)
+        .await;
+
+    let task_names: Vec<&str> = summary.tasks.iter().map(|t| t.name.as_str()).collect();
+    assert!(task_names.contains(&"leave-cluster"));
+    assert!(task_names
+        .iter()
+        .any(|name| name.starts_with("shard-handoff-")));
+
+    assert!(summary.tasks.iter().all(|t| !t.timed_out));
+    assert!(shard.is_stopped());
+}
@@ -0,0 +1,209 @@
+use crate::util::{
+    GetCounterRequest, GetStatusRequest, GetStatusResponse, SetStatusRequest, TestActor,
+    TestActorStatus,
+};
+use async_trait::async_trait;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{
+    ActorCreationErr, ActorFactory, ActorId, ActorRecipe, ActorRefErr, IntoActorId,
+};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::sharding::bulk::{BulkProgress, EntitySelector};
+use coerce::sharding::index::{entity_index, EntityAttributes, EntityIndex, IndexQuery};
+use coerce::sharding::Sharding;
+use std::time::{Duration, Instant};
+use tracing::Level;
+
+pub mod util;
+
+pub struct TestActorRecipe;
+
+impl ActorRecipe for TestActorRecipe {
+    fn read_from_bytes(_bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self)
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(vec![])
+    }
+}
+
+#[derive(Clone)]
+pub struct TestActorFactory;
+
+#[async_trait]
+impl ActorFactory for TestActorFactory {
+    type Actor = TestActor;
+    type Recipe = TestActorRecipe;
+
+    async fn create(&self, _recipe: TestActorRecipe) -> Result<TestActor, ActorCreationErr> {
+        Ok(TestActor {
+            status: None,
+            counter: 0,
+        })
+    }
+}
+
+async fn create_system() -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_tag("node-1")
+        .with_id(1)
+        .with_actor_system(
+            ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new())),
+        )
+        .configure(entity_index::<TestActor>)
+        .with_actors(|a| {
+            a.with_actor(TestActorFactory)
+                .with_handler::<TestActor, SetStatusRequest>("SetStatusRequest")
+                .with_handler::<TestActor, GetStatusRequest>("GetStatusRequest")
+        })
+        .build()
+        .await
+}
+
+fn entity_ids(n: usize) -> Vec<ActorId> {
+    (0..n)
+        .map(|i| format!("entity-{i}").into_actor_id())
+        .collect()
+}
+
+const ACTIVE: SetStatusRequest = SetStatusRequest {
+    status: TestActorStatus::Active,
+};
+
+#[tokio::test]
+pub async fn test_sharding_bulk_operations() {
+    util::create_logger(Some(Level::DEBUG));
+
+    let remote = create_system().await;
+    let sharding = Sharding::<TestActorFactory>::builder(remote.clone())
+        .build()
+        .await;
+
+    let index = EntityIndex::<TestActor>::builder(remote.clone())
+        .build()
+        .await;
+
+    remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35111")
+        .start()
+        .await;
+
+    for (i, entity_id) in entity_ids(5).into_iter().enumerate() {
+        let status = if i % 2 == 0 { "overdue" } else { "paid" };
+        index
+            .update(entity_id, EntityAttributes::new().with("status", status))
+            .await
+            .unwrap();
+    }
+
+    // entities selected via the index
+    let report = sharding
+        .broadcast_to_entities(
+            EntitySelector::query(index.clone(), IndexQuery::eq("status", "overdue")),
+            ACTIVE,
+        )
+        .with_recipe(TestActorRecipe)
+        .max_in_flight(2)
+        .start()
+        .wait()
+        .await
+        .unwrap();
+
+    assert!(report.is_success(), "{:?}", report.failures);
+    assert_eq!(
+        report.progress,
+        BulkProgress {
+            total: 3,
+            succeeded: 3,
+            failed: 0
+        }
+    );
+
+    // only the selected entities were created
+    for (i, entity_id) in entity_ids(5).into_iter().enumerate() {
+        let status = sharding
+            .get(entity_id.clone(), None)
+            .send(GetStatusRequest)
+            .await;
+
+        let expected = if i % 2 == 0 {
+            Ok(GetStatusResponse::Ok(TestActorStatus::Active))
+        } else {
+            Err(ActorRefErr::NotFound(entity_id))
+        };
+
+        assert_eq!(status, expected);
+    }
+
+    // without a recipe, only existing entities receive the message
+    let report = sharding
+        .broadcast_to_entities(EntitySelector::ids(["entity-0", "entity-1"]), ACTIVE)
+        .start()
+        .wait()
+        .await
+        .unwrap();
+
+    assert_eq!(report.progress.succeeded, 1);
+    assert_eq!(
+        report.failures,
+        vec![(
+            "entity-1".into_actor_id(),
+            ActorRefErr::NotFound("entity-1".into_actor_id())
+        )]
+    );
+
+    // entities selected by id, the progress is reported as each entity completes
+    let start = Instant::now();
+    let operation = sharding
+        .broadcast_to_entities(entity_ids(10), ACTIVE)
+        .with_recipe(TestActorRecipe)
+        .rate_limit(20)
+        .start();
+
+    let mut progress = operation.progress();
+    let mut updates = 0;
+    while progress.changed().await.is_ok() {
+        updates += 1;
+    }
+
+    let report = operation.wait().await.unwrap();
+    assert_eq!(report.progress.succeeded, 10);
+    assert_eq!(*progress.borrow(), report.progress);
+    assert!(updates > 1);
+
+    // 10 entities at 20 per second, the first is sent immediately
+    assert!(start.elapsed() >= Duration::from_millis(450));
+
+    // cancelled operations stop sending the message to further entities
+    let operation = sharding
+        .broadcast_to_entities(
+            EntitySelector::ids((0..100).map(|i| format!("entity-{i}"))),
+            ACTIVE,
+        )
+        .with_recipe(TestActorRecipe)
+        .rate_limit(20)
+        .start();
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    operation.cancel();
+
+    let report = operation.wait().await.unwrap();
+    assert!(report.cancelled);
+    assert!(!report.is_success());
+    assert!(report.progress.completed() < 100);
+    assert_eq!(report.progress.total, 100);
+
+    // the message must have a remote handler registered
+    let result = sharding
+        .broadcast_to_entities(entity_ids(1), GetCounterRequest())
+        .start()
+        .wait()
+        .await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,169 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorId, ActorRecipe, IntoActorId};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::sharding::coordinator::ShardId;
+use coerce::sharding::extractor::{HashShardId, MessageExtractor};
+use coerce::sharding::Sharding;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+pub struct Account {
+    balance: u64,
+}
+
+impl Actor for Account {}
+
+/// Deposits into an account, the message carries the account it's for rather than being
+/// wrapped in an envelope
+pub struct Deposit {
+    account_id: String,
+    amount: u64,
+}
+
+impl Message for Deposit {
+    type Result = u64;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        let mut bytes = self.amount.to_be_bytes().to_vec();
+        bytes.extend_from_slice(self.account_id.as_bytes());
+        Ok(bytes)
+    }
+
+    fn from_bytes(mut b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        if b.len() < 8 {
+            return Err(MessageUnwrapErr::DeserializationErr);
+        }
+
+        let account_id = b.split_off(8);
+        Ok(Self {
+            amount: u64::from_be_bytes(b.try_into().unwrap()),
+            account_id: String::from_utf8(account_id)
+                .map_err(|_| MessageUnwrapErr::DeserializationErr)?,
+        })
+    }
+
+    fn read_remote_result(res: Vec<u8>) -> Result<u64, MessageUnwrapErr> {
+        res.try_into()
+            .map(u64::from_be_bytes)
+            .map_err(|_| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn write_remote_result(res: u64) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(res.to_be_bytes().to_vec())
+    }
+}
+
+#[async_trait]
+impl Handler<Deposit> for Account {
+    async fn handle(&mut self, message: Deposit, _ctx: &mut ActorContext) -> u64 {
+        self.balance += message.amount;
+        self.balance
+    }
+}
+
+pub struct AccountRecipe;
+
+impl ActorRecipe for AccountRecipe {
+    fn read_from_bytes(_bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self)
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(vec![])
+    }
+}
+
+#[derive(Clone)]
+pub struct AccountFactory;
+
+#[async_trait]
+impl ActorFactory for AccountFactory {
+    type Actor = Account;
+    type Recipe = AccountRecipe;
+
+    async fn create(&self, _recipe: AccountRecipe) -> Result<Account, ActorCreationErr> {
+        Ok(Account { balance: 0 })
+    }
+}
+
+pub struct AccountExtractor {
+    shards: HashShardId,
+}
+
+impl MessageExtractor<AccountFactory, Deposit> for AccountExtractor {
+    fn entity_id(&self, message: &Deposit) -> ActorId {
+        format!("account-{}", message.account_id).into_actor_id()
+    }
+
+    fn shard_id(&self, entity_id: &ActorId, _message: &Deposit) -> Option<ShardId> {
+        Some(self.shards.shard_id(entity_id))
+    }
+
+    fn recipe(&self, _message: &Deposit) -> Option<AccountRecipe> {
+        Some(AccountRecipe)
+    }
+}
+
+fn deposit(account_id: &str, amount: u64) -> Deposit {
+    Deposit {
+        account_id: account_id.to_string(),
+        amount,
+    }
+}
+
+#[tokio::test]
+pub async fn test_sharding_message_extractor() {
+    util::create_trace_logger();
+
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_actors(|a| {
+            a.with_actor(AccountFactory)
+                .with_handler::<Account, Deposit>("Deposit")
+        })
+        .with_id(1)
+        .build()
+        .await;
+
+    let _server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35276")
+        .start()
+        .await;
+
+    let shards = HashShardId::new(8);
+    let sharding = Sharding::<AccountFactory>::builder(remote.clone())
+        .with_allocator(shards)
+        .build()
+        .await;
+
+    let accounts = sharding.with_extractor(AccountExtractor { shards });
+
+    assert_eq!(accounts.send(deposit("a", 10)).await, Ok(10));
+    assert_eq!(accounts.send(deposit("b", 7)).await, Ok(7));
+    assert_eq!(accounts.send(deposit("a", 5)).await, Ok(15));
+
+    // the extractor and the allocator agree, so the entity can also be reached by its id
+    assert_eq!(
+        sharding.get("account-a", None).send(deposit("a", 0)).await,
+        Ok(15)
+    );
+
+    let location = sharding.locate("account-b").await.unwrap();
+    assert_eq!(
+        location.shard_id,
+        shards.shard_id(&"account-b".into_actor_id())
+    );
+    assert!(location.shard_id < 8);
+
+    remote.actor_system().shutdown().await;
+}
@@ -0,0 +1,159 @@
+use crate::util::TestActor;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActorId;
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::storage::JournalStorageRef;
+use coerce::persistent::Persistence;
+use coerce::remote::system::{NodeId, RemoteActorSystem};
+use coerce::sharding::index::{entity_index, EntityAttributes, EntityIndex, IndexQuery};
+use tracing::Level;
+
+pub mod util;
+
+struct SharedStorage(JournalStorageRef);
+
+impl StorageProvider for SharedStorage {
+    fn journal_storage(&self) -> Option<JournalStorageRef> {
+        Some(self.0.clone())
+    }
+}
+
+async fn create_system(node_id: NodeId, storage: JournalStorageRef) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_tag(format!("node-{node_id}"))
+        .with_id(node_id)
+        .with_actor_system(
+            ActorSystem::new().to_persistent(Persistence::from(SharedStorage(storage))),
+        )
+        .configure(entity_index::<TestActor>)
+        .build()
+        .await
+}
+
+fn invoice(status: &str, region: &str) -> EntityAttributes {
+    EntityAttributes::new()
+        .with("status", status)
+        .with("region", region)
+}
+
+#[tokio::test]
+pub async fn test_remote_entity_index_queries() {
+    util::create_logger(Some(Level::DEBUG));
+
+    let storage = InMemoryStorageProvider::new().journal_storage().unwrap();
+    let remote = create_system(1, storage.clone()).await;
+    let remote2 = create_system(2, storage.clone()).await;
+
+    let _index = EntityIndex::<TestActor>::builder(remote.clone())
+        .snapshot_interval(3)
+        .build()
+        .await;
+
+    let index2 = EntityIndex::<TestActor>::builder(remote2.clone())
+        .snapshot_interval(3)
+        .build()
+        .await;
+
+    remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30197")
+        .start()
+        .await;
+
+    remote2
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30198")
+        .with_seed_addr("localhost:30197")
+        .start()
+        .await;
+
+    // node 1 is the leader, so updates from node 2 are sent to the remote index
+    index2
+        .update("invoice-1", invoice("overdue", "eu"))
+        .await
+        .unwrap();
+    index2
+        .update("invoice-2", invoice("paid", "eu"))
+        .await
+        .unwrap();
+    index2
+        .update("invoice-3", invoice("overdue", "us"))
+        .await
+        .unwrap();
+    index2
+        .update("invoice-4", invoice("overdue", "eu"))
+        .await
+        .unwrap();
+
+    let ids = |ids: &[&str]| ids.iter().map(|id| id.into_actor_id()).collect::<Vec<_>>();
+
+    assert_eq!(
+        index2.find(IndexQuery::eq("status", "overdue")).await,
+        Ok(ids(&["invoice-1", "invoice-3", "invoice-4"]))
+    );
+
+    assert_eq!(
+        index2
+            .find(IndexQuery::eq("status", "overdue").and_eq("region", "eu"))
+            .await,
+        Ok(ids(&["invoice-1", "invoice-4"]))
+    );
+
+    assert_eq!(
+        index2
+            .find(IndexQuery::eq("status", "overdue").limit(1))
+            .await,
+        Ok(ids(&["invoice-1"]))
+    );
+
+    assert_eq!(
+        index2.find(IndexQuery::eq("status", "cancelled")).await,
+        Ok(vec![])
+    );
+
+    // updates replace the entity's previous attributes
+    index2
+        .update("invoice-1", invoice("paid", "eu"))
+        .await
+        .unwrap();
+    index2.remove("invoice-4").await.unwrap();
+
+    assert_eq!(
+        index2.find(IndexQuery::eq("status", "overdue")).await,
+        Ok(ids(&["invoice-3"]))
+    );
+
+    assert_eq!(
+        index2.find(IndexQuery::all()).await,
+        Ok(ids(&["invoice-1", "invoice-2", "invoice-3"]))
+    );
+
+    remote.actor_system().shutdown().await;
+    remote2.actor_system().shutdown().await;
+
+    // the index is recovered from its snapshot and journal
+    let remote3 = create_system(3, storage).await;
+    let index3 = EntityIndex::<TestActor>::builder(remote3.clone())
+        .build()
+        .await;
+
+    remote3
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30199")
+        .start()
+        .await;
+
+    assert_eq!(
+        index3.find(IndexQuery::eq("status", "paid")).await,
+        Ok(ids(&["invoice-1", "invoice-2"]))
+    );
+
+    assert_eq!(
+        index3.find(IndexQuery::eq("region", "us")).await,
+        Ok(ids(&["invoice-3"]))
+    );
+}
@@ -0,0 +1,136 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorRecipe};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::sharding::shard::passivation::PassivationConfig;
+use coerce::sharding::Sharding;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+pub struct Counter {
+    count: u64,
+}
+
+impl Actor for Counter {}
+
+pub struct Increment;
+
+impl Message for Increment {
+    type Result = u64;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+
+    fn from_bytes(_: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        Ok(Self)
+    }
+
+    fn read_remote_result(res: Vec<u8>) -> Result<u64, MessageUnwrapErr> {
+        res.try_into()
+            .map(u64::from_be_bytes)
+            .map_err(|_| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn write_remote_result(res: u64) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(res.to_be_bytes().to_vec())
+    }
+}
+
+#[async_trait]
+impl Handler<Increment> for Counter {
+    async fn handle(&mut self, _message: Increment, _ctx: &mut ActorContext) -> u64 {
+        self.count += 1;
+        self.count
+    }
+}
+
+pub struct CounterRecipe;
+
+impl ActorRecipe for CounterRecipe {
+    fn read_from_bytes(_bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self)
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(vec![])
+    }
+}
+
+/// Counts the entities created, so the test can tell when a passivated entity is re-created
+#[derive(Clone, Default)]
+pub struct CounterFactory {
+    created: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ActorFactory for CounterFactory {
+    type Actor = Counter;
+    type Recipe = CounterRecipe;
+
+    async fn create(&self, _recipe: CounterRecipe) -> Result<Counter, ActorCreationErr> {
+        self.created.fetch_add(1, Ordering::SeqCst);
+        Ok(Counter { count: 0 })
+    }
+}
+
+#[tokio::test]
+pub async fn test_sharding_passivates_idle_entities() {
+    util::create_trace_logger();
+
+    let factory = CounterFactory::default();
+    let created = factory.created.clone();
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_actors(|a| {
+            a.with_actor(factory)
+                .with_handler::<Counter, Increment>("Increment")
+        })
+        .with_id(1)
+        .build()
+        .await;
+
+    let _server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35273")
+        .start()
+        .await;
+
+    let sharding = Sharding::<CounterFactory>::builder(remote.clone())
+        .with_passivation(PassivationConfig {
+            idle_timeout: Duration::from_millis(300),
+            check_interval: Duration::from_millis(50),
+        })
+        .build()
+        .await;
+
+    let counter = sharding.get("counter", Some(CounterRecipe));
+
+    // entities that keep receiving messages aren't passivated
+    for expected in 1..=6 {
+        assert_eq!(counter.send(Increment).await, Ok(expected));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(created.load(Ordering::SeqCst), 1);
+
+    tokio::time::sleep(Duration::from_millis(600)).await;
+
+    // the idle entity was stopped, and is re-created by the next message
+    assert_eq!(counter.send(Increment).await, Ok(1));
+    assert_eq!(counter.send(Increment).await, Ok(2));
+    assert_eq!(created.load(Ordering::SeqCst), 2);
+
+    remote.actor_system().shutdown().await;
+}
@@ -1,26 +1,38 @@
 use crate::util::{
     GetStatusRequest, GetStatusResponse, SetStatusRequest, TestActor, TestActorStatus,
 };
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::oneshot;
 use tracing::Level;
 
+use coerce::actor::context::ActorContext;
 use coerce::actor::describe::DescribeAll;
 use coerce::actor::describe::DescribeOptions;
-use coerce::actor::message::Message;
+use coerce::actor::message::Handler;
 use coerce::actor::system::ActorSystem;
 use coerce::actor::{
     Actor, ActorCreationErr, ActorFactory, ActorRecipe, ActorRef, IntoActor, LocalActorRef,
 };
 use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
-use coerce::persistent::Persistence;
-
-use coerce::sharding::coordinator::{ShardCoordinator, ShardHostState, ShardHostStatus};
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::storage::JournalStorageRef;
+use coerce::persistent::journal::types::JournalTypes;
+use coerce::persistent::migration::MigrationSnapshot;
+use coerce::persistent::{Persistence, PersistentActor, Recover, RecoverSnapshot};
+
+use coerce::sharding::coordinator::allocation::{AllocateShard, AllocateShardResult};
+use coerce::sharding::coordinator::balancing::Rebalance;
+use coerce::sharding::coordinator::stats::GetShardingStats;
+use coerce::sharding::coordinator::strategy::{
+    LeastShardAllocationStrategy, ShardAllocationStrategy,
+};
+use coerce::sharding::coordinator::{ShardCoordinator, ShardHostState, ShardHostStatus, ShardId};
 
 use coerce::sharding::host::ShardHost;
 use coerce::sharding::Sharding;
 
+use coerce::remote::handler::{ActorHandler, RemoteActorHandler};
 use coerce::remote::heartbeat::HeartbeatConfig;
 use coerce::remote::net::server::RemoteServer;
 use coerce::remote::system::{NodeId, RemoteActorSystem};
@@ -195,3 +207,353 @@ pub async fn test_shard_rebalancing_upon_node_termination() {
 
     assert_eq!(res_after_losing_node_1.is_ok(), true);
 }
+
+pub struct CounterRecipe {
+    id: String,
+}
+
+impl ActorRecipe for CounterRecipe {
+    fn read_from_bytes(bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self {
+            id: String::from_utf8(bytes.clone()).ok()?,
+        })
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(self.id.clone().into_bytes())
+    }
+}
+
+#[derive(Clone)]
+pub struct CounterFactory;
+
+#[async_trait]
+impl ActorFactory for CounterFactory {
+    type Actor = Counter;
+    type Recipe = CounterRecipe;
+
+    async fn create(&self, recipe: CounterRecipe) -> Result<Counter, ActorCreationErr> {
+        Ok(Counter {
+            id: recipe.id,
+            count: 0,
+            recovered_messages: 0,
+            recovered_snapshot: false,
+        })
+    }
+}
+
+pub struct Counter {
+    id: String,
+    count: i32,
+    recovered_messages: i32,
+    recovered_snapshot: bool,
+}
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("()")]
+pub struct Increment;
+
+#[derive(JsonMessage, Serialize, Deserialize)]
+#[result("CounterState")]
+pub struct GetCounter;
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct CounterState {
+    count: i32,
+    recovered_messages: i32,
+    recovered_snapshot: bool,
+}
+
+#[derive(JsonSnapshot, Serialize, Deserialize)]
+pub struct CounterSnapshot {
+    count: i32,
+}
+
+#[async_trait]
+impl PersistentActor for Counter {
+    fn persistence_key(&self, _ctx: &ActorContext) -> String {
+        format!("counter-{}", &self.id)
+    }
+
+    fn configure(journal: &mut JournalTypes<Self>) {
+        journal
+            .snapshot::<CounterSnapshot>("counter-snapshot")
+            .message::<Increment>("increment");
+    }
+
+    async fn on_migrate(&mut self, ctx: &mut ActorContext) -> Option<MigrationSnapshot> {
+        self.migration_snapshot_of(CounterSnapshot { count: self.count }, ctx)
+    }
+}
+
+#[async_trait]
+impl Handler<Increment> for Counter {
+    async fn handle(&mut self, message: Increment, ctx: &mut ActorContext) {
+        if self.persist(&message, ctx).await.is_ok() {
+            self.count += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl Handler<GetCounter> for Counter {
+    async fn handle(&mut self, _message: GetCounter, _ctx: &mut ActorContext) -> CounterState {
+        CounterState {
+            count: self.count,
+            recovered_messages: self.recovered_messages,
+            recovered_snapshot: self.recovered_snapshot,
+        }
+    }
+}
+
+#[async_trait]
+impl Recover<Increment> for Counter {
+    async fn recover(&mut self, _message: Increment, _ctx: &mut ActorContext) {
+        self.count += 1;
+        self.recovered_messages += 1;
+    }
+}
+
+#[async_trait]
+impl RecoverSnapshot<CounterSnapshot> for Counter {
+    async fn recover(&mut self, snapshot: CounterSnapshot, _ctx: &mut ActorContext) {
+        self.count = snapshot.count;
+        self.recovered_snapshot = true;
+    }
+}
+
+/// Shares one journal between nodes, while each node has its own [`Persistence`]
+struct SharedStorage(JournalStorageRef);
+
+impl StorageProvider for SharedStorage {
+    fn journal_storage(&self) -> Option<JournalStorageRef> {
+        Some(self.0.clone())
+    }
+}
+
+async fn create_counter_system(
+    storage: JournalStorageRef,
+    listen_addr: &str,
+    node_id: NodeId,
+    seed_addr: Option<&str>,
+    role: &str,
+) -> (RemoteActorSystem, RemoteServer) {
+    let sys = ActorSystem::new().to_persistent(Persistence::from(SharedStorage(storage)));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_tag(format!("node-{node_id}"))
+        .with_actors(|a| {
+            a.with_actor(CounterFactory)
+                .with_handler::<Counter, Increment>("Increment")
+                .with_handler::<Counter, GetCounter>("GetCounter")
+        })
+        .attribute("role", role)
+        .with_id(node_id)
+        .build()
+        .await;
+
+    let mut server = remote.clone().cluster_worker().listen_addr(listen_addr);
+
+    if let Some(seed_addr) = seed_addr {
+        server = server.with_seed_addr(seed_addr);
+    }
+
+    let server = server.start().await;
+
+    (remote, server)
+}
+
+#[tokio::test]
+pub async fn test_shard_rebalancing_ships_entity_snapshots() {
+    util::create_logger(Some(Level::DEBUG));
+
+    let storage = InMemoryStorageProvider::new().journal_storage().unwrap();
+    let (remote_a, _server_a) =
+        create_counter_system(storage.clone(), "127.0.0.1:30171", 1, None, "default").await;
+
+    let sharding_a = Sharding::<CounterFactory>::builder(remote_a.clone())
+        .prefer_node_with_attr("role", "counters")
+        .with_snapshot_shipping()
+        .build()
+        .await;
+
+    let counter = sharding_a.get(
+        "counter-1".to_string(),
+        Some(CounterRecipe {
+            id: "counter-1".to_string(),
+        }),
+    );
+
+    for _ in 0..5 {
+        counter.send(Increment).await.expect("increment");
+    }
+
+    let state = counter.send(GetCounter).await.expect("get counter");
+    assert_eq!(state.count, 5);
+    assert!(!state.recovered_snapshot);
+
+    // node B is preferred for the counter shard, so the shard is moved to it once it joins
+    let (remote_b, _server_b) = create_counter_system(
+        storage,
+        "127.0.0.1:30172",
+        2,
+        Some("127.0.0.1:30171"),
+        "counters",
+    )
+    .await;
+
+    let sharding_b = Sharding::<CounterFactory>::builder(remote_b.clone())
+        .prefer_node_with_attr("role", "counters")
+        .with_snapshot_shipping()
+        .build()
+        .await;
+
+    let counter = sharding_b.get("counter-1".to_string(), None);
+    let mut migrated_state = None;
+    for _ in 0..40 {
+        if let Ok(state) = counter.send(GetCounter).await {
+            if state.recovered_snapshot {
+                migrated_state = Some(state);
+                break;
+            }
+        }
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+
+    let expected_state = CounterState {
+        count: 5,
+        recovered_messages: 0,
+        recovered_snapshot: true,
+    };
+
+    assert_eq!(migrated_state, Some(expected_state));
+    assert!(remote_b
+        .actor_system()
+        .persistence()
+        .unwrap()
+        .shipped_snapshots()
+        .is_empty());
+}
+
+/// Allocates shards with the [`LeastShardAllocationStrategy`], recording
+/// the number of shards chosen by each round of rebalancing
+struct RecordedStrategy {
+    rounds: Arc<Mutex<Vec<usize>>>,
+}
+
+impl ShardAllocationStrategy for RecordedStrategy {
+    fn allocate(&self, shard_id: ShardId, hosts: &[&ShardHostState]) -> Option<NodeId> {
+        LeastShardAllocationStrategy.allocate(shard_id, hosts)
+    }
+
+    fn rebalance(&self, hosts: &[&ShardHostState], max_shards: usize) -> Vec<ShardId> {
+        let shards = LeastShardAllocationStrategy.rebalance(hosts, max_shards);
+        self.rounds.lock().unwrap().push(shards.len());
+        shards
+    }
+}
+
+#[tokio::test]
+pub async fn test_shard_rebalancing_moves_limited_shards_per_round() {
+    util::create_trace_logger();
+
+    let handler = RemoteActorHandler::<TestActor, TestActorFactory>::new(TestActorFactory);
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_actors(|a| a.with_actor(TestActorFactory))
+        .with_id(1)
+        .build()
+        .await;
+
+    let mut hosts = vec![];
+    for node_id in 1..=3 {
+        let shard_host: ActorRef<ShardHost> = ShardHost::new(
+            TestActor::type_name().to_string(),
+            handler.new_boxed(),
+            None,
+        )
+        .into_actor(
+            Some(format!("shard-host-{}", node_id)),
+            remote.actor_system(),
+        )
+        .await
+        .expect("ShardHost start")
+        .into();
+
+        hosts.push(shard_host);
+    }
+
+    let rounds = Arc::new(Mutex::new(vec![]));
+    let mut shard_coordinator = ShardCoordinator::new(
+        TestActor::type_name().to_string(),
+        hosts[0].clone().unwrap_local(),
+    )
+    .with_allocation_strategy(Arc::new(RecordedStrategy {
+        rounds: rounds.clone(),
+    }))
+    .with_max_rebalance_shards(2);
+
+    // only node 1 is available, so every shard is allocated to it
+    for (node_id, shard_host) in [(2, hosts[1].clone()), (3, hosts[2].clone())] {
+        shard_coordinator.add_host(ShardHostState {
+            node_id,
+            node_tag: format!("node-{}", node_id),
+            shards: Default::default(),
+            actor: shard_host,
+            status: ShardHostStatus::Unavailable,
+        });
+    }
+
+    let shard_coordinator = shard_coordinator
+        .into_actor(Some("shard-coordinator".to_string()), remote.actor_system())
+        .await
+        .expect("ShardCoordinator start");
+
+    for shard_id in 0..6 {
+        let allocation = shard_coordinator
+            .send(AllocateShard {
+                shard_id,
+                rebalancing: false,
+            })
+            .await
+            .expect("shard allocation");
+
+        assert_eq!(allocation, AllocateShardResult::Allocated(shard_id, 1));
+    }
+
+    shard_coordinator
+        .exec(|coordinator| {
+            coordinator.on_node_reachability_changed(2, true);
+            coordinator.on_node_reachability_changed(3, true);
+        })
+        .await
+        .unwrap();
+
+    shard_coordinator.notify(Rebalance::All).unwrap();
+
+    // rebalancing continues until the strategy has no more shards to move
+    for _ in 0..40 {
+        if rounds.lock().unwrap().last() == Some(&0) {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(*rounds.lock().unwrap(), vec![2, 2, 0]);
+
+    let stats = shard_coordinator.send(GetShardingStats).await.unwrap();
+    let mut shard_counts: Vec<(NodeId, u64)> = stats
+        .nodes
+        .iter()
+        .map(|node| (node.node_id, node.shard_count))
+        .collect();
+
+    shard_counts.sort();
+    assert_eq!(stats.total_shards, 6);
+    assert_eq!(shard_counts, vec![(1, 2), (2, 2), (3, 2)]);
+
+    remote.actor_system().shutdown().await;
+}
@@ -0,0 +1,168 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorRecipe};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::net::server::RemoteServer;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::sharding::Sharding;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+/// Stands in for an entity that does scheduled background work once it has started
+pub struct Ticker;
+
+impl Actor for Ticker {}
+
+pub struct Ping;
+
+impl Message for Ping {
+    type Result = ();
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+
+    fn from_bytes(_: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        Ok(Self)
+    }
+
+    fn read_remote_result(_: Vec<u8>) -> Result<(), MessageUnwrapErr> {
+        Ok(())
+    }
+
+    fn write_remote_result(_: ()) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait]
+impl Handler<Ping> for Ticker {
+    async fn handle(&mut self, _message: Ping, _ctx: &mut ActorContext) {}
+}
+
+pub struct TickerRecipe;
+
+impl ActorRecipe for TickerRecipe {
+    fn read_from_bytes(_bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self)
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(vec![])
+    }
+}
+
+/// Counts the entities created, so the test can tell when an entity has been restarted
+#[derive(Clone, Default)]
+pub struct TickerFactory {
+    created: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ActorFactory for TickerFactory {
+    type Actor = Ticker;
+    type Recipe = TickerRecipe;
+
+    async fn create(&self, _recipe: TickerRecipe) -> Result<Ticker, ActorCreationErr> {
+        self.created.fetch_add(1, Ordering::SeqCst);
+        Ok(Ticker)
+    }
+}
+
+async fn create_system(
+    persistence: Persistence,
+    factory: TickerFactory,
+    listen_addr: &str,
+) -> (RemoteActorSystem, RemoteServer) {
+    let sys = ActorSystem::new().to_persistent(persistence);
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_actors(|a| a.with_actor(factory).with_handler::<Ticker, Ping>("Ping"))
+        .with_id(1)
+        .build()
+        .await;
+
+    let server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr(listen_addr)
+        .start()
+        .await;
+
+    (remote, server)
+}
+
+async fn wait_for_created(created: &AtomicUsize, expected: usize) -> bool {
+    for _ in 0..100 {
+        if created.load(Ordering::SeqCst) == expected {
+            return true;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    false
+}
+
+/// Starts the ticker entity, then restarts the node (sharing the same persistence backplane),
+/// returning whether the restarted node created the ticker before it was sent a message,
+/// and the number of times the restarted node created the ticker
+async fn restart_node(remember_entities: bool, listen_addr: &str) -> (bool, usize) {
+    let persistence = Persistence::from(InMemoryStorageProvider::new());
+
+    let factory = TickerFactory::default();
+    let (remote, server) = create_system(persistence.clone(), factory, listen_addr).await;
+
+    let sharding = Sharding::<TickerFactory>::builder(remote.clone())
+        .with_remember_entities(remember_entities)
+        .build()
+        .await;
+    sharding
+        .get("ticker", Some(TickerRecipe))
+        .send(Ping)
+        .await
+        .unwrap();
+
+    server.stop();
+    remote.actor_system().shutdown().await;
+
+    let factory = TickerFactory::default();
+    let created = factory.created.clone();
+    let (remote, _server) = create_system(persistence, factory, listen_addr).await;
+
+    let sharding = Sharding::<TickerFactory>::builder(remote.clone())
+        .with_remember_entities(remember_entities)
+        .build()
+        .await;
+
+    // the coordinator re-allocates the shards it allocated before the restart once it recovers
+    let restarted = wait_for_created(&created, 1).await;
+
+    // the entity can be reached without a recipe either way, since the shard recovered it
+    sharding.get("ticker", None).send(Ping).await.unwrap();
+    remote.actor_system().shutdown().await;
+
+    (restarted, created.load(Ordering::SeqCst))
+}
+
+#[tokio::test]
+pub async fn test_sharding_remembers_entities_by_default() {
+    util::create_trace_logger();
+
+    assert_eq!(restart_node(true, "localhost:35274").await, (true, 1));
+}
+
+#[tokio::test]
+pub async fn test_sharding_entities_started_on_demand() {
+    util::create_trace_logger();
+
+    assert_eq!(restart_node(false, "localhost:35275").await, (false, 1));
+}
@@ -0,0 +1,149 @@
+use crate::util::{GetStatusRequest, GetStatusResponse, TestActor};
+
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{ActorCreationErr, ActorFactory, ActorId, ActorRecipe, ActorRefErr};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::sharding::coordinator::ShardId;
+use coerce::sharding::host::warmup::WarmUpEntity;
+use coerce::sharding::host::ShardAllocator;
+use coerce::sharding::Sharding;
+use std::time::Duration;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+pub struct TestActorRecipe;
+
+impl ActorRecipe for TestActorRecipe {
+    fn read_from_bytes(_bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self)
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(vec![])
+    }
+}
+
+#[derive(Clone)]
+pub struct TestActorFactory;
+
+#[async_trait]
+impl ActorFactory for TestActorFactory {
+    type Actor = TestActor;
+    type Recipe = TestActorRecipe;
+
+    async fn create(&self, _recipe: TestActorRecipe) -> Result<TestActor, ActorCreationErr> {
+        Ok(TestActor {
+            status: None,
+            counter: 0,
+        })
+    }
+}
+
+/// Allocates every entity to the same shard, so allocating one entity's shard
+/// allocates the shard of every entity
+struct SingleShardAllocator;
+
+impl ShardAllocator for SingleShardAllocator {
+    fn allocate(&mut self, _actor_id: &ActorId) -> ShardId {
+        1
+    }
+}
+
+async fn create_system() -> RemoteActorSystem {
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_actors(|a| {
+            a.with_actor(TestActorFactory)
+                .with_handler::<TestActor, GetStatusRequest>("GetStatusRequest")
+        })
+        .with_id(1)
+        .build()
+        .await
+}
+
+fn warm_up_entity(actor_id: &str) -> WarmUpEntity {
+    WarmUpEntity::new(actor_id, &TestActorRecipe).unwrap()
+}
+
+async fn wait_for_entity(sharding: &Sharding<TestActorFactory>, actor_id: &str) -> bool {
+    // referencing an entity without a recipe only reaches it if it's already running
+    let entity = sharding.get(actor_id, None);
+    for _ in 0..50 {
+        match entity.send(GetStatusRequest).await {
+            Ok(GetStatusResponse::None) => return true,
+            Ok(_) | Err(ActorRefErr::NotFound(_)) => {
+                tokio::time::sleep(Duration::from_millis(20)).await
+            }
+            Err(_) => return false,
+        }
+    }
+
+    false
+}
+
+#[tokio::test]
+pub async fn test_sharding_warm_up_on_shard_allocation() {
+    util::create_trace_logger();
+
+    let remote = create_system().await;
+    let _server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35251")
+        .start()
+        .await;
+
+    let sharding = Sharding::<TestActorFactory>::builder(remote.clone())
+        .with_allocator(SingleShardAllocator)
+        .with_warm_up(vec![warm_up_entity("hot-1"), warm_up_entity("hot-2")])
+        .build()
+        .await;
+
+    // the first request allocates the shard, which then warms up the hot entities
+    let _ = sharding
+        .get("cold", Some(TestActorRecipe))
+        .send(GetStatusRequest)
+        .await;
+
+    assert!(wait_for_entity(&sharding, "hot-1").await);
+    assert!(wait_for_entity(&sharding, "hot-2").await);
+    assert!(!wait_for_entity(&sharding, "not-hot").await);
+
+    remote.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_sharding_warm_up_on_demand() {
+    util::create_trace_logger();
+
+    let remote = create_system().await;
+    let _server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35252")
+        .start()
+        .await;
+
+    let sharding = Sharding::<TestActorFactory>::builder(remote.clone())
+        .with_allocator(SingleShardAllocator)
+        .build()
+        .await;
+
+    let _ = sharding
+        .get("cold", Some(TestActorRecipe))
+        .send(GetStatusRequest)
+        .await;
+
+    assert!(!wait_for_entity(&sharding, "hot-1").await);
+
+    sharding.warm_up(vec![warm_up_entity("hot-1")]).unwrap();
+    assert!(wait_for_entity(&sharding, "hot-1").await);
+
+    remote.actor_system().shutdown().await;
+}
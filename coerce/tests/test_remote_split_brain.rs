@@ -0,0 +1,280 @@
+use chrono::{TimeZone, Utc};
+use coerce::actor::system::ActorSystem;
+use coerce::remote::cluster::node::{NodeStatus, RemoteNode, RemoteNodeState};
+use coerce::remote::heartbeat::downing::{
+    DowningDecision, DowningProvider, KeepMajority, KeepOldest, Partition,
+    SplitBrainResolverConfig, StaticQuorum,
+};
+use coerce::remote::heartbeat::failure_detector::FailureDetectorConfig;
+use coerce::remote::heartbeat::HeartbeatConfig;
+use coerce::remote::system::{NodeId, RemoteActorSystem};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+pub mod util;
+
+fn node(id: NodeId, started_at_secs: i64) -> RemoteNodeState {
+    RemoteNodeState::new(RemoteNode::new(
+        id,
+        format!("node-{id}"),
+        format!("node-{id}"),
+        Utc.timestamp_opt(started_at_secs, 0).single(),
+        Default::default(),
+        vec![],
+    ))
+}
+
+fn partition(reachable: &[(NodeId, i64)], unreachable: &[(NodeId, i64)]) -> Partition {
+    Partition {
+        reachable: reachable.iter().map(|(id, age)| node(*id, *age)).collect(),
+        unreachable: unreachable
+            .iter()
+            .map(|(id, age)| node(*id, *age))
+            .collect(),
+    }
+}
+
+fn heartbeat_config(split_brain_resolver: SplitBrainResolverConfig) -> HeartbeatConfig {
+    HeartbeatConfig {
+        interval: Duration::from_millis(100),
+        ping_timeout: Duration::from_secs(30),
+        unhealthy_node_heartbeat_timeout: Duration::from_secs(10),
+        terminated_node_heartbeat_timeout: Duration::from_secs(30),
+        failure_detector: FailureDetectorConfig {
+            threshold: 8.0,
+            max_sample_size: 100,
+            min_std_deviation: Duration::from_millis(50),
+            acceptable_heartbeat_pause: Duration::from_millis(200),
+            first_heartbeat_estimate: Duration::from_millis(100),
+        },
+        split_brain_resolver: Some(split_brain_resolver.stable_after(Duration::from_millis(1000))),
+        ..Default::default()
+    }
+}
+
+async fn create_system(node_id: NodeId, config: HeartbeatConfig) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_tag(format!("node-{node_id}"))
+        .configure(|c| c.heartbeat(config))
+        .build()
+        .await
+}
+
+/// Starts a node on its own single-threaded runtime, so it can be frozen, simulating a partition
+/// between it and the rest of the cluster
+fn start_freezable_node(
+    node_id: NodeId,
+    listen_addr: &'static str,
+    seed_addr: Option<&'static str>,
+    config: HeartbeatConfig,
+) -> (mpsc::Sender<Duration>, JoinHandle<()>) {
+    let (freeze_tx, freeze_rx) = mpsc::channel::<Duration>();
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let node = std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async move {
+                let remote = create_system(node_id, config).await;
+                let mut worker = remote.clone().cluster_worker().listen_addr(listen_addr);
+                if let Some(seed_addr) = seed_addr {
+                    worker = worker.with_seed_addr(seed_addr);
+                }
+
+                let _server = worker.start().await;
+                ready_tx.send(()).unwrap();
+
+                loop {
+                    match freeze_rx.try_recv() {
+                        Ok(duration) => std::thread::sleep(duration),
+                        Err(mpsc::TryRecvError::Empty) => {
+                            tokio::time::sleep(Duration::from_millis(10)).await
+                        }
+                        Err(mpsc::TryRecvError::Disconnected) => break,
+                    }
+                }
+
+                remote.actor_system().shutdown().await;
+            });
+    });
+
+    ready_rx.recv().unwrap();
+    (freeze_tx, node)
+}
+
+async fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let start = std::time::Instant::now();
+    while start.elapsed() < timeout {
+        if condition() {
+            return true;
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    false
+}
+
+async fn node_status(remote: &RemoteActorSystem, node_id: NodeId) -> Option<NodeStatus> {
+    remote
+        .get_nodes()
+        .await
+        .into_iter()
+        .find(|n| n.id == node_id)
+        .map(|n| n.status)
+}
+
+#[test]
+pub fn test_downing_providers() {
+    let majority = partition(&[(1, 0), (2, 1)], &[(3, 2)]);
+    let minority = partition(&[(3, 2)], &[(1, 0), (2, 1)]);
+    let even = partition(&[(2, 0), (3, 1)], &[(1, 2), (4, 3)]);
+
+    assert_eq!(
+        KeepMajority.decide(&majority),
+        DowningDecision::DownUnreachable
+    );
+    assert_eq!(
+        KeepMajority.decide(&minority),
+        DowningDecision::DownReachable
+    );
+
+    // ties are broken by keeping the side with the lowest node id
+    assert_eq!(KeepMajority.decide(&even), DowningDecision::DownReachable);
+
+    assert_eq!(
+        KeepOldest.decide(&majority),
+        DowningDecision::DownUnreachable
+    );
+    assert_eq!(KeepOldest.decide(&minority), DowningDecision::DownReachable);
+    assert_eq!(KeepOldest.decide(&even), DowningDecision::DownUnreachable);
+
+    let quorum = StaticQuorum::new(2);
+    assert_eq!(quorum.decide(&majority), DowningDecision::DownUnreachable);
+    assert_eq!(quorum.decide(&minority), DowningDecision::DownReachable);
+    assert_eq!(quorum.decide(&even), DowningDecision::DownAll);
+    assert_eq!(
+        StaticQuorum::new(3).decide(&majority),
+        DowningDecision::DownAll
+    );
+}
+
+#[test]
+pub fn test_remote_split_brain_keep_majority() {
+    util::create_trace_logger();
+
+    let config = heartbeat_config(SplitBrainResolverConfig::new(KeepMajority));
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let remote = runtime.block_on(async {
+        let remote = create_system(1, config.clone()).await;
+        let _ = remote
+            .clone()
+            .cluster_worker()
+            .listen_addr("127.0.0.1:35101")
+            .start()
+            .await;
+
+        remote
+    });
+
+    let (freeze_tx, node_3) = start_freezable_node(
+        3,
+        "127.0.0.1:35103",
+        Some("127.0.0.1:35101"),
+        config.clone(),
+    );
+
+    runtime.block_on(async {
+        let remote_2 = create_system(2, config).await;
+        let _ = remote_2
+            .clone()
+            .cluster_worker()
+            .listen_addr("127.0.0.1:35102")
+            .with_seed_addr("127.0.0.1:35101")
+            .start()
+            .await;
+
+        remote
+            .wait_for_members(3, Duration::from_secs(5))
+            .await
+            .expect("cluster formed");
+
+        // wait for a few heartbeats, so the failure detectors have a history to compare against
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        freeze_tx.send(Duration::from_secs(6)).unwrap();
+
+        let mut quarantined = false;
+        for _ in 0..100 {
+            if node_status(&remote, 3).await == Some(NodeStatus::Quarantined)
+                && node_status(&remote_2, 3).await == Some(NodeStatus::Quarantined)
+            {
+                quarantined = true;
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        // the majority side downs node 3, and carries on
+        assert!(quarantined);
+        assert_eq!(node_status(&remote, 2).await, Some(NodeStatus::Healthy));
+        assert!(!remote.actor_system().is_terminated());
+        assert!(!remote_2.actor_system().is_terminated());
+
+        remote.actor_system().shutdown().await;
+        remote_2.actor_system().shutdown().await;
+    });
+
+    drop(freeze_tx);
+    node_3.join().unwrap();
+}
+
+#[test]
+pub fn test_remote_split_brain_keep_oldest_downs_self() {
+    util::create_trace_logger();
+
+    let config = heartbeat_config(SplitBrainResolverConfig::new(KeepOldest));
+    let (freeze_tx, node_1) = start_freezable_node(1, "127.0.0.1:35104", None, config.clone());
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let remote_2 = create_system(2, config).await;
+        let _ = remote_2
+            .clone()
+            .cluster_worker()
+            .listen_addr("127.0.0.1:35105")
+            .with_seed_addr("127.0.0.1:35104")
+            .start()
+            .await;
+
+        remote_2
+            .wait_for_members(2, Duration::from_secs(5))
+            .await
+            .expect("cluster formed");
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        freeze_tx.send(Duration::from_secs(6)).unwrap();
+
+        // node 2 becomes the leader of its side of the partition, but node 1 is the oldest,
+        // so node 2 downs itself
+        let system = remote_2.actor_system().clone();
+        assert!(wait_until(Duration::from_secs(5), || system.is_terminated()).await);
+    });
+
+    drop(freeze_tx);
+    node_1.join().unwrap();
+}
@@ -8,6 +8,9 @@ extern crate tracing;
 extern crate async_trait;
 
 use coerce::actor::system::ActorSystem;
+use coerce::remote::cluster::builder::worker::BootstrapFallback;
+use coerce::remote::net::proto::network::SessionHandshake;
+use coerce::remote::net::security::handshake::AuthResult;
 use coerce::remote::net::security::jwt::Jwt;
 use coerce::remote::system::RemoteActorSystem;
 use std::time::Duration;
@@ -75,6 +78,127 @@ pub async fn test_remote_cluster_consistent_secrets() {
     assert_eq!(nodes_3.len(), 3);
 }
 
+#[tokio::test]
+pub async fn test_remote_handshake_token() {
+    util::create_trace_logger();
+
+    let remote = create_node(1, Some("token")).await;
+    remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:31031")
+        .start()
+        .await;
+
+    let remote_2 = create_node(2, Some("token")).await;
+    remote_2
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:31032")
+        .with_seed_addr("localhost:31031")
+        .start()
+        .await;
+
+    remote_2
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    // nodes with the wrong token, or without a token, can't join the cluster
+    for (node_id, token) in [(3, Some("wrong-token")), (4, None)] {
+        let node = create_node(node_id, token).await;
+        let server = node
+            .clone()
+            .cluster_worker()
+            .listen_addr(format!("localhost:3103{}", node_id))
+            .with_seed_addr("localhost:31031")
+            .bootstrap_timeout(Duration::from_secs(1), BootstrapFallback::Abort)
+            .try_start()
+            .await;
+
+        assert!(server.is_err());
+    }
+
+    assert_eq!(remote.get_nodes().await.len(), 2);
+}
+
+#[tokio::test]
+pub async fn test_remote_custom_authenticator() {
+    util::create_trace_logger();
+
+    let remote = RemoteActorSystem::builder()
+        .with_id(1)
+        .with_actor_system(ActorSystem::new())
+        .with_authenticator(|handshake: &SessionHandshake| {
+            if handshake.node_tag == "trusted" {
+                AuthResult::Accepted
+            } else {
+                AuthResult::Rejected(format!("untrusted node tag: {}", handshake.node_tag))
+            }
+        })
+        .build()
+        .await;
+
+    remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:31041")
+        .start()
+        .await;
+
+    let trusted = RemoteActorSystem::builder()
+        .with_id(2)
+        .with_tag("trusted")
+        .with_actor_system(ActorSystem::new())
+        .build()
+        .await;
+
+    trusted
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:31042")
+        .with_seed_addr("localhost:31041")
+        .start()
+        .await;
+
+    trusted
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let untrusted = RemoteActorSystem::builder()
+        .with_id(3)
+        .with_tag("untrusted")
+        .with_actor_system(ActorSystem::new())
+        .build()
+        .await;
+
+    let server = untrusted
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:31043")
+        .with_seed_addr("localhost:31041")
+        .bootstrap_timeout(Duration::from_secs(1), BootstrapFallback::Abort)
+        .try_start()
+        .await;
+
+    assert!(server.is_err());
+    assert_eq!(remote.get_nodes().await.len(), 2);
+}
+
+async fn create_node(node_id: u64, token: Option<&'static str>) -> RemoteActorSystem {
+    let builder = RemoteActorSystem::builder()
+        .with_id(node_id)
+        .with_actor_system(ActorSystem::new());
+
+    match token {
+        Some(token) => builder.with_handshake_token(token),
+        None => builder,
+    }
+    .build()
+    .await
+}
+
 async fn create_cluster_nodes(
     port_prefix: &'static str,
     secrets: Vec<&'static str>,
@@ -0,0 +1,171 @@
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::IntoActorId;
+use coerce::remote::cluster::builder::worker::BootstrapFallback;
+use coerce::remote::net::tls::mutual_tls;
+use coerce::remote::net::tls::rustls::{Certificate, PrivateKey, RootCertStore};
+use coerce::remote::system::RemoteActorSystem;
+use rcgen::{BasicConstraints, CertificateParams, IsCa};
+use std::time::Duration;
+use util::{GetStatusRequest, GetStatusResponse, TestActor, TestActorStatus};
+
+pub mod util;
+
+struct NodeCertificates {
+    roots: RootCertStore,
+    cert_chain: Vec<Certificate>,
+    key: PrivateKey,
+}
+
+fn create_ca() -> rcgen::Certificate {
+    let mut params = CertificateParams::new(vec![]);
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    rcgen::Certificate::from_params(params).unwrap()
+}
+
+fn create_node_certificates(ca: &rcgen::Certificate) -> NodeCertificates {
+    let cert =
+        rcgen::Certificate::from_params(CertificateParams::new(vec!["localhost".to_string()]))
+            .unwrap();
+
+    let mut roots = RootCertStore::empty();
+    roots
+        .add(&Certificate(ca.serialize_der().unwrap()))
+        .unwrap();
+
+    NodeCertificates {
+        roots,
+        cert_chain: vec![Certificate(cert.serialize_der_with_signer(ca).unwrap())],
+        key: PrivateKey(cert.serialize_private_key_der()),
+    }
+}
+
+async fn create_system(node_id: u64) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_handlers(|handlers| {
+            handlers.with_handler::<TestActor, GetStatusRequest>("TestActor.GetStatusRequest")
+        })
+        .build()
+        .await
+}
+
+#[tokio::test]
+pub async fn test_remote_mutual_tls() {
+    util::create_trace_logger();
+
+    let ca = create_ca();
+    let certs = create_node_certificates(&ca);
+    let (server_config, client_config) =
+        mutual_tls(certs.roots, certs.cert_chain, certs.key).unwrap();
+
+    let remote_a = create_system(1).await;
+    let remote_b = create_system(2).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30161")
+        .with_tls(server_config.clone(), client_config.clone())
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30162")
+        .with_seed_addr("localhost:30161")
+        .with_tls(server_config, client_config)
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let _ = remote_a
+        .actor_system()
+        .new_actor(
+            "test-actor",
+            TestActor {
+                status: Some(TestActorStatus::Active),
+                counter: 0,
+            },
+            Tracked,
+        )
+        .await;
+
+    let _ = remote_a
+        .actor_ref::<TestActor>("test-actor".into_actor_id())
+        .await
+        .expect("unable to get local ref");
+
+    let remote_actor = remote_b
+        .actor_ref::<TestActor>("test-actor".into_actor_id())
+        .await
+        .expect("unable to get remote ref");
+
+    assert!(remote_actor.is_remote());
+    assert_eq!(
+        remote_actor.send(GetStatusRequest).await,
+        Ok(GetStatusResponse::Ok(TestActorStatus::Active))
+    );
+}
+
+#[tokio::test]
+pub async fn test_remote_mutual_tls_rejects_untrusted_nodes() {
+    util::create_trace_logger();
+
+    let certs = create_node_certificates(&create_ca());
+    let (server_config, client_config) =
+        mutual_tls(certs.roots.clone(), certs.cert_chain, certs.key).unwrap();
+
+    let remote_a = create_system(1).await;
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30163")
+        .with_tls(server_config, client_config)
+        .start()
+        .await;
+
+    // the node trusts node 1's certificate, but presents a certificate issued by a CA
+    // that node 1 doesn't trust
+    let untrusted_certs = create_node_certificates(&create_ca());
+    let (server_config, client_config) =
+        mutual_tls(certs.roots, untrusted_certs.cert_chain, untrusted_certs.key).unwrap();
+
+    let untrusted = create_system(2).await;
+    let server = untrusted
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30164")
+        .with_seed_addr("localhost:30163")
+        .with_tls(server_config, client_config)
+        .bootstrap_timeout(Duration::from_secs(1), BootstrapFallback::Abort)
+        .try_start()
+        .await;
+
+    assert!(server.is_err());
+
+    // plaintext nodes can't join the cluster either
+    let plaintext = create_system(3).await;
+    let server = plaintext
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:30165")
+        .with_seed_addr("localhost:30163")
+        .bootstrap_timeout(Duration::from_secs(1), BootstrapFallback::Abort)
+        .try_start()
+        .await;
+
+    assert!(server.is_err());
+    assert_eq!(remote_a.get_nodes().await.len(), 1);
+}
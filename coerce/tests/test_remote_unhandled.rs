@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{ActorId, ActorRefErr, IntoActor};
+use coerce::remote::net::message::SessionEvent;
+use coerce::remote::net::proto::network::IdentifyEvent;
+use coerce::remote::net::unhandled::UnhandledFrameHook;
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use futures::SinkExt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+use util::*;
+
+pub mod util;
+
+/// Records the frames it's called with, and replies to messages sent to the `Echo` handler
+#[derive(Clone, Default)]
+struct RecordingHook {
+    frames: Arc<Mutex<Vec<Vec<u8>>>>,
+    unknown_handlers: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl UnhandledFrameHook for RecordingHook {
+    fn on_undecodable_frame(&self, _addr: &str, frame: &[u8], _sys: &RemoteActorSystem) -> bool {
+        self.frames.lock().unwrap().push(frame.to_vec());
+        true
+    }
+
+    async fn on_unknown_handler(
+        &self,
+        handler_type: &str,
+        _actor_id: &ActorId,
+        message: &[u8],
+        _sys: &RemoteActorSystem,
+    ) -> Option<Result<Vec<u8>, ActorRefErr>> {
+        self.unknown_handlers
+            .lock()
+            .unwrap()
+            .push(handler_type.to_string());
+
+        (handler_type == "Echo").then(|| Ok(message.to_vec()))
+    }
+}
+
+async fn create_system(hook: RecordingHook) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_handlers(move |handlers| handlers.with_unhandled_frame_hook(hook))
+        .build()
+        .await
+}
+
+#[tokio::test]
+pub async fn test_remote_unknown_handler_hook() {
+    util::create_trace_logger();
+
+    let hook = RecordingHook::default();
+    let remote = create_system(hook.clone()).await;
+
+    let actor = TestActor::new()
+        .into_actor(Some("unhandled-actor"), remote.actor_system())
+        .await
+        .unwrap();
+
+    let res = remote
+        .send_raw("Echo", actor.id.clone(), b"hello".to_vec())
+        .await;
+
+    assert_eq!(res.unwrap(), b"hello".to_vec());
+
+    // handlers the hook doesn't handle still fail as unsupported
+    let res = remote
+        .send_raw("Unknown", actor.id.clone(), b"hello".to_vec())
+        .await;
+
+    assert!(matches!(res, Err(ActorRefErr::NotSupported { .. })));
+    assert_eq!(
+        *hook.unknown_handlers.lock().unwrap(),
+        vec!["Echo".to_string(), "Unknown".to_string()]
+    );
+}
+
+#[tokio::test]
+pub async fn test_remote_undecodable_frame_hook() {
+    util::create_trace_logger();
+
+    let hook = RecordingHook::default();
+    let remote = create_system(hook.clone()).await;
+
+    let _ = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("127.0.0.1:35131")
+        .start()
+        .await;
+
+    let stream = TcpStream::connect("127.0.0.1:35131").await.unwrap();
+    let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+
+    let identify = SessionEvent::Identify(IdentifyEvent {
+        source_node_id: 2,
+        source_node_tag: "node-2".to_string(),
+        ..Default::default()
+    });
+
+    framed
+        .send(Bytes::from(identify.write_to_bytes().unwrap()))
+        .await
+        .unwrap();
+
+    // an event type this node doesn't know about, for example from a newer version
+    let frame = vec![250, 1, 2, 3];
+    framed.send(Bytes::from(frame.clone())).await.unwrap();
+
+    for _ in 0..100 {
+        if !hook.frames.lock().unwrap().is_empty() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert_eq!(*hook.frames.lock().unwrap(), vec![frame]);
+
+    remote.actor_system().shutdown().await;
+}
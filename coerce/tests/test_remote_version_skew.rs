@@ -0,0 +1,121 @@
+use coerce::actor::system::ActorSystem;
+use coerce::remote::cluster::version::{
+    CrateVersionSkew, NodeVersion, VersionSkew, VersionSkewPolicy,
+};
+use coerce::remote::net::version::ProtocolVersions;
+use coerce::remote::system::RemoteActorSystem;
+use std::time::Duration;
+
+pub mod util;
+
+async fn create_system(id: u64) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(id)
+        .with_tag(format!("system-{}", id))
+        .build()
+        .await
+}
+
+fn version(crate_version: &str, features: &[&str]) -> NodeVersion {
+    NodeVersion {
+        crate_version: crate_version.to_string(),
+        protocol_versions: ProtocolVersions::current(),
+        features: features.iter().map(|f| f.to_string()).collect(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_remote_node_versions_exchanged() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1).await;
+    let remote_b = create_system(2).await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35241")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35242")
+        .with_seed_addr("localhost:35241")
+        .start()
+        .await;
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .expect("cluster formed");
+
+    for system in [&remote_a, &remote_b] {
+        let nodes = system.get_nodes().await;
+        assert_eq!(nodes.len(), 2);
+
+        for node in nodes {
+            assert_eq!(node.version, Some(NodeVersion::current()));
+            assert!(VersionSkewPolicy::default()
+                .check(&NodeVersion::current(), node.version.as_ref())
+                .is_empty());
+        }
+    }
+}
+
+#[test]
+pub fn test_version_skew_policy() {
+    let local = version("0.8.11", &["remote", "sharding"]);
+    let policy = VersionSkewPolicy::default();
+
+    assert!(policy
+        .check(&local, Some(&version("0.8.12", &["remote", "sharding"])))
+        .is_empty());
+
+    assert_eq!(
+        policy.check(&local, Some(&version("0.9.0", &["remote", "sharding"]))),
+        vec![VersionSkew::CrateVersion {
+            local: "0.8.11".to_string(),
+            remote: "0.9.0".to_string(),
+        }]
+    );
+
+    let minor = VersionSkewPolicy {
+        max_crate_version_skew: CrateVersionSkew::Minor,
+        ..Default::default()
+    };
+
+    assert!(minor
+        .check(&local, Some(&version("0.9.0", &["remote", "sharding"])))
+        .is_empty());
+
+    assert_eq!(
+        policy.check(&local, Some(&version("0.8.11", &["remote", "metrics"]))),
+        vec![VersionSkew::Features {
+            missing: vec!["sharding".to_string()],
+            additional: vec!["metrics".to_string()],
+        }]
+    );
+
+    let mut newer_protocol = version("0.8.11", &["remote", "sharding"]);
+    newer_protocol.protocol_versions.max += 1;
+
+    assert_eq!(
+        policy.check(&local, Some(&newer_protocol)),
+        vec![VersionSkew::Protocol {
+            local: local.protocol_versions,
+            remote: newer_protocol.protocol_versions,
+        }]
+    );
+
+    assert_eq!(policy.check(&local, None), vec![VersionSkew::Unknown]);
+
+    let ignore_unknown = VersionSkewPolicy {
+        warn_on_unknown_version: false,
+        ..Default::default()
+    };
+
+    assert!(ignore_unknown.check(&local, None).is_empty());
+}
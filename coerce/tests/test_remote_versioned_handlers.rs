@@ -0,0 +1,238 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::versioned::{Versioned, VersionedHandler};
+use coerce::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorRecipe, ActorRef, ToActorId};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::remote::RemoteActorRef;
+use coerce::sharding::Sharding;
+use std::time::Duration;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+/// Replies with the version of the handler that handled the message
+pub struct Worker;
+
+impl Actor for Worker {}
+
+pub struct Work;
+
+impl Message for Work {
+    type Result = u32;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(vec![])
+    }
+
+    fn from_bytes(_: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        Ok(Self)
+    }
+
+    fn read_remote_result(res: Vec<u8>) -> Result<u32, MessageUnwrapErr> {
+        res.try_into()
+            .map(u32::from_be_bytes)
+            .map_err(|_| MessageUnwrapErr::DeserializationErr)
+    }
+
+    fn write_remote_result(res: u32) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(res.to_be_bytes().to_vec())
+    }
+}
+
+#[async_trait]
+impl Handler<Work> for Worker {
+    async fn handle(&mut self, _message: Work, _ctx: &mut ActorContext) -> u32 {
+        1
+    }
+}
+
+#[async_trait]
+impl VersionedHandler<Work, 2> for Worker {
+    async fn handle_version(&mut self, _message: Work, _ctx: &mut ActorContext) -> u32 {
+        2
+    }
+}
+
+pub struct WorkerRecipe;
+
+impl ActorRecipe for WorkerRecipe {
+    fn read_from_bytes(_bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self)
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(vec![])
+    }
+}
+
+#[derive(Clone)]
+pub struct WorkerFactory;
+
+#[async_trait]
+impl ActorFactory for WorkerFactory {
+    type Actor = Worker;
+    type Recipe = WorkerRecipe;
+
+    async fn create(&self, _recipe: WorkerRecipe) -> Result<Worker, ActorCreationErr> {
+        Ok(Worker)
+    }
+}
+
+async fn create_system(
+    persistence: Persistence,
+    node_id: u64,
+    message_version: Option<u32>,
+) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new().to_persistent(persistence))
+        .with_handlers(move |handlers| {
+            handlers
+                .with_actor(WorkerFactory)
+                .with_handler::<Worker, Work>("Worker.Work")
+                .with_versioned_handler::<Worker, Work, 2>("Worker.Work");
+
+            if let Some(version) = message_version {
+                handlers.message_version("Worker.Work", version);
+            }
+
+            handlers
+        })
+        .with_id(node_id)
+        .build()
+        .await
+}
+
+#[tokio::test]
+pub async fn test_remote_versioned_handlers() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(Persistence::from(InMemoryStorageProvider::new()), 1, None).await;
+    let remote_b = create_system(
+        Persistence::from(InMemoryStorageProvider::new()),
+        2,
+        Some(2),
+    )
+    .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35278")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35279")
+        .with_seed_addr("localhost:35278")
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let local_worker = remote_b
+        .actor_system()
+        .new_actor("worker".to_actor_id(), Worker, Tracked)
+        .await
+        .unwrap();
+
+    // node A hasn't been migrated, so the worker handles its messages with the unversioned handler
+    let worker = ActorRef::from(RemoteActorRef::<Worker>::new(
+        "worker".to_actor_id(),
+        2,
+        remote_a.clone(),
+    ));
+
+    assert_eq!(worker.send(Work).await, Ok(1));
+
+    remote_a.set_message_version("Worker.Work", Some(2));
+    assert_eq!(remote_a.message_version("Worker.Work"), 2);
+    assert_eq!(worker.send(Work).await, Ok(2));
+
+    // there's no handler for v3, so the message falls back to the unversioned handler
+    remote_a.set_message_version("Worker.Work", Some(3));
+    assert_eq!(worker.send(Work).await, Ok(1));
+
+    remote_a.set_message_version("Worker.Work", None);
+    assert_eq!(worker.send(Work).await, Ok(1));
+
+    // node B is configured to send v2, including to its own actors
+    let worker = ActorRef::from(RemoteActorRef::<Worker>::new(
+        "worker".to_actor_id(),
+        2,
+        remote_b.clone(),
+    ));
+
+    assert_eq!(worker.send(Work).await, Ok(2));
+    worker.notify(Work).await.unwrap();
+
+    // local references aren't versioned, a version can be chosen explicitly
+    assert_eq!(local_worker.send(Work).await, Ok(1));
+    assert_eq!(local_worker.send(Versioned::<Work, 2>(Work)).await, Ok(2));
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
+
+#[tokio::test]
+pub async fn test_sharded_versioned_handlers() {
+    util::create_trace_logger();
+
+    let persistence = Persistence::from(InMemoryStorageProvider::new());
+    let remote_a = create_system(persistence.clone(), 1, None).await;
+    let remote_b = create_system(persistence, 2, Some(2)).await;
+
+    let sharding_a = Sharding::<WorkerFactory>::builder(remote_a.clone())
+        .build()
+        .await;
+
+    let sharding_b = Sharding::<WorkerFactory>::builder(remote_b.clone())
+        .build()
+        .await;
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35283")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35284")
+        .with_seed_addr("localhost:35283")
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    // the entity is hosted by one of the nodes, so one of the two is sent via the other node
+    let worker_a = sharding_a.get("worker", Some(WorkerRecipe));
+    let worker_b = sharding_b.get("worker", Some(WorkerRecipe));
+
+    assert_eq!(worker_a.send(Work).await, Ok(1));
+    assert_eq!(worker_b.send(Work).await, Ok(2));
+
+    remote_a.set_message_version("Worker.Work", Some(2));
+    assert_eq!(worker_a.send(Work).await, Ok(2));
+
+    remote_b.set_message_version("Worker.Work", None);
+    assert_eq!(worker_b.send(Work).await, Ok(1));
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
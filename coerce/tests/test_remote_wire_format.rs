@@ -0,0 +1,240 @@
+use async_trait::async_trait;
+use bytes::BytesMut;
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::Handler;
+use coerce::actor::scheduler::ActorType::Tracked;
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, IntoActorId};
+use coerce::remote::net::encoding::{MessageCodec, MessagePackCodec, ProtobufCodec, WireFormat};
+use coerce::remote::net::message::{datetime_to_timestamp, ClientEvent, SessionEvent};
+use coerce::remote::net::proto::network::{
+    actor_ref_err, ActorRefErr, ClientErr, MessageRequest, NodeIdentity, RemoteNode,
+    SystemCapabilities,
+};
+use coerce::remote::net::StreamData;
+use coerce::remote::system::RemoteActorSystem;
+use coerce_macros::JsonMessage;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub mod util;
+
+#[derive(JsonMessage, Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[result("String")]
+pub struct Echo(String);
+
+pub struct EchoActor;
+
+impl Actor for EchoActor {}
+
+#[async_trait]
+impl Handler<Echo> for EchoActor {
+    async fn handle(&mut self, message: Echo, _ctx: &mut ActorContext) -> String {
+        message.0
+    }
+}
+
+fn encode_session_event(event: &SessionEvent, codec: &dyn MessageCodec) -> Vec<u8> {
+    let mut buffer = BytesMut::new();
+    assert!(event.write_to_buffer_with(codec, &mut buffer));
+    buffer.to_vec()
+}
+
+fn encode_client_event(event: &ClientEvent, codec: &dyn MessageCodec) -> Vec<u8> {
+    let mut buffer = BytesMut::new();
+    assert!(event.write_to_buffer_with(codec, &mut buffer));
+    buffer.to_vec()
+}
+
+fn node_identity() -> NodeIdentity {
+    NodeIdentity {
+        node_id: 1,
+        node_tag: "node-1".to_string(),
+        addr: "localhost:30101".to_string(),
+        node_started_at: Some(datetime_to_timestamp(&chrono::Utc::now())).into(),
+        peers: vec![RemoteNode {
+            node_id: 2,
+            addr: "localhost:30102".to_string(),
+            attributes: [("zone".to_string(), "eu-west-1a".to_string())].into(),
+            roles: vec!["worker".to_string()],
+            ..Default::default()
+        }],
+        capabilities: Some(SystemCapabilities {
+            actors: vec!["EchoActor".to_string()],
+            messages: vec!["EchoActor.Echo".to_string()],
+            ..Default::default()
+        })
+        .into(),
+        attributes: [("region".to_string(), "eu-west-1".to_string())].into(),
+        max_protocol_version: 2,
+        features: vec!["remote".to_string(), "msgpack".to_string()],
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn test_msgpack_event_round_trip() {
+    let identity = node_identity();
+    let frame = encode_client_event(&ClientEvent::Identity(identity.clone()), &MessagePackCodec);
+    match ClientEvent::read_from_slice(&frame) {
+        Some(ClientEvent::Identity(decoded)) => assert_eq!(decoded, identity),
+        _ => panic!("expected ClientEvent::Identity"),
+    }
+
+    let err = ClientErr {
+        message_id: "message-1".to_string(),
+        error: Some(ActorRefErr {
+            type_: actor_ref_err::ErrorType::Timeout.into(),
+            time_taken_millis: 5000,
+            ..Default::default()
+        })
+        .into(),
+        ..Default::default()
+    };
+
+    let frame = encode_client_event(&ClientEvent::Err(err.clone()), &MessagePackCodec);
+    match ClientEvent::read_from_slice(&frame) {
+        Some(ClientEvent::Err(decoded)) => assert_eq!(decoded, err),
+        _ => panic!("expected ClientEvent::Err"),
+    }
+
+    // truncated events are rejected, rather than partially decoded
+    assert!(ClientEvent::read_from_slice(&frame[..frame.len() - 1]).is_none());
+}
+
+#[test]
+pub fn test_msgpack_message_request() {
+    let request = MessageRequest {
+        message_id: "message-1".to_string(),
+        handler_type: "EchoActor.Echo".to_string(),
+        actor_id: "echo-actor".to_string(),
+        message: vec![0, 1, 2, 255],
+        requires_response: true,
+        origin_node_id: 2,
+        message_version: 3,
+        ..Default::default()
+    };
+
+    let event = SessionEvent::NotifyActor(request.clone());
+    let msgpack = encode_session_event(&event, &MessagePackCodec);
+    let protobuf = encode_session_event(&event, &ProtobufCodec);
+
+    match SessionEvent::read_from_slice(&msgpack) {
+        Some(SessionEvent::NotifyActor(decoded)) => assert_eq!(decoded, request),
+        _ => panic!("expected SessionEvent::NotifyActor"),
+    }
+
+    // messages to the same actor are ordered the same way, regardless of the format
+    assert_ne!(SessionEvent::ordering_key(&msgpack), 0);
+    assert_eq!(
+        SessionEvent::ordering_key(&msgpack),
+        SessionEvent::ordering_key(&protobuf)
+    );
+
+    assert_eq!(
+        SessionEvent::message_request_id(&msgpack),
+        Some("message-1".to_string())
+    );
+
+    assert_eq!(
+        SessionEvent::message_request_id(&protobuf),
+        Some("message-1".to_string())
+    );
+}
+
+#[test]
+pub fn test_wire_format_codec() {
+    assert!(WireFormat::Protobuf.is_available());
+    assert!(WireFormat::MessagePack.is_available());
+
+    assert_eq!(
+        WireFormat::MessagePack.codec().map(|codec| codec.format()),
+        Some(WireFormat::MessagePack)
+    );
+
+    assert_eq!(WireFormat::default(), WireFormat::Protobuf);
+}
+
+async fn create_system(node_id: u64, wire_format: WireFormat) -> RemoteActorSystem {
+    RemoteActorSystem::builder()
+        .with_actor_system(ActorSystem::new())
+        .with_id(node_id)
+        .with_wire_format(wire_format)
+        .with_handlers(|handlers| handlers.with_handler::<EchoActor, Echo>("EchoActor.Echo"))
+        .build()
+        .await
+}
+
+/// Sends a message from `remote_b` to an actor on `remote_a`, and back again
+async fn assert_echo(remote_a: &RemoteActorSystem, remote_b: &RemoteActorSystem) {
+    let actor_id = format!("echo-actor-{}", remote_a.node_id());
+    let _ = remote_a
+        .actor_system()
+        .new_actor(actor_id.clone(), EchoActor, Tracked)
+        .await
+        .unwrap();
+
+    // the actor is registered with `remote_b` asynchronously
+    let mut actor = None;
+    for _ in 0..50 {
+        actor = remote_b
+            .actor_ref::<EchoActor>(actor_id.clone().into_actor_id())
+            .await;
+
+        if actor.is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let actor = actor.expect("unable to get remote ref");
+
+    assert!(actor.is_remote());
+    assert_eq!(
+        actor.send(Echo("hello".to_string())).await,
+        Ok("hello".to_string())
+    );
+}
+
+#[tokio::test]
+pub async fn test_remote_mixed_wire_formats() {
+    util::create_trace_logger();
+
+    let remote_a = create_system(1, WireFormat::MessagePack).await;
+    let remote_b = create_system(2, WireFormat::Protobuf).await;
+
+    assert_eq!(remote_a.config().wire_format(), WireFormat::MessagePack);
+    assert_eq!(remote_b.config().wire_format(), WireFormat::Protobuf);
+
+    remote_a
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35281")
+        .start()
+        .await;
+
+    remote_b
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35282")
+        .with_seed_addr("localhost:35281")
+        .start()
+        .await;
+
+    remote_a
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    remote_b
+        .wait_for_members(2, Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_echo(&remote_a, &remote_b).await;
+    assert_echo(&remote_b, &remote_a).await;
+
+    remote_a.actor_system().shutdown().await;
+    remote_b.actor_system().shutdown().await;
+}
@@ -0,0 +1,244 @@
+use coerce::actor::context::ActorContext;
+use coerce::actor::message::{Handler, Message, MessageUnwrapErr, MessageWrapErr};
+use coerce::actor::system::ActorSystem;
+use coerce::actor::{Actor, ActorCreationErr, ActorFactory, ActorRecipe, IntoActor};
+use coerce::persistent::journal::provider::inmemory::InMemoryStorageProvider;
+use coerce::persistent::Persistence;
+use coerce::remote::system::RemoteActorSystem;
+use coerce::router::{ActorTarget, EntityTarget, MessageRouter, RouteErr, RoutePattern};
+use coerce::sharding::Sharding;
+
+pub mod util;
+
+#[macro_use]
+extern crate async_trait;
+
+/// Sums the readings it receives, for the device the actor stands in for
+pub struct Device {
+    total: u64,
+}
+
+impl Actor for Device {}
+
+/// Adds to an order's item count, the order isn't part of the message,
+/// it's taken from the identifier the message was received with
+pub struct AddItems {
+    count: u64,
+}
+
+pub struct Reading(u64);
+
+fn read_u64(bytes: Vec<u8>) -> Result<u64, MessageUnwrapErr> {
+    bytes
+        .try_into()
+        .map(u64::from_be_bytes)
+        .map_err(|_| MessageUnwrapErr::DeserializationErr)
+}
+
+impl Message for Reading {
+    type Result = u64;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        read_u64(b).map(Reading)
+    }
+
+    fn read_remote_result(res: Vec<u8>) -> Result<u64, MessageUnwrapErr> {
+        read_u64(res)
+    }
+
+    fn write_remote_result(res: u64) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(res.to_be_bytes().to_vec())
+    }
+}
+
+#[async_trait]
+impl Handler<Reading> for Device {
+    async fn handle(&mut self, message: Reading, _ctx: &mut ActorContext) -> u64 {
+        self.total += message.0;
+        self.total
+    }
+}
+
+pub struct Order {
+    items: u64,
+}
+
+impl Actor for Order {}
+
+impl Message for AddItems {
+    type Result = u64;
+
+    fn as_bytes(&self) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(self.count.to_be_bytes().to_vec())
+    }
+
+    fn from_bytes(b: Vec<u8>) -> Result<Self, MessageUnwrapErr> {
+        read_u64(b).map(|count| AddItems { count })
+    }
+
+    fn read_remote_result(res: Vec<u8>) -> Result<u64, MessageUnwrapErr> {
+        read_u64(res)
+    }
+
+    fn write_remote_result(res: u64) -> Result<Vec<u8>, MessageWrapErr> {
+        Ok(res.to_be_bytes().to_vec())
+    }
+}
+
+#[async_trait]
+impl Handler<AddItems> for Order {
+    async fn handle(&mut self, message: AddItems, _ctx: &mut ActorContext) -> u64 {
+        self.items += message.count;
+        self.items
+    }
+}
+
+pub struct OrderRecipe;
+
+impl ActorRecipe for OrderRecipe {
+    fn read_from_bytes(_bytes: &Vec<u8>) -> Option<Self> {
+        Some(Self)
+    }
+
+    fn write_to_bytes(&self) -> Option<Vec<u8>> {
+        Some(vec![])
+    }
+}
+
+#[derive(Clone)]
+pub struct OrderFactory;
+
+#[async_trait]
+impl ActorFactory for OrderFactory {
+    type Actor = Order;
+    type Recipe = OrderRecipe;
+
+    async fn create(&self, _recipe: OrderRecipe) -> Result<Order, ActorCreationErr> {
+        Ok(Order { items: 0 })
+    }
+}
+
+#[test]
+pub fn test_route_pattern_matching() {
+    let pattern = RoutePattern::parse("devices/{device_id}/*/{metric}").unwrap();
+    let params = pattern.matches("devices/1/sensor-a/temperature").unwrap();
+    assert_eq!(params.get("device_id"), Some("1"));
+    assert_eq!(params.get("metric"), Some("temperature"));
+    assert_eq!(params.get("sensor"), None);
+
+    assert!(pattern.matches("devices/1/sensor-a").is_none());
+    assert!(pattern
+        .matches("devices/1/sensor-a/temperature/max")
+        .is_none());
+    assert!(pattern.matches("sensors/1/sensor-a/temperature").is_none());
+
+    let pattern = RoutePattern::parse("/orders/{order_id}/#").unwrap();
+    assert!(pattern.matches("/orders/1").is_some());
+    assert!(pattern.matches("/orders/1/items/2").is_some());
+    assert!(pattern.matches("orders/1").is_none());
+
+    assert!(RoutePattern::parse("orders/#/items").is_err());
+    assert!(RoutePattern::parse("orders/{}").is_err());
+    assert!(RoutePattern::parse("orders/order-{order_id}").is_err());
+    assert!(RoutePattern::parse("{id}/{id}").is_err());
+}
+
+#[tokio::test]
+pub async fn test_router_dispatch() {
+    util::create_trace_logger();
+
+    let sys = ActorSystem::new().to_persistent(Persistence::from(InMemoryStorageProvider::new()));
+    let remote = RemoteActorSystem::builder()
+        .with_actor_system(sys)
+        .with_actors(|a| {
+            a.with_actor(OrderFactory)
+                .with_handler::<Order, AddItems>("AddItems")
+        })
+        .with_id(1)
+        .build()
+        .await;
+
+    let _server = remote
+        .clone()
+        .cluster_worker()
+        .listen_addr("localhost:35277")
+        .start()
+        .await;
+
+    let _device = Device { total: 0 }
+        .into_actor(Some("device-1"), remote.actor_system())
+        .await
+        .unwrap();
+
+    let orders = Sharding::<OrderFactory>::builder(remote.clone())
+        .build()
+        .await;
+
+    let router = MessageRouter::builder(remote.clone())
+        .route::<Reading, _>(
+            "devices/{device_id}/readings",
+            ActorTarget::<Device>::new("device-{device_id}"),
+        )
+        .route_with(
+            "/orders/{order_id}/items",
+            EntityTarget::new(orders.clone(), "order-{order_id}")
+                .with_recipe(|_| Some(OrderRecipe)),
+            |_params, payload| AddItems::from_bytes(payload),
+        )
+        .build();
+
+    // raw payloads, as received by a broker consumer
+    let result = router
+        .dispatch("devices/1/readings", 5u64.to_be_bytes().to_vec())
+        .await
+        .unwrap();
+
+    assert_eq!(Reading::read_remote_result(result), Ok(5));
+
+    // decoded messages, as received by a typed ingress
+    assert_eq!(
+        router.send("devices/1/readings", Reading(3)).await.unwrap(),
+        8
+    );
+    assert_eq!(
+        router
+            .send("/orders/1/items", AddItems { count: 2 })
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        orders
+            .get("order-1", None)
+            .send(AddItems { count: 1 })
+            .await,
+        Ok(3)
+    );
+
+    // routes only match messages of the type they accept
+    assert!(matches!(
+        router.send("/orders/1/items", Reading(1)).await,
+        Err(RouteErr::NoRoute(_))
+    ));
+
+    assert!(matches!(
+        router
+            .dispatch("devices/2/readings", 1u64.to_be_bytes().to_vec())
+            .await,
+        Err(RouteErr::ActorNotFound(_))
+    ));
+
+    assert!(matches!(
+        router.dispatch("devices/1/status", vec![]).await,
+        Err(RouteErr::NoRoute(_))
+    ));
+
+    assert!(!router.matches("/orders"));
+    assert!(router.matches("/orders/2/items"));
+
+    remote.actor_system().shutdown().await;
+}
@@ -1,6 +1,6 @@
 use coerce::actor::context::ActorContext;
 use coerce::actor::message::{Handler, Message};
-use coerce::actor::scheduler::timer::{Timer, TimerTick};
+use coerce::actor::scheduler::timer::{timer_stats, Timer, TimerTick};
 use coerce::actor::system::ActorSystem;
 use coerce::actor::Actor;
 use std::time::{Duration, Instant};
@@ -72,3 +72,72 @@ pub async fn test_timer() {
     }
     assert_eq!(ticks_after_stopping.len(), ticks_after_stopping_and_waiting);
 }
+
+#[tokio::test]
+pub async fn test_timer_stop() {
+    let actor_ref = ActorSystem::new()
+        .new_anon_actor(TimerActor { ticks: vec![] })
+        .await
+        .unwrap();
+
+    let timer =
+        Timer::start_immediately(actor_ref.clone(), Duration::from_millis(20), TestTimer {});
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(timer.stop());
+
+    // allow any tick that was in-flight when the timer was stopped to be processed
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let ticks_after_stopping = actor_ref.exec(|a| a.ticks.len()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let ticks_after_waiting = actor_ref.exec(|a| a.ticks.len()).await.unwrap();
+
+    assert!(ticks_after_stopping >= 3);
+    assert_eq!(ticks_after_stopping, ticks_after_waiting);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn test_timer_many_timers() {
+    const TIMERS: usize = 2000;
+    const TICK_DURATION: Duration = Duration::from_millis(50);
+
+    let system = ActorSystem::new();
+    let start = Instant::now();
+
+    let mut actors = vec![];
+    let mut timers = vec![];
+    for _ in 0..TIMERS {
+        let actor_ref = system
+            .new_anon_actor(TimerActor { ticks: vec![] })
+            .await
+            .unwrap();
+
+        timers.push(Timer::start(actor_ref.clone(), TICK_DURATION, TestTimer {}));
+        actors.push(actor_ref);
+    }
+
+    let stats_before = timer_stats();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let stats_after = timer_stats();
+
+    for actor_ref in &actors {
+        let ticks = actor_ref.exec(|a| a.ticks.clone()).await.unwrap();
+        assert!(ticks.len() >= 2);
+
+        // ticks are never delivered early
+        assert!(ticks[0].duration_since(start) >= TICK_DURATION);
+        for window in ticks.windows(2) {
+            assert!(window[1].duration_since(window[0]) >= TICK_DURATION);
+        }
+    }
+
+    // the wheel wakes up at a fixed rate, regardless of how many timers are active
+    let wakeups = stats_after.wheel_wakeups - stats_before.wheel_wakeups;
+    let ticks = stats_after.ticks_delivered - stats_before.ticks_delivered;
+    assert!(ticks >= (TIMERS * 2) as u64);
+    assert!(wakeups * 10 < ticks);
+
+    for timer in timers {
+        timer.stop();
+    }
+}
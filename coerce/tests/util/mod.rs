@@ -8,7 +8,9 @@ use coerce::actor::system::ActorSystem;
 #[cfg(feature = "remote")]
 use coerce::remote::system::{builder::RemoteSystemConfigBuilder, NodeId, RemoteActorSystem};
 
+use std::future::Future;
 use std::str::FromStr;
+use std::time::Duration;
 use tracing::Level;
 use tracing_subscriber::fmt::format::FmtSpan;
 
@@ -152,6 +154,17 @@ where
     remote
 }
 
+/// Waits until `condition` is met, panicking if it isn't met within 5 seconds
+pub async fn eventually<F: Future<Output = bool>>(mut condition: impl FnMut() -> F) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !condition().await {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("condition not met within timeout")
+}
+
 pub fn create_trace_logger() {
     let _ = tracing_subscriber::fmt()
         // enable everything
@@ -0,0 +1,245 @@
+//! `coerce-cli`, a command line tool for inspecting and administering Coerce clusters.
+//!
+//! The tool joins the cluster via the seed node as a node without any actors, using the
+//! [`ClusterClient`], so it can be used wherever the cluster's HTTP API hasn't been deployed.
+//!
+//! ```sh
+//! coerce-cli --seed_addr 127.0.0.1:30100 members
+//! coerce-cli --seed_addr 127.0.0.1:30100 locate-actor user-1
+//! coerce-cli --seed_addr 127.0.0.1:30100 locate-entity ChatStream general
+//! coerce-cli --seed_addr 127.0.0.1:30100 events
+//! coerce-cli --seed_addr 127.0.0.1:30100 send --actor user-1 --handler GetStatusRequest 'null'
+//!
+//! export COERCE_ADMIN_TOKEN=admin-token
+//! coerce-cli --seed_addr 127.0.0.1:30100 admin --node 1 list-actors
+//! coerce-cli --seed_addr 127.0.0.1:30100 admin --node 1 stop-actor user-1
+//! coerce-cli --seed_addr 127.0.0.1:30100 admin --node 1 metrics
+//! coerce-cli --seed_addr 127.0.0.1:30100 admin --node 1 log-level DEBUG
+//! ```
+
+use clap::{arg, value_parser, ArgAction, ArgMatches, Command};
+use coerce::remote::admin::{AdminCommand, AdminResponse};
+use coerce::remote::stream::system::{ClusterEvent, SystemEvent};
+use coerce::remote::system::NodeId;
+use coerce_py::client::{ClientConfig, ClusterClient};
+use std::time::Duration;
+
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:30099";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn main() {
+    let mut matches = cli().get_matches();
+
+    let seed_addr = matches.remove_one::<String>("seed_addr").unwrap();
+    let listen_addr = matches
+        .remove_one::<String>("listen_addr")
+        .unwrap_or_else(|| DEFAULT_LISTEN_ADDR.to_string());
+
+    let client = match ClusterClient::connect(ClientConfig {
+        listen_addr,
+        seed_addr,
+        node_id: None,
+        node_tag: Some("coerce-cli".to_string()),
+    }) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("failed to connect to the cluster: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let result = match matches.subcommand() {
+        Some(("members", _)) => members(&client),
+        Some(("locate-actor", matches)) => locate_actor(&client, matches),
+        Some(("locate-entity", matches)) => locate_entity(&client, matches),
+        Some(("events", _)) => events(&client),
+        Some(("send", matches)) => send(&client, matches),
+        Some(("admin", matches)) => admin(&client, matches),
+        _ => unreachable!("subcommand required"),
+    };
+
+    client.close();
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn cli() -> Command {
+    Command::new("coerce-cli")
+        .about("Inspects and administers Coerce clusters")
+        .arg(arg!(--seed_addr <TCP_ADDR> "The host and port of any node in the cluster").env("COERCE_SEED_ADDR"))
+        .arg(arg!(--listen_addr [TCP_ADDR] "The host and port which the CLI will listen to connections from (default=127.0.0.1:30099)").env("COERCE_LISTEN_ADDR"))
+        .subcommand_required(true)
+        .subcommand(Command::new("members").about("Lists every node in the cluster"))
+        .subcommand(
+            Command::new("locate-actor")
+                .about("Locates the node hosting an actor")
+                .arg(arg!(<ACTOR_ID> "The ID of the actor")),
+        )
+        .subcommand(
+            Command::new("locate-entity")
+                .about("Locates the shard, and the node hosting the shard, of a sharded entity")
+                .arg(arg!(<ENTITY_TYPE> "The sharded entity type name"))
+                .arg(arg!(<ENTITY_ID> "The ID of the entity")),
+        )
+        .subcommand(Command::new("events").about("Prints cluster events as they happen, until interrupted"))
+        .subcommand(
+            Command::new("send")
+                .about("Sends a JSON encoded message to an actor, and prints the reply")
+                .arg(arg!(--actor <ACTOR_ID> "The ID of the actor"))
+                .arg(arg!(--handler <HANDLER> "The name the message handler was registered with"))
+                .arg(arg!(--tell "Don't wait for the message to be handled").action(ArgAction::SetTrue))
+                .arg(arg!(<JSON> "The JSON encoded message")),
+        )
+        .subcommand(
+            Command::new("admin")
+                .about("Sends an admin command to a node, which must have been started with admin enabled")
+                .arg(arg!(--node <NODE_ID> "The ID of the node the command is sent to").value_parser(value_parser!(NodeId)))
+                .arg(arg!(--token <TOKEN> "The admin token the node was configured with").env("COERCE_ADMIN_TOKEN"))
+                .subcommand_required(true)
+                .subcommand(Command::new("list-actors").about("Lists every actor tracked by the node"))
+                .subcommand(
+                    Command::new("stop-actor")
+                        .about("Stops an actor tracked by the node")
+                        .arg(arg!(<ACTOR_ID> "The ID of the actor to stop")),
+                )
+                .subcommand(Command::new("metrics").about("Dumps the node's statistics and metrics"))
+                .subcommand(
+                    Command::new("log-level")
+                        .about("Changes the node's log level")
+                        .arg(arg!(<LEVEL> "The new log level, or filter directive")),
+                ),
+        )
+}
+
+fn members(client: &ClusterClient) -> Result<(), String> {
+    let mut members = client.members();
+    members.sort_by_key(|node| node.id);
+
+    println!("{:<20}\t{:<24}\t{:<20}\tSTATUS", "NODE_ID", "ADDR", "TAG");
+    for node in members {
+        println!(
+            "{:<20}\t{:<24}\t{:<20}\t{:?}",
+            node.id, node.addr, node.tag, node.status
+        );
+    }
+
+    Ok(())
+}
+
+fn locate_actor(client: &ClusterClient, matches: &ArgMatches) -> Result<(), String> {
+    let actor_id = matches.get_one::<String>("ACTOR_ID").unwrap();
+
+    match client.locate_actor(actor_id) {
+        Some(node_id) => {
+            println!("{}", node_id);
+            Ok(())
+        }
+        None => Err(format!("actor {} not found", actor_id)),
+    }
+}
+
+fn locate_entity(client: &ClusterClient, matches: &ArgMatches) -> Result<(), String> {
+    let entity_type = matches.get_one::<String>("ENTITY_TYPE").unwrap();
+    let entity_id = matches.get_one::<String>("ENTITY_ID").unwrap();
+
+    match client.locate_entity(entity_type, entity_id) {
+        Some(location) => {
+            println!("{}", serde_json::to_string_pretty(&location).unwrap());
+            Ok(())
+        }
+        None => Err(format!("no nodes are hosting entity type {}", entity_type)),
+    }
+}
+
+fn events(client: &ClusterClient) -> Result<(), String> {
+    let mut events = client
+        .cluster_events()
+        .map_err(|e| format!("failed to subscribe to cluster events: {}", e))?;
+
+    while let Some(event) = events.recv() {
+        let SystemEvent::Cluster(event) = event.as_ref();
+        match event {
+            ClusterEvent::MemberUp(member_up) => println!(
+                "member up (leader_id={}, nodes={})",
+                member_up.leader_id,
+                member_up.nodes.len()
+            ),
+            ClusterEvent::NodeAdded(node) => {
+                println!("node added (node_id={}, addr={})", node.id, node.addr)
+            }
+            ClusterEvent::NodeRemoved(node) => {
+                println!("node removed (node_id={}, addr={})", node.id, node.addr)
+            }
+            ClusterEvent::LeaderChanged(node_id) => {
+                println!("leader changed (node_id={})", node_id)
+            }
+            ClusterEvent::Standalone => println!("standalone"),
+            ClusterEvent::NodeQuarantined(node) => {
+                println!("node quarantined (node_id={}, addr={})", node.id, node.addr)
+            }
+            ClusterEvent::NodeUnreachable(node) => {
+                println!("node unreachable (node_id={}, addr={})", node.id, node.addr)
+            }
+            ClusterEvent::NodeReachable(node) => {
+                println!("node reachable (node_id={}, addr={})", node.id, node.addr)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn send(client: &ClusterClient, matches: &ArgMatches) -> Result<(), String> {
+    let actor_id = matches.get_one::<String>("actor").unwrap();
+    let handler = matches.get_one::<String>("handler").unwrap();
+    let json = matches.get_one::<String>("JSON").unwrap();
+
+    serde_json::from_str::<serde_json::Value>(json)
+        .map_err(|e| format!("invalid message JSON: {}", e))?;
+
+    let message = json.as_bytes().to_vec();
+    if matches.get_flag("tell") {
+        return client
+            .tell(actor_id, handler, message)
+            .map_err(|e| format!("failed to send message: {}", e));
+    }
+
+    let reply = client
+        .ask(actor_id, handler, message, Some(REQUEST_TIMEOUT))
+        .map_err(|e| format!("failed to send message: {}", e))?;
+
+    println!("{}", String::from_utf8_lossy(&reply));
+    Ok(())
+}
+
+fn admin(client: &ClusterClient, matches: &ArgMatches) -> Result<(), String> {
+    let node_id = *matches.get_one::<NodeId>("node").unwrap();
+    let token = matches.get_one::<String>("token").unwrap();
+
+    let command = match matches.subcommand() {
+        Some(("list-actors", _)) => AdminCommand::ListActors,
+        Some(("stop-actor", matches)) => AdminCommand::StopActor {
+            actor_id: matches.get_one::<String>("ACTOR_ID").unwrap().clone(),
+        },
+        Some(("metrics", _)) => AdminCommand::DumpMetrics,
+        Some(("log-level", matches)) => AdminCommand::SetLogLevel {
+            level: matches.get_one::<String>("LEVEL").unwrap().clone(),
+        },
+        _ => unreachable!("subcommand required"),
+    };
+
+    let response = client
+        .admin(node_id, token, command, Some(REQUEST_TIMEOUT))
+        .map_err(|e| format!("failed to send admin command to node {}: {}", node_id, e))?;
+
+    match response {
+        AdminResponse::Err(e) => Err(format!("node {}: {}", node_id, e)),
+        response => {
+            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+            Ok(())
+        }
+    }
+}
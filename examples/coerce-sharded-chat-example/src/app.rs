@@ -102,7 +102,7 @@ impl ShardedChat {
         info!("shutting down");
 
         info!("stopping http api");
-        let _ = self.http_api.stop().await;
+        let _ = self.http_api.stop(false).await;
 
         self.remote_server.stop();
         self.system.actor_system().shutdown().await;
@@ -0,0 +1,449 @@
+use crate::journal::retry::{CapacityMode, RetryPolicy};
+use crate::journal::schema::create_table;
+
+use coerce::persistent::journal::provider::StorageProvider;
+use coerce::persistent::journal::storage::{JournalEntry, JournalStorage, JournalStorageRef};
+
+use aws_sdk_dynamodb::operation::query::builders::QueryFluentBuilder;
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::{AttributeValue, DeleteRequest, PutRequest, WriteRequest};
+use aws_sdk_dynamodb::Client;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub mod retry;
+pub(crate) mod schema;
+
+/// Partition key of both tables
+pub(crate) const PERSISTENCE_ID: &str = "persistence_id";
+
+/// Sort key of both tables
+pub(crate) const SEQUENCE: &str = "sequence";
+
+const PAYLOAD_TYPE: &str = "payload_type";
+
+const ENTRY: &str = "entry";
+
+/// The maximum number of items DynamoDB accepts in a single `BatchWriteItem` request,
+/// larger batches are split into multiple requests
+const MAX_BATCH_WRITE_ITEMS: usize = 25;
+
+#[derive(Clone)]
+pub struct DynamoDbStorageProvider {
+    dynamodb: JournalStorageRef,
+}
+
+pub struct DynamoDbStorageConfig {
+    pub journal_table: String,
+    pub snapshot_table: String,
+    pub capacity_mode: CapacityMode,
+
+    /// Overrides the retry policy of the [`CapacityMode`]
+    pub retry_policy: Option<RetryPolicy>,
+
+    /// Creates the journal and snapshot tables when connecting, if they don't already exist
+    pub create_tables: bool,
+}
+
+pub struct DynamoDbJournalStorage {
+    client: Client,
+    journal_table: String,
+    snapshot_table: String,
+    retry_policy: RetryPolicy,
+}
+
+impl Default for DynamoDbStorageConfig {
+    fn default() -> Self {
+        Self {
+            journal_table: "coerce_journal".to_string(),
+            snapshot_table: "coerce_snapshot".to_string(),
+            capacity_mode: CapacityMode::OnDemand,
+            retry_policy: None,
+            create_tables: true,
+        }
+    }
+}
+
+impl DynamoDbStorageProvider {
+    pub async fn new(client: Client, config: DynamoDbStorageConfig) -> anyhow::Result<Self> {
+        if config.create_tables {
+            create_table(&client, &config.journal_table, &config.capacity_mode).await?;
+            create_table(&client, &config.snapshot_table, &config.capacity_mode).await?;
+        }
+
+        let retry_policy = config
+            .retry_policy
+            .unwrap_or_else(|| config.capacity_mode.retry_policy());
+
+        let dynamodb = Arc::new(DynamoDbJournalStorage {
+            client,
+            journal_table: config.journal_table,
+            snapshot_table: config.snapshot_table,
+            retry_policy,
+        });
+
+        Ok(DynamoDbStorageProvider { dynamodb })
+    }
+
+    /// Creates the client from the AWS configuration found in the environment,
+    /// such as `AWS_REGION` and `AWS_PROFILE`
+    #[cfg(feature = "aws-config")]
+    pub async fn from_env(config: DynamoDbStorageConfig) -> anyhow::Result<Self> {
+        let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        Self::new(Client::new(&aws_config), config).await
+    }
+}
+
+impl StorageProvider for DynamoDbStorageProvider {
+    fn journal_storage(&self) -> Option<JournalStorageRef> {
+        Some(self.dynamodb.clone())
+    }
+}
+
+#[async_trait]
+impl JournalStorage for DynamoDbJournalStorage {
+    async fn write_snapshot(
+        &self,
+        persistence_id: &str,
+        entry: JournalEntry,
+    ) -> anyhow::Result<()> {
+        let item = encode(persistence_id, &entry)?;
+
+        self.retry_policy
+            .retry(|| {
+                self.client
+                    .put_item()
+                    .table_name(&self.snapshot_table)
+                    .set_item(Some(item.clone()))
+                    .send()
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn write_message(&self, persistence_id: &str, entry: JournalEntry) -> anyhow::Result<()> {
+        let item = encode(persistence_id, &entry)?;
+
+        // messages are never overwritten, a message with the same sequence is an error
+        self.retry_policy
+            .retry(|| {
+                self.client
+                    .put_item()
+                    .table_name(&self.journal_table)
+                    .set_item(Some(item.clone()))
+                    .condition_expression("attribute_not_exists(#seq)")
+                    .expression_attribute_names("#seq", SEQUENCE)
+                    .send()
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Writes the messages using `BatchWriteItem`, each request (of up to 25 messages) is
+    /// applied independently, so a failed batch may be partially written.
+    async fn write_message_batch(
+        &self,
+        persistence_id: &str,
+        entries: Vec<JournalEntry>,
+    ) -> anyhow::Result<()> {
+        let requests = entries
+            .iter()
+            .map(|entry| {
+                let item = encode(persistence_id, entry)?;
+                let put = PutRequest::builder().set_item(Some(item)).build()?;
+                Ok(WriteRequest::builder().put_request(put).build())
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        self.batch_write(&self.journal_table, requests).await
+    }
+
+    async fn read_latest_snapshot(
+        &self,
+        persistence_id: &str,
+    ) -> anyhow::Result<Option<JournalEntry>> {
+        let query = self
+            .query(&self.snapshot_table, persistence_id, "#pid = :pid")
+            .scan_index_forward(false)
+            .limit(1);
+
+        let output = self.retry_policy.retry(|| query.clone().send()).await?;
+        output.items().first().map(decode).transpose()
+    }
+
+    async fn read_latest_messages(
+        &self,
+        persistence_id: &str,
+        from_sequence: i64,
+    ) -> anyhow::Result<Option<Vec<JournalEntry>>> {
+        let query = self
+            .query(
+                &self.journal_table,
+                persistence_id,
+                "#pid = :pid AND #seq > :from_sequence",
+            )
+            .expression_attribute_names("#seq", SEQUENCE)
+            .expression_attribute_values(":from_sequence", sequence(from_sequence));
+
+        let entries = self
+            .query_all(query)
+            .await?
+            .iter()
+            .map(decode)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Some(entries).filter(|entries| !entries.is_empty()))
+    }
+
+    async fn read_message(
+        &self,
+        persistence_id: &str,
+        sequence_id: i64,
+    ) -> anyhow::Result<Option<JournalEntry>> {
+        let output = self
+            .retry_policy
+            .retry(|| {
+                self.client
+                    .get_item()
+                    .table_name(&self.journal_table)
+                    .set_key(Some(key(persistence_id, sequence(sequence_id))))
+                    .consistent_read(true)
+                    .send()
+            })
+            .await?;
+
+        output.item().map(decode).transpose()
+    }
+
+    async fn read_messages(
+        &self,
+        persistence_id: &str,
+        from_sequence: i64,
+        to_sequence: i64,
+    ) -> anyhow::Result<Option<Vec<JournalEntry>>> {
+        let query = self
+            .query(
+                &self.journal_table,
+                persistence_id,
+                "#pid = :pid AND #seq BETWEEN :from_sequence AND :to_sequence",
+            )
+            .expression_attribute_names("#seq", SEQUENCE)
+            .expression_attribute_values(":from_sequence", sequence(from_sequence))
+            .expression_attribute_values(":to_sequence", sequence(to_sequence));
+
+        let entries = self
+            .query_all(query)
+            .await?
+            .iter()
+            .map(decode)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Some(entries).filter(|entries| !entries.is_empty()))
+    }
+
+    async fn delete_messages_to(
+        &self,
+        persistence_id: &str,
+        to_sequence: i64,
+    ) -> anyhow::Result<()> {
+        self.delete_to(&self.journal_table, persistence_id, Some(to_sequence))
+            .await
+    }
+
+    async fn delete_snapshots_to(
+        &self,
+        persistence_id: &str,
+        to_sequence: i64,
+    ) -> anyhow::Result<()> {
+        self.delete_to(&self.snapshot_table, persistence_id, Some(to_sequence))
+            .await
+    }
+
+    async fn delete_all(&self, persistence_id: &str) -> anyhow::Result<()> {
+        self.delete_to(&self.journal_table, persistence_id, None)
+            .await?;
+
+        self.delete_to(&self.snapshot_table, persistence_id, None)
+            .await
+    }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        self.client
+            .describe_table()
+            .table_name(&self.journal_table)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl DynamoDbJournalStorage {
+    /// A consistent query of the items belonging to `persistence_id`, ordered by sequence,
+    /// where `#pid` and `:pid` refer to the persistence id
+    fn query(&self, table: &str, persistence_id: &str, condition: &str) -> QueryFluentBuilder {
+        self.client
+            .query()
+            .table_name(table)
+            .key_condition_expression(condition)
+            .expression_attribute_names("#pid", PERSISTENCE_ID)
+            .expression_attribute_values(":pid", AttributeValue::S(persistence_id.to_string()))
+            .consistent_read(true)
+    }
+
+    /// Reads every page of the query's results
+    async fn query_all(
+        &self,
+        query: QueryFluentBuilder,
+    ) -> anyhow::Result<Vec<HashMap<String, AttributeValue>>> {
+        let mut items = vec![];
+        let mut start_key = None;
+
+        loop {
+            let output = self
+                .retry_policy
+                .retry(|| {
+                    query
+                        .clone()
+                        .set_exclusive_start_key(start_key.clone())
+                        .send()
+                })
+                .await?;
+
+            items.extend(output.items.unwrap_or_default());
+            match output.last_evaluated_key {
+                Some(last_evaluated_key) => start_key = Some(last_evaluated_key),
+                None => return Ok(items),
+            }
+        }
+    }
+
+    /// Deletes the items with a sequence lower than `to_sequence`, or every item if there's no
+    /// `to_sequence`. DynamoDB can only delete items by key, so the keys are queried first.
+    async fn delete_to(
+        &self,
+        table: &str,
+        persistence_id: &str,
+        to_sequence: Option<i64>,
+    ) -> anyhow::Result<()> {
+        let query = match to_sequence {
+            Some(to_sequence) => self
+                .query(table, persistence_id, "#pid = :pid AND #seq < :to_sequence")
+                .expression_attribute_values(":to_sequence", sequence(to_sequence)),
+            None => self.query(table, persistence_id, "#pid = :pid"),
+        };
+
+        let query = query
+            .projection_expression("#pid, #seq")
+            .expression_attribute_names("#seq", SEQUENCE);
+
+        let requests = self
+            .query_all(query)
+            .await?
+            .into_iter()
+            .map(|key| {
+                let delete = DeleteRequest::builder().set_key(Some(key)).build()?;
+                Ok(WriteRequest::builder().delete_request(delete).build())
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        self.batch_write(table, requests).await
+    }
+
+    /// Writes the requests in batches, re-sending any items DynamoDB left unprocessed
+    /// (typically because the table's throughput was exceeded) until the retry policy's
+    /// attempts are exhausted
+    async fn batch_write(&self, table: &str, requests: Vec<WriteRequest>) -> anyhow::Result<()> {
+        for batch in requests.chunks(MAX_BATCH_WRITE_ITEMS) {
+            let mut pending = batch.to_vec();
+            let mut attempt = 1;
+
+            loop {
+                let output = self
+                    .retry_policy
+                    .retry(|| {
+                        self.client
+                            .batch_write_item()
+                            .request_items(table, pending.clone())
+                            .send()
+                    })
+                    .await?;
+
+                pending = output
+                    .unprocessed_items
+                    .and_then(|mut unprocessed| unprocessed.remove(table))
+                    .unwrap_or_default();
+
+                if pending.is_empty() {
+                    break;
+                }
+
+                if attempt >= self.retry_policy.max_attempts {
+                    return Err(anyhow::anyhow!(
+                        "{} items were left unprocessed after {} attempts",
+                        pending.len(),
+                        attempt
+                    ));
+                }
+
+                let delay = self.retry_policy.delay(attempt);
+                debug!(
+                    "batch write left {} items unprocessed, attempt={}, retrying in {:?}",
+                    pending.len(),
+                    attempt,
+                    delay
+                );
+
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn sequence(sequence: i64) -> AttributeValue {
+    AttributeValue::N(sequence.to_string())
+}
+
+fn key(persistence_id: &str, sequence: AttributeValue) -> HashMap<String, AttributeValue> {
+    HashMap::from([
+        (
+            PERSISTENCE_ID.to_string(),
+            AttributeValue::S(persistence_id.to_string()),
+        ),
+        (SEQUENCE.to_string(), sequence),
+    ])
+}
+
+fn encode(
+    persistence_id: &str,
+    entry: &JournalEntry,
+) -> anyhow::Result<HashMap<String, AttributeValue>> {
+    let bytes = entry
+        .write_to_bytes()
+        .ok_or_else(|| anyhow::anyhow!("failed to serialize journal entry"))?;
+
+    let mut item = key(persistence_id, sequence(entry.sequence));
+    item.insert(
+        PAYLOAD_TYPE.to_string(),
+        AttributeValue::S(entry.payload_type.to_string()),
+    );
+    item.insert(ENTRY.to_string(), AttributeValue::B(Blob::new(bytes)));
+
+    Ok(item)
+}
+
+fn decode(item: &HashMap<String, AttributeValue>) -> anyhow::Result<JournalEntry> {
+    let bytes = item
+        .get(ENTRY)
+        .and_then(|entry| entry.as_b().ok())
+        .ok_or_else(|| anyhow::anyhow!("journal item has no entry attribute"))?;
+
+    JournalEntry::read_from_slice(bytes.as_ref())
+        .ok_or_else(|| anyhow::anyhow!("failed to deserialize journal entry"))
+}
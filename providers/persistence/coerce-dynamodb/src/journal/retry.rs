@@ -0,0 +1,97 @@
+use aws_sdk_dynamodb::error::ProvideErrorMetadata;
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Error codes returned when requests exceed the table's throughput, or the account's limits,
+/// which succeed once retried
+const THROTTLING_ERROR_CODES: &[&str] = &[
+    "ProvisionedThroughputExceededException",
+    "ThrottlingException",
+    "RequestLimitExceeded",
+];
+
+/// How the journal and snapshot tables are billed for reads and writes, which decides how the
+/// tables are created (when `create_tables` is enabled) and how throttled requests are retried.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub enum CapacityMode {
+    /// Pay-per-request, the table scales with traffic so throttling is brief, requests are
+    /// retried a few times with short delays
+    #[default]
+    OnDemand,
+
+    /// The table has a fixed throughput, requests exceeding it are throttled until capacity is
+    /// available again, so requests are retried more times with longer delays
+    Provisioned {
+        read_capacity_units: i64,
+        write_capacity_units: i64,
+    },
+}
+
+/// Retries requests that were throttled, and batch writes that were only partially processed,
+/// with an exponential backoff between attempts.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: usize,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl CapacityMode {
+    pub fn retry_policy(&self) -> RetryPolicy {
+        match self {
+            Self::OnDemand => RetryPolicy {
+                max_attempts: 3,
+                base_delay: Duration::from_millis(25),
+                max_delay: Duration::from_millis(500),
+            },
+            Self::Provisioned { .. } => RetryPolicy {
+                max_attempts: 10,
+                base_delay: Duration::from_millis(50),
+                max_delay: Duration::from_secs(5),
+            },
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before the attempt after `attempt`, doubling with every attempt
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16) as u32;
+        self.base_delay
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_delay)
+    }
+
+    pub(crate) async fn retry<T, E, F, Fut>(&self, mut request: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: ProvideErrorMetadata,
+    {
+        let mut attempt = 1;
+        loop {
+            match request().await {
+                Err(e) if attempt < self.max_attempts && is_throttled(&e) => {
+                    let delay = self.delay(attempt);
+                    debug!(
+                        "request throttled (code={:?}), attempt={}, retrying in {:?}",
+                        e.code(),
+                        attempt,
+                        delay
+                    );
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+
+                result => return result,
+            }
+        }
+    }
+}
+
+fn is_throttled(e: &impl ProvideErrorMetadata) -> bool {
+    e.code()
+        .is_some_and(|code| THROTTLING_ERROR_CODES.contains(&code))
+}
@@ -0,0 +1,98 @@
+use crate::journal::retry::CapacityMode;
+use crate::journal::{PERSISTENCE_ID, SEQUENCE};
+
+use aws_sdk_dynamodb::error::ProvideErrorMetadata;
+use aws_sdk_dynamodb::types::{
+    AttributeDefinition, BillingMode, KeySchemaElement, KeyType, ProvisionedThroughput,
+    ScalarAttributeType, TableStatus,
+};
+use aws_sdk_dynamodb::Client;
+
+use std::time::Duration;
+
+/// How long to wait for a newly created table to become active
+const TABLE_ACTIVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+const TABLE_STATUS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Creates the table if it doesn't already exist, partitioned by persistence id and sorted by
+/// sequence number, waiting until it's active
+pub async fn create_table(
+    client: &Client,
+    table_name: &str,
+    capacity_mode: &CapacityMode,
+) -> anyhow::Result<()> {
+    let mut request = client
+        .create_table()
+        .table_name(table_name)
+        .attribute_definitions(
+            AttributeDefinition::builder()
+                .attribute_name(PERSISTENCE_ID)
+                .attribute_type(ScalarAttributeType::S)
+                .build()?,
+        )
+        .attribute_definitions(
+            AttributeDefinition::builder()
+                .attribute_name(SEQUENCE)
+                .attribute_type(ScalarAttributeType::N)
+                .build()?,
+        )
+        .key_schema(
+            KeySchemaElement::builder()
+                .attribute_name(PERSISTENCE_ID)
+                .key_type(KeyType::Hash)
+                .build()?,
+        )
+        .key_schema(
+            KeySchemaElement::builder()
+                .attribute_name(SEQUENCE)
+                .key_type(KeyType::Range)
+                .build()?,
+        );
+
+    request = match capacity_mode {
+        CapacityMode::OnDemand => request.billing_mode(BillingMode::PayPerRequest),
+        CapacityMode::Provisioned {
+            read_capacity_units,
+            write_capacity_units,
+        } => request
+            .billing_mode(BillingMode::Provisioned)
+            .provisioned_throughput(
+                ProvisionedThroughput::builder()
+                    .read_capacity_units(*read_capacity_units)
+                    .write_capacity_units(*write_capacity_units)
+                    .build()?,
+            ),
+    };
+
+    match request.send().await {
+        Ok(_) => {
+            debug!("created table, table_name={}", table_name);
+        }
+        Err(e) if e.code() == Some("ResourceInUseException") => {
+            trace!("table already exists, table_name={}", table_name);
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    tokio::time::timeout(TABLE_ACTIVE_TIMEOUT, wait_until_active(client, table_name))
+        .await
+        .map_err(|_| anyhow::anyhow!("table {} didn't become active in time", table_name))?
+}
+
+async fn wait_until_active(client: &Client, table_name: &str) -> anyhow::Result<()> {
+    loop {
+        let table = client
+            .describe_table()
+            .table_name(table_name)
+            .send()
+            .await?;
+
+        let status = table.table().and_then(|table| table.table_status());
+        if status == Some(&TableStatus::Active) {
+            return Ok(());
+        }
+
+        tokio::time::sleep(TABLE_STATUS_POLL_INTERVAL).await;
+    }
+}
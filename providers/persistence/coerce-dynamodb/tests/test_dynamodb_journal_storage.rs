@@ -0,0 +1,40 @@
+use aws_sdk_dynamodb::config::{BehaviorVersion, Credentials, Region};
+use coerce::journal_provider_tck;
+use coerce_dynamodb::journal::{DynamoDbStorageConfig, DynamoDbStorageProvider};
+
+const TEST_DYNAMODB_ENDPOINT: &str = "http://localhost:8000";
+
+/// Connects to DynamoDB Local at `COERCE_DYNAMODB_ENDPOINT` (or [`TEST_DYNAMODB_ENDPOINT`]), each
+/// provider uses its own tables so tests can run concurrently without sharing any state
+async fn create_provider() -> DynamoDbStorageProvider {
+    let endpoint = std::env::var("COERCE_DYNAMODB_ENDPOINT")
+        .unwrap_or_else(|_| TEST_DYNAMODB_ENDPOINT.to_string());
+
+    let client_config = aws_sdk_dynamodb::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .endpoint_url(endpoint)
+        .region(Region::new("us-east-1"))
+        .credentials_provider(Credentials::new(
+            "coerce",
+            "coerce",
+            None,
+            None,
+            "coerce-test",
+        ))
+        .build();
+
+    let table_suffix = uuid::Uuid::new_v4().simple().to_string();
+
+    DynamoDbStorageProvider::new(
+        aws_sdk_dynamodb::Client::from_conf(client_config),
+        DynamoDbStorageConfig {
+            journal_table: format!("coerce_journal_{}", &table_suffix),
+            snapshot_table: format!("coerce_snapshot_{}", &table_suffix),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("dynamodb connection")
+}
+
+journal_provider_tck!(DynamoDbStorageProvider, create_provider().await);
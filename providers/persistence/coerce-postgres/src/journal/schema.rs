@@ -0,0 +1,116 @@
+use crate::journal::PostgresStorageConfig;
+
+/// Statements used by [`PostgresJournalStorage`], built once from the configured table names
+///
+/// [`PostgresJournalStorage`]: crate::journal::PostgresJournalStorage
+pub(crate) struct Statements {
+    /// Inserts messages, without the `VALUES` clause, which is built for each batch
+    pub insert_batch: String,
+    pub insert_message: String,
+    pub upsert_snapshot: String,
+    pub read_latest_snapshot: String,
+    pub read_latest_messages: String,
+    pub read_message: String,
+    pub read_messages: String,
+    pub delete_messages_to: String,
+    pub delete_snapshots_to: String,
+    pub delete_all_messages: String,
+    pub delete_all_snapshots: String,
+    pub read_messages_by_tag: String,
+
+    /// Serialises writes of tagged messages, held until the writing transaction commits
+    pub lock_tagged_writes: String,
+}
+
+/// Creates the journal and snapshot tables, if they don't already exist.
+///
+/// Entries are stored encoded as a [`JournalEntry`], alongside the persistence id, sequence and
+/// payload type, so the tables can be inspected without decoding each entry. Messages also store
+/// their tags, and a global `ordering`, which is the offset messages are read by tag from.
+///
+/// Journal tables created before tags were stored have the columns added.
+///
+/// [`JournalEntry`]: coerce::persistent::journal::storage::JournalEntry
+pub(crate) fn create_schema(config: &PostgresStorageConfig) -> String {
+    let journal_table = &config.journal_table;
+    let snapshot_table = &config.snapshot_table;
+
+    format!(
+        "CREATE TABLE IF NOT EXISTS {journal_table} (
+            persistence_id TEXT NOT NULL,
+            sequence BIGINT NOT NULL,
+            payload_type TEXT NOT NULL,
+            entry BYTEA NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            ordering BIGSERIAL NOT NULL,
+            tags TEXT[] NOT NULL DEFAULT '{{}}',
+            PRIMARY KEY (persistence_id, sequence)
+        );
+
+        ALTER TABLE {journal_table} ADD COLUMN IF NOT EXISTS ordering BIGSERIAL NOT NULL;
+        ALTER TABLE {journal_table} ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{{}}';
+
+        CREATE INDEX IF NOT EXISTS {journal_table}_tags_idx ON {journal_table} USING GIN (tags);
+
+        CREATE TABLE IF NOT EXISTS {snapshot_table} (
+            persistence_id TEXT NOT NULL,
+            sequence BIGINT NOT NULL,
+            payload_type TEXT NOT NULL,
+            entry BYTEA NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            PRIMARY KEY (persistence_id, sequence)
+        );"
+    )
+}
+
+impl Statements {
+    pub fn new(config: &PostgresStorageConfig) -> Self {
+        let journal_table = &config.journal_table;
+        let snapshot_table = &config.snapshot_table;
+
+        let insert_batch = format!(
+            "INSERT INTO {journal_table} (persistence_id, sequence, payload_type, entry, tags)"
+        );
+
+        Self {
+            insert_message: format!("{insert_batch} VALUES ($1, $2, $3, $4, $5)"),
+            insert_batch,
+            upsert_snapshot: format!(
+                "INSERT INTO {snapshot_table} (persistence_id, sequence, payload_type, entry) \
+                 VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (persistence_id, sequence) \
+                 DO UPDATE SET payload_type = EXCLUDED.payload_type, entry = EXCLUDED.entry"
+            ),
+            read_latest_snapshot: format!(
+                "SELECT entry FROM {snapshot_table} WHERE persistence_id = $1 \
+                 ORDER BY sequence DESC LIMIT 1"
+            ),
+            read_latest_messages: format!(
+                "SELECT entry FROM {journal_table} WHERE persistence_id = $1 AND sequence > $2 \
+                 ORDER BY sequence ASC"
+            ),
+            read_message: format!(
+                "SELECT entry FROM {journal_table} WHERE persistence_id = $1 AND sequence = $2"
+            ),
+            read_messages: format!(
+                "SELECT entry FROM {journal_table} WHERE persistence_id = $1 \
+                 AND sequence >= $2 AND sequence <= $3 ORDER BY sequence ASC"
+            ),
+            delete_messages_to: format!(
+                "DELETE FROM {journal_table} WHERE persistence_id = $1 AND sequence < $2"
+            ),
+            delete_snapshots_to: format!(
+                "DELETE FROM {snapshot_table} WHERE persistence_id = $1 AND sequence < $2"
+            ),
+            delete_all_messages: format!("DELETE FROM {journal_table} WHERE persistence_id = $1"),
+            delete_all_snapshots: format!("DELETE FROM {snapshot_table} WHERE persistence_id = $1"),
+            read_messages_by_tag: format!(
+                "SELECT persistence_id, ordering, entry FROM {journal_table} \
+                 WHERE tags @> ARRAY[$1::TEXT] AND ordering > $2 ORDER BY ordering ASC LIMIT $3"
+            ),
+            lock_tagged_writes: format!(
+                "SELECT pg_advisory_xact_lock(hashtext('{journal_table}'))"
+            ),
+        }
+    }
+}
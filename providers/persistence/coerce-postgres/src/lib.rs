@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate async_trait;
+
+#[macro_use]
+extern crate tracing;
+
+pub mod journal;
@@ -4,13 +4,33 @@ use coerce::actor::Actor;
 use coerce::persistent::journal::storage::JournalEntry;
 use redis::aio::ConnectionLike;
 
+use std::mem;
+use std::time::Duration;
 use tokio::sync::oneshot::Sender;
 
-pub(crate) struct RedisJournal<C>(pub C);
+/// The maximum number of entries written by a single `ZADD` when writing a batch
+const MAX_BATCH_WRITE_ENTRIES: usize = 1000;
+
+pub(crate) struct RedisJournal<C> {
+    connection: C,
+    pending_writes: Vec<Write>,
+    flush_scheduled: bool,
+}
+
+impl<C> RedisJournal<C> {
+    pub fn new(connection: C) -> Self {
+        Self {
+            connection,
+            pending_writes: vec![],
+            flush_scheduled: false,
+        }
+    }
+}
 
 pub(crate) struct Write {
     pub key: String,
     pub entry: JournalEntry,
+    pub expire: Option<Duration>,
     pub result_channel: Sender<anyhow::Result<()>>,
 }
 
@@ -18,6 +38,13 @@ impl Message for Write {
     type Result = ();
 }
 
+/// Writes every [`Write`] received since the last flush in a single pipeline
+pub(crate) struct FlushWrites;
+
+impl Message for FlushWrites {
+    type Result = ();
+}
+
 pub(crate) struct WriteBatch {
     pub key: String,
     pub entries: Vec<JournalEntry>,
@@ -55,6 +82,12 @@ impl Message for ReadMessage {
     type Result = ();
 }
 
+pub(crate) struct Ping;
+
+impl Message for Ping {
+    type Result = anyhow::Result<()>;
+}
+
 pub(crate) struct Delete(pub Vec<String>);
 
 impl Message for Delete {
@@ -79,21 +112,74 @@ impl<C: 'static + ConnectionLike + Send + Sync> Handler<Write> for RedisJournal<
 where
     C: Clone,
 {
-    async fn handle(&mut self, message: Write, _ctx: &mut ActorContext) {
-        let connection = self.0.clone();
+    async fn handle(&mut self, message: Write, ctx: &mut ActorContext) {
+        self.pending_writes.push(message);
+
+        // writes received before the flush is handled are included in the same pipeline
+        if !self.flush_scheduled {
+            self.flush_scheduled = true;
+            let _ = ctx.actor_ref::<Self>().notify(FlushWrites);
+        }
+    }
+}
+
+#[async_trait]
+impl<C: 'static + ConnectionLike + Send + Sync> Handler<FlushWrites> for RedisJournal<C>
+where
+    C: Clone,
+{
+    async fn handle(&mut self, _message: FlushWrites, _ctx: &mut ActorContext) {
+        self.flush_scheduled = false;
+
+        let writes = mem::take(&mut self.pending_writes);
+        if writes.is_empty() {
+            return;
+        }
+
+        let mut pipeline = redis::pipe();
+        let mut result_channels = Vec::with_capacity(writes.len());
+        for write in writes {
+            let bytes = match write.entry.write_to_bytes() {
+                Some(bytes) => bytes,
+                None => {
+                    let err = anyhow::anyhow!("failed to serialize journal entry");
+                    let _ = write.result_channel.send(Err(err));
+                    continue;
+                }
+            };
+
+            pipeline
+                .cmd("ZADD")
+                .arg(&write.key)
+                .arg(write.entry.sequence)
+                .arg(bytes)
+                .ignore();
+
+            if let Some(expire) = write.expire {
+                pipeline
+                    .cmd("PEXPIRE")
+                    .arg(&write.key)
+                    .arg(expire.as_millis() as u64)
+                    .ignore();
+            }
+
+            result_channels.push(write.result_channel);
+        }
+
+        let connection = self.connection.clone();
         let _ = tokio::spawn(async move {
             let mut connection = connection;
-            if let Err(e) = redis::cmd("ZADD")
-                .arg(message.key)
-                .arg(message.entry.sequence)
-                .arg(&message.entry.write_to_bytes().expect("serialized journal"))
-                .query_async::<C, ()>(&mut connection)
-                .await
-            {
-                let err = anyhow::Error::new(e);
-                let _ = message.result_channel.send(Err(err));
-            } else {
-                let _ = message.result_channel.send(Ok(()));
+            match pipeline.query_async::<C, ()>(&mut connection).await {
+                Ok(_) => {
+                    for result_channel in result_channels {
+                        let _ = result_channel.send(Ok(()));
+                    }
+                }
+                Err(e) => {
+                    for result_channel in result_channels {
+                        let _ = result_channel.send(Err(anyhow::anyhow!("{}", &e)));
+                    }
+                }
             }
         });
     }
@@ -105,20 +191,25 @@ where
     C: Clone,
 {
     async fn handle(&mut self, message: WriteBatch, _ctx: &mut ActorContext) {
-        let connection = self.0.clone();
+        let connection = self.connection.clone();
         let _ = tokio::spawn(async move {
             let mut connection = connection;
 
-            let mut cmd = redis::cmd("ZADD");
+            // large batches are split into multiple commands, written atomically
+            let mut pipeline = redis::pipe();
+            pipeline.atomic();
 
-            cmd.arg(message.key);
+            for entries in message.entries.chunks(MAX_BATCH_WRITE_ENTRIES) {
+                let cmd = pipeline.cmd("ZADD").arg(&message.key);
+                for entry in entries {
+                    cmd.arg(entry.sequence)
+                        .arg(entry.write_to_bytes().expect("serialized journal"));
+                }
 
-            for entry in message.entries {
-                cmd.arg(entry.sequence)
-                    .arg(entry.write_to_bytes().expect("serialized journal"));
+                cmd.ignore();
             }
 
-            if let Err(e) = cmd.query_async::<C, ()>(&mut connection).await {
+            if let Err(e) = pipeline.query_async::<C, ()>(&mut connection).await {
                 let _ = message.result_channel.send(Err(anyhow::Error::new(e)));
             } else {
                 let _ = message.result_channel.send(Ok(()));
@@ -133,7 +224,7 @@ where
     C: Clone,
 {
     async fn handle(&mut self, message: DeleteRange, _ctx: &mut ActorContext) {
-        let connection = self.0.clone();
+        let connection = self.connection.clone();
         let _ = tokio::spawn(async move {
             let mut connection = connection;
             if let Err(e) = redis::cmd("ZREMRANGEBYSCORE")
@@ -158,7 +249,7 @@ where
     C: Clone,
 {
     async fn handle(&mut self, message: ReadSnapshot, _ctx: &mut ActorContext) {
-        let connection = self.0.clone();
+        let connection = self.connection.clone();
         let _ = tokio::spawn(async move {
             let mut connection = connection;
 
@@ -193,7 +284,7 @@ where
     C: Clone,
 {
     async fn handle(&mut self, message: ReadMessage, _ctx: &mut ActorContext) {
-        let connection = self.0.clone();
+        let connection = self.connection.clone();
         let _ = tokio::spawn(async move {
             let mut connection = connection;
 
@@ -226,7 +317,7 @@ where
     C: Clone,
 {
     async fn handle(&mut self, message: ReadMessages, _ctx: &mut ActorContext) {
-        let connection = self.0.clone();
+        let connection = self.connection.clone();
         let _ = tokio::spawn(async move {
             let mut connection = connection;
 
@@ -264,9 +355,23 @@ where
     C: Clone,
 {
     async fn handle(&mut self, message: Delete, _ctx: &mut ActorContext) -> anyhow::Result<()> {
-        let _ = redis::cmd("DEL")
+        redis::cmd("DEL")
             .arg(message.0)
-            .query_async(&mut self.0)
+            .query_async::<C, ()>(&mut self.connection)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<C: 'static + ConnectionLike + Send + Sync> Handler<Ping> for RedisJournal<C>
+where
+    C: Clone,
+{
+    async fn handle(&mut self, _message: Ping, _ctx: &mut ActorContext) -> anyhow::Result<()> {
+        redis::cmd("PING")
+            .query_async::<C, ()>(&mut self.connection)
             .await?;
 
         Ok(())
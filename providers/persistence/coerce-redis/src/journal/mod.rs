@@ -1,5 +1,6 @@
 use crate::journal::actor::{
-    Delete, DeleteRange, ReadMessage, ReadMessages, ReadSnapshot, RedisJournal, Write, WriteBatch,
+    Delete, DeleteRange, Ping, ReadMessage, ReadMessages, ReadSnapshot, RedisJournal, Write,
+    WriteBatch,
 };
 
 use coerce::actor::system::ActorSystem;
@@ -11,6 +12,7 @@ use redis::aio::ConnectionLike;
 
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::oneshot;
 
@@ -26,6 +28,23 @@ pub struct RedisStorageConfig {
     pub key_prefix: String,
     pub cluster: bool,
     pub use_key_hashtags: bool,
+
+    /// When set, the snapshots of a persistent actor expire once no snapshot has been written
+    /// for the duration, the journal is then replayed from the start when the actor is recovered,
+    /// unless its messages have been deleted.
+    pub snapshot_ttl: Option<Duration>,
+}
+
+impl Default for RedisStorageConfig {
+    fn default() -> Self {
+        Self {
+            nodes: vec!["redis://127.0.0.1:6379/".to_string()],
+            key_prefix: String::new(),
+            cluster: false,
+            use_key_hashtags: false,
+            snapshot_ttl: None,
+        }
+    }
 }
 
 pub struct RedisJournalStorage<C: 'static + ConnectionLike + Send + Sync>
@@ -94,7 +113,7 @@ where
     const REDIS_JOURNAL_COUNTER: AtomicU32 = AtomicU32::new(1);
     let config = Arc::new(config);
 
-    let redis_journal = RedisJournal(redis)
+    let redis_journal = RedisJournal::new(redis)
         .into_actor(
             Some(format!(
                 "redis-journal-{}",
@@ -140,6 +159,7 @@ where
         self.redis_journal.notify(Write {
             key,
             entry,
+            expire: self.config.snapshot_ttl,
             result_channel: tx,
         })?;
 
@@ -153,6 +173,7 @@ where
         self.redis_journal.notify(Write {
             key,
             entry,
+            expire: None,
             result_channel,
         })?;
 
@@ -251,7 +272,24 @@ where
         self.redis_journal.notify(DeleteRange {
             key,
             start_sequence: 0,
-            end_sequence: to_sequence,
+            end_sequence: to_sequence - 1,
+            result_channel,
+        })?;
+
+        rx.await?
+    }
+
+    async fn delete_snapshots_to(
+        &self,
+        persistence_id: &str,
+        to_sequence: i64,
+    ) -> anyhow::Result<()> {
+        let (result_channel, rx) = oneshot::channel();
+        let key = (self.key_provider_fn)(persistence_id, "snapshot", self.config.as_ref());
+        self.redis_journal.notify(DeleteRange {
+            key,
+            start_sequence: 0,
+            end_sequence: to_sequence - 1,
             result_channel,
         })?;
 
@@ -266,6 +304,10 @@ where
             .send(Delete(vec![journal_key, snapshot_key]))
             .await?
     }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        self.redis_journal.send(Ping).await?
+    }
 }
 
 fn get_clustered_redis_key(
@@ -1,10 +1,12 @@
 use coerce::actor::system::ActorSystem;
+use coerce::persistent::journal::metadata::EventMetadata;
 use coerce::persistent::journal::provider::StorageProvider;
 use coerce::persistent::journal::storage::JournalEntry;
 use coerce::persistent::storage::JournalStorageRef;
 use coerce::persistent::Persistence;
 
 use coerce_redis::journal::{RedisStorageConfig, RedisStorageProvider};
+use std::time::Duration;
 
 const TEST_REDIS_HOST: &str = "redis://127.0.0.1:6379/";
 
@@ -126,21 +128,55 @@ pub async fn test_redis_journal_delete_messages_range() {
     redis.delete_all(persistence_id).await.expect("delete all");
 
     let latest_messages = latest_messages.unwrap().unwrap();
-    assert_eq!(latest_messages.len(), 2);
+    assert_eq!(latest_messages.len(), 3);
+}
+
+#[tokio::test]
+pub async fn test_redis_journal_snapshot_ttl() {
+    let persistence_id = "hi";
+    let ctx = new_test_context_with_config(RedisStorageConfig {
+        nodes: vec![TEST_REDIS_HOST.to_string()],
+        key_prefix: "test_redis_journal_snapshot_ttl:".to_string(),
+        snapshot_ttl: Some(Duration::from_millis(200)),
+        ..RedisStorageConfig::default()
+    })
+    .await;
+
+    let redis = ctx.storage;
+    let entries = generate_entries(1);
+
+    for entry in entries {
+        redis
+            .write_snapshot(persistence_id, entry)
+            .await
+            .expect("write snapshot");
+    }
+
+    let snapshot = redis.read_latest_snapshot(persistence_id).await;
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let expired_snapshot = redis.read_latest_snapshot(persistence_id).await;
+
+    redis.delete_all(persistence_id).await.expect("delete all");
+
+    assert!(snapshot.expect("load latest snapshot").is_some());
+    assert!(expired_snapshot.expect("load latest snapshot").is_none());
 }
 
 async fn new_test_context(key_prefix: &str) -> RedisTestCtx {
+    new_test_context_with_config(RedisStorageConfig {
+        nodes: vec![TEST_REDIS_HOST.to_string()],
+        key_prefix: key_prefix.to_string(),
+        cluster: false,
+        use_key_hashtags: false,
+        snapshot_ttl: None,
+    })
+    .await
+}
+
+async fn new_test_context_with_config(config: RedisStorageConfig) -> RedisTestCtx {
     let system = ActorSystem::new();
-    let provider = RedisStorageProvider::connect(
-        RedisStorageConfig {
-            nodes: vec![TEST_REDIS_HOST.to_string()],
-            key_prefix: key_prefix.to_string(),
-            cluster: false,
-            use_key_hashtags: false,
-        },
-        &system,
-    )
-    .await;
+    let provider = RedisStorageProvider::connect(config, &system).await;
 
     let storage = provider.journal_storage().expect("journal storage");
     system.to_persistent(provider.into());
@@ -154,6 +190,7 @@ fn generate_entries(n: i32) -> Vec<JournalEntry> {
             sequence: n as i64,
             payload_type: "test".into(),
             bytes: vec![1, 3, 3, 7].into(),
+            metadata: EventMetadata::default(),
         })
         .collect()
 }
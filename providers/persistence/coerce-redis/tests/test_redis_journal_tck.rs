@@ -0,0 +1,24 @@
+use coerce::actor::system::ActorSystem;
+use coerce::journal_provider_tck;
+use coerce_redis::journal::{RedisStorageConfig, RedisStorageProvider};
+
+const TEST_REDIS_HOST: &str = "redis://127.0.0.1:6379/";
+
+/// Each provider writes under its own key prefix, so tests can run concurrently without
+/// sharing any state
+async fn create_provider() -> RedisStorageProvider {
+    let system = ActorSystem::new();
+    let key_prefix = format!("journal-tck-{}:", system.system_id());
+
+    RedisStorageProvider::connect(
+        RedisStorageConfig {
+            nodes: vec![TEST_REDIS_HOST.to_string()],
+            key_prefix,
+            ..RedisStorageConfig::default()
+        },
+        &system,
+    )
+    .await
+}
+
+journal_provider_tck!(RedisStorageProvider, create_provider().await);